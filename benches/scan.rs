@@ -0,0 +1,58 @@
+//! End-to-end scan benchmarks: `feluda --offline --json` over the synthetic projects that
+//! `feluda bench` writes. Run with `cargo bench`.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use tempfile::TempDir;
+
+const FELUDA: &str = env!("CARGO_BIN_EXE_feluda");
+
+/// Dependency counts of the synthetic projects
+const SIZES: [usize; 2] = [500, 2000];
+
+fn write_fixtures(dir: &Path, packages: usize) {
+    let status = Command::new(FELUDA)
+        .args(["bench", "--iterations", "1", "--packages"])
+        .arg(packages.to_string())
+        .arg("--fixtures")
+        .arg(dir)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .expect("failed to run feluda bench");
+    assert!(status.success(), "feluda bench failed to write fixtures");
+}
+
+fn scan(project: &Path) {
+    let status = Command::new(FELUDA)
+        .args(["--offline", "--json", "--path"])
+        .arg(project)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .expect("failed to run feluda");
+    assert!(status.success(), "scan of {} failed", project.display());
+}
+
+fn bench_scans(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scan");
+    group.sample_size(10);
+    for packages in SIZES {
+        let fixtures = TempDir::new().unwrap();
+        write_fixtures(fixtures.path(), packages);
+        for ecosystem in ["node", "go"] {
+            let project = fixtures.path().join(ecosystem);
+            group.bench_with_input(
+                BenchmarkId::new(ecosystem, packages),
+                &project,
+                |b, project| b.iter(|| scan(project)),
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_scans);
+criterion_main!(benches);
@@ -0,0 +1,315 @@
+//! `feluda accept`: record an audited exception in `.feludaignore`
+//!
+//! Accepting a dependency adds (or updates) its `.feludaignore` entry with the reason given,
+//! and can commit the change, on a new branch if asked, so it's ready to push for review in
+//! repositories where the ignore rules are protected.
+
+use std::fs;
+use std::path::Path;
+
+use colored::*;
+
+use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
+use crate::ignore::IGNORE_FILE;
+
+/// Accept `package` (`name[@version]`) for `reason` in `path`'s `.feludaignore`
+pub fn handle_accept_command(
+    path: &str,
+    package: &str,
+    reason: &str,
+    branch: Option<&str>,
+    commit: bool,
+) -> FeludaResult<()> {
+    let (name, version) = split_package(package);
+    let reason = reason.trim();
+    if name.is_empty() {
+        return Err(FeludaError::InvalidData(
+            "accept needs a package name".to_string(),
+        ));
+    }
+    if reason.is_empty() {
+        return Err(FeludaError::InvalidData(
+            "accept needs a --reason for the audit trail".to_string(),
+        ));
+    }
+    if reason.contains('\n') {
+        return Err(FeludaError::InvalidData(
+            "the --reason must fit on one line".to_string(),
+        ));
+    }
+
+    let dir = Path::new(path);
+    let ignore_path = dir.join(IGNORE_FILE);
+
+    // Switch branches before touching the file, so the working tree change lands on it
+    let repo = if branch.is_some() || commit {
+        Some(open_repository(dir)?)
+    } else {
+        None
+    };
+    if let (Some(repo), Some(branch)) = (&repo, branch) {
+        create_branch(repo, branch)?;
+    }
+
+    let existing = match fs::read_to_string(&ignore_path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.into()),
+    };
+    let (updated, replaced) = accept_entry(&existing, name, version, reason);
+    fs::write(&ignore_path, updated)?;
+
+    let entry = entry_label(name, version);
+    let action = if replaced { "Updated" } else { "Accepted" };
+    println!(
+        "✅ {action} {} in {}: {}",
+        entry.bold(),
+        ignore_path.display(),
+        reason
+    );
+
+    if let Some(repo) = &repo {
+        let id = commit_ignore_file(repo, &ignore_path, &entry, reason)?;
+        // A detached HEAD has no branch name to show
+        let on = repo
+            .head()
+            .ok()
+            .and_then(|head| head.shorthand().map(str::to_string))
+            .unwrap_or_else(|| "HEAD".to_string());
+        println!("📝 Committed {} on {on}", &id.to_string()[..7]);
+        if let Some(branch) = branch {
+            println!(
+                "   Push it for review with: {}",
+                format!("git push -u origin {branch}").cyan()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// `name[@version]`; a leading `@` belongs to a scoped npm name
+fn split_package(package: &str) -> (&str, &str) {
+    let package = package.trim();
+    match package.rfind('@') {
+        Some(index) if index > 0 => (&package[..index], &package[index + 1..]),
+        _ => (package, ""),
+    }
+}
+
+fn entry_label(name: &str, version: &str) -> String {
+    if version.is_empty() {
+        name.to_string()
+    } else {
+        format!("{name}@{version}")
+    }
+}
+
+/// `content` with an entry for `name@version` carrying `reason`, and whether an existing entry
+/// for it was replaced rather than a new one appended
+fn accept_entry(content: &str, name: &str, version: &str, reason: &str) -> (String, bool) {
+    let label = entry_label(name, version);
+    let line = format!("{label}  # {reason}");
+
+    let mut replaced = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|existing| {
+            // Same split as the ignore file parser: a comment starts at `#` after whitespace
+            let entry = existing
+                .split_once(" #")
+                .or_else(|| existing.split_once("\t#"))
+                .map_or(existing, |(entry, _)| entry)
+                .trim();
+            if !replaced && !entry.starts_with('#') && entry == label {
+                replaced = true;
+                line.clone()
+            } else {
+                existing.to_string()
+            }
+        })
+        .collect();
+    if !replaced {
+        lines.push(line);
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    (updated, replaced)
+}
+
+fn open_repository(dir: &Path) -> FeludaResult<git2::Repository> {
+    git2::Repository::discover(dir).map_err(|e| {
+        FeludaError::InvalidData(format!(
+            "{} is not inside a git repository: {e}",
+            dir.display()
+        ))
+    })
+}
+
+/// Create `branch` at HEAD and check it out; the working tree is left as it is
+fn create_branch(repo: &git2::Repository, branch: &str) -> FeludaResult<()> {
+    let head = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|e| {
+            FeludaError::InvalidData(format!("cannot branch from HEAD (no commits yet?): {e}"))
+        })?;
+    repo.branch(branch, &head, false)
+        .map_err(|e| FeludaError::InvalidData(format!("cannot create branch '{branch}': {e}")))?;
+    repo.set_head(&format!("refs/heads/{branch}"))
+        .map_err(|e| FeludaError::InvalidData(format!("cannot switch to '{branch}': {e}")))?;
+    log(
+        LogLevel::Info,
+        &format!("Created and switched to branch {branch}"),
+    );
+    Ok(())
+}
+
+/// Commit only the ignore file, leaving any other changes in the working tree alone
+fn commit_ignore_file(
+    repo: &git2::Repository,
+    ignore_path: &Path,
+    entry: &str,
+    reason: &str,
+) -> FeludaResult<git2::Oid> {
+    let git_error = |e: git2::Error| FeludaError::InvalidData(format!("git: {e}"));
+
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| FeludaError::InvalidData("bare repositories are not supported".into()))?
+        .canonicalize()?;
+    let relative = ignore_path
+        .canonicalize()?
+        .strip_prefix(&workdir)
+        .map_err(|_| {
+            FeludaError::InvalidData(format!(
+                "{} is outside the repository at {}",
+                ignore_path.display(),
+                workdir.display()
+            ))
+        })?
+        .to_path_buf();
+
+    // Stage the file, then commit HEAD's tree plus just that entry, so anything else the user
+    // had staged stays staged rather than being swept into the commit
+    let mut index = repo.index().map_err(git_error)?;
+    index.add_path(&relative).map_err(git_error)?;
+    index.write().map_err(git_error)?;
+    let staged = index.get_path(&relative, 0).ok_or_else(|| {
+        FeludaError::InvalidData(format!("git: {} not staged", relative.display()))
+    })?;
+
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let mut commit_index = git2::Index::new().map_err(git_error)?;
+    if let Some(parent) = &parent {
+        commit_index
+            .read_tree(&parent.tree().map_err(git_error)?)
+            .map_err(git_error)?;
+    }
+    commit_index.add(&staged).map_err(git_error)?;
+    let tree = repo
+        .find_tree(commit_index.write_tree_to(repo).map_err(git_error)?)
+        .map_err(git_error)?;
+
+    let signature = repo.signature().map_err(|e| {
+        FeludaError::InvalidData(format!("set git user.name and user.email to commit: {e}"))
+    })?;
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    let message = format!("Accept {entry} in {IGNORE_FILE}\n\nReason: {reason}\n");
+
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        &message,
+        &tree,
+        &parents,
+    )
+    .map_err(git_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_package() {
+        assert_eq!(split_package("left-pad@1.3.0"), ("left-pad", "1.3.0"));
+        assert_eq!(split_package("@myorg/ui"), ("@myorg/ui", ""));
+        assert_eq!(split_package("@myorg/ui@2.0.0"), ("@myorg/ui", "2.0.0"));
+        assert_eq!(split_package("serde"), ("serde", ""));
+    }
+
+    #[test]
+    fn test_accept_entry_appends_and_replaces() {
+        let (content, replaced) = accept_entry("", "left-pad", "1.3.0", "vendored");
+        assert!(!replaced);
+        assert_eq!(content, "left-pad@1.3.0  # vendored\n");
+
+        let existing = "# Audited exceptions\nleft-pad@1.3.0  # old reason\nlodash\n";
+        let (content, replaced) = accept_entry(existing, "left-pad", "1.3.0", "relicensed");
+        assert!(replaced);
+        assert_eq!(
+            content,
+            "# Audited exceptions\nleft-pad@1.3.0  # relicensed\nlodash\n"
+        );
+
+        // Another version of the same package is a separate entry
+        let (content, replaced) = accept_entry(existing, "left-pad", "", "any release");
+        assert!(!replaced);
+        assert!(content.ends_with("lodash\nleft-pad  # any release\n"));
+    }
+
+    #[test]
+    fn test_accept_commits_on_new_branch() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = repo.signature().unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+
+        // Unrelated staged work stays out of the commit
+        fs::write(dir.path().join("notes.txt"), "wip\n").unwrap();
+        index.add_path(Path::new("notes.txt")).unwrap();
+        index.write().unwrap();
+
+        handle_accept_command(
+            dir.path().to_str().unwrap(),
+            "acme-crypto@2.1.0",
+            "internal fork",
+            Some("feluda/accept-acme-crypto"),
+            false,
+        )
+        .unwrap();
+
+        let head = repo.head().unwrap();
+        assert_eq!(head.shorthand(), Some("feluda/accept-acme-crypto"));
+        let commit = head.peel_to_commit().unwrap();
+        assert_eq!(commit.parent_count(), 1);
+        assert!(commit.message().unwrap().contains("Reason: internal fork"));
+        let blob = commit
+            .tree()
+            .unwrap()
+            .get_path(Path::new(IGNORE_FILE))
+            .unwrap()
+            .to_object(&repo)
+            .unwrap()
+            .peel_to_blob()
+            .unwrap();
+        assert_eq!(blob.content(), b"acme-crypto@2.1.0  # internal fork\n");
+        assert!(commit
+            .tree()
+            .unwrap()
+            .get_path(Path::new("notes.txt"))
+            .is_err());
+    }
+}
@@ -0,0 +1,350 @@
+//! Library interface: scan a project without the command line
+//!
+//! [`scan`] runs the analysis `feluda --json` runs, with the compatibility verdicts and fix
+//! suggestions, and returns the dependencies instead of printing them. Nothing is written to
+//! stdout, the progress spinners stay off unless [`ScanOptions::progress`] is set, and the
+//! process is never exited: failing a release on restrictive licenses is up to the caller,
+//! through [`ScanResult::has_restrictive`] and [`ScanResult::has_incompatible`].
+//!
+//! Policy is read like the CLI reads it, except that `.feluda.toml` is looked up in the scanned
+//! directory rather than the current one: the global config file and `FELUDA_*` environment
+//! variables apply too. [`ScanOptions::config`] replaces all of them. The GitHub API and token
+//! are the caller's alone: the scanned project's `[github]` table is not read. Scans in one
+//! process run one at a time, since each holds its configuration for the whole scan.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::config::FeludaConfig;
+use crate::debug::{FeludaError, FeludaResult, ParseWarning, ResolutionError};
+use crate::ignore::IgnoredDependency;
+use crate::licenses::{LicenseCompatibility, LicenseInfo};
+use crate::report_metadata::{Report, ReportMetadata};
+use crate::{analyze_dependencies, annotate_compatibility, Analysis, CheckConfig};
+
+/// What to scan and how. Start from [`ScanOptions::default`], which scans like `feluda` with no
+/// flags, and set the fields to change.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct ScanOptions {
+    /// Only scan this ecosystem: `rust`, `node`, `go`, `python`, `java`, `android`, `c`, `cpp`,
+    /// `r`, `ruby`, `php`, `dotnet`, `elixir`, `carthage` or `wasm`
+    pub language: Option<String>,
+    /// The project's license; detected from the project's files when `None`
+    pub project_license: Option<String>,
+    /// Strict mode: share-alike licenses count as restrictive, unknown ones as incompatible
+    pub strict: bool,
+    /// Skip local license files and only ask the registries (`--no-local`)
+    pub no_local: bool,
+    /// Skip the vendored and unmanaged code scan of the whole tree (`--no-vendor-scan`)
+    pub no_vendor_scan: bool,
+    /// Ecosystems to leave out (`--skip`)
+    pub skip: Vec<String>,
+    /// Leave development dependencies out (`--no-dev`)
+    pub no_dev: bool,
+    /// Leave transitive dependencies out (`--direct-only`)
+    pub direct_only: bool,
+    /// Resolve Go's full module graph (`--include-transitive`)
+    pub include_transitive: bool,
+    /// Check at most this many dependencies (`--limit`); the metadata marks the scan partial
+    pub limit: Option<usize>,
+    /// Check about this percentage of the dependencies (`--sample`)
+    pub sample: Option<u8>,
+    /// Inherit upstream licenses for unlicensed GitHub forks (`--resolve-forks`)
+    pub resolve_forks: bool,
+    /// Look up known vulnerabilities on OSV.dev (`--vulnerabilities`)
+    pub vulnerabilities: bool,
+    /// Fail when a manifest can't be parsed, instead of reporting a warning (`--strict-parse`)
+    pub strict_parse: bool,
+    /// Use only cached and local data, no network (`--offline`)
+    pub offline: bool,
+    /// GitHub token for license lookups; the `[github] token` of [`ScanOptions::config`] or of
+    /// the global config file when `None`
+    pub github_token: Option<String>,
+    /// REST API of a GitHub Enterprise Server to send GitHub lookups to; the `[github] api` of
+    /// [`ScanOptions::config`] or of the global config file when `None`
+    pub github_api: Option<String>,
+    /// Show progress spinners on stderr
+    pub progress: bool,
+    /// Configuration to scan with, instead of the project's `.feluda.toml`, the global config
+    /// file and `FELUDA_*` variables
+    pub config: Option<FeludaConfig>,
+}
+
+/// The dependencies of a scanned project and what the scan ran into
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ScanResult {
+    pub dependencies: Vec<LicenseInfo>,
+    /// The project license compatibility was checked against, if known
+    pub project_license: Option<String>,
+    /// Manifests that couldn't be parsed
+    pub warnings: Vec<ParseWarning>,
+    /// Registry and API lookups that failed; affected licenses may be unknown
+    pub resolution_errors: Vec<ResolutionError>,
+    /// Dependencies ignore rules left out
+    pub ignored: Vec<IgnoredDependency>,
+    pub metadata: ReportMetadata,
+}
+
+impl ScanResult {
+    /// Dependencies whose license is restrictive
+    pub fn restrictive(&self) -> impl Iterator<Item = &LicenseInfo> {
+        self.dependencies.iter().filter(|dep| dep.is_restrictive)
+    }
+
+    /// Dependencies whose license is incompatible with the project license
+    pub fn incompatible(&self) -> impl Iterator<Item = &LicenseInfo> {
+        self.dependencies
+            .iter()
+            .filter(|dep| dep.compatibility == LicenseCompatibility::Incompatible)
+    }
+
+    /// What `--fail-on-restrictive` fails on
+    pub fn has_restrictive(&self) -> bool {
+        self.restrictive().next().is_some()
+    }
+
+    /// What `--fail-on-incompatible` fails on
+    pub fn has_incompatible(&self) -> bool {
+        self.incompatible().next().is_some()
+    }
+
+    /// The report `feluda --json` writes, which `feluda diff` and `feluda tui` read
+    pub fn to_json(&self) -> FeludaResult<String> {
+        serde_json::to_string_pretty(&self.report())
+            .map_err(|e| FeludaError::Serialization(format!("Failed to serialize report: {e}")))
+    }
+
+    /// The report `feluda --yaml` writes
+    pub fn to_yaml(&self) -> FeludaResult<String> {
+        serde_yaml::to_string(&self.report())
+            .map_err(|e| FeludaError::Serialization(format!("Failed to serialize report: {e}")))
+    }
+
+    fn report(&self) -> Report<'_> {
+        Report::new(
+            Some(&self.metadata),
+            self.project_license.as_deref(),
+            &self.dependencies,
+        )
+        .with_warnings(&self.warnings)
+    }
+}
+
+/// Scan the project at `path`
+pub fn scan(path: impl AsRef<Path>, options: ScanOptions) -> FeludaResult<ScanResult> {
+    let path = path.as_ref();
+    if !path.is_dir() {
+        return Err(FeludaError::InvalidData(format!(
+            "{} is not a directory",
+            path.display()
+        )));
+    }
+
+    if let Some(config) = &options.config {
+        config.validate()?;
+    }
+
+    // Where GitHub lookups go and the token they carry come from the caller, never from the
+    // scanned project's `.feluda.toml`
+    let github_config = match &options.config {
+        Some(config) => config.github.clone(),
+        None => crate::config::load_user_config()
+            .map(|config| config.github)
+            .unwrap_or_default(),
+    };
+
+    // Held for the whole scan: the scopes below are process-wide
+    static SCANNING: Mutex<()> = Mutex::new(());
+    let _scanning = SCANNING.lock().unwrap_or_else(|e| e.into_inner());
+    let _config = crate::config::scope_config(path, options.config);
+    let _github = crate::licenses::scope_github(
+        options.github_api.or(github_config.api),
+        options.github_token.or(github_config.token),
+    );
+    let _modes = ModeGuard::set(options.offline, !options.progress);
+
+    let config = CheckConfig {
+        path: path.to_string_lossy().to_string(),
+        json: true,
+        yaml: false,
+        verbose: false,
+        restrictive: false,
+        gui: false,
+        language: options.language,
+        ci_format: None,
+        output_file: None,
+        output_dir: None,
+        keep_reports: None,
+        compress: None,
+        chart: false,
+        fail_on_restrictive: false,
+        incompatible: false,
+        fail_on_incompatible: false,
+        fail_on_resolution_errors: false,
+        osi_only: false,
+        project_license: options.project_license,
+        gist: false,
+        osi: None,
+        strict: options.strict,
+        no_local: options.no_local,
+        no_vendor_scan: options.no_vendor_scan,
+        artifacts: Vec::new(),
+        resolve_forks: options.resolve_forks,
+        vulnerabilities: options.vulnerabilities,
+        strict_parse: options.strict_parse,
+        cargo_lock: None,
+        no_dev: options.no_dev,
+        direct_only: options.direct_only,
+        include_transitive: options.include_transitive,
+        limit: options.limit,
+        sample: options.sample,
+        cargo: Default::default(),
+        skip: options.skip,
+        changed_since: None,
+        github_sbom: None,
+        baseline: None,
+        force_baseline: false,
+        write_baseline: None,
+        image: None,
+        sbom: None,
+        output_format: None,
+        project_name: crate::project_name(None, path),
+        licenses_dir: None,
+        obligations: None,
+        obligations_issues: None,
+        pending_updates: None,
+        result_file: None,
+    };
+    let analysis = analyze_dependencies(&config);
+    let Analysis {
+        mut dependencies,
+        project_license,
+        warnings,
+        resolution_errors,
+        ignored,
+    } = analysis?;
+    annotate_compatibility(&mut dependencies, &project_license, config.strict);
+    crate::suggestions::annotate_suggestions(&mut dependencies);
+
+    let mut metadata = ReportMetadata::collect(path);
+    // The embedding program's arguments aren't Feluda's
+    metadata.arguments.clear();
+    Ok(ScanResult {
+        dependencies,
+        project_license,
+        warnings,
+        resolution_errors,
+        ignored,
+        metadata,
+    })
+}
+
+/// Puts the caller's offline and spinner settings back when the scan ends
+struct ModeGuard {
+    offline: bool,
+    quiet: bool,
+}
+
+impl ModeGuard {
+    fn set(offline: bool, quiet: bool) -> Self {
+        let guard = ModeGuard {
+            offline: crate::cancel::is_offline(),
+            quiet: crate::cli::is_quiet(),
+        };
+        crate::cancel::set_offline(offline);
+        crate::cli::set_quiet(quiet);
+        guard
+    }
+}
+
+impl Drop for ModeGuard {
+    fn drop(&mut self) {
+        crate::cancel::set_offline(self.offline);
+        crate::cli::set_quiet(self.quiet);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_result() {
+        let result = ScanResult {
+            dependencies: vec![
+                LicenseInfo::fixture("serde", "1.0.0", "MIT"),
+                LicenseInfo::fixture("readline", "1.0.0", "GPL-3.0").restrictive(),
+                LicenseInfo {
+                    compatibility: LicenseCompatibility::Incompatible,
+                    ..LicenseInfo::fixture("sdk", "1.0.0", "SSPL-1.0")
+                },
+            ],
+            project_license: Some("MIT".to_string()),
+            warnings: vec![ParseWarning {
+                file: "web/package.json".to_string(),
+                reason: "invalid package.json JSON".to_string(),
+            }],
+            resolution_errors: Vec::new(),
+            ignored: Vec::new(),
+            metadata: ReportMetadata::collect(Path::new(".")),
+        };
+        assert!(result.has_restrictive());
+        assert!(result.has_incompatible());
+        assert_eq!(
+            result
+                .restrictive()
+                .map(|dep| dep.name())
+                .collect::<Vec<_>>(),
+            vec!["readline"]
+        );
+        assert_eq!(
+            result
+                .incompatible()
+                .map(|dep| dep.name())
+                .collect::<Vec<_>>(),
+            vec!["sdk"]
+        );
+
+        // The JSON is the report `feluda diff` and `feluda tui` load
+        let json = result.to_json().unwrap();
+        let stored: crate::report_metadata::StoredReport = serde_json::from_str(&json).unwrap();
+        let (dependencies, metadata) = stored.into_parts();
+        assert_eq!(dependencies.len(), 3);
+        assert_eq!(metadata, Some(result.metadata.clone()));
+        let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(report["warnings"][0]["file"], "web/package.json");
+        assert!(result.to_yaml().unwrap().contains("name: readline"));
+    }
+
+    #[test]
+    fn test_scan_leaves_process_settings_alone() {
+        let project = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            project.path().join(crate::config::PROJECT_CONFIG_FILE),
+            "[github]\napi = \"https://ghe.invalid/api/v3\"\ntoken = \"project-token\"\n",
+        )
+        .unwrap();
+
+        let options = ScanOptions {
+            offline: true,
+            ..Default::default()
+        };
+        scan(project.path(), options).unwrap();
+
+        // The scanned tree can't point the caller's GitHub lookups, or its token, elsewhere
+        assert_eq!(crate::licenses::github_api_url(), "https://api.github.com");
+        assert_eq!(crate::licenses::get_github_token(), None);
+        assert!(!crate::cancel::is_offline());
+        assert!(!crate::cli::is_quiet());
+    }
+
+    #[test]
+    fn test_scan_rejects_missing_directory() {
+        assert!(matches!(
+            scan("no/such/project", ScanOptions::default()),
+            Err(FeludaError::InvalidData(_))
+        ));
+    }
+}
@@ -0,0 +1,539 @@
+//! Shipped artifact scanning (`--artifacts`)
+//!
+//! Manifests describe what a build *should* pull in; build outputs show what actually ships.
+//! Fat jars bundle other jars, wheels vendor their dependencies, native libraries statically
+//! link third-party code and carry its license text as string data. This module walks build
+//! output directories, looks inside archives (`.jar`, `.war`, `.whl`, `.zip`, `.nupkg`,
+//! `.tar.gz`…) and native binaries (`.so`, `.dll`, `.dylib`, executables) for embedded
+//! LICENSE/NOTICE/COPYING texts, and reports licensed components no manifest accounts for.
+//! Entries are named `<artifact>!/<path inside it>` and carry [`ARTIFACT_MARKER`] in the
+//! version column, like [`crate::vendor_scan`]'s findings.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+
+use ignore::WalkBuilder;
+
+use crate::debug::{log, LogLevel};
+use crate::image::layers::{decompress, read_entries, EntryKind};
+use crate::licenses::{
+    detect_license_from_content, detect_license_from_source_header, fetch_licenses_from_github,
+    get_osi_status, is_license_ignored, is_license_restrictive, LicenseCompatibility, LicenseInfo,
+    OsiStatus,
+};
+
+/// Marker placed in the version column of a license found inside a build artifact.
+pub const ARTIFACT_MARKER: &str = "artifact";
+
+/// Zip-based archives: Java, Python wheels/eggs, NuGet, Android
+const ZIP_EXTENSIONS: &[&str] = &[
+    "jar", "war", "ear", "aar", "whl", "egg", "zip", "nupkg", "apk", "vsix",
+];
+
+/// Tarballs, optionally compressed (the compression is sniffed from the content)
+const TAR_SUFFIXES: &[&str] = &[".tar", ".tar.gz", ".tgz", ".tar.zst", ".tar.zstd", ".crate"];
+
+/// Native code whose string data may hold license texts
+const BINARY_EXTENSIONS: &[&str] = &["so", "dll", "dylib", "a", "lib", "exe", "node", "wasm"];
+
+/// Archives nested deeper than this (a jar in a jar in a war) aren't opened
+const MAX_NESTING: usize = 2;
+
+/// Binaries larger than this are skipped rather than read into memory
+const MAX_BINARY_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Shortest printable run considered when looking for license text inside a binary
+const MIN_STRING_LEN: usize = 24;
+
+/// Runs shorter than this are only checked for an SPDX tag, not full license text
+const MIN_LICENSE_TEXT_LEN: usize = 200;
+
+/// A license found inside an artifact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Finding {
+    /// `<artifact>!/<component path>`
+    name: String,
+    license: String,
+}
+
+/// Scan build output directories for embedded licenses and return them as [`LicenseInfo`]
+/// entries ready to be appended to the dependency report.
+///
+/// Components matching a name in `known_dependencies` are left out, as are licenses equal to
+/// `project_license` (an artifact's own LICENSE). Compatibility is annotated by the caller.
+pub fn scan_artifacts(
+    dirs: &[&Path],
+    known_dependencies: &[String],
+    project_license: Option<&str>,
+    strict: bool,
+) -> Vec<LicenseInfo> {
+    let known: BTreeSet<String> = known_dependencies
+        .iter()
+        .flat_map(|name| {
+            // Maven coordinates (`group:artifact`) are matched on the artifact id too
+            let name = name.to_lowercase();
+            let artifact = name.rsplit(':').next().map(str::to_string);
+            std::iter::once(name).chain(artifact)
+        })
+        .collect();
+
+    let mut findings: Vec<Finding> = Vec::new();
+    for dir in dirs {
+        findings.extend(collect_findings(dir));
+    }
+    findings.retain(|finding| {
+        let is_known = component_name(&finding.name)
+            .is_some_and(|component| known.contains(&component.to_lowercase()));
+        let is_own = project_license
+            .is_some_and(|project| project.trim().eq_ignore_ascii_case(finding.license.trim()));
+        !is_known && !is_own && !is_license_ignored(Some(&finding.license))
+    });
+    if findings.is_empty() {
+        return Vec::new();
+    }
+
+    let known_licenses = fetch_licenses_from_github().unwrap_or_else(|e| {
+        log(
+            LogLevel::Warn,
+            &format!("Failed to fetch license registry for artifact scan: {e}"),
+        );
+        HashMap::new()
+    });
+
+    findings
+        .into_iter()
+        .map(|finding| {
+            let license = Some(finding.license);
+            LicenseInfo {
+                name: finding.name,
+                version: ARTIFACT_MARKER.to_string(),
+                is_restrictive: is_license_restrictive(&license, &known_licenses, strict),
+                osi_status: license
+                    .as_deref()
+                    .map(get_osi_status)
+                    .unwrap_or(OsiStatus::Unknown),
+                license,
+                compatibility: LicenseCompatibility::Unknown,
+                sub_project: None,
+                found_in: Vec::new(),
+                repository: None,
+                declared_in: None,
+                internal: false,
+                inherited_from: None,
+                ecosystem: None,
+                suggestions: Vec::new(),
+                update_policy: None,
+                introduced_by: Vec::new(),
+                purl: None,
+                vulnerabilities: None,
+                manifest: None,
+                data_license: None,
+                project_dirs: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+/// Every embedded license under `dir`, one per component and license
+fn collect_findings(dir: &Path) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let walker = WalkBuilder::new(dir)
+        .standard_filters(false)
+        .sort_by_file_path(|a, b| a.cmp(b))
+        .build();
+
+    for entry in walker.flatten() {
+        if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+            continue;
+        }
+        let path = entry.path();
+        let relative = path.strip_prefix(dir).unwrap_or(path);
+        let label = Path::new(dir.file_name().unwrap_or_default())
+            .join(relative)
+            .display()
+            .to_string();
+        let Some(kind) = artifact_kind(&entry.file_name().to_string_lossy(), Some(path)) else {
+            continue;
+        };
+        let result = match kind {
+            ArtifactKind::Zip => fs::File::open(path)
+                .map_err(|e| e.to_string())
+                .and_then(|file| scan_zip(file, &label, 0)),
+            ArtifactKind::Tar => fs::File::open(path)
+                .map_err(|e| e.to_string())
+                .and_then(|file| scan_tar(file, &label, 0)),
+            ArtifactKind::Binary => scan_binary_file(path, &label),
+        };
+        match result {
+            Ok(found) => findings.extend(found),
+            Err(e) => log(
+                LogLevel::Warn,
+                &format!("Could not inspect artifact {}: {e}", path.display()),
+            ),
+        }
+    }
+
+    findings.sort_by(|a, b| a.name.cmp(&b.name).then(a.license.cmp(&b.license)));
+    findings.dedup();
+    log(
+        LogLevel::Info,
+        &format!(
+            "Found {} embedded licenses in artifacts under {}",
+            findings.len(),
+            dir.display()
+        ),
+    );
+    findings
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArtifactKind {
+    Zip,
+    Tar,
+    Binary,
+}
+
+/// What a file is, by name. Extensionless files on disk (`path`) count as binaries when
+/// they're ELF/Mach-O/PE executables.
+fn artifact_kind(name: &str, path: Option<&Path>) -> Option<ArtifactKind> {
+    let lower = name.to_lowercase();
+    if TAR_SUFFIXES.iter().any(|suffix| lower.ends_with(suffix)) {
+        return Some(ArtifactKind::Tar);
+    }
+    let extension = Path::new(&lower)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_string();
+    if ZIP_EXTENSIONS.contains(&extension.as_str()) {
+        return Some(ArtifactKind::Zip);
+    }
+    // `libfoo.so.1.2` carries its version after the extension
+    if BINARY_EXTENSIONS.contains(&extension.as_str()) || lower.contains(".so.") {
+        return Some(ArtifactKind::Binary);
+    }
+    if extension.is_empty() && path.is_some_and(has_executable_magic) {
+        return Some(ArtifactKind::Binary);
+    }
+    None
+}
+
+fn has_executable_magic(path: &Path) -> bool {
+    let mut magic = [0u8; 4];
+    let Ok(mut file) = fs::File::open(path) else {
+        return false;
+    };
+    if file.read_exact(&mut magic).is_err() {
+        return false;
+    }
+    matches!(
+        magic,
+        [0x7f, b'E', b'L', b'F']
+            | [0xcf, 0xfa, 0xed, 0xfe]
+            | [0xfe, 0xed, 0xfa, 0xcf]
+            | [0xca, 0xfe, 0xba, 0xbe]
+            | [b'M', b'Z', _, _]
+    )
+}
+
+/// Whether an entry inside an archive is a license or notice text
+fn is_license_entry(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path).to_ascii_uppercase();
+    let stem = file_name.split('.').next().unwrap_or(&file_name);
+    ["LICENSE", "LICENCE", "COPYING", "NOTICE"]
+        .iter()
+        .any(|prefix| stem.starts_with(prefix))
+        // `license.py`, `LicenseChecker.class`: code, not license text
+        && !path.ends_with(".class")
+        && !path.ends_with(".py")
+        && !path.ends_with(".js")
+}
+
+/// The component a license entry belongs to: the directory holding it inside the artifact,
+/// with `META-INF/` and `*.dist-info/licenses/` folded into their parent
+fn component_path(entry: &str) -> String {
+    let mut parts: Vec<&str> = entry.split('/').filter(|part| !part.is_empty()).collect();
+    parts.pop();
+    while let Some(last) = parts.last() {
+        if last.eq_ignore_ascii_case("META-INF") || last.eq_ignore_ascii_case("licenses") {
+            parts.pop();
+        } else {
+            break;
+        }
+    }
+    parts.join("/")
+}
+
+fn finding_name(artifact: &str, component: &str) -> String {
+    if component.is_empty() {
+        artifact.to_string()
+    } else {
+        format!("{artifact}!/{component}")
+    }
+}
+
+fn scan_zip(
+    reader: impl Read + Seek,
+    artifact: &str,
+    depth: usize,
+) -> Result<Vec<Finding>, String> {
+    let mut archive = zip::ZipArchive::new(reader).map_err(|e| e.to_string())?;
+    let mut findings = Vec::new();
+
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index).map_err(|e| e.to_string())?;
+        if !file.is_file() {
+            continue;
+        }
+        let name = file.name().to_string();
+        if is_license_entry(&name) {
+            let mut content = String::new();
+            if file.read_to_string(&mut content).is_ok() {
+                if let Some(license) = detect_license_from_content(&content) {
+                    findings.push(Finding {
+                        name: finding_name(artifact, &component_path(&name)),
+                        license,
+                    });
+                }
+            }
+            continue;
+        }
+        if depth >= MAX_NESTING {
+            continue;
+        }
+        let nested = format!("{artifact}!/{name}");
+        match artifact_kind(&name, None) {
+            Some(ArtifactKind::Zip) => {
+                let mut bytes = Vec::new();
+                file.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+                findings.extend(scan_zip(Cursor::new(bytes), &nested, depth + 1)?);
+            }
+            Some(ArtifactKind::Tar) => {
+                findings.extend(scan_tar(&mut file, &nested, depth + 1)?);
+            }
+            Some(ArtifactKind::Binary) => {
+                let mut bytes = Vec::new();
+                file.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+                findings.extend(binary_licenses(&bytes).into_iter().map(|license| Finding {
+                    name: nested.clone(),
+                    license,
+                }));
+            }
+            None => {}
+        }
+    }
+    Ok(findings)
+}
+
+fn scan_tar(reader: impl Read, artifact: &str, depth: usize) -> Result<Vec<Finding>, String> {
+    let mut findings = Vec::new();
+    read_entries(
+        decompress(reader).map_err(|e| e.to_string())?,
+        |entry, data| {
+            if entry.kind != EntryKind::File {
+                return Ok(());
+            }
+            let path = entry.path.trim_start_matches("./");
+            if is_license_entry(path) {
+                let mut content = String::new();
+                if data.read_to_string(&mut content).is_ok() {
+                    if let Some(license) = detect_license_from_content(&content) {
+                        findings.push(Finding {
+                            name: finding_name(artifact, &component_path(path)),
+                            license,
+                        });
+                    }
+                }
+            } else if depth < MAX_NESTING && artifact_kind(path, None) == Some(ArtifactKind::Zip) {
+                let mut bytes = Vec::new();
+                data.read_to_end(&mut bytes)?;
+                let nested = format!("{artifact}!/{path}");
+                if let Ok(found) = scan_zip(Cursor::new(bytes), &nested, depth + 1) {
+                    findings.extend(found);
+                }
+            }
+            Ok(())
+        },
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(findings)
+}
+
+fn scan_binary_file(path: &Path, artifact: &str) -> Result<Vec<Finding>, String> {
+    let size = fs::metadata(path).map_err(|e| e.to_string())?.len();
+    if size > MAX_BINARY_SIZE {
+        log(
+            LogLevel::Info,
+            &format!(
+                "Skipping {} ({size} bytes) in artifact scan",
+                path.display()
+            ),
+        );
+        return Ok(Vec::new());
+    }
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    Ok(binary_licenses(&bytes)
+        .into_iter()
+        .map(|license| Finding {
+            name: artifact.to_string(),
+            license,
+        })
+        .collect())
+}
+
+/// Licenses whose text or SPDX tag appears in a binary's printable string data
+fn binary_licenses(bytes: &[u8]) -> BTreeSet<String> {
+    let mut licenses = BTreeSet::new();
+    let printable = |b: &u8| b.is_ascii_graphic() || matches!(b, b' ' | b'\t' | b'\n' | b'\r');
+
+    for run in bytes.split(|b| !printable(b)) {
+        if run.len() < MIN_STRING_LEN {
+            continue;
+        }
+        let text = String::from_utf8_lossy(run);
+        if text.contains("SPDX-License-Identifier:") {
+            licenses.extend(detect_license_from_source_header(&text));
+        } else if run.len() >= MIN_LICENSE_TEXT_LEN {
+            licenses.extend(detect_license_from_content(&text));
+        }
+    }
+    licenses
+}
+
+/// The package name a finding's component path suggests, for matching against manifests:
+/// `guava-31.1-jre.jar` → `guava`, `requests-2.31.0.dist-info` → `requests`
+fn component_name(name: &str) -> Option<String> {
+    let last = name.rsplit("!/").next()?;
+    let last = last.trim_end_matches('/').rsplit('/').next()?;
+    let stem = last
+        .strip_suffix(".dist-info")
+        .or_else(|| last.strip_suffix(".egg-info"))
+        .or_else(|| last.rsplit_once('.').map(|(stem, _)| stem))
+        .unwrap_or(last);
+    // Drop the version: everything from the first `-<digit>` or `_<digit>` on
+    let bytes = stem.as_bytes();
+    let end = (1..bytes.len())
+        .find(|&i| matches!(bytes[i - 1], b'-' | b'_') && bytes[i].is_ascii_digit())
+        .map(|i| i - 1)
+        .unwrap_or(stem.len());
+    let name = &stem[..end];
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    const MIT_TEXT: &str = "MIT License\n\nPermission is hereby granted, free of charge, to any \
+person obtaining a copy of this software and associated documentation files (the \"Software\"), \
+to deal in the Software without restriction.\n";
+
+    const GPL3_TEXT: &str = "GNU GENERAL PUBLIC LICENSE\nVersion 3, 29 June 2007\n\nCopyright (C) \
+2007 Free Software Foundation, Inc. Everyone is permitted to copy and distribute verbatim copies \
+of this license document, but changing it is not allowed.\n";
+
+    fn zip_bytes(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_fat_jar_nested_licenses() {
+        let dir = TempDir::new().unwrap();
+        let nested = zip_bytes(&[("META-INF/LICENSE", GPL3_TEXT.as_bytes())]);
+        let fat = zip_bytes(&[
+            ("META-INF/LICENSE", MIT_TEXT.as_bytes()),
+            ("BOOT-INF/lib/readline-8.2.jar", &nested),
+            ("com/example/App.class", b"\xca\xfe\xba\xbe"),
+        ]);
+        fs::write(dir.path().join("app.jar"), fat).unwrap();
+
+        let build = dir
+            .path()
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .to_string();
+        assert_eq!(
+            collect_findings(dir.path()),
+            vec![
+                Finding {
+                    name: format!("{build}/app.jar"),
+                    license: "MIT".to_string(),
+                },
+                Finding {
+                    name: format!("{build}/app.jar!/BOOT-INF/lib/readline-8.2.jar"),
+                    license: "GPL-3.0".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_known_and_own_licenses_are_left_out() {
+        let dir = TempDir::new().unwrap();
+        let nested = zip_bytes(&[("META-INF/LICENSE", GPL3_TEXT.as_bytes())]);
+        let fat = zip_bytes(&[
+            ("META-INF/LICENSE", MIT_TEXT.as_bytes()),
+            ("BOOT-INF/lib/readline-8.2.jar", &nested),
+        ]);
+        fs::write(dir.path().join("app.jar"), fat).unwrap();
+
+        let deps = scan_artifacts(
+            &[dir.path()],
+            &["org.gnu:readline".to_string()],
+            Some("MIT"),
+            false,
+        );
+        assert!(deps.is_empty());
+    }
+
+    #[test]
+    fn test_binary_license_strings() {
+        let mut bytes = vec![0x7f, b'E', b'L', b'F', 0, 1, 2];
+        bytes.extend_from_slice(MIT_TEXT.repeat(2).as_bytes());
+        bytes.extend_from_slice(&[0, 0, 3]);
+        bytes.extend_from_slice(b"// SPDX-License-Identifier: Apache-2.0");
+        bytes.push(0);
+
+        let licenses = binary_licenses(&bytes);
+        assert!(licenses.contains("MIT"));
+        assert!(licenses.contains("Apache-2.0"));
+    }
+
+    #[test]
+    fn test_component_name() {
+        assert_eq!(
+            component_name("build/app.jar!/BOOT-INF/lib/guava-31.1-jre.jar"),
+            Some("guava".to_string())
+        );
+        assert_eq!(
+            component_name("dist/app.whl!/requests-2.31.0.dist-info"),
+            Some("requests".to_string())
+        );
+        assert_eq!(
+            component_name("target/libfoo_sys.so"),
+            Some("libfoo_sys".to_string())
+        );
+    }
+
+    #[test]
+    fn test_component_path() {
+        assert_eq!(component_path("META-INF/LICENSE"), "");
+        assert_eq!(
+            component_path("requests-2.31.0.dist-info/licenses/LICENSE"),
+            "requests-2.31.0.dist-info"
+        );
+        assert_eq!(component_path("vendor/zlib/COPYING"), "vendor/zlib");
+    }
+}
@@ -0,0 +1,557 @@
+//! Comparison against a previous report
+//!
+//! `--baseline <FILE>` takes the JSON written by an earlier `feluda --json` run. Rows are
+//! matched by name and version first; a package still present under another version, or
+//! whose license moved, counts as changed. Whatever is left on either side was added or
+//! removed.
+//!
+//! Report metadata records the hash of the lockfiles the rows were resolved from. Comparing two reports
+//! whose lockfile states differ is refused unless `--force` is passed: the rows would differ
+//! because the dependencies moved, which hides whether the license data itself drifted.
+//!
+//! Outside the TUI the baseline suppresses known findings instead: a restrictive or
+//! incompatible dependency the baseline already flagged for the same reason is still reported
+//! but doesn't fail the build, whatever its version now, so only new findings do.
+//! `--write-baseline <FILE>` records the current scan as such a baseline.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use colored::*;
+
+use crate::conflicts::{find_license_conflicts, LicenseConflict};
+use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
+use crate::licenses::{LicenseCompatibility, LicenseInfo};
+use crate::report_metadata::{Report, ReportMetadata, StoredReport};
+
+/// How a row differs from the baseline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RowChange {
+    Added,
+    Removed,
+    /// What the baseline said before, e.g. "was v1.2.0, license was MIT"
+    Changed(String),
+    Unchanged,
+}
+
+/// Result of comparing a scan with a baseline report
+#[derive(Debug, Default)]
+pub struct BaselineDiff {
+    /// Change for every current and removed row, keyed by (name, version)
+    pub changes: HashMap<(String, String), RowChange>,
+    /// Baseline rows with no counterpart in the current scan
+    pub removed: Vec<LicenseInfo>,
+}
+
+impl BaselineDiff {
+    pub fn change_for(&self, item: &LicenseInfo) -> &RowChange {
+        self.changes
+            .get(&(item.name.clone(), item.version.clone()))
+            .unwrap_or(&RowChange::Unchanged)
+    }
+
+    /// Count of (added, removed, changed) rows
+    pub fn counts(&self) -> (usize, usize, usize) {
+        self.changes.values().fold(
+            (0, 0, 0),
+            |(added, removed, changed), change| match change {
+                RowChange::Added => (added + 1, removed, changed),
+                RowChange::Removed => (added, removed + 1, changed),
+                RowChange::Changed(_) => (added, removed, changed + 1),
+                RowChange::Unchanged => (added, removed, changed),
+            },
+        )
+    }
+}
+
+/// Read a report written by `feluda --json`
+pub fn load_baseline(path: &Path) -> FeludaResult<Vec<LicenseInfo>> {
+    load_baseline_with_metadata(path).map(|(baseline, _)| baseline)
+}
+
+/// [`load_baseline`], with the scan metadata the report carries
+pub fn load_baseline_with_metadata(
+    path: &Path,
+) -> FeludaResult<(Vec<LicenseInfo>, Option<ReportMetadata>)> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        FeludaError::InvalidData(format!("Failed to read baseline {}: {e}", path.display()))
+    })?;
+    let baseline = serde_json::from_str::<StoredReport>(&content)
+        .map_err(|e| {
+            FeludaError::InvalidData(format!(
+                "Baseline {} is not a `feluda --json` report: {e}",
+                path.display()
+            ))
+        })?
+        .into_parts();
+
+    log(
+        LogLevel::Info,
+        &format!(
+            "Loaded baseline with {} dependencies from {}",
+            baseline.0.len(),
+            path.display()
+        ),
+    );
+    Ok(baseline)
+}
+
+/// Write the scan as a baseline for later `--baseline` runs: a `--json` report of every
+/// dependency
+pub fn write_baseline(
+    path: &Path,
+    dependencies: &[LicenseInfo],
+    project_license: Option<&str>,
+    metadata: &ReportMetadata,
+) -> FeludaResult<()> {
+    let report = Report::new(Some(metadata), project_license, dependencies);
+    let json = serde_json::to_string_pretty(&report)
+        .map_err(|e| FeludaError::Serialization(format!("Failed to serialize baseline: {e}")))?;
+    std::fs::write(path, json + "\n").map_err(|e| {
+        FeludaError::FileWrite(format!("Failed to write baseline {}: {e}", path.display()))
+    })?;
+
+    let findings = dependencies.iter().filter(|dep| is_finding(dep)).count();
+    eprintln!(
+        "Baseline written to: {} ({findings} finding{})",
+        path.display(),
+        if findings == 1 { "" } else { "s" }
+    );
+    Ok(())
+}
+
+/// Findings a baseline already had, which are reported but don't fail the build
+#[derive(Debug, Default)]
+pub struct KnownFindings {
+    restrictive: HashSet<String>,
+    incompatible: HashSet<String>,
+    /// (copyleft, conflicting) license pairs
+    conflicts: HashSet<(String, String)>,
+}
+
+impl KnownFindings {
+    pub fn from_baseline(baseline: &[LicenseInfo]) -> Self {
+        Self {
+            restrictive: baseline
+                .iter()
+                .filter(|dep| dep.is_restrictive)
+                .map(|dep| dep.name.clone())
+                .collect(),
+            incompatible: baseline
+                .iter()
+                .filter(|dep| dep.compatibility == LicenseCompatibility::Incompatible)
+                .map(|dep| dep.name.clone())
+                .collect(),
+            conflicts: find_license_conflicts(baseline)
+                .iter()
+                .map(conflict_key)
+                .collect(),
+        }
+    }
+
+    /// Whether the baseline already flagged `name` as a `kind` (`restrictive`, `incompatible`)
+    /// finding
+    pub fn knows(&self, kind: &str, name: &str) -> bool {
+        match kind {
+            "restrictive" => self.restrictive.contains(name),
+            "incompatible" => self.incompatible.contains(name),
+            _ => false,
+        }
+    }
+
+    /// Whether the baseline already flagged `dep` for everything it's flagged for now
+    pub fn is_known(&self, dep: &LicenseInfo) -> bool {
+        (!dep.is_restrictive || self.restrictive.contains(&dep.name))
+            && (dep.compatibility != LicenseCompatibility::Incompatible
+                || self.incompatible.contains(&dep.name))
+    }
+
+    /// Sort the scan's findings into the new ones and the known ones the baseline suppresses
+    pub fn check(&self, dependencies: &[LicenseInfo]) -> BaselineCheck {
+        let new_restrictive = dependencies
+            .iter()
+            .filter(|dep| dep.is_restrictive && !self.restrictive.contains(&dep.name))
+            .cloned()
+            .collect();
+        let new_incompatible = dependencies
+            .iter()
+            .filter(|dep| {
+                dep.compatibility == LicenseCompatibility::Incompatible
+                    && !self.incompatible.contains(&dep.name)
+            })
+            .cloned()
+            .collect();
+        let new_conflicts = find_license_conflicts(dependencies)
+            .into_iter()
+            .filter(|conflict| !self.conflicts.contains(&conflict_key(conflict)))
+            .collect();
+        let suppressed = dependencies
+            .iter()
+            .filter(|dep| is_finding(dep) && self.is_known(dep))
+            .count();
+        BaselineCheck {
+            new_restrictive,
+            new_incompatible,
+            new_conflicts,
+            suppressed,
+        }
+    }
+}
+
+/// The scan's findings measured against a baseline
+#[derive(Debug, Default)]
+pub struct BaselineCheck {
+    pub new_restrictive: Vec<LicenseInfo>,
+    pub new_incompatible: Vec<LicenseInfo>,
+    pub new_conflicts: Vec<LicenseConflict>,
+    /// Restrictive or incompatible dependencies the baseline already had
+    pub suppressed: usize,
+}
+
+impl BaselineCheck {
+    /// Whether anything restrictive the baseline doesn't have was found
+    pub fn has_new_restrictive(&self) -> bool {
+        !self.new_restrictive.is_empty()
+    }
+
+    /// Whether anything incompatible the baseline doesn't have was found, conflicts included
+    pub fn has_new_incompatible(&self) -> bool {
+        !self.new_incompatible.is_empty() || !self.new_conflicts.is_empty()
+    }
+
+    /// List the new findings and how many known ones were suppressed.
+    ///
+    /// Written to stderr like the other report sections.
+    pub fn print(&self, baseline_path: &str) {
+        let new =
+            self.new_restrictive.len() + self.new_incompatible.len() + self.new_conflicts.len();
+        eprintln!(
+            "{} {}",
+            "📌".bold(),
+            format!(
+                "Baseline {baseline_path}: {new} new finding{}, {} known one{} not failing the build",
+                if new == 1 { "" } else { "s" },
+                self.suppressed,
+                if self.suppressed == 1 { "" } else { "s" }
+            )
+            .bold()
+        );
+        for (deps, kind) in [
+            (&self.new_restrictive, "restrictive"),
+            (&self.new_incompatible, "incompatible"),
+        ] {
+            for dep in deps {
+                eprintln!(
+                    "  • {} {} ({}, {kind})",
+                    dep.name.as_str().bold(),
+                    dep.version,
+                    dep.get_license()
+                );
+            }
+        }
+        for conflict in &self.new_conflicts {
+            eprintln!("  • {conflict}");
+        }
+        eprintln!();
+    }
+}
+
+/// Whether `dep` is restrictive or incompatible
+fn is_finding(dep: &LicenseInfo) -> bool {
+    dep.is_restrictive || dep.compatibility == LicenseCompatibility::Incompatible
+}
+
+fn conflict_key(conflict: &LicenseConflict) -> (String, String) {
+    (conflict.copyleft.clone(), conflict.conflicting.clone())
+}
+
+/// Rows of two reports paired up: by name and version first, then a package still present
+/// under another version by name alone
+#[derive(Debug, Default)]
+pub struct RowMatch<'a> {
+    /// (earlier row, current row)
+    pub matched: Vec<(&'a LicenseInfo, &'a LicenseInfo)>,
+    /// Current rows with no earlier counterpart
+    pub added: Vec<&'a LicenseInfo>,
+    /// Earlier rows with no current counterpart
+    pub removed: Vec<&'a LicenseInfo>,
+}
+
+/// Pair the rows of the current scan with those of an earlier report
+pub fn match_rows<'a>(current: &'a [LicenseInfo], earlier: &'a [LicenseInfo]) -> RowMatch<'a> {
+    let mut remaining: Vec<&LicenseInfo> = earlier.iter().collect();
+    let mut rows = RowMatch::default();
+    let mut unmatched = Vec::new();
+
+    for dep in current {
+        match remaining
+            .iter()
+            .position(|old| old.name == dep.name && old.version == dep.version)
+        {
+            Some(index) => rows.matched.push((remaining.swap_remove(index), dep)),
+            None => unmatched.push(dep),
+        }
+    }
+
+    // Same package under another version
+    for dep in unmatched {
+        match remaining.iter().position(|old| old.name == dep.name) {
+            Some(index) => rows.matched.push((remaining.swap_remove(index), dep)),
+            None => rows.added.push(dep),
+        }
+    }
+
+    rows.removed = remaining;
+    rows
+}
+
+/// Compare the current scan with a baseline report
+pub fn diff_against_baseline(current: &[LicenseInfo], baseline: &[LicenseInfo]) -> BaselineDiff {
+    let rows = match_rows(current, baseline);
+    let mut changes = HashMap::new();
+
+    for (old, dep) in rows.matched {
+        let mut was = Vec::new();
+        if old.version != dep.version {
+            was.push(format!("was v{}", old.version));
+        }
+        if old.license != dep.license {
+            was.push(format!("license was {}", old.get_license()));
+        }
+        let change = if was.is_empty() {
+            RowChange::Unchanged
+        } else {
+            RowChange::Changed(was.join(", "))
+        };
+        changes.insert(key(dep), change);
+    }
+    for dep in rows.added {
+        changes.insert(key(dep), RowChange::Added);
+    }
+
+    let removed: Vec<LicenseInfo> = rows.removed.into_iter().cloned().collect();
+    for dep in &removed {
+        changes.insert(key(dep), RowChange::Removed);
+    }
+
+    BaselineDiff { changes, removed }
+}
+
+/// Refuse to compare reports generated from different lockfile states, unless `force`.
+///
+/// The hashes are the ones each report's metadata records. Version churn would show up as
+/// license changes and hide whether the license data itself drifted; with `force` the
+/// mismatch is only logged.
+pub fn check_lockfile_state(
+    current: &[LicenseInfo],
+    current_hash: Option<&str>,
+    baseline: &[LicenseInfo],
+    baseline_hash: Option<&str>,
+    baseline_path: &Path,
+    force: bool,
+) -> FeludaResult<()> {
+    let Some(warning) = lockfile_state_warning(
+        current,
+        current_hash,
+        baseline,
+        baseline_hash,
+        baseline_path,
+    ) else {
+        return Ok(());
+    };
+    if force {
+        log(LogLevel::Warn, &format!("{warning} (--force)"));
+        return Ok(());
+    }
+    Err(FeludaError::Lockfile(format!(
+        "{warning}; regenerate the baseline or pass --force to compare anyway"
+    )))
+}
+
+/// Why comparing `current` with `baseline` may mislead, naming the lockfiles behind the rows.
+///
+/// Reports that predate lockfile hashes, or scans of trees without lockfiles, carry no hash
+/// and compare without a warning.
+fn lockfile_state_warning(
+    current: &[LicenseInfo],
+    current_hash: Option<&str>,
+    baseline: &[LicenseInfo],
+    baseline_hash: Option<&str>,
+    baseline_path: &Path,
+) -> Option<String> {
+    let (Some(current_hash), Some(baseline_hash)) = (current_hash, baseline_hash) else {
+        log(
+            LogLevel::Info,
+            "Lockfile state not recorded on both sides, comparing without checking it",
+        );
+        return None;
+    };
+    if current_hash == baseline_hash {
+        return None;
+    }
+
+    let mut lockfiles: Vec<&str> = current
+        .iter()
+        .chain(baseline)
+        .filter_map(|dep| dep.manifest.as_deref())
+        .filter(|manifest| {
+            Path::new(manifest)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(crate::manifest::is_lockfile)
+        })
+        .collect();
+    lockfiles.sort_unstable();
+    lockfiles.dedup();
+    let lockfiles = if lockfiles.is_empty() {
+        "the lockfiles".to_string()
+    } else {
+        lockfiles.join(", ")
+    };
+    Some(format!(
+        "{lockfiles} changed since {} was generated ({baseline_hash}, now {current_hash}); \
+         version changes may show up as license changes",
+        baseline_path.display()
+    ))
+}
+
+fn key(dep: &LicenseInfo) -> (String, String) {
+    (dep.name.clone(), dep.version.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::licenses::LicenseCompatibility;
+
+    #[test]
+    fn test_diff_against_baseline() {
+        let baseline = vec![
+            LicenseInfo::fixture("serde", "1.0.0", "MIT"),
+            LicenseInfo::fixture("tokio", "1.0.0", "MIT"),
+            LicenseInfo::fixture("openssl", "0.10.0", "Apache-2.0"),
+            LicenseInfo::fixture("left-pad", "1.0.0", "WTFPL"),
+        ];
+        let current = vec![
+            LicenseInfo::fixture("serde", "1.0.0", "MIT"),
+            LicenseInfo::fixture("tokio", "1.1.0", "MIT"),
+            LicenseInfo::fixture("openssl", "0.10.0", "GPL-3.0"),
+            LicenseInfo::fixture("rayon", "1.8.0", "MIT"),
+        ];
+
+        let diff = diff_against_baseline(&current, &baseline);
+        assert_eq!(diff.change_for(&current[0]), &RowChange::Unchanged);
+        assert_eq!(
+            diff.change_for(&current[1]),
+            &RowChange::Changed("was v1.0.0".to_string())
+        );
+        assert_eq!(
+            diff.change_for(&current[2]),
+            &RowChange::Changed("license was Apache-2.0".to_string())
+        );
+        assert_eq!(diff.change_for(&current[3]), &RowChange::Added);
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].name, "left-pad");
+        assert_eq!(diff.change_for(&diff.removed[0]), &RowChange::Removed);
+        assert_eq!(diff.counts(), (1, 1, 2));
+    }
+
+    #[test]
+    fn test_known_findings() {
+        let flagged = |name: &str, version: &str, incompatible: bool| {
+            let mut row = LicenseInfo::fixture(name, version, "GPL-3.0-only");
+            row.is_restrictive = true;
+            if incompatible {
+                row.compatibility = LicenseCompatibility::Incompatible;
+            }
+            row
+        };
+        let known = KnownFindings::from_baseline(&[
+            LicenseInfo::fixture("serde", "1.0.0", "MIT"),
+            flagged("readline", "8.1", false),
+        ]);
+
+        // A version bump of a known finding stays known; a new reason to flag it doesn't
+        let check = known.check(&[
+            LicenseInfo::fixture("serde", "1.0.0", "MIT"),
+            flagged("readline", "8.2", false),
+        ]);
+        assert!(!check.has_new_restrictive() && !check.has_new_incompatible());
+        assert_eq!(check.suppressed, 1);
+
+        let check = known.check(&[
+            flagged("readline", "8.2", true),
+            flagged("gmp", "6.3", false),
+        ]);
+        assert_eq!(check.new_restrictive.len(), 1);
+        assert_eq!(check.new_restrictive[0].name, "gmp");
+        assert_eq!(check.new_incompatible.len(), 1);
+        assert_eq!(check.new_incompatible[0].name, "readline");
+        assert_eq!(check.suppressed, 0);
+        assert!(known.knows("restrictive", "readline"));
+        assert!(!known.knows("incompatible", "readline"));
+    }
+
+    #[test]
+    fn test_check_lockfile_state() {
+        let path = Path::new("baseline.json");
+        let mut row = LicenseInfo::fixture("serde", "1.0.0", "MIT");
+        row.manifest = Some("Cargo.lock".to_string());
+        let rows = vec![row];
+        let current = Some("sha256:aaa");
+
+        assert_eq!(
+            lockfile_state_warning(&rows, current, &rows, Some("sha256:aaa"), path),
+            None
+        );
+        let warning =
+            lockfile_state_warning(&rows, current, &rows, Some("sha256:bbb"), path).unwrap();
+        assert!(warning.starts_with("Cargo.lock changed since baseline.json"));
+        assert!(warning.contains("sha256:bbb, now sha256:aaa"));
+        let err = check_lockfile_state(&rows, current, &rows, Some("sha256:bbb"), path, false)
+            .unwrap_err();
+        assert!(matches!(err, FeludaError::Lockfile(_)));
+        assert!(
+            check_lockfile_state(&rows, current, &rows, Some("sha256:bbb"), path, true).is_ok()
+        );
+
+        // Older reports without a hash are still comparable, and so is an empty report
+        assert_eq!(
+            lockfile_state_warning(&rows, current, &rows, None, path),
+            None
+        );
+        assert_eq!(
+            lockfile_state_warning(&rows, None, &rows, current, path),
+            None
+        );
+        assert!(check_lockfile_state(&rows, current, &rows, None, path, false).is_ok());
+        assert!(check_lockfile_state(&[], current, &[], Some("sha256:bbb"), path, false).is_err());
+    }
+
+    #[test]
+    fn test_load_baseline_from_json_report() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("report.json");
+        let report =
+            serde_json::to_string_pretty(&vec![LicenseInfo::fixture("serde", "1.0.0", "MIT")])
+                .unwrap();
+        std::fs::write(&path, report).unwrap();
+
+        let baseline = load_baseline(&path).unwrap();
+        assert_eq!(baseline.len(), 1);
+        assert_eq!(baseline[0].name, "serde");
+
+        let dependencies = [LicenseInfo::fixture("serde", "1.0.0", "MIT")];
+        let report = serde_json::to_string_pretty(&crate::report_metadata::Report::new(
+            None,
+            None,
+            &dependencies,
+        ))
+        .unwrap();
+        std::fs::write(&path, report).unwrap();
+        assert_eq!(load_baseline(&path).unwrap()[0].name, "serde");
+
+        std::fs::write(&path, "{\"not\": \"a report\"}").unwrap();
+        assert!(load_baseline(&path).is_err());
+    }
+}
@@ -0,0 +1,366 @@
+//! `feluda bench` (hidden): time scans of large synthetic projects
+//!
+//! Writes an npm project and a vendored Go module with `--packages` dependencies each, whose
+//! licenses sit in package.json files and in bare LICENSE texts, and times offline scans of them
+//! along with license text detection on its own. Each case runs `--iterations` times and reports
+//! the median, so parallelism and caching changes can be compared run over run.
+//! `--baseline` takes an earlier `--json` result and fails when a case got slower than
+//! `--tolerance` allows, which is how CI catches performance regressions.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Instant;
+
+use colored::*;
+use serde::{Deserialize, Serialize};
+use tempfile::TempDir;
+
+use crate::config::FeludaConfig;
+use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
+use crate::license_corpus;
+use crate::licenses::detect_license_from_content;
+use crate::parser::parse_root_with_config;
+
+/// Licenses the synthetic dependencies cycle through; every fourth one only has a LICENSE text
+const FIXTURE_LICENSES: [&str; 4] = ["MIT", "ISC", "BSD-3-Clause", "Zlib"];
+
+/// Timings of one benchmark case, in milliseconds
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BenchCase {
+    pub name: String,
+    pub median_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+}
+
+/// What `feluda bench --json` prints, and what `--baseline` reads back
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub packages: usize,
+    pub iterations: usize,
+    pub cases: Vec<BenchCase>,
+}
+
+/// A case that got slower than the baseline allows
+#[derive(Debug, PartialEq)]
+struct Regression {
+    case: String,
+    baseline_ms: f64,
+    current_ms: f64,
+}
+
+pub fn handle_bench_command(
+    packages: usize,
+    iterations: usize,
+    json: bool,
+    baseline: Option<&str>,
+    tolerance: f64,
+    fixtures: Option<&str>,
+) -> FeludaResult<()> {
+    let baseline = baseline.map(read_baseline).transpose()?;
+    if let Some(baseline) = baseline.as_ref().filter(|b| b.packages != packages) {
+        let message = format!(
+            "Baseline was measured with {} packages, this run would use {packages}",
+            baseline.packages
+        );
+        eprintln!("❌ {message}. Pass --packages {}.", baseline.packages);
+        return Err(FeludaError::InvalidData(message));
+    }
+
+    let temp_dir;
+    let root = match fixtures {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            temp_dir = TempDir::new().map_err(|e| {
+                FeludaError::TempDir(format!("Failed to create temporary directory: {e}"))
+            })?;
+            temp_dir.path().to_path_buf()
+        }
+    };
+    write_node_fixture(&root.join("node"), packages)?;
+    write_go_fixture(&root.join("go"), packages)?;
+
+    // Synthetic packages exist nowhere but on disk
+    crate::cancel::set_offline(true);
+    let config = FeludaConfig::default();
+    let iterations = iterations.max(1);
+
+    let mut cases = Vec::new();
+    for ecosystem in ["node", "go"] {
+        let project = root.join(ecosystem);
+        cases.push(time_case(ecosystem, iterations, || {
+            parse_root_with_config(&project, None, &config, false, None).map(|_| ())
+        })?);
+    }
+    let texts = license_texts(packages);
+    cases.push(time_case("license-text", iterations, || {
+        for text in &texts {
+            detect_license_from_content(text);
+        }
+        Ok(())
+    })?);
+
+    let report = BenchReport {
+        packages,
+        iterations,
+        cases,
+    };
+    if json {
+        let output = serde_json::to_string_pretty(&report).map_err(|e| {
+            FeludaError::Serialization(format!("Failed to serialize benchmark results: {e}"))
+        })?;
+        println!("{output}");
+    } else {
+        print_report(&report);
+    }
+    if let Some(dir) = fixtures {
+        eprintln!("Synthetic projects kept in: {dir}");
+    }
+
+    let Some(baseline) = baseline else {
+        return Ok(());
+    };
+    let regressions = regressions(&report, &baseline, tolerance);
+    if regressions.is_empty() {
+        eprintln!("No case is more than {tolerance}% slower than the baseline");
+        return Ok(());
+    }
+    for regression in &regressions {
+        eprintln!(
+            "{} {}: {:.1} ms, baseline {:.1} ms",
+            "Regression".red().bold(),
+            regression.case,
+            regression.current_ms,
+            regression.baseline_ms
+        );
+    }
+    log(
+        LogLevel::Warn,
+        "Exiting with non-zero status due to performance regressions",
+    );
+    process::exit(1);
+}
+
+fn read_baseline(path: &str) -> FeludaResult<BenchReport> {
+    fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read baseline {path}: {e}"))
+        .and_then(|content| {
+            serde_json::from_str(&content)
+                .map_err(|e| format!("Invalid benchmark baseline {path}: {e}"))
+        })
+        .map_err(|message| {
+            eprintln!("❌ {message}");
+            FeludaError::InvalidData(message)
+        })
+}
+
+/// Run `run` `iterations` times and summarise the wall-clock times
+fn time_case(
+    name: &str,
+    iterations: usize,
+    mut run: impl FnMut() -> FeludaResult<()>,
+) -> FeludaResult<BenchCase> {
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        run()?;
+        samples.push(start.elapsed().as_secs_f64() * 1000.0);
+    }
+    samples.sort_by(f64::total_cmp);
+    log(LogLevel::Info, &format!("Benchmark {name}: {samples:?} ms"));
+    Ok(BenchCase {
+        name: name.to_string(),
+        median_ms: samples[samples.len() / 2],
+        min_ms: samples[0],
+        max_ms: samples[samples.len() - 1],
+    })
+}
+
+/// Cases whose median exceeds the baseline's by more than `tolerance` percent
+fn regressions(current: &BenchReport, baseline: &BenchReport, tolerance: f64) -> Vec<Regression> {
+    current
+        .cases
+        .iter()
+        .filter_map(|case| {
+            let before = baseline.cases.iter().find(|b| b.name == case.name)?;
+            (case.median_ms > before.median_ms * (1.0 + tolerance / 100.0)).then(|| Regression {
+                case: case.name.clone(),
+                baseline_ms: before.median_ms,
+                current_ms: case.median_ms,
+            })
+        })
+        .collect()
+}
+
+fn print_report(report: &BenchReport) {
+    println!(
+        "{} {} packages, {} iterations",
+        "Benchmark".bold(),
+        report.packages,
+        report.iterations
+    );
+    for case in &report.cases {
+        println!(
+            "  {:<14} median {:>9.1} ms  (min {:.1}, max {:.1})",
+            case.name, case.median_ms, case.min_ms, case.max_ms
+        );
+    }
+}
+
+fn fixture_license(index: usize) -> &'static str {
+    FIXTURE_LICENSES[index % FIXTURE_LICENSES.len()]
+}
+
+/// A LICENSE file as packages ship it: the reference text under the package's copyright line
+fn license_file(index: usize) -> String {
+    let text = license_corpus::reference_text(fixture_license(index)).unwrap_or_default();
+    format!("Copyright (c) 2024 Bench Author {index}\n\n{text}")
+}
+
+/// License texts for the detection case, one per package; every fifth one matches no corpus
+/// text and goes on to the marker rules
+fn license_texts(packages: usize) -> Vec<String> {
+    (0..packages)
+        .map(|index| match index % 5 {
+            4 => "Apache License\nVersion 2.0, January 2004\nhttp://www.apache.org/licenses/\n\n\
+                  TERMS AND CONDITIONS FOR USE, REPRODUCTION, AND DISTRIBUTION"
+                .to_string(),
+            _ => license_file(index),
+        })
+        .collect()
+}
+
+fn create_dir(dir: &Path) -> FeludaResult<()> {
+    fs::create_dir_all(dir)
+        .map_err(|e| FeludaError::FileWrite(format!("Failed to create {}: {e}", dir.display())))
+}
+
+fn write_file(path: &Path, content: &str) -> FeludaResult<()> {
+    fs::write(path, content)
+        .map_err(|e| FeludaError::FileWrite(format!("Failed to write {}: {e}", path.display())))
+}
+
+/// An npm project: package.json, a v3 package-lock.json and an installed node_modules
+fn write_node_fixture(dir: &Path, packages: usize) -> FeludaResult<()> {
+    create_dir(dir)?;
+    let name = |index: usize| format!("bench-pkg-{index}");
+    let version = |index: usize| format!("1.{}.{}", index / 100, index % 100);
+
+    let dependencies: serde_json::Map<String, serde_json::Value> = (0..packages)
+        .map(|index| (name(index), format!("^{}", version(index)).into()))
+        .collect();
+    let manifest = serde_json::json!({
+        "name": "feluda-bench",
+        "version": "1.0.0",
+        "dependencies": dependencies,
+    });
+
+    let mut locked = serde_json::Map::new();
+    locked.insert(
+        String::new(),
+        serde_json::json!({"name": "feluda-bench", "dependencies": dependencies}),
+    );
+    for index in 0..packages {
+        locked.insert(
+            format!("node_modules/{}", name(index)),
+            serde_json::json!({"version": version(index)}),
+        );
+
+        let package_dir = dir.join("node_modules").join(name(index));
+        create_dir(&package_dir)?;
+        let mut package = serde_json::json!({"name": name(index), "version": version(index)});
+        if index % 4 == 3 {
+            write_file(&package_dir.join("LICENSE"), &license_file(index))?;
+        } else {
+            package["license"] = fixture_license(index).into();
+        }
+        write_file(&package_dir.join("package.json"), &package.to_string())?;
+    }
+    let lockfile = serde_json::json!({
+        "name": "feluda-bench",
+        "lockfileVersion": 3,
+        "packages": locked,
+    });
+
+    write_file(&dir.join("package.json"), &manifest.to_string())?;
+    write_file(&dir.join("package-lock.json"), &lockfile.to_string())
+}
+
+/// A Go module whose dependencies are vendored with their LICENSE files
+fn write_go_fixture(dir: &Path, packages: usize) -> FeludaResult<()> {
+    create_dir(dir)?;
+    let mut go_mod = String::from("module example.com/feluda-bench\n\ngo 1.22\n\nrequire (\n");
+    for index in 0..packages {
+        let module = format!("example.com/bench/mod{index}");
+        go_mod.push_str(&format!("\t{module} v1.0.{index}\n"));
+
+        let vendored = dir.join("vendor").join(&module);
+        create_dir(&vendored)?;
+        write_file(&vendored.join("LICENSE"), &license_file(index))?;
+    }
+    go_mod.push_str(")\n");
+    write_file(&dir.join("go.mod"), &go_mod)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(cases: &[(&str, f64)]) -> BenchReport {
+        BenchReport {
+            packages: 100,
+            iterations: 3,
+            cases: cases
+                .iter()
+                .map(|(name, median_ms)| BenchCase {
+                    name: name.to_string(),
+                    median_ms: *median_ms,
+                    min_ms: *median_ms,
+                    max_ms: *median_ms,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_regressions_respect_tolerance() {
+        let baseline = report(&[("node", 100.0), ("go", 50.0)]);
+        let current = report(&[("node", 115.0), ("go", 65.0), ("license-text", 10.0)]);
+
+        assert_eq!(
+            regressions(&current, &baseline, 20.0),
+            vec![Regression {
+                case: "go".to_string(),
+                baseline_ms: 50.0,
+                current_ms: 65.0,
+            }]
+        );
+        assert_eq!(regressions(&current, &baseline, 50.0), Vec::new());
+    }
+
+    #[test]
+    fn test_fixtures_carry_detectable_licenses() {
+        let temp_dir = TempDir::new().unwrap();
+        write_node_fixture(&temp_dir.path().join("node"), 8).unwrap();
+        write_go_fixture(&temp_dir.path().join("go"), 8).unwrap();
+
+        let lockfile = fs::read_to_string(temp_dir.path().join("node/package-lock.json")).unwrap();
+        assert!(lockfile.contains("node_modules/bench-pkg-7"));
+        let go_mod = fs::read_to_string(temp_dir.path().join("go/go.mod")).unwrap();
+        assert!(go_mod.contains("example.com/bench/mod7 v1.0.7"));
+
+        for index in 0..8 {
+            let license = fs::read_to_string(
+                temp_dir
+                    .path()
+                    .join(format!("go/vendor/example.com/bench/mod{index}/LICENSE")),
+            )
+            .unwrap();
+            assert_eq!(
+                detect_license_from_content(&license).as_deref(),
+                Some(fixture_license(index))
+            );
+        }
+    }
+}
@@ -0,0 +1,644 @@
+//! Third-party bundles declared outside `package.json`.
+//!
+//! Browser extensions and Electron apps ship code npm never sees: a minified library listed in
+//! an extension's `content_scripts`, a helper binary or prebuilt SDK that electron-builder copies
+//! into the app through `extraResources`. This module reads those declarations — WebExtension
+//! `manifest.json` files and electron-builder configs (`electron-builder.{json,yml,yaml}` or the
+//! `build` key of `package.json`) — and reports every declared bundle that isn't the project's
+//! own code as a [`LicenseInfo`] entry. The bundle's relative path is the entry name, the version
+//! column carries [`BUNDLED_MARKER`], and `declared_in` points at the line that declares it.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ignore::WalkBuilder;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::debug::{log, LogLevel};
+use crate::licenses::{
+    detect_license_from_content, detect_license_from_source_header, detect_license_in_dir,
+    fetch_licenses_from_github, get_osi_status, is_license_ignored, is_license_restrictive,
+    normalize_license_id, read_header_region, LicenseCompatibility, LicenseInfo, OsiStatus,
+};
+use crate::manifest::ManifestLocation;
+use crate::spdx;
+use crate::vendor_scan::is_vendor_dir_name;
+
+/// Marker placed in the version column of a bundle declared by an extension or Electron config.
+pub const BUNDLED_MARKER: &str = "bundled";
+
+/// Standalone electron-builder configuration files.
+const ELECTRON_BUILDER_CONFIGS: &[&str] = &[
+    "electron-builder.json",
+    "electron-builder.yml",
+    "electron-builder.yaml",
+];
+
+/// electron-builder keys listing files copied into the app next to the npm-managed `files`.
+const ELECTRON_EXTRA_KEYS: &[&str] = &["extraResources", "extraFiles"];
+
+/// Platform sections of an electron-builder config that may carry their own extra files.
+const ELECTRON_PLATFORM_KEYS: &[&str] = &["mac", "mas", "win", "linux"];
+
+/// How far below the project root configs are looked for: extension manifests usually live in
+/// `extension/`, `src/` or `public/` rather than at the root.
+const MAX_CONFIG_DEPTH: usize = 3;
+
+/// Directories never searched for configs: installed packages and build output, whose copies of
+/// a manifest would report every bundle twice.
+const SKIP_DIRS: &[&str] = &[
+    "node_modules",
+    "bower_components",
+    "target",
+    "vendor",
+    "dist",
+    "build",
+    "out",
+];
+
+/// Directory names that hold third-party code in extension and Electron layouts, on top of the
+/// vendor directories [`crate::vendor_scan`] knows.
+const LIBRARY_DIR_NAMES: &[&str] = &["lib", "libs"];
+
+/// A config that declares bundles, and the paths it declares.
+struct Declarations {
+    /// The config file, relative to the project root.
+    config: PathBuf,
+    /// Directory the declared paths are relative to.
+    base: PathBuf,
+    content: String,
+    paths: Vec<String>,
+}
+
+/// A declared bundle that isn't the project's own code.
+#[derive(Debug)]
+struct Finding {
+    /// Path relative to the project root — the reported entry name.
+    path: PathBuf,
+    license: Option<String>,
+    declared_in: ManifestLocation,
+}
+
+/// Every string in a value that is either a string or an array of strings.
+fn strings(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(path)) => vec![path.clone()],
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Scripts, styles and resources a WebExtension manifest loads into the browser.
+///
+/// Returns nothing for a `manifest.json` without `manifest_version`, which is a web app or
+/// some other tool's manifest rather than an extension's.
+fn extension_paths(manifest: &Value) -> Vec<String> {
+    if manifest.get("manifest_version").is_none() {
+        return Vec::new();
+    }
+
+    let mut paths = Vec::new();
+    for script in manifest["content_scripts"].as_array().into_iter().flatten() {
+        paths.extend(strings(script.get("js")));
+        paths.extend(strings(script.get("css")));
+    }
+    let background = &manifest["background"];
+    paths.extend(strings(background.get("scripts")));
+    paths.extend(strings(background.get("service_worker")));
+    // Manifest V2 lists resource paths; V3 groups them in `{resources, matches}` objects
+    for resource in manifest["web_accessible_resources"]
+        .as_array()
+        .into_iter()
+        .flatten()
+    {
+        match resource {
+            Value::String(path) => paths.push(path.clone()),
+            group => paths.extend(strings(group.get("resources"))),
+        }
+    }
+    paths
+}
+
+/// Sources of the extra files an electron-builder config copies into the app.
+fn electron_paths(config: &Value) -> Vec<String> {
+    let sections = std::iter::once(config).chain(
+        ELECTRON_PLATFORM_KEYS
+            .iter()
+            .filter_map(|platform| config.get(platform)),
+    );
+    sections
+        .flat_map(|section| {
+            ELECTRON_EXTRA_KEYS
+                .iter()
+                .flat_map(|key| file_set_sources(section.get(key)))
+        })
+        .collect()
+}
+
+/// `from` paths of an electron-builder `FileSet`: a path, a `{from, to, filter}` object or a
+/// list of either.
+fn file_set_sources(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(path)) => vec![path.clone()],
+        Some(Value::Object(set)) => set
+            .get("from")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .into_iter()
+            .collect(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .flat_map(|item| file_set_sources(Some(item)))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The paths a config declares, when it is an extension manifest or an electron-builder config.
+fn declared_paths(path: &Path, file_name: &str, content: &str) -> Vec<String> {
+    let parsed: Option<Value> = if file_name.ends_with(".json") {
+        serde_json::from_str(content).ok()
+    } else {
+        serde_yaml::from_str(content).ok()
+    };
+    let Some(parsed) = parsed else {
+        log(
+            LogLevel::Warn,
+            &format!("Failed to parse {} for bundle declarations", path.display()),
+        );
+        return Vec::new();
+    };
+
+    match file_name {
+        "manifest.json" => extension_paths(&parsed),
+        "package.json" => parsed.get("build").map(electron_paths).unwrap_or_default(),
+        _ => electron_paths(&parsed),
+    }
+}
+
+/// Every extension manifest and electron-builder config under `root` that declares bundles.
+fn find_declarations(root: &Path) -> Vec<Declarations> {
+    let walker = WalkBuilder::new(root)
+        .max_depth(Some(MAX_CONFIG_DEPTH))
+        .sort_by_file_path(|a, b| a.cmp(b))
+        .filter_entry(|entry| {
+            let is_dir = entry.file_type().is_some_and(|ft| ft.is_dir());
+            !(is_dir
+                && entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| SKIP_DIRS.contains(&name)))
+        })
+        .build();
+
+    walker
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+        .filter_map(|entry| {
+            let path = entry.path();
+            let file_name = entry.file_name().to_str()?;
+            if !(file_name == "manifest.json"
+                || file_name == "package.json"
+                || ELECTRON_BUILDER_CONFIGS.contains(&file_name))
+            {
+                return None;
+            }
+            let content = fs::read_to_string(path).ok()?;
+            let paths = declared_paths(path, file_name, &content);
+            if paths.is_empty() {
+                return None;
+            }
+            Some(Declarations {
+                config: path.strip_prefix(root).unwrap_or(path).to_path_buf(),
+                base: path.parent().unwrap_or(root).to_path_buf(),
+                content,
+                paths,
+            })
+        })
+        .collect()
+}
+
+/// Whether a declared path names one concrete file or directory rather than a glob or an
+/// electron-builder macro such as `${os}`.
+fn is_literal_path(declared: &str) -> bool {
+    !declared.is_empty() && !declared.starts_with('!') && !declared.contains(['*', '?', '{', '['])
+}
+
+/// Whether `path` (relative to the project root) sits in a directory that holds third-party code.
+fn in_library_dir(path: &Path) -> bool {
+    path.parent()
+        .into_iter()
+        .flat_map(Path::components)
+        .filter_map(|component| component.as_os_str().to_str())
+        .any(|name| {
+            is_vendor_dir_name(name)
+                || LIBRARY_DIR_NAMES
+                    .iter()
+                    .any(|known| known.eq_ignore_ascii_case(name))
+        })
+}
+
+/// Whether the file is a minified build, which a project doesn't write by hand.
+fn is_minified(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| {
+            let name = name.to_ascii_lowercase();
+            name.ends_with(".min.js") || name.ends_with(".min.mjs") || name.ends_with(".min.css")
+        })
+}
+
+/// The license a bundled file's leading banner declares.
+///
+/// Besides an `SPDX-License-Identifier:` tag, library banners state it as `@license MIT`,
+/// `Released under the MIT license` or `Licensed under the Apache License, Version 2.0`.
+fn banner_license(header: &str) -> Option<String> {
+    if let Some(license) = detect_license_from_source_header(header) {
+        return Some(license);
+    }
+
+    let statement = Regex::new(
+        r"(?i)(?:@license|licen[cs]ed under(?: the)?|released under(?: the)?|licen[cs]e:)\s+([^\r\n|*]+)",
+    )
+    .expect("valid license statement regex");
+    let license = statement.captures_iter(header).find_map(|caps| {
+        let rest = caps[1].trim().trim_end_matches('.');
+        if spdx::is_compound(rest) && spdx::parse_strict(rest).is_some() {
+            return Some(rest.to_string());
+        }
+        // An SPDX id leads the statement (`@license MIT`, `Released under the MIT license`)
+        let first = rest
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .trim_matches(|c: char| matches!(c, ',' | ';' | '(' | ')' | '"' | '\''));
+        if !first.is_empty() && get_osi_status(first) != OsiStatus::Unknown {
+            return Some(first.to_string());
+        }
+        // Otherwise it is spelled out (`Apache License, Version 2.0`)
+        let normalized = normalize_license_id(rest);
+        (normalized != rest).then_some(normalized)
+    });
+    license.or_else(|| detect_license_from_content(header))
+}
+
+/// Inspect one declared path, returning the bundle's relative path and license when it is
+/// third-party code.
+///
+/// A file counts when its banner names a license other than the project's, when it is
+/// minified, or when it sits in a `lib/` or vendor directory; a directory counts when it
+/// carries a license file or sits in such a directory. Anything else is the project's own
+/// assets — icons, popups, its own scripts.
+fn inspect(
+    root: &Path,
+    base: &Path,
+    declared: &str,
+    project_license: Option<&str>,
+) -> Option<(PathBuf, Option<String>)> {
+    // Extension paths may be written root-relative (`/lib/jquery.js`)
+    let declared = declared
+        .trim()
+        .trim_start_matches("./")
+        .trim_start_matches('/');
+    if !is_literal_path(declared) {
+        return None;
+    }
+    let path = base.join(declared);
+    let rel = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+    // npm packages copied from node_modules are already reported by the Node analyzer
+    if rel
+        .components()
+        .any(|component| component.as_os_str() == "node_modules")
+    {
+        return None;
+    }
+
+    let library = in_library_dir(&rel);
+    let (license, third_party) = if path.is_dir() {
+        let license = detect_license_in_dir(&path);
+        let third_party = library || license.is_some();
+        (license, third_party)
+    } else if path.is_file() {
+        let license = read_header_region(&path)
+            .and_then(|header| banner_license(&header))
+            .or_else(|| {
+                // A library copied into `lib/jquery/` usually keeps its LICENSE beside it
+                let dir = path.parent()?;
+                if library && dir != base && dir != root {
+                    detect_license_in_dir(dir)
+                } else {
+                    None
+                }
+            });
+        let third_party = library || is_minified(&path) || license.is_some();
+        (license, third_party)
+    } else {
+        log(
+            LogLevel::Warn,
+            &format!("Declared bundle {} does not exist", rel.display()),
+        );
+        return None;
+    };
+
+    let own_license = license.as_deref().is_some_and(|license| {
+        project_license.is_some_and(|project| project.trim().eq_ignore_ascii_case(license.trim()))
+    });
+    if !third_party || (own_license && !library && !is_minified(&path)) {
+        return None;
+    }
+    Some((rel, license))
+}
+
+/// 1-based line of `content` that mentions `declared`, falling back to the first line.
+fn declaration_line(content: &str, declared: &str) -> usize {
+    content
+        .lines()
+        .position(|line| line.contains(declared))
+        .map_or(1, |index| index + 1)
+}
+
+/// Read every extension manifest and electron-builder config under `root` and return the
+/// third-party bundles they declare.
+///
+/// `known_dependencies` are the entries already reported — dependency names and the paths the
+/// vendored scan found — so a bundle inside an already-reported vendored directory isn't
+/// reported twice.
+fn collect_findings(
+    root: &Path,
+    known_dependencies: &[String],
+    project_license: Option<&str>,
+) -> Vec<Finding> {
+    let mut findings: Vec<Finding> = Vec::new();
+    for declarations in find_declarations(root) {
+        for declared in &declarations.paths {
+            let Some((path, license)) =
+                inspect(root, &declarations.base, declared, project_license)
+            else {
+                continue;
+            };
+            let already_reported = known_dependencies
+                .iter()
+                .any(|known| path.starts_with(known))
+                || findings.iter().any(|finding| finding.path == path);
+            if already_reported || is_license_ignored(license.as_deref()) {
+                continue;
+            }
+
+            log(
+                LogLevel::Warn,
+                &format!(
+                    "Bundle {} declared in {} (license: {})",
+                    path.display(),
+                    declarations.config.display(),
+                    license.as_deref().unwrap_or("unknown")
+                ),
+            );
+            findings.push(Finding {
+                declared_in: ManifestLocation {
+                    file: declarations.config.display().to_string(),
+                    line: declaration_line(&declarations.content, declared.trim()),
+                },
+                path,
+                license,
+            });
+        }
+    }
+    findings
+}
+
+/// Scan the project's extension manifests and electron-builder configs for third-party bundles
+/// and return them as [`LicenseInfo`] entries ready to be appended to the dependency report.
+///
+/// Compatibility is left [`LicenseCompatibility::Unknown`] for the caller's annotation pass, and
+/// the license registry is fetched only when a bundle was found.
+pub fn scan_declared_bundles(
+    root: &Path,
+    known_dependencies: &[String],
+    project_license: Option<&str>,
+    strict: bool,
+) -> Vec<LicenseInfo> {
+    let findings = collect_findings(root, known_dependencies, project_license);
+    if findings.is_empty() {
+        return Vec::new();
+    }
+
+    let known_licenses = fetch_licenses_from_github().unwrap_or_else(|e| {
+        log(
+            LogLevel::Warn,
+            &format!("Failed to fetch license registry for bundle scan: {e}"),
+        );
+        HashMap::new()
+    });
+
+    findings
+        .into_iter()
+        .map(|finding| {
+            let osi_status = match &finding.license {
+                Some(license) => get_osi_status(license),
+                None => OsiStatus::Unknown,
+            };
+            let is_restrictive = is_license_restrictive(&finding.license, &known_licenses, strict);
+            LicenseInfo {
+                name: finding.path.display().to_string(),
+                version: BUNDLED_MARKER.to_string(),
+                license: finding.license,
+                is_restrictive,
+                compatibility: LicenseCompatibility::Unknown,
+                osi_status,
+                sub_project: None,
+                found_in: Vec::new(),
+                repository: None,
+                declared_in: Some(finding.declared_in),
+                internal: false,
+                inherited_from: None,
+                ecosystem: None,
+                suggestions: Vec::new(),
+                update_policy: None,
+                introduced_by: Vec::new(),
+                purl: None,
+                vulnerabilities: None,
+                manifest: None,
+                data_license: None,
+                project_dirs: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn names(findings: &[Finding]) -> Vec<String> {
+        findings
+            .iter()
+            .map(|f| f.path.display().to_string().replace('\\', "/"))
+            .collect()
+    }
+
+    #[test]
+    fn test_extension_paths_cover_v2_and_v3_manifests() {
+        let v3: Value = serde_json::json!({
+            "manifest_version": 3,
+            "background": { "service_worker": "background.js" },
+            "content_scripts": [{ "matches": ["<all_urls>"], "js": ["lib/purify.min.js", "content.js"], "css": ["content.css"] }],
+            "web_accessible_resources": [{ "resources": ["vendor/pdf.worker.js"], "matches": ["<all_urls>"] }]
+        });
+        assert_eq!(
+            extension_paths(&v3),
+            vec![
+                "lib/purify.min.js",
+                "content.js",
+                "content.css",
+                "background.js",
+                "vendor/pdf.worker.js"
+            ]
+        );
+
+        let v2: Value = serde_json::json!({
+            "manifest_version": 2,
+            "background": { "scripts": ["jquery.js", "bg.js"] },
+            "web_accessible_resources": ["fonts/icons.woff"]
+        });
+        assert_eq!(
+            extension_paths(&v2),
+            vec!["jquery.js", "bg.js", "fonts/icons.woff"]
+        );
+
+        let pwa: Value = serde_json::json!({ "name": "app", "icons": [] });
+        assert!(extension_paths(&pwa).is_empty());
+    }
+
+    #[test]
+    fn test_electron_paths_read_file_sets_and_platform_sections() {
+        let config: Value = serde_yaml::from_str(
+            "appId: com.example.app\n\
+             extraResources:\n  - from: resources/ffmpeg\n    to: ffmpeg\n  - assets/**\n\
+             mac:\n  extraFiles: sdk/Mac.framework\n",
+        )
+        .unwrap();
+        assert_eq!(
+            electron_paths(&config),
+            vec!["resources/ffmpeg", "assets/**", "sdk/Mac.framework"]
+        );
+    }
+
+    #[test]
+    fn test_banner_license() {
+        assert_eq!(
+            banner_license(
+                "/*!\n * jQuery JavaScript Library v2.2.4\n * Released under the MIT license\n */"
+            )
+            .as_deref(),
+            Some("MIT")
+        );
+        assert_eq!(
+            banner_license(
+                "/*! pako 2.1.0 https://github.com/nodeca/pako @license (MIT AND Zlib) */"
+            )
+            .as_deref(),
+            Some("(MIT AND Zlib)")
+        );
+        assert_eq!(
+            banner_license("/**\n * @license MIT\n * lodash <https://lodash.com/>\n */").as_deref(),
+            Some("MIT")
+        );
+        assert_eq!(
+            banner_license(
+                "/* Licensed under the Apache License, Version 2.0 (the \"License\") */"
+            )
+            .as_deref(),
+            Some("Apache-2.0")
+        );
+        assert_eq!(banner_license("// our popup script\n"), None);
+    }
+
+    #[test]
+    fn test_reports_extension_bundles_but_not_own_scripts() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "extension/manifest.json",
+            r#"{
+  "manifest_version": 3,
+  "content_scripts": [{
+    "matches": ["<all_urls>"],
+    "js": ["lib/jquery.min.js", "content.js", "/node_modules/lodash/lodash.js"]
+  }]
+}"#,
+        );
+        write(
+            dir.path(),
+            "extension/lib/jquery.min.js",
+            "/*! jQuery v3.7.1 | (c) OpenJS Foundation | jquery.org/license */",
+        );
+        write(dir.path(), "extension/content.js", "console.log('hi');\n");
+
+        let findings = collect_findings(dir.path(), &[], Some("MIT"));
+        assert_eq!(names(&findings), vec!["extension/lib/jquery.min.js"]);
+        assert_eq!(findings[0].license, None);
+        assert_eq!(
+            findings[0].declared_in,
+            ManifestLocation {
+                file: Path::new("extension")
+                    .join("manifest.json")
+                    .display()
+                    .to_string(),
+                line: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn test_reports_electron_extra_resources_with_license() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "package.json",
+            r#"{
+  "name": "app",
+  "build": {
+    "extraResources": [{ "from": "resources/ffmpeg", "to": "ffmpeg" }, "resources/icons"]
+  }
+}"#,
+        );
+        write(
+            dir.path(),
+            "resources/ffmpeg/COPYING",
+            "GNU GENERAL PUBLIC LICENSE\nVersion 3, 29 June 2007\n",
+        );
+        write(dir.path(), "resources/icons/app.png", "png");
+
+        let findings = collect_findings(dir.path(), &[], Some("MIT"));
+        assert_eq!(names(&findings), vec!["resources/ffmpeg"]);
+        assert_eq!(findings[0].license.as_deref(), Some("GPL-3.0"));
+        assert_eq!(findings[0].declared_in.line, 4);
+    }
+
+    #[test]
+    fn test_skips_bundles_already_reported_by_the_vendored_scan() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "manifest.json",
+            r#"{"manifest_version": 2, "background": {"scripts": ["vendor/pako/pako.min.js"]}}"#,
+        );
+        write(dir.path(), "vendor/pako/pako.min.js", "/*! pako */");
+
+        let findings = collect_findings(dir.path(), &["vendor/pako".to_string()], None);
+        assert!(findings.is_empty());
+    }
+}
@@ -1,12 +1,17 @@
 //! Caching functionality for license data
 //!
+//! Two caches live here: the GitHub license catalogue (`github_licenses.json`) and the
+//! per-package cache (`package_licenses.json`) holding licenses the resolver chain found
+//! remotely, keyed `ecosystem:name@version`. The package cache also remembers packages no
+//! remote source knew a license for, for a day rather than a month, so repeated scans don't
+//! ask again every run but pick up metadata published since.
+//!
 //! Future considerations:
-//! - Per-package license cache (language:package:version keys)
 //! - Dependency manifest cache with mtime tracking for incremental analysis
 
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 use crate::debug::{log, log_error, FeludaResult, LogLevel};
@@ -14,7 +19,11 @@ use crate::licenses::License;
 
 const CACHE_SUBDIR: &str = "feluda";
 const GITHUB_LICENSES_CACHE_FILE: &str = "github_licenses.json";
+const PACKAGE_LICENSES_CACHE_FILE: &str = "package_licenses.json";
+const SPDX_LICENSE_LIST_CACHE_FILE: &str = "spdx_licenses.json";
+const SPDX_LICENSE_LIST_PREVIOUS_FILE: &str = "spdx_licenses.previous.json";
 const CACHE_TTL_SECS: u64 = 30 * 24 * 60 * 60; // 30 days
+const MISS_TTL_SECS: u64 = 24 * 60 * 60; // 1 day
 
 const CACHE_VERSION: u32 = 1;
 
@@ -45,6 +54,16 @@ fn ensure_cache_dir() -> FeludaResult<PathBuf> {
     Ok(cache_dir)
 }
 
+/// Create the cache directory if needed and write and remove a probe file in it, so a
+/// read-only or full cache is found before a scan silently stops caching
+pub fn check_cache_writable() -> FeludaResult<PathBuf> {
+    let cache_dir = ensure_cache_dir()?;
+    let probe = cache_dir.join(".feluda-write-test");
+    fs::write(&probe, b"ok")?;
+    fs::remove_file(&probe)?;
+    Ok(cache_dir)
+}
+
 fn github_cache_path() -> FeludaResult<PathBuf> {
     Ok(cache_dir_path()?.join(GITHUB_LICENSES_CACHE_FILE))
 }
@@ -94,7 +113,8 @@ pub fn load_github_licenses_from_cache() -> FeludaResult<Option<HashMap<String,
                     );
                     return Ok(None);
                 }
-                if !is_entry_fresh(entry.timestamp) {
+                // Offline, an old list beats none: it can't be refreshed anyway
+                if !is_entry_fresh(entry.timestamp) && !crate::cancel::is_offline() {
                     log(
                         LogLevel::Info,
                         "GitHub licenses cache is stale, will re-fetch",
@@ -173,9 +193,162 @@ pub fn clear_github_licenses_cache() -> FeludaResult<()> {
         log(LogLevel::Info, "No cache to clear");
     }
 
+    let package_cache_path = cache_dir_path()?.join(PACKAGE_LICENSES_CACHE_FILE);
+    if package_cache_path.exists() {
+        fs::remove_file(&package_cache_path)
+            .inspect_err(|e| log_error("Failed to clear package license cache", e))?;
+        log(LogLevel::Info, "Cleared package license cache");
+    }
+
+    Ok(())
+}
+
+/// A license the resolver chain found for one package
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PackageLicense {
+    /// `None` when every remote source answered without a license
+    pub license: Option<String>,
+    /// Which resolver answered, e.g. "registry"
+    pub source: String,
+    pub timestamp: u64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default)]
+struct PackageCacheFile {
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    entries: HashMap<String, PackageLicense>,
+}
+
+/// Load the per-package license cache, dropping licenses older than the cache TTL and misses
+/// older than the miss TTL (both kept with `--offline`, where they can't be looked up again)
+pub fn load_package_licenses() -> FeludaResult<HashMap<String, PackageLicense>> {
+    let cache_path = cache_dir_path()?.join(PACKAGE_LICENSES_CACHE_FILE);
+    if !cache_path.exists() {
+        log(LogLevel::Info, "No package license cache found");
+        return Ok(HashMap::new());
+    }
+
+    let content = fs::read_to_string(&cache_path)?;
+    Ok(package_licenses_from_content(&content))
+}
+
+fn package_licenses_from_content(content: &str) -> HashMap<String, PackageLicense> {
+    match serde_json::from_str::<PackageCacheFile>(content) {
+        Ok(file) if file.version == CACHE_VERSION => file
+            .entries
+            .into_iter()
+            .filter(|(_, entry)| {
+                let ttl = if entry.license.is_some() {
+                    CACHE_TTL_SECS
+                } else {
+                    MISS_TTL_SECS
+                };
+                crate::cancel::is_offline() || entry_age_secs(entry.timestamp) < ttl
+            })
+            .collect(),
+        Ok(_) => {
+            log(
+                LogLevel::Info,
+                "Package license cache version mismatch, starting fresh",
+            );
+            HashMap::new()
+        }
+        Err(e) => {
+            log(
+                LogLevel::Warn,
+                &format!("Corrupt package license cache, starting fresh: {e}"),
+            );
+            HashMap::new()
+        }
+    }
+}
+
+pub fn save_package_licenses(entries: &HashMap<String, PackageLicense>) -> FeludaResult<()> {
+    let cache_path = ensure_cache_dir()?.join(PACKAGE_LICENSES_CACHE_FILE);
+    let json = serde_json::to_string_pretty(&serde_json::json!({
+        "version": CACHE_VERSION,
+        "entries": entries,
+    }))
+    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+
+    fs::write(&cache_path, json)
+        .inspect_err(|e| log_error("Failed to write package license cache", e))?;
+    log(
+        LogLevel::Info,
+        &format!(
+            "Saved {} package licenses to cache at {}",
+            entries.len(),
+            cache_path.display()
+        ),
+    );
     Ok(())
 }
 
+/// Seconds since the Unix epoch, for cache timestamps
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Read the cached SPDX license list, if `feluda licenses update` has stored one.
+pub fn load_spdx_license_list() -> FeludaResult<Option<String>> {
+    let path = cache_dir_path()?.join(SPDX_LICENSE_LIST_CACHE_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(fs::read_to_string(path)?))
+}
+
+/// Store a new SPDX license list, keeping the current one around for rollback.
+pub fn save_spdx_license_list(json: &str) -> FeludaResult<PathBuf> {
+    let cache_dir = ensure_cache_dir()?;
+    rotate_spdx_license_list(&cache_dir, json)
+}
+
+/// Swap the current SPDX license list with the previous one.
+///
+/// Returns `false` when there is nothing to roll back to. Rolling back twice restores
+/// the list that was current before the first rollback.
+pub fn rollback_spdx_license_list() -> FeludaResult<bool> {
+    swap_spdx_license_lists(&cache_dir_path()?)
+}
+
+fn rotate_spdx_license_list(cache_dir: &Path, json: &str) -> FeludaResult<PathBuf> {
+    let current = cache_dir.join(SPDX_LICENSE_LIST_CACHE_FILE);
+    if current.exists() {
+        fs::rename(&current, cache_dir.join(SPDX_LICENSE_LIST_PREVIOUS_FILE))?;
+    }
+    fs::write(&current, json).inspect_err(|e| log_error("Failed to write SPDX list", e))?;
+    log(
+        LogLevel::Info,
+        &format!("Saved SPDX license list to {}", current.display()),
+    );
+    Ok(current)
+}
+
+fn swap_spdx_license_lists(cache_dir: &Path) -> FeludaResult<bool> {
+    let current = cache_dir.join(SPDX_LICENSE_LIST_CACHE_FILE);
+    let previous = cache_dir.join(SPDX_LICENSE_LIST_PREVIOUS_FILE);
+    if !previous.exists() {
+        return Ok(false);
+    }
+
+    let swap = cache_dir.join(format!("{SPDX_LICENSE_LIST_CACHE_FILE}.swap"));
+    if current.exists() {
+        fs::rename(&current, &swap)?;
+    }
+    fs::rename(&previous, &current)?;
+    if swap.exists() {
+        fs::rename(&swap, &previous)?;
+    }
+    log(LogLevel::Info, "Rolled back SPDX license list");
+    Ok(true)
+}
+
 #[derive(Debug, serde::Serialize)]
 pub struct CacheStatus {
     pub exists: bool,
@@ -200,7 +373,7 @@ impl CacheStatus {
         }
     }
 
-    fn format_age(secs: u64) -> String {
+    pub(crate) fn format_age(secs: u64) -> String {
         const HOUR: u64 = 3600;
         const DAY: u64 = 24 * HOUR;
 
@@ -301,13 +474,6 @@ mod tests {
         }
     }
 
-    fn now_secs() -> u64 {
-        SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_secs()
-    }
-
     #[test]
     fn fresh_entry_is_fresh() {
         assert!(is_entry_fresh(now_secs()));
@@ -378,6 +544,24 @@ mod tests {
         assert!(load_from_content("{}").is_none());
     }
 
+    #[test]
+    fn spdx_license_list_rotation_and_rollback() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let current = dir.path().join(SPDX_LICENSE_LIST_CACHE_FILE);
+
+        assert!(!swap_spdx_license_lists(dir.path()).unwrap());
+
+        rotate_spdx_license_list(dir.path(), "v1").unwrap();
+        rotate_spdx_license_list(dir.path(), "v2").unwrap();
+        assert_eq!(fs::read_to_string(&current).unwrap(), "v2");
+
+        assert!(swap_spdx_license_lists(dir.path()).unwrap());
+        assert_eq!(fs::read_to_string(&current).unwrap(), "v1");
+
+        assert!(swap_spdx_license_lists(dir.path()).unwrap());
+        assert_eq!(fs::read_to_string(&current).unwrap(), "v2");
+    }
+
     #[test]
     fn format_size_bytes() {
         assert_eq!(CacheStatus::format_size(500), "500 B");
@@ -413,4 +597,57 @@ mod tests {
     fn format_age_days() {
         assert_eq!(CacheStatus::format_age(172_800), "2 days ago");
     }
+
+    #[test]
+    fn package_licenses_drop_stale_entries() {
+        let content = serde_json::json!({
+            "version": CACHE_VERSION,
+            "entries": {
+                "npm:left-pad@1.3.0": {
+                    "license": "WTFPL",
+                    "source": "registry",
+                    "timestamp": now_secs(),
+                },
+                "pypi:requests@2.0.0": {
+                    "license": "Apache-2.0",
+                    "source": "clearlydefined",
+                    "timestamp": now_secs() - CACHE_TTL_SECS - 1,
+                },
+            },
+        })
+        .to_string();
+
+        let entries = package_licenses_from_content(&content);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entries["npm:left-pad@1.3.0"].license.as_deref(),
+            Some("WTFPL")
+        );
+
+        assert!(package_licenses_from_content("not json").is_empty());
+        assert!(package_licenses_from_content(r#"{"version": 0, "entries": {}}"#).is_empty());
+    }
+
+    #[test]
+    fn package_license_misses_expire_sooner() {
+        let miss = |age: u64| {
+            serde_json::json!({
+                "license": null,
+                "source": "none",
+                "timestamp": now_secs() - age,
+            })
+        };
+        let content = serde_json::json!({
+            "version": CACHE_VERSION,
+            "entries": {
+                "npm:fresh-miss@1.0.0": miss(60),
+                "npm:stale-miss@1.0.0": miss(MISS_TTL_SECS + 1),
+            },
+        })
+        .to_string();
+
+        let entries = package_licenses_from_content(&content);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries["npm:fresh-miss@1.0.0"].license, None);
+    }
 }
@@ -0,0 +1,382 @@
+//! Scan timeout and Ctrl+C handling
+//!
+//! `--timeout` and Ctrl+C both cancel the scan rather than killing the process. Registry
+//! lookups go through [`http_get`] (or check [`is_cancelled`] first), which refuses to start
+//! new requests once the scan is cancelled, and retries back off with
+//! [`sleep_unless_cancelled`]. The affected dependencies come back with an unknown license,
+//! the scan finishes with what it has, and the report is marked incomplete.
+//!
+//! A second Ctrl+C exits immediately, restoring the terminal first if the TUI is up.
+//!
+//! `--offline` closes the same door for the whole run: [`http_get`] and [`http_send`] refuse
+//! every request, and lookups that build their own client check [`is_offline`] first.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::debug::{log, record_resolution_error, FeludaError, LogLevel};
+
+/// Exit code for a scan cut short by Ctrl+C (128 + SIGINT, as shells report it)
+pub const EXIT_INTERRUPTED: i32 = 130;
+/// Exit code for a scan cut short by `--timeout` (the same as coreutils `timeout`)
+pub const EXIT_TIMED_OUT: i32 = 124;
+
+/// Timeout for registry requests that don't set their own
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often a wait checks whether the scan was cancelled
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+static REASON: Mutex<Option<CancelReason>> = Mutex::new(None);
+static DEADLINE: Mutex<Option<Instant>> = Mutex::new(None);
+static TUI_ACTIVE: AtomicBool = AtomicBool::new(false);
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+static CLIENT: OnceLock<reqwest::blocking::Client> = OnceLock::new();
+
+/// Why a scan stopped early
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelReason {
+    Interrupted,
+    TimedOut(Duration),
+}
+
+impl CancelReason {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            CancelReason::Interrupted => EXIT_INTERRUPTED,
+            CancelReason::TimedOut(_) => EXIT_TIMED_OUT,
+        }
+    }
+}
+
+impl std::fmt::Display for CancelReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CancelReason::Interrupted => write!(f, "interrupted by Ctrl+C"),
+            CancelReason::TimedOut(timeout) => {
+                write!(f, "timed out after {}s (--timeout)", timeout.as_secs())
+            }
+        }
+    }
+}
+
+/// A registry request that did not complete
+#[derive(Debug, thiserror::Error)]
+pub enum FetchError {
+    #[error("skipped, scan {0}")]
+    Cancelled(CancelReason),
+
+    #[error("skipped, network access is disabled by --offline")]
+    Offline,
+
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
+
+impl From<FetchError> for FeludaError {
+    fn from(err: FetchError) -> Self {
+        match err {
+            FetchError::Http(err) => FeludaError::Http(err),
+            // Refused by --offline or a cancelled scan before anything was sent
+            err => FeludaError::Config(err.to_string()),
+        }
+    }
+}
+
+/// Install the Ctrl+C handler and, with `--timeout`, start the scan deadline
+pub fn install(timeout: Option<Duration>) {
+    if let Err(err) = ctrlc::set_handler(|| {
+        if is_cancelled() {
+            force_exit();
+        }
+        eprintln!(
+            "\n⏹️  Interrupted: finishing with partial results (press Ctrl+C again to quit now)"
+        );
+        cancel(CancelReason::Interrupted);
+    }) {
+        log(
+            LogLevel::Warn,
+            &format!("Failed to install Ctrl+C handler: {err}"),
+        );
+    }
+
+    if let Some(timeout) = timeout {
+        let deadline = Instant::now() + timeout;
+        if let Ok(mut current) = DEADLINE.lock() {
+            *current = Some(deadline);
+        }
+        thread::spawn(move || {
+            thread::sleep(timeout);
+            if deadline_remaining().is_some() && !is_cancelled() {
+                eprintln!(
+                    "\n⏱️  Scan timed out after {}s: finishing with partial results",
+                    timeout.as_secs()
+                );
+                cancel(CancelReason::TimedOut(timeout));
+            }
+        });
+    }
+}
+
+/// The scan is done: `--timeout` no longer applies (e.g. while the TUI is open)
+pub fn disarm_timeout() {
+    if let Ok(mut deadline) = DEADLINE.lock() {
+        *deadline = None;
+    }
+}
+
+fn deadline_remaining() -> Option<Duration> {
+    DEADLINE
+        .lock()
+        .ok()
+        .and_then(|deadline| *deadline)
+        .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+}
+
+/// Stop the scan: pending and future registry lookups are skipped
+pub fn cancel(reason: CancelReason) {
+    if let Ok(mut current) = REASON.lock() {
+        current.get_or_insert(reason);
+    }
+    CANCELLED.store(true, Ordering::SeqCst);
+    log(LogLevel::Warn, &format!("Scan cancelled: {reason}"));
+}
+
+pub fn is_cancelled() -> bool {
+    CANCELLED.load(Ordering::SeqCst)
+}
+
+/// Why the scan was cancelled, if it was
+pub fn cancel_reason() -> Option<CancelReason> {
+    REASON.lock().ok().and_then(|reason| *reason)
+}
+
+/// `--offline`: refuse all network access for the rest of the run
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::SeqCst);
+    if offline {
+        log(
+            LogLevel::Info,
+            "Offline mode: network lookups are disabled, licenses come from local metadata and the cache",
+        );
+    }
+}
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::SeqCst)
+}
+
+/// Record whether the TUI owns the terminal, so a forced exit can restore it
+pub fn set_tui_active(active: bool) {
+    TUI_ACTIVE.store(active, Ordering::SeqCst);
+}
+
+fn force_exit() -> ! {
+    if TUI_ACTIVE.load(Ordering::SeqCst) {
+        ratatui::restore();
+    }
+    std::process::exit(EXIT_INTERRUPTED);
+}
+
+/// `default`, shortened so a request started now cannot outlive the `--timeout` deadline
+pub fn request_timeout(default: Duration) -> Duration {
+    capped_timeout(default, deadline_remaining())
+}
+
+fn capped_timeout(default: Duration, remaining: Option<Duration>) -> Duration {
+    match remaining {
+        // reqwest treats a zero timeout as an immediate failure; keep a sliver so the
+        // error reads as a timeout rather than a client error
+        Some(remaining) => default.min(remaining).max(Duration::from_millis(1)),
+        None => default,
+    }
+}
+
+/// `reqwest::blocking::get` that is skipped once the scan is cancelled
+///
+/// Requests share one client, so lookups against the same registry reuse connections.
+/// A request already in flight runs to completion; its timeout is capped by the
+/// `--timeout` deadline, so it cannot hold the scan past it.
+pub fn http_get(url: &str) -> Result<reqwest::blocking::Response, FetchError> {
+    if is_offline() {
+        return Err(FetchError::Offline);
+    }
+    if let Some(reason) = cancel_reason() {
+        return Err(FetchError::Cancelled(reason));
+    }
+
+    let request = shared_client()?
+        .get(url)
+        .timeout(request_timeout(DEFAULT_REQUEST_TIMEOUT));
+    http_send(request, url)
+}
+
+/// The client every request shares, so lookups against the same host reuse connections.
+///
+/// It sends a `feluda/<version>` User-Agent, which GitHub's API requires. Set each request's
+/// timeout with [`request_timeout`] and send it with [`http_send`].
+pub fn shared_client() -> Result<&'static reqwest::blocking::Client, FetchError> {
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+    let client = reqwest::blocking::Client::builder()
+        .user_agent(concat!("feluda/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    Ok(CLIENT.get_or_init(|| client))
+}
+
+/// [`http_get`] for a request that needs its own method, headers or body
+///
+/// Build it on [`shared_client`] and cap its timeout with [`request_timeout`].
+pub fn http_send(
+    request: reqwest::blocking::RequestBuilder,
+    url: &str,
+) -> Result<reqwest::blocking::Response, FetchError> {
+    if is_offline() {
+        return Err(FetchError::Offline);
+    }
+    if let Some(reason) = cancel_reason() {
+        return Err(FetchError::Cancelled(reason));
+    }
+
+    let source = registry_source(url);
+    match request.send() {
+        Ok(response) => {
+            crate::rate_limit::record(&source, response.headers());
+            // 404s are answers ("no such package/version"); throttling and server
+            // errors mean the question never got one
+            let status = response.status();
+            if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                record_resolution_error(source, url, format!("HTTP {status}"));
+            }
+            Ok(response)
+        }
+        Err(err) => {
+            record_resolution_error(source, url, err.to_string());
+            Err(err.into())
+        }
+    }
+}
+
+/// Sleep before a retry, waking early if the scan is cancelled
+///
+/// Returns `false` when the scan was cancelled and the retry should be dropped.
+pub fn sleep_unless_cancelled(duration: Duration) -> bool {
+    let until = Instant::now() + duration;
+    loop {
+        if is_cancelled() {
+            return false;
+        }
+        let remaining = until.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return true;
+        }
+        thread::sleep(remaining.min(POLL_INTERVAL));
+    }
+}
+
+/// Human-readable name of the registry or API behind a lookup URL
+pub fn registry_source(url: &str) -> String {
+    // Also covers a GitHub Enterprise Server configured under `[github]`
+    if url.starts_with(&crate::licenses::github_api_url()) {
+        return "GitHub API".to_string();
+    }
+
+    let host = url
+        .split("://")
+        .nth(1)
+        .unwrap_or(url)
+        .split(['/', ':'])
+        .next()
+        .unwrap_or_default();
+
+    let source = match host {
+        "registry.npmjs.org" => "npm registry",
+        "pypi.org" => "PyPI",
+        "repo1.maven.org" | "search.maven.org" => "Maven Central",
+        "dl.google.com" => "Google Maven",
+        "pkg.go.dev" => "pkg.go.dev",
+        "api.nuget.org" => "NuGet",
+        "rubygems.org" => "RubyGems",
+        "repo.packagist.org" => "Packagist",
+        "hex.pm" => "Hex",
+        "conan.io" => "Conan Center",
+        "api.clearlydefined.io" => "ClearlyDefined",
+        "api.osv.dev" => "OSV.dev",
+        "raw.githubusercontent.com" if url.contains("/microsoft/vcpkg/") => "vcpkg registry",
+        "api.github.com" => "GitHub API",
+        host if host == "r-universe.dev" || host.ends_with(".r-universe.dev") => "R-universe",
+        host => host,
+    };
+    source.to_string()
+}
+
+/// Resolves once the scan is cancelled, for racing async requests against Ctrl+C
+pub async fn cancelled() {
+    while !is_cancelled() {
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capped_timeout() {
+        let default = Duration::from_secs(30);
+        assert_eq!(capped_timeout(default, None), default);
+        assert_eq!(
+            capped_timeout(default, Some(Duration::from_secs(5))),
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            capped_timeout(default, Some(Duration::from_secs(60))),
+            default
+        );
+        assert_eq!(
+            capped_timeout(default, Some(Duration::ZERO)),
+            Duration::from_millis(1)
+        );
+    }
+
+    #[test]
+    fn test_sleep_unless_cancelled() {
+        let started = Instant::now();
+        assert!(sleep_unless_cancelled(Duration::from_millis(150)));
+        assert!(started.elapsed() >= Duration::from_millis(150));
+        assert!(sleep_unless_cancelled(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_registry_source() {
+        assert_eq!(
+            registry_source("https://registry.npmjs.org/left-pad/1.3.0"),
+            "npm registry"
+        );
+        assert_eq!(
+            registry_source("https://cran.r-universe.dev/api/packages/ggplot2"),
+            "R-universe"
+        );
+        assert_eq!(
+            registry_source("https://registry.example.com:8443/pkg"),
+            "registry.example.com"
+        );
+    }
+
+    #[test]
+    fn test_cancel_reason_exit_codes() {
+        assert_eq!(CancelReason::Interrupted.exit_code(), 130);
+        assert_eq!(
+            CancelReason::TimedOut(Duration::from_secs(90)).exit_code(),
+            124
+        );
+        assert_eq!(
+            CancelReason::TimedOut(Duration::from_secs(90)).to_string(),
+            "timed out after 90s (--timeout)"
+        );
+    }
+}
@@ -8,6 +8,7 @@ use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
+use crate::config::{CargoConfig, CargoLockMode};
 // Import from the debug module instead of defining here
 use crate::debug::{is_debug_mode, log, LogLevel};
 
@@ -20,6 +21,26 @@ pub enum CiFormat {
     Jenkins,
     /// SARIF 2.1.0 format (GitHub Advanced Security, VS Code Problems panel)
     Sarif,
+    /// GitLab license scanning report (license_scanning artifact, schema v2.1)
+    Gitlab,
+}
+
+/// Compression for report files written to --output-dir
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ReportCompression {
+    /// gzip (.gz)
+    Gzip,
+    /// Zstandard (.zst)
+    Zstd,
+}
+
+impl ReportCompression {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ReportCompression::Gzip => "gz",
+            ReportCompression::Zstd => "zst",
+        }
+    }
 }
 
 /// SBOM format options
@@ -33,6 +54,43 @@ pub enum SbomFormat {
     All,
 }
 
+/// Formats `--sbom` writes the scan results in
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum SbomExport {
+    /// SPDX 2.3 JSON
+    Spdx,
+    /// CycloneDX 1.5 JSON
+    Cyclonedx,
+    /// CycloneDX 1.5 XML
+    CyclonedxXml,
+}
+
+impl SbomExport {
+    /// Extension of the file the SBOM is written to when no --output-file is given
+    pub fn extension(self) -> &'static str {
+        match self {
+            SbomExport::Spdx => "spdx.json",
+            SbomExport::Cyclonedx => "cdx.json",
+            SbomExport::CyclonedxXml => "cdx.xml",
+        }
+    }
+}
+
+/// Standalone documents `--output-format` writes the scan results as
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    /// Self-contained HTML page with sortable, filterable tables and a license chart
+    Html,
+}
+
+impl OutputFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Html => "html",
+        }
+    }
+}
+
 /// OSI filter options
 #[derive(ValueEnum, Clone, Debug)]
 pub enum OsiFilter {
@@ -44,6 +102,26 @@ pub enum OsiFilter {
     Unknown,
 }
 
+/// `feluda generate` subcommands
+#[derive(Subcommand, Debug, Clone)]
+pub enum GenerateCommand {
+    /// THIRD-PARTY-NOTICES: dependencies by license, with their copyright lines, license texts and
+    /// Apache-2.0 NOTICE files
+    Notice {
+        /// Path to the local project directory
+        #[arg(short, long, default_value = "./")]
+        path: String,
+
+        /// Specify the language to scan
+        #[arg(long, short)]
+        language: Option<String>,
+
+        /// File to write, by default THIRD-PARTY-NOTICES in the project directory
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
 /// SBOM Subcommands
 #[derive(Subcommand, Debug, Clone)]
 pub enum SbomCommand {
@@ -83,6 +161,100 @@ pub enum SbomCommand {
     },
 }
 
+/// License classes for `feluda licenses list --class`
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+pub enum LicenseClass {
+    /// Licenses Feluda flags as restrictive
+    Restrictive,
+    /// Licenses Feluda does not flag as restrictive
+    Permissive,
+    /// OSI-approved licenses
+    Osi,
+    /// Licenses the FSF lists as free/libre
+    Fsf,
+    /// Deprecated SPDX identifiers
+    Deprecated,
+    /// Data and content licenses (Creative Commons, Open Data Commons, CDLA)
+    Data,
+}
+
+/// Configuration subcommands
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigCommand {
+    /// Show where configuration is read from, in order of precedence
+    Show {
+        /// Print the merged configuration a scan would use, after every source and flag
+        #[arg(long)]
+        effective: bool,
+    },
+}
+
+/// License knowledge subcommands
+#[derive(Subcommand, Debug, Clone)]
+pub enum LicensesCommand {
+    /// Download the latest SPDX license list into the cache
+    Update {
+        /// SPDX license list release to pin (e.g. 3.24), or "latest" to unpin
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Restore the license list that was cached before the last update
+        #[arg(long, conflicts_with = "version")]
+        rollback: bool,
+    },
+    /// List every known license with its classification
+    List {
+        /// Only show licenses of this class
+        #[arg(long, value_enum)]
+        class: Option<LicenseClass>,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+
+        /// Classify with strict mode (share-alike licenses count as restrictive)
+        #[arg(long)]
+        strict: bool,
+    },
+}
+
+/// `feluda history` subcommands
+#[derive(Subcommand, Debug, Clone)]
+pub enum HistoryCommand {
+    /// Scan the project and store its dependency licenses as a snapshot
+    Record {
+        /// Project directory
+        #[arg(short, long, default_value = "./")]
+        path: String,
+
+        /// Scan this git revision instead of the working tree, to backfill older commits
+        #[arg(long, value_name = "GIT_REV", conflicts_with = "report")]
+        rev: Option<String>,
+
+        /// Record a saved `--json`/`--yaml` report instead of scanning
+        #[arg(long, value_name = "FILE")]
+        report: Option<String>,
+
+        /// History database [default: <path>/.feluda/history.db]
+        #[arg(long, value_name = "FILE")]
+        db: Option<String>,
+    },
+    /// Show when each restrictive dependency was introduced
+    Report {
+        /// Project directory, whose git history describes the recorded commits
+        #[arg(short, long, default_value = "./")]
+        path: String,
+
+        /// History database [default: <path>/.feluda/history.db]
+        #[arg(long, value_name = "FILE")]
+        db: Option<String>,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 /// CLI Commands
 #[derive(Subcommand, Debug, Clone)]
 pub enum Commands {
@@ -99,6 +271,10 @@ pub enum Commands {
         /// Specify the project license explicitly
         #[arg(long)]
         project_license: Option<String>,
+
+        /// File to generate without the interactive menu
+        #[command(subcommand)]
+        command: Option<GenerateCommand>,
     },
     /// Generate Software Bill of Materials (SBOM)
     Sbom {
@@ -120,6 +296,67 @@ pub enum Commands {
         #[arg(long)]
         clear: bool,
     },
+    /// Check whether a dependency license is compatible with a project license
+    CheckCompat {
+        /// The project license (SPDX identifier)
+        #[arg(long = "project", value_name = "SPDX")]
+        project_license: String,
+
+        /// The dependency license (SPDX identifier or expression)
+        #[arg(long = "dep", value_name = "SPDX")]
+        dependency_license: String,
+
+        /// Treat licenses missing from the compatibility matrix as incompatible
+        #[arg(long)]
+        strict: bool,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show the curated permissive alternatives and relicensed releases known for a package
+    Explain {
+        /// Package name as it appears in the report
+        package: String,
+
+        /// Installed version, to tell whether upgrading past a relicense would help
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+    /// Manage the license knowledge Feluda uses
+    Licenses {
+        #[command(subcommand)]
+        command: LicensesCommand,
+    },
+    /// Inspect the configuration Feluda loads (global, project, environment, flags)
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// List the project roots a scan would find, their files, analyzers and resolvers
+    Detect {
+        /// Path to the local project directory
+        #[arg(short, long, default_value = "./")]
+        path: String,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check registry access, the cache and the external tools a scan needs, with fixes
+    Doctor {
+        /// Path to the local project directory, whose ecosystems decide what is checked
+        #[arg(short, long, default_value = "./")]
+        path: String,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
     /// Initialise Feluda in the current project (generates .feluda.toml and .pre-commit-config.yaml)
     Init {
         /// Path to the local project directory
@@ -134,6 +371,22 @@ pub enum Commands {
         #[arg(long)]
         no_pre_commit: bool,
     },
+    /// Scan a Git repository by URL, or a local directory
+    ///
+    /// A URL is cloned into a temporary directory, like --repo; the options are the usual scan flags
+    Scan {
+        /// Git URL (https://, ssh://, git@host:org/repo, github.com/org/repo) or project directory
+        #[arg(value_name = "TARGET")]
+        target: String,
+
+        /// Scan options, as for `feluda` itself (--json, --fail-on-restrictive, ...)
+        #[arg(
+            value_name = "OPTIONS",
+            trailing_var_arg = true,
+            allow_hyphen_values = true
+        )]
+        options: Vec<String>,
+    },
     /// Continuously re-scan when dependency files change (filesystem watch)
     Watch {
         /// Path to the local project directory
@@ -144,6 +397,128 @@ pub enum Commands {
         #[arg(long, default_value_t = 500)]
         debounce: u64,
     },
+    /// Browse a saved report in the TUI, without re-scanning the project
+    Tui {
+        /// Report written by `feluda --json` or `--yaml`, optionally `.gz`/`.zst` compressed
+        #[arg(value_name = "REPORT")]
+        report: String,
+
+        /// Previous report to compare against; the TUI highlights new, removed and changed rows
+        #[arg(long, value_name = "FILE")]
+        baseline: Option<String>,
+
+        /// With `--gui`, compare with `--baseline` even when it was generated from a different lockfile state
+        #[arg(long, requires = "baseline")]
+        force: bool,
+    },
+    /// Compare two saved reports: dependencies added, removed and with a changed license
+    Diff {
+        /// Report of the earlier scan, written by `feluda --json` or `--yaml` (`.gz`/`.zst` too)
+        #[arg(value_name = "OLD")]
+        old: String,
+
+        /// Report of the later scan
+        #[arg(value_name = "NEW")]
+        new: String,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+
+        /// Fail with non-zero exit code when a dependency became restrictive
+        #[arg(long)]
+        fail_on_restrictive: bool,
+
+        /// Fail with non-zero exit code when a dependency became incompatible
+        #[arg(long)]
+        fail_on_incompatible: bool,
+
+        /// Compare the reports even when they were generated from different lockfile states
+        #[arg(long)]
+        force: bool,
+    },
+    /// Record license snapshots over time and see when restrictive dependencies came in
+    History {
+        #[command(subcommand)]
+        command: HistoryCommand,
+    },
+    /// Scan a container image: its OS packages and the language projects inside it
+    Image {
+        /// Image reference (alpine:3.20, ghcr.io/org/app@sha256:…), `docker save` archive or OCI layout directory
+        #[arg(value_name = "REF")]
+        reference: String,
+
+        /// Platform to scan in multi-platform images (os/arch[/variant]); defaults to the host's
+        #[arg(long)]
+        platform: Option<String>,
+    },
+    /// Scan several repositories and rank them in an organization-wide rollup report
+    Rollup {
+        /// Repository directories or Git URLs to scan
+        #[arg(value_name = "REPO")]
+        repos: Vec<String>,
+
+        /// File listing repositories to scan, one path (relative to the file) or Git URL per line
+        #[arg(long, value_name = "FILE")]
+        manifest: Option<String>,
+
+        /// Directory for the per-repository reports and rollup.json
+        #[arg(long, value_name = "DIR", default_value = "feluda-rollup")]
+        out: String,
+
+        /// Number of repositories shown in the ranking table (all of them are in rollup.json)
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+    },
+    /// Accept a dependency as an audited exception by adding it to .feludaignore
+    Accept {
+        /// Dependency to accept, as name or name@version
+        #[arg(value_name = "PKG")]
+        package: String,
+
+        /// Why the dependency is accepted, recorded in .feludaignore and the commit message
+        #[arg(long)]
+        reason: String,
+
+        /// Path to the local project directory
+        #[arg(short, long, default_value = "./")]
+        path: String,
+
+        /// Create this branch from HEAD and commit the change on it, ready to push for review
+        #[arg(long, value_name = "NAME")]
+        branch: Option<String>,
+
+        /// Commit the change on the current branch
+        #[arg(long, conflicts_with = "branch")]
+        commit: bool,
+    },
+    /// Time offline scans of large synthetic projects, optionally against an earlier run
+    #[command(hide = true)]
+    Bench {
+        /// Dependencies in each synthetic project
+        #[arg(long, default_value_t = 2000)]
+        packages: usize,
+
+        /// Runs per case; the median is reported
+        #[arg(long, default_value_t = 5)]
+        iterations: usize,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+
+        /// Earlier `feluda bench --json` output; exit non-zero when a case got slower
+        #[arg(long, value_name = "FILE")]
+        baseline: Option<String>,
+
+        /// Percentage a case may exceed its baseline median by before it counts as a regression
+        #[arg(long, default_value_t = 20.0, requires = "baseline")]
+        tolerance: f64,
+
+        /// Write the synthetic projects into this directory and keep them
+        #[arg(long, value_name = "DIR")]
+        fixtures: Option<String>,
+    },
 }
 
 /// Styling for clap's generated help, matching Feluda's cyan branding
@@ -189,6 +564,19 @@ pub struct Cli {
     #[arg(long, help_heading = HEADING_SOURCE)]
     pub repo: Option<String>,
 
+    /// Scan a manifest or lockfile read from stdin instead of a project directory
+    #[arg(
+        long,
+        requires = "stdin_format",
+        conflicts_with = "repo",
+        help_heading = HEADING_SOURCE
+    )]
+    pub stdin: bool,
+
+    /// File name of the input piped to --stdin (package-lock.json, requirements.txt, go.mod, ...)
+    #[arg(long, value_name = "FILE_NAME", requires = "stdin", help_heading = HEADING_SOURCE)]
+    pub stdin_format: Option<String>,
+
     /// Access token for HTTPS repository authentication
     #[arg(long, requires = "repo", help_heading = HEADING_SOURCE)]
     pub token: Option<String>,
@@ -205,6 +593,19 @@ pub struct Cli {
     #[arg(long, env = "GITHUB_TOKEN", global = true, help_heading = HEADING_SOURCE)]
     pub github_token: Option<String>,
 
+    /// Analyze the repository's GitHub dependency graph SBOM instead of cloning it
+    #[arg(long, requires = "repo", help_heading = HEADING_SOURCE)]
+    pub github_sbom: bool,
+
+    /// Scan the manifests and lockfiles of a git commit, branch or tag instead of the working tree
+    #[arg(
+        long,
+        value_name = "GIT_REV",
+        conflicts_with_all = ["stdin", "github_sbom", "changed_since"],
+        help_heading = HEADING_SOURCE
+    )]
+    pub rev: Option<String>,
+
     /// Output in JSON format (skips the TUI table, useful for CI/CD)
     #[arg(long, short, group = "output", help_heading = HEADING_OUTPUT)]
     pub json: bool,
@@ -213,10 +614,66 @@ pub struct Cli {
     #[arg(long, short, group = "output", help_heading = HEADING_OUTPUT)]
     pub yaml: bool,
 
+    /// Write the results as an SBOM instead of a report (to --output-file, or stdout)
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        group = "output",
+        conflicts_with = "ci_format",
+        help_heading = HEADING_OUTPUT
+    )]
+    pub sbom: Option<SbomExport>,
+
+    /// Write the results as a standalone document to share, e.g. an HTML report (to --output-file, or stdout)
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        group = "output",
+        conflicts_with = "ci_format",
+        help_heading = HEADING_OUTPUT
+    )]
+    pub output_format: Option<OutputFormat>,
+
+    /// Also write one file per unique license text the dependencies use into DIR, with an index.json
+    #[arg(long, value_name = "DIR", help_heading = HEADING_OUTPUT)]
+    pub licenses_dir: Option<String>,
+
+    /// Also write the license obligations (NOTICE files, source disclosure, ...) as a tasks JSON to FILE
+    #[arg(long, value_name = "FILE", help_heading = HEADING_OUTPUT)]
+    pub obligations: Option<String>,
+
+    /// Open a GitHub issue in OWNER/REPO for each license obligation without an open one (needs a GitHub token)
+    #[arg(long, value_name = "OWNER/REPO", help_heading = HEADING_OUTPUT)]
+    pub obligations_issues: Option<String>,
+
+    /// Also write a small JSON summary (exit code, counts, violation ids) to PATH [default: feluda-result.json]
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 0..=1,
+        default_missing_value = crate::result_file::DEFAULT_RESULT_FILE,
+        help_heading = HEADING_OUTPUT
+    )]
+    pub result_file: Option<String>,
+
+    /// Predict the license changes of the open Dependabot/Renovate pull requests in OWNER/REPO
+    #[arg(long, value_name = "OWNER/REPO", help_heading = HEADING_OUTPUT)]
+    pub pending_updates: Option<String>,
+
     /// Enable verbose output
     #[arg(long, help_heading = HEADING_OUTPUT)]
     pub verbose: bool,
 
+    /// List the manifests, resolvers, endpoints and policy a scan would use, without fetching anything
+    #[arg(long, help_heading = HEADING_OUTPUT)]
+    pub dry_run: bool,
+
+    /// Add a bar chart of the license distribution to the summary output
+    #[arg(long, help_heading = HEADING_OUTPUT)]
+    pub chart: bool,
+
     /// Show only restrictive dependencies
     #[arg(long, short, help_heading = HEADING_FILTERS)]
     pub restrictive: bool,
@@ -225,18 +682,111 @@ pub struct Cli {
     #[arg(long, short, help_heading = HEADING_OUTPUT)]
     pub gui: bool,
 
+    /// Previous `--json` report to compare against: only findings it doesn't have fail the build, and the TUI highlights new, removed and changed rows
+    #[arg(long, value_name = "FILE", help_heading = HEADING_OUTPUT)]
+    pub baseline: Option<String>,
+
+    /// Record this scan's dependencies in FILE, to pass as --baseline later
+    #[arg(long, value_name = "FILE", help_heading = HEADING_OUTPUT)]
+    pub write_baseline: Option<String>,
+
+    /// With `--gui`, compare with `--baseline` even when it was generated from a different lockfile state
+    #[arg(long, requires = "baseline", help_heading = HEADING_OUTPUT)]
+    pub force: bool,
+
     /// Specify the language to scan
     #[arg(long, short, help_heading = HEADING_FILTERS)]
     pub language: Option<String>,
 
-    /// Output format for CI systems (github, jenkins, sarif)
+    /// Skip ecosystems that are not shipped (comma-separated, e.g. rust,python)
+    #[arg(long, value_delimiter = ',', help_heading = HEADING_FILTERS)]
+    pub skip: Vec<String>,
+
+    /// Only analyze project roots whose manifests or lockfiles changed since this git ref
+    #[arg(long, value_name = "GIT_REF", help_heading = HEADING_FILTERS)]
+    pub changed_since: Option<String>,
+
+    /// Leave out development-only dependencies (Bazel `dev_dependency = True`, Rust `[dev-dependencies]`, Go test-only modules)
+    #[arg(long, help_heading = HEADING_FILTERS)]
+    pub no_dev: bool,
+
+    /// Report only declared dependencies, not the transitive tree a lockfile pins
+    #[arg(long, help_heading = HEADING_FILTERS)]
+    pub direct_only: bool,
+
+    /// Include Go's indirect dependencies from the module graph (`go mod graph`, else go.sum)
+    #[arg(long, help_heading = HEADING_FILTERS, conflicts_with = "direct_only")]
+    pub include_transitive: bool,
+
+    /// Check at most N dependencies and mark the report as a partial scan (quick smoke check)
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help_heading = HEADING_FILTERS
+    )]
+    pub limit: Option<u64>,
+
+    /// Check a random PERCENT of the dependencies and mark the report as a partial scan
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        value_parser = clap::value_parser!(u8).range(1..=100),
+        help_heading = HEADING_FILTERS
+    )]
+    pub sample: Option<u8>,
+
+    /// Leave Rust `[dev-dependencies]` out of `cargo metadata`'s dependency graph
+    #[arg(long, help_heading = HEADING_FILTERS)]
+    pub no_dev_deps: bool,
+
+    /// Leave Rust `[build-dependencies]` out of `cargo metadata`'s dependency graph
+    #[arg(long, help_heading = HEADING_FILTERS)]
+    pub no_build_deps: bool,
+
+    /// Rust features to activate when resolving dependencies (comma-separated)
+    #[arg(long, value_delimiter = ',', help_heading = HEADING_FILTERS)]
+    pub features: Vec<String>,
+
+    /// Do not activate the Rust crate's `default` feature when resolving dependencies
+    #[arg(long, help_heading = HEADING_FILTERS)]
+    pub no_default_features: bool,
+
+    /// Output format for CI systems (github, jenkins, sarif, gitlab)
     #[arg(long, value_enum, help_heading = HEADING_CI)]
     pub ci_format: Option<CiFormat>,
 
-    /// Path to write the CI report file
+    /// Path to write the report to instead of stdout (CI formats, SBOM, HTML, JSON, YAML)
     #[arg(long, help_heading = HEADING_CI)]
     pub output_file: Option<String>,
 
+    /// Directory to write a timestamped report file to on every run
+    #[arg(long, value_name = "DIR", conflicts_with = "output_file", help_heading = HEADING_CI)]
+    pub output_dir: Option<String>,
+
+    /// Keep only the newest N reports in --output-dir
+    #[arg(
+        long,
+        value_name = "N",
+        requires = "output_dir",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help_heading = HEADING_CI
+    )]
+    pub keep_reports: Option<u64>,
+
+    /// Compress reports written to --output-dir (--output-file compresses by its .gz/.zst extension)
+    #[arg(long, value_enum, requires = "output_dir", help_heading = HEADING_CI)]
+    pub compress: Option<ReportCompression>,
+
+    /// Stop the scan after this many seconds and report what was found so far
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help_heading = HEADING_CI
+    )]
+    pub timeout: Option<u64>,
+
     /// Fail with non-zero exit code when restrictive licenses are found
     #[arg(long, help_heading = HEADING_CI)]
     pub fail_on_restrictive: bool,
@@ -249,6 +799,14 @@ pub struct Cli {
     #[arg(long, help_heading = HEADING_CI)]
     pub fail_on_incompatible: bool,
 
+    /// Fail with non-zero exit code when registry/API lookups failed during the scan
+    #[arg(long, help_heading = HEADING_CI)]
+    pub fail_on_resolution_errors: bool,
+
+    /// Fail with non-zero exit code when a dependency's license is not OSI approved
+    #[arg(long, help_heading = HEADING_CI)]
+    pub osi_only: bool,
+
     /// Specify the project license (overrides auto-detection)
     #[arg(long, help_heading = HEADING_DETECTION)]
     pub project_license: Option<String>,
@@ -269,9 +827,44 @@ pub struct Cli {
     #[arg(long, help_heading = HEADING_DETECTION)]
     pub no_local: bool,
 
+    /// Forbid network access: licenses come from local metadata and the cache only, and
+    /// anything they can't answer is reported as unknown
+    #[arg(
+        long,
+        env = "FELUDA_OFFLINE",
+        value_parser = clap::builder::BoolishValueParser::new(),
+        conflicts_with_all = ["no_local", "repo"],
+        help_heading = HEADING_DETECTION
+    )]
+    pub offline: bool,
+
     /// Skip the vendored/unmanaged dependency tree walk (faster on large repos)
     #[arg(long, help_heading = HEADING_DETECTION)]
     pub no_vendor_scan: bool,
+
+    /// Build output directories to inspect for licenses embedded in shipped artifacts (jars, wheels, .so/.dll, tarballs)
+    #[arg(long, value_name = "DIR", value_delimiter = ',', help_heading = HEADING_DETECTION)]
+    pub artifacts: Vec<String>,
+
+    /// For git dependencies on a GitHub fork with no license, use the upstream project's license
+    #[arg(long, help_heading = HEADING_DETECTION)]
+    pub resolve_forks: bool,
+
+    /// Look up known vulnerabilities of each dependency version on OSV.dev (sends package URLs to api.osv.dev)
+    #[arg(long, conflicts_with = "offline", help_heading = HEADING_DETECTION)]
+    pub vulnerabilities: bool,
+
+    /// Fail when a dependency manifest cannot be parsed instead of reporting a warning
+    #[arg(long, help_heading = HEADING_DETECTION)]
+    pub strict_parse: bool,
+
+    /// Run `cargo metadata --locked`: fail instead of updating an out-of-date Cargo.lock
+    #[arg(long, conflicts_with = "frozen", help_heading = HEADING_CI)]
+    pub locked: bool,
+
+    /// Run `cargo metadata --frozen`: like --locked, and cargo may not touch the network
+    #[arg(long, help_heading = HEADING_CI)]
+    pub frozen: bool,
 }
 
 impl Cli {
@@ -285,15 +878,90 @@ impl Cli {
                     path: "".to_string(),
                     language: None,
                     project_license: None,
+                    command: None,
                 }
             }
         }
     }
 
+    /// `feluda scan <TARGET> [OPTIONS]` as the default command: the command line parsed again
+    /// with the target passed as --repo or --path. Other commands are returned as they are.
+    pub fn expand_scan_command(self) -> Self {
+        match self.command {
+            Some(Commands::Scan {
+                ref target,
+                ref options,
+            }) => {
+                let argv: Vec<String> = env::args().collect();
+                Cli::parse_from(scan_arguments(&argv, target, options))
+            }
+            _ => self,
+        }
+    }
+
     /// Check if this is the default behavior
     pub fn is_default_command(&self) -> bool {
         self.command.is_none()
     }
+
+    /// Cargo.lock handling requested with --locked/--frozen; `None` defers to `.feluda.toml`
+    pub fn cargo_lock(&self) -> Option<CargoLockMode> {
+        if self.frozen {
+            Some(CargoLockMode::Frozen)
+        } else if self.locked {
+            Some(CargoLockMode::Locked)
+        } else {
+            None
+        }
+    }
+
+    /// Cargo resolution requested with --no-dev-deps, --no-build-deps, --features and
+    /// --no-default-features, layered over `.feluda.toml`'s `[cargo]` table
+    pub fn cargo_options(&self) -> CargoConfig {
+        CargoConfig {
+            no_dev_deps: self.no_dev_deps,
+            no_build_deps: self.no_build_deps,
+            features: self.features.clone(),
+            no_default_features: self.no_default_features,
+        }
+    }
+}
+
+/// The command line for `feluda scan <TARGET> [OPTIONS]` as a default-command invocation: the
+/// options given before `scan` are kept, the scan options follow, and the target becomes
+/// `--repo` or `--path`.
+fn scan_arguments(argv: &[String], target: &str, options: &[String]) -> Vec<String> {
+    // The target and the options close the command line; `scan` is the last token before them
+    let target_at = argv.len().saturating_sub(options.len() + 1);
+    let scan_at = argv[..target_at]
+        .iter()
+        .rposition(|arg| arg == "scan")
+        .unwrap_or(target_at);
+
+    let mut arguments: Vec<String> = argv.iter().take(scan_at).cloned().collect();
+    arguments.extend(argv.iter().take(target_at).skip(scan_at + 1).cloned());
+    arguments.extend(options.iter().cloned());
+    match repository_url(target) {
+        Some(url) => arguments.extend(["--repo".to_string(), url]),
+        None => arguments.extend(["--path".to_string(), target.to_string()]),
+    }
+    arguments
+}
+
+/// The clone URL for a `feluda scan` target, or `None` for a local directory. Host-first
+/// shorthands like `github.com/org/repo` are cloned over HTTPS.
+fn repository_url(target: &str) -> Option<String> {
+    if target.contains("://") || target.starts_with("git@") {
+        return Some(target.to_string());
+    }
+    if std::path::Path::new(target).exists() {
+        return None;
+    }
+    let mut segments = target.split('/');
+    let host = segments.next()?;
+    let is_host = host.contains('.') && !host.starts_with('.');
+    (is_host && segments.filter(|segment| !segment.is_empty()).count() >= 2)
+        .then(|| format!("https://{target}"))
 }
 
 /// The FELUDA wordmark, rendered from the 5x7 glyph bitmaps of the
@@ -611,6 +1279,19 @@ pub fn print_version_info() {
     );
 }
 
+/// Whether spinners stay off stderr, as for library callers
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Turn the progress spinners of [`with_spinner`] off or back on
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::SeqCst);
+}
+
+/// Whether the progress spinners are off, see [`set_quiet`]
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::SeqCst)
+}
+
 /// A loading indicator that displays a spinner and progress updates
 /// without deleting the previous line
 pub struct LoadingIndicator {
@@ -708,7 +1389,7 @@ impl LoadingIndicator {
 ///
 /// # Examples
 ///
-/// ```
+/// ```ignore
 /// let result = with_spinner("Processing data", |indicator| {
 ///     // Initial work
 ///     let data = prepare_data();
@@ -724,6 +1405,9 @@ pub fn with_spinner<F, T>(message: &str, f: F) -> T
 where
     F: FnOnce(&LoadingIndicator) -> T,
 {
+    if is_quiet() {
+        return f(&LoadingIndicator::new(message));
+    }
     if is_debug_mode() {
         log(LogLevel::Info, &format!("Operation: {message}"));
         let start = std::time::Instant::now();
@@ -775,6 +1459,8 @@ mod tests {
             command: None,
             path: "./".to_string(),
             repo: None,
+            stdin: false,
+            stdin_format: None,
             token: None,
             ssh_key: None,
             ssh_passphrase: None,
@@ -790,12 +1476,50 @@ mod tests {
             fail_on_restrictive: false,
             incompatible: false,
             fail_on_incompatible: false,
+            fail_on_resolution_errors: false,
+            osi_only: false,
             project_license: None,
             gist: false,
             osi: None,
             strict: false,
             no_local: false,
+            offline: false,
             no_vendor_scan: false,
+            artifacts: Vec::new(),
+            resolve_forks: false,
+            vulnerabilities: false,
+            strict_parse: false,
+            skip: Vec::new(),
+            changed_since: None,
+            no_dev: false,
+            direct_only: false,
+            include_transitive: false,
+            no_dev_deps: false,
+            no_build_deps: false,
+            features: Vec::new(),
+            no_default_features: false,
+            github_sbom: false,
+            limit: None,
+            sample: None,
+            rev: None,
+            baseline: None,
+            write_baseline: None,
+            force: false,
+            output_dir: None,
+            keep_reports: None,
+            compress: None,
+            chart: false,
+            locked: false,
+            frozen: false,
+            dry_run: false,
+            sbom: None,
+            output_format: None,
+            licenses_dir: None,
+            obligations: None,
+            obligations_issues: None,
+            pending_updates: None,
+            result_file: None,
+            timeout: None,
         };
 
         assert_eq!(cli.path, "./");
@@ -816,9 +1540,12 @@ mod tests {
                 path: "/test/path".to_string(),
                 language: Some("rust".to_string()),
                 project_license: Some("MIT".to_string()),
+                command: None,
             }),
             path: "./".to_string(),
             repo: None,
+            stdin: false,
+            stdin_format: None,
             token: None,
             ssh_key: None,
             ssh_passphrase: None,
@@ -834,12 +1561,50 @@ mod tests {
             fail_on_restrictive: false,
             incompatible: false,
             fail_on_incompatible: false,
+            fail_on_resolution_errors: false,
+            osi_only: false,
             project_license: None,
             gist: false,
             osi: None,
             strict: false,
             no_local: false,
+            offline: false,
             no_vendor_scan: false,
+            artifacts: Vec::new(),
+            resolve_forks: false,
+            vulnerabilities: false,
+            strict_parse: false,
+            skip: Vec::new(),
+            changed_since: None,
+            no_dev: false,
+            direct_only: false,
+            include_transitive: false,
+            no_dev_deps: false,
+            no_build_deps: false,
+            features: Vec::new(),
+            no_default_features: false,
+            github_sbom: false,
+            limit: None,
+            sample: None,
+            rev: None,
+            baseline: None,
+            write_baseline: None,
+            force: false,
+            output_dir: None,
+            keep_reports: None,
+            compress: None,
+            chart: false,
+            locked: false,
+            frozen: false,
+            dry_run: false,
+            sbom: None,
+            output_format: None,
+            licenses_dir: None,
+            obligations: None,
+            obligations_issues: None,
+            pending_updates: None,
+            result_file: None,
+            timeout: None,
         };
 
         let cmd = cli.get_command_args();
@@ -848,6 +1613,7 @@ mod tests {
                 path,
                 language,
                 project_license,
+                ..
             } => {
                 assert_eq!(path, "/test/path");
                 assert_eq!(language, Some("rust".to_string()));
@@ -855,8 +1621,22 @@ mod tests {
             }
             Commands::Sbom { .. }
             | Commands::Cache { .. }
+            | Commands::CheckCompat { .. }
+            | Commands::Explain { .. }
+            | Commands::Licenses { .. }
+            | Commands::Detect { .. }
+            | Commands::Doctor { .. }
             | Commands::Init { .. }
-            | Commands::Watch { .. } => {
+            | Commands::Watch { .. }
+            | Commands::Tui { .. }
+            | Commands::Diff { .. }
+            | Commands::History { .. }
+            | Commands::Scan { .. }
+            | Commands::Config { .. }
+            | Commands::Image { .. }
+            | Commands::Rollup { .. }
+            | Commands::Accept { .. }
+            | Commands::Bench { .. } => {
                 panic!("Expected Generate command");
             }
         }
@@ -870,6 +1650,8 @@ mod tests {
             command: None,
             path: "./test".to_string(),
             repo: None,
+            stdin: false,
+            stdin_format: None,
             token: None,
             ssh_key: None,
             ssh_passphrase: None,
@@ -885,12 +1667,50 @@ mod tests {
             fail_on_restrictive: false,
             incompatible: false,
             fail_on_incompatible: false,
+            fail_on_resolution_errors: false,
+            osi_only: false,
             project_license: None,
             gist: false,
             osi: None,
             strict: false,
             no_local: false,
+            offline: false,
             no_vendor_scan: false,
+            artifacts: Vec::new(),
+            resolve_forks: false,
+            vulnerabilities: false,
+            strict_parse: false,
+            skip: Vec::new(),
+            changed_since: None,
+            no_dev: false,
+            direct_only: false,
+            include_transitive: false,
+            no_dev_deps: false,
+            no_build_deps: false,
+            features: Vec::new(),
+            no_default_features: false,
+            github_sbom: false,
+            limit: None,
+            sample: None,
+            rev: None,
+            baseline: None,
+            write_baseline: None,
+            force: false,
+            output_dir: None,
+            keep_reports: None,
+            compress: None,
+            chart: false,
+            locked: false,
+            frozen: false,
+            dry_run: false,
+            sbom: None,
+            output_format: None,
+            licenses_dir: None,
+            obligations: None,
+            obligations_issues: None,
+            pending_updates: None,
+            result_file: None,
+            timeout: None,
         };
 
         let cmd = cli.get_command_args();
@@ -899,6 +1719,7 @@ mod tests {
                 path,
                 language,
                 project_license,
+                ..
             } => {
                 assert_eq!(path, "");
                 assert_eq!(language, None);
@@ -906,8 +1727,22 @@ mod tests {
             }
             Commands::Sbom { .. }
             | Commands::Cache { .. }
+            | Commands::CheckCompat { .. }
+            | Commands::Explain { .. }
+            | Commands::Licenses { .. }
+            | Commands::Detect { .. }
+            | Commands::Doctor { .. }
             | Commands::Init { .. }
-            | Commands::Watch { .. } => {
+            | Commands::Watch { .. }
+            | Commands::Tui { .. }
+            | Commands::Diff { .. }
+            | Commands::History { .. }
+            | Commands::Scan { .. }
+            | Commands::Config { .. }
+            | Commands::Image { .. }
+            | Commands::Rollup { .. }
+            | Commands::Accept { .. }
+            | Commands::Bench { .. } => {
                 panic!("Expected Generate command");
             }
         }
@@ -1121,6 +1956,7 @@ mod tests {
             path: "./".to_string(),
             language: None,
             project_license: None,
+            command: None,
         };
 
         let cloned_cmd = generate_cmd.clone();
@@ -1131,11 +1967,13 @@ mod tests {
                     path: p1,
                     language: l1,
                     project_license: pl1,
+                    ..
                 },
                 Commands::Generate {
                     path: p2,
                     language: l2,
                     project_license: pl2,
+                    ..
                 },
             ) => {
                 assert_eq!(p1, p2);
@@ -1245,4 +2083,78 @@ mod tests {
             _ => panic!("Expected Sbom command"),
         }
     }
+
+    #[test]
+    fn test_scan_arguments() {
+        let argv = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let options = argv(&["--json", "--language", "scan"]);
+
+        // Options before and after `scan` both reach the re-parsed command line
+        assert_eq!(
+            scan_arguments(
+                &argv(&[
+                    "feluda",
+                    "--strict",
+                    "scan",
+                    "-d",
+                    "https://github.com/org/repo",
+                    "--json",
+                    "--language",
+                    "scan"
+                ]),
+                "https://github.com/org/repo",
+                &options
+            ),
+            argv(&[
+                "feluda",
+                "--strict",
+                "-d",
+                "--json",
+                "--language",
+                "scan",
+                "--repo",
+                "https://github.com/org/repo"
+            ])
+        );
+        assert_eq!(
+            scan_arguments(&argv(&["feluda", "scan", "./"]), "./", &[]),
+            argv(&["feluda", "--path", "./"])
+        );
+
+        let cli = Cli::parse_from(scan_arguments(
+            &argv(&["feluda", "scan", "github.com/org/repo", "--json"]),
+            "github.com/org/repo",
+            &argv(&["--json"]),
+        ));
+        assert!(cli.is_default_command());
+        assert!(cli.json);
+        assert_eq!(cli.repo.as_deref(), Some("https://github.com/org/repo"));
+
+        // A second `scan` is one of the options and survives the expansion
+        let cli = Cli::parse_from(scan_arguments(
+            &argv(&["feluda", "scan", "github.com/a/b", "scan", "github.com/c/d"]),
+            "github.com/a/b",
+            &argv(&["scan", "github.com/c/d"]),
+        ));
+        assert!(matches!(cli.command, Some(Commands::Scan { .. })));
+    }
+
+    #[test]
+    fn test_repository_url() {
+        for url in [
+            "https://github.com/org/repo",
+            "ssh://git@gitlab.com/org/repo.git",
+            "git@github.com:org/repo.git",
+            "file:///srv/git/repo",
+        ] {
+            assert_eq!(repository_url(url).as_deref(), Some(url));
+        }
+        assert_eq!(
+            repository_url("github.com/org/repo").as_deref(),
+            Some("https://github.com/org/repo")
+        );
+        assert_eq!(repository_url("./"), None);
+        assert_eq!(repository_url("services/api"), None);
+        assert_eq!(repository_url("github.com"), None);
+    }
 }
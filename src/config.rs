@@ -1,15 +1,68 @@
 //! Configuration handling for Feluda
 //!
 //! This module provides functionality to load and manage configuration settings.
-//! Configuration can be provided through:
+//! Configuration can be provided through, each overriding the ones before it:
 //!
 //! 1. Default values (built into the binary)
-//! 2. `.feluda.toml` file in the project root
-//! 3. Environment variables prefixed with `FELUDA_`
+//! 2. User-global `$XDG_CONFIG_HOME/feluda/config.toml` (`~/.config/feluda/config.toml`)
+//! 3. `.feluda.toml` file in the project root
+//! 4. Environment variables prefixed with `FELUDA_`
+//! 5. Scan flags that mirror a setting (`--strict`, `--skip`, `--no-dev`, `--direct-only`,
+//!    `--include-transitive`, `--locked`/`--frozen`, and the Cargo flags `--no-dev-deps`,
+//!    `--no-build-deps`, `--features`, `--no-default-features`)
+//!
+//! `feluda config show --effective` prints the result, naming the source of every key.
 //!
 //! # Configuration File Example
 //!
 //! ```toml
+//! # Ecosystems to leave out of the scan (same names as `--language`)
+//! skip = ["python"]
+//! # Run `cargo metadata` with --locked (or --frozen) so scans never rewrite Cargo.lock
+//! cargo_lock = "locked"
+//! # Leave development-only dependencies (e.g. Bazel `dev_dependency = True`) out of the scan
+//! no_dev = true
+//! # Report only the dependencies manifests declare, not the full tree npm lockfiles pin
+//! direct_only = false
+//! # Resolve Go's full module graph rather than the requirements go.mod lists
+//! include_transitive = false
+//!
+//! [cargo]
+//! # Resolve Rust dependencies the way the release binary is built
+//! no_dev_deps = true
+//! no_build_deps = false
+//! features = ["tls"]
+//! no_default_features = false
+//!
+//! [internal]
+//! # The organisation's own packages: never looked up remotely, never Unknown
+//! namespaces = ["@myorg/*", "github.com/myorg/*", "myorg-*"]
+//! license = "LicenseRef-Myorg-Proprietary"
+//!
+//! [resolvers]
+//! # Where licenses are looked up, in order; leave a source out to disable it
+//! order = ["local", "cache", "registry", "clearlydefined", "github"]
+//! # Dependencies looked up at once; lookups mostly wait on the network
+//! jobs = 16
+//!
+//! # Project license of each part of a monorepo with mixed licensing; dependencies are checked
+//! # against the license of the directory that declares them (or its nearest configured parent)
+//! [project_licenses]
+//! "services/api" = "AGPL-3.0"
+//! "packages/sdk" = "Apache-2.0"
+//!
+//! [github]
+//! # GitHub Enterprise Server: send license and repository lookups to its REST API
+//! api = "https://github.example.com/api/v3"
+//! # Prefer FELUDA_GITHUB_TOKEN to keeping the token in the file
+//! token = "ghp_..."
+//!
+//! # Also send every report to an internal compliance service (see `outputs.rs` for the sinks)
+//! [outputs.compliance]
+//! type = "http"
+//! url = "https://compliance.example.com/api/feluda"
+//! token_env = "COMPLIANCE_TOKEN"
+//!
 //! [licenses]
 //! # Override the default list of restrictive licenses
 //! restrictive = [
@@ -24,6 +77,35 @@
 //!     "Apache-2.0",   # Apache License 2.0
 //! ]
 //!
+//! # How SPDX NOASSERTION (not determined) and NONE (no license granted) are treated:
+//! # "unknown", "permissive" or "restrictive"
+//! noassertion = "unknown"
+//! none = "restrictive"
+//!
+//! # Report license files or metadata matching a rule as that license
+//! [[licenses.rules]]
+//! license = "LicenseRef-Acme-Proprietary"
+//! pattern = "(?i)acme corp(oration)? proprietary"
+//!
+//! [[licenses.rules]]
+//! license = "LicenseRef-Acme-Internal"
+//! keywords = ["ACME", "internal use only"]
+//!
+//! # How custom LicenseRef- identifiers are treated; `*` matches a prefix
+//! [licenses.refs]
+//! "LicenseRef-Acme-Proprietary" = "permissive"
+//! "LicenseRef-Commercial-*" = "restrictive"
+//!
+//! # Policy decisions that override the restrictive list and the compatibility matrix;
+//! # `*` matches any run of characters, package entries beat license entries
+//! [licenses.allow]
+//! licenses = ["MIT", "Apache-2.0", "BSD-*", "MPL-2.0"]
+//! packages = ["readline@8.2"]
+//!
+//! [licenses.deny]
+//! licenses = ["AGPL-*", "SSPL-1.0"]
+//! packages = ["left-pad"]
+//!
 //! [[dependencies.ignore]]
 //! name = "github.com/opcotech/elemo-pre-mailer"
 //! version = "v1.0.0"
@@ -44,16 +126,25 @@
 //! export FELUDA_LICENSES_RESTRICTIVE='["GPL-3.0","AGPL-3.0"]'
 //! # Override ignore licenses list
 //! export FELUDA_LICENSES_IGNORE='["MIT","Apache-2.0"]'
+//! # Only ask the registries
+//! export FELUDA_RESOLVERS_ORDER='["registry"]'
+//! # Token for the [github] API
+//! export FELUDA_GITHUB_TOKEN='ghp_...'
 //! ```
 
+use colored::*;
 use figment::{
     providers::{Env, Format, Serialized, Toml},
-    Figment,
+    Figment, Source,
 };
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
 
 use crate::debug::{log, log_debug, log_error, FeludaError, FeludaResult, LogLevel};
+use crate::outputs::OutputSink;
+use crate::resolver::{ResolverSource, DEFAULT_JOBS, DEFAULT_ORDER};
 
 /// Main configuration structure for Feluda
 #[derive(Debug, Deserialize, Serialize, Default, Clone)]
@@ -64,17 +155,311 @@ pub struct FeludaConfig {
     pub dependencies: DependencyConfig,
     #[serde(default)]
     pub strict: bool,
+    /// Ecosystems to leave out of the scan (same names as `--language`)
+    #[serde(default)]
+    pub skip: Vec<String>,
+    /// Whether `cargo metadata` may update Cargo.lock
+    #[serde(default)]
+    pub cargo_lock: CargoLockMode,
+    /// Leave development-only dependencies out of the scan
+    #[serde(default)]
+    pub no_dev: bool,
+    /// Report only declared dependencies, not the transitive ones a lockfile pins
+    #[serde(default)]
+    pub direct_only: bool,
+    /// Resolve Go's full module graph instead of reading go.mod's requirements
+    #[serde(default)]
+    pub include_transitive: bool,
+    #[serde(default)]
+    pub cargo: CargoConfig,
+    #[serde(default)]
+    pub resolvers: ResolverConfig,
+    #[serde(default)]
+    pub internal: InternalConfig,
+    #[serde(default)]
+    pub github: GithubConfig,
+    /// Project license of a part of a monorepo, by directory relative to the project root;
+    /// subdirectories inherit the nearest entry
+    #[serde(default)]
+    pub project_licenses: BTreeMap<String, String>,
+    /// Where each report is sent besides the terminal, by name
+    #[serde(default)]
+    pub outputs: BTreeMap<String, OutputSink>,
+}
+
+/// The GitHub that license and repository lookups go to
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+pub struct GithubConfig {
+    /// REST API base URL of a GitHub Enterprise Server, e.g. `https://github.example.com/api/v3`
+    #[serde(default)]
+    pub api: Option<String>,
+    /// Token for that API, used when neither `--github-token` nor `GITHUB_TOKEN` is set
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+impl GithubConfig {
+    /// Validates the GitHub configuration
+    pub fn validate(&self) -> FeludaResult<()> {
+        if let Some(ref api) = self.api {
+            if !(api.starts_with("https://") || api.starts_with("http://")) {
+                return Err(FeludaError::Config(format!(
+                    "[github] api must be an http(s) URL, got '{api}'"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The organisation's own packages, which get an assumed license instead of a lookup
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct InternalConfig {
+    /// Package name patterns, `*` matching any run of characters: `@myorg/*`,
+    /// `github.com/myorg/*`, `myorg-*`
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+    /// License reported for internal packages with no license of their own
+    #[serde(default = "default_internal_license")]
+    pub license: String,
+}
+
+impl Default for InternalConfig {
+    fn default() -> Self {
+        Self {
+            namespaces: Vec::new(),
+            license: default_internal_license(),
+        }
+    }
+}
+
+fn default_internal_license() -> String {
+    "LicenseRef-Internal".to_string()
+}
+
+impl InternalConfig {
+    /// Whether `name` falls under one of the internal namespaces
+    pub fn is_internal(&self, name: &str) -> bool {
+        self.namespaces
+            .iter()
+            .any(|pattern| matches_namespace(pattern, name))
+    }
+
+    /// Validates the internal namespace configuration
+    pub fn validate(&self) -> FeludaResult<()> {
+        if self
+            .namespaces
+            .iter()
+            .any(|pattern| pattern.trim().is_empty())
+        {
+            return Err(FeludaError::Config(
+                "Empty pattern found in [internal] namespaces".to_string(),
+            ));
+        }
+        if self.license.trim().is_empty() {
+            return Err(FeludaError::Config(
+                "[internal] license must not be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Match a package name against a namespace pattern where `*` matches any run of characters
+pub(crate) fn matches_namespace(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard: exact match
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Where licenses are looked up, see [`crate::resolver`]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ResolverConfig {
+    /// Sources to try, first answer wins. Sources left out are disabled.
+    #[serde(default = "default_resolver_order")]
+    pub order: Vec<ResolverSource>,
+    /// How many dependencies are looked up at once, across every ecosystem
+    #[serde(default = "default_resolver_jobs")]
+    pub jobs: usize,
+}
+
+impl Default for ResolverConfig {
+    fn default() -> Self {
+        Self {
+            order: default_resolver_order(),
+            jobs: default_resolver_jobs(),
+        }
+    }
+}
+
+fn default_resolver_order() -> Vec<ResolverSource> {
+    DEFAULT_ORDER.to_vec()
+}
+
+fn default_resolver_jobs() -> usize {
+    DEFAULT_JOBS
+}
+
+impl ResolverConfig {
+    /// Validates the resolver configuration
+    pub fn validate(&self) -> FeludaResult<()> {
+        for (index, source) in self.order.iter().enumerate() {
+            if self.order[..index].contains(source) {
+                return Err(FeludaError::Config(format!(
+                    "Resolver '{source}' is listed more than once in [resolvers] order"
+                )));
+            }
+        }
+
+        if self.order.is_empty() {
+            log(
+                LogLevel::Warn,
+                "[resolvers] order is empty: every dependency license will be unknown",
+            );
+        }
+        if self.jobs == 0 {
+            return Err(FeludaError::Config(
+                "[resolvers] jobs must be at least 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// How Rust scans treat Cargo.lock, mirroring cargo's own flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CargoLockMode {
+    /// Let cargo create or update the lockfile as needed
+    #[default]
+    Update,
+    /// `--locked`: fail if Cargo.lock is missing or out of date
+    Locked,
+    /// `--frozen`: `--locked` without network access
+    Frozen,
+}
+
+impl CargoLockMode {
+    /// Flag to pass to `cargo metadata`
+    pub fn cargo_flag(self) -> Option<&'static str> {
+        match self {
+            CargoLockMode::Update => None,
+            CargoLockMode::Locked => Some("--locked"),
+            CargoLockMode::Frozen => Some("--frozen"),
+        }
+    }
+}
+
+/// Which part of a Rust project's dependency graph `cargo metadata` resolves
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
+pub struct CargoConfig {
+    /// Leave out `[dev-dependencies]` and whatever only they pull in
+    #[serde(default)]
+    pub no_dev_deps: bool,
+    /// Leave out `[build-dependencies]` and whatever only they pull in
+    #[serde(default)]
+    pub no_build_deps: bool,
+    /// Features to activate, as `cargo build --features` takes them
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// Do not activate the `default` feature
+    #[serde(default)]
+    pub no_default_features: bool,
 }
 
 impl FeludaConfig {
+    /// Layer the scan flags that mirror a setting on top of the loaded configuration
+    pub fn apply_flags(
+        &mut self,
+        strict: bool,
+        cargo_lock: Option<CargoLockMode>,
+        no_dev: bool,
+        direct_only: bool,
+        include_transitive: bool,
+        skip: &[String],
+    ) {
+        self.strict |= strict;
+        if let Some(cargo_lock) = cargo_lock {
+            self.cargo_lock = cargo_lock;
+        }
+        self.no_dev |= no_dev;
+        self.direct_only |= direct_only;
+        self.include_transitive |= include_transitive;
+        for ecosystem in skip {
+            if !self.skip.contains(ecosystem) {
+                self.skip.push(ecosystem.clone());
+            }
+        }
+    }
+
+    /// Layer the Cargo resolution flags on top of the `[cargo]` table
+    pub fn apply_cargo_flags(&mut self, cargo: &CargoConfig) {
+        self.cargo.no_dev_deps |= cargo.no_dev_deps;
+        self.cargo.no_build_deps |= cargo.no_build_deps;
+        self.cargo.no_default_features |= cargo.no_default_features;
+        for feature in &cargo.features {
+            if !self.cargo.features.contains(feature) {
+                self.cargo.features.push(feature.clone());
+            }
+        }
+    }
+
     /// Validates the configuration for logical consistency and correctness
     pub fn validate(&self) -> FeludaResult<()> {
         self.licenses.validate()?;
         self.dependencies.validate()?;
+        self.resolvers.validate()?;
+        self.internal.validate()?;
+        self.github.validate()?;
+        validate_project_licenses(&self.project_licenses)?;
+        for (name, sink) in &self.outputs {
+            sink.validate(name)?;
+        }
         Ok(())
     }
 }
 
+/// Validates the `[project_licenses]` directories and licenses
+fn validate_project_licenses(project_licenses: &BTreeMap<String, String>) -> FeludaResult<()> {
+    for (dir, license) in project_licenses {
+        if normalize_project_dir(dir).is_empty() {
+            return Err(FeludaError::Config(format!(
+                "[project_licenses] directory '{dir}' must name a subdirectory; set the project license for the root with --project-license or a LICENSE file"
+            )));
+        }
+        if license.trim().is_empty() {
+            return Err(FeludaError::Config(format!(
+                "[project_licenses] license for '{dir}' must not be empty"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// `./services/api/` as `services/api`, with `/` separators
+pub fn normalize_project_dir(dir: &str) -> String {
+    dir.replace('\\', "/")
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
 /// Configuration for license-related settings
 ///
 /// By default, the following licenses are considered restrictive:
@@ -93,6 +478,24 @@ pub struct LicenseConfig {
     pub restrictive: Vec<String>,
     #[serde(default)]
     pub ignore: Vec<String>,
+    /// Custom detection rules, checked before the built-in license text rules
+    #[serde(default)]
+    pub rules: Vec<LicenseRule>,
+    /// Classifications for `LicenseRef-*` identifiers; a key ending in `*` matches a prefix
+    #[serde(default)]
+    pub refs: BTreeMap<String, LicenseRefClass>,
+    /// How `NOASSERTION` (license not determined) is treated
+    #[serde(default = "default_noassertion_policy")]
+    pub noassertion: SpecialLicensePolicy,
+    /// How `NONE` (explicitly no license, npm's `UNLICENSED`) is treated
+    #[serde(default = "default_none_policy")]
+    pub none: SpecialLicensePolicy,
+    /// Licenses and packages that always pass, whatever the matrix and restrictive list say
+    #[serde(default)]
+    pub allow: LicensePolicyList,
+    /// Licenses and packages that always fail
+    #[serde(default)]
+    pub deny: LicensePolicyList,
 }
 
 impl Default for LicenseConfig {
@@ -100,6 +503,161 @@ impl Default for LicenseConfig {
         Self {
             restrictive: default_restrictive_licenses(),
             ignore: Vec::new(),
+            rules: Vec::new(),
+            refs: BTreeMap::new(),
+            noassertion: default_noassertion_policy(),
+            none: default_none_policy(),
+            allow: LicensePolicyList::default(),
+            deny: LicensePolicyList::default(),
+        }
+    }
+}
+
+fn default_noassertion_policy() -> SpecialLicensePolicy {
+    SpecialLicensePolicy::Unknown
+}
+
+fn default_none_policy() -> SpecialLicensePolicy {
+    SpecialLicensePolicy::Restrictive
+}
+
+/// How the SPDX special values `NOASSERTION` and `NONE` are treated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpecialLicensePolicy {
+    /// Like any unknown license: restrictive and incompatible only in strict mode
+    Unknown,
+    /// Not restrictive, and compatible with any project license
+    Permissive,
+    /// Restrictive, and incompatible with every project license
+    Restrictive,
+}
+
+impl std::fmt::Display for SpecialLicensePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "unknown"),
+            Self::Permissive => write!(f, "permissive"),
+            Self::Restrictive => write!(f, "restrictive"),
+        }
+    }
+}
+
+/// How a `LicenseRef-*` identifier listed in `[licenses.refs]` is treated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LicenseRefClass {
+    /// Not restrictive, and compatible with any project license
+    Permissive,
+    /// Restrictive, and incompatible with every project license
+    Restrictive,
+}
+
+impl std::fmt::Display for LicenseRefClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Permissive => write!(f, "permissive"),
+            Self::Restrictive => write!(f, "restrictive"),
+        }
+    }
+}
+
+/// A `[licenses.allow]` or `[licenses.deny]` table. Patterns match with `*` standing for any
+/// run of characters.
+///
+/// A license policy overrides both the restrictive list and the compatibility matrix: allowed
+/// licenses are never restrictive and compatible with any project license, denied ones are
+/// restrictive and incompatible with every project license. The most specific matching
+/// pattern decides (exact before wildcard, then the longest), and deny wins a tie. Package
+/// entries are per-dependency exceptions and take precedence over the license lists.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct LicensePolicyList {
+    /// License IDs: `MIT`, `BSD-*`. `GPL-3.0` also covers `GPL-3.0-only` and `GPL-3.0-or-later`.
+    #[serde(default)]
+    pub licenses: Vec<String>,
+    /// Package names, optionally pinned to one version: `left-pad`, `@acme/*`, `readline@8.2`
+    #[serde(default)]
+    pub packages: Vec<String>,
+}
+
+impl LicensePolicyList {
+    fn validate(&self, table: &str) -> FeludaResult<()> {
+        if self
+            .licenses
+            .iter()
+            .chain(&self.packages)
+            .any(|pattern| pattern.trim().is_empty())
+        {
+            return Err(FeludaError::Config(format!(
+                "Empty pattern found in [{table}]"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Whether a `[licenses.allow]`/`[licenses.deny]` decision lets a license or package pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyVerdict {
+    Allow,
+    Deny,
+}
+
+impl std::fmt::Display for PolicyVerdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Allow => write!(f, "licenses.allow"),
+            Self::Deny => write!(f, "licenses.deny"),
+        }
+    }
+}
+
+/// A `[[licenses.rules]]` entry: license text or metadata matching it is reported as `license`
+///
+/// Set either `pattern` (a regex) or `keywords` (all must appear, case-insensitively).
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct LicenseRule {
+    /// License ID to report, e.g. "LicenseRef-Acme-Proprietary"
+    pub license: String,
+    #[serde(default)]
+    pub pattern: Option<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+impl LicenseRule {
+    fn validate(&self) -> FeludaResult<()> {
+        if self.license.trim().is_empty() {
+            return Err(FeludaError::Config(
+                "License rule is missing its `license` ID".to_string(),
+            ));
+        }
+
+        match (&self.pattern, self.keywords.is_empty()) {
+            (Some(_), false) | (None, true) => Err(FeludaError::Config(format!(
+                "License rule for '{}' needs exactly one of `pattern` or `keywords`",
+                self.license
+            ))),
+            (Some(pattern), true) => regex::Regex::new(pattern).map(|_| ()).map_err(|e| {
+                FeludaError::Config(format!(
+                    "Invalid pattern in license rule for '{}': {e}",
+                    self.license
+                ))
+            }),
+            (None, false) => {
+                if self
+                    .keywords
+                    .iter()
+                    .any(|keyword| keyword.trim().is_empty())
+                {
+                    return Err(FeludaError::Config(format!(
+                        "Empty keyword in license rule for '{}'",
+                        self.license
+                    )));
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -201,6 +759,43 @@ impl LicenseConfig {
             );
         }
 
+        for rule in &self.rules {
+            rule.validate()?;
+        }
+
+        self.allow.validate("licenses.allow")?;
+        self.deny.validate("licenses.deny")?;
+        let contradictions: Vec<&String> = self
+            .allow
+            .licenses
+            .iter()
+            .filter(|pattern| self.deny.licenses.contains(pattern))
+            .chain(
+                self.allow
+                    .packages
+                    .iter()
+                    .filter(|pattern| self.deny.packages.contains(pattern)),
+            )
+            .collect();
+        if !contradictions.is_empty() {
+            return Err(FeludaError::Config(format!(
+                "Patterns found in both [licenses.allow] and [licenses.deny]: {}",
+                contradictions
+                    .iter()
+                    .map(|pattern| pattern.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+
+        for key in self.refs.keys() {
+            if !key.starts_with("LicenseRef-") && !key.starts_with("DocumentRef-") {
+                return Err(FeludaError::Config(format!(
+                    "[licenses.refs] key '{key}' must be a LicenseRef- or DocumentRef- identifier"
+                )));
+            }
+        }
+
         log_debug("License configuration validation passed", &self.restrictive);
         log_debug("Ignore licenses configuration", &self.ignore);
         Ok(())
@@ -337,23 +932,30 @@ impl DependencyConfig {
         Ok(())
     }
 
-    /// Check if a dependency should be ignored based on configuration
-    /// Returns true if the dependency matches an ignore rule (name and optionally version)
-    pub fn should_ignore_dependency(&self, name: &str, version: Option<&str>) -> bool {
-        self.ignore.iter().any(|ignored| {
-            // Match by name (case-sensitive)
-            if ignored.name != name {
-                return false;
-            }
+    /// Find the ignore rule covering a dependency, if any
+    /// Matches on name and, when the rule gives one, version
+    pub fn ignore_rule(&self, name: &str, version: Option<&str>) -> Option<&IgnoreDependency> {
+        self.ignore
+            .iter()
+            .find(|ignored| ignored.matches(name, version))
+    }
+}
 
-            // If version is specified in ignore rule, match exactly
-            if !ignored.version.is_empty() {
-                return version.is_some_and(|v| v == ignored.version);
-            }
+impl IgnoreDependency {
+    /// Whether this rule covers the dependency `name` at `version`
+    pub fn matches(&self, name: &str, version: Option<&str>) -> bool {
+        // Match by name (case-sensitive)
+        if self.name != name {
+            return false;
+        }
 
-            // If version is empty in ignore rule, ignore all versions
-            true
-        })
+        // If version is specified in ignore rule, match exactly
+        if !self.version.is_empty() {
+            return version.is_some_and(|v| v == self.version);
+        }
+
+        // If version is empty in ignore rule, ignore all versions
+        true
     }
 }
 
@@ -381,44 +983,156 @@ fn default_restrictive_licenses() -> Vec<String> {
     licenses
 }
 
+/// Project-level configuration file, read from the working directory
+pub const PROJECT_CONFIG_FILE: &str = ".feluda.toml";
+
+/// Where the library's `scan` reads configuration from while it runs, in place of the working
+/// directory
+static CONFIG_SCOPE: RwLock<Option<ConfigScope>> = RwLock::new(None);
+
+#[derive(Debug, Clone)]
+struct ConfigScope {
+    /// Directory holding the project's `.feluda.toml`
+    project_dir: PathBuf,
+    /// Configuration to use instead of the files and environment
+    config: Option<FeludaConfig>,
+}
+
+/// Restores the working-directory configuration when dropped, see [`scope_config`]
+pub struct ConfigScopeGuard(());
+
+impl Drop for ConfigScopeGuard {
+    fn drop(&mut self) {
+        *CONFIG_SCOPE.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+/// Until the guard is dropped, read `.feluda.toml` from `project_dir` rather than the working
+/// directory, or use `config` as is instead of every configuration source
+pub fn scope_config(project_dir: &Path, config: Option<FeludaConfig>) -> ConfigScopeGuard {
+    *CONFIG_SCOPE.write().unwrap_or_else(|e| e.into_inner()) = Some(ConfigScope {
+        project_dir: project_dir.to_path_buf(),
+        config,
+    });
+    ConfigScopeGuard(())
+}
+
+fn config_scope() -> Option<ConfigScope> {
+    CONFIG_SCOPE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// User-global configuration file: `$XDG_CONFIG_HOME/feluda/config.toml`, or
+/// `~/.config/feluda/config.toml` when `XDG_CONFIG_HOME` is unset
+pub fn global_config_path() -> Option<PathBuf> {
+    // The XDG spec says to ignore a relative XDG_CONFIG_HOME
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| dirs::home_dir().map(|home| home.join(".config")))?;
+    Some(base.join("feluda").join("config.toml"))
+}
+
 /// Loads the configuration using the following providers (in order of precedence):
 ///
 /// 1. Environment variables prefixed with `FELUDA_`
 /// 2. `.feluda.toml` file in the project root
-/// 3. Default values
+/// 3. The user-global config file, see [`global_config_path`]
+/// 4. Default values
 ///
 /// # Environment Variables
 ///
 /// Environment variables are transformed by:
 /// 1. Removing the `FELUDA_` prefix
 /// 2. Converting to lowercase
-/// 3. Converting underscores to dots for nested keys
+/// 3. Converting the underscore after a section name to a dot, see [`env_key_path`]
 ///
 /// For example:
 /// - `FELUDA_LICENSES_RESTRICTIVE` -> `licenses.restrictive`
+/// - `FELUDA_NO_DEV` -> `no_dev`
 pub fn load_config() -> FeludaResult<FeludaConfig> {
     log(LogLevel::Info, "Loading Feluda configuration");
+    if let Some(config) = config_scope().and_then(|scope| scope.config) {
+        config.validate()?;
+        return Ok(config);
+    }
+    extract_config(&config_figment())
+}
 
+/// Loads the configuration of whoever runs Feluda rather than of a project: the user-global
+/// config file and `FELUDA_` environment variables over the defaults, without `.feluda.toml`
+pub fn load_user_config() -> FeludaResult<FeludaConfig> {
+    extract_config(&merged_figment(None))
+}
+
+/// The configuration providers [`load_config`] reads, merged in precedence order
+fn config_figment() -> Figment {
+    let config_path = match config_scope() {
+        Some(scope) => scope.project_dir.join(PROJECT_CONFIG_FILE),
+        None => PathBuf::from(PROJECT_CONFIG_FILE),
+    };
+    merged_figment(Some(config_path))
+}
+
+/// Defaults, the user-global file, the project file at `config_path` when given and the
+/// environment, each overriding what came before
+fn merged_figment(config_path: Option<PathBuf>) -> Figment {
     // Start with default values
     let mut figment = Figment::new().merge(Serialized::defaults(FeludaConfig::default()));
 
-    // Check if .feluda.toml exists and add it if it does
-    let config_path = Path::new(".feluda.toml");
-    if config_path.exists() {
+    // The user-global file, then the project's, each overriding what came before
+    if let Some(global_path) = global_config_path().filter(|path| path.is_file()) {
         log(
             LogLevel::Info,
-            &format!("Found configuration file: {}", config_path.display()),
+            &format!("Found global configuration file: {}", global_path.display()),
         );
-        figment = figment.merge(Toml::file(config_path));
-    } else {
-        log(LogLevel::Info, "No .feluda.toml file found, using defaults");
+        figment = figment.merge(Toml::file(global_path));
+    }
+
+    if let Some(config_path) = config_path {
+        if config_path.exists() {
+            log(
+                LogLevel::Info,
+                &format!("Found configuration file: {}", config_path.display()),
+            );
+            figment = figment.merge(Toml::file(config_path));
+        } else {
+            log(LogLevel::Info, "No .feluda.toml file found, using defaults");
+        }
     }
 
     // Add environment variables
-    figment = figment.merge(Env::prefixed("FELUDA_").split("_"));
+    let keys = toml::Table::try_from(FeludaConfig::default()).unwrap_or_default();
+    figment = figment
+        .merge(Env::prefixed("FELUDA_").map(move |key| env_key_path(&keys, key.as_str()).into()));
     log(LogLevel::Info, "Checking for FELUDA_ environment variables");
+    figment
+}
+
+/// The configuration key a `FELUDA_` variable (prefix removed) sets. An underscore only
+/// separates a section when the default configuration has one by that name, so keys that
+/// contain underscores stay reachable: `no_dev` stays `no_dev`, `licenses_restrictive`
+/// becomes `licenses.restrictive`.
+fn env_key_path(table: &toml::Table, key: &str) -> String {
+    let key = key.to_ascii_lowercase();
+    if table.contains_key(&key) {
+        return key;
+    }
+    table
+        .iter()
+        .filter_map(|(name, value)| {
+            let rest = key.strip_prefix(name.as_str())?.strip_prefix('_')?;
+            Some((name, value.as_table()?, rest))
+        })
+        .max_by_key(|(name, _, _)| name.len())
+        .map(|(name, section, rest)| format!("{name}.{}", env_key_path(section, rest)))
+        .unwrap_or(key)
+}
 
-    // Extract the final configuration
+/// Extract and validate the configuration a merged [`Figment`] holds
+fn extract_config(figment: &Figment) -> FeludaResult<FeludaConfig> {
     match figment.extract::<FeludaConfig>() {
         Ok(config) => {
             log(LogLevel::Info, "Configuration loaded successfully");
@@ -442,6 +1156,244 @@ pub fn load_config() -> FeludaResult<FeludaConfig> {
     }
 }
 
+/// Scan flags `feluda config show` layers on top, see [`FeludaConfig::apply_flags`]
+#[derive(Debug, Default)]
+pub struct ConfigFlags {
+    pub strict: bool,
+    pub cargo_lock: Option<CargoLockMode>,
+    pub no_dev: bool,
+    pub direct_only: bool,
+    pub include_transitive: bool,
+    pub skip: Vec<String>,
+    pub cargo: CargoConfig,
+}
+
+impl ConfigFlags {
+    /// Configuration keys a scan flag can set
+    const KEYS: [&'static str; 10] = [
+        "strict",
+        "cargo_lock",
+        "no_dev",
+        "direct_only",
+        "include_transitive",
+        "skip",
+        "cargo.no_dev_deps",
+        "cargo.no_build_deps",
+        "cargo.features",
+        "cargo.no_default_features",
+    ];
+
+    /// The flags that were passed, as they were written
+    fn describe(&self) -> Vec<String> {
+        Self::KEYS
+            .iter()
+            .filter_map(|key| self.flag_for(key))
+            .collect()
+    }
+
+    /// The flag that set configuration `key`, if one was passed
+    fn flag_for(&self, key: &str) -> Option<String> {
+        match key {
+            "strict" if self.strict => Some("--strict".to_string()),
+            "cargo_lock" => match self.cargo_lock {
+                Some(CargoLockMode::Locked) => Some("--locked".to_string()),
+                Some(CargoLockMode::Frozen) => Some("--frozen".to_string()),
+                _ => None,
+            },
+            "no_dev" if self.no_dev => Some("--no-dev".to_string()),
+            "direct_only" if self.direct_only => Some("--direct-only".to_string()),
+            "include_transitive" if self.include_transitive => {
+                Some("--include-transitive".to_string())
+            }
+            "skip" if !self.skip.is_empty() => Some(format!("--skip {}", self.skip.join(","))),
+            "cargo.no_dev_deps" if self.cargo.no_dev_deps => Some("--no-dev-deps".to_string()),
+            "cargo.no_build_deps" if self.cargo.no_build_deps => {
+                Some("--no-build-deps".to_string())
+            }
+            "cargo.features" if !self.cargo.features.is_empty() => {
+                Some(format!("--features {}", self.cargo.features.join(",")))
+            }
+            "cargo.no_default_features" if self.cargo.no_default_features => {
+                Some("--no-default-features".to_string())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// `feluda config show`: list the configuration sources, or with `effective` print the
+/// configuration they merge into, each key annotated with the source that set it
+pub fn handle_config_show_command(effective: bool, flags: ConfigFlags) -> FeludaResult<()> {
+    if effective {
+        let figment = config_figment();
+        let mut config = extract_config(&figment)?;
+        config.apply_flags(
+            flags.strict,
+            flags.cargo_lock,
+            flags.no_dev,
+            flags.direct_only,
+            flags.include_transitive,
+            &flags.skip,
+        );
+        config.apply_cargo_flags(&flags.cargo);
+        print!("{}", effective_config_toml(config, &figment, &flags)?);
+        return Ok(());
+    }
+
+    let found = |path: &Path| {
+        if path.is_file() {
+            "found".green().to_string()
+        } else {
+            "not found".dimmed().to_string()
+        }
+    };
+    let mut env_vars: Vec<String> = std::env::vars()
+        .map(|(name, _)| name)
+        .filter(|name| name.starts_with("FELUDA_"))
+        .collect();
+    env_vars.sort();
+    let none = || "none".dimmed().to_string();
+
+    println!(
+        "{}",
+        "Configuration sources, lowest precedence first".bold()
+    );
+    println!("  1. Built-in defaults");
+    match global_config_path() {
+        Some(path) => println!("  2. Global: {} ({})", path.display(), found(&path)),
+        None => println!("  2. Global: {}", none()),
+    }
+    let project = std::env::current_dir()
+        .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+        .unwrap_or_else(|_| PathBuf::from(PROJECT_CONFIG_FILE));
+    println!("  3. Project: {} ({})", project.display(), found(&project));
+    println!(
+        "  4. Environment: {}",
+        if env_vars.is_empty() {
+            none()
+        } else {
+            env_vars.join(", ")
+        }
+    );
+    let flags = flags.describe();
+    println!(
+        "  5. Flags: {}",
+        if flags.is_empty() {
+            none()
+        } else {
+            flags.join(" ")
+        }
+    );
+    println!();
+    println!(
+        "Run {} for the merged result.",
+        "feluda config show --effective".bold()
+    );
+    Ok(())
+}
+
+/// The merged configuration as TOML, with the GitHub token redacted and a trailing comment on
+/// every key naming the source that set it
+fn effective_config_toml(
+    mut config: FeludaConfig,
+    figment: &Figment,
+    flags: &ConfigFlags,
+) -> FeludaResult<String> {
+    if config.github.token.is_some() {
+        config.github.token = Some("<redacted>".to_string());
+    }
+    for sink in config.outputs.values_mut() {
+        if let OutputSink::Http { headers, .. } = sink {
+            headers
+                .values_mut()
+                .for_each(|value| *value = "<redacted>".to_string());
+        }
+    }
+    let table = toml::Table::try_from(&config)
+        .map_err(|e| FeludaError::Config(format!("Failed to serialize configuration: {e}")))?;
+
+    let source = |key: &str| match flags.flag_for(key) {
+        Some(flag) => format!("flag {flag}"),
+        None => key_source(figment, key),
+    };
+    let mut out = "# Effective configuration; each key notes the source that set it\n".to_string();
+    write_annotated_table(&mut out, &table, &[], &source);
+    Ok(out)
+}
+
+/// Where the value of a merged configuration key came from
+fn key_source(figment: &Figment, key: &str) -> String {
+    let global = global_config_path();
+    // Keys inside a map (e.g. license overrides) resolve to the provider that set the map
+    let mut lookup = key;
+    let metadata = loop {
+        if let Some(metadata) = figment.find_metadata(lookup) {
+            break metadata;
+        }
+        match lookup.rsplit_once('.') {
+            Some((parent, _)) => lookup = parent,
+            None => return "default".to_string(),
+        }
+    };
+
+    match &metadata.source {
+        Some(Source::File(path)) if global.as_ref() == Some(path) => {
+            format!("global {}", path.display())
+        }
+        Some(Source::File(path)) => format!("project {}", path.display()),
+        Some(Source::Code(_)) => "default".to_string(),
+        // Environment variables have no source location
+        _ => format!("env FELUDA_{}", lookup.to_uppercase().replace('.', "_")),
+    }
+}
+
+/// Write a table's plain keys under its `[header]`, then each of its sub-tables
+fn write_annotated_table(
+    out: &mut String,
+    table: &toml::Table,
+    path: &[&str],
+    source: &dyn Fn(&str) -> String,
+) {
+    let leaves: Vec<(&String, &toml::Value)> = table
+        .iter()
+        .filter(|(_, value)| !value.is_table())
+        .collect();
+    if !leaves.is_empty() {
+        if !path.is_empty() {
+            let header: Vec<String> = path.iter().map(|key| toml_key(key)).collect();
+            out.push_str(&format!("\n[{}]\n", header.join(".")));
+        }
+        for (key, value) in leaves {
+            let dotted: Vec<&str> = path.iter().copied().chain([key.as_str()]).collect();
+            out.push_str(&format!(
+                "{} = {value}  # {}\n",
+                toml_key(key),
+                source(&dotted.join("."))
+            ));
+        }
+    }
+
+    for (key, value) in table {
+        if let toml::Value::Table(child) = value {
+            let child_path: Vec<&str> = path.iter().copied().chain([key.as_str()]).collect();
+            write_annotated_table(out, child, &child_path, source);
+        }
+    }
+}
+
+/// A key as TOML writes it: bare when it can be, quoted otherwise
+fn toml_key(key: &str) -> String {
+    if !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        key.to_string()
+    } else {
+        toml::Value::String(key.to_string()).to_string()
+    }
+}
+
 // Remove the unused function
 // Keep it in the tests but commented out for reference
 // pub fn has_env_var(var_name: &str) -> bool {
@@ -472,6 +1424,55 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_scoped_config() {
+        temp_env::with_var("FELUDA_LICENSES_RESTRICTIVE", None::<&str>, || {
+            let _cwd = setup();
+            let project = TempDir::new().unwrap();
+            fs::write(
+                project.path().join(PROJECT_CONFIG_FILE),
+                "[licenses]\nrestrictive = [\"TEST-1.0\"]\n",
+            )
+            .unwrap();
+
+            {
+                // The scanned project's file, not the working directory's
+                let _scope = scope_config(project.path(), None);
+                assert_eq!(
+                    load_config().unwrap().licenses.restrictive,
+                    vec!["TEST-1.0"]
+                );
+            }
+            assert_eq!(load_config().unwrap().licenses.restrictive.len(), 7);
+
+            let explicit = FeludaConfig {
+                strict: true,
+                ..Default::default()
+            };
+            let _scope = scope_config(project.path(), Some(explicit));
+            let config = load_config().unwrap();
+            assert!(config.strict);
+            assert_eq!(config.licenses.restrictive.len(), 7);
+        });
+    }
+
+    #[test]
+    fn test_user_config_skips_project_file() {
+        temp_env::with_var("FELUDA_GITHUB_API", None::<&str>, || {
+            let _cwd = setup();
+            let project = TempDir::new().unwrap();
+            fs::write(
+                project.path().join(PROJECT_CONFIG_FILE),
+                "[github]\napi = \"https://ghe.invalid/api/v3\"\n",
+            )
+            .unwrap();
+
+            let _scope = scope_config(project.path(), None);
+            assert!(load_config().unwrap().github.api.is_some());
+            assert_eq!(load_user_config().unwrap().github.api, None);
+        });
+    }
+
     #[test]
     fn test_toml_config() {
         temp_env::with_var("FELUDA_LICENSES_RESTRICTIVE", None::<&str>, || {
@@ -712,15 +1713,15 @@ restrictive = ["TOML-LICENSE-1", "TOML-LICENSE-2"]"#,
     #[test]
     fn test_config_serialization() {
         let config = FeludaConfig {
-            strict: false,
             licenses: LicenseConfig {
                 restrictive: vec!["TEST-1.0".to_string(), "TEST-2.0".to_string()],
-                ignore: Vec::new(),
+                ..Default::default()
             },
             dependencies: DependencyConfig {
                 max_depth: 5,
                 ignore: Vec::new(),
             },
+            ..Default::default()
         };
 
         // Test that config can be serialized and deserialized
@@ -750,7 +1751,7 @@ restrictive = ["TOML-LICENSE-1", "TOML-LICENSE-2"]"#,
     fn test_license_config_serde() {
         let config = LicenseConfig {
             restrictive: vec!["MIT".to_string(), "Apache-2.0".to_string()],
-            ignore: Vec::new(),
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -847,7 +1848,7 @@ restrictive = [
     fn test_license_config_validation_empty_list() {
         let config = LicenseConfig {
             restrictive: vec![],
-            ignore: Vec::new(),
+            ..Default::default()
         };
         // Empty list should pass validation but generate a warning
         assert!(config.validate().is_ok());
@@ -857,7 +1858,7 @@ restrictive = [
     fn test_license_config_validation_empty_license() {
         let config = LicenseConfig {
             restrictive: vec!["MIT".to_string(), "".to_string(), "GPL-3.0".to_string()],
-            ignore: Vec::new(),
+            ..Default::default()
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -876,7 +1877,7 @@ restrictive = [
                 "MIT".to_string(),
                 "Apache-2.0".to_string(),
             ],
-            ignore: Vec::new(),
+            ..Default::default()
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -894,7 +1895,7 @@ restrictive = [
                 "GPL-3.0".to_string(),
                 "SEE LICENSE IN LICENSE".to_string(),
             ],
-            ignore: Vec::new(),
+            ..Default::default()
         };
         assert!(config.validate().is_ok());
     }
@@ -966,15 +1967,15 @@ restrictive = [
     #[test]
     fn test_feluda_config_validation_success() {
         let config = FeludaConfig {
-            strict: false,
             licenses: LicenseConfig {
                 restrictive: vec!["MIT".to_string(), "GPL-3.0".to_string()],
-                ignore: Vec::new(),
+                ..Default::default()
             },
             dependencies: DependencyConfig {
                 max_depth: 10,
                 ignore: Vec::new(),
             },
+            ..Default::default()
         };
         assert!(config.validate().is_ok());
     }
@@ -982,15 +1983,17 @@ restrictive = [
     #[test]
     fn test_feluda_config_validation_license_failure() {
         let config = FeludaConfig {
-            strict: false,
             licenses: LicenseConfig {
-                restrictive: vec!["".to_string()], // Invalid empty license
+                restrictive: vec!["".to_string()],
+                // Invalid empty license
                 ignore: Vec::new(),
+                ..Default::default()
             },
             dependencies: DependencyConfig {
                 max_depth: 10,
                 ignore: Vec::new(),
             },
+            ..Default::default()
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -1003,15 +2006,16 @@ restrictive = [
     #[test]
     fn test_feluda_config_validation_dependency_failure() {
         let config = FeludaConfig {
-            strict: false,
             licenses: LicenseConfig {
                 restrictive: vec!["MIT".to_string()],
-                ignore: Vec::new(),
+                ..Default::default()
             },
             dependencies: DependencyConfig {
                 max_depth: 0,
                 ignore: Vec::new(),
-            }, // Invalid zero depth
+            },
+            // Invalid zero depth,
+            ..Default::default()
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -1089,6 +2093,27 @@ max_depth = 5"#,
         );
     }
 
+    #[test]
+    fn test_toml_config_with_skip() {
+        temp_env::with_var("FELUDA_LICENSES_RESTRICTIVE", None::<&str>, || {
+            let dir = setup();
+            std::env::set_current_dir(dir.path()).unwrap();
+
+            fs::write(
+                ".feluda.toml",
+                r#"skip = ["python", "ruby"]
+
+[licenses]
+restrictive = ["GPL-3.0"]"#,
+            )
+            .unwrap();
+
+            let config = load_config().unwrap();
+            assert_eq!(config.skip, vec!["python", "ruby"]);
+            assert_eq!(config.licenses.restrictive, vec!["GPL-3.0"]);
+        });
+    }
+
     // Tests for ignore licenses functionality
     #[test]
     fn test_toml_config_with_ignore() {
@@ -1170,6 +2195,7 @@ ignore = []"#,
         let config = LicenseConfig {
             restrictive: vec!["GPL-3.0".to_string()],
             ignore: vec!["MIT".to_string(), "".to_string(), "Apache-2.0".to_string()],
+            ..Default::default()
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -1188,6 +2214,7 @@ ignore = []"#,
                 "Apache-2.0".to_string(),
                 "MIT".to_string(),
             ],
+            ..Default::default()
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -1201,6 +2228,7 @@ ignore = []"#,
         let config = LicenseConfig {
             restrictive: vec!["GPL-3.0".to_string(), "MIT".to_string()],
             ignore: vec!["MIT".to_string(), "Apache-2.0".to_string()],
+            ..Default::default()
         };
         // Should pass validation but generate a warning
         assert!(config.validate().is_ok());
@@ -1211,6 +2239,7 @@ ignore = []"#,
         let config = LicenseConfig {
             restrictive: vec!["GPL-3.0".to_string(), "AGPL-3.0".to_string()],
             ignore: vec!["MIT".to_string(), "Apache-2.0".to_string()],
+            ..Default::default()
         };
         assert!(config.validate().is_ok());
         assert_eq!(config.restrictive.len(), 2);
@@ -1256,6 +2285,7 @@ ignore = [
         let config = LicenseConfig {
             restrictive: vec!["GPL-3.0".to_string()],
             ignore: vec!["MIT".to_string(), "Apache-2.0".to_string()],
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -1278,9 +2308,9 @@ ignore = [
                 reason: "Test reason".to_string(),
             }],
         };
-        assert!(config.should_ignore_dependency("lodash", Some("4.17.21")));
-        assert!(!config.should_ignore_dependency("lodash", Some("4.17.20")));
-        assert!(!config.should_ignore_dependency("underscore", Some("4.17.21")));
+        assert!(config.ignore_rule("lodash", Some("4.17.21")).is_some());
+        assert!(config.ignore_rule("lodash", Some("4.17.20")).is_none());
+        assert!(config.ignore_rule("underscore", Some("4.17.21")).is_none());
     }
 
     #[test]
@@ -1293,10 +2323,10 @@ ignore = [
                 reason: "Ignore all versions".to_string(),
             }],
         };
-        assert!(config.should_ignore_dependency("lodash", Some("4.17.21")));
-        assert!(config.should_ignore_dependency("lodash", Some("4.17.20")));
-        assert!(config.should_ignore_dependency("lodash", None));
-        assert!(!config.should_ignore_dependency("underscore", Some("1.0.0")));
+        assert!(config.ignore_rule("lodash", Some("4.17.21")).is_some());
+        assert!(config.ignore_rule("lodash", Some("4.17.20")).is_some());
+        assert!(config.ignore_rule("lodash", None).is_some());
+        assert!(config.ignore_rule("underscore", Some("1.0.0")).is_none());
     }
 
     #[test]
@@ -1316,10 +2346,10 @@ ignore = [
                 },
             ],
         };
-        assert!(config.should_ignore_dependency("lodash", Some("4.17.21")));
-        assert!(!config.should_ignore_dependency("lodash", Some("4.17.20")));
-        assert!(config.should_ignore_dependency("underscore", Some("1.0.0")));
-        assert!(config.should_ignore_dependency("underscore", None));
+        assert!(config.ignore_rule("lodash", Some("4.17.21")).is_some());
+        assert!(config.ignore_rule("lodash", Some("4.17.20")).is_none());
+        assert!(config.ignore_rule("underscore", Some("1.0.0")).is_some());
+        assert!(config.ignore_rule("underscore", None).is_some());
     }
 
     #[test]
@@ -1416,23 +2446,25 @@ reason = "All versions ignored"
             assert_eq!(config.dependencies.ignore.len(), 2);
             assert!(config
                 .dependencies
-                .should_ignore_dependency("lodash", Some("4.17.21")));
-            assert!(!config
+                .ignore_rule("lodash", Some("4.17.21"))
+                .is_some());
+            assert!(config
                 .dependencies
-                .should_ignore_dependency("lodash", Some("4.17.20")));
+                .ignore_rule("lodash", Some("4.17.20"))
+                .is_none());
             assert!(config
                 .dependencies
-                .should_ignore_dependency("underscore", Some("1.0.0")));
+                .ignore_rule("underscore", Some("1.0.0"))
+                .is_some());
         });
     }
 
     #[test]
     fn test_feluda_config_with_dependency_ignore() {
         let config = FeludaConfig {
-            strict: false,
             licenses: LicenseConfig {
                 restrictive: vec!["GPL-3.0".to_string()],
-                ignore: Vec::new(),
+                ..Default::default()
             },
             dependencies: DependencyConfig {
                 max_depth: 10,
@@ -1442,11 +2474,13 @@ reason = "All versions ignored"
                     reason: "Test".to_string(),
                 }],
             },
+            ..Default::default()
         };
         assert!(config.validate().is_ok());
         assert!(config
             .dependencies
-            .should_ignore_dependency("lodash", Some("4.17.21")));
+            .ignore_rule("lodash", Some("4.17.21"))
+            .is_some());
     }
 
     #[test]
@@ -1493,9 +2527,438 @@ reason = "All versions ignored"
             ],
         };
 
-        assert!(config.should_ignore_dependency("package1", Some("any-version")));
-        assert!(config.should_ignore_dependency("package1", None));
-        assert!(config.should_ignore_dependency("package2", Some("1.0.0")));
-        assert!(!config.should_ignore_dependency("package2", Some("2.0.0")));
+        assert!(config
+            .ignore_rule("package1", Some("any-version"))
+            .is_some());
+        assert!(config.ignore_rule("package1", None).is_some());
+        assert!(config.ignore_rule("package2", Some("1.0.0")).is_some());
+        assert!(config.ignore_rule("package2", Some("2.0.0")).is_none());
+    }
+
+    #[test]
+    fn test_load_config_resolver_order() {
+        temp_env::with_var("FELUDA_LICENSES_RESTRICTIVE", None::<&str>, || {
+            let dir = tempfile::tempdir().unwrap();
+            std::env::set_current_dir(dir.path()).unwrap();
+
+            let config = load_config().unwrap();
+            assert_eq!(config.resolvers.order, DEFAULT_ORDER.to_vec());
+
+            fs::write(
+                ".feluda.toml",
+                r#"[resolvers]
+order = ["registry", "local"]
+"#,
+            )
+            .unwrap();
+            let config = load_config().unwrap();
+            assert_eq!(
+                config.resolvers.order,
+                vec![ResolverSource::Registry, ResolverSource::Local]
+            );
+        });
+    }
+
+    #[test]
+    fn test_resolver_config_rejects_duplicates() {
+        let config = ResolverConfig {
+            order: vec![ResolverSource::Cache, ResolverSource::Cache],
+            jobs: DEFAULT_JOBS,
+        };
+        assert!(config.validate().is_err());
+
+        let config = ResolverConfig {
+            order: Vec::new(),
+            jobs: DEFAULT_JOBS,
+        };
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_resolver_config_jobs() {
+        let config: FeludaConfig = toml::from_str(
+            "[resolvers]
+jobs = 4
+",
+        )
+        .unwrap();
+        assert_eq!(config.resolvers.jobs, 4);
+        assert_eq!(config.resolvers.order, DEFAULT_ORDER.to_vec());
+        assert_eq!(FeludaConfig::default().resolvers.jobs, DEFAULT_JOBS);
+
+        let config: FeludaConfig = toml::from_str(
+            "[resolvers]
+jobs = 0
+",
+        )
+        .unwrap();
+        assert!(config.resolvers.validate().is_err());
+    }
+
+    #[test]
+    fn test_github_config() {
+        let config: FeludaConfig =
+            toml::from_str("[github]\napi = \"https://github.example.com/api/v3\"\n").unwrap();
+        assert_eq!(
+            config.github.api.as_deref(),
+            Some("https://github.example.com/api/v3")
+        );
+        assert!(config.github.token.is_none());
+        assert!(config.validate().is_ok());
+
+        let config = GithubConfig {
+            api: Some("github.example.com".to_string()),
+            token: None,
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_project_licenses() {
+        let config: FeludaConfig = toml::from_str(
+            "[project_licenses]\n\"./services/api/\" = \"AGPL-3.0\"\n\"packages/sdk\" = \"Apache-2.0\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config
+                .project_licenses
+                .get("./services/api/")
+                .map(String::as_str),
+            Some("AGPL-3.0")
+        );
+        assert!(config.validate().is_ok());
+        assert_eq!(normalize_project_dir("./services/api/"), "services/api");
+        assert_eq!(normalize_project_dir("services\\api"), "services/api");
+
+        let config: FeludaConfig = toml::from_str("[project_licenses]\n\".\" = \"MIT\"\n").unwrap();
+        assert!(config.validate().is_err());
+        let config: FeludaConfig =
+            toml::from_str("[project_licenses]\n\"services/api\" = \" \"\n").unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_license_rule_validation() {
+        let rule = |pattern: Option<&str>, keywords: &[&str]| LicenseRule {
+            license: "LicenseRef-Acme".to_string(),
+            pattern: pattern.map(str::to_string),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+        };
+
+        assert!(rule(Some("(?i)acme proprietary"), &[]).validate().is_ok());
+        assert!(rule(None, &["ACME", "internal"]).validate().is_ok());
+        assert!(rule(None, &[]).validate().is_err());
+        assert!(rule(Some("acme"), &["acme"]).validate().is_err());
+        assert!(rule(Some("(unclosed"), &[]).validate().is_err());
+        assert!(rule(None, &[" "]).validate().is_err());
+    }
+
+    #[test]
+    fn test_license_refs() {
+        let config: FeludaConfig = toml::from_str(
+            r#"
+[licenses.refs]
+"LicenseRef-Acme-Proprietary" = "permissive"
+"LicenseRef-Commercial-*" = "restrictive"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.licenses.refs.get("LicenseRef-Commercial-*"),
+            Some(&LicenseRefClass::Restrictive)
+        );
+        assert!(config.validate().is_ok());
+
+        let mut config = FeludaConfig::default();
+        config
+            .licenses
+            .refs
+            .insert("Acme-Proprietary".to_string(), LicenseRefClass::Permissive);
+        assert!(config.validate().is_err());
+
+        assert!(
+            toml::from_str::<FeludaConfig>("[licenses.refs]\n\"LicenseRef-X\" = \"maybe\"\n")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_license_policy_lists() {
+        let config: FeludaConfig = toml::from_str(
+            r#"
+[licenses.allow]
+licenses = ["MIT", "BSD-*"]
+packages = ["readline@8.2"]
+
+[licenses.deny]
+licenses = ["AGPL-*"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.licenses.allow.licenses, vec!["MIT", "BSD-*"]);
+        assert_eq!(config.licenses.allow.packages, vec!["readline@8.2"]);
+        assert!(config.licenses.deny.packages.is_empty());
+        assert!(config.validate().is_ok());
+
+        let mut contradictory = config.clone();
+        contradictory.licenses.deny.licenses.push("MIT".to_string());
+        assert!(contradictory.validate().is_err());
+
+        let mut empty = config;
+        empty.licenses.deny.packages.push(" ".to_string());
+        assert!(empty.validate().is_err());
+    }
+
+    #[test]
+    fn test_special_license_policies() {
+        let config = FeludaConfig::default();
+        assert_eq!(config.licenses.noassertion, SpecialLicensePolicy::Unknown);
+        assert_eq!(config.licenses.none, SpecialLicensePolicy::Restrictive);
+
+        let config: FeludaConfig =
+            toml::from_str("[licenses]\nnoassertion = \"restrictive\"\nnone = \"unknown\"\n")
+                .unwrap();
+        assert_eq!(
+            config.licenses.noassertion,
+            SpecialLicensePolicy::Restrictive
+        );
+        assert_eq!(config.licenses.none, SpecialLicensePolicy::Unknown);
+    }
+
+    #[test]
+    fn test_internal_namespaces() {
+        let config = InternalConfig {
+            namespaces: vec![
+                "@myorg/*".to_string(),
+                "github.com/myorg/*".to_string(),
+                "myorg-*".to_string(),
+                "exact-name".to_string(),
+                "*-internal-*".to_string(),
+            ],
+            ..Default::default()
+        };
+
+        assert!(config.is_internal("@myorg/ui"));
+        assert!(config.is_internal("github.com/myorg/service/v2"));
+        assert!(config.is_internal("myorg-utils"));
+        assert!(config.is_internal("exact-name"));
+        assert!(config.is_internal("acme-internal-tools"));
+        assert!(!config.is_internal("@other/ui"));
+        assert!(!config.is_internal("exact-name-2"));
+        assert!(!config.is_internal("github.com/myorganisation/x"));
+        assert_eq!(config.license, "LicenseRef-Internal");
+
+        assert!(InternalConfig::default().validate().is_ok());
+        let empty_pattern = InternalConfig {
+            namespaces: vec![" ".to_string()],
+            ..Default::default()
+        };
+        assert!(empty_pattern.validate().is_err());
+    }
+
+    #[test]
+    fn test_load_config_global_then_project() {
+        let global = tempfile::tempdir().unwrap();
+        fs::create_dir_all(global.path().join("feluda")).unwrap();
+        fs::write(
+            global.path().join("feluda").join("config.toml"),
+            r#"skip = ["python"]
+
+[licenses]
+restrictive = ["GLOBAL-LICENSE"]
+"#,
+        )
+        .unwrap();
+
+        temp_env::with_vars(
+            [
+                ("XDG_CONFIG_HOME", Some(global.path().to_str().unwrap())),
+                ("FELUDA_LICENSES_RESTRICTIVE", None),
+            ],
+            || {
+                let dir = tempfile::tempdir().unwrap();
+                std::env::set_current_dir(dir.path()).unwrap();
+
+                let config = load_config().unwrap();
+                assert_eq!(config.licenses.restrictive, vec!["GLOBAL-LICENSE"]);
+                assert_eq!(config.skip, vec!["python"]);
+
+                // The project file wins over the global one, key by key
+                fs::write(
+                    PROJECT_CONFIG_FILE,
+                    r#"[licenses]
+restrictive = ["PROJECT-LICENSE"]
+"#,
+                )
+                .unwrap();
+                let config = load_config().unwrap();
+                assert_eq!(config.licenses.restrictive, vec!["PROJECT-LICENSE"]);
+                assert_eq!(config.skip, vec!["python"]);
+            },
+        );
+    }
+
+    #[test]
+    fn test_global_config_path_ignores_relative_xdg() {
+        temp_env::with_var("XDG_CONFIG_HOME", Some("relative/dir"), || {
+            let path = global_config_path().unwrap();
+            assert!(path.is_absolute() || dirs::home_dir().is_none());
+            assert!(path.ends_with("feluda/config.toml"));
+        });
+    }
+
+    #[test]
+    fn test_apply_flags() {
+        let mut config = FeludaConfig {
+            skip: vec!["python".to_string()],
+            ..Default::default()
+        };
+        config.apply_flags(
+            true,
+            Some(CargoLockMode::Frozen),
+            false,
+            true,
+            false,
+            &["python".to_string(), "rust".to_string()],
+        );
+        assert!(config.strict);
+        assert_eq!(config.cargo_lock, CargoLockMode::Frozen);
+        assert!(!config.no_dev);
+        assert!(config.direct_only);
+        assert_eq!(config.skip, vec!["python", "rust"]);
+    }
+
+    #[test]
+    fn test_apply_cargo_flags() {
+        let mut config: FeludaConfig = toml::from_str(
+            r#"
+[cargo]
+no_build_deps = true
+features = ["tls"]
+"#,
+        )
+        .unwrap();
+        config.apply_cargo_flags(&CargoConfig {
+            no_dev_deps: true,
+            features: vec!["tls".to_string(), "serde".to_string()],
+            ..Default::default()
+        });
+        assert!(config.cargo.no_dev_deps);
+        assert!(config.cargo.no_build_deps);
+        assert!(!config.cargo.no_default_features);
+        assert_eq!(config.cargo.features, vec!["tls", "serde"]);
+
+        let flags = ConfigFlags {
+            cargo: config.cargo.clone(),
+            ..Default::default()
+        };
+        assert_eq!(
+            flags.describe(),
+            vec!["--no-dev-deps", "--no-build-deps", "--features tls,serde"]
+        );
+    }
+
+    #[test]
+    fn test_effective_config_toml_redacts_token() {
+        let figment = Figment::new().merge(Serialized::defaults(FeludaConfig::default()));
+        let mut config = FeludaConfig::default();
+        config.github.token = Some("ghp_secret".to_string());
+        let toml = effective_config_toml(config, &figment, &ConfigFlags::default()).unwrap();
+        assert!(!toml.contains("ghp_secret"));
+        assert!(toml.contains("<redacted>"));
+        let parsed: FeludaConfig = toml::from_str(&toml).unwrap();
+        assert_eq!(parsed.resolvers.order, DEFAULT_ORDER.to_vec());
+    }
+
+    #[test]
+    fn test_effective_config_toml_annotates_sources() {
+        let global = tempfile::tempdir().unwrap();
+        temp_env::with_vars(
+            [
+                ("XDG_CONFIG_HOME", Some(global.path().to_str().unwrap())),
+                ("FELUDA_LICENSES_RESTRICTIVE", None),
+                ("FELUDA_STRICT", Some("true")),
+            ],
+            || {
+                let dir = setup();
+                fs::write(
+                    PROJECT_CONFIG_FILE,
+                    r#"no_dev = true
+
+[licenses]
+restrictive = ["PROJECT-LICENSE"]
+"#,
+                )
+                .unwrap();
+
+                let figment = config_figment();
+                let mut config = extract_config(&figment).unwrap();
+                let flags = ConfigFlags {
+                    skip: vec!["python".to_string()],
+                    ..Default::default()
+                };
+                config.apply_flags(
+                    flags.strict,
+                    flags.cargo_lock,
+                    flags.no_dev,
+                    flags.direct_only,
+                    flags.include_transitive,
+                    &flags.skip,
+                );
+                let toml = effective_config_toml(config, &figment, &flags).unwrap();
+
+                let line = |key: &str| {
+                    toml.lines()
+                        .find(|line| line.starts_with(&format!("{key} = ")))
+                        .unwrap()
+                        .to_string()
+                };
+                assert!(line("strict").ends_with("# env FELUDA_STRICT"));
+                assert!(line("no_dev").contains("# project "));
+                assert!(line("no_dev").ends_with(PROJECT_CONFIG_FILE));
+                assert!(line("restrictive").contains("\"PROJECT-LICENSE\"]  # project "));
+                assert!(line("skip").ends_with("# flag --skip python"));
+                assert!(line("max_depth").ends_with("# default"));
+                assert!(toml.contains("\n[licenses]\n"));
+
+                // The comments keep it loadable as a config file
+                let parsed: FeludaConfig = toml::from_str(&toml).unwrap();
+                assert_eq!(parsed.skip, vec!["python"]);
+                drop(dir);
+            },
+        );
+    }
+
+    #[test]
+    fn test_env_sets_keys_with_underscores() {
+        let global = tempfile::tempdir().unwrap();
+        temp_env::with_vars(
+            [
+                ("XDG_CONFIG_HOME", Some(global.path().to_str().unwrap())),
+                ("FELUDA_LICENSES_RESTRICTIVE", None),
+                ("FELUDA_NO_DEV", Some("true")),
+                ("FELUDA_CARGO_LOCK", Some("frozen")),
+                ("FELUDA_DEPENDENCIES_MAX_DEPTH", Some("3")),
+            ],
+            || {
+                let dir = setup();
+                let figment = config_figment();
+                let config = extract_config(&figment).unwrap();
+                assert!(config.no_dev);
+                assert_eq!(config.cargo_lock, CargoLockMode::Frozen);
+                assert_eq!(config.dependencies.max_depth, 3);
+
+                let toml =
+                    effective_config_toml(config, &figment, &ConfigFlags::default()).unwrap();
+                assert!(toml.contains("no_dev = true  # env FELUDA_NO_DEV"));
+                drop(dir);
+            },
+        );
+    }
+
+    #[test]
+    fn test_toml_key() {
+        assert_eq!(toml_key("max_depth"), "max_depth");
+        assert_eq!(toml_key("GPL-3.0"), "\"GPL-3.0\"");
     }
 }
@@ -0,0 +1,190 @@
+//! Project-level license conflicts
+//!
+//! Per-dependency checks compare each license with the project license. Strong copyleft
+//! licenses (GPL, AGPL) extend to the whole combined work, though, so two dependencies can
+//! clash with each other even when each one is individually fine: a GPL-2.0-only library
+//! next to an Apache-2.0 one cannot be distributed together under any license. This module
+//! checks every pair of licenses present against the compatibility matrix row of the copyleft
+//! license that would have to govern the combination.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::licenses::{
+    is_known_to_matrix, is_license_compatible, normalize_license_id, LicenseCompatibility,
+    LicenseInfo,
+};
+use crate::spdx;
+
+/// Copyleft licenses whose terms cover the combined work (compatibility matrix rows)
+const GOVERNING_LICENSES: &[&str] = &["GPL-2.0", "GPL-3.0", "AGPL-3.0"];
+
+/// Two licenses in the dependency tree that cannot be combined: the combination would have to
+/// be distributed under `copyleft`, and `conflicting` doesn't allow that
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct LicenseConflict {
+    pub copyleft: String,
+    pub copyleft_packages: Vec<String>,
+    pub conflicting: String,
+    pub conflicting_packages: Vec<String>,
+}
+
+impl std::fmt::Display for LicenseConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({}) cannot be combined with {} ({})",
+            self.copyleft,
+            self.copyleft_packages.join(", "),
+            self.conflicting,
+            self.conflicting_packages.join(", ")
+        )
+    }
+}
+
+/// Find pairs of dependency licenses that conflict with each other, regardless of the project
+/// license. Internal packages and licenses the matrix doesn't know are left out.
+pub fn find_license_conflicts(deps: &[LicenseInfo]) -> Vec<LicenseConflict> {
+    let mut by_license: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for dep in deps.iter().filter(|dep| !dep.is_internal()) {
+        if let Some(license) = dep.license.as_deref() {
+            by_license
+                .entry(license)
+                .or_default()
+                .push(format!("{}@{}", dep.name, dep.version));
+        }
+    }
+
+    let mut conflicts = Vec::new();
+    for (&copyleft, copyleft_packages) in &by_license {
+        if governing_row(copyleft).is_none() {
+            continue;
+        }
+        for (&other, other_packages) in &by_license {
+            if other == copyleft || !is_known_to_matrix(other) || can_govern(copyleft, other) {
+                continue;
+            }
+            // Two copyleft licenses are fine when either can govern the combination, and a
+            // clash between them is reported once
+            if governing_row(other).is_some()
+                && (can_govern(other, copyleft)
+                    || conflicts.iter().any(|c: &LicenseConflict| {
+                        c.copyleft == other && c.conflicting == copyleft
+                    }))
+            {
+                continue;
+            }
+            conflicts.push(LicenseConflict {
+                copyleft: copyleft.to_string(),
+                copyleft_packages: copyleft_packages.clone(),
+                conflicting: other.to_string(),
+                conflicting_packages: other_packages.clone(),
+            });
+        }
+    }
+    conflicts
+}
+
+/// The matrix row of a governing copyleft license. "Or later" versions of GPL-2.0 can be
+/// distributed under GPL-3.0, so they use its row.
+fn governing_row(license: &str) -> Option<&'static str> {
+    if spdx::is_compound(license) {
+        return None;
+    }
+    let normalized = normalize_license_id(license);
+    let row = *GOVERNING_LICENSES.iter().find(|row| **row == normalized)?;
+    if row == "GPL-2.0" && (license.ends_with("-or-later") || license.ends_with('+')) {
+        return Some("GPL-3.0");
+    }
+    Some(row)
+}
+
+/// Whether a work under `governing` may include code under `license`
+fn can_govern(governing: &str, license: &str) -> bool {
+    let Some(row) = governing_row(governing) else {
+        return false;
+    };
+    // `-only` copyleft can't move to another version (GPL-2.0-only code can't go under any
+    // 3.0 license), while GPL-3.0 and AGPL-3.0 explicitly allow combining with each other
+    if governing_row(license).is_some()
+        && license.ends_with("-only")
+        && license_version(&normalize_license_id(license)) != license_version(row)
+    {
+        return false;
+    }
+    is_license_compatible(license, row, false) != LicenseCompatibility::Incompatible
+}
+
+/// `3.0` in `AGPL-3.0`
+fn license_version(id: &str) -> &str {
+    id.rsplit('-').next().unwrap_or(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gpl2_only_conflicts_with_apache() {
+        let conflicts = find_license_conflicts(&[
+            LicenseInfo::fixture("readline", "1.0.0", "GPL-2.0-only"),
+            LicenseInfo::fixture("tokio", "1.0.0", "Apache-2.0"),
+            LicenseInfo::fixture("serde", "1.0.0", "MIT"),
+        ]);
+        assert_eq!(
+            conflicts,
+            vec![LicenseConflict {
+                copyleft: "GPL-2.0-only".to_string(),
+                copyleft_packages: vec!["readline@1.0.0".to_string()],
+                conflicting: "Apache-2.0".to_string(),
+                conflicting_packages: vec!["tokio@1.0.0".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_or_later_and_permissive_combinations_are_consistent() {
+        assert!(find_license_conflicts(&[
+            LicenseInfo::fixture("readline", "1.0.0", "GPL-2.0-or-later"),
+            LicenseInfo::fixture("tokio", "1.0.0", "Apache-2.0"),
+        ])
+        .is_empty());
+        assert!(find_license_conflicts(&[
+            LicenseInfo::fixture("tokio", "1.0.0", "Apache-2.0"),
+            LicenseInfo::fixture("serde", "1.0.0", "MIT"),
+            LicenseInfo::fixture("mystery", "1.0.0", "Unknown"),
+        ])
+        .is_empty());
+    }
+
+    #[test]
+    fn test_copyleft_pairs() {
+        // AGPL-3.0 can govern GPL-3.0 code
+        assert!(find_license_conflicts(&[
+            LicenseInfo::fixture("a", "1.0.0", "GPL-3.0-only"),
+            LicenseInfo::fixture("b", "1.0.0", "AGPL-3.0-only")
+        ])
+        .is_empty());
+
+        // GPL-2.0-only and GPL-3.0-only can't be combined; reported once
+        let conflicts = find_license_conflicts(&[
+            LicenseInfo::fixture("a", "1.0.0", "GPL-2.0-only"),
+            LicenseInfo::fixture("b", "1.0.0", "GPL-3.0-only"),
+        ]);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].copyleft, "GPL-2.0-only");
+        assert_eq!(conflicts[0].conflicting, "GPL-3.0-only");
+    }
+
+    #[test]
+    fn test_internal_packages_are_ignored() {
+        let mut internal = LicenseInfo::fixture("acme-core", "1.0.0", "GPL-2.0-only");
+        internal.internal = true;
+        assert!(find_license_conflicts(&[
+            internal,
+            LicenseInfo::fixture("tokio", "1.0.0", "Apache-2.0")
+        ])
+        .is_empty());
+    }
+}
@@ -0,0 +1,230 @@
+//! Public-domain dedications and data/content licenses
+//!
+//! Datasets and model weights come under licenses written for content rather than code:
+//! Creative Commons, Open Data Commons, the Community Data License Agreement and the open
+//! government licences. Their terms (credit the source, share adaptations alike, no
+//! commercial use, no derivatives) don't map onto the software compatibility matrix, so
+//! they get their own classification. Public-domain dedications and attribution-only
+//! licenses are compatible with any project license; non-commercial and no-derivatives ones
+//! are restrictive, and share-alike ones are restrictive in strict mode like other
+//! share-alike licenses. `[licenses.allow]` and `[licenses.deny]` still override both.
+
+use serde::{Deserialize, Serialize};
+
+use crate::spdx::{self, SpdxExpression};
+
+/// How a data or content license treats the projects using it, least demanding first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DataLicenseClass {
+    /// No conditions at all (CC0, PDDL, public domain)
+    PublicDomain,
+    /// Credit the source (CC-BY, ODC-By, CDLA-Permissive, OGL)
+    Attribution,
+    /// Adaptations must be shared under the same license (CC-BY-SA, ODbL, CDLA-Sharing)
+    ShareAlike,
+    /// Adaptations may not be shared (CC-BY-ND)
+    NoDerivatives,
+    /// No commercial use (CC-BY-NC)
+    NonCommercial,
+}
+
+impl DataLicenseClass {
+    /// Whether Feluda flags the class as restrictive; share-alike only in strict mode
+    pub fn is_restrictive(self, strict: bool) -> bool {
+        match self {
+            Self::PublicDomain | Self::Attribution => false,
+            Self::ShareAlike => strict,
+            Self::NoDerivatives | Self::NonCommercial => true,
+        }
+    }
+
+    /// Whether content under the class can go with any project license: it keeps its own
+    /// license next to the code, and only asks for credit at most
+    pub fn is_compatible_with_any(self) -> bool {
+        self <= Self::Attribution
+    }
+}
+
+impl std::fmt::Display for DataLicenseClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PublicDomain => write!(f, "public domain"),
+            Self::Attribution => write!(f, "attribution"),
+            Self::ShareAlike => write!(f, "share-alike"),
+            Self::NoDerivatives => write!(f, "no derivatives"),
+            Self::NonCommercial => write!(f, "non-commercial"),
+        }
+    }
+}
+
+/// The terms of one data or content license
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DataLicense {
+    pub attribution: bool,
+    pub share_alike: bool,
+    pub no_derivatives: bool,
+    pub non_commercial: bool,
+}
+
+impl DataLicense {
+    const PUBLIC_DOMAIN: Self = Self {
+        attribution: false,
+        share_alike: false,
+        no_derivatives: false,
+        non_commercial: false,
+    };
+    const ATTRIBUTION: Self = Self {
+        attribution: true,
+        ..Self::PUBLIC_DOMAIN
+    };
+    const SHARE_ALIKE: Self = Self {
+        share_alike: true,
+        ..Self::ATTRIBUTION
+    };
+
+    /// The most demanding of the license's terms
+    pub fn class(self) -> DataLicenseClass {
+        if self.non_commercial {
+            DataLicenseClass::NonCommercial
+        } else if self.no_derivatives {
+            DataLicenseClass::NoDerivatives
+        } else if self.share_alike {
+            DataLicenseClass::ShareAlike
+        } else if self.attribution {
+            DataLicenseClass::Attribution
+        } else {
+            DataLicenseClass::PublicDomain
+        }
+    }
+}
+
+/// The terms of `id` when it is a data or content license, `None` for anything else. Takes
+/// SPDX identifiers and the lowercase, versionless spellings dataset hubs use (`cc-by-nc-4.0`,
+/// `odbl`, `pddl`).
+pub fn data_license(id: &str) -> Option<DataLicense> {
+    let id = id.trim().to_ascii_uppercase();
+    let id = id.strip_prefix("LICENSEREF-").unwrap_or(&id);
+
+    if let Some(rest) = id.strip_prefix("CC-BY") {
+        // `CC-BY-NC-SA-4.0`, `CC-BY-3.0-DE`: the terms are the tokens before the version
+        let terms: Vec<&str> = rest.split('-').filter(|token| !token.is_empty()).collect();
+        return Some(DataLicense {
+            attribution: true,
+            share_alike: terms.contains(&"SA"),
+            no_derivatives: terms.contains(&"ND"),
+            non_commercial: terms.contains(&"NC"),
+        });
+    }
+
+    let family = |prefix: &str| id == prefix || id.starts_with(&format!("{prefix}-"));
+    if id.starts_with("CC0")
+        || family("PDDL")
+        || family("CC-PDDC")
+        || family("CC-PDM")
+        || family("DL-DE-ZERO")
+        || matches!(id, "PUBLIC-DOMAIN" | "PUBLIC DOMAIN" | "PUBLICDOMAIN")
+    {
+        Some(DataLicense::PUBLIC_DOMAIN)
+    } else if family("ODBL") || family("CDLA-SHARING") {
+        Some(DataLicense::SHARE_ALIKE)
+    } else if family("ODC-BY")
+        || family("CDLA-PERMISSIVE")
+        || family("OGL-UK")
+        || family("OGL-CANADA")
+        || family("DL-DE-BY")
+        || family("NLOD")
+    {
+        Some(DataLicense::ATTRIBUTION)
+    } else {
+        None
+    }
+}
+
+/// The class of `id` when it is a data or content license
+pub fn data_license_class(id: &str) -> Option<DataLicenseClass> {
+    data_license(id).map(DataLicense::class)
+}
+
+/// The class a license expression puts its data licenses in: the most demanding of an
+/// `AND`, the least demanding alternative of an `OR`. `None` when the expression names no
+/// data license.
+pub fn expression_class(license: &str) -> Option<DataLicenseClass> {
+    fn class(expression: &SpdxExpression) -> Option<DataLicenseClass> {
+        match expression {
+            SpdxExpression::License(id) | SpdxExpression::With { license: id, .. } => {
+                data_license_class(id)
+            }
+            SpdxExpression::And(a, b) => class(a).max(class(b)),
+            SpdxExpression::Or(a, b) => match (class(a), class(b)) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                // The other alternative is a software license, which the matrix judges
+                _ => None,
+            },
+        }
+    }
+    class(&spdx::parse(license))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_license_class() {
+        use DataLicenseClass::*;
+
+        for (id, expected) in [
+            ("CC0-1.0", Some(PublicDomain)),
+            ("PDDL-1.0", Some(PublicDomain)),
+            ("pddl", Some(PublicDomain)),
+            ("LicenseRef-public-domain", Some(PublicDomain)),
+            ("CC-BY-4.0", Some(Attribution)),
+            ("cc-by-3.0-de", Some(Attribution)),
+            ("ODC-By-1.0", Some(Attribution)),
+            ("CDLA-Permissive-2.0", Some(Attribution)),
+            ("OGL-UK-3.0", Some(Attribution)),
+            ("CC-BY-SA-4.0", Some(ShareAlike)),
+            ("ODbL-1.0", Some(ShareAlike)),
+            ("odbl", Some(ShareAlike)),
+            ("CDLA-Sharing-1.0", Some(ShareAlike)),
+            ("CC-BY-ND-4.0", Some(NoDerivatives)),
+            ("cc-by-nc-4.0", Some(NonCommercial)),
+            ("CC-BY-NC-SA-4.0", Some(NonCommercial)),
+            ("CC-BY-NC-ND-3.0-IGO", Some(NonCommercial)),
+            ("MIT", None),
+            ("Unlicense", None),
+            ("ODC-Bylaws", None),
+        ] {
+            assert_eq!(data_license_class(id), expected, "{id}");
+        }
+
+        let license = data_license("CC-BY-NC-SA-4.0").unwrap();
+        assert!(license.attribution && license.share_alike && license.non_commercial);
+        assert!(!license.no_derivatives);
+
+        assert!(!PublicDomain.is_restrictive(true));
+        assert!(!Attribution.is_restrictive(true));
+        assert!(!ShareAlike.is_restrictive(false));
+        assert!(ShareAlike.is_restrictive(true));
+        assert!(NoDerivatives.is_restrictive(false));
+        assert!(NonCommercial.is_restrictive(false));
+        assert!(Attribution.is_compatible_with_any());
+        assert!(!ShareAlike.is_compatible_with_any());
+    }
+
+    #[test]
+    fn test_expression_class() {
+        use DataLicenseClass::*;
+
+        assert_eq!(expression_class("CC-BY-4.0"), Some(Attribution));
+        assert_eq!(expression_class("CC-BY-4.0 AND ODbL-1.0"), Some(ShareAlike));
+        assert_eq!(expression_class("CC-BY-4.0 AND MIT"), Some(Attribution));
+        assert_eq!(
+            expression_class("CC-BY-SA-4.0 OR CC0-1.0"),
+            Some(PublicDomain)
+        );
+        assert_eq!(expression_class("CC-BY-NC-4.0 OR MIT"), None);
+        assert_eq!(expression_class("Apache-2.0"), None);
+    }
+}
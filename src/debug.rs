@@ -1,8 +1,20 @@
+use serde::Serialize;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
 // Static atomic flag for debug mode
 pub static DEBUG_MODE: AtomicBool = AtomicBool::new(false);
 
+// Manifest parse warnings collected during a scan
+static PARSE_WARNINGS: Mutex<Vec<ParseWarning>> = Mutex::new(Vec::new());
+
+// Failed registry/API lookups collected during a scan
+static RESOLUTION_ERRORS: Mutex<Vec<ResolutionError>> = Mutex::new(Vec::new());
+
+thread_local! {
+    static THREAD_RESOLUTION_ERRORS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
 // Log levels for different types of debug information
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LogLevel {
@@ -103,7 +115,112 @@ where
     }
 }
 
+/// A dependency manifest that could not be (fully) parsed
+///
+/// Analyzers record these instead of silently dropping the file, so the report can
+/// show what was skipped and `--strict-parse` can turn them into a hard failure.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParseWarning {
+    pub file: String,
+    pub reason: String,
+}
+
+/// Record a manifest parse failure for the current scan
+pub fn record_parse_warning(file: impl Into<String>, reason: impl Into<String>) {
+    let warning = ParseWarning {
+        file: file.into(),
+        reason: reason.into(),
+    };
+    log(
+        LogLevel::Warn,
+        &format!("Parse warning for {}: {}", warning.file, warning.reason),
+    );
+    if let Ok(mut warnings) = PARSE_WARNINGS.lock() {
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    }
+}
+
+/// Drain all parse warnings recorded since the last call
+pub fn take_parse_warnings() -> Vec<ParseWarning> {
+    PARSE_WARNINGS
+        .lock()
+        .map(|mut warnings| std::mem::take(&mut *warnings))
+        .unwrap_or_default()
+}
+
 /// Create a custom error type that includes debug information
+/// A registry or API lookup that failed, leaving a dependency's license unresolved
+///
+/// Network failures (connection errors, timeouts, rate limits, 5xx responses) are not the
+/// same as "this package has no license"; collecting them lets the report say how much of
+/// the scan rests on lookups that never completed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResolutionError {
+    /// Where the lookup went, e.g. "npm registry" or "PyPI"
+    pub source: String,
+    /// What was requested, usually the URL
+    pub target: String,
+    pub reason: String,
+}
+
+/// Record a failed registry/API lookup for the current scan
+pub fn record_resolution_error(
+    source: impl Into<String>,
+    target: impl Into<String>,
+    reason: impl Into<String>,
+) {
+    let error = ResolutionError {
+        source: source.into(),
+        target: target.into(),
+        reason: reason.into(),
+    };
+    log(
+        LogLevel::Warn,
+        &format!(
+            "Resolution error from {} for {}: {}",
+            error.source, error.target, error.reason
+        ),
+    );
+    THREAD_RESOLUTION_ERRORS.with(|count| count.set(count.get() + 1));
+    if let Ok(mut errors) = RESOLUTION_ERRORS.lock() {
+        if !errors.contains(&error) {
+            errors.push(error);
+        }
+    }
+}
+
+/// How many resolution errors this thread has recorded, so a lookup can tell whether its
+/// requests failed rather than came back empty
+pub fn thread_resolution_error_count() -> usize {
+    THREAD_RESOLUTION_ERRORS.with(|count| count.get())
+}
+
+/// Drain all resolution errors recorded since the last call
+pub fn take_resolution_errors() -> Vec<ResolutionError> {
+    RESOLUTION_ERRORS
+        .lock()
+        .map(|mut errors| std::mem::take(&mut *errors))
+        .unwrap_or_default()
+}
+
+/// Failed lookups per source, most failures first
+pub fn resolution_error_counts(errors: &[ResolutionError]) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for error in errors {
+        match counts
+            .iter_mut()
+            .find(|(source, _)| *source == error.source)
+        {
+            Some((_, count)) => *count += 1,
+            None => counts.push((error.source.clone(), 1)),
+        }
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum FeludaError {
     #[error("IO error: {0}")]
@@ -122,6 +239,9 @@ pub enum FeludaError {
     #[error("Parser error: {0}")]
     Parser(String),
 
+    #[error("Lockfile error: {0}")]
+    Lockfile(String),
+
     #[error("Repository clone error: {0}")]
     RepositoryClone(String),
 
@@ -179,6 +299,31 @@ mod tests {
         assert!(!is_debug_mode());
     }
 
+    #[test]
+    fn test_resolution_error_counts() {
+        let error = |source: &str, target: &str| ResolutionError {
+            source: source.to_string(),
+            target: target.to_string(),
+            reason: "connection reset".to_string(),
+        };
+        let errors = vec![
+            error("PyPI", "https://pypi.org/pypi/requests/2.31.0/json"),
+            error("npm registry", "https://registry.npmjs.org/left-pad"),
+            error("PyPI", "https://pypi.org/pypi/flask/3.0.0/json"),
+            error("Maven Central", "https://repo1.maven.org/maven2/junit"),
+        ];
+
+        assert_eq!(
+            resolution_error_counts(&errors),
+            vec![
+                ("PyPI".to_string(), 2),
+                ("Maven Central".to_string(), 1),
+                ("npm registry".to_string(), 1),
+            ]
+        );
+        assert!(resolution_error_counts(&[]).is_empty());
+    }
+
     #[test]
     fn test_log_level_as_str() {
         assert_eq!(LogLevel::Info.as_str(), "INFO");
@@ -331,6 +476,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_warnings_are_collected_and_drained() {
+        // Other tests may record warnings concurrently, so only look at our own files.
+        let ours = |w: &ParseWarning| w.file.starts_with("debug-test/");
+
+        record_parse_warning("debug-test/package.json", "expected value at line 1");
+        record_parse_warning("debug-test/package.json", "expected value at line 1");
+        record_parse_warning("debug-test/go.mod", "permission denied");
+
+        let warnings: Vec<ParseWarning> = take_parse_warnings().into_iter().filter(ours).collect();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].file, "debug-test/package.json");
+        assert_eq!(warnings[1].reason, "permission denied");
+        assert!(!take_parse_warnings().iter().any(ours));
+    }
+
     #[test]
     fn test_log_level_debug_format() {
         let info = LogLevel::Info;
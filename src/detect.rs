@@ -0,0 +1,271 @@
+//! `feluda detect`: which projects a scan of a path would find, and how it would analyze them
+//!
+//! Lists each project root with its ecosystem, the manifests and lockfiles it holds, the
+//! files its analyzer starts from and the resolver chain its licenses go through. Project
+//! files deeper in the tree are listed too: a scan only reads the path's own directory, so
+//! they are the usual answer to "why isn't my project being scanned?".
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use colored::*;
+use serde::Serialize;
+
+use crate::config::{self, FeludaConfig};
+use crate::debug::{FeludaError, FeludaResult};
+use crate::dry_run::registry_endpoints;
+use crate::manifest::discover_dependency_files;
+use crate::parser::{plan_roots, PlannedRoot};
+use crate::resolver::ResolverSource;
+
+/// Ecosystems whose analyzer follows the root manifest into workspace members
+const WORKSPACE_ECOSYSTEMS: [&str; 4] = ["rust", "node", "go", "python"];
+
+/// What `feluda detect` found under a path
+#[derive(Debug, Serialize)]
+struct Detection {
+    path: String,
+    roots: Vec<DetectedRoot>,
+    /// Directories below the path holding project files a scan of the path won't read
+    nested: Vec<NestedProject>,
+}
+
+/// A project root and the analysis a scan would run on it
+#[derive(Debug, Serialize)]
+struct DetectedRoot {
+    ecosystem: &'static str,
+    files: Vec<String>,
+    analyzer: &'static str,
+    reads: Vec<String>,
+    resolvers: Vec<String>,
+    skipped: Option<String>,
+}
+
+/// Project files in a subdirectory, outside what a scan of the path reads
+#[derive(Debug, Serialize)]
+struct NestedProject {
+    path: String,
+    ecosystems: Vec<&'static str>,
+    files: Vec<String>,
+    /// Set when the root has the same ecosystem, whose analyzer may reach it as a workspace member
+    possible_workspace_member: bool,
+}
+
+pub fn handle_detect_command(path: &str, json: bool) -> FeludaResult<()> {
+    let config = config::load_config()?;
+    let detection = detect(Path::new(path), &config)?;
+
+    if json {
+        let output = serde_json::to_string_pretty(&detection).map_err(|e| {
+            FeludaError::Serialization(format!("Failed to serialize detection report: {e}"))
+        })?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    print_detection(&detection);
+    Ok(())
+}
+
+/// Detect the project roots in `path` and the projects nested below it
+fn detect(path: &Path, config: &FeludaConfig) -> FeludaResult<Detection> {
+    let roots: Vec<DetectedRoot> = plan_roots(path, None, &config.skip)?
+        .into_iter()
+        .map(|root| DetectedRoot {
+            ecosystem: root.ecosystem,
+            analyzer: analyzer_name(root.ecosystem),
+            resolvers: resolver_chain(root.ecosystem, &config.resolvers.order),
+            files: root.files,
+            reads: root.reads,
+            skipped: root.skipped,
+        })
+        .collect();
+
+    let root_ecosystems: Vec<&str> = roots.iter().map(|root| root.ecosystem).collect();
+    let mut nested = Vec::new();
+    for dir in nested_project_dirs(path) {
+        let planned: Vec<PlannedRoot> = plan_roots(&dir, None, &config.skip)?;
+        if planned.is_empty() {
+            continue;
+        }
+        let ecosystems: Vec<&'static str> = planned.iter().map(|root| root.ecosystem).collect();
+        let files: BTreeSet<String> = planned.into_iter().flat_map(|root| root.files).collect();
+        nested.push(NestedProject {
+            path: dir.strip_prefix(path).unwrap_or(&dir).display().to_string(),
+            possible_workspace_member: ecosystems.iter().any(|ecosystem| {
+                WORKSPACE_ECOSYSTEMS.contains(ecosystem) && root_ecosystems.contains(ecosystem)
+            }),
+            ecosystems,
+            files: files.into_iter().collect(),
+        });
+    }
+
+    Ok(Detection {
+        path: path.display().to_string(),
+        roots,
+        nested,
+    })
+}
+
+/// Subdirectories of `path` holding a dependency file, gitignored and vendored trees excluded
+fn nested_project_dirs(path: &Path) -> Vec<PathBuf> {
+    let dirs: BTreeSet<PathBuf> = discover_dependency_files(path)
+        .into_iter()
+        .filter_map(|file| file.parent().map(Path::to_path_buf))
+        .filter(|dir| dir != path)
+        .collect();
+    dirs.into_iter().collect()
+}
+
+/// The analyzer a root's dependencies go through, as users know its tooling
+fn analyzer_name(ecosystem: &str) -> &'static str {
+    match ecosystem {
+        "android" => "Android/KMP Gradle modules and license plugin reports",
+        "c" => "C build files, pkg-config and system packages",
+        "carthage" => "Carthage Cartfile",
+        "cpp" => "C++ (vcpkg, Conan, CMake, Bazel)",
+        "dotnet" => ".NET solutions and projects (NuGet)",
+        "java" => "Java Maven/Gradle",
+        "rust" => "Rust (cargo metadata)",
+        "node" => "Node.js package.json and lockfile",
+        "go" => "Go modules",
+        "python" => "Python requirements, Pipfile, pyproject and lockfiles",
+        "php" => "PHP Composer",
+        "elixir" => "Elixir Mix",
+        "r" => "R DESCRIPTION and renv",
+        "ruby" => "Ruby Bundler",
+        "wasm" => "WebAssembly components (wkg, WIT)",
+        _ => "unknown",
+    }
+}
+
+/// The resolver steps a root's license lookups take, with the hosts its registry step queries
+fn resolver_chain(ecosystem: &str, order: &[ResolverSource]) -> Vec<String> {
+    order
+        .iter()
+        .map(|source| match source {
+            ResolverSource::Registry => match registry_endpoints(ecosystem) {
+                [] => format!("{source} (none for {ecosystem})"),
+                hosts => format!("{source} ({})", hosts.join(", ")),
+            },
+            _ => source.to_string(),
+        })
+        .collect()
+}
+
+fn print_detection(detection: &Detection) {
+    println!("{}", format!("Project roots in {}", detection.path).bold());
+    if detection.roots.is_empty() {
+        println!("  none: no supported manifest in this directory");
+    }
+    for root in &detection.roots {
+        match &root.skipped {
+            Some(reason) => println!(
+                "  • {} {}",
+                root.ecosystem.bold(),
+                format!("skipped by {reason}").dimmed()
+            ),
+            None => println!("  • {}", root.ecosystem.bold()),
+        }
+        println!("      {:<10} {}", "files:", root.files.join(", "));
+        println!("      {:<10} {}", "analyzer:", root.analyzer);
+        if !root.reads.is_empty() {
+            println!("      {:<10} {}", "reads:", root.reads.join(", "));
+        }
+        println!("      {:<10} {}", "resolvers:", root.resolvers.join(" → "));
+    }
+    println!();
+
+    if detection.nested.is_empty() {
+        return;
+    }
+    println!("{}", "Project files in subdirectories".bold());
+    println!(
+        "  {}",
+        "A scan reads only the path's own directory; scan these with --path <dir>".dimmed()
+    );
+    for project in &detection.nested {
+        let note = if project.possible_workspace_member {
+            format!(
+                " {}",
+                "(covered if the root declares it a workspace member)".dimmed()
+            )
+        } else {
+            String::new()
+        };
+        println!(
+            "  • {}: {} ({}){note}",
+            project.path,
+            project.ecosystems.join(", "),
+            project.files.join(", ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_detect_roots_and_nested_projects() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+        fs::write(dir.path().join("Cargo.lock"), "").unwrap();
+        fs::write(dir.path().join("package.json"), "{}").unwrap();
+        fs::write(dir.path().join("yarn.lock"), "").unwrap();
+
+        let member = dir.path().join("crates").join("core");
+        fs::create_dir_all(&member).unwrap();
+        fs::write(member.join("Cargo.toml"), "[package]\nname = \"core\"\n").unwrap();
+        let service = dir.path().join("services").join("api");
+        fs::create_dir_all(&service).unwrap();
+        fs::write(service.join("go.mod"), "module example.com/api\n").unwrap();
+        let vendored = dir.path().join("node_modules").join("left-pad");
+        fs::create_dir_all(&vendored).unwrap();
+        fs::write(vendored.join("package.json"), "{}").unwrap();
+
+        let config = FeludaConfig {
+            skip: vec!["node".to_string()],
+            ..Default::default()
+        };
+        let detection = detect(dir.path(), &config).unwrap();
+
+        let rust = detection
+            .roots
+            .iter()
+            .find(|root| root.ecosystem == "rust")
+            .unwrap();
+        assert_eq!(rust.files, vec!["Cargo.lock", "Cargo.toml"]);
+        assert_eq!(rust.reads, vec!["Cargo.toml"]);
+        assert_eq!(rust.skipped, None);
+        assert!(rust
+            .resolvers
+            .contains(&"registry (none for rust)".to_string()));
+
+        let node = detection
+            .roots
+            .iter()
+            .find(|root| root.ecosystem == "node")
+            .unwrap();
+        assert_eq!(node.reads, vec!["package.json", "yarn.lock"]);
+        assert_eq!(node.skipped.as_deref(), Some("skip list"));
+        assert!(node
+            .resolvers
+            .contains(&"registry (https://registry.npmjs.org)".to_string()));
+
+        let nested: Vec<(&str, bool)> = detection
+            .nested
+            .iter()
+            .map(|project| (project.path.as_str(), project.possible_workspace_member))
+            .collect();
+        assert_eq!(
+            nested,
+            vec![
+                (Path::new("crates").join("core").to_str().unwrap(), true),
+                (Path::new("services").join("api").to_str().unwrap(), false),
+            ]
+        );
+        assert_eq!(detection.nested[1].ecosystems, vec!["go"]);
+    }
+}
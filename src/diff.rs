@@ -0,0 +1,345 @@
+//! `feluda diff`: compare two saved reports
+//!
+//! Takes the `--json` or `--yaml` reports of two scans, typically the base branch and a pull
+//! request, and lists the dependencies that were added, removed or changed license between
+//! them. Rows are paired the way `--baseline` pairs them: by name and version, then by name, so
+//! a version bump that keeps the license isn't a change. A dependency that is restrictive or
+//! incompatible in the new report but wasn't in the old one is a regression; with
+//! `--fail-on-restrictive` / `--fail-on-incompatible` only those fail the build, so a PR is
+//! gated on what it introduced rather than on what the project already had.
+
+use std::path::Path;
+use std::process;
+
+use colored::*;
+use serde::Serialize;
+
+use crate::baseline::match_rows;
+use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
+use crate::licenses::{LicenseCompatibility, LicenseInfo};
+use crate::report_archive::load_report_with_metadata;
+
+/// What changed between two reports
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct ReportDiff {
+    pub added: Vec<DiffRow>,
+    pub removed: Vec<DiffRow>,
+    pub license_changed: Vec<LicenseChange>,
+    /// Dependencies restrictive or incompatible in the new report that weren't in the old one
+    pub regressions: Vec<Regression>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct DiffRow {
+    pub name: String,
+    pub version: String,
+    pub license: String,
+    pub is_restrictive: bool,
+    pub compatibility: LicenseCompatibility,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct LicenseChange {
+    pub name: String,
+    pub old_version: String,
+    pub new_version: String,
+    pub old_license: String,
+    pub new_license: String,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Regression {
+    pub name: String,
+    pub version: String,
+    pub license: String,
+    pub restrictive: bool,
+    pub incompatible: bool,
+}
+
+impl ReportDiff {
+    fn has_restrictive_regression(&self) -> bool {
+        self.regressions
+            .iter()
+            .any(|regression| regression.restrictive)
+    }
+
+    fn has_incompatible_regression(&self) -> bool {
+        self.regressions
+            .iter()
+            .any(|regression| regression.incompatible)
+    }
+}
+
+pub fn handle_diff_command(
+    old: &str,
+    new: &str,
+    json: bool,
+    fail_on_restrictive: bool,
+    fail_on_incompatible: bool,
+    force: bool,
+) -> FeludaResult<()> {
+    let (old_report, old_metadata) =
+        load_report_with_metadata(Path::new(old)).inspect_err(|e| eprintln!("❌ {e}"))?;
+    let (new_report, new_metadata) =
+        load_report_with_metadata(Path::new(new)).inspect_err(|e| eprintln!("❌ {e}"))?;
+    crate::baseline::check_lockfile_state(
+        &new_report,
+        new_metadata
+            .and_then(|metadata| metadata.lockfile_hash)
+            .as_deref(),
+        &old_report,
+        old_metadata
+            .and_then(|metadata| metadata.lockfile_hash)
+            .as_deref(),
+        Path::new(old),
+        force,
+    )
+    .inspect_err(|e| eprintln!("❌ {e}"))?;
+    let diff = diff_reports(&old_report, &new_report);
+
+    if json {
+        let output = serde_json::to_string_pretty(&diff)
+            .map_err(|e| FeludaError::Serialization(format!("Failed to serialize diff: {e}")))?;
+        println!("{output}");
+    } else {
+        print_diff(&diff);
+    }
+
+    if (fail_on_restrictive && diff.has_restrictive_regression())
+        || (fail_on_incompatible && diff.has_incompatible_regression())
+    {
+        log(
+            LogLevel::Warn,
+            "Exiting with non-zero status due to license regressions",
+        );
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// Compare the dependencies of an old and a new report
+pub fn diff_reports(old: &[LicenseInfo], new: &[LicenseInfo]) -> ReportDiff {
+    let rows = match_rows(new, old);
+    let mut diff = ReportDiff {
+        added: rows.added.iter().map(|dep| row(dep)).collect(),
+        removed: rows.removed.iter().map(|dep| row(dep)).collect(),
+        ..ReportDiff::default()
+    };
+
+    for (before, after) in &rows.matched {
+        if before.license != after.license {
+            diff.license_changed.push(LicenseChange {
+                name: after.name.clone(),
+                old_version: before.version.clone(),
+                new_version: after.version.clone(),
+                old_license: before.get_license(),
+                new_license: after.get_license(),
+            });
+        }
+    }
+
+    let current = rows
+        .matched
+        .iter()
+        .map(|(before, after)| (*after, Some(*before)))
+        .chain(rows.added.iter().map(|after| (*after, None)));
+    for (after, before) in current {
+        let restrictive = after.is_restrictive && !before.is_some_and(|dep| dep.is_restrictive);
+        let incompatible = is_incompatible(after) && !before.is_some_and(is_incompatible);
+        if restrictive || incompatible {
+            diff.regressions.push(Regression {
+                name: after.name.clone(),
+                version: after.version.clone(),
+                license: after.get_license(),
+                restrictive,
+                incompatible,
+            });
+        }
+    }
+
+    diff.added.sort_by(|a, b| a.name.cmp(&b.name));
+    diff.removed.sort_by(|a, b| a.name.cmp(&b.name));
+    diff.license_changed.sort_by(|a, b| a.name.cmp(&b.name));
+    diff.regressions.sort_by(|a, b| a.name.cmp(&b.name));
+    log(
+        LogLevel::Info,
+        &format!(
+            "Diff: {} added, {} removed, {} license changes, {} regressions",
+            diff.added.len(),
+            diff.removed.len(),
+            diff.license_changed.len(),
+            diff.regressions.len()
+        ),
+    );
+    diff
+}
+
+fn row(dep: &LicenseInfo) -> DiffRow {
+    DiffRow {
+        name: dep.name.clone(),
+        version: dep.version.clone(),
+        license: dep.get_license(),
+        is_restrictive: dep.is_restrictive,
+        compatibility: dep.compatibility,
+    }
+}
+
+fn is_incompatible(dep: &LicenseInfo) -> bool {
+    dep.compatibility == LicenseCompatibility::Incompatible
+}
+
+fn print_diff(diff: &ReportDiff) {
+    println!(
+        "{} {}",
+        "🔀".bold(),
+        format!(
+            "{} added, {} removed, {} license change{}",
+            diff.added.len(),
+            diff.removed.len(),
+            diff.license_changed.len(),
+            if diff.license_changed.len() == 1 {
+                ""
+            } else {
+                "s"
+            }
+        )
+        .bold()
+    );
+    for dep in &diff.added {
+        println!(
+            "  {} {} {} ({})",
+            "+".green().bold(),
+            dep.name.as_str().bold(),
+            dep.version,
+            dep.license
+        );
+    }
+    for dep in &diff.removed {
+        println!(
+            "  {} {} {} ({})",
+            "-".red().bold(),
+            dep.name.as_str().bold(),
+            dep.version,
+            dep.license
+        );
+    }
+    for change in &diff.license_changed {
+        let version = if change.old_version == change.new_version {
+            change.new_version.clone()
+        } else {
+            format!("{} → {}", change.old_version, change.new_version)
+        };
+        println!(
+            "  {} {} {version}: {} → {}",
+            "~".yellow().bold(),
+            change.name.as_str().bold(),
+            change.old_license,
+            change.new_license
+        );
+    }
+
+    if diff.regressions.is_empty() {
+        println!("\nNo new restrictive or incompatible dependencies");
+        return;
+    }
+    println!(
+        "\n{} {}",
+        "⚠️".bold(),
+        format!(
+            "{} regression{}: newly restrictive or incompatible",
+            diff.regressions.len(),
+            if diff.regressions.len() == 1 { "" } else { "s" }
+        )
+        .bold()
+    );
+    for regression in &diff.regressions {
+        let kinds: Vec<&str> = [
+            (regression.restrictive, "restrictive"),
+            (regression.incompatible, "incompatible"),
+        ]
+        .into_iter()
+        .filter_map(|(flagged, kind)| flagged.then_some(kind))
+        .collect();
+        println!(
+            "  • {} {} ({}, {})",
+            regression.name.as_str().bold(),
+            regression.version,
+            regression.license,
+            kinds.join(", ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_reports() {
+        let old = vec![
+            LicenseInfo::fixture("serde", "1.0.0", "MIT"),
+            LicenseInfo::fixture("left-pad", "1.0.0", "WTFPL"),
+            LicenseInfo::fixture("readline", "8.1", "GPL-3.0").restrictive(),
+            LicenseInfo::fixture("chart", "2.0.0", "MIT"),
+        ];
+        let new = vec![
+            LicenseInfo::fixture("serde", "1.1.0", "MIT"),
+            LicenseInfo::fixture("readline", "8.2", "GPL-3.0").restrictive(),
+            LicenseInfo::fixture("chart", "3.0.0", "AGPL-3.0").restrictive(),
+            LicenseInfo {
+                compatibility: LicenseCompatibility::Incompatible,
+                ..LicenseInfo::fixture("sdk", "0.4.0", "SSPL-1.0")
+            },
+        ];
+
+        let diff = diff_reports(&old, &new);
+        assert_eq!(
+            diff.added
+                .iter()
+                .map(|d| d.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["sdk"]
+        );
+        assert_eq!(
+            diff.removed
+                .iter()
+                .map(|d| d.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["left-pad"]
+        );
+        assert_eq!(
+            diff.license_changed,
+            vec![LicenseChange {
+                name: "chart".to_string(),
+                old_version: "2.0.0".to_string(),
+                new_version: "3.0.0".to_string(),
+                old_license: "MIT".to_string(),
+                new_license: "AGPL-3.0".to_string(),
+            }]
+        );
+        // readline was already restrictive, serde only moved versions
+        assert_eq!(
+            diff.regressions,
+            vec![
+                Regression {
+                    name: "chart".to_string(),
+                    version: "3.0.0".to_string(),
+                    license: "AGPL-3.0".to_string(),
+                    restrictive: true,
+                    incompatible: false,
+                },
+                Regression {
+                    name: "sdk".to_string(),
+                    version: "0.4.0".to_string(),
+                    license: "SSPL-1.0".to_string(),
+                    restrictive: false,
+                    incompatible: true,
+                },
+            ]
+        );
+        assert!(diff.has_restrictive_regression());
+        assert!(diff.has_incompatible_regression());
+
+        assert_eq!(diff_reports(&new, &new), ReportDiff::default());
+    }
+}
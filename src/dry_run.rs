@@ -0,0 +1,462 @@
+//! `--dry-run`: what a scan would read, contact and enforce, without running it
+//!
+//! Lists the project roots and dependency files the parser would pick up, the resolver chain,
+//! the network endpoints its steps would reach and the policy the results would be judged
+//! against. Nothing is cloned, parsed or fetched, so a configuration can be checked from a
+//! locked-down machine before the real scan is allowed out.
+
+use std::path::Path;
+
+use colored::*;
+
+use crate::cli::Cli;
+use crate::config::{self, CargoLockMode, FeludaConfig};
+use crate::debug::FeludaResult;
+use crate::licenses::{detect_project_license, github_api_url};
+use crate::parser::{plan_roots, PlannedRoot};
+use crate::resolver::ResolverSource;
+
+/// Base URL of the ClearlyDefined definitions API
+pub(crate) const CLEARLYDEFINED_API: &str = "https://api.clearlydefined.io";
+
+/// A host the scan would send requests to, and what for
+#[derive(Debug, PartialEq, Eq)]
+struct Endpoint {
+    url: String,
+    purpose: String,
+}
+
+/// Print the plan for a scan of `path` with the options in `args`
+pub fn print_plan(args: &Cli, path: &Path) -> FeludaResult<()> {
+    let mut config = config::load_config()?;
+    config.apply_flags(
+        args.strict,
+        args.cargo_lock(),
+        args.no_dev,
+        args.direct_only,
+        args.include_transitive,
+        &args.skip,
+    );
+    config.apply_cargo_flags(&args.cargo_options());
+
+    // A remote repository isn't cloned, so its manifests can't be listed
+    let roots = if args.repo.is_some() {
+        Vec::new()
+    } else {
+        plan_roots(path, args.language.as_deref(), &config.skip)?
+    };
+
+    println!("{}", "Dry run: nothing is cloned, parsed or fetched".bold());
+    println!();
+
+    println!("{}", "Project roots".bold());
+    match &args.repo {
+        Some(repo) if args.github_sbom => {
+            println!("  • {repo}: GitHub dependency graph SBOM, no local manifests")
+        }
+        Some(repo) => println!("  • {repo}: manifests are listed once the repository is cloned"),
+        None if roots.is_empty() => {
+            println!("  • none: no supported project files in {}", path.display())
+        }
+        None => {
+            for root in &roots {
+                let files = if root.files.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", root.files.join(", "))
+                };
+                match &root.skipped {
+                    Some(reason) => println!(
+                        "  • {}{files} {}",
+                        root.ecosystem,
+                        format!("skipped by {reason}").dimmed()
+                    ),
+                    None => println!("  • {}{files}", root.ecosystem.bold()),
+                }
+            }
+        }
+    }
+    if let Some(ref git_ref) = args.changed_since {
+        println!("  Project roots with no dependency file changed since {git_ref} are skipped");
+    }
+    println!();
+
+    println!("{}", "Resolvers".bold());
+    for (index, source) in config.resolvers.order.iter().enumerate() {
+        let note = match source {
+            ResolverSource::Local if args.no_local => " (disabled by --no-local)",
+            source if args.offline && source.is_remote() => " (disabled by --offline)",
+            _ => "",
+        };
+        println!("  {}. {source}{note}", index + 1);
+    }
+    if config.resolvers.order.is_empty() {
+        println!("  none: every dependency license will be unknown");
+    }
+    println!();
+
+    println!("{}", "Network endpoints".bold());
+    let endpoints = plan_endpoints(args, &config, &roots);
+    if endpoints.is_empty() {
+        println!("  none{}", if args.offline { " (--offline)" } else { "" });
+    }
+    for endpoint in &endpoints {
+        println!("  • {}: {}", endpoint.url, endpoint.purpose);
+    }
+    println!();
+
+    println!("{}", "Policy".bold());
+    for (name, value) in plan_policy(args, &config, path) {
+        println!("  {:<24} {value}", format!("{name}:"));
+    }
+
+    Ok(())
+}
+
+/// Every endpoint the scan would contact, in roughly the order it would contact them
+fn plan_endpoints(args: &Cli, config: &FeludaConfig, roots: &[PlannedRoot]) -> Vec<Endpoint> {
+    let mut endpoints: Vec<Endpoint> = Vec::new();
+    if args.offline {
+        return endpoints;
+    }
+    let github = github_api_url();
+    let mut add = |url: &str, purpose: String| {
+        if let Some(existing) = endpoints.iter_mut().find(|e| e.url == url) {
+            existing.purpose = format!("{}; {purpose}", existing.purpose);
+        } else {
+            endpoints.push(Endpoint {
+                url: url.to_string(),
+                purpose,
+            });
+        }
+    };
+
+    if let Some(ref repo) = args.repo {
+        if args.github_sbom {
+            add(&github, "repository dependency graph SBOM".into());
+        } else {
+            add(repo, "git clone".into());
+        }
+    }
+
+    if !matches!(crate::cache::load_github_licenses_from_cache(), Ok(Some(_))) {
+        add(&github, "license list (not cached yet)".into());
+    }
+
+    let ecosystems: Vec<&str> = roots
+        .iter()
+        .filter(|root| root.skipped.is_none())
+        .map(|root| root.ecosystem)
+        .collect();
+    let order = &config.resolvers.order;
+
+    if ecosystems.contains(&"rust") && config.cargo_lock != CargoLockMode::Frozen {
+        add(
+            "https://index.crates.io",
+            "cargo metadata index updates (pass --frozen to forbid)".into(),
+        );
+    }
+    if order.contains(&ResolverSource::Registry) {
+        for ecosystem in &ecosystems {
+            for url in registry_endpoints(ecosystem) {
+                add(url, format!("{ecosystem} registry lookups"));
+            }
+        }
+    }
+    if !ecosystems.is_empty() {
+        if order.contains(&ResolverSource::ClearlyDefined) {
+            add(CLEARLYDEFINED_API, "license definitions".into());
+        }
+        if order.contains(&ResolverSource::Github) {
+            add(&github, "repository licenses".into());
+        }
+        if args.resolve_forks {
+            add(&github, "upstream licenses of forks".into());
+        }
+        // Texts missing from the local caches are tried against each registry in turn
+        if args.licenses_dir.is_some() {
+            for url in [
+                "https://crates.io",
+                "https://registry.npmjs.org",
+                "https://pypi.org",
+                "https://pkg.go.dev",
+                &github,
+            ] {
+                add(url, "license texts (--licenses-dir)".into());
+            }
+        }
+    }
+    if args.obligations_issues.is_some() {
+        add(&github, "obligation issues (--obligations-issues)".into());
+    }
+    if args.pending_updates.is_some() {
+        add(
+            &github,
+            "pending update pull requests (--pending-updates)".into(),
+        );
+    }
+
+    endpoints
+}
+
+/// Registries an ecosystem's `registry` resolver step queries
+pub(crate) fn registry_endpoints(ecosystem: &str) -> &'static [&'static str] {
+    match ecosystem {
+        "node" => &["https://registry.npmjs.org"],
+        "python" => &["https://pypi.org"],
+        "java" | "android" => &[
+            "https://repo1.maven.org",
+            "https://search.maven.org",
+            "https://dl.google.com",
+        ],
+        "go" => &["https://pkg.go.dev"],
+        "dotnet" => &["https://api.nuget.org"],
+        "ruby" => &["https://rubygems.org"],
+        "php" => &["https://repo.packagist.org"],
+        "elixir" => &["https://hex.pm"],
+        "wasm" => &["https://wasi.dev", "https://ghcr.io"],
+        "carthage" => &["https://api.github.com"],
+        "r" => &["https://r-universe.dev"],
+        "cpp" => &["https://conan.io", "https://raw.githubusercontent.com"],
+        // Rust licenses come from `cargo metadata`, C from pkg-config and system packages
+        _ => &[],
+    }
+}
+
+/// The settings the results would be judged against, as `(name, value)` rows
+fn plan_policy(args: &Cli, config: &FeludaConfig, path: &Path) -> Vec<(&'static str, String)> {
+    let on_off = |enabled: bool| if enabled { "on" } else { "off" }.to_string();
+    let list = |items: &[String]| {
+        if items.is_empty() {
+            "none".to_string()
+        } else {
+            items.join(", ")
+        }
+    };
+
+    // Detection only reads the project's own license files
+    let project_license = match &args.project_license {
+        Some(license) => format!("{license} (--project-license)"),
+        None if args.repo.is_some() => "detected after cloning".to_string(),
+        None => match detect_project_license(&path.to_string_lossy()) {
+            Ok(Some(license)) => format!("{license} (detected)"),
+            _ => "unknown: compatibility checks are skipped".to_string(),
+        },
+    };
+
+    let file_rules = crate::ignore::load_ignore_file(path);
+    let ignored_dependencies: Vec<String> = config
+        .dependencies
+        .ignore
+        .iter()
+        .chain(file_rules.iter().map(|sourced| &sourced.rule))
+        .map(|dep| {
+            if dep.version.is_empty() {
+                dep.name.clone()
+            } else {
+                format!("{}@{}", dep.name, dep.version)
+            }
+        })
+        .collect();
+
+    let mut gates = Vec::new();
+    if args.fail_on_restrictive {
+        gates.push("restrictive".to_string());
+    }
+    if args.fail_on_incompatible {
+        gates.push("incompatible".to_string());
+    }
+    if args.fail_on_resolution_errors {
+        gates.push("resolution errors".to_string());
+    }
+    if args.osi_only {
+        gates.push("non-OSI (--osi-only)".to_string());
+    }
+
+    let mut cargo_features = if config.cargo.no_default_features {
+        Vec::new()
+    } else {
+        vec!["default".to_string()]
+    };
+    cargo_features.extend(config.cargo.features.iter().cloned());
+    let cargo_features = list(&cargo_features);
+
+    let mut tree_walks = vec!["own source headers".to_string()];
+    if !args.no_vendor_scan {
+        tree_walks.push("vendored code".to_string());
+    }
+    tree_walks.push("extension and Electron bundles".to_string());
+    if !args.artifacts.is_empty() {
+        tree_walks.push(format!("artifacts in {}", args.artifacts.join(", ")));
+    }
+
+    vec![
+        ("Project license", project_license),
+        ("Strict mode", on_off(config.strict)),
+        (
+            "Language filter",
+            args.language.clone().unwrap_or_else(|| "all".to_string()),
+        ),
+        ("Skipped ecosystems", list(&config.skip)),
+        ("Dev dependencies", on_off(!config.no_dev)),
+        ("Transitive dependencies", on_off(!config.direct_only)),
+        ("Go module graph", on_off(config.include_transitive)),
+        ("Cargo dev-deps", on_off(!config.cargo.no_dev_deps)),
+        ("Cargo build-deps", on_off(!config.cargo.no_build_deps)),
+        ("Cargo features", cargo_features),
+        ("Restrictive licenses", list(&config.licenses.restrictive)),
+        ("Ignored licenses", list(&config.licenses.ignore)),
+        (
+            "Allowed",
+            list(
+                &[
+                    config.licenses.allow.licenses.clone(),
+                    config.licenses.allow.packages.clone(),
+                ]
+                .concat(),
+            ),
+        ),
+        (
+            "Denied",
+            list(
+                &[
+                    config.licenses.deny.licenses.clone(),
+                    config.licenses.deny.packages.clone(),
+                ]
+                .concat(),
+            ),
+        ),
+        ("Ignored dependencies", list(&ignored_dependencies)),
+        ("Fails on", list(&gates)),
+        ("Tree walks", list(&tree_walks)),
+        (
+            "Timeout",
+            args.timeout
+                .map_or("none".to_string(), |seconds| format!("{seconds}s")),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn root(ecosystem: &'static str, skipped: Option<&str>) -> PlannedRoot {
+        PlannedRoot {
+            ecosystem,
+            files: Vec::new(),
+            reads: Vec::new(),
+            skipped: skipped.map(str::to_string),
+        }
+    }
+
+    fn urls(endpoints: &[Endpoint]) -> Vec<&str> {
+        endpoints.iter().map(|e| e.url.as_str()).collect()
+    }
+
+    #[test]
+    fn test_plan_endpoints_follow_resolver_order() {
+        let args = Cli::parse_from(["feluda", "--frozen"]);
+        let roots = [root("node", None), root("python", Some("skip list"))];
+
+        let config = FeludaConfig::default();
+        let endpoints = plan_endpoints(&args, &config, &roots);
+        let contacted = urls(&endpoints);
+        assert!(contacted.contains(&"https://registry.npmjs.org"));
+        assert!(contacted.contains(&CLEARLYDEFINED_API));
+        assert!(!contacted.contains(&"https://pypi.org"));
+
+        let mut config = FeludaConfig::default();
+        config.resolvers.order = vec![ResolverSource::Local, ResolverSource::Cache];
+        let endpoints = plan_endpoints(&args, &config, &roots);
+        let contacted = urls(&endpoints);
+        assert!(!contacted.contains(&"https://registry.npmjs.org"));
+        assert!(!contacted.contains(&CLEARLYDEFINED_API));
+    }
+
+    #[test]
+    fn test_plan_endpoints_cargo_index() {
+        let roots = [root("rust", None)];
+        let config = FeludaConfig::default();
+
+        let args = Cli::parse_from(["feluda"]);
+        let endpoints = plan_endpoints(&args, &config, &roots);
+        assert!(urls(&endpoints).contains(&"https://index.crates.io"));
+
+        let frozen = FeludaConfig {
+            cargo_lock: CargoLockMode::Frozen,
+            ..Default::default()
+        };
+        let endpoints = plan_endpoints(&args, &frozen, &roots);
+        assert!(!urls(&endpoints).contains(&"https://index.crates.io"));
+    }
+
+    #[test]
+    fn test_plan_endpoints_licenses_dir() {
+        let roots = [root("go", None)];
+        let config = FeludaConfig::default();
+
+        let args = Cli::parse_from(["feluda"]);
+        let endpoints = plan_endpoints(&args, &config, &roots);
+        assert!(!urls(&endpoints).contains(&"https://crates.io"));
+
+        let args = Cli::parse_from(["feluda", "--licenses-dir", "out/licenses"]);
+        let endpoints = plan_endpoints(&args, &config, &roots);
+        let crates = endpoints
+            .iter()
+            .find(|e| e.url == "https://crates.io")
+            .unwrap();
+        assert_eq!(crates.purpose, "license texts (--licenses-dir)");
+        let pkgsite = endpoints
+            .iter()
+            .find(|e| e.url == "https://pkg.go.dev")
+            .unwrap();
+        assert_eq!(
+            pkgsite.purpose,
+            "go registry lookups; license texts (--licenses-dir)"
+        );
+    }
+
+    #[test]
+    fn test_plan_endpoints_remote_repository() {
+        let args = Cli::parse_from(["feluda", "--repo", "https://github.com/user/repo"]);
+        let endpoints = plan_endpoints(&args, &FeludaConfig::default(), &[]);
+        assert_eq!(endpoints[0].url, "https://github.com/user/repo");
+        assert_eq!(endpoints[0].purpose, "git clone");
+    }
+
+    #[test]
+    fn test_plan_endpoints_offline() {
+        let roots = [root("rust", None), root("node", None)];
+        let args = Cli::parse_from(["feluda", "--offline", "--licenses-dir", "out/licenses"]);
+        assert!(plan_endpoints(&args, &FeludaConfig::default(), &roots).is_empty());
+
+        let remote = Cli::try_parse_from(["feluda", "--offline", "--repo", "https://x.test/r"]);
+        assert!(remote.is_err());
+    }
+
+    #[test]
+    fn test_plan_policy() {
+        let args = Cli::parse_from([
+            "feluda",
+            "--project-license",
+            "MIT",
+            "--fail-on-restrictive",
+            "--no-vendor-scan",
+        ]);
+        let policy = plan_policy(&args, &FeludaConfig::default(), Path::new("."));
+        let value = |name: &str| {
+            policy
+                .iter()
+                .find(|(row, _)| *row == name)
+                .map(|(_, value)| value.clone())
+                .unwrap()
+        };
+        assert_eq!(value("Project license"), "MIT (--project-license)");
+        assert_eq!(value("Fails on"), "restrictive");
+        assert!(!value("Tree walks").contains("vendored"));
+        assert_eq!(value("Dev dependencies"), "on");
+        assert_eq!(value("Denied"), "none");
+    }
+}
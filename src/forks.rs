@@ -0,0 +1,198 @@
+//! Upstream licenses for forked dependencies (`--resolve-forks`)
+//!
+//! Forks pulled in as git dependencies often lose the license metadata their upstream has: the
+//! manifest was never published, or GitHub can't detect a license file that was renamed. When
+//! such a dependency's license is unknown and its repository is a GitHub fork, the license of
+//! the repository it was forked from is used instead, and the dependency records which
+//! upstream it inherited the license from.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::debug::{log, LogLevel};
+use crate::licenses::{
+    fetch_licenses_from_github, get_osi_status, github_host, is_license_compatible,
+    is_license_restrictive, LicenseInfo,
+};
+use crate::resolver::{github_api_get, known_license, parse_github_repository};
+
+/// Fill in unknown licenses of forked GitHub dependencies from their upstream. Returns how
+/// many dependencies inherited a license.
+pub fn resolve_fork_licenses(
+    deps: &mut [LicenseInfo],
+    project_license: Option<&str>,
+    strict: bool,
+) -> usize {
+    let candidates: Vec<usize> = deps
+        .iter()
+        .enumerate()
+        .filter(|(_, dep)| has_unknown_license(dep) && fork_location(dep).is_some())
+        .map(|(index, _)| index)
+        .collect();
+    if candidates.is_empty() {
+        return 0;
+    }
+
+    let known_licenses = fetch_licenses_from_github().unwrap_or_else(|_| HashMap::new());
+    let mut inherited = 0;
+    for index in candidates {
+        if crate::cancel::is_cancelled() {
+            break;
+        }
+
+        let dep = &mut deps[index];
+        let Some((owner, repo)) = fork_location(dep) else {
+            continue;
+        };
+        let Some((upstream, license)) = upstream_license(&owner, &repo) else {
+            continue;
+        };
+
+        log(
+            LogLevel::Info,
+            &format!(
+                "{} is a fork of {upstream}, inheriting its {license} license",
+                dep.name
+            ),
+        );
+        if let Some(project) = project_license {
+            dep.compatibility = is_license_compatible(&license, project, strict);
+        }
+        dep.osi_status = get_osi_status(&license);
+        let license = Some(license);
+        dep.is_restrictive = is_license_restrictive(&license, &known_licenses, strict);
+        dep.license = license;
+        dep.inherited_from = Some(upstream);
+        inherited += 1;
+    }
+    inherited
+}
+
+fn has_unknown_license(dep: &LicenseInfo) -> bool {
+    dep.license
+        .as_ref()
+        .and_then(|license| known_license(license.clone()))
+        .is_none()
+}
+
+/// Where a dependency's code lives on GitHub: a git version spec (`github:owner/repo#ref`,
+/// `git+https://github.com/...`) or its repository URL
+fn fork_location(dep: &LicenseInfo) -> Option<(String, String)> {
+    let version = dep.version.as_str();
+    let host = github_host();
+    let from_version =
+        (version.starts_with("github:") || version.starts_with("git+") || version.contains(&host))
+            .then(|| parse_github_repository(version))
+            .flatten();
+
+    from_version.or_else(|| {
+        dep.repository
+            .as_deref()
+            .filter(|repository| repository.contains(&host))
+            .and_then(parse_github_repository)
+    })
+}
+
+/// The upstream (`owner/repo`) and its license, if `owner/repo` is a fork
+fn upstream_license(owner: &str, repo: &str) -> Option<(String, String)> {
+    let json = github_api_get(&format!("repos/{owner}/{repo}"))?;
+    let (upstream, license) = upstream_from_repo_json(&json)?;
+    match license {
+        Some(license) => Some((upstream, license)),
+        // The repos endpoint leaves `license` null when detection hasn't run; ask directly
+        None => {
+            let json = github_api_get(&format!("repos/{upstream}/license"))?;
+            let license = json["license"]["spdx_id"].as_str()?.to_string();
+            known_license(license).map(|license| (upstream, license))
+        }
+    }
+}
+
+/// Parent repo name and (when GitHub reports one) license from a `GET /repos/...` response.
+/// `None` unless the repo is a fork.
+fn upstream_from_repo_json(json: &Value) -> Option<(String, Option<String>)> {
+    if !json["fork"].as_bool().unwrap_or(false) {
+        return None;
+    }
+    let parent = &json["parent"];
+    let upstream = parent["full_name"].as_str()?.to_string();
+    let license = parent["license"]["spdx_id"]
+        .as_str()
+        .and_then(|license| known_license(license.to_string()));
+    Some((upstream, license))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fork_location() {
+        assert_eq!(
+            fork_location(&LicenseInfo::fixture(
+                "left-pad",
+                "github:someone/left-pad#fix",
+                None
+            )),
+            Some(("someone".to_string(), "left-pad".to_string()))
+        );
+        assert_eq!(
+            fork_location(
+                &LicenseInfo::fixture("left-pad", "0.1.0", None)
+                    .with_repository("https://github.com/someone/left-pad")
+            ),
+            Some(("someone".to_string(), "left-pad".to_string()))
+        );
+        assert_eq!(
+            fork_location(
+                &LicenseInfo::fixture("left-pad", "1.3.0", None)
+                    .with_repository("https://www.npmjs.com/package/left-pad")
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_has_unknown_license() {
+        assert!(has_unknown_license(&LicenseInfo::fixture(
+            "left-pad", "1.0.0", None
+        )));
+        assert!(has_unknown_license(&LicenseInfo::fixture(
+            "left-pad",
+            "1.0.0",
+            "Unknown (failed to retrieve)"
+        )));
+        assert!(!has_unknown_license(&LicenseInfo::fixture(
+            "left-pad", "1.0.0", "MIT"
+        )));
+    }
+
+    #[test]
+    fn test_upstream_from_repo_json() {
+        let fork = serde_json::json!({
+            "fork": true,
+            "license": null,
+            "parent": {
+                "full_name": "stevemao/left-pad",
+                "license": { "spdx_id": "WTFPL" }
+            }
+        });
+        assert_eq!(
+            upstream_from_repo_json(&fork),
+            Some(("stevemao/left-pad".to_string(), Some("WTFPL".to_string())))
+        );
+
+        let undetected = serde_json::json!({
+            "fork": true,
+            "parent": { "full_name": "stevemao/left-pad", "license": { "spdx_id": "NOASSERTION" } }
+        });
+        assert_eq!(
+            upstream_from_repo_json(&undetected),
+            Some(("stevemao/left-pad".to_string(), None))
+        );
+
+        let original = serde_json::json!({ "fork": false, "license": { "spdx_id": "MIT" } });
+        assert_eq!(upstream_from_repo_json(&original), None);
+    }
+}
@@ -1,8 +1,8 @@
 use crate::cli::with_spinner;
 use crate::debug::{log, log_debug, LogLevel};
 use crate::licenses::{
-    detect_project_license, is_license_compatible, read_license_text_in_dir, LicenseCompatibility,
-    LicenseInfo,
+    detect_project_license, is_license_compatible, read_license_text_in_dir,
+    read_notice_text_in_dir, LicenseCompatibility, LicenseInfo,
 };
 use crate::parser::parse_root;
 use colored::*;
@@ -610,32 +610,52 @@ fn fetch_license_from_local_cache(
     name: &str,
     version: &str,
     project_root: &Path,
+) -> Option<String> {
+    read_from_local_cache(name, version, project_root, read_license_text_in_dir)
+}
+
+/// Read a dependency's NOTICE file from the local toolchain caches. Apache-2.0 section 4(d)
+/// asks redistributors to pass it on; packages without one return `None`.
+pub fn fetch_notice_content(name: &str, version: &str, project_root: &Path) -> Option<String> {
+    read_from_local_cache(name, version, project_root, read_notice_text_in_dir)
+}
+
+/// Run `read` on the dependency's directory in each ecosystem's local cache, first hit wins
+fn read_from_local_cache(
+    name: &str,
+    version: &str,
+    project_root: &Path,
+    read: fn(&Path) -> Option<String>,
 ) -> Option<String> {
     // Go module cache: $GOMODCACHE/<escaped-module>@<version>/
-    if let Some(text) = local_license_from_go_cache(name, version) {
+    if let Some(text) = local_license_from_go_cache(name, version, read) {
         return Some(text);
     }
     // Cargo registry sources: $CARGO_HOME/registry/src/<index>/<name>-<version>/
-    if let Some(text) = local_license_from_cargo_cache(name, version) {
+    if let Some(text) = local_license_from_cargo_cache(name, version, read) {
         return Some(text);
     }
     // Python site-packages: <site-packages>/<name>/ or <name>-<version>.dist-info/
-    if let Some(text) = local_license_from_python_cache(name, version) {
+    if let Some(text) = local_license_from_python_cache(name, version, read) {
         return Some(text);
     }
     // Node modules: <project>/node_modules/<name>/
-    if let Some(text) = local_license_from_node_modules(name, project_root) {
+    if let Some(text) = local_license_from_node_modules(name, project_root, read) {
         return Some(text);
     }
     None
 }
 
-/// Locate a Go module in the local module cache and read its license text.
+/// Locate a Go module in the local module cache and `read` its license text (or NOTICE).
 ///
 /// Reuses the same cache-path resolution and case-escaping the Go analyzer uses, so the two stay
 /// in lockstep. Go module paths always contain a slash (e.g. `github.com/gin-gonic/gin`), which
 /// cheaply rules out non-Go names before touching the filesystem.
-fn local_license_from_go_cache(name: &str, version: &str) -> Option<String> {
+fn local_license_from_go_cache(
+    name: &str,
+    version: &str,
+    read: fn(&Path) -> Option<String>,
+) -> Option<String> {
     if !name.contains('/') {
         return None;
     }
@@ -643,7 +663,7 @@ fn local_license_from_go_cache(name: &str, version: &str) -> Option<String> {
     let cache = crate::languages::go::get_gomodcache_path()?;
 
     let exact = crate::languages::go::build_module_cache_path(&cache, name, version);
-    if let Some(text) = read_license_text_in_dir(&exact) {
+    if let Some(text) = read(&exact) {
         return Some(text);
     }
 
@@ -657,7 +677,7 @@ fn local_license_from_go_cache(name: &str, version: &str) -> Option<String> {
             .and_then(|n| n.to_str())
             .is_some_and(|n| n.starts_with(&prefix));
         if is_match {
-            if let Some(text) = read_license_text_in_dir(&path) {
+            if let Some(text) = read(&path) {
                 return Some(text);
             }
         }
@@ -666,18 +686,22 @@ fn local_license_from_go_cache(name: &str, version: &str) -> Option<String> {
     None
 }
 
-/// Locate a crate in the local Cargo registry cache and read its license text.
+/// Locate a crate in the local Cargo registry cache and `read` its license text (or NOTICE).
 ///
 /// `registry/src` holds one subdirectory per registry index (e.g. `index.crates.io-<hash>`), so
 /// we scan each for a `<name>-<version>` crate source directory.
-fn local_license_from_cargo_cache(name: &str, version: &str) -> Option<String> {
+fn local_license_from_cargo_cache(
+    name: &str,
+    version: &str,
+    read: fn(&Path) -> Option<String>,
+) -> Option<String> {
     let src_root = cargo_home_dir()?.join("registry").join("src");
     let crate_dir = format!("{name}-{version}");
 
     for entry in fs::read_dir(&src_root).ok()?.flatten() {
         let candidate = entry.path().join(&crate_dir);
         if candidate.is_dir() {
-            if let Some(text) = read_license_text_in_dir(&candidate) {
+            if let Some(text) = read(&candidate) {
                 return Some(text);
             }
         }
@@ -695,12 +719,16 @@ fn cargo_home_dir() -> Option<PathBuf> {
     Some(PathBuf::from(home).join(".cargo"))
 }
 
-/// Locate a Python package in the local site-packages and read its license text.
+/// Locate a Python package in the local site-packages and `read` its license text (or NOTICE).
 ///
 /// Tries the importable package directory (with the usual `-`/`_` normalization) and the wheel
 /// metadata directory (`<name>-<version>.dist-info/`), which is where `pip` drops the bundled
 /// `LICENSE` file.
-fn local_license_from_python_cache(name: &str, version: &str) -> Option<String> {
+fn local_license_from_python_cache(
+    name: &str,
+    version: &str,
+    read: fn(&Path) -> Option<String>,
+) -> Option<String> {
     let underscored = name.replace('-', "_");
 
     for site in crate::languages::python::get_python_site_packages_paths() {
@@ -712,7 +740,7 @@ fn local_license_from_python_cache(name: &str, version: &str) -> Option<String>
         ];
         for dir in candidates {
             if dir.is_dir() {
-                if let Some(text) = read_license_text_in_dir(&dir) {
+                if let Some(text) = read(&dir) {
                     return Some(text);
                 }
             }
@@ -722,20 +750,28 @@ fn local_license_from_python_cache(name: &str, version: &str) -> Option<String>
     None
 }
 
-/// Locate a Node package in the project's `node_modules` and read its license text.
+/// Locate a Node package in the project's `node_modules` and `read` its license text (or NOTICE).
 ///
 /// Scoped names (`@scope/pkg`) map to `node_modules/@scope/pkg`, which `Path::join` produces
 /// correctly from the slash in the name.
-fn local_license_from_node_modules(name: &str, project_root: &Path) -> Option<String> {
+fn local_license_from_node_modules(
+    name: &str,
+    project_root: &Path,
+    read: fn(&Path) -> Option<String>,
+) -> Option<String> {
     let dir = project_root.join("node_modules").join(name);
     if dir.is_dir() {
-        return read_license_text_in_dir(&dir);
+        return read(&dir);
     }
     None
 }
 
 /// Fetch the actual license content for a dependency
-fn fetch_actual_license_content(name: &str, version: &str, project_root: &Path) -> Option<String> {
+pub fn fetch_actual_license_content(
+    name: &str,
+    version: &str,
+    project_root: &Path,
+) -> Option<String> {
     log(
         LogLevel::Info,
         &format!("Attempting to fetch actual license content for {name} v{version}"),
@@ -1506,17 +1542,18 @@ pub fn handle_generate_command(
     }
 
     // Parse and analyze dependencies
-    let mut analyzed_data = match parse_root(&path, language.as_deref(), false, false) {
-        Ok(data) => data,
-        Err(e) => {
-            println!("{} Failed to parse dependencies: {}", "❌".red().bold(), e);
-            log(
-                LogLevel::Error,
-                &format!("Failed to parse dependencies: {e}"),
-            );
-            return;
-        }
-    };
+    let mut analyzed_data =
+        match parse_root(&path, language.as_deref(), &[], false, false, None, false) {
+            Ok(data) => data,
+            Err(e) => {
+                println!("{} Failed to parse dependencies: {}", "❌".red().bold(), e);
+                log(
+                    LogLevel::Error,
+                    &format!("Failed to parse dependencies: {e}"),
+                );
+                return;
+            }
+        };
 
     log_debug("Analyzed dependencies for generate command", &analyzed_data);
 
@@ -1587,19 +1624,17 @@ mod tests {
                 name: "serde".to_string(),
                 version: "1.0.151".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "tokio".to_string(),
                 version: "1.0.2".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ]
     }
@@ -1834,28 +1869,25 @@ mod tests {
                 name: "package1".to_string(),
                 version: "1.0.0".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "package2".to_string(),
                 version: "2.0.0".to_string(),
                 license: Some("Apache-2.0".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "package3".to_string(),
                 version: "1.5.0".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ];
 
@@ -1898,11 +1930,8 @@ mod tests {
         let test_data = vec![LicenseInfo {
             name: "unknown_package".to_string(),
             version: "1.0.0".to_string(),
-            license: None,
             is_restrictive: true,
-            compatibility: LicenseCompatibility::Unknown,
-            osi_status: crate::licenses::OsiStatus::Unknown,
-            sub_project: None,
+            ..Default::default()
         }];
 
         let content = generate_notice_content(&test_data);
@@ -1939,10 +1968,9 @@ mod tests {
             name: "test_package".to_string(),
             version: "1.0.0".to_string(),
             license: Some("MIT".to_string()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: crate::licenses::OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         }];
 
         generate_notice_file(&license_data, path);
@@ -1971,10 +1999,9 @@ mod tests {
             name: "new_package".to_string(),
             version: "2.0.0".to_string(),
             license: Some("Apache-2.0".to_string()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: crate::licenses::OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         }];
 
         generate_notice_file(&license_data, path);
@@ -1995,10 +2022,9 @@ mod tests {
             name: "test_package".to_string(),
             version: "1.0.0".to_string(),
             license: Some("MIT".to_string()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: crate::licenses::OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         }];
 
         generate_third_party_licenses_file(&license_data, path);
@@ -2057,7 +2083,8 @@ mod tests {
         fs::create_dir_all(&pkg_dir).unwrap();
         fs::write(pkg_dir.join("LICENSE"), "The MIT License\n\nCopyright (c)").unwrap();
 
-        let text = local_license_from_node_modules("left-pad", temp.path());
+        let text =
+            local_license_from_node_modules("left-pad", temp.path(), read_license_text_in_dir);
         assert!(text.is_some());
         assert!(text.unwrap().contains("MIT License"));
     }
@@ -2069,7 +2096,8 @@ mod tests {
         fs::create_dir_all(&pkg_dir).unwrap();
         fs::write(pkg_dir.join("LICENSE"), "Apache License\nVersion 2.0").unwrap();
 
-        let text = local_license_from_node_modules("@scope/pkg", temp.path());
+        let text =
+            local_license_from_node_modules("@scope/pkg", temp.path(), read_license_text_in_dir);
         assert!(text.is_some());
         assert!(text.unwrap().contains("Apache License"));
     }
@@ -2077,7 +2105,10 @@ mod tests {
     #[test]
     fn test_local_license_from_node_modules_missing_returns_none() {
         let temp = TempDir::new().unwrap();
-        assert!(local_license_from_node_modules("absent", temp.path()).is_none());
+        assert!(
+            local_license_from_node_modules("absent", temp.path(), read_license_text_in_dir)
+                .is_none()
+        );
     }
 
     #[test]
@@ -0,0 +1,760 @@
+//! `feluda history`: dependency licenses over time
+//!
+//! `feluda history record` scans the project, or reads a saved report, and stores every
+//! dependency's license as a snapshot in a local SQLite database (`.feluda/history.db` by
+//! default), tagged with the commit that was scanned. `feluda history report` orders the
+//! snapshots by commit date and shows, for each dependency that is restrictive in the latest
+//! one, the scan and commit that introduced it, and which restrictive dependencies have gone
+//! since. Older commits can be recorded after the fact with `--rev`, so a history can be
+//! backfilled from `git rev-list`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use colored::*;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use tempfile::TempDir;
+
+use crate::cli::HistoryCommand;
+use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
+use crate::licenses::{LicenseCompatibility, LicenseInfo};
+use crate::report_archive::load_report_with_metadata;
+use crate::report_metadata::{GitState, ReportMetadata};
+use crate::{analyze_dependencies, annotate_compatibility, Analysis, CheckConfig};
+
+/// Database location relative to the project directory
+const DEFAULT_DB: &str = ".feluda/history.db";
+
+/// Bumped when the tables change; stored in `PRAGMA user_version`
+const SCHEMA_VERSION: i32 = 1;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS scans (
+    id INTEGER PRIMARY KEY,
+    recorded_at INTEGER NOT NULL,
+    commit_id TEXT,
+    branch TEXT,
+    dirty INTEGER NOT NULL DEFAULT 0,
+    committed_at INTEGER
+);
+CREATE TABLE IF NOT EXISTS dependencies (
+    scan_id INTEGER NOT NULL REFERENCES scans(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    version TEXT NOT NULL,
+    ecosystem TEXT,
+    license TEXT NOT NULL,
+    restrictive INTEGER NOT NULL,
+    compatibility TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS dependencies_scan ON dependencies(scan_id);
+";
+
+/// One recorded scan
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scan {
+    pub id: i64,
+    /// Unix seconds
+    pub recorded_at: i64,
+    pub commit: Option<String>,
+    pub branch: Option<String>,
+    /// The working tree had uncommitted changes
+    pub dirty: bool,
+    /// Unix seconds of the commit, when the repository knew it at record time
+    pub committed_at: Option<i64>,
+    pub dependencies: Vec<SnapshotDependency>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotDependency {
+    pub name: String,
+    pub version: String,
+    pub ecosystem: Option<String>,
+    pub license: String,
+    pub restrictive: bool,
+    pub compatibility: LicenseCompatibility,
+}
+
+impl SnapshotDependency {
+    fn from_license_info(dep: &LicenseInfo) -> Self {
+        Self {
+            name: dep.name.clone(),
+            version: dep.version.clone(),
+            ecosystem: dep.ecosystem.clone(),
+            license: dep.get_license(),
+            restrictive: dep.is_restrictive,
+            compatibility: dep.compatibility,
+        }
+    }
+
+    /// The same dependency across scans: its name within its ecosystem, whatever the version
+    fn key(&self) -> (String, String) {
+        (
+            self.ecosystem.clone().unwrap_or_default(),
+            self.name.clone(),
+        )
+    }
+}
+
+/// What `feluda history report` shows
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct HistoryReport {
+    pub scans: usize,
+    /// Restrictive in the latest scan, with the scan that introduced them
+    pub restrictive: Vec<Introduction>,
+    /// Restrictive in an earlier scan, but not in the latest one
+    pub resolved: Vec<Resolution>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Introduction {
+    pub name: String,
+    pub ecosystem: Option<String>,
+    pub version: String,
+    pub license: String,
+    /// First scan of the run of scans, up to the latest, it was restrictive in
+    pub introduced: ScanRef,
+    /// The scan before, which didn't have it (as restrictive); `None` if it always had
+    pub previous: Option<ScanRef>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Resolution {
+    pub name: String,
+    pub ecosystem: Option<String>,
+    pub license: String,
+    /// Last scan it was restrictive in
+    pub last_seen: ScanRef,
+    /// The scan after, where it was gone or no longer restrictive
+    pub resolved: ScanRef,
+}
+
+/// A scan and, when the repository still has it, its commit
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ScanRef {
+    pub scan: i64,
+    /// RFC 3339
+    pub recorded_at: String,
+    pub commit: Option<String>,
+    pub dirty: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub committed_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+pub fn handle_history_command(command: HistoryCommand, template: CheckConfig) -> FeludaResult<()> {
+    match command {
+        HistoryCommand::Record {
+            path,
+            rev,
+            report,
+            db,
+        } => {
+            let db = db_path(&path, db.as_deref());
+            let (dependencies, git) = match report {
+                Some(report) => {
+                    let (dependencies, metadata) = load_report_with_metadata(Path::new(&report))
+                        .inspect_err(|e| eprintln!("❌ {e}"))?;
+                    (dependencies, metadata.and_then(|metadata| metadata.git))
+                }
+                None => scan(&path, rev.as_deref(), template)?,
+            };
+            let mut connection = open(&db)?;
+            let committed_at = git
+                .as_ref()
+                .and_then(|git| commit_time(Path::new(&path), &git.commit));
+            let id = record(
+                &mut connection,
+                &dependencies,
+                git.as_ref(),
+                committed_at,
+                crate::cache::now_secs() as i64,
+            )?;
+
+            let restrictive = dependencies.iter().filter(|dep| dep.is_restrictive).count();
+            let commit = git
+                .as_ref()
+                .map(|git| format!(" at {}", short(&git.commit)))
+                .unwrap_or_default();
+            println!(
+                "{} {}",
+                "📼".bold(),
+                format!(
+                    "Recorded scan #{id}{commit}: {} dependencies, {restrictive} restrictive",
+                    dependencies.len()
+                )
+                .bold()
+            );
+            println!("   {}", db.display());
+            Ok(())
+        }
+        HistoryCommand::Report { path, db, json } => {
+            let db = db_path(&path, db.as_deref());
+            if !db.is_file() {
+                let message = format!(
+                    "No license history at {}; record scans with `feluda history record` first",
+                    db.display()
+                );
+                eprintln!("❌ {message}");
+                return Err(FeludaError::InvalidData(message));
+            }
+            let scans = load_scans(&open(&db)?)?;
+            let repo = git2::Repository::discover(&path).ok();
+            let report = history_report(&scans, |scan| scan_ref(scan, repo.as_ref()));
+
+            if json {
+                let output = serde_json::to_string_pretty(&report).map_err(|e| {
+                    FeludaError::Serialization(format!("Failed to serialize history: {e}"))
+                })?;
+                println!("{output}");
+            } else {
+                print_report(&report);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn db_path(path: &str, db: Option<&str>) -> PathBuf {
+    db.map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(path).join(DEFAULT_DB))
+}
+
+/// Scan `path`, or `rev` of its repository, with compatibility annotated like a report
+fn scan(
+    path: &str,
+    rev: Option<&str>,
+    template: CheckConfig,
+) -> FeludaResult<(Vec<LicenseInfo>, Option<GitState>)> {
+    let (scan_path, _checkout) = match rev {
+        Some(rev) => {
+            let checkout = TempDir::new().map_err(|e| {
+                FeludaError::TempDir(format!("Failed to create temporary directory: {e}"))
+            })?;
+            let scan_path =
+                crate::revision::checkout_revision(Path::new(path), rev, checkout.path())
+                    .inspect_err(|e| eprintln!("❌ {e}"))?;
+            (scan_path, Some(checkout))
+        }
+        None => (PathBuf::from(path), None),
+    };
+
+    let config = CheckConfig {
+        path: scan_path.to_string_lossy().to_string(),
+        ..template
+    };
+    let Analysis {
+        mut dependencies,
+        project_license,
+        ..
+    } = analyze_dependencies(&config)?;
+    annotate_compatibility(&mut dependencies, &project_license, config.strict);
+    // With --rev this is the checked-out commit, else the working tree's HEAD
+    let git = ReportMetadata::collect(Path::new(path)).git;
+    Ok((dependencies, git))
+}
+
+/// Open the database, creating it and its tables when needed
+pub fn open(db: &Path) -> FeludaResult<Connection> {
+    if let Some(dir) = db.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let connection = Connection::open(db).map_err(|e| database_error(db, e))?;
+    let version: i32 = connection
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| database_error(db, e))?;
+    if version > SCHEMA_VERSION {
+        return Err(FeludaError::InvalidData(format!(
+            "{} was written by a newer Feluda (schema {version}); upgrade to read it",
+            db.display()
+        )));
+    }
+    connection
+        .execute_batch(SCHEMA)
+        .and_then(|_| connection.pragma_update(None, "user_version", SCHEMA_VERSION))
+        .and_then(|_| connection.pragma_update(None, "foreign_keys", true))
+        .map_err(|e| database_error(db, e))?;
+    log(
+        LogLevel::Info,
+        &format!("Opened license history {}", db.display()),
+    );
+    Ok(connection)
+}
+
+fn database_error(db: &Path, e: rusqlite::Error) -> FeludaError {
+    FeludaError::InvalidData(format!("License history {}: {e}", db.display()))
+}
+
+fn sql_error(e: rusqlite::Error) -> FeludaError {
+    FeludaError::InvalidData(format!("License history: {e}"))
+}
+
+/// Store one snapshot and return its scan id
+pub fn record(
+    connection: &mut Connection,
+    dependencies: &[LicenseInfo],
+    git: Option<&GitState>,
+    committed_at: Option<i64>,
+    recorded_at: i64,
+) -> FeludaResult<i64> {
+    let transaction = connection.transaction().map_err(sql_error)?;
+    transaction
+        .execute(
+            "INSERT INTO scans (recorded_at, commit_id, branch, dirty, committed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                recorded_at,
+                git.map(|git| &git.commit),
+                git.and_then(|git| git.branch.as_ref()),
+                git.is_some_and(|git| git.dirty),
+                committed_at
+            ],
+        )
+        .map_err(sql_error)?;
+    let id = transaction.last_insert_rowid();
+    {
+        let mut insert = transaction
+            .prepare(
+                "INSERT INTO dependencies
+                 (scan_id, name, version, ecosystem, license, restrictive, compatibility)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )
+            .map_err(sql_error)?;
+        for dep in dependencies
+            .iter()
+            .map(SnapshotDependency::from_license_info)
+        {
+            insert
+                .execute(params![
+                    id,
+                    dep.name,
+                    dep.version,
+                    dep.ecosystem,
+                    dep.license,
+                    dep.restrictive,
+                    compatibility_name(dep.compatibility)
+                ])
+                .map_err(sql_error)?;
+        }
+    }
+    transaction.commit().map_err(sql_error)?;
+    log(
+        LogLevel::Info,
+        &format!(
+            "Recorded scan {id} with {} dependencies",
+            dependencies.len()
+        ),
+    );
+    Ok(id)
+}
+
+/// Every scan with its dependencies, oldest commit first. Scans without a known commit date
+/// are placed by when they were recorded.
+pub fn load_scans(connection: &Connection) -> FeludaResult<Vec<Scan>> {
+    let mut scans: Vec<Scan> = connection
+        .prepare(
+            "SELECT id, recorded_at, commit_id, branch, dirty, committed_at FROM scans
+             ORDER BY COALESCE(committed_at, recorded_at), recorded_at, id",
+        )
+        .and_then(|mut statement| {
+            statement
+                .query_map([], |row| {
+                    Ok(Scan {
+                        id: row.get(0)?,
+                        recorded_at: row.get(1)?,
+                        commit: row.get(2)?,
+                        branch: row.get(3)?,
+                        dirty: row.get(4)?,
+                        committed_at: row.get(5)?,
+                        dependencies: Vec::new(),
+                    })
+                })?
+                .collect()
+        })
+        .map_err(sql_error)?;
+
+    let mut dependencies: BTreeMap<i64, Vec<SnapshotDependency>> = BTreeMap::new();
+    let mut statement = connection
+        .prepare(
+            "SELECT scan_id, name, version, ecosystem, license, restrictive, compatibility
+             FROM dependencies ORDER BY rowid",
+        )
+        .map_err(sql_error)?;
+    let rows = statement
+        .query_map([], |row| {
+            let compatibility: String = row.get(6)?;
+            Ok((
+                row.get::<_, i64>(0)?,
+                SnapshotDependency {
+                    name: row.get(1)?,
+                    version: row.get(2)?,
+                    ecosystem: row.get(3)?,
+                    license: row.get(4)?,
+                    restrictive: row.get(5)?,
+                    compatibility: parse_compatibility(&compatibility),
+                },
+            ))
+        })
+        .map_err(sql_error)?;
+    for row in rows {
+        let (scan, dep) = row.map_err(sql_error)?;
+        dependencies.entry(scan).or_default().push(dep);
+    }
+    for scan in &mut scans {
+        scan.dependencies = dependencies.remove(&scan.id).unwrap_or_default();
+    }
+    Ok(scans)
+}
+
+fn compatibility_name(compatibility: LicenseCompatibility) -> &'static str {
+    match compatibility {
+        LicenseCompatibility::Compatible => "compatible",
+        LicenseCompatibility::Incompatible => "incompatible",
+        LicenseCompatibility::Unknown => "unknown",
+    }
+}
+
+fn parse_compatibility(name: &str) -> LicenseCompatibility {
+    match name {
+        "compatible" => LicenseCompatibility::Compatible,
+        "incompatible" => LicenseCompatibility::Incompatible,
+        _ => LicenseCompatibility::Unknown,
+    }
+}
+
+/// When each dependency restrictive in the latest scan became so, and which restrictive ones
+/// went away. `describe` turns a scan into the reference shown for it.
+pub fn history_report(scans: &[Scan], describe: impl Fn(&Scan) -> ScanRef) -> HistoryReport {
+    let restrictive: Vec<BTreeMap<(String, String), &SnapshotDependency>> = scans
+        .iter()
+        .map(|scan| {
+            scan.dependencies
+                .iter()
+                .filter(|dep| dep.restrictive)
+                .map(|dep| (dep.key(), dep))
+                .collect()
+        })
+        .collect();
+    let mut report = HistoryReport {
+        scans: scans.len(),
+        ..HistoryReport::default()
+    };
+    let Some(latest) = restrictive.last() else {
+        return report;
+    };
+
+    for (key, dep) in latest {
+        let mut first = scans.len() - 1;
+        while first > 0 && restrictive[first - 1].contains_key(key) {
+            first -= 1;
+        }
+        report.restrictive.push(Introduction {
+            name: dep.name.clone(),
+            ecosystem: dep.ecosystem.clone(),
+            version: dep.version.clone(),
+            license: dep.license.clone(),
+            introduced: describe(&scans[first]),
+            previous: first.checked_sub(1).map(|index| describe(&scans[index])),
+        });
+    }
+
+    let gone: BTreeSet<&(String, String)> = restrictive
+        .iter()
+        .flat_map(|scan| scan.keys())
+        .filter(|key| !latest.contains_key(*key))
+        .collect();
+    for key in gone {
+        let last = restrictive
+            .iter()
+            .rposition(|scan| scan.contains_key(key))
+            .expect("key comes from a scan");
+        let dep = restrictive[last][key];
+        report.resolved.push(Resolution {
+            name: dep.name.clone(),
+            ecosystem: dep.ecosystem.clone(),
+            license: dep.license.clone(),
+            last_seen: describe(&scans[last]),
+            resolved: describe(&scans[last + 1]),
+        });
+    }
+
+    report
+        .restrictive
+        .sort_by(|a, b| (a.introduced.scan, &a.name).cmp(&(b.introduced.scan, &b.name)));
+    report
+        .resolved
+        .sort_by(|a, b| (a.resolved.scan, &a.name).cmp(&(b.resolved.scan, &b.name)));
+    report
+}
+
+/// The reference for a scan, with its commit's author and summary when `repo` has it
+fn scan_ref(scan: &Scan, repo: Option<&git2::Repository>) -> ScanRef {
+    let commit = scan.commit.as_deref().and_then(|id| {
+        let oid = git2::Oid::from_str(id).ok()?;
+        repo?.find_commit(oid).ok()
+    });
+    ScanRef {
+        scan: scan.id,
+        recorded_at: rfc3339(scan.recorded_at),
+        commit: scan.commit.clone(),
+        dirty: scan.dirty,
+        committed_at: scan
+            .committed_at
+            .or_else(|| commit.as_ref().map(|commit| commit.time().seconds()))
+            .map(rfc3339),
+        author: commit
+            .as_ref()
+            .and_then(|commit| commit.author().name().map(str::to_string)),
+        summary: commit
+            .as_ref()
+            .and_then(|commit| commit.summary().map(str::to_string)),
+    }
+}
+
+/// Commit time of `commit` in the repository containing `path`
+fn commit_time(path: &Path, commit: &str) -> Option<i64> {
+    let repo = git2::Repository::discover(path).ok()?;
+    let commit = repo.find_commit(git2::Oid::from_str(commit).ok()?).ok()?;
+    Some(commit.time().seconds())
+}
+
+fn rfc3339(secs: i64) -> String {
+    chrono::DateTime::from_timestamp(secs, 0)
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_default()
+}
+
+fn short(commit: &str) -> &str {
+    &commit[..commit.len().min(12)]
+}
+
+/// `abc123def456 (2026-03-01, "Add charts", Jane Doe)`, or the scan when it has no commit
+fn describe(scan: &ScanRef) -> String {
+    let Some(ref commit) = scan.commit else {
+        return format!("scan #{} ({})", scan.scan, date(&scan.recorded_at));
+    };
+    let mut details = vec![date(
+        scan.committed_at.as_ref().unwrap_or(&scan.recorded_at),
+    )];
+    if let Some(ref summary) = scan.summary {
+        details.push(format!("\"{summary}\""));
+    }
+    if let Some(ref author) = scan.author {
+        details.push(author.clone());
+    }
+    if scan.dirty {
+        details.push("uncommitted changes".to_string());
+    }
+    format!("{} ({})", short(commit), details.join(", "))
+}
+
+fn date(rfc3339: &str) -> String {
+    rfc3339.get(..10).unwrap_or(rfc3339).to_string()
+}
+
+fn print_report(report: &HistoryReport) {
+    println!(
+        "{} {}",
+        "📼".bold(),
+        format!(
+            "License history: {} scan{}",
+            report.scans,
+            if report.scans == 1 { "" } else { "s" }
+        )
+        .bold()
+    );
+
+    if report.restrictive.is_empty() {
+        println!("\nNo restrictive dependencies in the latest scan");
+    } else {
+        println!(
+            "\n{}",
+            "Restrictive dependencies, by when they came in".bold()
+        );
+        for dep in &report.restrictive {
+            println!(
+                "  • {} {} ({})",
+                dep.name.as_str().bold(),
+                dep.version,
+                dep.license.as_str().red()
+            );
+            let introduced = match dep.previous {
+                Some(ref previous) => format!(
+                    "introduced in {}, after {}",
+                    describe(&dep.introduced),
+                    short(previous.commit.as_deref().unwrap_or("an unrecorded commit"))
+                ),
+                None => format!(
+                    "present since the first recorded scan, {}",
+                    describe(&dep.introduced)
+                ),
+            };
+            println!("      {introduced}");
+        }
+    }
+
+    if !report.resolved.is_empty() {
+        println!("\n{}", "No longer restrictive".bold());
+        for dep in &report.resolved {
+            println!(
+                "  • {} ({}): gone in {}",
+                dep.name.as_str().bold(),
+                dep.license,
+                describe(&dep.resolved)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(commit: &str) -> GitState {
+        GitState {
+            commit: commit.to_string(),
+            branch: Some("main".to_string()),
+            dirty: false,
+        }
+    }
+
+    #[test]
+    fn test_record_and_load_scans() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = dir.path().join("history/feluda.db");
+        let mut connection = open(&db).unwrap();
+
+        // Backfilled out of order: the commit dates decide the order
+        record(
+            &mut connection,
+            &[LicenseInfo::fixture("chart", "3.0.0", "AGPL-3.0")
+                .restrictive()
+                .with_compatibility(LicenseCompatibility::Compatible)
+                .with_ecosystem("node")],
+            Some(&git("bbbb")),
+            Some(2_000),
+            10_000,
+        )
+        .unwrap();
+        record(
+            &mut connection,
+            &[LicenseInfo::fixture("chart", "2.0.0", "MIT").with_ecosystem("node")],
+            Some(&git("aaaa")),
+            Some(1_000),
+            11_000,
+        )
+        .unwrap();
+        record(&mut connection, &[], None, None, 12_000).unwrap();
+        drop(connection);
+
+        let scans = load_scans(&open(&db).unwrap()).unwrap();
+        assert_eq!(
+            scans
+                .iter()
+                .map(|scan| scan.commit.as_deref())
+                .collect::<Vec<_>>(),
+            vec![Some("aaaa"), Some("bbbb"), None]
+        );
+        assert_eq!(scans[1].branch.as_deref(), Some("main"));
+        assert_eq!(
+            scans[1].dependencies,
+            vec![SnapshotDependency {
+                name: "chart".to_string(),
+                version: "3.0.0".to_string(),
+                ecosystem: Some("node".to_string()),
+                license: "AGPL-3.0".to_string(),
+                restrictive: true,
+                compatibility: LicenseCompatibility::Compatible,
+            }]
+        );
+        assert!(scans[2].dependencies.is_empty());
+    }
+
+    #[test]
+    fn test_history_report() {
+        let snapshot = |id: i64, deps: &[LicenseInfo]| Scan {
+            id,
+            recorded_at: id * 100,
+            commit: Some(format!("commit{id}")),
+            branch: None,
+            dirty: false,
+            committed_at: None,
+            dependencies: deps
+                .iter()
+                .map(SnapshotDependency::from_license_info)
+                .collect(),
+        };
+        let scans = vec![
+            snapshot(
+                1,
+                &[
+                    LicenseInfo::fixture("readline", "8.1", "GPL-3.0")
+                        .restrictive()
+                        .with_ecosystem("node"),
+                    LicenseInfo::fixture("left-pad", "1.0.0", "WTFPL")
+                        .restrictive()
+                        .with_ecosystem("node"),
+                ],
+            ),
+            snapshot(
+                2,
+                &[
+                    LicenseInfo::fixture("readline", "8.2", "GPL-3.0")
+                        .restrictive()
+                        .with_ecosystem("node"),
+                    LicenseInfo::fixture("chart", "3.0.0", "AGPL-3.0")
+                        .restrictive()
+                        .with_ecosystem("node"),
+                ],
+            ),
+            snapshot(
+                3,
+                &[
+                    LicenseInfo::fixture("readline", "8.2", "GPL-3.0")
+                        .restrictive()
+                        .with_ecosystem("node"),
+                    LicenseInfo::fixture("chart", "3.1.0", "AGPL-3.0")
+                        .restrictive()
+                        .with_ecosystem("node"),
+                    LicenseInfo::fixture("sdk", "1.0.0", "MIT").with_ecosystem("node"),
+                ],
+            ),
+        ];
+        let report = history_report(&scans, |scan| scan_ref(scan, None));
+
+        assert_eq!(report.scans, 3);
+        let introduced: Vec<(&str, &str, i64, Option<i64>)> = report
+            .restrictive
+            .iter()
+            .map(|dep| {
+                (
+                    dep.name.as_str(),
+                    dep.version.as_str(),
+                    dep.introduced.scan,
+                    dep.previous.as_ref().map(|scan| scan.scan),
+                )
+            })
+            .collect();
+        assert_eq!(
+            introduced,
+            vec![("readline", "8.2", 1, None), ("chart", "3.1.0", 2, Some(1))]
+        );
+        assert_eq!(
+            report.restrictive[1].introduced.commit.as_deref(),
+            Some("commit2")
+        );
+
+        assert_eq!(report.resolved.len(), 1);
+        assert_eq!(report.resolved[0].name, "left-pad");
+        assert_eq!(report.resolved[0].last_seen.scan, 1);
+        assert_eq!(report.resolved[0].resolved.scan, 2);
+
+        assert_eq!(
+            history_report(&[], |scan| scan_ref(scan, None)),
+            HistoryReport::default()
+        );
+    }
+}
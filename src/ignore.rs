@@ -0,0 +1,447 @@
+//! Dependencies left out of a scan on purpose
+//!
+//! Ignore rules come from `[[dependencies.ignore]]` in `.feluda.toml`, from a
+//! `.feludaignore` file in the scanned directory, one dependency per line:
+//!
+//! ```text
+//! # name[@version]  # reason
+//! left-pad@1.3.0    # vendored and relicensed, see legal ticket 42
+//! @myorg/internal-ui  # first-party package published to npm
+//! ```
+//!
+//! and from suppression comments in the manifest declaring a direct dependency, on the line
+//! itself or in the comments right above it:
+//!
+//! ```toml
+//! # feluda: ignore reason="internal fork"
+//! acme-crypto = "2.1"
+//! ```
+//!
+//! Matched dependencies are dropped before the report is built, but recorded so the report can
+//! list them, with their reasons, in an "ignored" section of their own.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use colored::*;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::config::{DependencyConfig, IgnoreDependency, PROJECT_CONFIG_FILE};
+use crate::debug::{log, record_parse_warning, LogLevel};
+use crate::licenses::LicenseInfo;
+
+/// Ignore file read from the root of the scanned directory
+pub const IGNORE_FILE: &str = ".feludaignore";
+
+// Dependencies dropped by an ignore rule during a scan
+static IGNORED_DEPENDENCIES: Mutex<Vec<IgnoredDependency>> = Mutex::new(Vec::new());
+
+/// A dependency an ignore rule removed from the scan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IgnoredDependency {
+    pub name: String,
+    pub version: String,
+    /// Why it's ignored, as given by the rule (empty when the rule gave none)
+    pub reason: String,
+    /// File the matching rule came from
+    pub source: String,
+}
+
+/// An ignore rule and where it was written
+#[derive(Debug, Clone)]
+pub struct SourcedRule {
+    pub rule: IgnoreDependency,
+    /// `.feludaignore`, or `file:line` of a manifest suppression comment
+    pub source: String,
+}
+
+/// Read the ignore rules in `dir`'s `.feludaignore`, if it has one
+pub fn load_ignore_file(dir: &Path) -> Vec<SourcedRule> {
+    let path = dir.join(IGNORE_FILE);
+    match fs::read_to_string(&path) {
+        Ok(content) => {
+            let rules: Vec<SourcedRule> = parse_ignore_file(&content)
+                .into_iter()
+                .map(|rule| SourcedRule {
+                    rule,
+                    source: IGNORE_FILE.to_string(),
+                })
+                .collect();
+            log(
+                LogLevel::Info,
+                &format!(
+                    "Loaded {} ignore rules from {}",
+                    rules.len(),
+                    path.display()
+                ),
+            );
+            rules
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => {
+            record_parse_warning(path.display().to_string(), err.to_string());
+            Vec::new()
+        }
+    }
+}
+
+/// Parse `.feludaignore` lines: `name[@version]`, optionally followed by `# reason`
+fn parse_ignore_file(content: &str) -> Vec<IgnoreDependency> {
+    content
+        .lines()
+        .filter_map(|line| {
+            // A reason is a comment after the entry; `#` only starts one at the start of the
+            // line or after whitespace, so `github.com/org/repo#v2`-style names survive
+            let comment = line.char_indices().find(|&(index, c)| {
+                c == '#' && (index == 0 || line[..index].ends_with(char::is_whitespace))
+            });
+            let (entry, reason) = match comment {
+                Some((index, _)) => (&line[..index], line[index + 1..].trim()),
+                None => (line, ""),
+            };
+            let entry = entry.trim();
+            if entry.is_empty() {
+                return None;
+            }
+
+            // Scoped npm names start with `@`, so only a later `@` splits off the version
+            let (name, version) = match entry.rfind('@') {
+                Some(index) if index > 0 => (&entry[..index], &entry[index + 1..]),
+                _ => (entry, ""),
+            };
+            Some(IgnoreDependency {
+                name: name.to_string(),
+                version: version.to_string(),
+                reason: reason.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Rules from the suppression comments on the declarations of `dependencies`
+///
+/// Each dependency's `declared_in` manifest (relative to `scan_root`) is read once; a
+/// `feluda: ignore` comment on the declaring line, or among the comment lines directly above
+/// it, ignores that dependency at its scanned version.
+pub fn inline_suppressions(dependencies: &[LicenseInfo], scan_root: &Path) -> Vec<SourcedRule> {
+    let mut manifests: HashMap<&str, Option<Vec<String>>> = HashMap::new();
+    let mut rules = Vec::new();
+    for dep in dependencies {
+        let Some(location) = dep.declared_in() else {
+            continue;
+        };
+        let lines = manifests.entry(location.file.as_str()).or_insert_with(|| {
+            fs::read_to_string(scan_root.join(&location.file))
+                .ok()
+                .map(|content| content.lines().map(str::to_string).collect())
+        });
+        let Some(lines) = lines else {
+            continue;
+        };
+        if let Some(reason) = suppression_reason(lines, location.line - 1) {
+            rules.push(SourcedRule {
+                rule: IgnoreDependency {
+                    name: dep.name.clone(),
+                    version: dep.version.clone(),
+                    reason,
+                },
+                source: format!("{}:{}", location.file, location.line),
+            });
+        }
+    }
+    if !rules.is_empty() {
+        log(
+            LogLevel::Info,
+            &format!("Found {} inline suppression comments", rules.len()),
+        );
+    }
+    rules
+}
+
+/// `# feluda: ignore reason="..."` (or `//` in go.mod), with an optional reason
+fn suppression_marker() -> &'static Regex {
+    static MARKER: OnceLock<Regex> = OnceLock::new();
+    MARKER.get_or_init(|| {
+        Regex::new(r#"(?:#|//)\s*feluda:\s*ignore\b(?:\s+reason\s*=\s*"([^"]*)")?"#).unwrap()
+    })
+}
+
+/// The reason given by a suppression comment on line `index` (0-based) or in the comment
+/// block right above it; `None` when the declaration isn't suppressed
+fn suppression_reason(lines: &[String], index: usize) -> Option<String> {
+    let is_comment = |line: &str| {
+        let line = line.trim_start();
+        line.starts_with('#') || line.starts_with("//")
+    };
+    let above = lines[..index.min(lines.len())]
+        .iter()
+        .rev()
+        .take_while(|line| is_comment(line));
+
+    lines
+        .get(index)
+        .into_iter()
+        .chain(above)
+        .find_map(|line| suppression_marker().captures(line))
+        .map(|captures| {
+            captures
+                .get(1)
+                .map(|reason| reason.as_str().trim().to_string())
+                .unwrap_or_default()
+        })
+}
+
+/// Drop the dependencies an ignore rule matches, recording each for the report
+///
+/// `.feluda.toml` rules are checked before `rules`, so a dependency matched by both is
+/// attributed to the configuration.
+pub fn remove_ignored(
+    dependencies: &mut Vec<LicenseInfo>,
+    config: &DependencyConfig,
+    rules: &[SourcedRule],
+) {
+    let before = dependencies.len();
+    let mut ignored = Vec::new();
+    dependencies.retain(|dep| {
+        let rule = config
+            .ignore_rule(&dep.name, Some(&dep.version))
+            .map(|rule| (rule, PROJECT_CONFIG_FILE))
+            .or_else(|| {
+                rules
+                    .iter()
+                    .find(|sourced| sourced.rule.matches(&dep.name, Some(&dep.version)))
+                    .map(|sourced| (&sourced.rule, sourced.source.as_str()))
+            });
+        match rule {
+            Some((rule, source)) => {
+                ignored.push(IgnoredDependency {
+                    name: dep.name.clone(),
+                    version: dep.version.clone(),
+                    reason: rule.reason.clone(),
+                    source: source.to_string(),
+                });
+                false
+            }
+            None => true,
+        }
+    });
+
+    if !ignored.is_empty() {
+        log(
+            LogLevel::Info,
+            &format!(
+                "Filtered out {} ignored dependencies, {} remaining",
+                before - dependencies.len(),
+                dependencies.len()
+            ),
+        );
+    }
+    if let Ok(mut recorded) = IGNORED_DEPENDENCIES.lock() {
+        for dep in ignored {
+            if !recorded.contains(&dep) {
+                recorded.push(dep);
+            }
+        }
+    }
+}
+
+/// Drain the dependencies ignored since the last call
+pub fn take_ignored_dependencies() -> Vec<IgnoredDependency> {
+    IGNORED_DEPENDENCIES
+        .lock()
+        .map(|mut ignored| std::mem::take(&mut *ignored))
+        .unwrap_or_default()
+}
+
+/// List the dependencies ignore rules left out of the report, with their reasons.
+///
+/// Written to stderr like the parse warnings, so JSON/YAML on stdout stays valid.
+pub fn print_ignored_dependencies(ignored: &[IgnoredDependency]) {
+    if ignored.is_empty() {
+        return;
+    }
+
+    eprintln!(
+        "{} {}",
+        "🙈".bold(),
+        format!(
+            "Ignored: {} dependenc{} left out by ignore rules",
+            ignored.len(),
+            if ignored.len() == 1 { "y" } else { "ies" }
+        )
+        .bold()
+    );
+    for dep in ignored {
+        let reason = if dep.reason.is_empty() {
+            "no reason given".dimmed().to_string()
+        } else {
+            dep.reason.clone()
+        };
+        eprintln!(
+            "  • {} {}: {reason} {}",
+            dep.name.as_str().bold(),
+            dep.version,
+            format!("({})", dep.source).dimmed()
+        );
+    }
+    eprintln!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, version: &str, reason: &str) -> IgnoreDependency {
+        IgnoreDependency {
+            name: name.to_string(),
+            version: version.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_ignore_file() {
+        let rules = parse_ignore_file(
+            "# Audited exceptions\n\
+             \n\
+             left-pad@1.3.0  # vendored, see legal#42\n\
+             @myorg/ui\t# first-party\n\
+             @babel/core@7.23.0\n\
+             github.com/acme/tool#v2\n",
+        );
+        assert_eq!(
+            rules
+                .iter()
+                .map(|r| (r.name.as_str(), r.version.as_str(), r.reason.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("left-pad", "1.3.0", "vendored, see legal#42"),
+                ("@myorg/ui", "", "first-party"),
+                ("@babel/core", "7.23.0", ""),
+                ("github.com/acme/tool#v2", "", ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remove_ignored_records_reasons() {
+        take_ignored_dependencies();
+        let config = DependencyConfig {
+            ignore: vec![rule("lodash", "", "dev tooling only")],
+            ..Default::default()
+        };
+        let file_rules: Vec<SourcedRule> = [
+            rule("lodash", "4.17.21", "shadowed by the config rule"),
+            rule("left-pad", "1.3.0", "vendored"),
+            rule("left-pad", "9.9.9", "other version"),
+        ]
+        .into_iter()
+        .map(|rule| SourcedRule {
+            rule,
+            source: IGNORE_FILE.to_string(),
+        })
+        .collect();
+        let mut deps = vec![
+            LicenseInfo::fixture("lodash", "4.17.21", "MIT"),
+            LicenseInfo::fixture("left-pad", "1.3.0", "MIT"),
+            LicenseInfo::fixture("serde", "1.0.0", "MIT"),
+        ];
+
+        remove_ignored(&mut deps, &config, &file_rules);
+
+        assert_eq!(deps.len(), 1);
+        assert_eq!(deps[0].name, "serde");
+        let ignored: Vec<IgnoredDependency> = take_ignored_dependencies()
+            .into_iter()
+            .filter(|dep| dep.name == "lodash" || dep.name == "left-pad")
+            .collect();
+        assert_eq!(
+            ignored,
+            vec![
+                IgnoredDependency {
+                    name: "lodash".to_string(),
+                    version: "4.17.21".to_string(),
+                    reason: "dev tooling only".to_string(),
+                    source: PROJECT_CONFIG_FILE.to_string(),
+                },
+                IgnoredDependency {
+                    name: "left-pad".to_string(),
+                    version: "1.3.0".to_string(),
+                    reason: "vendored".to_string(),
+                    source: IGNORE_FILE.to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_ignore_file() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load_ignore_file(dir.path()).is_empty());
+
+        fs::write(dir.path().join(IGNORE_FILE), "left-pad # vendored\n").unwrap();
+        let rules = load_ignore_file(dir.path());
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].rule.name, "left-pad");
+        assert!(rules[0].rule.version.is_empty());
+        assert_eq!(rules[0].source, IGNORE_FILE);
+    }
+
+    #[test]
+    fn test_suppression_reason() {
+        let lines: Vec<String> = [
+            "[dependencies]",
+            "# feluda: ignore reason=\"internal fork\"",
+            "# pinned until the 2.x migration",
+            "acme-crypto = \"2.1\"",
+            "serde = \"1\"  # feluda: ignore",
+            "rand = \"0.8\"",
+            "",
+            "# feluda: ignore reason=\"not above a comment block\"",
+            "",
+            "tokio = \"1\"",
+        ]
+        .map(String::from)
+        .to_vec();
+
+        assert_eq!(
+            suppression_reason(&lines, 3),
+            Some("internal fork".to_string())
+        );
+        assert_eq!(suppression_reason(&lines, 4), Some(String::new()));
+        assert_eq!(suppression_reason(&lines, 5), None);
+        assert_eq!(suppression_reason(&lines, 9), None);
+    }
+
+    #[test]
+    fn test_inline_suppressions() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("requirements.txt"),
+            "# feluda: ignore reason=\"internal fork\"\nacme-utils==1.2\nrequests==2.31\n",
+        )
+        .unwrap();
+        let located = |name: &str, version: &str, line: usize| LicenseInfo {
+            declared_in: Some(crate::manifest::ManifestLocation {
+                file: "requirements.txt".to_string(),
+                line,
+            }),
+            ..LicenseInfo::fixture(name, version, "MIT")
+        };
+        let deps = vec![
+            located("acme-utils", "1.2", 2),
+            located("requests", "2.31", 3),
+            LicenseInfo::fixture("urllib3", "2.0.0", "MIT"),
+        ];
+
+        let rules = inline_suppressions(&deps, dir.path());
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].rule.name, "acme-utils");
+        assert_eq!(rules[0].rule.version, "1.2");
+        assert_eq!(rules[0].rule.reason, "internal fork");
+        assert_eq!(rules[0].source, "requirements.txt:2");
+    }
+}
@@ -0,0 +1,447 @@
+//! Layer tarballs: a minimal streaming tar reader and overlay-style extraction
+//!
+//! Each layer is a (possibly gzip/zstd compressed) tar archive applied on top of the previous
+//! ones. Whiteout entries delete files from lower layers: `.wh.<name>` removes `<name>` and
+//! `.wh..wh..opq` empties its directory. Entries are confined to the root filesystem: `..`
+//! components are rejected and symlinks are only created when they resolve inside it.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+
+use crate::debug::{log, LogLevel};
+
+const BLOCK: usize = 512;
+const WHITEOUT_PREFIX: &str = ".wh.";
+const OPAQUE_WHITEOUT: &str = ".wh..wh..opq";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryKind {
+    File,
+    Directory,
+    Symlink(String),
+    Hardlink(String),
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub path: String,
+    pub kind: EntryKind,
+}
+
+/// Wrap a layer in the decompressor its magic bytes call for (gzip, zstd or none)
+pub fn decompress<'a>(reader: impl Read + 'a) -> io::Result<Box<dyn Read + 'a>> {
+    let mut reader = BufReader::new(reader);
+    let magic = reader.fill_buf()?;
+    if magic.starts_with(&[0x1f, 0x8b]) {
+        Ok(Box::new(flate2::read::GzDecoder::new(reader)))
+    } else if magic.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        Ok(Box::new(zstd::Decoder::with_buffer(reader)?))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+/// Walk the entries of a tar stream. `visit` may read the entry's data; whatever it leaves
+/// unread is skipped. GNU long names and PAX `path`/`linkpath` records are applied.
+pub fn read_entries<R: Read>(
+    mut reader: R,
+    mut visit: impl FnMut(&Entry, &mut dyn Read) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut header = [0u8; BLOCK];
+    let mut long_name: Option<String> = None;
+    let mut long_link: Option<String> = None;
+
+    loop {
+        if !read_block(&mut reader, &mut header)? || header.iter().all(|&b| b == 0) {
+            return Ok(());
+        }
+        let size = parse_size(&header[124..136])?;
+        let typeflag = header[156];
+
+        // Metadata entries describe the next header rather than a file of their own
+        if matches!(typeflag, b'L' | b'K' | b'x' | b'g') {
+            let mut data = Vec::new();
+            (&mut reader).take(size).read_to_end(&mut data)?;
+            skip_padding(&mut reader, size)?;
+            match typeflag {
+                b'L' => long_name = Some(c_string(&data)),
+                b'K' => long_link = Some(c_string(&data)),
+                b'x' => {
+                    for (key, value) in parse_pax(&data) {
+                        match key.as_str() {
+                            "path" => long_name = Some(value),
+                            "linkpath" => long_link = Some(value),
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        let path = long_name.take().unwrap_or_else(|| header_path(&header));
+        let link = long_link
+            .take()
+            .unwrap_or_else(|| c_string(&header[157..257]));
+        let kind = match typeflag {
+            b'0' | b'\0' | b'7' => EntryKind::File,
+            b'5' => EntryKind::Directory,
+            b'2' => EntryKind::Symlink(link),
+            b'1' => EntryKind::Hardlink(link),
+            _ => EntryKind::Other,
+        };
+        let entry = Entry { path, kind };
+
+        let mut data = (&mut reader).take(size);
+        visit(&entry, &mut data)?;
+        io::copy(&mut data, &mut io::sink())?;
+        skip_padding(&mut reader, size)?;
+    }
+}
+
+/// Apply one layer onto `rootfs`, honouring whiteouts
+pub fn apply_layer(reader: impl Read, rootfs: &Path) -> io::Result<()> {
+    // Paths this layer wrote; an opaque whiteout only clears what lower layers left behind
+    let mut written: HashSet<PathBuf> = HashSet::new();
+
+    read_entries(decompress(reader)?, |entry, data| {
+        let Some(relative) = sanitize(&entry.path) else {
+            log(
+                LogLevel::Warn,
+                &format!(
+                    "Skipping layer entry outside the image root: {}",
+                    entry.path
+                ),
+            );
+            return Ok(());
+        };
+        let Some(file_name) = relative.file_name().and_then(|name| name.to_str()) else {
+            return Ok(());
+        };
+        let parent = relative.parent().unwrap_or(Path::new(""));
+
+        if file_name == OPAQUE_WHITEOUT {
+            let dir = rootfs.join(parent);
+            if let Ok(children) = fs::read_dir(&dir) {
+                for child in children.flatten() {
+                    let child_relative = parent.join(child.file_name());
+                    if !written.contains(&child_relative) {
+                        remove(&child.path())?;
+                    }
+                }
+            }
+            return Ok(());
+        }
+        if let Some(hidden) = file_name.strip_prefix(WHITEOUT_PREFIX) {
+            remove(&rootfs.join(parent).join(hidden))?;
+            return Ok(());
+        }
+
+        let target = rootfs.join(&relative);
+        match &entry.kind {
+            EntryKind::Directory => {
+                if target.symlink_metadata().is_ok_and(|meta| !meta.is_dir()) {
+                    remove(&target)?;
+                }
+                fs::create_dir_all(&target)?;
+            }
+            EntryKind::File => {
+                prepare(&target)?;
+                io::copy(data, &mut fs::File::create(&target)?)?;
+            }
+            EntryKind::Hardlink(link) => {
+                let Some(source) = sanitize(link).map(|link| rootfs.join(link)) else {
+                    return Ok(());
+                };
+                prepare(&target)?;
+                if source.is_file() {
+                    fs::copy(&source, &target)?;
+                }
+            }
+            EntryKind::Symlink(link) => {
+                if let Some(resolved) = resolve_link(parent, link) {
+                    prepare(&target)?;
+                    symlink(&rootfs.join(resolved), &target)?;
+                }
+            }
+            EntryKind::Other => return Ok(()),
+        }
+        written.insert(relative);
+        Ok(())
+    })
+}
+
+/// Unpack a plain (non-layer) archive such as a `docker save` tarball
+pub fn unpack_archive(reader: impl Read, dest: &Path) -> io::Result<()> {
+    read_entries(decompress(reader)?, |entry, data| {
+        let Some(relative) = sanitize(&entry.path) else {
+            return Ok(());
+        };
+        let target = dest.join(relative);
+        match entry.kind {
+            EntryKind::Directory => fs::create_dir_all(&target),
+            EntryKind::File => {
+                prepare(&target)?;
+                io::copy(data, &mut fs::File::create(&target)?).map(|_| ())
+            }
+            _ => Ok(()),
+        }
+    })
+}
+
+/// An archive path relative to the root, or `None` when it climbs out of it
+fn sanitize(path: &str) -> Option<PathBuf> {
+    let mut relative = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir | Component::RootDir => {}
+            Component::ParentDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!relative.as_os_str().is_empty()).then_some(relative)
+}
+
+/// Where a symlink in `parent` pointing at `link` lands, relative to the root. Absolute
+/// targets are taken from the image root; targets escaping it are rejected.
+fn resolve_link(parent: &Path, link: &str) -> Option<PathBuf> {
+    let mut resolved = if link.starts_with('/') {
+        PathBuf::new()
+    } else {
+        parent.to_path_buf()
+    };
+    for component in Path::new(link).components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::ParentDir => {
+                if !resolved.pop() {
+                    return None;
+                }
+            }
+            Component::CurDir | Component::RootDir => {}
+            Component::Prefix(_) => return None,
+        }
+    }
+    Some(resolved)
+}
+
+/// Make room for a new entry: create its parent and drop whatever a lower layer left there
+fn prepare(target: &Path) -> io::Result<()> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if target.symlink_metadata().is_ok() {
+        remove(target)?;
+    }
+    Ok(())
+}
+
+fn remove(path: &Path) -> io::Result<()> {
+    match path.symlink_metadata() {
+        Ok(meta) if meta.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(_) => Ok(()),
+    }
+}
+
+#[cfg(unix)]
+fn symlink(original: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(original, link)
+}
+
+#[cfg(not(unix))]
+fn symlink(original: &Path, link: &Path) -> io::Result<()> {
+    if original.is_file() {
+        fs::copy(original, link).map(|_| ())
+    } else {
+        Ok(())
+    }
+}
+
+/// Read a whole block; `false` at a clean end of stream
+fn read_block(reader: &mut impl Read, block: &mut [u8; BLOCK]) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < BLOCK {
+        match reader.read(&mut block[filled..])? {
+            0 if filled == 0 => return Ok(false),
+            0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            n => filled += n,
+        }
+    }
+    Ok(true)
+}
+
+fn skip_padding(reader: &mut impl Read, size: u64) -> io::Result<()> {
+    let padding = (BLOCK as u64 - size % BLOCK as u64) % BLOCK as u64;
+    io::copy(&mut reader.take(padding), &mut io::sink())?;
+    Ok(())
+}
+
+/// Octal size field, or GNU base-256 when the high bit is set
+fn parse_size(field: &[u8]) -> io::Result<u64> {
+    if field[0] & 0x80 != 0 {
+        return Ok(field[1..]
+            .iter()
+            .fold(u64::from(field[0] & 0x7f), |size, &b| {
+                (size << 8) | u64::from(b)
+            }));
+    }
+    let digits = c_string(field);
+    let digits = digits.trim();
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    u64::from_str_radix(digits, 8).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("bad tar size {digits:?}: {e}"),
+        )
+    })
+}
+
+/// `prefix/name` for ustar headers, `name` otherwise
+fn header_path(header: &[u8; BLOCK]) -> String {
+    let name = c_string(&header[0..100]);
+    if &header[257..262] == b"ustar" {
+        let prefix = c_string(&header[345..500]);
+        if !prefix.is_empty() {
+            return format!("{prefix}/{name}");
+        }
+    }
+    name
+}
+
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// `<length> <key>=<value>\n` records of a PAX extended header
+fn parse_pax(data: &[u8]) -> Vec<(String, String)> {
+    let text = String::from_utf8_lossy(data);
+    text.lines()
+        .filter_map(|record| {
+            let (_, pair) = record.split_once(' ')?;
+            let (key, value) = pair.split_once('=')?;
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// A ustar header + padded data for one entry
+    pub fn tar_entry(path: &str, typeflag: u8, link: &str, data: &[u8]) -> Vec<u8> {
+        let mut header = [0u8; BLOCK];
+        header[..path.len()].copy_from_slice(path.as_bytes());
+        header[100..107].copy_from_slice(b"0000644");
+        header[124..135].copy_from_slice(format!("{:011o}", data.len()).as_bytes());
+        header[156] = typeflag;
+        header[157..157 + link.len()].copy_from_slice(link.as_bytes());
+        header[257..263].copy_from_slice(b"ustar\0");
+
+        let mut entry = header.to_vec();
+        entry.extend_from_slice(data);
+        entry.resize(entry.len().div_ceil(BLOCK) * BLOCK, 0);
+        entry
+    }
+
+    pub fn tar(entries: &[Vec<u8>]) -> Vec<u8> {
+        let mut archive: Vec<u8> = entries.concat();
+        archive.extend_from_slice(&[0u8; BLOCK * 2]);
+        archive
+    }
+
+    #[test]
+    fn test_read_entries() {
+        let long_name = format!("app/{}/package.json", "nested".repeat(20));
+        let archive = tar(&[
+            tar_entry("etc/", b'5', "", b""),
+            tar_entry("etc/os-release", b'0', "", b"ID=alpine\n"),
+            tar_entry(
+                "././@LongLink",
+                b'L',
+                "",
+                format!("{long_name}\0").as_bytes(),
+            ),
+            tar_entry("truncated", b'0', "", b"{}"),
+        ]);
+
+        let mut seen = Vec::new();
+        read_entries(archive.as_slice(), |entry, data| {
+            let mut content = String::new();
+            data.read_to_string(&mut content)?;
+            seen.push((entry.path.clone(), entry.kind.clone(), content));
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(seen.len(), 3);
+        assert_eq!(seen[0].1, EntryKind::Directory);
+        assert_eq!(seen[1].2, "ID=alpine\n");
+        assert_eq!(seen[2].0, long_name);
+        assert_eq!(seen[2].2, "{}");
+    }
+
+    #[test]
+    fn test_apply_layers_with_whiteouts() {
+        let rootfs = TempDir::new().unwrap();
+        let base = tar(&[
+            tar_entry("app/package.json", b'0', "", b"{}"),
+            tar_entry("app/old.txt", b'0', "", b"old"),
+            tar_entry("cache/a", b'0', "", b"a"),
+            tar_entry("cache/b", b'0', "", b"b"),
+        ]);
+        let top = tar(&[
+            tar_entry("app/.wh.old.txt", b'0', "", b""),
+            tar_entry("cache/c", b'0', "", b"c"),
+            tar_entry("cache/.wh..wh..opq", b'0', "", b""),
+            tar_entry("../escape", b'0', "", b"nope"),
+        ]);
+        apply_layer(base.as_slice(), rootfs.path()).unwrap();
+        apply_layer(top.as_slice(), rootfs.path()).unwrap();
+
+        assert!(rootfs.path().join("app/package.json").exists());
+        assert!(!rootfs.path().join("app/old.txt").exists());
+        assert!(!rootfs.path().join("cache/a").exists());
+        assert!(rootfs.path().join("cache/c").exists());
+        assert!(!rootfs.path().parent().unwrap().join("escape").exists());
+    }
+
+    #[test]
+    fn test_gzip_layers_are_decompressed() {
+        use std::io::Write;
+
+        let rootfs = TempDir::new().unwrap();
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(&tar(&[tar_entry("go.mod", b'0', "", b"module x\n")]))
+            .unwrap();
+        apply_layer(encoder.finish().unwrap().as_slice(), rootfs.path()).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(rootfs.path().join("go.mod")).unwrap(),
+            "module x\n"
+        );
+    }
+
+    #[test]
+    fn test_resolve_link() {
+        assert_eq!(
+            resolve_link(Path::new("usr/share/doc"), "libz1"),
+            Some(PathBuf::from("usr/share/doc/libz1"))
+        );
+        assert_eq!(
+            resolve_link(Path::new("usr/share/doc"), "/etc/passwd"),
+            Some(PathBuf::from("etc/passwd"))
+        );
+        assert_eq!(resolve_link(Path::new("usr"), "../../etc"), None);
+    }
+}
@@ -0,0 +1,535 @@
+//! Container image scanning (`feluda image <ref>`)
+//!
+//! Pulls an OCI or Docker image from its registry (or reads a `docker save` archive / OCI
+//! layout directory), applies its layers onto a temporary root filesystem, and reports both
+//! the OS packages recorded in the distro package database and the dependencies of every
+//! language project found inside. The regular policy (restrictive, compatibility, OSI) then
+//! runs over the whole image.
+
+pub mod layers;
+mod packages;
+pub mod registry;
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use ignore::WalkBuilder;
+use serde_json::Value;
+use tempfile::TempDir;
+
+use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
+use crate::languages::Language;
+use crate::licenses::{
+    fetch_licenses_from_github, get_osi_status, is_license_ignored, is_license_restrictive,
+    LicenseCompatibility, LicenseInfo, OsiStatus,
+};
+use crate::CheckConfig;
+
+use registry::{ImageReference, RegistryClient};
+
+/// Root filesystem directories that hold the OS, not applications
+const OS_DIRS: &[&str] = &[
+    "bin",
+    "boot",
+    "dev",
+    "etc",
+    "lib",
+    "lib32",
+    "lib64",
+    "libx32",
+    "proc",
+    "run",
+    "sbin",
+    "sys",
+    "tmp",
+    "usr/bin",
+    "usr/include",
+    "usr/lib",
+    "usr/lib64",
+    "usr/libexec",
+    "usr/sbin",
+    "usr/share",
+    "var/cache",
+    "var/lib",
+    "var/log",
+];
+
+/// Directory names holding installed packages or caches rather than projects
+const SKIP_DIRS: &[&str] = &[
+    "node_modules",
+    "site-packages",
+    "dist-packages",
+    ".cargo",
+    ".rustup",
+    ".npm",
+    ".m2",
+    ".gradle",
+    ".git",
+    "vendor",
+];
+
+/// Go's module cache (`$GOPATH/pkg/mod`) holds a `go.mod` per downloaded module
+const GO_MODULE_CACHE: &str = "pkg/mod";
+
+const MAX_PROJECT_DEPTH: usize = 8;
+
+/// An image to scan and the platform to pick from multi-platform indexes
+#[derive(Debug, Clone)]
+pub struct ImageRequest {
+    pub reference: String,
+    /// `os/arch[/variant]`; defaults to the host's
+    pub platform: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Platform {
+    os: String,
+    architecture: String,
+    variant: Option<String>,
+}
+
+impl Platform {
+    fn parse(platform: &str) -> Option<Self> {
+        let mut parts = platform.split('/');
+        let os = parts.next().filter(|os| !os.is_empty())?;
+        let architecture = parts.next().filter(|arch| !arch.is_empty())?;
+        Some(Self {
+            os: os.to_string(),
+            architecture: architecture.to_string(),
+            variant: parts.next().map(str::to_string),
+        })
+    }
+
+    fn host() -> Self {
+        let architecture = match std::env::consts::ARCH {
+            "x86_64" => "amd64",
+            "aarch64" => "arm64",
+            "x86" => "386",
+            "powerpc64" => "ppc64le",
+            arch => arch,
+        };
+        Self {
+            os: "linux".to_string(),
+            architecture: architecture.to_string(),
+            variant: None,
+        }
+    }
+
+    fn matches(&self, platform: &Value) -> bool {
+        let field = |key: &str| platform.get(key).and_then(Value::as_str);
+        field("os") == Some(self.os.as_str())
+            && field("architecture") == Some(self.architecture.as_str())
+            && self
+                .variant
+                .as_deref()
+                .is_none_or(|variant| field("variant") == Some(variant))
+    }
+}
+
+/// Where manifests and blobs come from
+enum ImageStore {
+    Registry(RegistryClient),
+    /// An OCI image layout: `index.json` plus `blobs/<algorithm>/<hex>`
+    Layout(PathBuf),
+}
+
+impl ImageStore {
+    fn manifest(&mut self, digest: &str) -> FeludaResult<Value> {
+        match self {
+            Self::Registry(client) => client.manifest(digest),
+            Self::Layout(dir) => {
+                let content = fs::read_to_string(layout_blob_path(dir, digest)?)?;
+                serde_json::from_str(&content).map_err(|e| {
+                    FeludaError::InvalidData(format!("Invalid image manifest {digest}: {e}"))
+                })
+            }
+        }
+    }
+
+    fn blob(&mut self, digest: &str) -> FeludaResult<Box<dyn Read>> {
+        match self {
+            Self::Registry(client) => Ok(Box::new(client.blob(digest)?)),
+            Self::Layout(dir) => Ok(Box::new(fs::File::open(layout_blob_path(dir, digest)?)?)),
+        }
+    }
+}
+
+fn layout_blob_path(dir: &Path, digest: &str) -> FeludaResult<PathBuf> {
+    let (algorithm, hex) = digest
+        .split_once(':')
+        .filter(|(algorithm, hex)| {
+            [algorithm, hex]
+                .iter()
+                .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()))
+        })
+        .ok_or_else(|| FeludaError::InvalidData(format!("Invalid blob digest: {digest}")))?;
+    Ok(dir.join("blobs").join(algorithm).join(hex))
+}
+
+/// Pull `request`, unpack it and analyze everything installed in it
+pub fn analyze_image(
+    request: &ImageRequest,
+    config: &CheckConfig,
+) -> FeludaResult<Vec<LicenseInfo>> {
+    let platform = match request.platform.as_deref() {
+        Some(platform) => Platform::parse(platform).ok_or_else(|| {
+            FeludaError::InvalidData(format!(
+                "--platform must look like os/arch[/variant], got '{platform}'"
+            ))
+        })?,
+        None => Platform::host(),
+    };
+
+    let rootfs = TempDir::new()
+        .map_err(|e| FeludaError::TempDir(format!("Failed to create image root: {e}")))?;
+    unpack_image(&request.reference, &platform, rootfs.path())?;
+
+    let known_licenses = fetch_licenses_from_github()?;
+    let feluda_config = crate::config::load_config()?;
+
+    let mut dependencies: Vec<LicenseInfo> = packages::find_os_packages(rootfs.path())
+        .into_iter()
+        .map(|package| LicenseInfo {
+            is_restrictive: is_license_restrictive(
+                &package.license,
+                &known_licenses,
+                config.strict,
+            ),
+            osi_status: package
+                .license
+                .as_deref()
+                .map(get_osi_status)
+                .unwrap_or(OsiStatus::Unknown),
+            name: package.name,
+            version: package.version,
+            license: package.license,
+            compatibility: LicenseCompatibility::Unknown,
+            sub_project: None,
+            found_in: vec![package.manager.to_string()],
+            repository: None,
+            declared_in: None,
+            internal: false,
+            inherited_from: None,
+            ecosystem: None,
+            suggestions: Vec::new(),
+            update_policy: None,
+            introduced_by: Vec::new(),
+            purl: None,
+            vulnerabilities: None,
+            manifest: None,
+            data_license: None,
+            project_dirs: Vec::new(),
+        })
+        .filter(|dep| !is_license_ignored(dep.license.as_deref()))
+        .collect();
+    crate::ignore::remove_ignored(&mut dependencies, &feluda_config.dependencies, &[]);
+
+    for project in find_embedded_projects(rootfs.path()) {
+        let label = format!(
+            "/{}",
+            project
+                .strip_prefix(rootfs.path())
+                .unwrap_or(&project)
+                .display()
+        );
+        log(
+            LogLevel::Info,
+            &format!("Scanning image project at {label}"),
+        );
+        let mut deps = crate::parser::parse_root(
+            &project,
+            config.language.as_deref(),
+            &config.skip,
+            config.strict,
+            config.no_local,
+            config.cargo_lock,
+            config.no_dev,
+        )?;
+        for dep in &mut deps {
+            dep.sub_project.get_or_insert_with(|| label.clone());
+        }
+        dependencies.extend(deps);
+    }
+
+    for dep in &mut dependencies {
+        dep.internal = feluda_config.internal.is_internal(&dep.name);
+    }
+    log(
+        LogLevel::Info,
+        &format!(
+            "Image {} has {} dependencies",
+            request.reference,
+            dependencies.len()
+        ),
+    );
+    Ok(dependencies)
+}
+
+/// Apply the image's layers onto `rootfs`. `image` is a registry reference, a `docker save`
+/// archive or an OCI layout directory.
+fn unpack_image(image: &str, platform: &Platform, rootfs: &Path) -> FeludaResult<()> {
+    let local = Path::new(image);
+    let _archive_dir;
+    let (mut store, manifest) = if local.exists() {
+        let layout = if local.is_dir() {
+            local.to_path_buf()
+        } else {
+            let dir = TempDir::new().map_err(|e| {
+                FeludaError::TempDir(format!("Failed to create archive directory: {e}"))
+            })?;
+            layers::unpack_archive(fs::File::open(local)?, dir.path())?;
+            let path = dir.path().to_path_buf();
+            _archive_dir = dir;
+            path
+        };
+
+        // `docker save` lists the layer files of each image in manifest.json
+        if let Some(layer_paths) = docker_save_layers(&layout)? {
+            for layer in layer_paths {
+                layers::apply_layer(fs::File::open(&layer)?, rootfs)?;
+            }
+            return Ok(());
+        }
+        let index = fs::read_to_string(layout.join("index.json")).map_err(|_| {
+            FeludaError::InvalidData(format!(
+                "{image} is neither a docker save archive nor an OCI image layout"
+            ))
+        })?;
+        let index: Value = serde_json::from_str(&index)
+            .map_err(|e| FeludaError::InvalidData(format!("Invalid OCI index.json: {e}")))?;
+        (ImageStore::Layout(layout), index)
+    } else {
+        let reference = ImageReference::parse(image)
+            .ok_or_else(|| FeludaError::InvalidData(format!("Invalid image reference: {image}")))?;
+        log(LogLevel::Info, &format!("Pulling {reference}"));
+        let mut client = RegistryClient::new(&reference)?;
+        let manifest = client.manifest(&reference.reference)?;
+        (ImageStore::Registry(client), manifest)
+    };
+
+    let manifest = select_platform_manifest(&mut store, manifest, platform)?;
+    let layer_digests: Vec<String> = manifest
+        .get("layers")
+        .and_then(Value::as_array)
+        .ok_or_else(|| FeludaError::InvalidData("Image manifest lists no layers".to_string()))?
+        .iter()
+        .filter_map(|layer| layer.get("digest")?.as_str().map(str::to_string))
+        .collect();
+
+    for (index, digest) in layer_digests.iter().enumerate() {
+        log(
+            LogLevel::Info,
+            &format!(
+                "Applying layer {}/{}: {digest}",
+                index + 1,
+                layer_digests.len()
+            ),
+        );
+        layers::apply_layer(store.blob(digest)?, rootfs)?;
+    }
+    Ok(())
+}
+
+/// Follow image indexes down to the single-platform manifest for `platform`
+fn select_platform_manifest(
+    store: &mut ImageStore,
+    mut manifest: Value,
+    platform: &Platform,
+) -> FeludaResult<Value> {
+    // Indexes can nest (an OCI layout's index.json pointing at a multi-platform index)
+    for _ in 0..4 {
+        let Some(entries) = manifest.get("manifests").and_then(Value::as_array) else {
+            return Ok(manifest);
+        };
+        let entry = match entries.as_slice() {
+            [only] if only.get("platform").is_none() => only,
+            entries => entries
+                .iter()
+                .find(|entry| entry.get("platform").is_some_and(|p| platform.matches(p)))
+                .ok_or_else(|| {
+                    FeludaError::InvalidData(format!(
+                        "The image has no {}/{} variant; pick one with --platform",
+                        platform.os, platform.architecture
+                    ))
+                })?,
+        };
+        let digest = entry
+            .get("digest")
+            .and_then(Value::as_str)
+            .ok_or_else(|| FeludaError::InvalidData("Image index entry has no digest".into()))?
+            .to_string();
+        manifest = store.manifest(&digest)?;
+    }
+    Err(FeludaError::InvalidData(
+        "Image indexes are nested too deeply".to_string(),
+    ))
+}
+
+/// Layer files of the first image in a `docker save` manifest.json
+fn docker_save_layers(dir: &Path) -> FeludaResult<Option<Vec<PathBuf>>> {
+    let Ok(content) = fs::read_to_string(dir.join("manifest.json")) else {
+        return Ok(None);
+    };
+    let manifest: Value = serde_json::from_str(&content)
+        .map_err(|e| FeludaError::InvalidData(format!("Invalid docker save manifest: {e}")))?;
+    let Some(layers) = manifest
+        .get(0)
+        .and_then(|image| image.get("Layers"))
+        .and_then(Value::as_array)
+    else {
+        return Ok(None);
+    };
+    layers
+        .iter()
+        .filter_map(Value::as_str)
+        .map(|layer| {
+            if Path::new(layer)
+                .components()
+                .any(|c| !matches!(c, std::path::Component::Normal(_)))
+            {
+                return Err(FeludaError::InvalidData(format!(
+                    "docker save manifest points outside the archive: {layer}"
+                )));
+            }
+            Ok(dir.join(layer))
+        })
+        .collect::<FeludaResult<Vec<_>>>()
+        .map(Some)
+}
+
+/// Directories inside the image holding a language manifest, outside the OS directories
+fn find_embedded_projects(rootfs: &Path) -> Vec<PathBuf> {
+    let root = rootfs.to_path_buf();
+    let walker = WalkBuilder::new(rootfs)
+        .standard_filters(false)
+        .follow_links(false)
+        .max_depth(Some(MAX_PROJECT_DEPTH))
+        .sort_by_file_path(|a, b| a.cmp(b))
+        .filter_entry(move |entry| {
+            if !entry.file_type().is_some_and(|ft| ft.is_dir()) {
+                return true;
+            }
+            let relative = entry.path().strip_prefix(&root).unwrap_or(entry.path());
+            let name = entry.file_name().to_str().unwrap_or_default();
+            !(OS_DIRS.iter().any(|dir| relative == Path::new(dir))
+                || SKIP_DIRS.contains(&name)
+                || relative.ends_with(GO_MODULE_CACHE))
+        })
+        .build();
+
+    let mut projects: Vec<PathBuf> = Vec::new();
+    for entry in walker.flatten() {
+        if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+            continue;
+        }
+        let Some(name) = entry.file_name().to_str() else {
+            continue;
+        };
+        if Language::from_file_name(name).is_none() {
+            continue;
+        }
+        if let Some(dir) = entry.path().parent() {
+            if !projects.iter().any(|project| project == dir) {
+                projects.push(dir.to_path_buf());
+            }
+        }
+    }
+    projects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::layers::tests::{tar, tar_entry};
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_platform() {
+        let arm = Platform::parse("linux/arm64/v8").unwrap();
+        assert_eq!(arm.variant.as_deref(), Some("v8"));
+        assert!(arm.matches(&json!({"os": "linux", "architecture": "arm64", "variant": "v8"})));
+        assert!(!arm.matches(&json!({"os": "linux", "architecture": "amd64"})));
+        assert!(Platform::parse("linux").is_none());
+    }
+
+    #[test]
+    fn test_unpack_oci_layout() {
+        let layout = TempDir::new().unwrap();
+        let blobs = layout.path().join("blobs/sha256");
+        fs::create_dir_all(&blobs).unwrap();
+
+        fs::write(
+            blobs.join("layer1"),
+            tar(&[
+                tar_entry(
+                    "lib/apk/db/installed",
+                    b'0',
+                    "",
+                    b"P:musl\nV:1.2.4\nL:MIT\n\n",
+                ),
+                tar_entry("app/go.mod", b'0', "", b"module example.com/app\n"),
+            ]),
+        )
+        .unwrap();
+        fs::write(
+            blobs.join("amd64"),
+            json!({"layers": [{"digest": "sha256:layer1"}]}).to_string(),
+        )
+        .unwrap();
+        fs::write(
+            layout.path().join("index.json"),
+            json!({"manifests": [
+                {"digest": "sha256:arm64", "platform": {"os": "linux", "architecture": "arm64"}},
+                {"digest": "sha256:amd64", "platform": {"os": "linux", "architecture": "amd64"}},
+            ]})
+            .to_string(),
+        )
+        .unwrap();
+
+        let rootfs = TempDir::new().unwrap();
+        unpack_image(
+            layout.path().to_str().unwrap(),
+            &Platform::parse("linux/amd64").unwrap(),
+            rootfs.path(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            packages::find_os_packages(rootfs.path())[0].name,
+            "musl".to_string()
+        );
+        assert_eq!(
+            find_embedded_projects(rootfs.path()),
+            vec![rootfs.path().join("app")]
+        );
+    }
+
+    #[test]
+    fn test_embedded_projects_skip_os_and_package_dirs() {
+        let rootfs = TempDir::new().unwrap();
+        for file in [
+            "srv/web/package.json",
+            "srv/web/node_modules/left-pad/package.json",
+            "usr/share/doc/example/Cargo.toml",
+            "root/go/pkg/mod/github.com/x/y@v1/go.mod",
+        ] {
+            let path = rootfs.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        assert_eq!(
+            find_embedded_projects(rootfs.path()),
+            vec![rootfs.path().join("srv/web")]
+        );
+    }
+
+    #[test]
+    fn test_layout_blob_path_rejects_traversal() {
+        assert!(layout_blob_path(Path::new("/layout"), "sha256:../../etc").is_err());
+        assert_eq!(
+            layout_blob_path(Path::new("/layout"), "sha256:abc").unwrap(),
+            Path::new("/layout/blobs/sha256/abc")
+        );
+    }
+}
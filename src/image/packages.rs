@@ -0,0 +1,234 @@
+//! OS packages installed in an image root filesystem
+//!
+//! - **apk** (Alpine): `lib/apk/db/installed`, whose `L:` field holds the license
+//! - **dpkg** (Debian, Ubuntu, distroless): `var/lib/dpkg/status` and `status.d/`, licensed
+//!   by the package's DEP-5 `usr/share/doc/<pkg>/copyright`
+//! - **rpm** (Fedora, RHEL, SUSE): the rpmdb is SQLite or Berkeley DB, so it is queried with
+//!   the host's `rpm --root`
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::debug::{log, record_parse_warning, LogLevel};
+use crate::languages::system::debian_package_license;
+
+/// A package recorded in an OS package database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OsPackage {
+    pub name: String,
+    pub version: String,
+    pub license: Option<String>,
+    /// `apk`, `dpkg` or `rpm`
+    pub manager: &'static str,
+}
+
+/// Every OS package installed in `rootfs`
+pub fn find_os_packages(rootfs: &Path) -> Vec<OsPackage> {
+    let mut packages = Vec::new();
+
+    if let Ok(content) = fs::read_to_string(rootfs.join("lib/apk/db/installed")) {
+        packages.extend(parse_apk_installed(&content));
+    }
+
+    let mut dpkg_databases = vec![rootfs.join("var/lib/dpkg/status")];
+    // Distroless images ship one status file per package instead
+    if let Ok(entries) = fs::read_dir(rootfs.join("var/lib/dpkg/status.d")) {
+        let mut entries: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+        entries.sort();
+        dpkg_databases.extend(entries.into_iter().filter(|path| path.is_file()));
+    }
+    for database in dpkg_databases {
+        if let Ok(content) = fs::read_to_string(&database) {
+            packages.extend(
+                parse_dpkg_status(&content)
+                    .into_iter()
+                    .map(|(name, version)| OsPackage {
+                        license: debian_package_license(&rootfs.join("usr/share/doc"), &name),
+                        name,
+                        version,
+                        manager: "dpkg",
+                    }),
+            );
+        }
+    }
+
+    let rpm_database = ["var/lib/rpm", "usr/lib/sysimage/rpm"]
+        .iter()
+        .map(|dir| rootfs.join(dir))
+        .find(|dir| dir.is_dir());
+    if let Some(database) = rpm_database {
+        packages.extend(query_rpm_database(rootfs, &database));
+    }
+
+    log(
+        LogLevel::Info,
+        &format!("Found {} OS packages in the image", packages.len()),
+    );
+    packages
+}
+
+/// `P:` (name), `V:` (version) and `L:` (license) of each apk database paragraph
+fn parse_apk_installed(content: &str) -> Vec<OsPackage> {
+    content
+        .split("\n\n")
+        .filter_map(|paragraph| {
+            let field = |key: &str| {
+                paragraph
+                    .lines()
+                    .find_map(|line| line.strip_prefix(key))
+                    .map(|value| value.trim().to_string())
+            };
+            Some(OsPackage {
+                name: field("P:")?,
+                version: field("V:").unwrap_or_default(),
+                license: field("L:").filter(|license| !license.is_empty()),
+                manager: "apk",
+            })
+        })
+        .collect()
+}
+
+/// `(Package, Version)` of each installed package in a dpkg status file
+fn parse_dpkg_status(content: &str) -> Vec<(String, String)> {
+    content
+        .split("\n\n")
+        .filter_map(|paragraph| {
+            let field = |key: &str| {
+                paragraph.lines().find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    (name == key).then(|| value.trim().to_string())
+                })
+            };
+            // Removed packages keep a `deinstall ok config-files` entry until purged
+            if field("Status").is_some_and(|status| !status.ends_with(" installed")) {
+                return None;
+            }
+            Some((field("Package")?, field("Version").unwrap_or_default()))
+        })
+        .collect()
+}
+
+fn query_rpm_database(rootfs: &Path, database: &Path) -> Vec<OsPackage> {
+    let output = Command::new("rpm")
+        .arg("--root")
+        .arg(rootfs)
+        .args([
+            "-qa",
+            "--qf",
+            "%{NAME}\t%{VERSION}-%{RELEASE}\t%{LICENSE}\n",
+        ])
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            parse_rpm_query(&String::from_utf8_lossy(&output.stdout))
+        }
+        Ok(output) => {
+            record_parse_warning(
+                database.display().to_string(),
+                format!(
+                    "rpm could not read the image's package database: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            );
+            Vec::new()
+        }
+        Err(_) => {
+            record_parse_warning(
+                database.display().to_string(),
+                "the image has an rpm database, but rpm is not installed to read it",
+            );
+            Vec::new()
+        }
+    }
+}
+
+/// `name<TAB>version-release<TAB>license` lines
+fn parse_rpm_query(stdout: &str) -> Vec<OsPackage> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let name = fields.next()?.trim();
+            if name.is_empty() || name.starts_with("gpg-pubkey") {
+                return None;
+            }
+            Some(OsPackage {
+                name: name.to_string(),
+                version: fields.next().unwrap_or_default().to_string(),
+                license: fields
+                    .next()
+                    .map(str::trim)
+                    .filter(|license| !license.is_empty() && *license != "(none)")
+                    .map(str::to_string),
+                manager: "rpm",
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_apk_installed() {
+        let content = "C:Q1abc=\nP:musl\nV:1.2.4-r2\nA:x86_64\nL:MIT\n\nC:Q1def=\nP:busybox\nV:1.36.1-r5\nL:GPL-2.0-only\n\n";
+        assert_eq!(
+            parse_apk_installed(content),
+            vec![
+                OsPackage {
+                    name: "musl".to_string(),
+                    version: "1.2.4-r2".to_string(),
+                    license: Some("MIT".to_string()),
+                    manager: "apk",
+                },
+                OsPackage {
+                    name: "busybox".to_string(),
+                    version: "1.36.1-r5".to_string(),
+                    license: Some("GPL-2.0-only".to_string()),
+                    manager: "apk",
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dpkg_packages_take_their_copyright_license() {
+        let rootfs = TempDir::new().unwrap();
+        fs::create_dir_all(rootfs.path().join("var/lib/dpkg")).unwrap();
+        fs::write(
+            rootfs.path().join("var/lib/dpkg/status"),
+            "Package: zlib1g\nStatus: install ok installed\nVersion: 1:1.2.13\n\n\
+             Package: oldpkg\nStatus: deinstall ok config-files\nVersion: 1.0\n",
+        )
+        .unwrap();
+        fs::create_dir_all(rootfs.path().join("usr/share/doc/zlib1g")).unwrap();
+        fs::write(
+            rootfs.path().join("usr/share/doc/zlib1g/copyright"),
+            "Format: https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/\n\n\
+             Files: *\nLicense: Zlib\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            find_os_packages(rootfs.path()),
+            vec![OsPackage {
+                name: "zlib1g".to_string(),
+                version: "1:1.2.13".to_string(),
+                license: Some("Zlib".to_string()),
+                manager: "dpkg",
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_rpm_query() {
+        let stdout = "bash\t5.2.26-3.fc40\tGPL-3.0-or-later\ngpg-pubkey\t1-1\tpubkey\nfilesystem\t3.18-8\t(none)\n";
+        let packages = parse_rpm_query(stdout);
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].license.as_deref(), Some("GPL-3.0-or-later"));
+        assert_eq!(packages[1].license, None);
+    }
+}
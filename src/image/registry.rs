@@ -0,0 +1,332 @@
+//! OCI distribution (registry v2) client
+//!
+//! Pulls anonymously by default, exchanging the `WWW-Authenticate` challenge for a bearer
+//! token. Credentials saved by `docker login` in `~/.docker/config.json` (or
+//! `$DOCKER_CONFIG/config.json`) are used for private repositories.
+
+use std::fmt;
+use std::time::Duration;
+
+use regex::Regex;
+use reqwest::blocking::{Client, Response};
+use reqwest::header::{ACCEPT, AUTHORIZATION, WWW_AUTHENTICATE};
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
+
+const DOCKER_HUB: &str = "registry-1.docker.io";
+
+/// Media types accepted for manifests: image indexes first, then single-platform manifests
+const MANIFEST_TYPES: &str = "application/vnd.oci.image.index.v1+json, \
+    application/vnd.docker.distribution.manifest.list.v2+json, \
+    application/vnd.oci.image.manifest.v1+json, \
+    application/vnd.docker.distribution.manifest.v2+json";
+
+/// `[registry/]repository[:tag][@digest]`, normalized the way `docker pull` does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageReference {
+    pub registry: String,
+    pub repository: String,
+    /// Tag or `sha256:` digest
+    pub reference: String,
+}
+
+impl ImageReference {
+    pub fn parse(image: &str) -> Option<Self> {
+        let image = image.trim();
+        if image.is_empty() || image.contains(char::is_whitespace) {
+            return None;
+        }
+        let (name, digest) = match image.split_once('@') {
+            Some((name, digest)) => (name, Some(digest)),
+            None => (image, None),
+        };
+        // A tag follows the last colon, unless that colon belongs to a registry port
+        let (name, tag) = match name.rsplit_once(':') {
+            Some((name, tag)) if !tag.contains('/') => (name, Some(tag)),
+            _ => (name, None),
+        };
+
+        let (registry, repository) = match name.split_once('/') {
+            Some((first, rest))
+                if first.contains('.') || first.contains(':') || first == "localhost" =>
+            {
+                (first.to_string(), rest.to_string())
+            }
+            _ => (DOCKER_HUB.to_string(), name.to_string()),
+        };
+        let registry = if registry == "docker.io" || registry == "index.docker.io" {
+            DOCKER_HUB.to_string()
+        } else {
+            registry
+        };
+        let repository = if registry == DOCKER_HUB && !repository.contains('/') {
+            format!("library/{repository}")
+        } else {
+            repository
+        };
+        if repository.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            registry,
+            repository,
+            reference: digest.or(tag).unwrap_or("latest").to_string(),
+        })
+    }
+}
+
+impl fmt::Display for ImageReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let separator = if self.reference.contains(':') {
+            '@'
+        } else {
+            ':'
+        };
+        write!(
+            f,
+            "{}/{}{separator}{}",
+            self.registry, self.repository, self.reference
+        )
+    }
+}
+
+pub struct RegistryClient {
+    client: Client,
+    base_url: String,
+    repository: String,
+    /// `auth` from the docker config: base64 `user:password`
+    credentials: Option<String>,
+    /// `Authorization` header earned from the registry's challenge
+    authorization: Option<String>,
+}
+
+impl RegistryClient {
+    pub fn new(image: &ImageReference) -> FeludaResult<Self> {
+        if crate::cancel::is_offline() {
+            return Err(FeludaError::Config(format!(
+                "Cannot reach registry {} with --offline",
+                image.registry
+            )));
+        }
+        let scheme =
+            if image.registry.starts_with("localhost") || image.registry.starts_with("127.0.0.1") {
+                "http"
+            } else {
+                "https"
+            };
+        let client = Client::builder()
+            .user_agent("feluda-license-checker/1.0")
+            .connect_timeout(Duration::from_secs(30))
+            .build()?;
+        Ok(Self {
+            client,
+            base_url: format!("{scheme}://{}", image.registry),
+            repository: image.repository.clone(),
+            credentials: docker_config_credentials(&image.registry),
+            authorization: None,
+        })
+    }
+
+    /// The manifest or image index for a tag or digest
+    pub fn manifest(&mut self, reference: &str) -> FeludaResult<Value> {
+        let path = format!("manifests/{reference}");
+        Ok(self.get(&path, Some(MANIFEST_TYPES))?.json::<Value>()?)
+    }
+
+    /// A blob (layer or config) as a byte stream
+    pub fn blob(&mut self, digest: &str) -> FeludaResult<Response> {
+        self.get(&format!("blobs/{digest}"), None)
+    }
+
+    fn get(&mut self, path: &str, accept: Option<&str>) -> FeludaResult<Response> {
+        let url = format!("{}/v2/{}/{path}", self.base_url, self.repository);
+        log(LogLevel::Info, &format!("Fetching {url}"));
+
+        let mut response = self.send(&url, accept)?;
+        if response.status() == StatusCode::UNAUTHORIZED && self.authorization.is_none() {
+            let challenge = response
+                .headers()
+                .get(WWW_AUTHENTICATE)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string();
+            self.authorization = Some(self.authorize(&challenge)?);
+            response = self.send(&url, accept)?;
+        }
+
+        let status = response.status();
+        if !status.is_success() {
+            let hint = match status.as_u16() {
+                401 | 403 => " (private images need `docker login` credentials)",
+                404 => " (image or tag not found)",
+                _ => "",
+            };
+            return Err(FeludaError::InvalidData(format!(
+                "Registry request for {url} failed with {status}{hint}"
+            )));
+        }
+        Ok(response)
+    }
+
+    fn send(&self, url: &str, accept: Option<&str>) -> FeludaResult<Response> {
+        let mut request = self.client.get(url);
+        if let Some(accept) = accept {
+            request = request.header(ACCEPT, accept);
+        }
+        if let Some(ref authorization) = self.authorization {
+            request = request.header(AUTHORIZATION, authorization);
+        }
+        Ok(request.send()?)
+    }
+
+    /// Answer a `WWW-Authenticate` challenge with an `Authorization` header value
+    fn authorize(&self, challenge: &str) -> FeludaResult<String> {
+        let (scheme, params) = challenge.split_once(' ').unwrap_or((challenge, ""));
+        if scheme.eq_ignore_ascii_case("basic") {
+            return self
+                .credentials
+                .as_ref()
+                .map(|credentials| format!("Basic {credentials}"))
+                .ok_or_else(|| {
+                    FeludaError::InvalidData(format!(
+                        "{} requires credentials; run `docker login` first",
+                        self.base_url
+                    ))
+                });
+        }
+
+        let params = parse_challenge(params);
+        let realm = params
+            .iter()
+            .find(|(key, _)| key == "realm")
+            .map(|(_, value)| value.clone())
+            .ok_or_else(|| {
+                FeludaError::InvalidData(format!(
+                    "{} sent an unsupported authentication challenge: {challenge}",
+                    self.base_url
+                ))
+            })?;
+        let mut query: Vec<(&str, String)> = params
+            .iter()
+            .filter(|(key, _)| key == "service" || key == "scope")
+            .map(|(key, value)| (key.as_str(), value.clone()))
+            .collect();
+        if !query.iter().any(|(key, _)| *key == "scope") {
+            query.push(("scope", format!("repository:{}:pull", self.repository)));
+        }
+
+        let url = reqwest::Url::parse_with_params(&realm, &query)
+            .map_err(|e| FeludaError::InvalidData(format!("Invalid token realm {realm}: {e}")))?;
+        let mut request = self.client.get(url);
+        if let Some(ref credentials) = self.credentials {
+            request = request.header(AUTHORIZATION, format!("Basic {credentials}"));
+        }
+        let response = request.send()?;
+        if !response.status().is_success() {
+            return Err(FeludaError::InvalidData(format!(
+                "Token request to {realm} failed with {}",
+                response.status()
+            )));
+        }
+        let body: Value = response.json()?;
+        body.get("token")
+            .or_else(|| body.get("access_token"))
+            .and_then(Value::as_str)
+            .map(|token| format!("Bearer {token}"))
+            .ok_or_else(|| FeludaError::InvalidData(format!("{realm} returned no token")))
+    }
+}
+
+/// `realm="…",service="…",scope="…"` pairs of a bearer challenge
+fn parse_challenge(params: &str) -> Vec<(String, String)> {
+    let pair = Regex::new(r#"(\w+)="([^"]*)""#).expect("valid challenge regex");
+    pair.captures_iter(params)
+        .map(|caps| (caps[1].to_string(), caps[2].to_string()))
+        .collect()
+}
+
+/// The `auth` entry `docker login` stored for `registry`
+fn docker_config_credentials(registry: &str) -> Option<String> {
+    let config_dir = std::env::var_os("DOCKER_CONFIG")
+        .map(std::path::PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".docker")))?;
+    let config: Value =
+        serde_json::from_str(&std::fs::read_to_string(config_dir.join("config.json")).ok()?)
+            .ok()?;
+    let auths = config.get("auths")?.as_object()?;
+    let keys: &[&str] = if registry == DOCKER_HUB {
+        &[
+            "https://index.docker.io/v1/",
+            "docker.io",
+            "index.docker.io",
+        ]
+    } else {
+        &[registry]
+    };
+    auths
+        .iter()
+        .find(|(key, _)| {
+            let host = key
+                .trim_start_matches("https://")
+                .trim_start_matches("http://")
+                .trim_end_matches('/');
+            keys.contains(&key.as_str()) || keys.contains(&host)
+        })
+        .and_then(|(_, entry)| entry.get("auth")?.as_str())
+        .filter(|auth| !auth.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_image_reference() {
+        let alpine = ImageReference::parse("alpine").unwrap();
+        assert_eq!(alpine.registry, DOCKER_HUB);
+        assert_eq!(alpine.repository, "library/alpine");
+        assert_eq!(alpine.reference, "latest");
+
+        let ghcr = ImageReference::parse("ghcr.io/anistark/feluda:1.2.0").unwrap();
+        assert_eq!(ghcr.registry, "ghcr.io");
+        assert_eq!(ghcr.repository, "anistark/feluda");
+        assert_eq!(ghcr.reference, "1.2.0");
+
+        let local = ImageReference::parse("localhost:5000/app@sha256:abc").unwrap();
+        assert_eq!(local.registry, "localhost:5000");
+        assert_eq!(local.repository, "app");
+        assert_eq!(local.reference, "sha256:abc");
+        assert_eq!(local.to_string(), "localhost:5000/app@sha256:abc");
+
+        let user = ImageReference::parse("docker.io/bitnami/redis").unwrap();
+        assert_eq!(user.registry, DOCKER_HUB);
+        assert_eq!(user.repository, "bitnami/redis");
+
+        assert!(ImageReference::parse("").is_none());
+    }
+
+    #[test]
+    fn test_parse_challenge() {
+        let params = parse_challenge(
+            r#"realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:library/alpine:pull""#,
+        );
+        assert_eq!(
+            params,
+            vec![
+                (
+                    "realm".to_string(),
+                    "https://auth.docker.io/token".to_string()
+                ),
+                ("service".to_string(), "registry.docker.io".to_string()),
+                (
+                    "scope".to_string(),
+                    "repository:library/alpine:pull".to_string()
+                ),
+            ]
+        );
+    }
+}
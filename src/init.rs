@@ -24,6 +24,7 @@ fn detect_languages(path: &Path) -> Vec<String> {
         ("pom.xml", "Java/Maven"),
         ("build.gradle", "Java/Gradle"),
         ("build.gradle.kts", "Java/Gradle"),
+        ("gradle/libs.versions.toml", "Android/Kotlin"),
         ("CMakeLists.txt", "C++"),
         ("vcpkg.json", "C++"),
         ("conanfile.txt", "C++"),
@@ -35,6 +36,12 @@ fn detect_languages(path: &Path) -> Vec<String> {
         ("renv.lock", "R"),
         ("composer.json", "PHP"),
         ("composer.lock", "PHP"),
+        ("mix.exs", "Elixir"),
+        ("mix.lock", "Elixir"),
+        ("wkg.toml", "WebAssembly"),
+        ("wkg.lock", "WebAssembly"),
+        ("Cartfile", "Carthage"),
+        ("Cartfile.resolved", "Carthage"),
         ("Gemfile", "Ruby"),
         ("Gemfile.lock", "Ruby"),
     ];
@@ -53,6 +60,7 @@ fn detect_languages(path: &Path) -> Vec<String> {
             if (name_str.ends_with(".csproj")
                 || name_str.ends_with(".fsproj")
                 || name_str.ends_with(".vbproj")
+                || name_str.ends_with(".sln")
                 || name_str.ends_with(".slnx"))
                 && !detected.contains(&"C#/.NET")
             {
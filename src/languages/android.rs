@@ -0,0 +1,720 @@
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::FeludaConfig;
+use crate::debug::{log, record_parse_warning, LogLevel};
+use crate::languages::java::{
+    maven_licenses, parse_gradle_coordinate, parse_gradle_dependencies, read_gradle_lockfiles,
+    read_gradle_properties, resolve_gradle_variable, JavaDependency,
+};
+use crate::licenses::LicenseInfo;
+
+/// Where Gradle looks for the default version catalog
+const VERSION_CATALOG: &str = "gradle/libs.versions.toml";
+
+/// Build files of a Gradle project or module, Kotlin DSL first
+const BUILD_FILES: [&str; 2] = ["build.gradle.kts", "build.gradle"];
+
+/// Libraries and bundles declared in a `libs.versions.toml`, keyed by normalized alias
+#[derive(Debug, Default)]
+struct VersionCatalog {
+    libraries: HashMap<String, JavaDependency>,
+    bundles: HashMap<String, Vec<String>>,
+}
+
+/// Whether the Gradle build in `dir` is an Android or Kotlin Multiplatform one: it declares
+/// its dependencies in a version catalog, or applies the Android or KMP plugin. Other
+/// Gradle builds are left to the Java analyzer.
+pub fn is_android_project(dir: &Path) -> bool {
+    if dir.join(VERSION_CATALOG).is_file() {
+        return true;
+    }
+    ["settings.gradle.kts", "settings.gradle"]
+        .iter()
+        .chain(BUILD_FILES.iter())
+        .filter_map(|name| fs::read_to_string(dir.join(name)).ok())
+        .any(|content| {
+            content.contains("com.android.")
+                || content.contains("kotlin(\"multiplatform\")")
+                || content.contains("org.jetbrains.kotlin.multiplatform")
+        })
+}
+
+pub fn analyze_android_licenses(file_path: &str, config: &FeludaConfig) -> Vec<LicenseInfo> {
+    log(
+        LogLevel::Info,
+        &format!("Analyzing Android/Kotlin Multiplatform dependencies from: {file_path}"),
+    );
+
+    let project_dir = Path::new(file_path).parent().unwrap_or(Path::new("."));
+    let settings = match fs::read_to_string(file_path) {
+        Ok(content) => content,
+        Err(e) => {
+            record_parse_warning(file_path, format!("failed to read Gradle settings: {e}"));
+            return Vec::new();
+        }
+    };
+
+    let dirs = module_dirs(project_dir, &settings);
+
+    // A license plugin's report already resolved the classpath and its licenses
+    let reports = read_license_reports(&dirs, config.no_dev);
+    if !reports.is_empty() {
+        log(
+            LogLevel::Info,
+            &format!(
+                "Using {} dependencies from Gradle license plugin reports",
+                reports.len()
+            ),
+        );
+        let reported: HashMap<String, String> = reports
+            .iter()
+            .filter_map(|(dep, license)| {
+                let license = license.clone()?;
+                Some((format!("{}:{}", dep.group_id, dep.artifact_id), license))
+            })
+            .collect();
+        let deps = reports.into_iter().map(|(dep, _)| dep).collect();
+        return maven_licenses(sorted(deps), true, &reported, config);
+    }
+
+    let (deps, locked) = collect_android_dependencies(project_dir, &dirs, config.no_dev);
+    if deps.is_empty() {
+        log(LogLevel::Warn, "No Android dependencies found");
+        return Vec::new();
+    }
+
+    maven_licenses(deps, locked, &HashMap::new(), config)
+}
+
+/// The build's dependencies and whether they come from Gradle lockfiles, which already
+/// pin the full transitive set. Otherwise they are the catalog entries and literal
+/// coordinates declared in `dirs`, the root project and its included modules.
+fn collect_android_dependencies(
+    project_dir: &Path,
+    dirs: &[PathBuf],
+    no_dev: bool,
+) -> (Vec<JavaDependency>, bool) {
+    let locked: Vec<JavaDependency> = dirs
+        .iter()
+        .filter_map(|dir| read_gradle_lockfiles(dir))
+        .flatten()
+        .collect();
+    if !locked.is_empty() {
+        return (sorted(locked), true);
+    }
+
+    let catalog = read_version_catalog(&project_dir.join(VERSION_CATALOG));
+    let props = read_gradle_properties(project_dir);
+    let mut deps = Vec::new();
+    let mut referenced = false;
+
+    for dir in dirs {
+        let Some(content) = BUILD_FILES
+            .iter()
+            .find_map(|name| fs::read_to_string(dir.join(name)).ok())
+        else {
+            continue;
+        };
+        for alias in catalog_references(&content, no_dev) {
+            referenced = true;
+            deps.extend(catalog.resolve(&alias));
+        }
+        deps.extend(parse_gradle_dependencies(&content));
+    }
+
+    // Build files that reach the catalog some other way (convention plugins, buildSrc)
+    // still ship what it declares
+    if !referenced {
+        deps.extend(catalog.libraries.into_values());
+    }
+
+    for dep in &mut deps {
+        dep.version = resolve_gradle_variable(&dep.version, &props);
+    }
+    (sorted(deps), false)
+}
+
+fn sorted(mut deps: Vec<JavaDependency>) -> Vec<JavaDependency> {
+    deps.sort_by(|a, b| {
+        a.group_id
+            .cmp(&b.group_id)
+            .then(a.artifact_id.cmp(&b.artifact_id))
+    });
+    deps.dedup_by(|a, b| a.group_id == b.group_id && a.artifact_id == b.artifact_id);
+    deps
+}
+
+// =============================================================================
+// SETTINGS AND BUILD FILE PARSING
+// =============================================================================
+
+/// The root project's directory and those of the modules its settings file includes
+fn module_dirs(project_dir: &Path, settings: &str) -> Vec<PathBuf> {
+    let mut dirs = vec![project_dir.to_path_buf()];
+    dirs.extend(
+        included_modules(settings)
+            .into_iter()
+            .map(|module| project_dir.join(module)),
+    );
+    dirs
+}
+
+/// Directories of the modules `include`d by a settings file, `:core:data` being `core/data`
+fn included_modules(settings: &str) -> Vec<PathBuf> {
+    let include_re = Regex::new(r#"(?m)^\s*include\s*\(?([^)\n]*)"#).unwrap();
+    let module_re = Regex::new(r#"["']:?([^"']+)["']"#).unwrap();
+
+    include_re
+        .captures_iter(settings)
+        .flat_map(|cap| {
+            module_re
+                .captures_iter(&cap[1])
+                .map(|module| module[1].split(':').collect::<PathBuf>())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Catalog aliases (`libs.androidx.core.ktx`) a build file's dependency blocks use, in
+/// configurations that end up in the app. Test, annotation processor and, with
+/// `no_dev`, debug-only configurations are left out.
+fn catalog_references(content: &str, no_dev: bool) -> Vec<String> {
+    let reference_re = Regex::new(
+        r#"\b([A-Za-z]+)\s*[\(\s]\s*(?:(?:platform|enforcedPlatform)\s*\(\s*)?libs\.([A-Za-z0-9_.]+)"#,
+    )
+    .unwrap();
+    let shipped = |configuration: &str| {
+        let lower = configuration.to_ascii_lowercase();
+        ["implementation", "api", "compileonly", "runtimeonly"]
+            .iter()
+            .any(|suffix| lower.ends_with(suffix))
+            && !lower.contains("test")
+            && !(no_dev && lower.starts_with("debug"))
+    };
+
+    reference_re
+        .captures_iter(content)
+        .filter(|cap| shipped(&cap[1]))
+        .map(|cap| {
+            let alias = cap[2].trim_end_matches(".get");
+            normalize_alias(alias)
+        })
+        .collect()
+}
+
+/// Gradle turns `-`, `_` and `.` in catalog aliases into the same accessor separator
+fn normalize_alias(alias: &str) -> String {
+    alias.replace(['-', '_'], ".").to_ascii_lowercase()
+}
+
+// =============================================================================
+// VERSION CATALOG PARSING
+// =============================================================================
+
+fn read_version_catalog(path: &Path) -> VersionCatalog {
+    match fs::read_to_string(path) {
+        Ok(content) => parse_version_catalog(&content).unwrap_or_else(|e| {
+            record_parse_warning(
+                path.display().to_string(),
+                format!("invalid version catalog: {e}"),
+            );
+            VersionCatalog::default()
+        }),
+        Err(_) => VersionCatalog::default(),
+    }
+}
+
+fn parse_version_catalog(content: &str) -> Result<VersionCatalog, toml::de::Error> {
+    let table: toml::Table = toml::from_str(content)?;
+    let empty = toml::Table::new();
+    let section = |name: &str| table.get(name).and_then(|v| v.as_table()).unwrap_or(&empty);
+
+    let versions: HashMap<&str, String> = section("versions")
+        .iter()
+        .filter_map(|(name, value)| Some((name.as_str(), version_value(value)?)))
+        .collect();
+
+    let libraries = section("libraries")
+        .iter()
+        .filter_map(|(alias, value)| {
+            let dep = match value {
+                toml::Value::String(coordinate) => parse_gradle_coordinate(coordinate)?,
+                toml::Value::Table(library) => {
+                    let (group_id, artifact_id) = match library.get("module") {
+                        Some(module) => {
+                            let (group, name) = module.as_str()?.split_once(':')?;
+                            (group.to_string(), name.to_string())
+                        }
+                        None => (
+                            library.get("group")?.as_str()?.to_string(),
+                            library.get("name")?.as_str()?.to_string(),
+                        ),
+                    };
+                    let version = match library.get("version") {
+                        Some(toml::Value::Table(version)) if version.contains_key("ref") => {
+                            let name = version.get("ref")?.as_str()?;
+                            versions.get(name).cloned()
+                        }
+                        Some(version) => version_value(version),
+                        // Managed by a platform (BOM) the build imports
+                        None => None,
+                    };
+                    JavaDependency {
+                        group_id,
+                        artifact_id,
+                        version: version.unwrap_or_else(|| "RELEASE".to_string()),
+                    }
+                }
+                _ => return None,
+            };
+            Some((normalize_alias(alias), dep))
+        })
+        .collect();
+
+    let bundles = section("bundles")
+        .iter()
+        .filter_map(|(name, aliases)| {
+            let aliases = aliases
+                .as_array()?
+                .iter()
+                .filter_map(|alias| alias.as_str().map(normalize_alias))
+                .collect();
+            Some((normalize_alias(name), aliases))
+        })
+        .collect();
+
+    Ok(VersionCatalog { libraries, bundles })
+}
+
+/// A concrete version: a plain string, or the `strictly`/`require`/`prefer` of a rich one
+fn version_value(value: &toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(version) => Some(version.clone()),
+        toml::Value::Table(rich) => ["strictly", "require", "prefer"]
+            .iter()
+            .find_map(|key| rich.get(*key)?.as_str())
+            .map(|version| version.trim_matches(['[', ']']).to_string()),
+        _ => None,
+    }
+}
+
+impl VersionCatalog {
+    /// The libraries a `libs.` accessor stands for: one library, or a whole bundle
+    fn resolve(&self, alias: &str) -> Vec<JavaDependency> {
+        if let Some(bundle) = alias.strip_prefix("bundles.") {
+            let mut seen = HashSet::new();
+            return self
+                .bundles
+                .get(bundle)
+                .into_iter()
+                .flatten()
+                .filter(|alias| seen.insert(alias.as_str()))
+                .filter_map(|alias| self.libraries.get(alias).cloned())
+                .collect();
+        }
+        self.libraries.get(alias).cloned().into_iter().collect()
+    }
+}
+
+// =============================================================================
+// LICENSE PLUGIN REPORTS
+// =============================================================================
+
+/// Where `app.cash.licensee` writes `artifacts.json`, per variant on Android builds
+const LICENSEE_REPORTS: &str = "build/reports/licensee";
+
+/// Where `com.jaredsburrows.license` writes its `license<Variant>Report.json`
+const LICENSE_PLUGIN_REPORTS: &str = "build/reports/licenses";
+
+/// A dependency a license plugin report lists, with the license it recorded if any
+type ReportedDependency = (JavaDependency, Option<String>);
+
+/// Dependencies and licenses from the reports Gradle license plugins left in the modules'
+/// build directories. The reports list a variant's resolved runtime classpath, so together
+/// they are the full dependency set. With `no_dev`, debug variants are left out.
+fn read_license_reports(dirs: &[PathBuf], no_dev: bool) -> Vec<ReportedDependency> {
+    let mut reported: Vec<ReportedDependency> = Vec::new();
+    for dir in dirs {
+        for (variant, path) in licensee_reports(&dir.join(LICENSEE_REPORTS))
+            .into_iter()
+            .chain(license_plugin_reports(&dir.join(LICENSE_PLUGIN_REPORTS)))
+        {
+            if no_dev && variant.to_lowercase().contains("debug") {
+                continue;
+            }
+            log(
+                LogLevel::Info,
+                &format!("Reading license report {}", path.display()),
+            );
+            let parsed = fs::read_to_string(&path)
+                .map_err(|e| format!("failed to read license report: {e}"))
+                .and_then(|content| {
+                    serde_json::from_str::<serde_json::Value>(&content)
+                        .map_err(|e| format!("invalid license report: {e}"))
+                });
+            match parsed {
+                Ok(report) => reported.extend(parse_license_report(&report)),
+                Err(reason) => record_parse_warning(path.display().to_string(), reason),
+            }
+        }
+    }
+
+    // An artifact several reports list keeps the first license recorded for it
+    let mut merged: HashMap<(String, String), ReportedDependency> = HashMap::new();
+    for (dep, license) in reported {
+        let key = (dep.group_id.clone(), dep.artifact_id.clone());
+        match merged.get_mut(&key) {
+            Some(existing) => {
+                if existing.1.is_none() {
+                    existing.1 = license;
+                }
+            }
+            None => {
+                merged.insert(key, (dep, license));
+            }
+        }
+    }
+    merged.into_values().collect()
+}
+
+/// `artifacts.json` files under a licensee report directory, keyed by variant. JVM builds
+/// write one at the top; Android builds one per variant directory.
+fn licensee_reports(dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut reports: Vec<(String, PathBuf)> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| {
+            (
+                entry.file_name().to_string_lossy().into_owned(),
+                entry.path().join("artifacts.json"),
+            )
+        })
+        .chain(std::iter::once((String::new(), dir.join("artifacts.json"))))
+        .filter(|(_, path)| path.is_file())
+        .collect();
+    reports.sort();
+    reports
+}
+
+/// `license<Variant>Report.json` files in a license plugin report directory, keyed by variant
+fn license_plugin_reports(dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut reports: Vec<(String, PathBuf)> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let variant = name.strip_prefix("license")?.strip_suffix("Report.json")?;
+            Some((variant.to_string(), entry.path()))
+        })
+        .collect();
+    reports.sort();
+    reports
+}
+
+/// Entries of either report format, a JSON array of artifacts.
+///
+/// licensee records `groupId`/`artifactId`/`version` with SPDX identifiers in
+/// `spdxLicenses` and anything it could not match in `unknownLicenses`; the license plugin
+/// records a `group:artifact:version` `dependency` with the POM's license names in
+/// `licenses`. Several licenses mean the artifact may be used under any of them.
+fn parse_license_report(report: &serde_json::Value) -> Vec<ReportedDependency> {
+    let text = |value: &serde_json::Value, key: &str| {
+        value.get(key).and_then(|v| v.as_str()).map(str::to_string)
+    };
+
+    report
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let dep = match text(entry, "dependency") {
+                Some(coordinate) => parse_gradle_coordinate(&coordinate)?,
+                None => JavaDependency {
+                    group_id: text(entry, "groupId")?,
+                    artifact_id: text(entry, "artifactId")?,
+                    version: text(entry, "version").unwrap_or_default(),
+                },
+            };
+
+            let mut licenses: Vec<String> = Vec::new();
+            let sources = [
+                ("spdxLicenses", "identifier"),
+                ("unknownLicenses", "name"),
+                ("licenses", "license"),
+            ];
+            for (list, key) in sources {
+                for license in entry
+                    .get(list)
+                    .and_then(|l| l.as_array())
+                    .into_iter()
+                    .flatten()
+                {
+                    if let Some(name) = text(license, key).filter(|n| !n.trim().is_empty()) {
+                        if !licenses.contains(&name) {
+                            licenses.push(name);
+                        }
+                    }
+                }
+            }
+            let license = (!licenses.is_empty()).then(|| licenses.join(" OR "));
+            Some((dep, license))
+        })
+        .collect()
+}
+
+// TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const CATALOG: &str = r#"
+[versions]
+agp = "8.5.0"
+coreKtx = "1.13.1"
+compose-bom = "2024.06.00"
+okhttp = { strictly = "4.12.0" }
+
+[libraries]
+androidx-core-ktx = { group = "androidx.core", name = "core-ktx", version.ref = "coreKtx" }
+androidx-compose-bom = { module = "androidx.compose:compose-bom", version.ref = "compose-bom" }
+androidx-ui = { group = "androidx.compose.ui", name = "ui" }
+androidx-material3 = { module = "androidx.compose.material3:material3" }
+okhttp = { module = "com.squareup.okhttp3:okhttp", version.ref = "okhttp" }
+junit = "junit:junit:4.13.2"
+
+[bundles]
+compose = ["androidx-ui", "androidx-material3"]
+
+[plugins]
+android-application = { id = "com.android.application", version.ref = "agp" }
+"#;
+
+    #[test]
+    fn test_parse_version_catalog() {
+        let catalog = parse_version_catalog(CATALOG).unwrap();
+        assert_eq!(catalog.libraries.len(), 6);
+
+        let core = &catalog.libraries["androidx.core.ktx"];
+        assert_eq!(core.group_id, "androidx.core");
+        assert_eq!(core.artifact_id, "core-ktx");
+        assert_eq!(core.version, "1.13.1");
+        assert_eq!(catalog.libraries["okhttp"].version, "4.12.0");
+        assert_eq!(catalog.libraries["junit"].version, "4.13.2");
+        // Versions managed by the Compose BOM
+        assert_eq!(catalog.libraries["androidx.ui"].version, "RELEASE");
+
+        let bundle = catalog.resolve("bundles.compose");
+        let artifacts: Vec<&str> = bundle.iter().map(|d| d.artifact_id.as_str()).collect();
+        assert_eq!(artifacts, vec!["ui", "material3"]);
+        assert!(catalog.resolve("missing").is_empty());
+        assert!(parse_version_catalog("[libraries\n").is_err());
+    }
+
+    #[test]
+    fn test_catalog_references() {
+        let build = r#"
+dependencies {
+    implementation(libs.androidx.core.ktx)
+    implementation(platform(libs.androidx.compose.bom))
+    implementation(libs.bundles.compose)
+    debugImplementation(libs.androidx.ui.tooling)
+    testImplementation(libs.junit)
+    androidTestImplementation(libs.androidx.espresso.core)
+    ksp(libs.room.compiler)
+}
+kotlin {
+    sourceSets {
+        commonMain.dependencies {
+            api(libs.okhttp.get())
+        }
+    }
+}
+"#;
+        let refs = catalog_references(build, false);
+        assert_eq!(
+            refs,
+            vec![
+                "androidx.core.ktx",
+                "androidx.compose.bom",
+                "bundles.compose",
+                "androidx.ui.tooling",
+                "okhttp",
+            ]
+        );
+        assert!(!catalog_references(build, true).contains(&"androidx.ui.tooling".to_string()));
+
+        let groovy = "dependencies {\n    implementation libs.androidx.core.ktx\n}\n";
+        assert_eq!(catalog_references(groovy, false), vec!["androidx.core.ktx"]);
+    }
+
+    #[test]
+    fn test_included_modules() {
+        let settings = r#"
+rootProject.name = "MyApp"
+include(":app")
+include(":core:data", ":feature:home")
+include ':legacy'
+"#;
+        assert_eq!(
+            included_modules(settings),
+            vec![
+                PathBuf::from("app"),
+                PathBuf::from("core/data"),
+                PathBuf::from("feature/home"),
+                PathBuf::from("legacy"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_android_dependencies() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("gradle")).unwrap();
+        fs::create_dir_all(root.join("app")).unwrap();
+        fs::write(root.join(VERSION_CATALOG), CATALOG).unwrap();
+        let settings = "include(\":app\")\n";
+        fs::write(root.join("settings.gradle.kts"), settings).unwrap();
+        fs::write(
+            root.join("build.gradle.kts"),
+            "plugins {\n    alias(libs.plugins.android.application) apply false\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("app").join("build.gradle.kts"),
+            r#"dependencies {
+    implementation(libs.androidx.core.ktx)
+    implementation(libs.bundles.compose)
+    implementation("com.jakewharton.timber:timber:5.0.1")
+    testImplementation(libs.junit)
+}
+"#,
+        )
+        .unwrap();
+        assert!(is_android_project(root));
+
+        let (deps, locked) =
+            collect_android_dependencies(root, &module_dirs(root, settings), false);
+        assert!(!locked);
+        let coords: Vec<String> = deps
+            .iter()
+            .map(|d| format!("{}:{}", d.group_id, d.artifact_id))
+            .collect();
+        assert_eq!(
+            coords,
+            vec![
+                "androidx.compose.material3:material3",
+                "androidx.compose.ui:ui",
+                "androidx.core:core-ktx",
+                "com.jakewharton.timber:timber",
+            ]
+        );
+
+        // A module's lockfile pins the full set instead
+        fs::write(
+            root.join("app").join("gradle.lockfile"),
+            "androidx.core:core:1.13.1=releaseRuntimeClasspath\n",
+        )
+        .unwrap();
+        let (deps, locked) =
+            collect_android_dependencies(root, &module_dirs(root, settings), false);
+        assert!(locked);
+        assert_eq!(deps.len(), 1);
+    }
+
+    #[test]
+    fn test_read_license_reports() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let app = root.join("app");
+
+        let licensee = app.join(LICENSEE_REPORTS);
+        fs::create_dir_all(licensee.join("release")).unwrap();
+        fs::create_dir_all(licensee.join("debug")).unwrap();
+        fs::write(
+            licensee.join("release").join("artifacts.json"),
+            r#"[
+  {"groupId": "androidx.core", "artifactId": "core", "version": "1.13.1",
+   "spdxLicenses": [{"identifier": "Apache-2.0", "name": "Apache License 2.0"}]},
+  {"groupId": "com.example", "artifactId": "dual", "version": "2.0.0",
+   "spdxLicenses": [{"identifier": "MIT"}], "unknownLicenses": [{"name": "Custom"}]},
+  {"groupId": "com.example", "artifactId": "bare", "version": "0.1.0"}
+]"#,
+        )
+        .unwrap();
+        fs::write(
+            licensee.join("debug").join("artifacts.json"),
+            r#"[{"groupId": "com.squareup.leakcanary", "artifactId": "leakcanary-android", "version": "2.14"}]"#,
+        )
+        .unwrap();
+
+        let plugin = root.join(LICENSE_PLUGIN_REPORTS);
+        fs::create_dir_all(&plugin).unwrap();
+        fs::write(
+            plugin.join("licenseReleaseReport.json"),
+            r#"[
+  {"project": "OkHttp", "dependency": "com.squareup.okhttp3:okhttp:4.12.0",
+   "licenses": [{"license": "The Apache Software License, Version 2.0"}]},
+  {"project": "Core", "dependency": "androidx.core:core:1.13.1", "licenses": []}
+]"#,
+        )
+        .unwrap();
+
+        let dirs = module_dirs(root, "include(\":app\")\n");
+        let reported = read_license_reports(&dirs, false);
+        let licenses: HashMap<String, Option<String>> = reported
+            .iter()
+            .map(|(dep, license)| {
+                (
+                    format!("{}:{}", dep.group_id, dep.artifact_id),
+                    license.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(licenses.len(), 5);
+        assert_eq!(
+            licenses["androidx.core:core"].as_deref(),
+            Some("Apache-2.0")
+        );
+        assert_eq!(
+            licenses["com.example:dual"].as_deref(),
+            Some("MIT OR Custom")
+        );
+        assert_eq!(licenses["com.example:bare"], None);
+        assert_eq!(
+            licenses["com.squareup.okhttp3:okhttp"].as_deref(),
+            Some("The Apache Software License, Version 2.0")
+        );
+        assert!(licenses.contains_key("com.squareup.leakcanary:leakcanary-android"));
+
+        let reported = read_license_reports(&dirs, true);
+        assert_eq!(reported.len(), 4);
+        assert!(reported
+            .iter()
+            .all(|(dep, _)| dep.artifact_id != "leakcanary-android"));
+    }
+
+    #[test]
+    fn test_is_android_project_plugin_without_catalog() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("build.gradle"),
+            "apply plugin: 'java'\n",
+        )
+        .unwrap();
+        assert!(!is_android_project(temp_dir.path()));
+
+        fs::write(
+            temp_dir.path().join("build.gradle.kts"),
+            "plugins {\n    id(\"com.android.application\")\n}\n",
+        )
+        .unwrap();
+        assert!(is_android_project(temp_dir.path()));
+    }
+}
@@ -6,10 +6,12 @@ use std::process::Command;
 
 use crate::config::FeludaConfig;
 use crate::debug::{log, log_debug, log_error, LogLevel};
+use crate::languages::system::system_package_license;
 use crate::licenses::{
     detect_license_in_dir, fetch_licenses_from_github, is_license_restrictive,
     LicenseCompatibility, LicenseInfo,
 };
+use crate::resolver::{resolve_all, resolve_license, LicenseQuery};
 
 pub fn analyze_c_licenses(project_path: &str, config: &FeludaConfig) -> Vec<LicenseInfo> {
     log(
@@ -56,39 +58,50 @@ pub fn analyze_c_licenses(project_path: &str, config: &FeludaConfig) -> Vec<Lice
 
     let dependencies = all_deps;
 
-    dependencies
-        .into_iter()
-        .map(|(name, version)| {
-            log(
-                LogLevel::Info,
-                &format!("Processing dependency: {name} ({version})"),
-            );
+    resolve_all(&dependencies, |(name, version)| {
+        log(
+            LogLevel::Info,
+            &format!("Processing dependency: {name} ({version})"),
+        );
 
-            let license_result = fetch_license_for_c_dependency(&name, &version);
-            let license = Some(license_result);
-            let is_restrictive = is_license_restrictive(&license, &known_licenses, config.strict);
+        let license_result = fetch_license_for_c_dependency(name, version);
+        let license = Some(license_result);
+        let is_restrictive = is_license_restrictive(&license, &known_licenses, config.strict);
 
-            if is_restrictive {
-                log(
-                    LogLevel::Warn,
-                    &format!("Restrictive license found: {license:?} for {name}"),
-                );
-            }
+        if is_restrictive {
+            log(
+                LogLevel::Warn,
+                &format!("Restrictive license found: {license:?} for {name}"),
+            );
+        }
 
-            LicenseInfo {
-                name,
-                version,
-                license: license.clone(),
-                is_restrictive,
-                compatibility: LicenseCompatibility::Unknown,
-                osi_status: match &license {
-                    Some(l) => crate::licenses::get_osi_status(l),
-                    None => crate::licenses::OsiStatus::Unknown,
-                },
-                sub_project: None,
-            }
-        })
-        .collect()
+        LicenseInfo {
+            name: name.clone(),
+            version: version.clone(),
+            license: license.clone(),
+            is_restrictive,
+            compatibility: LicenseCompatibility::Unknown,
+            osi_status: match &license {
+                Some(l) => crate::licenses::get_osi_status(l),
+                None => crate::licenses::OsiStatus::Unknown,
+            },
+            sub_project: None,
+            found_in: Vec::new(),
+            repository: None,
+            declared_in: None,
+            internal: false,
+            inherited_from: None,
+            ecosystem: None,
+            suggestions: Vec::new(),
+            update_policy: None,
+            introduced_by: Vec::new(),
+            purl: None,
+            vulnerabilities: None,
+            manifest: None,
+            data_license: None,
+            project_dirs: Vec::new(),
+        }
+    })
 }
 
 fn detect_c_dependencies(project_path: &str, config: &FeludaConfig) -> Vec<(String, String)> {
@@ -547,18 +560,17 @@ fn parse_pkgconfig_dependencies(
 }
 
 fn fetch_license_for_c_dependency(name: &str, version: &str) -> String {
-    if version == "system" {
-        if let Ok(license) = get_system_package_license(name) {
-            return license;
+    // C has no package registry; everything is local to the machine
+    let local = |name: &str, version: &str| {
+        if version == "system" {
+            return system_package_license(name);
         }
-    }
-
-    // Local fallback: Debian-style installs ship a license at /usr/share/doc/<pkg>/copyright.
-    if let Some(license) = detect_license_in_system_doc_dir(name) {
-        return license;
-    }
+        // Debian-style installs ship a license at /usr/share/doc/<pkg>/copyright
+        detect_license_in_system_doc_dir(name)
+    };
 
-    format!("Unknown license for {name}: {version}")
+    resolve_license(&LicenseQuery::new("c", name, version), Some(&local), None)
+        .unwrap_or_else(|| format!("Unknown license for {name}: {version}"))
 }
 
 /// Probe `/usr/share/doc/<pkg>/` for a bundled license file (Debian's `copyright`
@@ -571,36 +583,6 @@ fn detect_license_in_doc_root(doc_root: &Path, package_name: &str) -> Option<Str
     detect_license_in_dir(&doc_root.join(package_name))
 }
 
-fn get_system_package_license(package_name: &str) -> Result<String, String> {
-    if let Ok(output) = Command::new("dpkg-query")
-        .args(["-f", "${Package} ${License}\n", "-W", package_name])
-        .output()
-    {
-        if output.status.success() {
-            let stdout_str = String::from_utf8_lossy(&output.stdout);
-            if let Some(line) = stdout_str.lines().next() {
-                if let Some(space_pos) = line.find(' ') {
-                    return Ok(line[space_pos + 1..].to_string());
-                }
-            }
-        }
-    }
-
-    if let Ok(output) = Command::new("rpm")
-        .args(["-q", "--qf", "%{LICENSE}\n", package_name])
-        .output()
-    {
-        if output.status.success() {
-            let license = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            if !license.is_empty() && license != "(none)" {
-                return Ok(license);
-            }
-        }
-    }
-
-    Err(format!("Could not determine license for {package_name}"))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,269 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::config::FeludaConfig;
+use crate::debug::{log, log_error, record_parse_warning, LogLevel};
+use crate::licenses::{
+    detect_license_in_dir, fetch_licenses_from_github, is_license_restrictive,
+    LicenseCompatibility, LicenseInfo,
+};
+use crate::resolver::{parse_github_repository, resolve_all, resolve_license, LicenseQuery};
+
+/// Where a Carthage dependency comes from
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum CarthageSource {
+    /// A GitHub repository: `owner/repo`, or a full URL for GitHub Enterprise
+    GitHub(String),
+    /// Any git repository URL or local path
+    Git(String),
+    /// A prebuilt framework, described by a JSON spec at this URL
+    Binary(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CarthageDependency {
+    /// The repository or framework name, which is also its directory under `Carthage/Checkouts/`
+    pub(crate) name: String,
+    pub(crate) version: String,
+    pub(crate) source: CarthageSource,
+}
+
+impl CarthageDependency {
+    /// Web URL of the dependency's repository, when it is hosted on GitHub
+    fn repository(&self) -> Option<String> {
+        let location = match &self.source {
+            CarthageSource::GitHub(location) if !location.contains("://") => {
+                return Some(format!("https://github.com/{location}"));
+            }
+            CarthageSource::GitHub(location) | CarthageSource::Git(location) => location,
+            CarthageSource::Binary(_) => return None,
+        };
+        parse_github_repository(location)
+            .map(|(owner, repo)| format!("https://github.com/{owner}/{repo}"))
+    }
+}
+
+pub fn analyze_carthage_licenses(file_path: &str, config: &FeludaConfig) -> Vec<LicenseInfo> {
+    log(
+        LogLevel::Info,
+        &format!("Analyzing Carthage dependencies from: {file_path}"),
+    );
+
+    let content = match fs::read_to_string(file_path) {
+        Ok(c) => c,
+        Err(e) => {
+            record_parse_warning(file_path, format!("failed to read Cartfile: {e}"));
+            return Vec::new();
+        }
+    };
+    let project_dir = Path::new(file_path).parent().unwrap_or(Path::new("."));
+
+    // `Cartfile.resolved` pins the full transitive set; a bare `Cartfile` only lists
+    // direct requirements, with version constraints
+    let mut deps = parse_cartfile(&content);
+
+    // Cartfile.private lists the dependencies only the project's own tests and tooling use
+    if config.no_dev {
+        let private: HashSet<String> = fs::read_to_string(project_dir.join("Cartfile.private"))
+            .map(|content| parse_cartfile(&content))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|dep| dep.name)
+            .collect();
+        deps.retain(|dep| !private.contains(&dep.name));
+    }
+
+    if deps.is_empty() {
+        log(LogLevel::Warn, "No Carthage dependencies found");
+        return Vec::new();
+    }
+
+    log(
+        LogLevel::Info,
+        &format!("Found {} Carthage dependencies", deps.len()),
+    );
+
+    let known_licenses = match fetch_licenses_from_github() {
+        Ok(licenses) => licenses,
+        Err(err) => {
+            log_error("Failed to fetch licenses from GitHub", &err);
+            HashMap::new()
+        }
+    };
+    let checkouts = project_dir.join("Carthage").join("Checkouts");
+
+    resolve_all(&deps, |dep| {
+        let repository = dep.repository();
+        let license = fetch_carthage_license(dep, repository.as_deref(), &checkouts);
+        let is_restrictive =
+            is_license_restrictive(&Some(license.clone()), &known_licenses, config.strict);
+
+        LicenseInfo {
+            name: dep.name.clone(),
+            version: dep.version.clone(),
+            license: Some(license.clone()),
+            is_restrictive,
+            compatibility: LicenseCompatibility::Unknown,
+            osi_status: crate::licenses::get_osi_status(&license),
+            sub_project: None,
+            found_in: Vec::new(),
+            repository,
+            declared_in: None,
+            internal: false,
+            inherited_from: None,
+            ecosystem: None,
+            suggestions: Vec::new(),
+            update_policy: None,
+            introduced_by: Vec::new(),
+            purl: None,
+            vulnerabilities: None,
+            manifest: None,
+            data_license: None,
+            project_dirs: Vec::new(),
+        }
+    })
+}
+
+// =============================================================================
+// CARTFILE PARSING
+// =============================================================================
+
+/// Every dependency a `Cartfile`, `Cartfile.private` or `Cartfile.resolved` lists
+fn parse_cartfile(content: &str) -> Vec<CarthageDependency> {
+    content.lines().filter_map(parse_cartfile_line).collect()
+}
+
+/// One `github "owner/repo" "1.0.0"` line. `Cartfile.resolved` quotes the resolved tag or
+/// commit; a `Cartfile` gives a requirement such as `~> 5.0` or a quoted branch instead,
+/// whose version is kept with the operator dropped.
+pub(crate) fn parse_cartfile_line(line: &str) -> Option<CarthageDependency> {
+    let line = line.split('#').next().unwrap_or(line).trim();
+    let (kind, rest) = line.split_once(char::is_whitespace)?;
+    let rest = rest.trim_start().strip_prefix('"')?;
+    let (location, requirement) = rest.split_once('"')?;
+
+    let source = match kind {
+        "github" => CarthageSource::GitHub(location.to_string()),
+        "git" => CarthageSource::Git(location.to_string()),
+        "binary" => CarthageSource::Binary(location.to_string()),
+        _ => return None,
+    };
+
+    let name = location
+        .trim_end_matches('/')
+        .rsplit(['/', ':'])
+        .next()
+        .map(|name| name.trim_end_matches(".git").trim_end_matches(".json"))
+        .filter(|name| !name.is_empty())?
+        .to_string();
+
+    let version = requirement
+        .trim()
+        .trim_start_matches(['~', '>', '=', '<'])
+        .trim()
+        .trim_matches('"');
+    let version = if version.is_empty() {
+        "latest".to_string()
+    } else {
+        version.to_string()
+    };
+
+    Some(CarthageDependency {
+        name,
+        version,
+        source,
+    })
+}
+
+// =============================================================================
+// LICENSE LOOKUP
+// =============================================================================
+
+/// The license file in the dependency's `Carthage/Checkouts/` directory, then the license
+/// GitHub detected for its repository. Binary frameworks have neither and stay unknown
+/// unless a license override or the cache supplies one.
+fn fetch_carthage_license(
+    dep: &CarthageDependency,
+    repository: Option<&str>,
+    checkouts: &Path,
+) -> String {
+    let local = |_: &str, _: &str| detect_license_in_dir(&checkouts.join(&dep.name));
+
+    resolve_license(
+        &LicenseQuery::new("carthage", &dep.name, &dep.version).with_repository(repository),
+        Some(&local),
+        None,
+    )
+    .unwrap_or_else(|| "Unknown".to_string())
+}
+
+// TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESOLVED: &str = r#"binary "https://dl.google.com/dl/firebase/ios/carthage/FirebaseAnalyticsBinary.json" "10.24.0"
+git "https://github.com/realm/realm-swift.git" "v10.49.1"
+git "https://gitlab.com/acme/Toolkit.git" "2f1c9a0e7b"
+github "Alamofire/Alamofire" "5.9.1"
+github "https://ghe.example.com/mobile/Networking" "1.2.0"
+"#;
+
+    #[test]
+    fn test_parse_cartfile_resolved() {
+        let deps = parse_cartfile(RESOLVED);
+        assert_eq!(deps.len(), 5);
+
+        assert_eq!(deps[0].name, "FirebaseAnalyticsBinary");
+        assert_eq!(deps[0].version, "10.24.0");
+        assert_eq!(deps[0].repository(), None);
+
+        assert_eq!(deps[1].name, "realm-swift");
+        assert_eq!(deps[1].version, "v10.49.1");
+        assert_eq!(
+            deps[1].repository().as_deref(),
+            Some("https://github.com/realm/realm-swift")
+        );
+
+        assert_eq!(deps[2].name, "Toolkit");
+        assert_eq!(deps[2].repository(), None);
+
+        assert_eq!(deps[3].name, "Alamofire");
+        assert_eq!(
+            deps[3].source,
+            CarthageSource::GitHub("Alamofire/Alamofire".to_string())
+        );
+        assert_eq!(
+            deps[3].repository().as_deref(),
+            Some("https://github.com/Alamofire/Alamofire")
+        );
+
+        assert_eq!(deps[4].name, "Networking");
+    }
+
+    #[test]
+    fn test_parse_cartfile_requirements() {
+        let deps = parse_cartfile(
+            r#"# Networking
+github "Alamofire/Alamofire" ~> 5.9
+github "ReactiveX/RxSwift" == 6.6.0
+github "Quick/Nimble" "main" # tests only
+git "file:///Users/dev/LocalKit"
+"#,
+        );
+        let versions: Vec<(&str, &str)> = deps
+            .iter()
+            .map(|dep| (dep.name.as_str(), dep.version.as_str()))
+            .collect();
+        assert_eq!(
+            versions,
+            vec![
+                ("Alamofire", "5.9"),
+                ("RxSwift", "6.6.0"),
+                ("Nimble", "main"),
+                ("LocalKit", "latest"),
+            ]
+        );
+    }
+}
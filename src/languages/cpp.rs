@@ -4,13 +4,17 @@ use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::{Mutex, OnceLock};
 
 use crate::config::FeludaConfig;
-use crate::debug::{log, log_debug, log_error, LogLevel};
+use crate::debug::{log, log_debug, log_error, record_parse_warning, LogLevel};
+use crate::languages::system::system_package_license;
 use crate::licenses::{
     detect_license_in_dir, fetch_licenses_from_github, is_license_restrictive,
     LicenseCompatibility, LicenseInfo,
 };
+use crate::resolver::{resolve_all, resolve_license, LicenseQuery};
+use crate::starlark;
 
 #[derive(Debug, Clone)]
 enum CppPackageManager {
@@ -52,6 +56,12 @@ pub fn analyze_cpp_licenses(project_path: &str, config: &FeludaConfig) -> Vec<Li
     );
     log_debug("Direct C++ dependencies", &direct_dependencies);
 
+    let project_dir = Path::new(project_path).parent().unwrap_or(Path::new("."));
+    let repositories = match package_manager {
+        CppPackageManager::CMake => cmake_repositories(project_dir),
+        _ => HashMap::new(),
+    };
+
     let max_depth = config.dependencies.max_depth;
     log(
         LogLevel::Info,
@@ -73,41 +83,52 @@ pub fn analyze_cpp_licenses(project_path: &str, config: &FeludaConfig) -> Vec<Li
     );
     log_debug("All C++ dependencies", &all_deps);
 
-    let dependencies = all_deps;
+    resolve_all(&all_deps, |(name, version)| {
+        log(
+            LogLevel::Info,
+            &format!("Processing dependency: {name} ({version})"),
+        );
 
-    dependencies
-        .into_iter()
-        .map(|(name, version)| {
+        let repository = repositories.get(name).cloned();
+        let license_result =
+            fetch_license_for_cpp_dependency(project_dir, name, version, repository.as_deref());
+        let license = Some(license_result);
+        let is_restrictive = is_license_restrictive(&license, &known_licenses, config.strict);
+
+        if is_restrictive {
             log(
-                LogLevel::Info,
-                &format!("Processing dependency: {name} ({version})"),
+                LogLevel::Warn,
+                &format!("Restrictive license found: {license:?} for {name}"),
             );
+        }
 
-            let license_result = fetch_license_for_cpp_dependency(&name, &version);
-            let license = Some(license_result);
-            let is_restrictive = is_license_restrictive(&license, &known_licenses, config.strict);
-
-            if is_restrictive {
-                log(
-                    LogLevel::Warn,
-                    &format!("Restrictive license found: {license:?} for {name}"),
-                );
-            }
-
-            LicenseInfo {
-                name,
-                version,
-                license: license.clone(),
-                is_restrictive,
-                compatibility: LicenseCompatibility::Unknown,
-                osi_status: match &license {
-                    Some(l) => crate::licenses::get_osi_status(l),
-                    None => crate::licenses::OsiStatus::Unknown,
-                },
-                sub_project: None,
-            }
-        })
-        .collect()
+        LicenseInfo {
+            name: name.clone(),
+            version: version.clone(),
+            license: license.clone(),
+            is_restrictive,
+            compatibility: LicenseCompatibility::Unknown,
+            osi_status: match &license {
+                Some(l) => crate::licenses::get_osi_status(l),
+                None => crate::licenses::OsiStatus::Unknown,
+            },
+            sub_project: None,
+            found_in: Vec::new(),
+            repository,
+            declared_in: None,
+            internal: false,
+            inherited_from: None,
+            ecosystem: None,
+            suggestions: Vec::new(),
+            update_policy: None,
+            introduced_by: Vec::new(),
+            purl: None,
+            vulnerabilities: None,
+            manifest: None,
+            data_license: None,
+            project_dirs: Vec::new(),
+        }
+    })
 }
 
 fn detect_cpp_dependencies_with_type(
@@ -116,12 +137,18 @@ fn detect_cpp_dependencies_with_type(
 ) -> (Vec<(String, String)>, CppPackageManager) {
     let project_dir = Path::new(project_path).parent().unwrap_or(Path::new("."));
 
-    if let Ok(vcpkg_deps) = parse_vcpkg_dependencies(project_dir, config) {
-        log(
-            LogLevel::Info,
-            &format!("Found {} vcpkg dependencies", vcpkg_deps.len()),
-        );
-        return (vcpkg_deps, CppPackageManager::Vcpkg);
+    match parse_vcpkg_dependencies(project_dir, config) {
+        Ok(vcpkg_deps) => {
+            log(
+                LogLevel::Info,
+                &format!("Found {} vcpkg dependencies", vcpkg_deps.len()),
+            );
+            return (vcpkg_deps, CppPackageManager::Vcpkg);
+        }
+        Err(err) if project_dir.join("vcpkg.json").exists() => {
+            record_parse_warning(project_dir.join("vcpkg.json").display().to_string(), err);
+        }
+        Err(_) => {}
     }
 
     if let Ok(conan_deps) = parse_conan_dependencies(project_dir, config) {
@@ -140,12 +167,18 @@ fn detect_cpp_dependencies_with_type(
         return (cmake_deps, CppPackageManager::CMake);
     }
 
-    if let Ok(bazel_deps) = parse_bazel_dependencies(project_dir, config) {
-        log(
-            LogLevel::Info,
-            &format!("Found {} bazel dependencies", bazel_deps.len()),
-        );
-        return (bazel_deps, CppPackageManager::Bazel);
+    match parse_bazel_dependencies(project_dir, config) {
+        Ok(bazel_deps) => {
+            log(
+                LogLevel::Info,
+                &format!("Found {} bazel dependencies", bazel_deps.len()),
+            );
+            return (bazel_deps, CppPackageManager::Bazel);
+        }
+        Err(err) if project_dir.join("MODULE.bazel").exists() => {
+            record_parse_warning(project_dir.join("MODULE.bazel").display().to_string(), err);
+        }
+        Err(_) => {}
     }
 
     (Vec::new(), CppPackageManager::Unknown)
@@ -257,38 +290,30 @@ fn resolve_vcpkg_transitive(
     _version: &str,
 ) -> Result<Vec<(String, String)>, String> {
     // Try to fetch dependencies from vcpkg registry
-    let url = format!(
-        "https://raw.githubusercontent.com/microsoft/vcpkg/master/ports/{package_name}/vcpkg.json"
-    );
-
-    if let Ok(response) = reqwest::blocking::get(&url) {
-        if response.status().is_success() {
-            if let Ok(json) = response.json::<Value>() {
-                let mut dependencies = Vec::new();
-
-                if let Some(deps) = json.get("dependencies").and_then(|d| d.as_array()) {
-                    for dep in deps {
-                        match dep {
-                            Value::String(name) => {
-                                dependencies.push((name.clone(), "latest".to_string()));
-                            }
-                            Value::Object(obj) => {
-                                if let Some(name) = obj.get("name").and_then(|n| n.as_str()) {
-                                    let version = obj
-                                        .get("version")
-                                        .and_then(|v| v.as_str())
-                                        .unwrap_or("latest");
-                                    dependencies.push((name.to_string(), version.to_string()));
-                                }
-                            }
-                            _ => {}
+    if let Some(json) = fetch_registry_json(&vcpkg_port_url(package_name)) {
+        let mut dependencies = Vec::new();
+
+        if let Some(deps) = json.get("dependencies").and_then(|d| d.as_array()) {
+            for dep in deps {
+                match dep {
+                    Value::String(name) => {
+                        dependencies.push((name.clone(), "latest".to_string()));
+                    }
+                    Value::Object(obj) => {
+                        if let Some(name) = obj.get("name").and_then(|n| n.as_str()) {
+                            let version = obj
+                                .get("version")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("latest");
+                            dependencies.push((name.to_string(), version.to_string()));
                         }
                     }
+                    _ => {}
                 }
-
-                return Ok(dependencies);
             }
         }
+
+        return Ok(dependencies);
     }
 
     Ok(Vec::new())
@@ -299,29 +324,23 @@ fn resolve_conan_transitive(
     version: &str,
 ) -> Result<Vec<(String, String)>, String> {
     // Try to fetch dependencies from Conan Center
-    let url = format!("https://conan.io/center/api/packages/{package_name}/{version}");
-
-    if let Ok(response) = reqwest::blocking::get(&url) {
-        if response.status().is_success() {
-            if let Ok(json) = response.json::<Value>() {
-                let mut dependencies = Vec::new();
-
-                if let Some(requires) = json.get("requires").and_then(|r| r.as_array()) {
-                    for req in requires {
-                        if let Some(req_str) = req.as_str() {
-                            if let Some(slash_pos) = req_str.find('/') {
-                                let name = &req_str[..slash_pos];
-                                let version = &req_str[slash_pos + 1..];
-                                let clean_version = version.split('@').next().unwrap_or(version);
-                                dependencies.push((name.to_string(), clean_version.to_string()));
-                            }
-                        }
+    if let Some(json) = fetch_registry_json(&conan_center_url(package_name, version)) {
+        let mut dependencies = Vec::new();
+
+        if let Some(requires) = json.get("requires").and_then(|r| r.as_array()) {
+            for req in requires {
+                if let Some(req_str) = req.as_str() {
+                    if let Some(slash_pos) = req_str.find('/') {
+                        let name = &req_str[..slash_pos];
+                        let version = &req_str[slash_pos + 1..];
+                        let clean_version = version.split('@').next().unwrap_or(version);
+                        dependencies.push((name.to_string(), clean_version.to_string()));
                     }
                 }
-
-                return Ok(dependencies);
             }
         }
+
+        return Ok(dependencies);
     }
 
     Ok(Vec::new())
@@ -537,51 +556,254 @@ fn parse_cmake_dependencies(
 
     let content = fs::read_to_string(&cmake_file)
         .map_err(|e| format!("Failed to read CMakeLists.txt: {e}"))?;
+    let commands = cmake_commands(&content);
 
-    let mut dependencies = Vec::new();
+    let mut dependencies: Vec<(String, String)> = fetchcontent_declarations(&commands)
+        .into_iter()
+        .map(|source| (source.name, source.version))
+        .collect();
+    let add = |dependencies: &mut Vec<(String, String)>, name: &str, version: &str| {
+        if !dependencies
+            .iter()
+            .any(|(known, _)| known.eq_ignore_ascii_case(name))
+        {
+            dependencies.push((name.to_string(), version.to_string()));
+        }
+    };
+
+    // Content declared in an included file is only visible through MakeAvailable
+    for (_, args) in commands
+        .iter()
+        .filter(|(command, _)| command == "fetchcontent_makeavailable")
+    {
+        for name in args {
+            add(&mut dependencies, name, "git");
+        }
+    }
 
-    let fetchcontent_regex = Regex::new(r"FetchContent_Declare\s*\(\s*(\w+)")
-        .map_err(|e| format!("Failed to compile FetchContent regex: {e}"))?;
+    for (_, args) in commands
+        .iter()
+        .filter(|(command, _)| command == "find_package")
+    {
+        let Some(name) = args.first() else { continue };
+        let version = args
+            .get(1)
+            .filter(|arg| arg.starts_with(|c: char| c.is_ascii_digit()))
+            .map_or("system", String::as_str);
+        add(&mut dependencies, name, version);
+    }
 
-    for cap in fetchcontent_regex.captures_iter(&content) {
-        if let Some(dep_name) = cap.get(1) {
-            dependencies.push((dep_name.as_str().to_string(), "git".to_string()));
+    // Imported targets (`fmt::fmt`) name packages found some other way, e.g. a toolchain file
+    for (_, args) in commands
+        .iter()
+        .filter(|(command, _)| command == "target_link_libraries")
+    {
+        for target in args.iter().skip(1) {
+            if let Some((package, _)) = target.split_once("::") {
+                if !CMAKE_BUILTIN_NAMESPACES.contains(&package) && !package.contains('$') {
+                    add(&mut dependencies, package, "system");
+                }
+            }
         }
     }
 
-    let find_package_regex = Regex::new(r"find_package\s*\(\s*(\w+)(?:\s+([^)]+))?\)")
-        .map_err(|e| format!("Failed to compile find_package regex: {e}"))?;
+    Ok(dependencies)
+}
+
+/// Imported-target namespaces provided by CMake itself rather than a third-party package
+const CMAKE_BUILTIN_NAMESPACES: &[&str] = &["Threads", "CMake"];
+
+/// A `FetchContent_Declare` call
+#[derive(Debug, Clone, PartialEq)]
+struct FetchContentSource {
+    name: String,
+    /// `GIT_TAG`, or a version in the `URL`; `git` when neither pins one
+    version: String,
+    /// `GIT_REPOSITORY` or `URL`, for repository-based license lookups
+    repository: Option<String>,
+}
 
-    for cap in find_package_regex.captures_iter(&content) {
-        if let Some(pkg_name) = cap.get(1) {
-            let version = cap
-                .get(2)
-                .map(|v| v.as_str().trim())
-                .and_then(|v| {
-                    if v.starts_with("REQUIRED") || v.starts_with("COMPONENTS") {
-                        None
-                    } else {
-                        Some(v.split_whitespace().next().unwrap_or("system"))
-                    }
-                })
-                .unwrap_or("system");
+fn fetchcontent_declarations(commands: &[(String, Vec<String>)]) -> Vec<FetchContentSource> {
+    commands
+        .iter()
+        .filter(|(command, _)| command == "fetchcontent_declare")
+        .filter_map(|(_, args)| {
+            let name = args.first()?.clone();
+            let value_of = |key: &str| {
+                args.iter()
+                    .position(|arg| arg == key)
+                    .and_then(|i| args.get(i + 1))
+                    .filter(|value| !value.contains("${"))
+            };
+            let url = value_of("URL");
+            let version = value_of("GIT_TAG")
+                .map(|tag| tag.strip_prefix('v').unwrap_or(tag).to_string())
+                .or_else(|| url.map(String::as_str).and_then(version_in_url))
+                .unwrap_or_else(|| "git".to_string());
+            Some(FetchContentSource {
+                name,
+                version,
+                repository: value_of("GIT_REPOSITORY").or(url).cloned(),
+            })
+        })
+        .collect()
+}
+
+/// The release version in an archive URL: `.../download/v3.11.2/json.tar.xz`,
+/// `.../archive/refs/tags/10.2.1.tar.gz`
+fn version_in_url(url: &str) -> Option<String> {
+    let re = Regex::new(r"[/_-]v?(\d+(?:\.\d+)+)(?:[/._-]|$)").ok()?;
+    re.captures(url)
+        .and_then(|cap| cap.get(1))
+        .map(|m| m.as_str().to_string())
+}
 
-            dependencies.push((pkg_name.as_str().to_string(), version.to_string()));
+/// Repositories of the CMake project's `FetchContent_Declare` sources, by name
+fn cmake_repositories(project_dir: &Path) -> HashMap<String, String> {
+    let Ok(content) = fs::read_to_string(project_dir.join("CMakeLists.txt")) else {
+        return HashMap::new();
+    };
+    fetchcontent_declarations(&cmake_commands(&content))
+        .into_iter()
+        .filter_map(|source| Some((source.name, source.repository?)))
+        .collect()
+}
+
+/// Split a CMake script into commands: `(lowercased name, arguments)`. Handles `#` and
+/// bracket comments, quoted and bracket arguments, and calls spread over several lines.
+fn cmake_commands(content: &str) -> Vec<(String, Vec<String>)> {
+    let chars: Vec<char> = content.chars().collect();
+    let mut commands = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '#' {
+            i = skip_cmake_comment(&chars, i);
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let name: String = chars[start..i].iter().collect();
+            while i < chars.len() && (chars[i] == ' ' || chars[i] == '\t') {
+                i += 1;
+            }
+            if chars.get(i) == Some(&'(') {
+                let (args, end) = read_cmake_arguments(&chars, i + 1);
+                commands.push((name.to_ascii_lowercase(), args));
+                i = end;
+            }
+        } else {
+            i += 1;
         }
     }
+    commands
+}
 
-    Ok(dependencies)
+/// Skip a `#` line comment or `#[[ ... ]]` bracket comment, returning the index after it
+fn skip_cmake_comment(chars: &[char], start: usize) -> usize {
+    if chars.get(start + 1) == Some(&'[') {
+        if let Some((_, end)) = read_cmake_bracket(chars, start + 1) {
+            return end;
+        }
+    }
+    let mut i = start;
+    while i < chars.len() && chars[i] != '\n' {
+        i += 1;
+    }
+    i
+}
+
+/// A `[[...]]` / `[=[...]=]` bracket argument starting at `start`: its content and end index
+fn read_cmake_bracket(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let mut i = start + 1;
+    let mut level = 0;
+    while chars.get(i) == Some(&'=') {
+        level += 1;
+        i += 1;
+    }
+    if chars.get(i) != Some(&'[') {
+        return None;
+    }
+    let close: Vec<char> = std::iter::once(']')
+        .chain(std::iter::repeat_n('=', level))
+        .chain(std::iter::once(']'))
+        .collect();
+    let body_start = i + 1;
+    let mut j = body_start;
+    while j + close.len() <= chars.len() {
+        if chars[j..j + close.len()] == close[..] {
+            return Some((chars[body_start..j].iter().collect(), j + close.len()));
+        }
+        j += 1;
+    }
+    None
+}
+
+/// Arguments of a command whose `(` ends just before `start`, and the index after its `)`
+fn read_cmake_arguments(chars: &[char], start: usize) -> (Vec<String>, usize) {
+    let mut args = Vec::new();
+    let mut depth = 0;
+    let mut i = start;
+
+    while i < chars.len() {
+        match chars[i] {
+            ')' if depth == 0 => return (args, i + 1),
+            ')' => {
+                depth -= 1;
+                i += 1;
+            }
+            '(' => {
+                depth += 1;
+                i += 1;
+            }
+            '#' => i = skip_cmake_comment(chars, i),
+            '"' => {
+                let mut value = String::new();
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' && i + 1 < chars.len() {
+                        i += 1;
+                    }
+                    value.push(chars[i]);
+                    i += 1;
+                }
+                args.push(value);
+                i += 1;
+            }
+            '[' => match read_cmake_bracket(chars, i) {
+                Some((value, end)) => {
+                    args.push(value);
+                    i = end;
+                }
+                None => i += 1,
+            },
+            c if c.is_whitespace() => i += 1,
+            _ => {
+                let arg_start = i;
+                while i < chars.len()
+                    && !chars[i].is_whitespace()
+                    && !matches!(chars[i], '(' | ')' | '"' | '#')
+                {
+                    i += 1;
+                }
+                args.push(chars[arg_start..i].iter().collect());
+            }
+        }
+    }
+    (args, i)
 }
 
 fn parse_bazel_dependencies(
     project_dir: &Path,
-    _config: &FeludaConfig,
+    config: &FeludaConfig,
 ) -> Result<Vec<(String, String)>, String> {
     let module_bazel = project_dir.join("MODULE.bazel");
     let workspace = project_dir.join("WORKSPACE");
 
     if module_bazel.exists() {
-        parse_module_bazel(&module_bazel)
+        parse_module_bazel(&module_bazel, config.no_dev)
     } else if workspace.exists() {
         parse_workspace_bazel(&workspace)
     } else {
@@ -589,20 +811,36 @@ fn parse_bazel_dependencies(
     }
 }
 
-fn parse_module_bazel(module_path: &Path) -> Result<Vec<(String, String)>, String> {
+/// `bazel_dep` declarations from MODULE.bazel. A dep without a literal version (overridden
+/// elsewhere, or computed) is reported as `latest`; `dev_dependency = True` deps are left out
+/// when `no_dev` is set.
+fn parse_module_bazel(module_path: &Path, no_dev: bool) -> Result<Vec<(String, String)>, String> {
     let content =
         fs::read_to_string(module_path).map_err(|e| format!("Failed to read MODULE.bazel: {e}"))?;
+    let calls = starlark::parse_calls(&content)
+        .map_err(|e| format!("Failed to parse MODULE.bazel: {e}"))?;
 
     let mut dependencies = Vec::new();
-
-    let bazel_dep_regex =
-        Regex::new(r#"bazel_dep\s*\(\s*name\s*=\s*"([^"]+)"\s*,\s*version\s*=\s*"([^"]+)""#)
-            .map_err(|e| format!("Failed to compile bazel_dep regex: {e}"))?;
-
-    for cap in bazel_dep_regex.captures_iter(&content) {
-        if let (Some(name), Some(version)) = (cap.get(1), cap.get(2)) {
-            dependencies.push((name.as_str().to_string(), version.as_str().to_string()));
+    for call in calls.iter().filter(|call| call.name == "bazel_dep") {
+        let Some(name) = call.kwarg_str("name") else {
+            log(
+                LogLevel::Warn,
+                &format!(
+                    "MODULE.bazel:{}: bazel_dep without a literal name",
+                    call.line
+                ),
+            );
+            continue;
+        };
+        if no_dev && call.kwarg_bool("dev_dependency") == Some(true) {
+            log(
+                LogLevel::Info,
+                &format!("Skipping Bazel dev dependency: {name}"),
+            );
+            continue;
         }
+        let version = call.kwarg_str("version").unwrap_or("latest");
+        dependencies.push((name.to_string(), version.to_string()));
     }
 
     Ok(dependencies)
@@ -611,56 +849,125 @@ fn parse_module_bazel(module_path: &Path) -> Result<Vec<(String, String)>, Strin
 fn parse_workspace_bazel(workspace_path: &Path) -> Result<Vec<(String, String)>, String> {
     let content =
         fs::read_to_string(workspace_path).map_err(|e| format!("Failed to read WORKSPACE: {e}"))?;
+    let calls =
+        starlark::parse_calls(&content).map_err(|e| format!("Failed to parse WORKSPACE: {e}"))?;
 
-    let mut dependencies = Vec::new();
-
-    let http_archive_regex = Regex::new(r#"http_archive\s*\(\s*name\s*=\s*"([^"]+)""#)
-        .map_err(|e| format!("Failed to compile http_archive regex: {e}"))?;
-
-    for cap in http_archive_regex.captures_iter(&content) {
-        if let Some(name) = cap.get(1) {
-            dependencies.push((name.as_str().to_string(), "archive".to_string()));
-        }
-    }
-
-    Ok(dependencies)
+    Ok(calls
+        .iter()
+        .filter(|call| call.name == "http_archive")
+        .filter_map(|call| call.kwarg_str("name"))
+        .map(|name| (name.to_string(), "archive".to_string()))
+        .collect())
 }
 
-fn fetch_license_for_cpp_dependency(name: &str, version: &str) -> String {
-    match version {
+fn fetch_license_for_cpp_dependency(
+    project_dir: &Path,
+    name: &str,
+    version: &str,
+    repository: Option<&str>,
+) -> String {
+    let local = |name: &str, version: &str| match version {
+        "latest" | "git" => fetch_license_from_local_vcpkg(project_dir, name),
+        "system" => system_package_license(name),
+        v if v.chars().next().unwrap_or('0').is_ascii_digit() => {
+            conan_home().and_then(|home| fetch_license_from_conan_cache(&home, name, version))
+        }
+        _ => None,
+    };
+    let registry = |name: &str, version: &str| match version {
         "latest" | "git" => fetch_license_from_vcpkg_registry(name),
         v if v.chars().next().unwrap_or('0').is_ascii_digit() => {
             fetch_license_from_conan_center(name, version)
         }
-        "system" => fetch_license_from_system_package(name),
+        _ => None,
+    };
+
+    resolve_license(
+        &LicenseQuery::new("cpp", name, version).with_repository(repository),
+        Some(&local),
+        Some(&registry),
+    )
+    .unwrap_or_else(|| match version {
+        "latest" | "git" => format!("Unknown license (vcpkg: {name})"),
+        "system" => format!("Unknown license (system: {name})"),
+        v if v.chars().next().unwrap_or('0').is_ascii_digit() => {
+            format!("Unknown license (conan: {name})")
+        }
         _ => format!("Unknown license for {name}: {version}"),
+    })
+}
+
+fn vcpkg_port_url(port: &str) -> String {
+    format!("https://raw.githubusercontent.com/microsoft/vcpkg/master/ports/{port}/vcpkg.json")
+}
+
+fn conan_center_url(package_name: &str, version: &str) -> String {
+    format!("https://conan.io/center/api/packages/{package_name}/{version}")
+}
+
+/// Process-wide cache of fetched registry documents, keyed by URL. The transitive walk and
+/// the license lookup read the same port manifests and Conan Center entries, and lookups
+/// run in parallel, so each document is fetched once per scan.
+fn registry_json_cache() -> &'static Mutex<HashMap<String, Option<Value>>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Option<Value>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn fetch_registry_json(url: &str) -> Option<Value> {
+    if let Ok(cache) = registry_json_cache().lock() {
+        if let Some(cached) = cache.get(url) {
+            return cached.clone();
+        }
     }
+
+    let result = fetch_registry_json_uncached(url);
+
+    if let Ok(mut cache) = registry_json_cache().lock() {
+        cache.insert(url.to_string(), result.clone());
+    }
+
+    result
 }
 
-fn fetch_license_from_vcpkg_registry(package_name: &str) -> String {
-    let url = format!(
-        "https://raw.githubusercontent.com/microsoft/vcpkg/master/ports/{package_name}/vcpkg.json"
+fn fetch_registry_json_uncached(url: &str) -> Option<Value> {
+    log(
+        LogLevel::Info,
+        &format!("Fetching C++ registry data: {url}"),
     );
 
-    if let Ok(response) = reqwest::blocking::get(&url) {
-        if response.status().is_success() {
-            if let Ok(json) = response.json::<Value>() {
-                if let Some(license) = json.get("license").and_then(|l| l.as_str()) {
-                    return license.to_string();
-                }
-            }
-        }
+    let response = crate::cancel::http_get(url).ok()?;
+    if !response.status().is_success() {
+        return None;
     }
+    response.json::<Value>().ok()
+}
 
-    // Local fallback: vcpkg installs a copyright file at
-    // <VCPKG_ROOT>/installed/<triplet>/share/<port>/copyright.
-    if let Some(root) = vcpkg_root() {
-        if let Some(license) = detect_license_in_vcpkg_install(&root, package_name) {
-            return license;
-        }
+fn fetch_license_from_vcpkg_registry(package_name: &str) -> Option<String> {
+    fetch_registry_json(&vcpkg_port_url(package_name))?
+        .get("license")
+        .and_then(|l| l.as_str())
+        .map(str::to_string)
+}
+
+/// Local vcpkg metadata, checked before the registry: the port's bundled license file from a
+/// manifest-mode install (`<project>/vcpkg_installed`) or a classic install under
+/// `VCPKG_ROOT`, then the `license` field of the port manifest in `VCPKG_ROOT/ports`.
+fn fetch_license_from_local_vcpkg(project_dir: &Path, port: &str) -> Option<String> {
+    let manifest_install = project_dir.join("vcpkg_installed");
+    let mut roots = vec![manifest_install];
+    roots.extend(vcpkg_root().map(|root| root.join("installed")));
+    if let Some(license) = roots
+        .iter()
+        .find_map(|installed| detect_license_in_vcpkg_install(installed, port))
+    {
+        return Some(license);
     }
 
-    format!("Unknown license (vcpkg: {package_name})")
+    let manifest = vcpkg_root()?.join("ports").join(port).join("vcpkg.json");
+    let json: Value = serde_json::from_str(&fs::read_to_string(manifest).ok()?).ok()?;
+    json.get("license")
+        .and_then(|l| l.as_str())
+        .map(str::to_string)
 }
 
 /// The vcpkg root, from the `VCPKG_ROOT` env var.
@@ -668,11 +975,10 @@ fn vcpkg_root() -> Option<PathBuf> {
     std::env::var("VCPKG_ROOT").ok().map(PathBuf::from)
 }
 
-/// Probe a vcpkg tree for an installed port's bundled license file. The triplet
-/// (e.g. `x64-linux`) varies, so every `installed/<triplet>/share/<port>/` dir is tried.
-fn detect_license_in_vcpkg_install(vcpkg_root: &Path, port: &str) -> Option<String> {
-    let installed = vcpkg_root.join("installed");
-    let entries = fs::read_dir(&installed).ok()?;
+/// Probe a vcpkg install tree (`installed` or `vcpkg_installed`) for a port's bundled license
+/// file. The triplet (e.g. `x64-linux`) varies, so every `<triplet>/share/<port>/` dir is tried.
+fn detect_license_in_vcpkg_install(installed: &Path, port: &str) -> Option<String> {
+    let entries = fs::read_dir(installed).ok()?;
     for entry in entries.flatten() {
         let share_pkg = entry.path().join("share").join(port);
         if let Some(license) = detect_license_in_dir(&share_pkg) {
@@ -682,43 +988,62 @@ fn detect_license_in_vcpkg_install(vcpkg_root: &Path, port: &str) -> Option<Stri
     None
 }
 
-fn fetch_license_from_conan_center(package_name: &str, version: &str) -> String {
-    let url = format!("https://conan.io/center/api/packages/{package_name}/{version}");
+fn fetch_license_from_conan_center(package_name: &str, version: &str) -> Option<String> {
+    fetch_registry_json(&conan_center_url(package_name, version))?
+        .get("license")
+        .and_then(|l| l.as_str())
+        .map(str::to_string)
+}
 
-    if let Ok(response) = reqwest::blocking::get(&url) {
-        if response.status().is_success() {
-            if let Ok(json) = response.json::<Value>() {
-                if let Some(license) = json.get("license").and_then(|l| l.as_str()) {
-                    return license.to_string();
-                }
-            }
-        }
-    }
+/// Conan's home: `CONAN_HOME`, or `~/.conan2`
+fn conan_home() -> Option<PathBuf> {
+    std::env::var_os("CONAN_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".conan2")))
+}
 
-    // No local fallback for Conan: its content-addressed cache (~/.conan2/p/<hash>) can't be
-    // mapped to a package name without the `conan` CLI, so file probing isn't reliable here.
-    format!("Unknown license (conan: {package_name})")
+/// The `license` attribute of the recipe exported to the local Conan cache. Conan 2 keeps
+/// recipes under content-addressed `p/<hash>/e/` folders, so every exported recipe is read and
+/// matched on its `name` attribute; Conan 1 (`data/<name>/<version>/...`) is tried as well.
+fn fetch_license_from_conan_cache(conan_home: &Path, name: &str, version: &str) -> Option<String> {
+    let conan2_recipes = fs::read_dir(conan_home.join("p"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path().join("e").join("conanfile.py"));
+    let conan1_recipes = fs::read_dir(conan_home.join("data").join(name).join(version))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .flat_map(|user| fs::read_dir(user.path()).into_iter().flatten().flatten())
+        .map(|channel| channel.path().join("export").join("conanfile.py"));
+
+    conan2_recipes.chain(conan1_recipes).find_map(|recipe| {
+        let content = fs::read_to_string(recipe).ok()?;
+        conan_recipe_license(&content, name)
+    })
 }
 
-fn fetch_license_from_system_package(package_name: &str) -> String {
-    if let Ok(output) = Command::new("pkg-config")
-        .args(["--variable=license", package_name])
-        .output()
-    {
-        if output.status.success() {
-            let license = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            if !license.is_empty() {
-                return license;
-            }
-        }
+/// The `license` of a conanfile.py recipe named `name`. A tuple of licenses means all apply.
+fn conan_recipe_license(content: &str, name: &str) -> Option<String> {
+    let name_re = Regex::new(r#"(?m)^\s*name\s*=\s*["']([^"']+)["']"#).ok()?;
+    let recipe_name = name_re.captures(content)?.get(1)?.as_str();
+    if !recipe_name.eq_ignore_ascii_case(name) {
+        return None;
     }
 
-    // Local fallback: Debian-style installs ship a license at /usr/share/doc/<pkg>/copyright.
-    if let Some(license) = detect_license_in_dir(&Path::new("/usr/share/doc").join(package_name)) {
-        return license;
+    let license_re = Regex::new(r#"(?m)^\s*license\s*=\s*(.+)$"#).ok()?;
+    let value = license_re.captures(content)?.get(1)?.as_str();
+    let quoted = Regex::new(r#"["']([^"']+)["']"#).ok()?;
+    let licenses: Vec<&str> = quoted
+        .captures_iter(value)
+        .filter_map(|cap| cap.get(1).map(|m| m.as_str()))
+        .collect();
+    match licenses.as_slice() {
+        [] => None,
+        [license] => Some(license.to_string()),
+        licenses => Some(licenses.join(" AND ")),
     }
-
-    format!("Unknown license (system: {package_name})")
 }
 
 #[cfg(test)]
@@ -743,16 +1068,113 @@ mod tests {
         )
         .unwrap();
 
+        let installed = temp_dir.path().join("installed");
         assert_eq!(
-            detect_license_in_vcpkg_install(temp_dir.path(), "zlib"),
+            detect_license_in_vcpkg_install(&installed, "zlib"),
             Some("Apache-2.0".to_string())
         );
         assert_eq!(
-            detect_license_in_vcpkg_install(temp_dir.path(), "nonexistent"),
+            detect_license_in_vcpkg_install(&installed, "nonexistent"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_fetch_license_from_manifest_mode_vcpkg() {
+        let temp_dir = TempDir::new().unwrap();
+        let share_pkg = temp_dir
+            .path()
+            .join("vcpkg_installed")
+            .join("arm64-osx")
+            .join("share")
+            .join("fmt");
+        fs::create_dir_all(&share_pkg).unwrap();
+        fs::write(
+            share_pkg.join("copyright"),
+            "MIT License\n\nPermission is hereby granted, free of charge, to any person obtaining a copy",
+        )
+        .unwrap();
+
+        assert_eq!(
+            fetch_license_from_local_vcpkg(temp_dir.path(), "fmt"),
+            Some("MIT".to_string())
+        );
+    }
+
+    #[test]
+    fn test_fetch_license_from_conan_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let recipe = temp_dir
+            .path()
+            .join("p")
+            .join("zlib1a2b3c4d5e6f7")
+            .join("e");
+        fs::create_dir_all(&recipe).unwrap();
+        fs::write(
+            recipe.join("conanfile.py"),
+            "class ZlibConan(ConanFile):\n    name = \"zlib\"\n    license = \"Zlib\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            fetch_license_from_conan_cache(temp_dir.path(), "zlib", "1.3"),
+            Some("Zlib".to_string())
+        );
+        assert_eq!(
+            fetch_license_from_conan_cache(temp_dir.path(), "openssl", "3.2.0"),
             None
         );
     }
 
+    #[test]
+    fn test_parse_module_bazel() {
+        let temp_dir = TempDir::new().unwrap();
+        let module = temp_dir.path().join("MODULE.bazel");
+        fs::write(
+            &module,
+            r#"module(name = "app", version = "1.0")
+
+bazel_dep(name = "abseil-cpp", version = "20230802.0", repo_name = "com_google_absl")
+bazel_dep(
+    version = "1.2.13",
+    name = "zlib",
+)
+bazel_dep(name = "googletest", version = "1.14.0", dev_dependency = True)
+bazel_dep(name = "rules_cc")
+"#,
+        )
+        .unwrap();
+
+        let deps = parse_module_bazel(&module, false).unwrap();
+        assert_eq!(
+            deps,
+            vec![
+                ("abseil-cpp".to_string(), "20230802.0".to_string()),
+                ("zlib".to_string(), "1.2.13".to_string()),
+                ("googletest".to_string(), "1.14.0".to_string()),
+                ("rules_cc".to_string(), "latest".to_string()),
+            ]
+        );
+
+        let deps = parse_module_bazel(&module, true).unwrap();
+        assert!(!deps.iter().any(|(name, _)| name == "googletest"));
+        assert_eq!(deps.len(), 3);
+
+        fs::write(&module, "bazel_dep(name = \"zlib\"\n").unwrap();
+        assert!(parse_module_bazel(&module, false).is_err());
+    }
+
+    #[test]
+    fn test_conan_recipe_license() {
+        let recipe =
+            "class Boost(ConanFile):\n    name = 'boost'\n    license = ('BSL-1.0', 'MIT')\n";
+        assert_eq!(
+            conan_recipe_license(recipe, "boost"),
+            Some("BSL-1.0 AND MIT".to_string())
+        );
+        assert_eq!(conan_recipe_license(recipe, "zlib"), None);
+    }
+
     #[test]
     fn test_parse_vcpkg_dependencies() {
         let temp_dir = TempDir::new().unwrap();
@@ -841,13 +1263,87 @@ find_package(OpenSSL REQUIRED)
         let result = parse_cmake_dependencies(temp_dir.path(), &config).unwrap();
 
         assert!(!result.is_empty());
-        assert!(result.iter().any(|(name, _)| name == "json"));
+        assert!(result
+            .iter()
+            .any(|(name, version)| name == "json" && version == "3.10.5"));
         assert!(result
             .iter()
             .any(|(name, version)| name == "Boost" && version == "1.70"));
         assert!(result.iter().any(|(name, _)| name == "OpenSSL"));
     }
 
+    #[test]
+    fn test_parse_cmake_fetchcontent_and_imported_targets() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("CMakeLists.txt"),
+            r#"include(FetchContent)
+# FetchContent_Declare(commented GIT_TAG v0.0.1)
+FetchContent_Declare(
+  fmt
+  GIT_REPOSITORY https://github.com/fmtlib/fmt.git
+  GIT_TAG        v10.2.1 # release
+)
+fetchcontent_declare(spdlog
+  URL "https://github.com/gabime/spdlog/archive/refs/tags/v1.13.0.tar.gz")
+FetchContent_Declare(catch2 GIT_REPOSITORY https://github.com/catchorg/Catch2 GIT_TAG ${CATCH_TAG})
+FetchContent_MakeAvailable(fmt spdlog catch2 googletest)
+
+find_package(ZLIB 1.2.13 REQUIRED)
+target_link_libraries(app PRIVATE fmt::fmt ZLIB::ZLIB Threads::Threads
+    CURL::libcurl)
+"#,
+        )
+        .unwrap();
+
+        let result = parse_cmake_dependencies(temp_dir.path(), &FeludaConfig::default()).unwrap();
+        assert_eq!(
+            result,
+            vec![
+                ("fmt".to_string(), "10.2.1".to_string()),
+                ("spdlog".to_string(), "1.13.0".to_string()),
+                ("catch2".to_string(), "git".to_string()),
+                ("googletest".to_string(), "git".to_string()),
+                ("ZLIB".to_string(), "1.2.13".to_string()),
+                ("CURL".to_string(), "system".to_string()),
+            ]
+        );
+
+        let repositories = cmake_repositories(temp_dir.path());
+        assert_eq!(
+            repositories.get("fmt").map(String::as_str),
+            Some("https://github.com/fmtlib/fmt.git")
+        );
+        assert_eq!(
+            repositories.get("catch2").map(String::as_str),
+            Some("https://github.com/catchorg/Catch2")
+        );
+    }
+
+    #[test]
+    fn test_cmake_commands_bracket_arguments() {
+        let commands = cmake_commands(
+            "#[[ find_package(Hidden) ]]\nset(X [=[a ) b]=] \"c \\\" d\")\nfind_package(Qt6 COMPONENTS Core)",
+        );
+        assert_eq!(
+            commands,
+            vec![
+                (
+                    "set".to_string(),
+                    vec!["X".to_string(), "a ) b".to_string(), "c \" d".to_string()]
+                ),
+                (
+                    "find_package".to_string(),
+                    vec![
+                        "Qt6".to_string(),
+                        "COMPONENTS".to_string(),
+                        "Core".to_string()
+                    ]
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn test_analyze_cpp_licenses_empty() {
         let temp_dir = TempDir::new().unwrap();
@@ -1,5 +1,4 @@
 use regex::Regex;
-use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -7,11 +6,12 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use crate::config::FeludaConfig;
-use crate::debug::{log, log_debug, log_error, LogLevel};
+use crate::debug::{log, log_debug, log_error, record_parse_warning, LogLevel};
 use crate::licenses::{
     detect_license_from_content, detect_license_in_dir, fetch_licenses_from_github,
     is_license_restrictive, LicenseCompatibility, LicenseInfo,
 };
+use crate::resolver::{resolve_all, resolve_license, LicenseQuery};
 
 #[derive(Debug, Clone)]
 pub struct NuGetPackage {
@@ -58,7 +58,7 @@ pub fn analyze_dotnet_licenses(project_path: &str, config: &FeludaConfig) -> Vec
     let direct_deps = match detect_and_parse_project(project_path) {
         Ok(deps) => deps,
         Err(err) => {
-            log_error("Failed to parse .NET project", &err);
+            record_parse_warning(project_path, format!("failed to parse .NET project: {err}"));
             return Vec::new();
         }
     };
@@ -75,16 +75,29 @@ pub fn analyze_dotnet_licenses(project_path: &str, config: &FeludaConfig) -> Vec
         &format!("Using max dependency depth: {max_depth}"),
     );
 
-    let all_deps = resolve_dotnet_dependencies(project_path, &direct_deps, max_depth);
+    // Lock files pin the full transitive set, so with one per project there's nothing
+    // left for `dotnet list` to resolve
+    let all_deps = match read_lockfiles(project_path) {
+        Some(locked) => {
+            log(
+                LogLevel::Info,
+                &format!("Using {} packages from packages.lock.json", locked.len()),
+            );
+            locked
+        }
+        None => resolve_dotnet_dependencies(project_path, &direct_deps, max_depth),
+    };
 
     let mut licenses = Vec::new();
-    for (name, version) in all_deps {
+    let fetched = resolve_all(&all_deps, |(name, version)| {
+        fetch_license_for_nuget_package(name, version)
+    });
+    for ((name, version), license_result) in all_deps.into_iter().zip(fetched) {
         log(
             LogLevel::Info,
             &format!("Processing dependency: {name} ({version})"),
         );
 
-        let license_result = fetch_license_for_nuget_package(&name, &version);
         let license = Some(license_result);
         let is_restrictive = is_license_restrictive(&license, &known_licenses, config.strict);
 
@@ -106,6 +119,20 @@ pub fn analyze_dotnet_licenses(project_path: &str, config: &FeludaConfig) -> Vec
                 None => crate::licenses::OsiStatus::Unknown,
             },
             sub_project: None,
+            found_in: Vec::new(),
+            repository: None,
+            declared_in: None,
+            internal: false,
+            inherited_from: None,
+            ecosystem: None,
+            suggestions: Vec::new(),
+            update_policy: None,
+            introduced_by: Vec::new(),
+            purl: None,
+            vulnerabilities: None,
+            manifest: None,
+            data_license: None,
+            project_dirs: Vec::new(),
         });
     }
 
@@ -119,8 +146,11 @@ pub fn analyze_dotnet_licenses(project_path: &str, config: &FeludaConfig) -> Vec
 fn detect_and_parse_project(project_path: &str) -> Result<Vec<NuGetPackage>, String> {
     let path = Path::new(project_path);
 
-    if path.extension().and_then(|s| s.to_str()) == Some("slnx") {
-        parse_slnx_solution(project_path)
+    if matches!(
+        path.extension().and_then(|s| s.to_str()),
+        Some("sln" | "slnx")
+    ) {
+        parse_solution(project_path)
     } else if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
         if ext == "csproj" || ext == "fsproj" || ext == "vbproj" {
             parse_csproj_file(project_path)
@@ -137,27 +167,40 @@ fn detect_and_parse_project(project_path: &str) -> Result<Vec<NuGetPackage>, Str
     }
 }
 
-fn parse_slnx_solution(slnx_path: &str) -> Result<Vec<NuGetPackage>, String> {
-    log(LogLevel::Info, &format!("Parsing .slnx file: {slnx_path}"));
-
-    let content =
-        fs::read_to_string(slnx_path).map_err(|e| format!("Failed to read .slnx file: {e}"))?;
+/// The project files a solution lists: `<Project Path="...">` entries of a `.slnx`, or the
+/// `Project(...) = "Name", "path.csproj", ...` lines of a classic `.sln` (solution folders,
+/// which have no project file, are skipped)
+fn solution_projects(solution_path: &str) -> Result<Vec<PathBuf>, String> {
+    let content = fs::read_to_string(solution_path)
+        .map_err(|e| format!("Failed to read solution file: {e}"))?;
 
-    let re = Regex::new(r#"<Project\s+Path="([^"]+)""#)
-        .map_err(|e| format!("Failed to compile regex: {e}"))?;
+    let re = if solution_path.ends_with(".slnx") {
+        Regex::new(r#"<Project\s+Path="([^"]+)""#)
+    } else {
+        Regex::new(r#"(?m)^Project\("\{[^}]+\}"\)\s*=\s*"[^"]*"\s*,\s*"([^"]+\.(?:cs|fs|vb)proj)""#)
+    }
+    .map_err(|e| format!("Failed to compile regex: {e}"))?;
 
-    let slnx_dir = Path::new(slnx_path)
+    let solution_dir = Path::new(solution_path)
         .parent()
         .ok_or("Failed to get parent directory")?;
 
+    Ok(re
+        .captures_iter(&content)
+        .map(|cap| solution_dir.join(cap[1].replace('\\', "/")))
+        .collect())
+}
+
+fn parse_solution(solution_path: &str) -> Result<Vec<NuGetPackage>, String> {
+    log(
+        LogLevel::Info,
+        &format!("Parsing solution file: {solution_path}"),
+    );
+
     let mut all_packages = Vec::new();
     let mut seen = HashSet::new();
 
-    for cap in re.captures_iter(&content) {
-        let project_rel_path = &cap[1];
-        let normalized_path = project_rel_path.replace('\\', "/");
-        let project_path = slnx_dir.join(normalized_path);
-
+    for project_path in solution_projects(solution_path)? {
         log(
             LogLevel::Info,
             &format!("Found project in solution: {}", project_path.display()),
@@ -197,21 +240,27 @@ fn parse_csproj_file(csproj_path: &str) -> Result<Vec<NuGetPackage>, String> {
     let content =
         fs::read_to_string(csproj_path).map_err(|e| format!("Failed to read .csproj file: {e}"))?;
 
-    let mut packages = Vec::new();
+    let csproj_dir = Path::new(csproj_path)
+        .parent()
+        .ok_or("Failed to get parent directory")?;
 
-    let pkg_re = Regex::new(r#"<PackageReference\s+Include="([^"]+)"\s+Version="([^"]+)"\s*/?>"#)
-        .map_err(|e| format!("Failed to compile regex: {e}"))?;
+    // Central Package Management keeps the versions out of the project file
+    let central_versions = central_package_versions(csproj_dir);
 
-    for cap in pkg_re.captures_iter(&content) {
-        let name = cap[1].to_string();
-        let version = cap[2].to_string();
+    let mut packages = Vec::new();
+    for (name, version) in package_references(&content) {
+        let version = version
+            .or_else(|| central_versions.get(&name.to_lowercase()).cloned())
+            .unwrap_or_default();
+        if version.is_empty() {
+            log(
+                LogLevel::Warn,
+                &format!("No version found for package reference {name} in {csproj_path}"),
+            );
+        }
         packages.push(NuGetPackage { name, version });
     }
 
-    let csproj_dir = Path::new(csproj_path)
-        .parent()
-        .ok_or("Failed to get parent directory")?;
-
     if let Ok(project_refs) = parse_project_references(&content, csproj_dir) {
         for ref_path in project_refs {
             match parse_csproj_file(&ref_path) {
@@ -231,6 +280,99 @@ fn parse_csproj_file(csproj_path: &str) -> Result<Vec<NuGetPackage>, String> {
     Ok(packages)
 }
 
+/// `(name, version)` of every `<PackageReference>`, with the version from its `Version` or
+/// `VersionOverride` attribute or a `<Version>` child element, in any attribute order
+fn package_references(content: &str) -> Vec<(String, Option<String>)> {
+    let element_re =
+        Regex::new(r"(?s)<PackageReference\b([^>]*?)(?:/>|>(.*?)</PackageReference\s*>)").unwrap();
+    let child_version_re = Regex::new(r"<Version>\s*([^<]+?)\s*</Version>").unwrap();
+
+    element_re
+        .captures_iter(content)
+        .filter_map(|cap| {
+            let attributes = xml_attributes(&cap[1]);
+            let name = attributes.get("Include")?.clone();
+            let version = attributes
+                .get("VersionOverride")
+                .or_else(|| attributes.get("Version"))
+                .cloned()
+                .or_else(|| {
+                    cap.get(2)
+                        .and_then(|body| child_version_re.captures(body.as_str()))
+                        .map(|child| child[1].to_string())
+                })
+                .map(|version| clean_version(&version));
+            Some((name, version))
+        })
+        .collect()
+}
+
+/// Versions pinned in the nearest `Directory.Packages.props` above `project_dir`, keyed by
+/// lowercased package name (NuGet ids are case-insensitive)
+fn central_package_versions(project_dir: &Path) -> HashMap<String, String> {
+    let Some(props) = project_dir
+        .ancestors()
+        .map(|dir| dir.join("Directory.Packages.props"))
+        .find(|path| path.is_file())
+    else {
+        return HashMap::new();
+    };
+    log(
+        LogLevel::Info,
+        &format!("Reading central package versions from {}", props.display()),
+    );
+
+    let content = match fs::read_to_string(&props) {
+        Ok(content) => content,
+        Err(e) => {
+            record_parse_warning(props.display().to_string(), e.to_string());
+            return HashMap::new();
+        }
+    };
+    parse_central_package_versions(&content)
+}
+
+fn parse_central_package_versions(content: &str) -> HashMap<String, String> {
+    let element_re = Regex::new(r"<PackageVersion\b([^>]*?)/?>").unwrap();
+    element_re
+        .captures_iter(content)
+        .filter_map(|cap| {
+            let attributes = xml_attributes(&cap[1]);
+            Some((
+                attributes.get("Include")?.to_lowercase(),
+                clean_version(attributes.get("Version")?),
+            ))
+        })
+        .collect()
+}
+
+/// `name="value"` pairs of an XML start tag's attribute text
+fn xml_attributes(text: &str) -> HashMap<String, String> {
+    let attribute_re = Regex::new(r#"([\w.:-]+)\s*=\s*"([^"]*)""#).unwrap();
+    attribute_re
+        .captures_iter(text)
+        .map(|cap| (cap[1].to_string(), cap[2].to_string()))
+        .collect()
+}
+
+/// The lower bound of a NuGet version range (`[1.2.3, )` -> `1.2.3`); plain versions are
+/// returned as they are
+fn clean_version(version: &str) -> String {
+    let version = version.trim();
+    if version.starts_with(['[', '(']) {
+        version
+            .trim_start_matches(['[', '('])
+            .split(',')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .trim_end_matches([']', ')'])
+            .to_string()
+    } else {
+        version.to_string()
+    }
+}
+
 fn parse_project_references(content: &str, base_dir: &Path) -> Result<Vec<String>, String> {
     let re = Regex::new(r#"<ProjectReference\s+Include="([^"]+)"\s*/?>"#)
         .map_err(|e| format!("Failed to compile regex: {e}"))?;
@@ -261,19 +403,25 @@ fn parse_packages_lock_json(lock_path: &str) -> Result<Vec<NuGetPackage>, String
         .map_err(|e| format!("Failed to parse packages.lock.json: {e}"))?;
 
     let mut packages = Vec::new();
+    let mut seen = HashSet::new();
 
+    // One map per target framework; a package locked for several frameworks is listed once.
+    // `Project` entries are project references and carry no `resolved` version.
     if let Some(dependencies) = lock_data.dependencies {
         for (_framework, packages_map) in dependencies {
             for (name, info) in packages_map {
                 if let Some(resolved) = &info.resolved {
-                    packages.push(NuGetPackage {
-                        name: name.clone(),
-                        version: resolved.clone(),
-                    });
+                    if seen.insert(format!("{}@{resolved}", name.to_lowercase())) {
+                        packages.push(NuGetPackage {
+                            name: name.clone(),
+                            version: resolved.clone(),
+                        });
+                    }
                 }
             }
         }
     }
+    packages.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.version.cmp(&b.version)));
 
     log(
         LogLevel::Info,
@@ -282,6 +430,42 @@ fn parse_packages_lock_json(lock_path: &str) -> Result<Vec<NuGetPackage>, String
     Ok(packages)
 }
 
+/// The packages locked for `project_path`: its own `packages.lock.json`, or for a solution
+/// those of all its projects. `None` unless every project has a lock file, since an unlocked
+/// project's transitive dependencies would otherwise go missing.
+fn read_lockfiles(project_path: &str) -> Option<Vec<(String, String)>> {
+    let projects = if project_path.ends_with(".sln") || project_path.ends_with(".slnx") {
+        solution_projects(project_path).ok()?
+    } else {
+        vec![PathBuf::from(project_path)]
+    };
+    if projects.is_empty() {
+        return None;
+    }
+
+    let mut locked: Vec<(String, String)> = Vec::new();
+    for project in &projects {
+        let lock_path = project.parent()?.join("packages.lock.json");
+        if !lock_path.is_file() {
+            log(
+                LogLevel::Info,
+                &format!("No packages.lock.json next to {}", project.display()),
+            );
+            return None;
+        }
+        match parse_packages_lock_json(lock_path.to_str()?) {
+            Ok(packages) => locked.extend(packages.into_iter().map(|p| (p.name, p.version))),
+            Err(e) => {
+                record_parse_warning(lock_path.display().to_string(), e);
+                return None;
+            }
+        }
+    }
+    locked.sort();
+    locked.dedup();
+    Some(locked)
+}
+
 fn resolve_dotnet_dependencies(
     project_path: &str,
     direct_deps: &[NuGetPackage],
@@ -358,19 +542,12 @@ fn parse_dotnet_list_output(output: &str) -> Result<Vec<(String, String)>, Strin
 }
 
 fn fetch_license_for_nuget_package(name: &str, version: &str) -> String {
-    if let Ok(license) = fetch_from_local_nuget_cache(name, version) {
-        return license;
-    }
-
-    if let Ok(license) = fetch_from_nuget_api(name, version) {
-        return license;
-    }
-
-    log(
-        LogLevel::Warn,
-        &format!("Could not find license for {name} {version}"),
-    );
-    "Unknown".to_string()
+    resolve_license(
+        &LicenseQuery::new("dotnet", name, version),
+        Some(&|name: &str, version: &str| fetch_from_local_nuget_cache(name, version).ok()),
+        Some(&|name: &str, version: &str| fetch_from_nuget_api(name, version).ok()),
+    )
+    .unwrap_or_else(|| "Unknown".to_string())
 }
 
 fn fetch_from_local_nuget_cache(name: &str, version: &str) -> Result<String, String> {
@@ -398,10 +575,8 @@ fn fetch_from_local_nuget_cache(name: &str, version: &str) -> Result<String, Str
 }
 
 fn fetch_from_nuget_api(name: &str, version: &str) -> Result<String, String> {
-    let client = Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+    let client =
+        crate::cancel::shared_client().map_err(|e| format!("Failed to create HTTP client: {e}"))?;
 
     let nuspec_url = format!(
         "https://api.nuget.org/v3-flatcontainer/{}/{}/{}.nuspec",
@@ -415,9 +590,12 @@ fn fetch_from_nuget_api(name: &str, version: &str) -> Result<String, String> {
         &format!("Fetching from NuGet: {nuspec_url}"),
     );
 
-    let response = client
+    let request = client
         .get(&nuspec_url)
-        .send()
+        .timeout(crate::cancel::request_timeout(
+            std::time::Duration::from_secs(10),
+        ));
+    let response = crate::cancel::http_send(request, &nuspec_url)
         .map_err(|e| format!("Failed to fetch nuspec: {e}"))?;
 
     if !response.status().is_success() {
@@ -559,4 +737,115 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn test_package_references_any_form() {
+        let csproj = r#"<Project Sdk="Microsoft.NET.Sdk">
+  <ItemGroup>
+    <PackageReference Include="Newtonsoft.Json" Version="13.0.3" />
+    <PackageReference Version="[2.1.0, )" Include="Serilog"/>
+    <PackageReference Include="Polly">
+      <Version>8.2.0</Version>
+    </PackageReference>
+    <PackageReference Include="xunit" VersionOverride="2.6.1" />
+    <PackageReference Include="Dapper" />
+  </ItemGroup>
+</Project>"#;
+        assert_eq!(
+            package_references(csproj),
+            vec![
+                ("Newtonsoft.Json".to_string(), Some("13.0.3".to_string())),
+                ("Serilog".to_string(), Some("2.1.0".to_string())),
+                ("Polly".to_string(), Some("8.2.0".to_string())),
+                ("xunit".to_string(), Some("2.6.1".to_string())),
+                ("Dapper".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_csproj_uses_central_package_versions() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("Directory.Packages.props"),
+            r#"<Project><ItemGroup><PackageVersion Include="Dapper" Version="2.1.28" /></ItemGroup></Project>"#,
+        )
+        .unwrap();
+        let project_dir = temp_dir.path().join("src").join("App");
+        fs::create_dir_all(&project_dir).unwrap();
+        let csproj = project_dir.join("App.csproj");
+        fs::write(
+            &csproj,
+            r#"<Project><ItemGroup><PackageReference Include="dapper" /></ItemGroup></Project>"#,
+        )
+        .unwrap();
+
+        let packages = parse_csproj_file(csproj.to_str().unwrap()).unwrap();
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].name, "dapper");
+        assert_eq!(packages[0].version, "2.1.28");
+    }
+
+    #[test]
+    fn test_solution_projects_from_sln() {
+        let temp_dir = TempDir::new().unwrap();
+        let sln = temp_dir.path().join("App.sln");
+        fs::write(
+            &sln,
+            r#"Microsoft Visual Studio Solution File, Format Version 12.00
+Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "App", "src\App\App.csproj", "{11111111-1111-1111-1111-111111111111}"
+EndProject
+Project("{2150E333-8FDC-42A3-9474-1A3956D46DE8}") = "tests", "tests", "{22222222-2222-2222-2222-222222222222}"
+EndProject
+Project("{F2A71F9B-5D33-465A-A702-920D77279786}") = "Lib", "src\Lib\Lib.fsproj", "{33333333-3333-3333-3333-333333333333}"
+EndProject
+"#,
+        )
+        .unwrap();
+
+        let projects = solution_projects(sln.to_str().unwrap()).unwrap();
+        assert_eq!(
+            projects,
+            vec![
+                temp_dir.path().join("src/App/App.csproj"),
+                temp_dir.path().join("src/Lib/Lib.fsproj"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_lockfiles_dedupes_frameworks() {
+        let temp_dir = TempDir::new().unwrap();
+        let csproj = temp_dir.path().join("App.csproj");
+        fs::write(&csproj, "<Project />").unwrap();
+        fs::write(
+            temp_dir.path().join("packages.lock.json"),
+            r#"{
+  "version": 1,
+  "dependencies": {
+    "net6.0": {
+      "Serilog": { "type": "Direct", "resolved": "3.1.1" },
+      "Lib": { "type": "Project" }
+    },
+    "net8.0": {
+      "Serilog": { "type": "Direct", "resolved": "3.1.1" },
+      "System.Memory": { "type": "Transitive", "resolved": "4.5.5" }
+    }
+  }
+}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            read_lockfiles(csproj.to_str().unwrap()),
+            Some(vec![
+                ("Serilog".to_string(), "3.1.1".to_string()),
+                ("System.Memory".to_string(), "4.5.5".to_string()),
+            ])
+        );
+
+        // Without a lock file the dependencies still need resolving
+        fs::remove_file(temp_dir.path().join("packages.lock.json")).unwrap();
+        assert_eq!(read_lockfiles(csproj.to_str().unwrap()), None);
+    }
 }
@@ -0,0 +1,457 @@
+use regex::Regex;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::config::FeludaConfig;
+use crate::debug::{log, log_error, record_parse_warning, LogLevel};
+use crate::licenses::{
+    detect_license_in_dir, fetch_licenses_from_github, is_license_restrictive,
+    LicenseCompatibility, LicenseInfo,
+};
+use crate::resolver::{resolve_all, resolve_license, LicenseQuery, Lookup};
+
+/// Where a Mix dependency comes from
+#[derive(Debug, Clone, PartialEq)]
+enum MixSource {
+    /// A Hex package; `Some(org)` for a private organisation's repository (`hexpm:org`)
+    Hex(Option<String>),
+    /// A git checkout of this repository
+    Git(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ElixirDependency {
+    /// Package name on Hex, which an aliased dependency's lock key can differ from
+    name: String,
+    /// The dependency's name in the project, and its directory under `deps/`
+    app: String,
+    version: String,
+    source: MixSource,
+}
+
+pub fn analyze_elixir_licenses(file_path: &str, config: &FeludaConfig) -> Vec<LicenseInfo> {
+    log(
+        LogLevel::Info,
+        &format!("Analyzing Elixir dependencies from: {file_path}"),
+    );
+
+    let content = match fs::read_to_string(file_path) {
+        Ok(c) => c,
+        Err(e) => {
+            record_parse_warning(file_path, format!("failed to read Mix file: {e}"));
+            return Vec::new();
+        }
+    };
+    let project_dir = Path::new(file_path).parent().unwrap_or(Path::new("."));
+    let mix_exs = fs::read_to_string(project_dir.join("mix.exs")).unwrap_or_default();
+
+    // `mix.lock` pins the full transitive set; a bare `mix.exs` only lists direct
+    // requirements, with version constraints
+    let mut deps = if file_path.ends_with("mix.lock") {
+        parse_mix_lock(&content)
+    } else {
+        parse_mix_exs(&content)
+    };
+
+    // The lock doesn't record environments, so `--no-dev` leaves out the direct
+    // dependencies `mix.exs` limits to other environments than :prod
+    if config.no_dev {
+        let dev_only = dev_only_deps(&mix_exs);
+        deps.retain(|dep| !dev_only.contains(&dep.app));
+    }
+
+    if deps.is_empty() {
+        log(LogLevel::Warn, "No Elixir dependencies found");
+        return Vec::new();
+    }
+
+    log(
+        LogLevel::Info,
+        &format!("Found {} Elixir dependencies", deps.len()),
+    );
+
+    let known_licenses = match fetch_licenses_from_github() {
+        Ok(licenses) => licenses,
+        Err(err) => {
+            log_error("Failed to fetch licenses from GitHub", &err);
+            HashMap::new()
+        }
+    };
+    let deps_dir = project_dir.join("deps");
+
+    resolve_all(&deps, |dep| {
+        let license = fetch_elixir_license(dep, &deps_dir);
+        let is_restrictive =
+            is_license_restrictive(&Some(license.clone()), &known_licenses, config.strict);
+
+        LicenseInfo {
+            name: dep.name.clone(),
+            version: dep.version.clone(),
+            license: Some(license.clone()),
+            is_restrictive,
+            compatibility: LicenseCompatibility::Unknown,
+            osi_status: crate::licenses::get_osi_status(&license),
+            sub_project: None,
+            found_in: Vec::new(),
+            repository: match &dep.source {
+                MixSource::Git(url) => Some(url.trim_end_matches(".git").to_string()),
+                MixSource::Hex(_) => None,
+            },
+            declared_in: None,
+            internal: false,
+            inherited_from: None,
+            ecosystem: None,
+            suggestions: Vec::new(),
+            update_policy: None,
+            introduced_by: Vec::new(),
+            purl: None,
+            vulnerabilities: None,
+            manifest: None,
+            data_license: None,
+            project_dirs: Vec::new(),
+        }
+    })
+}
+
+// =============================================================================
+// MIX.LOCK / MIX.EXS PARSING
+// =============================================================================
+
+/// Parse the entries of a `mix.lock`, one `"app": {:hex | :git, ...}` tuple per line.
+/// Path dependencies aren't locked, so they don't appear.
+fn parse_mix_lock(content: &str) -> Vec<ElixirDependency> {
+    let hex_re = Regex::new(
+        r#"^\s*"([^"]+)":\s*\{:hex,\s*:"?([A-Za-z0-9_]+)"?,\s*"([^"]+)",.*"(hexpm(?::[^"]+)?)""#,
+    )
+    .unwrap();
+    let git_re =
+        Regex::new(r#"^\s*"([^"]+)":\s*\{:git,\s*"([^"]+)",\s*"([0-9a-f]+)",(.*)$"#).unwrap();
+    let tag_re = Regex::new(r#"tag:\s*"([^"]+)""#).unwrap();
+
+    let mut deps: Vec<ElixirDependency> = content
+        .lines()
+        .filter_map(|line| {
+            if let Some(cap) = hex_re.captures(line) {
+                let org = cap[4].strip_prefix("hexpm:").map(str::to_string);
+                return Some(ElixirDependency {
+                    app: cap[1].to_string(),
+                    name: cap[2].to_string(),
+                    version: cap[3].to_string(),
+                    source: MixSource::Hex(org),
+                });
+            }
+            let cap = git_re.captures(line)?;
+            // A tag names the release; otherwise the locked commit is the version
+            let version = tag_re
+                .captures(&cap[4])
+                .map(|tag| tag[1].trim_start_matches('v').to_string())
+                .unwrap_or_else(|| cap[3].chars().take(7).collect());
+            Some(ElixirDependency {
+                app: cap[1].to_string(),
+                name: cap[1].to_string(),
+                version,
+                source: MixSource::Git(cap[2].to_string()),
+            })
+        })
+        .collect();
+
+    deps.sort_by(|a, b| a.name.cmp(&b.name));
+    deps.dedup_by(|a, b| a.name == b.name);
+    deps
+}
+
+/// Best-effort parse of the `{:app, ...}` tuples in a `mix.exs`: Hex requirements with a
+/// version constraint, and `git:`/`github:` dependencies. Path dependencies are the
+/// project's own code and are skipped.
+fn parse_mix_exs(content: &str) -> Vec<ElixirDependency> {
+    let dep_re = Regex::new(r#"\{\s*:([a-z0-9_]+)\s*,([^{}]*)\}"#).unwrap();
+    let constraint_re = Regex::new(r#"^\s*"([^"]+)""#).unwrap();
+    let hex_re = Regex::new(r#"hex:\s*:"?([A-Za-z0-9_]+)"?"#).unwrap();
+    let git_re = Regex::new(r#"(git|github):\s*"([^"]+)""#).unwrap();
+    let org_re = Regex::new(r#"organization:\s*"([^"]+)""#).unwrap();
+
+    let mut deps: Vec<ElixirDependency> = dep_re
+        .captures_iter(content)
+        .filter_map(|cap| {
+            let app = cap[1].to_string();
+            let options = &cap[2];
+            if options.contains("path:") || options.contains("in_umbrella:") {
+                return None;
+            }
+            if let Some(git) = git_re.captures(options) {
+                let url = match &git[1] {
+                    "github" => format!("https://github.com/{}", &git[2]),
+                    _ => git[2].to_string(),
+                };
+                return Some(ElixirDependency {
+                    name: app.clone(),
+                    app,
+                    version: String::new(),
+                    source: MixSource::Git(url),
+                });
+            }
+            let constraint = constraint_re.captures(options)?;
+            Some(ElixirDependency {
+                name: hex_re
+                    .captures(options)
+                    .map_or_else(|| app.clone(), |hex| hex[1].to_string()),
+                app,
+                version: clean_requirement(&constraint[1]),
+                source: MixSource::Hex(org_re.captures(options).map(|org| org[1].to_string())),
+            })
+        })
+        .collect();
+
+    deps.sort_by(|a, b| a.name.cmp(&b.name));
+    deps.dedup_by(|a, b| a.name == b.name);
+    deps
+}
+
+/// Direct dependencies `mix.exs` restricts with `only:` to environments other than :prod
+fn dev_only_deps(mix_exs: &str) -> HashSet<String> {
+    let dep_re = Regex::new(r#"\{\s*:([a-z0-9_]+)\s*,([^{}]*)\}"#).unwrap();
+    let only_re = Regex::new(r#"only:\s*(\[[^\]]*\]|:[a-z_]+)"#).unwrap();
+
+    dep_re
+        .captures_iter(mix_exs)
+        .filter(|cap| {
+            only_re
+                .captures(&cap[2])
+                .is_some_and(|only| !only[1].contains(":prod"))
+        })
+        .map(|cap| cap[1].to_string())
+        .collect()
+}
+
+/// Extract a concrete version from a Mix requirement (`~> 1.7.10`, `>= 0.0.0 and < 2.0.0`)
+fn clean_requirement(requirement: &str) -> String {
+    let ver_re = Regex::new(r"[0-9][0-9A-Za-z.\-+]*").unwrap();
+    ver_re
+        .find(requirement)
+        .map(|m| m.as_str().to_string())
+        .unwrap_or_default()
+}
+
+// =============================================================================
+// HEX LICENSE LOOKUP
+// =============================================================================
+
+fn fetch_elixir_license(dep: &ElixirDependency, deps_dir: &Path) -> String {
+    let local = |_: &str, _: &str| fetch_from_deps(deps_dir, &dep.app);
+    let repository = match &dep.source {
+        MixSource::Git(url) => Some(url.as_str()),
+        MixSource::Hex(_) => None,
+    };
+    // Git dependencies and private organisation packages aren't on the public Hex API
+    let registry: Option<Lookup> = match &dep.source {
+        MixSource::Hex(None) => Some(&fetch_license_from_hex),
+        _ => None,
+    };
+
+    resolve_license(
+        &LicenseQuery::new("elixir", &dep.name, &dep.version).with_repository(repository),
+        Some(&local),
+        registry,
+    )
+    .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// A fetched dependency's license, from the `hex_metadata.config` Hex ships in
+/// `deps/<app>/`, or its license file
+fn fetch_from_deps(deps_dir: &Path, app: &str) -> Option<String> {
+    let package_dir = deps_dir.join(app);
+    fs::read_to_string(package_dir.join("hex_metadata.config"))
+        .ok()
+        .and_then(|content| hex_metadata_licenses(&content))
+        .or_else(|| detect_license_in_dir(&package_dir))
+}
+
+/// The `{<<"licenses">>,[<<"MIT">>]}` entry of a `hex_metadata.config`
+fn hex_metadata_licenses(content: &str) -> Option<String> {
+    let licenses_re = Regex::new(r#"\{<<"licenses">>,\s*\[([^\]]*)\]\}"#).unwrap();
+    let name_re = Regex::new(r#"<<"([^"]+)">>"#).unwrap();
+    let list = licenses_re.captures(content)?;
+    join_licenses(
+        name_re
+            .captures_iter(&list[1])
+            .map(|cap| cap[1].to_string()),
+    )
+}
+
+/// Look a package up in the Hex API. Licenses are package metadata there, not per release.
+fn fetch_license_from_hex(name: &str, _version: &str) -> Option<String> {
+    let url = format!("https://hex.pm/api/packages/{name}");
+    log(LogLevel::Info, &format!("Fetching Hex metadata: {url}"));
+
+    let response = crate::cancel::http_get(&url).ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let json: Value = response.json().ok()?;
+    hex_package_licenses(&json)
+}
+
+fn hex_package_licenses(json: &Value) -> Option<String> {
+    join_licenses(
+        json["meta"]["licenses"]
+            .as_array()?
+            .iter()
+            .filter_map(|license| license.as_str().map(str::to_string)),
+    )
+}
+
+/// Hex lists the licenses a package is offered under; several become an `A OR B` expression
+fn join_licenses(licenses: impl Iterator<Item = String>) -> Option<String> {
+    let licenses: Vec<String> = licenses
+        .map(|license| license.trim().to_string())
+        .filter(|license| !license.is_empty())
+        .collect();
+    match licenses.as_slice() {
+        [] => None,
+        [only] => Some(only.clone()),
+        _ if licenses.iter().any(|license| license.contains(' ')) => Some(
+            licenses
+                .iter()
+                .map(|license| format!("({license})"))
+                .collect::<Vec<_>>()
+                .join(" OR "),
+        ),
+        _ => Some(licenses.join(" OR ")),
+    }
+}
+
+// TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    const MIX_LOCK: &str = r#"%{
+  "castore": {:hex, :castore, "1.0.5", "9eeebb394cc9a0f3ae56b813459f990abb0a3dedee1be6b27fdb50301930502f", [:mix], [], "hexpm", "8d7c597c3e4a64c395980882d4bca3cebb8d74197c590dc272cfd3b6a6310578"},
+  "phoenix": {:hex, :phoenix, "1.7.10", "02189140a61b2ce85bb633a9b6fd02dff705a5f1596869547aeb2b2b95edd729", [:mix], [{:castore, ">= 0.0.0", [hex: :castore, repo: "hexpm", optional: false]}], "hexpm", "cf784932e010fd736d656d7fead6a584a4498efefe5b8227e9f383bf15bb79d0"},
+  "acme_auth": {:hex, :acme_auth, "2.3.0", "abc", [:mix], [], "hexpm:acme", "def"},
+  "json": {:hex, :jason, "1.4.1", "af1504e35f629ddcdd6addb3513c3853991f694921b1b9368b0bd32beb9f1b63", [:mix], [], "hexpm", "fbb01ecdfd565b56261302f7e1fcc27c4fb8f32d56eab74db621fc154604a7a1"},
+  "heroicons": {:git, "https://github.com/tailwindlabs/heroicons.git", "88ab3a0d790e6a47404cba02800a6b25d2afae50", [tag: "v2.1.1", sparse: "optimized"]},
+  "floki": {:git, "https://github.com/philss/floki.git", "1d5f6d8e3c6f2a7b8b0f0d6a9c4e2f1a0b9c8d7e", [branch: "main"]},
+}
+"#;
+
+    #[test]
+    fn test_parse_mix_lock() {
+        let deps = parse_mix_lock(MIX_LOCK);
+        let names: Vec<&str> = deps.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "acme_auth",
+                "castore",
+                "floki",
+                "heroicons",
+                "jason",
+                "phoenix"
+            ]
+        );
+
+        let phoenix = deps.iter().find(|d| d.name == "phoenix").unwrap();
+        assert_eq!(phoenix.version, "1.7.10");
+        assert_eq!(phoenix.source, MixSource::Hex(None));
+
+        // Aliased: the lock key is the app, the tuple names the Hex package
+        let jason = deps.iter().find(|d| d.name == "jason").unwrap();
+        assert_eq!(jason.app, "json");
+
+        let acme = deps.iter().find(|d| d.name == "acme_auth").unwrap();
+        assert_eq!(acme.source, MixSource::Hex(Some("acme".to_string())));
+
+        let heroicons = deps.iter().find(|d| d.name == "heroicons").unwrap();
+        assert_eq!(heroicons.version, "2.1.1");
+        assert_eq!(
+            heroicons.source,
+            MixSource::Git("https://github.com/tailwindlabs/heroicons.git".to_string())
+        );
+        let floki = deps.iter().find(|d| d.name == "floki").unwrap();
+        assert_eq!(floki.version, "1d5f6d8");
+    }
+
+    #[test]
+    fn test_parse_mix_exs() {
+        let mix_exs = r#"defmodule MyApp.MixProject do
+  use Mix.Project
+
+  defp deps do
+    [
+      {:phoenix, "~> 1.7.10"},
+      {:ecto_sql, "~> 3.10"},
+      {:json, "~> 1.4", hex: :jason},
+      {:credo, "~> 1.7", only: [:dev, :test], runtime: false},
+      {:telemetry_metrics, "~> 0.6", only: [:dev, :prod]},
+      {:heroicons, github: "tailwindlabs/heroicons", tag: "v2.1.1", app: false},
+      {:my_lib, path: "../my_lib"}
+    ]
+  end
+end
+"#;
+        let deps = parse_mix_exs(mix_exs);
+        let names: Vec<&str> = deps.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "credo",
+                "ecto_sql",
+                "heroicons",
+                "jason",
+                "phoenix",
+                "telemetry_metrics"
+            ]
+        );
+        let phoenix = deps.iter().find(|d| d.name == "phoenix").unwrap();
+        assert_eq!(phoenix.version, "1.7.10");
+        let heroicons = deps.iter().find(|d| d.name == "heroicons").unwrap();
+        assert_eq!(
+            heroicons.source,
+            MixSource::Git("https://github.com/tailwindlabs/heroicons".to_string())
+        );
+
+        let dev_only = dev_only_deps(mix_exs);
+        assert!(dev_only.contains("credo"));
+        assert!(!dev_only.contains("telemetry_metrics"));
+        assert!(!dev_only.contains("phoenix"));
+    }
+
+    #[test]
+    fn test_fetch_from_deps_reads_hex_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let package_dir = temp_dir.path().join("phoenix");
+        fs::create_dir_all(&package_dir).unwrap();
+        fs::write(
+            package_dir.join("hex_metadata.config"),
+            "{<<\"app\">>,<<\"phoenix\">>}.\n{<<\"licenses\">>,[<<\"MIT\">>]}.\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            fetch_from_deps(temp_dir.path(), "phoenix"),
+            Some("MIT".to_string())
+        );
+        assert_eq!(
+            hex_metadata_licenses("{<<\"licenses\">>,[<<\"Apache-2.0\">>,<<\"MIT\">>]}."),
+            Some("Apache-2.0 OR MIT".to_string())
+        );
+    }
+
+    #[test]
+    fn test_hex_package_licenses() {
+        let package = json!({
+            "name": "plug",
+            "meta": {"licenses": ["Apache-2.0"], "links": {}}
+        });
+        assert_eq!(
+            hex_package_licenses(&package),
+            Some("Apache-2.0".to_string())
+        );
+        assert_eq!(hex_package_licenses(&json!({"meta": {}})), None);
+    }
+}
@@ -1,19 +1,21 @@
 use regex::Regex;
 use reqwest::blocking::Client;
 use serde::Deserialize;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::thread::sleep;
 use std::time::Duration;
 
 use crate::config::FeludaConfig;
-use crate::debug::{log, log_debug, log_error, LogLevel};
+use crate::debug::{
+    log, log_debug, log_error, record_parse_warning, record_resolution_error, LogLevel,
+};
 use crate::licenses::{
     detect_license_in_dir, fetch_licenses_from_github, is_license_restrictive,
     LicenseCompatibility, LicenseInfo,
 };
+use crate::resolver::{known_license, resolve_all, resolve_license, LicenseQuery};
 
 /// Go module names to exclude from dependency analysis
 /// These are special Go directives and built-in modules, not actual dependencies
@@ -50,7 +52,7 @@ pub fn analyze_go_licenses(go_mod_path: &str, config: &FeludaConfig) -> Vec<Lice
     let content = match fs::read_to_string(go_mod_path) {
         Ok(content) => content,
         Err(err) => {
-            log_error(&format!("Failed to read go.mod file: {go_mod_path}"), &err);
+            record_parse_warning(go_mod_path, format!("failed to read go.mod: {err}"));
             return Vec::new();
         }
     };
@@ -62,23 +64,42 @@ pub fn analyze_go_licenses(go_mod_path: &str, config: &FeludaConfig) -> Vec<Lice
     );
     log_debug("Direct Go dependencies", &direct_dependencies);
 
-    // Try to resolve all dependencies using go mod graph
-    let max_depth = config.dependencies.max_depth;
-    log(
-        LogLevel::Info,
-        &format!("Using max dependency depth: {max_depth}"),
-    );
-    let all_deps = resolve_go_dependencies(go_mod_path, &direct_dependencies, max_depth);
+    // go.mod already lists every module the build needs (marked `// indirect` when not
+    // imported directly); the full module graph is opt-in
+    let all_deps = if config.include_transitive {
+        let max_depth = config.dependencies.max_depth;
+        log(
+            LogLevel::Info,
+            &format!("Using max dependency depth: {max_depth}"),
+        );
+        resolve_go_dependencies(go_mod_path, &direct_dependencies, max_depth)
+    } else {
+        direct_dependencies
+            .iter()
+            .map(|dep| (dep.name.clone(), dep.version.clone()))
+            .collect()
+    };
+
+    let all_deps = if config.no_dev {
+        without_test_only_modules(go_mod_path, all_deps)
+    } else {
+        all_deps
+    };
 
     // Process all resolved dependencies
     let mut licenses = Vec::new();
-    for (name, version) in all_deps {
+    let module_root = Path::new(go_mod_path)
+        .parent()
+        .unwrap_or_else(|| Path::new("."));
+    let fetched = resolve_all(&all_deps, |(name, version)| {
+        fetch_license_for_go_dependency(name, version, module_root)
+    });
+    for ((name, version), license_result) in all_deps.into_iter().zip(fetched) {
         log(
             LogLevel::Info,
             &format!("Processing dependency: {name} ({version})"),
         );
 
-        let license_result = fetch_license_for_go_dependency(name.as_str(), version.as_str());
         let license = Some(license_result);
         let is_restrictive = is_license_restrictive(&license, &known_licenses, config.strict);
 
@@ -100,6 +121,20 @@ pub fn analyze_go_licenses(go_mod_path: &str, config: &FeludaConfig) -> Vec<Lice
                 None => crate::licenses::OsiStatus::Unknown,
             },
             sub_project: None,
+            found_in: Vec::new(),
+            repository: None,
+            declared_in: None,
+            internal: false,
+            inherited_from: None,
+            ecosystem: None,
+            suggestions: Vec::new(),
+            update_policy: None,
+            introduced_by: Vec::new(),
+            purl: None,
+            vulnerabilities: None,
+            manifest: None,
+            data_license: None,
+            project_dirs: Vec::new(),
         });
     }
 
@@ -126,9 +161,9 @@ pub fn analyze_go_workspace_licenses(
     let content = match fs::read_to_string(&go_work_path) {
         Ok(c) => c,
         Err(err) => {
-            log_error(
-                &format!("Failed to read go.work: {}", go_work_path.display()),
-                &err,
+            record_parse_warning(
+                go_work_path.display().to_string(),
+                format!("failed to read go.work: {err}"),
             );
             return Vec::new();
         }
@@ -389,10 +424,23 @@ fn resolve_go_dependencies(
         }
     }
 
-    // Direct dependencies in case go mod graph fails
+    // go.sum records every module whose source was downloaded, without the depth information
+    let go_sum_path = Path::new(go_mod_path).with_file_name("go.sum");
+    if let Ok(content) = fs::read_to_string(&go_sum_path) {
+        let go_deps = parse_go_sum(&content);
+        if !go_deps.is_empty() {
+            log(
+                LogLevel::Info,
+                &format!("Resolved {} dependencies from go.sum", go_deps.len()),
+            );
+            return go_deps;
+        }
+    }
+
+    // Direct dependencies in case neither go mod graph nor go.sum is available
     log(
         LogLevel::Info,
-        "Falling back to direct dependencies only (go mod graph not available)",
+        "Falling back to go.mod requirements only (go mod graph and go.sum not available)",
     );
     direct_deps
         .iter()
@@ -400,6 +448,80 @@ fn resolve_go_dependencies(
         .collect()
 }
 
+/// Keep only the modules the module's non-test build uses. Modules reached only from `_test.go`
+/// files, `tool` directives or `//go:build tools` files are dev dependencies in Go's terms.
+/// When `go list` can't run, nothing is dropped.
+fn without_test_only_modules(
+    go_mod_path: &str,
+    deps: Vec<(String, String)>,
+) -> Vec<(String, String)> {
+    let build_modules = match Path::new(go_mod_path).parent().map(go_build_modules) {
+        Some(Ok(modules)) => modules,
+        Some(Err(err)) => {
+            log(
+                LogLevel::Warn,
+                &format!("Keeping test-only Go modules, the build list is unavailable: {err}"),
+            );
+            return deps;
+        }
+        None => return deps,
+    };
+
+    let (kept, dropped): (Vec<_>, Vec<_>) = deps
+        .into_iter()
+        .partition(|(name, _)| build_modules.contains(name));
+    log(
+        LogLevel::Info,
+        &format!(
+            "Left out {} Go modules only tests or tools use",
+            dropped.len()
+        ),
+    );
+    log_debug("Test-only Go modules", &dropped);
+    kept
+}
+
+/// Paths of the modules providing packages that `./...` imports outside tests
+fn go_build_modules(project_dir: &Path) -> Result<HashSet<String>, String> {
+    let mut command = Command::new("go");
+    command
+        .args([
+            "list",
+            "-e",
+            "-deps",
+            "-f",
+            "{{with .Module}}{{.Path}} {{.Version}}{{end}}",
+            "./...",
+        ])
+        .current_dir(project_dir);
+    if crate::cancel::is_offline() {
+        command.env("GOPROXY", "off").env("GOFLAGS", "-mod=mod");
+    }
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run go list: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("go list failed: {stderr}"));
+    }
+
+    Ok(parse_go_list_modules(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Parse `go list -deps -f '{{with .Module}}{{.Path}} {{.Version}}{{end}}'` output. The main
+/// module has no version and standard library packages no module, so neither is returned.
+fn parse_go_list_modules(output: &str) -> HashSet<String> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().split_once(' '))
+        .filter(|(_, version)| !version.trim().is_empty())
+        .map(|(path, _)| path.to_string())
+        .collect()
+}
+
 /// Resolve dependencies using go mod graph with depth limit
 fn resolve_with_go_mod_graph(
     go_mod_path: &str,
@@ -415,9 +537,13 @@ fn resolve_with_go_mod_graph(
     );
 
     // Run go mod graph to get dependency graph
-    let output = Command::new("go")
-        .args(["mod", "graph"])
-        .current_dir(project_dir)
+    let mut command = Command::new("go");
+    command.args(["mod", "graph"]).current_dir(project_dir);
+    if crate::cancel::is_offline() {
+        // Only the module cache; without it go.sum is the fallback
+        command.env("GOPROXY", "off").env("GOFLAGS", "-mod=mod");
+    }
+    let output = command
         .output()
         .map_err(|e| format!("Failed to run go mod graph: {e}"))?;
 
@@ -442,8 +568,6 @@ fn resolve_with_go_mod_graph(
 
 /// Parse go mod graph output to extract dependencies with depth awareness
 fn parse_go_mod_graph_output(output: &str, max_depth: u32) -> Vec<(String, String)> {
-    let mut all_deps = HashMap::new();
-    let mut depth_map = HashMap::new();
     let mut edges = HashMap::new();
 
     log(
@@ -462,14 +586,7 @@ fn parse_go_mod_graph_output(output: &str, max_depth: u32) -> Vec<(String, Strin
             let from = from.trim();
             let to = to.trim();
 
-            // Parse module name and version
-            if let Some((from_name, from_version)) = parse_go_module_version(from) {
-                all_deps.insert(from_name.clone(), from_version);
-            }
-
-            if let Some((to_name, to_version)) = parse_go_module_version(to) {
-                all_deps.insert(to_name.clone(), to_version);
-
+            if parse_go_module_version(to).is_some() {
                 // Track edges for depth calculation
                 edges
                     .entry(from.to_string())
@@ -500,17 +617,16 @@ fn parse_go_mod_graph_output(output: &str, max_depth: u32) -> Vec<(String, Strin
     let root_modules: Vec<_> = roots.difference(&destinations).collect();
 
     // Calculate depths using BFS from root modules
-    let mut queue = Vec::new();
+    let mut queue = VecDeque::new();
     let mut visited = HashSet::new();
 
-    for root in root_modules {
-        queue.push((root.clone(), 0u32));
-        depth_map.insert(root.clone(), 0);
+    for root in &root_modules {
+        queue.push_back(((*root).clone(), 0u32));
     }
 
     let mut depth_stats = HashMap::new();
 
-    while let Some((current, depth)) = queue.pop() {
+    while let Some((current, depth)) = queue.pop_front() {
         if visited.contains(&current) || depth >= max_depth {
             if depth >= max_depth {
                 log(
@@ -522,7 +638,6 @@ fn parse_go_mod_graph_output(output: &str, max_depth: u32) -> Vec<(String, Strin
         }
 
         visited.insert(current.clone());
-        depth_map.insert(current.clone(), depth);
 
         // Track depth statistics
         *depth_stats.entry(depth).or_insert(0) += 1;
@@ -531,27 +646,26 @@ fn parse_go_mod_graph_output(output: &str, max_depth: u32) -> Vec<(String, Strin
         if let Some(children) = edges.get(&current) {
             for child in children {
                 if !visited.contains(child) && depth + 1 < max_depth {
-                    queue.push((child.clone(), depth + 1));
+                    queue.push_back((child.clone(), depth + 1));
                 }
             }
         }
     }
 
-    // Filter dependencies based on depth limit
-    let filtered_deps: Vec<(String, String)> = all_deps
-        .into_iter()
-        .filter(|(name, _version)| {
-            // Find the full module name in depth_map
-            for (module_full, depth) in &depth_map {
-                if let Some((module_name, _)) = parse_go_module_version(module_full) {
-                    if module_name == *name && *depth < max_depth {
-                        return true;
-                    }
-                }
-            }
-            false
-        })
-        .collect();
+    // Keep the modules reached within the depth limit, minus the main module itself. The
+    // graph lists every version any module asks for; minimal version selection builds
+    // with the highest one.
+    let mut selected: HashMap<String, String> = HashMap::new();
+    for module_full in &visited {
+        if root_modules.contains(&module_full) {
+            continue;
+        }
+        if let Some((name, version)) = parse_go_module_version(module_full) {
+            select_go_version(&mut selected, name, version);
+        }
+    }
+    let mut filtered_deps: Vec<(String, String)> = selected.into_iter().collect();
+    filtered_deps.sort();
 
     // Log depth statistics
     for depth in 0..max_depth {
@@ -575,6 +689,48 @@ fn parse_go_mod_graph_output(output: &str, max_depth: u32) -> Vec<(String, Strin
     filtered_deps
 }
 
+/// Modules with downloaded source in a go.sum, at the highest version listed for each.
+/// Lines ending in `/go.mod` only hash a module's go.mod, fetched while resolving
+/// versions, and are skipped.
+fn parse_go_sum(content: &str) -> Vec<(String, String)> {
+    let mut selected: HashMap<String, String> = HashMap::new();
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(name), Some(version)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        if version.ends_with("/go.mod") || is_excluded_go_module(name) {
+            continue;
+        }
+        select_go_version(&mut selected, name.to_string(), version.to_string());
+    }
+    let mut deps: Vec<(String, String)> = selected.into_iter().collect();
+    deps.sort();
+    deps
+}
+
+/// Record `version` for `name` unless a higher version is already selected
+fn select_go_version(selected: &mut HashMap<String, String>, name: String, version: String) {
+    match selected.get(&name) {
+        Some(current) if compare_go_versions(current, &version).is_ge() => {}
+        _ => {
+            selected.insert(name, version);
+        }
+    }
+}
+
+/// Order Go module versions by semver, ignoring the `v` prefix and `+incompatible` suffix.
+/// Versions that do not parse compare as strings.
+fn compare_go_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let parse = |v: &str| {
+        semver::Version::parse(v.trim_start_matches('v').trim_end_matches("+incompatible"))
+    };
+    match (parse(a), parse(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
 /// Check if a module name should be excluded from dependency analysis
 fn is_excluded_go_module(module_name: &str) -> bool {
     EXCLUDED_GO_MODULES.contains(&module_name)
@@ -606,31 +762,56 @@ fn parse_go_module_version(module_str: &str) -> Option<(String, String)> {
     }
 }
 
-/// Fetch the license for a Go dependency, trying local sources first, then the pkg.go.dev API
+/// Fetch the license for a Go dependency through the resolver chain (the module's `vendor`
+/// directory, local go.mod and module cache, then the pkg.go.dev API by default)
 pub fn fetch_license_for_go_dependency(
     name: impl Into<String>,
     version: impl Into<String>,
+    module_root: &Path,
 ) -> String {
     let name = name.into();
     let version = version.into();
 
-    if let Some(license) = get_license_from_local_go_mod(&name) {
-        log(
-            LogLevel::Info,
-            &format!("Found license in local go.mod for {name}: {license}"),
-        );
-        return license;
-    }
+    resolve_license(
+        &LicenseQuery::new("go", &name, &version),
+        Some(&|name: &str, version: &str| {
+            get_license_from_vendor_dir(module_root, name)
+                .or_else(|| get_license_from_local_sources(name, version))
+        }),
+        Some(&|name: &str, version: &str| {
+            known_license(fetch_license_from_pkgsite_api(name, version))
+        }),
+    )
+    .unwrap_or_else(|| "Unknown".into())
+}
 
-    if let Some(license) = get_license_from_go_module_cache(&name, &version) {
+/// The license file of a module vendored with `go mod vendor`, which keeps the module's
+/// top-level license next to its packages in `vendor/<module path>`
+fn get_license_from_vendor_dir(module_root: &Path, name: &str) -> Option<String> {
+    let vendored = module_root.join("vendor").join(name);
+    let license = detect_license_in_dir(&vendored)?;
+    log(
+        LogLevel::Info,
+        &format!("Found license in vendor directory for {name}: {license}"),
+    );
+    Some(license)
+}
+
+fn get_license_from_local_sources(name: &str, version: &str) -> Option<String> {
+    if let Some(license) = get_license_from_local_go_mod(name) {
         log(
             LogLevel::Info,
-            &format!("Found license in Go module cache for {name}: {license}"),
+            &format!("Found license in local go.mod for {name}: {license}"),
         );
-        return license;
+        return Some(license);
     }
 
-    fetch_license_from_pkgsite_api(&name, &version)
+    let license = get_license_from_go_module_cache(name, version)?;
+    log(
+        LogLevel::Info,
+        &format!("Found license in Go module cache for {name}: {license}"),
+    );
+    Some(license)
 }
 
 fn get_license_from_local_go_mod(package_name: &str) -> Option<String> {
@@ -759,16 +940,11 @@ pub(crate) fn fetch_pkgsite_module_licenses(
     name: &str,
     version: &str,
 ) -> Option<Vec<PkgsiteLicense>> {
-    let client = match Client::builder()
-        .user_agent(concat!(
-            "feluda/",
-            env!("CARGO_PKG_VERSION"),
-            " (+https://github.com/anistark/feluda)"
-        ))
-        .connect_timeout(Duration::from_secs(60))
-        .timeout(Duration::from_secs(10))
-        .build()
-    {
+    if crate::cancel::is_cancelled() || crate::cancel::is_offline() {
+        return None;
+    }
+
+    let client = match crate::cancel::shared_client() {
         Ok(client) => client,
         Err(err) => {
             log_error("Failed to build HTTP client", &err);
@@ -777,7 +953,7 @@ pub(crate) fn fetch_pkgsite_module_licenses(
     };
 
     if !version.is_empty() && version != "unknown" {
-        if let Some(licenses) = fetch_pkgsite_module_version(&client, name, Some(version)) {
+        if let Some(licenses) = fetch_pkgsite_module_version(client, name, Some(version)) {
             return Some(licenses);
         }
         log(
@@ -785,7 +961,7 @@ pub(crate) fn fetch_pkgsite_module_licenses(
             &format!("pkg.go.dev has no entry for {name}@{version}, falling back to latest"),
         );
     }
-    fetch_pkgsite_module_version(&client, name, None)
+    fetch_pkgsite_module_version(client, name, None)
 }
 
 /// Query the pkg.go.dev module endpoint for one version (`None` means latest)
@@ -810,7 +986,13 @@ fn fetch_pkgsite_module_version(
     let wait_time = 12;
 
     while attempts < max_attempts {
-        match client.get(&api_url).send() {
+        if crate::cancel::is_cancelled() {
+            return None;
+        }
+        let request = client
+            .get(&api_url)
+            .timeout(crate::cancel::request_timeout(Duration::from_secs(10)));
+        match crate::cancel::http_send(request, &api_url) {
             Ok(response) => {
                 let status = response.status();
                 log(
@@ -827,8 +1009,10 @@ fn fetch_pkgsite_module_version(
                             max_attempts
                         ),
                     );
-                    sleep(Duration::from_secs(wait_time));
                     attempts += 1;
+                    if !crate::cancel::sleep_unless_cancelled(Duration::from_secs(wait_time)) {
+                        return None;
+                    }
                     continue;
                 }
 
@@ -849,6 +1033,7 @@ fn fetch_pkgsite_module_version(
                 return None;
             }
             Err(err) => {
+                // `http_send` has recorded the failure
                 log_error(&format!("Failed to fetch metadata for {name}"), &err);
                 return None;
             }
@@ -859,6 +1044,11 @@ fn fetch_pkgsite_module_version(
         LogLevel::Warn,
         &format!("Unable to fetch license for {name} after {attempts} attempts"),
     );
+    record_resolution_error(
+        "pkg.go.dev",
+        &api_url,
+        format!("still rate limited (HTTP 429) after {attempts} attempts"),
+    );
     None
 }
 
@@ -1040,10 +1230,51 @@ mod tests {
     #[test]
     fn test_fetch_license_for_go_dependency_error_handling() {
         // Test with invalid package name
-        let result = fetch_license_for_go_dependency("invalid/package/name", "v1.0.0");
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let result =
+            fetch_license_for_go_dependency("invalid/package/name", "v1.0.0", temp_dir.path());
         assert_eq!(result, "Unknown");
     }
 
+    #[test]
+    fn test_license_from_vendor_dir() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let vendored = temp_dir.path().join("vendor/github.com/pkg/errors");
+        fs::create_dir_all(&vendored).unwrap();
+        fs::write(
+            vendored.join("LICENSE"),
+            "Copyright (c) 2015, Dave Cheney <dave@cheney.net>\n\
+             All rights reserved.\n\n\
+             Redistribution and use in source and binary forms, with or without modification, \
+             are permitted provided that the following conditions are met:\n\n\
+             * Redistributions of source code must retain the above copyright notice, this \
+             list of conditions and the following disclaimer.\n\n\
+             * Redistributions in binary form must reproduce the above copyright notice, this \
+             list of conditions and the following disclaimer in the documentation and/or other \
+             materials provided with the distribution.\n\n\
+             THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS \"AS IS\" AND \
+             ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED \
+             WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. \
+             IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, \
+             INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT \
+             NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR \
+             PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, \
+             WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) \
+             ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE \
+             POSSIBILITY OF SUCH DAMAGE.\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_license_from_vendor_dir(temp_dir.path(), "github.com/pkg/errors").as_deref(),
+            Some("BSD-2-Clause")
+        );
+        assert_eq!(
+            get_license_from_vendor_dir(temp_dir.path(), "github.com/pkg/other"),
+            None
+        );
+    }
+
     #[test]
     fn test_go_packages_debug() {
         let go_package = GoPackages {
@@ -1118,6 +1349,48 @@ github.com/level2@v1.0.0 github.com/level3@v1.0.0"#;
         assert!(!dep_names.contains(&"github.com/level3".to_string()));
     }
 
+    #[test]
+    fn test_parse_go_mod_graph_output_selects_highest_version() {
+        let graph_output = r#"example.com/app github.com/a/lib@v1.2.0
+example.com/app golang.org/x/text@v0.3.7
+github.com/a/lib@v1.2.0 golang.org/x/text@v0.14.0
+github.com/a/lib@v1.2.0 github.com/b/old@v2.0.0+incompatible
+"#;
+
+        let deps = parse_go_mod_graph_output(graph_output, 5);
+        assert_eq!(
+            deps,
+            vec![
+                ("github.com/a/lib".to_string(), "v1.2.0".to_string()),
+                (
+                    "github.com/b/old".to_string(),
+                    "v2.0.0+incompatible".to_string()
+                ),
+                ("golang.org/x/text".to_string(), "v0.14.0".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_go_sum() {
+        let go_sum = r#"github.com/a/lib v1.2.0 h1:abc=
+github.com/a/lib v1.2.0/go.mod h1:def=
+github.com/only/gomod v1.0.0/go.mod h1:ghi=
+golang.org/x/text v0.3.7 h1:jkl=
+golang.org/x/text v0.3.7/go.mod h1:mno=
+golang.org/x/text v0.14.0 h1:pqr=
+golang.org/x/text v0.14.0/go.mod h1:stu=
+"#;
+
+        assert_eq!(
+            parse_go_sum(go_sum),
+            vec![
+                ("github.com/a/lib".to_string(), "v1.2.0".to_string()),
+                ("golang.org/x/text".to_string(), "v0.14.0".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn test_resolve_go_dependencies_fallback() {
         let direct_deps = vec![
@@ -1145,6 +1418,32 @@ github.com/level2@v1.0.0 github.com/level3@v1.0.0"#;
         );
     }
 
+    #[test]
+    fn test_parse_go_list_modules() {
+        let output = "\n\nexample.com/app \ngithub.com/gin-gonic/gin v1.9.1\n\
+                      golang.org/x/net v0.17.0\ngithub.com/gin-gonic/gin v1.9.1\n";
+        let modules = parse_go_list_modules(output);
+        assert_eq!(
+            modules,
+            HashSet::from([
+                "github.com/gin-gonic/gin".to_string(),
+                "golang.org/x/net".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_without_test_only_modules_keeps_all_without_go_list() {
+        let deps = vec![(
+            "github.com/stretchr/testify".to_string(),
+            "v1.8.4".to_string(),
+        )];
+        assert_eq!(
+            without_test_only_modules("/nonexistent/go.mod", deps.clone()),
+            deps
+        );
+    }
+
     #[test]
     fn test_is_excluded_go_module() {
         // Test that standard Go modules are excluded
@@ -1,6 +1,5 @@
 use quick_xml::events::Event;
 use quick_xml::reader::Reader;
-use rayon::prelude::*;
 use regex::Regex;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
@@ -9,17 +8,18 @@ use std::path::{Path, PathBuf};
 use std::sync::{Mutex, OnceLock};
 
 use crate::config::FeludaConfig;
-use crate::debug::{log, log_error, LogLevel};
+use crate::debug::{log, log_error, record_parse_warning, LogLevel};
 use crate::licenses::{
     detect_license_from_content, fetch_licenses_from_github, is_license_restrictive,
     LicenseCompatibility, LicenseInfo,
 };
+use crate::resolver::{resolve_all, resolve_license, LicenseQuery};
 
 #[derive(Debug, Clone)]
-struct JavaDependency {
-    group_id: String,
-    artifact_id: String,
-    version: String,
+pub(crate) struct JavaDependency {
+    pub(crate) group_id: String,
+    pub(crate) artifact_id: String,
+    pub(crate) version: String,
 }
 
 pub fn analyze_java_licenses(file_path: &str, config: &FeludaConfig) -> Vec<LicenseInfo> {
@@ -30,10 +30,18 @@ pub fn analyze_java_licenses(file_path: &str, config: &FeludaConfig) -> Vec<Lice
 
     let project_dir = Path::new(file_path).parent().unwrap_or(Path::new("."));
 
+    // A Gradle lockfile already pins the full transitive set
+    let mut locked = false;
     let deps = if file_path.ends_with("pom.xml") {
         parse_maven_pom(file_path)
     } else if file_path.ends_with("build.gradle") || file_path.ends_with("build.gradle.kts") {
-        parse_gradle_build(file_path, project_dir)
+        match read_gradle_lockfiles(project_dir) {
+            Some(deps) => {
+                locked = true;
+                deps
+            }
+            None => parse_gradle_build(file_path, project_dir),
+        }
     } else {
         Vec::new()
     };
@@ -43,6 +51,18 @@ pub fn analyze_java_licenses(file_path: &str, config: &FeludaConfig) -> Vec<Lice
         return Vec::new();
     }
 
+    maven_licenses(deps, locked, &HashMap::new(), config)
+}
+
+/// Licenses of Maven coordinates, expanded to their transitive set unless `locked` says
+/// they already are one. `reported` maps `group:artifact` to a license a build tool already
+/// recorded. Shared with the Android analyzer, whose dependencies are Maven artifacts too.
+pub(crate) fn maven_licenses(
+    deps: Vec<JavaDependency>,
+    locked: bool,
+    reported: &HashMap<String, String>,
+    config: &FeludaConfig,
+) -> Vec<LicenseInfo> {
     log(
         LogLevel::Info,
         &format!("Found {} direct Java dependencies", deps.len()),
@@ -52,7 +72,11 @@ pub fn analyze_java_licenses(file_path: &str, config: &FeludaConfig) -> Vec<Lice
     // mirroring the transitive resolution done for Rust/Go/Node. Bounded by the
     // configured max dependency depth; falls back to the direct dependencies if
     // the registry is unreachable.
-    let deps = resolve_transitive_dependencies(deps, config.dependencies.max_depth);
+    let deps = if locked {
+        deps
+    } else {
+        resolve_transitive_dependencies(deps, config.dependencies.max_depth)
+    };
 
     log(
         LogLevel::Info,
@@ -70,23 +94,41 @@ pub fn analyze_java_licenses(file_path: &str, config: &FeludaConfig) -> Vec<Lice
         }
     };
 
-    deps.par_iter()
-        .map(|dep| {
-            let license = fetch_maven_license(&dep.group_id, &dep.artifact_id, &dep.version);
-            let is_restrictive =
-                is_license_restrictive(&Some(license.clone()), &known_licenses, config.strict);
-
-            LicenseInfo {
-                name: format!("{}:{}", dep.group_id, dep.artifact_id),
-                version: dep.version.clone(),
-                license: Some(license.clone()),
-                is_restrictive,
-                compatibility: LicenseCompatibility::Unknown,
-                osi_status: crate::licenses::get_osi_status(&license),
-                sub_project: None,
-            }
-        })
-        .collect()
+    resolve_all(&deps, |dep| {
+        let name = format!("{}:{}", dep.group_id, dep.artifact_id);
+        let license = fetch_maven_license(
+            &dep.group_id,
+            &dep.artifact_id,
+            &dep.version,
+            reported.get(&name).map(String::as_str),
+        );
+        let is_restrictive =
+            is_license_restrictive(&Some(license.clone()), &known_licenses, config.strict);
+
+        LicenseInfo {
+            name,
+            version: dep.version.clone(),
+            license: Some(license.clone()),
+            is_restrictive,
+            compatibility: LicenseCompatibility::Unknown,
+            osi_status: crate::licenses::get_osi_status(&license),
+            sub_project: None,
+            found_in: Vec::new(),
+            repository: None,
+            declared_in: None,
+            internal: false,
+            inherited_from: None,
+            ecosystem: None,
+            suggestions: Vec::new(),
+            update_policy: None,
+            introduced_by: Vec::new(),
+            purl: None,
+            vulnerabilities: None,
+            manifest: None,
+            data_license: None,
+            project_dirs: Vec::new(),
+        }
+    })
 }
 
 // =============================================================================
@@ -97,16 +139,20 @@ fn parse_maven_pom(pom_path: &str) -> Vec<JavaDependency> {
     let content = match fs::read_to_string(pom_path) {
         Ok(c) => c,
         Err(e) => {
-            log_error(&format!("Failed to read pom.xml: {pom_path}"), &e);
+            record_parse_warning(pom_path, format!("failed to read pom.xml: {e}"));
             return Vec::new();
         }
     };
 
-    let properties = extract_effective_pom_properties(&content);
-    let managed_versions = extract_dependency_management(&content, &properties);
-    let mut deps = extract_pom_dependencies(&content, &properties, &managed_versions, false);
+    // Parents contribute properties, managed versions and inherited dependencies
+    let own_version = extract_pom_coordinates(&content)
+        .version
+        .unwrap_or_default();
+    let chain = load_pom_chain(content, Path::new(pom_path).parent(), &own_version);
+    let pom = EffectivePom::build(&chain, 0);
+    let mut deps = pom.dependencies(&chain, false);
 
-    // Deduplicate
+    // Deduplicate, keeping a module's own declaration over the one it inherits
     deps.sort_by(|a, b| {
         a.group_id
             .cmp(&b.group_id)
@@ -222,6 +268,12 @@ fn extract_pom_coordinates(content: &str) -> PomCoordinates {
 /// when absent, matching Maven's inheritance rules.
 fn extract_effective_pom_properties(content: &str) -> HashMap<String, String> {
     let mut props = extract_pom_properties(content);
+    insert_builtin_properties(content, &mut props);
+    props
+}
+
+/// Add the `project.*` properties of the POM in `content`, resolving them against `props`
+fn insert_builtin_properties(content: &str, props: &mut HashMap<String, String>) {
     let coords = extract_pom_coordinates(content);
 
     let group_id = coords.group_id.or_else(|| coords.parent_group_id.clone());
@@ -237,7 +289,7 @@ fn extract_effective_pom_properties(content: &str) -> HashMap<String, String> {
     ];
     for (key, value) in builtins {
         if let Some(value) = value {
-            let value = resolve_property(&value, &props);
+            let value = resolve_property(&value, props);
             // A value that is itself an unresolvable placeholder (e.g. an
             // inherited `${revision}`) would only propagate the placeholder;
             // leave the property undefined instead.
@@ -246,8 +298,6 @@ fn extract_effective_pom_properties(content: &str) -> HashMap<String, String> {
             }
         }
     }
-
-    props
 }
 
 fn extract_dependency_management(
@@ -282,6 +332,212 @@ fn extract_dependency_management(
     managed
 }
 
+/// BOMs a POM imports into its `<dependencyManagement>` (`<type>pom</type>` with
+/// `<scope>import</scope>`), whose managed versions then apply to the POM.
+fn extract_bom_imports(content: &str, properties: &HashMap<String, String>) -> Vec<JavaDependency> {
+    let dm_re = Regex::new(r"(?s)<dependencyManagement>(.*?)</dependencyManagement>").unwrap();
+    let dep_re = Regex::new(r"(?s)<dependency>(.*?)</dependency>").unwrap();
+
+    let Some(dm_cap) = dm_re.captures(content) else {
+        return Vec::new();
+    };
+    dep_re
+        .captures_iter(&dm_cap[1])
+        .filter_map(|dep_cap| {
+            let block = &dep_cap[1];
+            let is_import = extract_xml_tag(block, "scope").as_deref() == Some("import")
+                && extract_xml_tag(block, "type").as_deref() == Some("pom");
+            if !is_import {
+                return None;
+            }
+            let version = resolve_property(&extract_xml_tag(block, "version")?, properties);
+            // An import we can't pin to a version can't be fetched
+            if version.contains("${") {
+                return None;
+            }
+            Some(JavaDependency {
+                group_id: resolve_property(&extract_xml_tag(block, "groupId")?, properties),
+                artifact_id: resolve_property(&extract_xml_tag(block, "artifactId")?, properties),
+                version,
+            })
+        })
+        .collect()
+}
+
+// =============================================================================
+// EFFECTIVE POM
+// =============================================================================
+
+/// Properties and managed versions of a POM merged with its parent chain and the
+/// BOMs it imports, as Maven assembles them for the effective POM.
+#[derive(Debug, Default)]
+struct EffectivePom {
+    properties: HashMap<String, String>,
+    managed_versions: HashMap<String, String>,
+}
+
+impl EffectivePom {
+    /// Merge `chain` (a POM followed by its ancestors, nearest first). `depth` counts
+    /// nested BOM imports, bounded by `MAX_POM_PARENT_DEPTH`.
+    fn build(chain: &[String], depth: usize) -> Self {
+        // Ancestors first, so each POM's properties override the ones it inherits
+        let mut properties = HashMap::new();
+        for content in chain.iter().rev() {
+            properties.extend(extract_pom_properties(content));
+        }
+        if let Some(pom) = chain.first() {
+            insert_builtin_properties(pom, &mut properties);
+        }
+
+        // Versions a POM declares win over its parents', and declared ones over imported BOMs
+        let mut managed_versions = HashMap::new();
+        for content in chain {
+            for (key, version) in extract_dependency_management(content, &properties) {
+                managed_versions.entry(key).or_insert(version);
+            }
+        }
+        if depth < MAX_POM_PARENT_DEPTH {
+            for bom in chain
+                .iter()
+                .flat_map(|content| extract_bom_imports(content, &properties))
+            {
+                let Some(content) =
+                    fetch_pom_content(&bom.group_id, &bom.artifact_id, &bom.version)
+                else {
+                    log(
+                        LogLevel::Warn,
+                        &format!(
+                            "Could not fetch imported BOM {}:{}:{}",
+                            bom.group_id, bom.artifact_id, bom.version
+                        ),
+                    );
+                    continue;
+                };
+                let bom_chain = load_pom_chain(content, None, &bom.version);
+                for (key, version) in Self::build(&bom_chain, depth + 1).managed_versions {
+                    managed_versions.entry(key).or_insert(version);
+                }
+            }
+        }
+
+        Self {
+            properties,
+            managed_versions,
+        }
+    }
+
+    /// Dependencies declared across `chain`, the POM's own before the ones it inherits
+    fn dependencies(&self, chain: &[String], transitive: bool) -> Vec<JavaDependency> {
+        chain
+            .iter()
+            .flat_map(|content| {
+                extract_pom_dependencies(
+                    content,
+                    &self.properties,
+                    &self.managed_versions,
+                    transitive,
+                )
+            })
+            .collect()
+    }
+}
+
+/// A POM followed by its ancestors, nearest first, up to `MAX_POM_PARENT_DEPTH` parents.
+///
+/// With `dir` (the directory of a POM on disk) a parent is read from its `<relativePath>`,
+/// `../pom.xml` by default, when the POM there is the declared parent; otherwise it is
+/// fetched from Maven Central. `version` is the version the POM was fetched at.
+fn load_pom_chain(content: String, dir: Option<&Path>, version: &str) -> Vec<String> {
+    let mut chain = vec![content];
+    let mut dir = dir.map(Path::to_path_buf);
+    let mut version = version.to_string();
+
+    while chain.len() <= MAX_POM_PARENT_DEPTH {
+        let current = &chain[chain.len() - 1];
+        let Some(parent) = resolve_parent_coordinate(current, &version) else {
+            break;
+        };
+
+        let local = dir
+            .as_deref()
+            .and_then(|dir| read_local_parent(dir, current, &parent));
+        let content = match local {
+            Some((content, parent_dir)) => {
+                dir = Some(parent_dir);
+                content
+            }
+            None => {
+                dir = None;
+                match fetch_pom_content(&parent.group_id, &parent.artifact_id, &parent.version) {
+                    Some(content) => content,
+                    None => {
+                        log(
+                            LogLevel::Warn,
+                            &format!(
+                                "Could not fetch parent POM {}:{}:{}",
+                                parent.group_id, parent.artifact_id, parent.version
+                            ),
+                        );
+                        break;
+                    }
+                }
+            }
+        };
+        version = parent.version;
+        chain.push(content);
+    }
+
+    chain
+}
+
+/// Read the parent POM from the `<relativePath>` of the POM in `content`, which lives in
+/// `dir`. Returns the parent's content and directory, or `None` when the lookup is disabled
+/// (`<relativePath/>`) or the POM found there isn't `parent`.
+fn read_local_parent(
+    dir: &Path,
+    content: &str,
+    parent: &ParentCoordinate,
+) -> Option<(String, PathBuf)> {
+    let parent_re = Regex::new(r"(?s)<parent>(.*?)</parent>").ok()?;
+    let block = parent_re.captures(content)?.get(1)?.as_str();
+    let empty_re = Regex::new(r"<relativePath\s*/>").ok()?;
+    if empty_re.is_match(block) {
+        return None;
+    }
+    let relative =
+        extract_pom_tag(block, "relativePath").unwrap_or_else(|| "../pom.xml".to_string());
+
+    let mut path = dir.join(relative);
+    if path.is_dir() {
+        path = path.join("pom.xml");
+    }
+    let parent_content = fs::read_to_string(&path).ok()?;
+
+    let coords = extract_pom_coordinates(&parent_content);
+    let group_id = coords.group_id.or(coords.parent_group_id);
+    if coords.artifact_id.as_deref() != Some(parent.artifact_id.as_str())
+        || group_id.as_deref() != Some(parent.group_id.as_str())
+    {
+        log(
+            LogLevel::Info,
+            &format!(
+                "{} is not parent POM {}:{}, fetching it instead",
+                path.display(),
+                parent.group_id,
+                parent.artifact_id
+            ),
+        );
+        return None;
+    }
+
+    log(
+        LogLevel::Info,
+        &format!("Using local parent POM {}", path.display()),
+    );
+    let parent_dir = path.parent().unwrap_or(dir).to_path_buf();
+    Some((parent_content, parent_dir))
+}
+
 /// Extract dependencies from a POM's `<dependencies>` blocks.
 ///
 /// When `transitive` is set the caller is reading a *dependency's* POM rather
@@ -395,7 +651,7 @@ fn parse_gradle_build(build_path: &str, project_dir: &Path) -> Vec<JavaDependenc
     let content = match fs::read_to_string(build_path) {
         Ok(c) => c,
         Err(e) => {
-            log_error(&format!("Failed to read {build_path}"), &e);
+            record_parse_warning(build_path, format!("failed to read build file: {e}"));
             return Vec::new();
         }
     };
@@ -418,7 +674,7 @@ fn parse_gradle_build(build_path: &str, project_dir: &Path) -> Vec<JavaDependenc
     deps
 }
 
-fn parse_gradle_dependencies(content: &str) -> Vec<JavaDependency> {
+pub(crate) fn parse_gradle_dependencies(content: &str) -> Vec<JavaDependency> {
     let mut deps = Vec::new();
 
     // Match: implementation 'group:artifact:version' or implementation("group:artifact:version")
@@ -429,8 +685,9 @@ fn parse_gradle_dependencies(content: &str) -> Vec<JavaDependency> {
     .unwrap();
 
     // Match: implementation(group: 'com.example', name: 'lib', version: '1.0')
+    // and the Kotlin DSL's implementation(group = "com.example", name = "lib", version = "1.0")
     let named_re = Regex::new(
-        r#"(?s)(?:implementation|api|compileOnly|runtimeOnly|annotationProcessor|compile)\s*\(\s*group\s*:\s*['"]([^'"]+)['"]\s*,\s*name\s*:\s*['"]([^'"]+)['"]\s*,\s*version\s*:\s*['"]([^'"]+)['"]\s*\)"#,
+        r#"(?s)(?:implementation|api|compileOnly|runtimeOnly|annotationProcessor|compile)\s*\(\s*group\s*[:=]\s*['"]([^'"]+)['"]\s*,\s*name\s*[:=]\s*['"]([^'"]+)['"]\s*,\s*version\s*[:=]\s*['"]([^'"]+)['"]\s*\)"#,
     )
     .unwrap();
 
@@ -452,7 +709,7 @@ fn parse_gradle_dependencies(content: &str) -> Vec<JavaDependency> {
     deps
 }
 
-fn parse_gradle_coordinate(coord: &str) -> Option<JavaDependency> {
+pub(crate) fn parse_gradle_coordinate(coord: &str) -> Option<JavaDependency> {
     let parts: Vec<&str> = coord.split(':').collect();
     if parts.len() < 2 {
         return None;
@@ -476,7 +733,105 @@ fn parse_gradle_coordinate(coord: &str) -> Option<JavaDependency> {
     })
 }
 
-fn read_gradle_properties(project_dir: &Path) -> HashMap<String, String> {
+/// Dependencies pinned by Gradle dependency locking: `gradle.lockfile` next to the build
+/// file, or the per-configuration `gradle/dependency-locks/*.lockfile` of Gradle 6 and older.
+/// `None` when the project doesn't lock its dependencies.
+pub(crate) fn read_gradle_lockfiles(project_dir: &Path) -> Option<Vec<JavaDependency>> {
+    let lockfile = project_dir.join("gradle.lockfile");
+    let mut deps = if lockfile.is_file() {
+        log(
+            LogLevel::Info,
+            &format!("Reading Gradle lockfile: {}", lockfile.display()),
+        );
+        read_gradle_lockfile(&lockfile, None)
+    } else {
+        let locks_dir = project_dir.join("gradle").join("dependency-locks");
+        let mut lockfiles: Vec<PathBuf> = fs::read_dir(&locks_dir)
+            .ok()?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "lockfile"))
+            .collect();
+        if lockfiles.is_empty() {
+            return None;
+        }
+        lockfiles.sort();
+        log(
+            LogLevel::Info,
+            &format!("Reading Gradle lockfiles in {}", locks_dir.display()),
+        );
+        lockfiles
+            .iter()
+            .flat_map(|path| {
+                // The legacy format names the configuration in the file name
+                let configuration = path.file_stem().map(|stem| stem.to_string_lossy());
+                read_gradle_lockfile(path, configuration.as_deref())
+            })
+            .collect()
+    };
+
+    deps.sort_by(|a, b| {
+        a.group_id
+            .cmp(&b.group_id)
+            .then(a.artifact_id.cmp(&b.artifact_id))
+    });
+    deps.dedup_by(|a, b| a.group_id == b.group_id && a.artifact_id == b.artifact_id);
+    Some(deps)
+}
+
+fn read_gradle_lockfile(path: &Path, configuration: Option<&str>) -> Vec<JavaDependency> {
+    match fs::read_to_string(path) {
+        Ok(content) => parse_gradle_lockfile(&content, configuration),
+        Err(e) => {
+            record_parse_warning(
+                path.display().to_string(),
+                format!("failed to read Gradle lockfile: {e}"),
+            );
+            Vec::new()
+        }
+    }
+}
+
+/// Parse a Gradle lockfile, one `group:artifact:version=configuration,...` per line.
+///
+/// Dependencies locked only for test configurations are left out, as `test` scope is for
+/// Maven. Legacy per-configuration lockfiles list bare coordinates and pass their
+/// configuration in `configuration`.
+fn parse_gradle_lockfile(content: &str, configuration: Option<&str>) -> Vec<JavaDependency> {
+    let is_test = |configuration: &str| configuration.to_ascii_lowercase().contains("test");
+
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (coordinate, configurations) = match line.split_once('=') {
+                Some((coordinate, configurations)) => (coordinate, configurations),
+                None => (line, configuration.unwrap_or_default()),
+            };
+            // `empty=...` lists configurations that resolve nothing
+            if coordinate == "empty" {
+                return None;
+            }
+            let runtime = configurations.is_empty()
+                || configurations
+                    .split(',')
+                    .any(|configuration| !is_test(configuration.trim()));
+            if !runtime {
+                return None;
+            }
+
+            let mut parts = coordinate.split(':');
+            let (group_id, artifact_id, version) = (parts.next()?, parts.next()?, parts.next()?);
+            Some(JavaDependency {
+                group_id: group_id.to_string(),
+                artifact_id: artifact_id.to_string(),
+                version: version.to_string(),
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn read_gradle_properties(project_dir: &Path) -> HashMap<String, String> {
     let mut props = HashMap::new();
     let props_path = project_dir.join("gradle.properties");
 
@@ -495,7 +850,7 @@ fn read_gradle_properties(project_dir: &Path) -> HashMap<String, String> {
     props
 }
 
-fn resolve_gradle_variable(value: &str, props: &HashMap<String, String>) -> String {
+pub(crate) fn resolve_gradle_variable(value: &str, props: &HashMap<String, String>) -> String {
     // Handle ${propName} and $propName style references
     let re = Regex::new(r"\$\{?([A-Za-z_][A-Za-z0-9_.]*)\}?").unwrap();
     let mut result = value.to_string();
@@ -556,7 +911,7 @@ fn resolve_transitive_dependencies(
 
 /// Fetch an artifact's POM and extract the dependencies it propagates
 /// transitively (compile/runtime scope, non-optional), resolving `${properties}`
-/// and `<dependencyManagement>` declared within that POM.
+/// and `<dependencyManagement>` from the POM, its parents and imported BOMs.
 fn fetch_pom_transitive_deps(
     group_id: &str,
     artifact_id: &str,
@@ -567,32 +922,43 @@ fn fetch_pom_transitive_deps(
         None => return Vec::new(),
     };
 
-    let properties = extract_effective_pom_properties(&content);
-    let managed_versions = extract_dependency_management(&content, &properties);
-    extract_pom_dependencies(&content, &properties, &managed_versions, true)
+    let chain = load_pom_chain(content, None, version);
+    EffectivePom::build(&chain, 0).dependencies(&chain, true)
 }
 
 // =============================================================================
 // MAVEN CENTRAL LICENSE LOOKUP
 // =============================================================================
 
-fn fetch_maven_license(group_id: &str, artifact_id: &str, version: &str) -> String {
-    // Try fetching the POM from Maven Central and extracting license info
-    if let Some(license) = fetch_license_from_pom(group_id, artifact_id, version) {
-        return license;
-    }
-
-    // Fallback: Maven Central search API
-    if let Some(license) = fetch_license_from_search_api(group_id, artifact_id) {
-        return license;
-    }
-
-    // Local fallback: read the license text bundled inside the cached jar.
-    if let Some(license) = fetch_license_from_local_jar(group_id, artifact_id, version) {
-        return license;
-    }
+/// `reported` is a license a build tool already recorded for the artifact, such as a Gradle
+/// license plugin report, and is trusted like any other local metadata.
+fn fetch_maven_license(
+    group_id: &str,
+    artifact_id: &str,
+    version: &str,
+    reported: Option<&str>,
+) -> String {
+    // Local: the reported license, the POM in Gradle's cache, then the license text bundled
+    // inside the cached jar
+    let local = |_: &str, version: &str| {
+        reported
+            .map(str::to_string)
+            .or_else(|| fetch_license_from_gradle_cache(group_id, artifact_id, version))
+            .or_else(|| fetch_license_from_local_jar(group_id, artifact_id, version))
+    };
+    // Registry: the POM from Maven Central, then the Maven Central search API
+    let registry = |_: &str, version: &str| {
+        fetch_license_from_pom(group_id, artifact_id, version)
+            .or_else(|| fetch_license_from_search_api(group_id, artifact_id))
+    };
 
-    "Unknown".to_string()
+    let name = format!("{group_id}:{artifact_id}");
+    resolve_license(
+        &LicenseQuery::new("java", &name, version),
+        Some(&local),
+        Some(&registry),
+    )
+    .unwrap_or_else(|| "Unknown".to_string())
 }
 
 /// License files conventionally bundled inside a jar, in priority order. Maven artifacts
@@ -632,6 +998,37 @@ fn fetch_license_from_local_jar(
     detect_license_in_jar(&jar_path)
 }
 
+/// The license an artifact's POM declares, read from Gradle's module cache.
+///
+/// Gradle keeps it at `<gradle home>/caches/modules-2/files-2.1/<group>/<artifact>/<version>/<sha1>/`,
+/// where the home is `GRADLE_USER_HOME` or the default `~/.gradle`. This is the only local
+/// metadata for Android libraries, which ship as AARs rather than jars.
+fn fetch_license_from_gradle_cache(
+    group_id: &str,
+    artifact_id: &str,
+    version: &str,
+) -> Option<String> {
+    if version.is_empty() || version == "RELEASE" {
+        return None;
+    }
+
+    let gradle_home = std::env::var_os("GRADLE_USER_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".gradle")))?;
+    let version_dir = gradle_home
+        .join("caches/modules-2/files-2.1")
+        .join(group_id)
+        .join(artifact_id)
+        .join(version);
+    let pom_name = format!("{artifact_id}-{version}.pom");
+
+    fs::read_dir(version_dir)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| fs::read_to_string(entry.path().join(&pom_name)).ok())
+        .find_map(|pom| extract_license_from_pom_content(&pom))
+}
+
 fn maven_local_repo() -> Option<PathBuf> {
     if let Ok(repo) = std::env::var("MAVEN_REPO_LOCAL") {
         return Some(PathBuf::from(repo));
@@ -725,6 +1122,13 @@ fn fetch_pom_content(group_id: &str, artifact_id: &str, version: &str) -> Option
     result
 }
 
+/// Repositories POMs are fetched from, in order. Google's hosts the AndroidX, Play services
+/// and Firebase artifacts, which are not published to Maven Central.
+const MAVEN_REPOSITORIES: [&str; 2] = [
+    "https://repo1.maven.org/maven2",
+    "https://dl.google.com/dl/android/maven2",
+];
+
 fn fetch_pom_content_uncached(group_id: &str, artifact_id: &str, version: &str) -> Option<String> {
     let group_path = group_id.replace('.', "/");
     let effective_version = if version == "RELEASE" || version.is_empty() {
@@ -733,18 +1137,20 @@ fn fetch_pom_content_uncached(group_id: &str, artifact_id: &str, version: &str)
         version.to_string()
     };
 
-    let pom_url = format!(
-        "https://repo1.maven.org/maven2/{group_path}/{artifact_id}/{effective_version}/{artifact_id}-{effective_version}.pom"
-    );
+    MAVEN_REPOSITORIES.iter().find_map(|repository| {
+        let pom_url = format!(
+            "{repository}/{group_path}/{artifact_id}/{effective_version}/{artifact_id}-{effective_version}.pom"
+        );
 
-    log(LogLevel::Info, &format!("Fetching POM: {pom_url}"));
+        log(LogLevel::Info, &format!("Fetching POM: {pom_url}"));
 
-    let response = reqwest::blocking::get(&pom_url).ok()?;
-    if !response.status().is_success() {
-        return None;
-    }
+        let response = crate::cancel::http_get(&pom_url).ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
 
-    response.text().ok()
+        response.text().ok()
+    })
 }
 
 fn extract_license_from_pom_content(content: &str) -> Option<String> {
@@ -809,7 +1215,7 @@ fn fetch_latest_version(group_id: &str, artifact_id: &str) -> Option<String> {
         "https://search.maven.org/solrsearch/select?q=g:{group_id}+AND+a:{artifact_id}&rows=1&wt=json"
     );
 
-    let response = reqwest::blocking::get(&url).ok()?;
+    let response = crate::cancel::http_get(&url).ok()?;
     if !response.status().is_success() {
         return None;
     }
@@ -832,7 +1238,7 @@ fn fetch_license_from_search_api(group_id: &str, artifact_id: &str) -> Option<St
         &format!("Querying Maven Central search for {group_id}:{artifact_id}"),
     );
 
-    let response = reqwest::blocking::get(&url).ok()?;
+    let response = crate::cancel::http_get(&url).ok()?;
     if !response.status().is_success() {
         return None;
     }
@@ -961,6 +1367,83 @@ dependencies {
         assert!(deps.iter().any(|d| d.artifact_id == "kafka-clients"));
     }
 
+    #[test]
+    fn test_parse_gradle_dependencies_kotlin_named() {
+        let content = r#"
+dependencies {
+    implementation(group = "org.apache.kafka", name = "kafka-clients", version = "3.4.0")
+}
+"#;
+        let deps = parse_gradle_dependencies(content);
+        assert_eq!(deps.len(), 1);
+        assert_eq!(deps[0].artifact_id, "kafka-clients");
+        assert_eq!(deps[0].version, "3.4.0");
+    }
+
+    #[test]
+    fn test_parse_gradle_lockfile() {
+        let content = "\
+# This is a Gradle generated file for dependency locking.
+# Manual edits can break the build and are not advised.
+com.google.guava:failureaccess:1.0.1=compileClasspath,runtimeClasspath
+com.google.guava:guava:32.1.2-jre=compileClasspath,runtimeClasspath
+junit:junit:4.13.2=testCompileClasspath,testRuntimeClasspath
+org.mockito:mockito-core:5.2.0=androidTestRuntimeClasspath,debugUnitTestRuntimeClasspath
+org.slf4j:slf4j-api:2.0.9=runtimeClasspath,testRuntimeClasspath
+empty=annotationProcessor
+";
+        let deps = parse_gradle_lockfile(content, None);
+        let coords: Vec<String> = deps
+            .iter()
+            .map(|d| format!("{}:{}:{}", d.group_id, d.artifact_id, d.version))
+            .collect();
+        assert_eq!(
+            coords,
+            vec![
+                "com.google.guava:failureaccess:1.0.1",
+                "com.google.guava:guava:32.1.2-jre",
+                "org.slf4j:slf4j-api:2.0.9",
+            ]
+        );
+
+        // Legacy per-configuration lockfiles carry bare coordinates
+        let legacy = "com.google.guava:guava:32.1.2-jre\n";
+        assert_eq!(
+            parse_gradle_lockfile(legacy, Some("compileClasspath")).len(),
+            1
+        );
+        assert!(parse_gradle_lockfile(legacy, Some("testCompileClasspath")).is_empty());
+    }
+
+    #[test]
+    fn test_read_gradle_lockfiles() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(read_gradle_lockfiles(temp_dir.path()).is_none());
+
+        let locks_dir = temp_dir.path().join("gradle").join("dependency-locks");
+        fs::create_dir_all(&locks_dir).unwrap();
+        fs::write(
+            locks_dir.join("runtimeClasspath.lockfile"),
+            "org.slf4j:slf4j-api:2.0.9\n",
+        )
+        .unwrap();
+        fs::write(
+            locks_dir.join("compileClasspath.lockfile"),
+            "org.slf4j:slf4j-api:2.0.9\ncom.google.guava:guava:32.1.2-jre\n",
+        )
+        .unwrap();
+        let deps = read_gradle_lockfiles(temp_dir.path()).unwrap();
+        assert_eq!(deps.len(), 2);
+
+        // gradle.lockfile takes over from the legacy directory
+        fs::write(
+            temp_dir.path().join("gradle.lockfile"),
+            "junit:junit:4.13.2=testRuntimeClasspath\nempty=\n",
+        )
+        .unwrap();
+        assert!(read_gradle_lockfiles(temp_dir.path()).unwrap().is_empty());
+    }
+
     #[test]
     fn test_extract_xml_tag() {
         let block = "<groupId>com.example</groupId><artifactId>mylib</artifactId>";
@@ -1091,6 +1574,153 @@ dependencies {
         assert_eq!(deps[0].version, "5.3.20");
     }
 
+    #[test]
+    fn test_parse_maven_pom_local_parent() {
+        // A module inherits properties, managed versions and dependencies from the
+        // parent POM next to it, and its own declarations win.
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("pom.xml"),
+            r#"<project>
+    <groupId>com.example</groupId>
+    <artifactId>example-parent</artifactId>
+    <version>2.0.0</version>
+    <packaging>pom</packaging>
+    <properties>
+        <jackson.version>2.17.0</jackson.version>
+        <slf4j.version>2.0.9</slf4j.version>
+    </properties>
+    <dependencyManagement>
+        <dependencies>
+            <dependency>
+                <groupId>com.fasterxml.jackson.core</groupId>
+                <artifactId>jackson-databind</artifactId>
+                <version>${jackson.version}</version>
+            </dependency>
+        </dependencies>
+    </dependencyManagement>
+    <dependencies>
+        <dependency>
+            <groupId>org.slf4j</groupId>
+            <artifactId>slf4j-api</artifactId>
+            <version>${slf4j.version}</version>
+        </dependency>
+    </dependencies>
+</project>"#,
+        )
+        .unwrap();
+        let module_dir = temp_dir.path().join("service");
+        fs::create_dir(&module_dir).unwrap();
+        let pom_path = module_dir.join("pom.xml");
+        fs::write(
+            &pom_path,
+            r#"<project>
+    <parent>
+        <groupId>com.example</groupId>
+        <artifactId>example-parent</artifactId>
+        <version>2.0.0</version>
+    </parent>
+    <artifactId>service</artifactId>
+    <properties>
+        <slf4j.version>2.0.13</slf4j.version>
+    </properties>
+    <dependencies>
+        <dependency>
+            <groupId>com.fasterxml.jackson.core</groupId>
+            <artifactId>jackson-databind</artifactId>
+        </dependency>
+        <dependency>
+            <groupId>com.example</groupId>
+            <artifactId>example-core</artifactId>
+            <version>${project.version}</version>
+        </dependency>
+    </dependencies>
+</project>"#,
+        )
+        .unwrap();
+
+        let deps = parse_maven_pom(pom_path.to_str().unwrap());
+        let coords: Vec<String> = deps
+            .iter()
+            .map(|d| format!("{}:{}:{}", d.group_id, d.artifact_id, d.version))
+            .collect();
+        assert_eq!(
+            coords,
+            vec![
+                "com.example:example-core:2.0.0",
+                "com.fasterxml.jackson.core:jackson-databind:2.17.0",
+                "org.slf4j:slf4j-api:2.0.13",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_bom_imports() {
+        let content = r#"<project>
+  <properties><spring.version>6.1.4</spring.version></properties>
+  <dependencyManagement>
+    <dependencies>
+      <dependency>
+        <groupId>org.springframework</groupId>
+        <artifactId>spring-framework-bom</artifactId>
+        <version>${spring.version}</version>
+        <type>pom</type>
+        <scope>import</scope>
+      </dependency>
+      <dependency>
+        <groupId>com.google.guava</groupId>
+        <artifactId>guava</artifactId>
+        <version>33.0.0-jre</version>
+      </dependency>
+      <dependency>
+        <groupId>org.example</groupId>
+        <artifactId>unpinned-bom</artifactId>
+        <version>${undefined}</version>
+        <type>pom</type>
+        <scope>import</scope>
+      </dependency>
+    </dependencies>
+  </dependencyManagement>
+</project>"#;
+        let props = extract_effective_pom_properties(content);
+        let boms = extract_bom_imports(content, &props);
+        assert_eq!(boms.len(), 1);
+        assert_eq!(boms[0].group_id, "org.springframework");
+        assert_eq!(boms[0].artifact_id, "spring-framework-bom");
+        assert_eq!(boms[0].version, "6.1.4");
+    }
+
+    #[test]
+    fn test_read_local_parent_checks_coordinates() {
+        let temp_dir = TempDir::new().unwrap();
+        let module_dir = temp_dir.path().join("module");
+        fs::create_dir(&module_dir).unwrap();
+        fs::write(
+            temp_dir.path().join("pom.xml"),
+            "<project><groupId>com.example</groupId><artifactId>aggregator</artifactId></project>",
+        )
+        .unwrap();
+        let parent = ParentCoordinate {
+            group_id: "com.example".to_string(),
+            artifact_id: "example-parent".to_string(),
+            version: "1.0.0".to_string(),
+        };
+
+        // The default ../pom.xml is a different project
+        let child = "<project><parent><groupId>com.example</groupId><artifactId>example-parent</artifactId><version>1.0.0</version></parent></project>";
+        assert!(read_local_parent(&module_dir, child, &parent).is_none());
+
+        // <relativePath/> turns the local lookup off
+        fs::write(
+            temp_dir.path().join("pom.xml"),
+            "<project><groupId>com.example</groupId><artifactId>example-parent</artifactId></project>",
+        )
+        .unwrap();
+        assert!(read_local_parent(&module_dir, child, &parent).is_some());
+        let detached = child.replace("</parent>", "<relativePath/></parent>");
+        assert!(read_local_parent(&module_dir, &detached, &parent).is_none());
+    }
+
     #[test]
     fn test_extract_license_from_pom_content() {
         let content = r#"<licenses>
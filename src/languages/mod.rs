@@ -1,15 +1,21 @@
 //! Language-specific parsing and license analysis modules
 
+pub mod android;
 pub mod c;
+pub mod carthage;
 pub mod cpp;
 pub mod dotnet;
+pub mod elixir;
 pub mod go;
 pub mod java;
 pub mod node;
+pub mod php;
 pub mod python;
 pub mod r;
 pub mod ruby;
 pub mod rust;
+pub mod system;
+pub mod wasm;
 
 use crate::licenses::LicenseInfo;
 use std::path::Path;
@@ -33,16 +39,21 @@ pub trait LanguageParser {
 /// Language identification
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Language {
+    Android(&'static [&'static str]),
     C(&'static [&'static str]),
+    Carthage(&'static [&'static str]),
     Cpp(&'static [&'static str]),
     DotNet(&'static [&'static str]),
+    Elixir(&'static [&'static str]),
     Java(&'static [&'static str]),
     Rust(&'static str),
     Node(&'static str),
     Go(&'static str),
     Python(&'static [&'static str]),
+    Php(&'static [&'static str]),
     R(&'static [&'static str]),
     Ruby(&'static [&'static str]),
+    Wasm(&'static [&'static str]),
 }
 
 impl Language {
@@ -54,16 +65,24 @@ impl Language {
             "go.work" => Some(Language::Go("go.work")),
             "pom.xml" => Some(Language::Java(&JAVA_PATHS[..])),
             "build.gradle" | "build.gradle.kts" => Some(Language::Java(&JAVA_PATHS[..])),
+            "settings.gradle" | "settings.gradle.kts" => {
+                Some(Language::Android(&ANDROID_PATHS[..]))
+            }
             "vcpkg.json" => Some(Language::Cpp(&CPP_PATHS[..])),
             "conanfile.txt" | "conanfile.py" => Some(Language::Cpp(&CPP_PATHS[..])),
             "MODULE.bazel" => Some(Language::Cpp(&CPP_PATHS[..])),
             "configure.ac" | "configure.in" | "Makefile" => Some(Language::C(&C_PATHS[..])),
             "CMakeLists.txt" => Some(Language::Cpp(&CPP_PATHS[..])),
             "Gemfile" | "Gemfile.lock" => Some(Language::Ruby(&RUBY_PATHS[..])),
+            "composer.json" | "composer.lock" => Some(Language::Php(&PHP_PATHS[..])),
+            "mix.exs" | "mix.lock" => Some(Language::Elixir(&ELIXIR_PATHS[..])),
+            "wkg.lock" | "wkg.toml" => Some(Language::Wasm(&WASM_PATHS[..])),
+            "Cartfile.resolved" | "Cartfile" => Some(Language::Carthage(&CARTHAGE_PATHS[..])),
             _ => {
                 if file_name.ends_with(".csproj")
                     || file_name.ends_with(".fsproj")
                     || file_name.ends_with(".vbproj")
+                    || file_name.ends_with(".sln")
                     || file_name.ends_with(".slnx")
                 {
                     Some(Language::DotNet(&DOTNET_PATHS[..]))
@@ -82,6 +101,13 @@ impl Language {
 /// Java project file patterns (Maven and Gradle)
 pub const JAVA_PATHS: [&str; 3] = ["pom.xml", "build.gradle", "build.gradle.kts"];
 
+/// Android / Kotlin Multiplatform project file patterns: the Gradle settings file, which
+/// names the modules and sits next to the `gradle/libs.versions.toml` version catalog
+pub const ANDROID_PATHS: [&str; 2] = ["settings.gradle.kts", "settings.gradle"];
+
+/// Carthage (iOS/macOS) project file patterns, lockfile first
+pub const CARTHAGE_PATHS: [&str; 2] = ["Cartfile.resolved", "Cartfile"];
+
 /// C project file patterns
 pub const C_PATHS: [&str; 3] = ["configure.ac", "configure.in", "Makefile"];
 
@@ -108,5 +134,16 @@ pub const R_PATHS: [&str; 2] = ["DESCRIPTION", "renv.lock"];
 /// Ruby project file patterns
 pub const RUBY_PATHS: [&str; 2] = ["Gemfile.lock", "Gemfile"];
 
+/// PHP project file patterns (Composer), lockfile first
+pub const PHP_PATHS: [&str; 2] = ["composer.lock", "composer.json"];
+
+/// Elixir project file patterns (Mix), lockfile first
+pub const ELIXIR_PATHS: [&str; 2] = ["mix.lock", "mix.exs"];
+
+/// WebAssembly component project file patterns (wasm-pkg-tools), lockfile first. A `wit/`
+/// directory makes a component root too, see [`wasm::has_wit_packages`].
+pub const WASM_PATHS: [&str; 2] = ["wkg.lock", "wkg.toml"];
+
 /// .NET project file patterns
-pub const DOTNET_PATHS: [&str; 4] = [".csproj", ".fsproj", ".vbproj", ".slnx"];
+// Solutions first, so a directory holding one scans all of its projects
+pub const DOTNET_PATHS: [&str; 5] = [".slnx", ".sln", ".csproj", ".fsproj", ".vbproj"];
@@ -1,16 +1,18 @@
-use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use std::fs;
+use std::io::{self, BufRead};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
-use crate::debug::{log, log_debug, log_error, LogLevel};
+use crate::debug::{log, log_debug, log_error, record_parse_warning, LogLevel};
 use crate::licenses::{
     detect_license_in_dir, fetch_licenses_from_github, is_license_restrictive,
-    LicenseCompatibility, LicenseInfo,
+    normalize_license_id, LicenseCompatibility, LicenseInfo,
 };
+use crate::resolver::{resolve_all, resolve_license, LicenseQuery};
+use crate::spdx;
 
 /// Type alias for dependency detection
 type DependencyDetector = fn(&Path) -> Result<HashMap<String, String>, String>;
@@ -155,7 +157,7 @@ impl DependencyResolver {
         };
 
         let response =
-            reqwest::blocking::get(&url).map_err(|e| format!("Registry request failed: {e}"))?;
+            crate::cancel::http_get(&url).map_err(|e| format!("Registry request failed: {e}"))?;
 
         if !response.status().is_success() {
             return Err(format!("Registry returned status: {}", response.status()));
@@ -193,18 +195,7 @@ impl DependencyResolver {
             json
         };
 
-        let license = version_data
-            .get("license")
-            .and_then(|l| l.as_str())
-            .or_else(|| {
-                version_data
-                    .get("licenses")
-                    .and_then(|ls| ls.as_array())
-                    .and_then(|arr| arr.first())
-                    .and_then(|first| first.get("type"))
-                    .and_then(|t| t.as_str())
-            })
-            .map(String::from);
+        let license = license_from_manifest(version_data);
 
         let dependencies = self.extract_dependencies_from_json(version_data, "dependencies");
 
@@ -273,21 +264,71 @@ pub fn analyze_js_licenses_with_config(
         &format!("Analyzing JavaScript dependencies from: {package_json_path}"),
     );
 
+    let manifest = match std::fs::read_to_string(package_json_path)
+        .map_err(|e| format!("failed to read package.json: {e}"))
+        .and_then(|content| {
+            serde_json::from_str::<Value>(&content)
+                .map_err(|e| format!("invalid package.json: {e}"))
+        }) {
+        Ok(manifest) => manifest,
+        Err(reason) => {
+            record_parse_warning(package_json_path, reason);
+            return Vec::new();
+        }
+    };
+
     let project_root = Path::new(package_json_path)
         .parent()
         .unwrap_or(Path::new("."));
 
-    let all_dependencies = if project_root.join("pnpm-lock.yaml").exists() {
+    let mut all_dependencies: Vec<(String, String)> = if let Some(locked) =
+        read_node_lockfile(project_root, &manifest, config.no_dev).filter(|deps| !deps.is_empty())
+    {
+        // The lockfile pins the whole transitive tree, so no package manager need be run
+        log(LogLevel::Info, "Using the lockfile's dependency tree");
+        locked.into_iter().collect()
+    } else if project_root.join("pnpm-lock.yaml").exists() {
         log(
             LogLevel::Info,
             "Detected pnpm project - using specialized pnpm analysis",
         );
         analyze_pnpm_project_comprehensive(project_root, package_json_path)
+            .into_iter()
+            .collect()
     } else {
         log(LogLevel::Info, "Using general npm/yarn analysis");
         try_all_dependency_detection_methods(project_root, package_json_path)
+            .into_iter()
+            .collect()
     };
 
+    let dependents = npm_lockfile_dependents(project_root, config.no_dev);
+    let attribution = build_npm_workspace_attribution(project_root, &manifest);
+    if !attribution.is_empty() {
+        log(
+            LogLevel::Info,
+            &format!(
+                "Built npm workspace attribution for {} unique deps across members",
+                attribution.len()
+            ),
+        );
+    }
+
+    if config.direct_only {
+        // Workspaces record every member's declarations in the attribution map
+        let mut direct = HashMap::new();
+        record_direct_deps_from_json(&manifest, "root", &mut direct);
+        all_dependencies
+            .retain(|(name, _)| direct.contains_key(name) || attribution.contains_key(name));
+        log(
+            LogLevel::Info,
+            &format!(
+                "Kept {} direct dependencies (--direct-only)",
+                all_dependencies.len()
+            ),
+        );
+    }
+
     if all_dependencies.is_empty() {
         log(LogLevel::Warn, "No dependencies found using any method");
         return Vec::new();
@@ -302,7 +343,7 @@ pub fn analyze_js_licenses_with_config(
     );
     log_debug(
         "All detected dependencies (first 20)",
-        &all_dependencies.iter().take(20).collect::<HashMap<_, _>>(),
+        &all_dependencies.iter().take(20).collect::<Vec<_>>(),
     );
 
     let known_licenses = match fetch_licenses_from_github() {
@@ -319,47 +360,50 @@ pub fn analyze_js_licenses_with_config(
         }
     };
 
-    let attribution = build_npm_workspace_attribution(project_root, package_json_path);
-    if !attribution.is_empty() {
-        log(
-            LogLevel::Info,
-            &format!(
-                "Built npm workspace attribution for {} unique deps across members",
-                attribution.len()
-            ),
-        );
-    }
-
     // Process dependencies in parallel
-    all_dependencies
-        .par_iter()
-        .map(|(name, version)| {
-            let license = get_license_for_package(project_root, name, version, no_local);
-            let is_restrictive =
-                is_license_restrictive(&Some(license.clone()), &known_licenses, config.strict);
-
-            if is_restrictive {
-                log(
-                    LogLevel::Warn,
-                    &format!("Restrictive license found: {license} for {name}"),
-                );
-            }
+    resolve_all(&all_dependencies, |(name, version)| {
+        let license = get_license_for_package(project_root, name, version, no_local);
+        let is_restrictive =
+            is_license_restrictive(&Some(license.clone()), &known_licenses, config.strict);
 
-            let sub_project = attribution
+        if is_restrictive {
+            log(
+                LogLevel::Warn,
+                &format!("Restrictive license found: {license} for {name}"),
+            );
+        }
+
+        let sub_project = attribution
+            .get(name)
+            .map(|members| members.iter().cloned().collect::<Vec<_>>().join(", "));
+
+        LicenseInfo {
+            name: name.to_string(),
+            version: clean_version_string(version),
+            license: Some(license.clone()),
+            is_restrictive,
+            compatibility: LicenseCompatibility::Unknown,
+            osi_status: crate::licenses::get_osi_status(&license),
+            sub_project,
+            found_in: Vec::new(),
+            repository: None,
+            declared_in: None,
+            internal: false,
+            inherited_from: None,
+            ecosystem: None,
+            suggestions: Vec::new(),
+            update_policy: None,
+            introduced_by: dependents
                 .get(name)
-                .map(|members| members.iter().cloned().collect::<Vec<_>>().join(", "));
-
-            LicenseInfo {
-                name: name.to_string(),
-                version: clean_version_string(version),
-                license: Some(license.clone()),
-                is_restrictive,
-                compatibility: LicenseCompatibility::Unknown,
-                osi_status: crate::licenses::get_osi_status(&license),
-                sub_project,
-            }
-        })
-        .collect()
+                .map(|direct| direct.iter().cloned().collect())
+                .unwrap_or_default(),
+            purl: None,
+            vulnerabilities: None,
+            manifest: None,
+            data_license: None,
+            project_dirs: Vec::new(),
+        }
+    })
 }
 
 /// Build a map from dep name -> set of workspace member names that declare it.
@@ -368,19 +412,10 @@ pub fn analyze_js_licenses_with_config(
 /// attributed to the root package name (or "root" if unnamed).
 fn build_npm_workspace_attribution(
     project_root: &Path,
-    package_json_path: &str,
+    root_json: &Value,
 ) -> HashMap<String, std::collections::BTreeSet<String>> {
     let mut attribution: HashMap<String, std::collections::BTreeSet<String>> = HashMap::new();
 
-    let root_content = match fs::read_to_string(package_json_path) {
-        Ok(c) => c,
-        Err(_) => return attribution,
-    };
-    let root_json: Value = match serde_json::from_str(&root_content) {
-        Ok(v) => v,
-        Err(_) => return attribution,
-    };
-
     let workspaces = match root_json.get("workspaces") {
         Some(w) => w,
         None => return attribution,
@@ -404,7 +439,7 @@ fn build_npm_workspace_attribution(
         .and_then(|v| v.as_str())
         .map(String::from)
         .unwrap_or_else(|| "root".to_string());
-    record_direct_deps_from_json(&root_json, &root_name, &mut attribution);
+    record_direct_deps_from_json(root_json, &root_name, &mut attribution);
 
     for pattern in patterns {
         for dir in expand_workspace_pattern(project_root, pattern) {
@@ -963,7 +998,7 @@ fn parse_lockfiles(project_root: &Path) -> Result<HashMap<String, String>, Strin
     let mut deps = HashMap::new();
 
     // Parse pnpm-lock.yaml
-    if let Some(pnpm_deps) = parse_pnpm_lockfile(project_root) {
+    if let Some(pnpm_deps) = parse_pnpm_lockfile(project_root, false) {
         deps.extend(pnpm_deps);
     }
 
@@ -972,15 +1007,99 @@ fn parse_lockfiles(project_root: &Path) -> Result<HashMap<String, String>, Strin
         deps.extend(yarn_deps);
     }
 
-    // Parse package-lock.json
-    if let Some(npm_deps) = parse_npm_lockfile(project_root) {
+    // Parse npm-shrinkwrap.json / package-lock.json
+    if let Some(npm_deps) = parse_npm_lockfile(project_root, false) {
         deps.extend(npm_deps);
     }
 
     Ok(deps)
 }
 
-fn parse_pnpm_lockfile(project_root: &Path) -> Option<HashMap<String, String>> {
+/// The lockfile that pins a Node project's tree, as found next to its package.json
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NodeLockfile {
+    Npm,
+    Yarn,
+    Pnpm,
+}
+
+/// Which lockfile to read for the project at `project_root`.
+///
+/// When more than one lockfile is present, the package manager named in package.json's
+/// `packageManager` field decides; otherwise pnpm wins over yarn, and yarn over npm.
+fn detect_node_lockfile(project_root: &Path, manifest: &Value) -> Option<NodeLockfile> {
+    let present: Vec<NodeLockfile> = [
+        (
+            NodeLockfile::Pnpm,
+            project_root.join("pnpm-lock.yaml").exists(),
+        ),
+        (NodeLockfile::Yarn, project_root.join("yarn.lock").exists()),
+        (
+            NodeLockfile::Npm,
+            NPM_LOCKFILES
+                .iter()
+                .any(|name| project_root.join(name).exists()),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(kind, exists)| exists.then_some(kind))
+    .collect();
+
+    let declared = manifest
+        .get("packageManager")
+        .and_then(Value::as_str)
+        .and_then(|spec| match spec.split('@').next() {
+            Some("pnpm") => Some(NodeLockfile::Pnpm),
+            Some("yarn") => Some(NodeLockfile::Yarn),
+            Some("npm") => Some(NodeLockfile::Npm),
+            _ => None,
+        });
+
+    declared
+        .filter(|kind| present.contains(kind))
+        .or_else(|| present.first().copied())
+}
+
+/// File name of the lockfile the analyzer would read for the project at `project_root`
+pub(crate) fn node_lockfile_name(project_root: &Path) -> Option<&'static str> {
+    let manifest = fs::read_to_string(project_root.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .unwrap_or(Value::Null);
+    match detect_node_lockfile(project_root, &manifest)? {
+        NodeLockfile::Pnpm => Some("pnpm-lock.yaml"),
+        NodeLockfile::Yarn => Some("yarn.lock"),
+        NodeLockfile::Npm => NPM_LOCKFILES
+            .into_iter()
+            .find(|name| project_root.join(name).exists()),
+    }
+}
+
+/// Packages a lockfile pins: one `(name, version)` per resolved version, so a package installed
+/// at several versions (a nested `node_modules` copy, two majors in a pnpm store) is listed once
+/// for each
+type LockedPackages = BTreeSet<(String, String)>;
+
+/// Every package pinned by the lockfile next to package.json, or `None` when there is none
+fn read_node_lockfile(
+    project_root: &Path,
+    manifest: &Value,
+    no_dev: bool,
+) -> Option<LockedPackages> {
+    match detect_node_lockfile(project_root, manifest)? {
+        NodeLockfile::Pnpm => parse_pnpm_lockfile(project_root, no_dev),
+        NodeLockfile::Yarn => parse_yarn_lockfile(project_root),
+        NodeLockfile::Npm => parse_npm_lockfile(project_root, no_dev),
+    }
+}
+
+/// Every package version a pnpm-lock.yaml pins.
+///
+/// Handles the v5 (`/name/1.0.0`), v6 (`/name@1.0.0`) and v9 (`name@1.0.0`) key formats, dropping
+/// peer-dependency suffixes such as `(react@18.2.0)` or `_react@18.2.0`. Packages resolved from a
+/// local directory are the project's own and are left out. Lockfiles up to v6 flag dev-only
+/// packages, which are skipped when `no_dev` is set; v9 no longer records that.
+fn parse_pnpm_lockfile(project_root: &Path, no_dev: bool) -> Option<LockedPackages> {
     let lockfile_path = project_root.join("pnpm-lock.yaml");
     if !lockfile_path.exists() {
         return None;
@@ -988,33 +1107,173 @@ fn parse_pnpm_lockfile(project_root: &Path) -> Option<HashMap<String, String>> {
 
     log(LogLevel::Info, "Parsing pnpm-lock.yaml");
 
-    if let Ok(content) = fs::read_to_string(&lockfile_path) {
-        let mut deps = HashMap::new();
-
-        for line in content.lines() {
-            if line.trim().starts_with('/') && line.contains(':') {
-                if let Some(pkg_info) = line.trim().strip_prefix('/') {
-                    if let Some(colon_pos) = pkg_info.find(':') {
-                        let pkg_with_version = &pkg_info[..colon_pos];
-                        if let Some((pkg_name, version)) = pkg_with_version.rsplit_once('@') {
-                            deps.insert(pkg_name.to_string(), version.to_string());
-                        }
-                    }
-                }
+    let mut deps = LockedPackages::new();
+    let read = fs::File::open(&lockfile_path).and_then(|file| {
+        read_pnpm_packages(io::BufReader::new(file), |package| {
+            if package.local || (no_dev && package.dev) {
+                return;
+            }
+            let parsed = split_pnpm_package_key(&package.key);
+            let name = package
+                .name
+                .or_else(|| parsed.as_ref().map(|(name, _)| name.clone()));
+            let version = package
+                .version
+                .or_else(|| parsed.map(|(_, version)| version));
+            if let (Some(name), Some(version)) = (name, version) {
+                deps.insert((name, version));
             }
+        })
+    });
+    if let Err(e) = read {
+        record_parse_warning(
+            lockfile_path.display().to_string(),
+            format!("failed to read lockfile: {e}"),
+        );
+        return None;
+    }
+
+    log(
+        LogLevel::Info,
+        &format!("Parsed {} dependencies from pnpm-lock.yaml", deps.len()),
+    );
+    Some(deps)
+}
+
+/// One entry of a pnpm-lock.yaml `packages` section
+#[derive(Debug, Default, PartialEq)]
+struct PnpmPackage {
+    key: String,
+    name: Option<String>,
+    version: Option<String>,
+    dev: bool,
+    /// Resolved from a directory in the project rather than a registry
+    local: bool,
+}
+
+/// Hand every entry of a pnpm-lock.yaml's `packages` section to `visit`, reading a line at a time.
+///
+/// pnpm always writes its lockfile in the same block layout: top-level keys at column 0, package
+/// keys one level in and their fields below those. Following that layout keeps memory flat on
+/// monorepo lockfiles of hundreds of megabytes, where a YAML document tree costs several times
+/// the file size.
+fn read_pnpm_packages(reader: impl BufRead, mut visit: impl FnMut(PnpmPackage)) -> io::Result<()> {
+    let mut in_packages = false;
+    let mut entry_indent = None;
+    let mut field_indent = None;
+    let mut in_resolution = false;
+    let mut current: Option<PnpmPackage> = None;
+
+    for line in reader.lines() {
+        let line = line?;
+        let content = line.trim_end();
+        let trimmed = content.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
         }
+        let indent = content.len() - trimmed.len();
 
-        log(
-            LogLevel::Info,
-            &format!("Parsed {} dependencies from pnpm-lock.yaml", deps.len()),
-        );
-        Some(deps)
+        if indent == 0 {
+            current.take().map(&mut visit);
+            // `packages: {}` has no entries to read
+            in_packages = trimmed == "packages:";
+            entry_indent = None;
+            continue;
+        }
+        if !in_packages {
+            continue;
+        }
+
+        if indent <= *entry_indent.get_or_insert(indent) {
+            current.take().map(&mut visit);
+            let key = match trimmed.strip_suffix(':') {
+                Some(key) => key,
+                None => trimmed.split_once(": ").map_or(trimmed, |(key, _)| key),
+            };
+            current = Some(PnpmPackage {
+                key: yaml_scalar(key),
+                ..Default::default()
+            });
+            field_indent = None;
+            in_resolution = false;
+            continue;
+        }
+        let Some(package) = current.as_mut() else {
+            continue;
+        };
+
+        let (key, value) = trimmed
+            .split_once(':')
+            .map_or((trimmed, ""), |(key, value)| (key.trim(), value.trim()));
+        // Nested lines belong to a field: a dependency list, or a resolution in block style
+        if indent > *field_indent.get_or_insert(indent) {
+            package.local |= in_resolution && key == "directory";
+            continue;
+        }
+        in_resolution = key == "resolution";
+        match key {
+            "resolution" => package.local = flow_mapping_has_key(value, "directory"),
+            "dev" => package.dev = value == "true",
+            "name" if !value.is_empty() => package.name = Some(yaml_scalar(value)),
+            "version" if !value.is_empty() => package.version = Some(yaml_scalar(value)),
+            _ => {}
+        }
+    }
+    current.take().map(&mut visit);
+    Ok(())
+}
+
+/// A plain or quoted YAML scalar as a string
+fn yaml_scalar(value: &str) -> String {
+    let value = value.trim();
+    if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        inner.replace("''", "'")
+    } else if let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        inner.replace("\\\"", "\"").replace("\\\\", "\\")
     } else {
-        None
+        value.to_string()
     }
 }
 
-fn parse_yarn_lockfile(project_root: &Path) -> Option<HashMap<String, String>> {
+/// Whether a flow mapping such as `{directory: packages/ui, type: directory}` has `key`
+fn flow_mapping_has_key(value: &str, key: &str) -> bool {
+    value
+        .strip_prefix('{')
+        .and_then(|v| v.strip_suffix('}'))
+        .is_some_and(|fields| {
+            fields
+                .split(',')
+                .any(|field| field.split(':').next().map(str::trim) == Some(key))
+        })
+}
+
+/// Split a pnpm `packages` key into its package name and version
+fn split_pnpm_package_key(key: &str) -> Option<(String, String)> {
+    let key = key.strip_prefix('/').unwrap_or(key);
+    // Peer-dependency suffixes: `(peer@1.0.0)` since v6, `_peer@1.0.0` before
+    let key = key.split('(').next().unwrap_or(key);
+
+    let split_at = key.get(1..)?.find('@').map(|at| at + 1);
+    let (name, version) = match split_at {
+        // A package name holds at most one slash, after its scope; more means a v5 key
+        Some(at) if key[..at].matches('/').count() <= usize::from(key.starts_with('@')) => {
+            (&key[..at], &key[at + 1..])
+        }
+        // v5 keys separate the version with a slash: `/@scope/name/1.0.0_peer@2.0.0`
+        _ => key.split('_').next()?.rsplit_once('/')?,
+    };
+    let version = version.split('_').next().unwrap_or(version);
+
+    (!name.is_empty() && !version.is_empty()).then(|| (name.to_string(), version.to_string()))
+}
+
+/// Every package version a yarn.lock pins.
+///
+/// Reads both the classic v1 format (`version "1.0.0"`) and the YAML one written by yarn berry
+/// (`version: 1.0.0`). An entry header lists every range that resolved to it; a package locked at
+/// several versions has an entry, and a row, for each. Workspace, link and portal entries are the
+/// project's own and are left out. yarn.lock does not mark dev-only packages.
+fn parse_yarn_lockfile(project_root: &Path) -> Option<LockedPackages> {
     let lockfile_path = project_root.join("yarn.lock");
     if !lockfile_path.exists() {
         return None;
@@ -1022,80 +1281,271 @@ fn parse_yarn_lockfile(project_root: &Path) -> Option<HashMap<String, String>> {
 
     log(LogLevel::Info, "Parsing yarn.lock");
 
-    if let Ok(content) = fs::read_to_string(&lockfile_path) {
-        let mut deps = HashMap::new();
-        let mut current_package = None;
-
-        for line in content.lines() {
-            let trimmed = line.trim();
+    let mut deps = LockedPackages::new();
 
-            if !trimmed.is_empty()
-                && !trimmed.starts_with(' ')
-                && trimmed.contains('@')
-                && trimmed.ends_with(':')
-            {
-                let package_line = trimmed.trim_end_matches(':');
-                if let Some((name, _range)) = package_line.split_once('@') {
-                    current_package = Some(name.trim_matches('"').to_string());
-                }
+    let read = fs::File::open(&lockfile_path).and_then(|file| {
+        read_yarn_entries(io::BufReader::new(file), |entry| {
+            if entry.soft {
+                return;
             }
-
-            if let Some(version_line) = trimmed.strip_prefix("version ") {
-                if let Some(ref pkg_name) = current_package {
-                    let version = version_line.trim_matches('"');
-                    deps.insert(pkg_name.clone(), version.to_string());
-                    current_package = None;
-                }
+            if let (Some(name), Some(version)) = (entry.name, entry.version) {
+                deps.insert((name, version));
             }
-        }
-
-        log(
-            LogLevel::Info,
-            &format!("Parsed {} dependencies from yarn.lock", deps.len()),
+        })
+    });
+    if let Err(e) = read {
+        record_parse_warning(
+            lockfile_path.display().to_string(),
+            format!("failed to read lockfile: {e}"),
         );
-        Some(deps)
-    } else {
-        None
+        return None;
     }
+
+    log(
+        LogLevel::Info,
+        &format!("Parsed {} dependencies from yarn.lock", deps.len()),
+    );
+    Some(deps)
 }
 
-fn parse_npm_lockfile(project_root: &Path) -> Option<HashMap<String, String>> {
-    let lockfile_path = project_root.join("package-lock.json");
-    if !lockfile_path.exists() {
-        return None;
+/// One resolved package in a yarn.lock
+#[derive(Debug, Default)]
+struct YarnEntry {
+    name: Option<String>,
+    version: Option<String>,
+    soft: bool,
+}
+
+/// Hand every entry of a yarn.lock to `visit`, reading a line at a time so berry lockfiles of
+/// monorepos never have to fit in memory whole
+fn read_yarn_entries(reader: impl BufRead, mut visit: impl FnMut(YarnEntry)) -> io::Result<()> {
+    let mut current: Option<YarnEntry> = None;
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        if !line.starts_with(' ') {
+            current.take().map(&mut visit);
+            let Some(header) = line.strip_suffix(':') else {
+                continue;
+            };
+            let descriptors: Vec<String> = header
+                .split(',')
+                .map(|d| d.trim().trim_matches('"').to_string())
+                .filter(|d| !d.is_empty())
+                .collect();
+            if descriptors.iter().any(|d| d == "__metadata") {
+                continue;
+            }
+            let name = descriptors.first().and_then(|d| {
+                d.get(1..)
+                    .and_then(|rest| rest.find('@'))
+                    .map(|at| d[..=at].to_string())
+            });
+            let local = descriptors.iter().any(|d| {
+                ["@workspace:", "@link:", "@portal:", "@file:"]
+                    .iter()
+                    .any(|protocol| d.contains(protocol))
+            });
+            current = Some(YarnEntry {
+                name,
+                soft: local,
+                ..Default::default()
+            });
+            continue;
+        }
+
+        // Only the entry's own fields, not those of its nested dependency lists
+        let Some(field) = line.strip_prefix("  ").filter(|f| !f.starts_with(' ')) else {
+            continue;
+        };
+        let Some(entry) = current.as_mut() else {
+            continue;
+        };
+        let (key, value) = match field.split_once(':') {
+            Some((key, value)) if !key.contains(' ') => (key, value.trim()),
+            _ => field.split_once(' ').unwrap_or((field, "")),
+        };
+        let value = value.trim().trim_matches('"');
+        match key {
+            "version" if !value.is_empty() => entry.version = Some(value.to_string()),
+            "linkType" if value == "soft" => entry.soft = true,
+            _ => {}
+        }
     }
+    current.take().map(&mut visit);
+    Ok(())
+}
 
-    log(LogLevel::Info, "Parsing package-lock.json");
+/// npm lockfiles, in the order npm itself prefers them when both are present
+const NPM_LOCKFILES: [&str; 2] = ["npm-shrinkwrap.json", "package-lock.json"];
 
-    if let Ok(content) = fs::read_to_string(&lockfile_path) {
-        if let Ok(json) = serde_json::from_str::<Value>(&content) {
-            let mut deps = HashMap::new();
+/// Every package version an npm lockfile pins.
+///
+/// Lockfile v2/v3 list the installed tree under `packages`, keyed by install path
+/// (`node_modules/a/node_modules/b`); v1 nests `dependencies`. Nested copies count as well as the
+/// hoisted one, so a package installed at several versions is listed once for each. Workspace
+/// members and their links are the project's own packages and are left out, as are dev-only
+/// packages when `no_dev` is set.
+fn parse_npm_lockfile(project_root: &Path, no_dev: bool) -> Option<LockedPackages> {
+    let (file_name, lockfile_path) = NPM_LOCKFILES
+        .iter()
+        .map(|name| (*name, project_root.join(name)))
+        .find(|(_, path)| path.exists())?;
+
+    log(LogLevel::Info, &format!("Parsing {file_name}"));
+
+    let json = match fs::read_to_string(&lockfile_path)
+        .map_err(|e| format!("failed to read {file_name}: {e}"))
+        .and_then(|content| {
+            serde_json::from_str::<Value>(&content).map_err(|e| format!("invalid {file_name}: {e}"))
+        }) {
+        Ok(json) => json,
+        Err(reason) => {
+            record_parse_warning(lockfile_path.display().to_string(), reason);
+            return None;
+        }
+    };
 
-            if let Some(packages) = json.get("packages").and_then(|p| p.as_object()) {
-                for (path, info) in packages {
-                    if !path.is_empty() && !path.starts_with("node_modules/") {
-                        continue;
-                    }
+    let is_dev = |info: &Value| info.get("dev").and_then(Value::as_bool).unwrap_or(false);
+    let mut deps = LockedPackages::new();
 
-                    if let Some(name) = info.get("name").and_then(|n| n.as_str()) {
-                        if let Some(version) = info.get("version").and_then(|v| v.as_str()) {
-                            deps.insert(name.to_string(), version.to_string());
-                        }
-                    }
+    if let Some(packages) = json.get("packages").and_then(|p| p.as_object()) {
+        for (path, info) in packages {
+            if !path.contains("node_modules/") {
+                // The root package ("") and workspace member directories
+                continue;
+            }
+            if info.get("link").and_then(Value::as_bool).unwrap_or(false)
+                || (no_dev && is_dev(info))
+            {
+                continue;
+            }
+            let Some(version) = info.get("version").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let name = info
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or_else(|| path.rsplit("node_modules/").next().unwrap_or(path));
+            deps.insert((name.to_string(), version.to_string()));
+        }
+    } else if let Some(dependencies) = json.get("dependencies").and_then(|d| d.as_object()) {
+        // Lockfile v1
+        let mut pending = vec![dependencies];
+        while let Some(dependencies) = pending.pop() {
+            for (name, info) in dependencies {
+                if no_dev && is_dev(info) {
+                    continue;
+                }
+                if let Some(version) = info.get("version").and_then(|v| v.as_str()) {
+                    deps.insert((name.clone(), version.to_string()));
+                }
+                if let Some(nested) = info.get("dependencies").and_then(|d| d.as_object()) {
+                    pending.push(nested);
                 }
             }
+        }
+    }
 
-            log(
-                LogLevel::Info,
-                &format!("Parsed {} dependencies from package-lock.json", deps.len()),
-            );
-            Some(deps)
-        } else {
-            None
+    log(
+        LogLevel::Info,
+        &format!("Parsed {} dependencies from {file_name}", deps.len()),
+    );
+    Some(deps)
+}
+
+/// Map each package an npm lockfile (v2/v3) installs below the root's direct dependencies to
+/// the direct dependencies whose trees pull it in, following Node's `node_modules` lookup.
+/// Direct dependencies get no entry; v1 lockfiles and other package managers give an empty map.
+fn npm_lockfile_dependents(project_root: &Path, no_dev: bool) -> HashMap<String, BTreeSet<String>> {
+    let mut dependents: HashMap<String, BTreeSet<String>> = HashMap::new();
+    let Some(json) = NPM_LOCKFILES
+        .iter()
+        .map(|name| project_root.join(name))
+        .find(|path| path.exists())
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+    else {
+        return dependents;
+    };
+    let Some(packages) = json.get("packages").and_then(Value::as_object) else {
+        return dependents;
+    };
+
+    let dependency_names = |info: &Value, with_dev: bool| -> Vec<String> {
+        let mut fields = vec!["dependencies", "optionalDependencies"];
+        if with_dev {
+            fields.push("devDependencies");
+        }
+        fields
+            .into_iter()
+            .filter_map(|field| info.get(field).and_then(Value::as_object))
+            .flat_map(|deps| deps.keys().cloned())
+            .collect()
+    };
+    // Where `name` required from the package installed at `from` resolves to
+    let resolve = |from: &str, name: &str| -> Option<String> {
+        let mut base = from.to_string();
+        loop {
+            let candidate = if base.is_empty() {
+                format!("node_modules/{name}")
+            } else {
+                format!("{base}/node_modules/{name}")
+            };
+            if packages.contains_key(&candidate) {
+                return Some(candidate);
+            }
+            if base.is_empty() {
+                return None;
+            }
+            base = base
+                .rfind("node_modules/")
+                .map(|index| base[..index].trim_end_matches('/').to_string())
+                .unwrap_or_default();
+        }
+    };
+    let package_name = |path: &str| -> String {
+        packages[path]
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or_else(|| path.rsplit("node_modules/").next().unwrap_or(path))
+            .to_string()
+    };
+
+    let Some(root) = packages.get("") else {
+        return dependents;
+    };
+    let direct: BTreeSet<String> = dependency_names(root, !no_dev).into_iter().collect();
+    for direct_name in &direct {
+        let Some(start) = resolve("", direct_name) else {
+            continue;
+        };
+        let mut visited: HashSet<String> = HashSet::from([start.clone()]);
+        let mut queue: VecDeque<String> = VecDeque::from([start]);
+        while let Some(path) = queue.pop_front() {
+            for name in dependency_names(&packages[&path], false) {
+                let Some(dep_path) = resolve(&path, &name) else {
+                    continue;
+                };
+                if !visited.insert(dep_path.clone()) {
+                    continue;
+                }
+                let dep_name = package_name(&dep_path);
+                if !direct.contains(&dep_name) {
+                    dependents
+                        .entry(dep_name)
+                        .or_default()
+                        .insert(direct_name.clone());
+                }
+                queue.push_back(dep_path);
+            }
         }
-    } else {
-        None
     }
+    dependents
 }
 
 // =============================================================================
@@ -1447,23 +1897,34 @@ fn get_license_for_package(
     #[cfg(not(windows))]
     const NPM: &str = "npm";
 
-    let mut result = get_license_from_package_json(project_root, name, version);
-
-    if result.is_none() && !no_local {
-        result = get_license_from_local_license_file(project_root, name);
-    }
+    let local = |name: &str, version: &str| {
+        get_license_from_package_json(project_root, name, version)
+            .or_else(|| {
+                if no_local {
+                    None
+                } else {
+                    get_license_from_local_license_file(project_root, name)
+                }
+            })
+            .or_else(|| get_license_from_pnpm_metadata(project_root, name, version))
+    };
+    let registry = |name: &str, version: &str| {
+        get_license_from_npm_view(NPM, name, version)
+            .or_else(|| get_license_from_npm_registry_api(name, version))
+    };
 
-    result
-        .or_else(|| get_license_from_pnpm_metadata(project_root, name, version))
-        .or_else(|| get_license_from_npm_view(NPM, name, version))
-        .or_else(|| get_license_from_npm_registry_api(name, version))
-        .unwrap_or_else(|| "Unknown (failed to retrieve)".to_string())
+    resolve_license(
+        &LicenseQuery::new("node", name, version),
+        Some(&local),
+        Some(&registry),
+    )
+    .unwrap_or_else(|| "Unknown (failed to retrieve)".to_string())
 }
 
 fn get_license_from_package_json(
     project_root: &Path,
     package_name: &str,
-    _version: &str,
+    version: &str,
 ) -> Option<String> {
     let possible_paths = vec![
         if package_name.starts_with('@') {
@@ -1514,33 +1975,23 @@ fn get_license_from_package_json(
         },
     ];
 
+    // Only the hoisted copy is looked at, so a pinned version installed elsewhere in the tree
+    // must not borrow another version's license
+    let pinned = semver::Version::parse(version).is_ok();
+
     for package_path in possible_paths.into_iter().flatten() {
         if let Ok(content) = fs::read_to_string(&package_path) {
             if let Ok(json) = serde_json::from_str::<Value>(&content) {
-                if let Some(license) = json.get("license").and_then(|l| l.as_str()) {
-                    if !license.is_empty() && license != "UNLICENSED" {
-                        log(
-                            LogLevel::Info,
-                            &format!("Found license in package.json for {package_name}: {license}"),
-                        );
-                        return Some(license.to_string());
-                    }
+                let installed = json.get("version").and_then(Value::as_str);
+                if pinned && installed.is_some_and(|installed| installed != version) {
+                    continue;
                 }
-
-                if let Some(licenses) = json.get("licenses").and_then(|l| l.as_array()) {
-                    if let Some(first_license) = licenses.first() {
-                        if let Some(license_type) =
-                            first_license.get("type").and_then(|t| t.as_str())
-                        {
-                            log(
-                                LogLevel::Info,
-                                &format!(
-                                    "Found license in licenses array for {package_name}: {license_type}"
-                                ),
-                            );
-                            return Some(license_type.to_string());
-                        }
-                    }
+                if let Some(license) = license_from_manifest(&json) {
+                    log(
+                        LogLevel::Info,
+                        &format!("Found license in package.json for {package_name}: {license}"),
+                    );
+                    return Some(license);
                 }
             }
         }
@@ -1566,6 +2017,7 @@ fn get_license_from_npm_view(npm_cmd: &str, package_name: &str, version: &str) -
         .arg("view")
         .arg(&package_spec)
         .arg("license")
+        .arg("licenses")
         .arg("--json")
         .output()
         .ok()?;
@@ -1577,9 +2029,17 @@ fn get_license_from_npm_view(npm_cmd: &str, package_name: &str, version: &str) -
     let output_str = String::from_utf8_lossy(&output.stdout);
 
     if let Ok(json) = serde_json::from_str::<Value>(&output_str) {
-        if let Some(license) = json.as_str() {
-            return Some(license.to_string());
-        }
+        // One field prints its bare value; both print an object keyed by field name
+        let license = match json {
+            Value::String(license) => Some(license),
+            Value::Object(ref fields)
+                if fields.contains_key("license") || fields.contains_key("licenses") =>
+            {
+                license_from_manifest(&json)
+            }
+            ref legacy => legacy_license_expression(legacy),
+        };
+        return license.filter(|license| !license.is_empty());
     }
 
     let license = output_str.trim().trim_matches('"');
@@ -1609,30 +2069,19 @@ fn get_license_from_npm_registry_api(package_name: &str, version: &str) -> Optio
             format!("https://registry.npmjs.org/{package_name}/{ver}")
         };
 
-        if let Ok(response) = reqwest::blocking::get(&url) {
+        if let Ok(response) = crate::cancel::http_get(&url) {
             if response.status().is_success() {
                 if let Ok(json) = response.json::<Value>() {
-                    let license_paths = [
-                        vec!["license"],
-                        vec!["licenses", "0", "type"],
-                        vec!["licenses", "0"],
-                        vec!["latest", "license"],
-                    ];
-
-                    for path in &license_paths {
-                        if let Some(license_value) = get_nested_json_value(&json, path) {
-                            if let Some(license_str) = license_value.as_str() {
-                                if !license_str.is_empty() && license_str != "UNLICENSED" {
-                                    log(
-                                        LogLevel::Info,
-                                        &format!(
-                                            "Found license via registry API for {package_name}: {license_str}"
-                                        ),
-                                    );
-                                    return Some(license_str.to_string());
-                                }
-                            }
-                        }
+                    let license = license_from_manifest(&json)
+                        .or_else(|| json.get("latest").and_then(license_from_manifest));
+                    if let Some(license) = license {
+                        log(
+                            LogLevel::Info,
+                            &format!(
+                                "Found license via registry API for {package_name}: {license}"
+                            ),
+                        );
+                        return Some(license);
                     }
                 }
             }
@@ -1665,10 +2114,8 @@ fn get_license_from_pnpm_metadata(
                         .join("package.json");
                     if let Ok(content) = fs::read_to_string(&package_json_path) {
                         if let Ok(json) = serde_json::from_str::<Value>(&content) {
-                            if let Some(license) = json.get("license").and_then(|l| l.as_str()) {
-                                if !license.is_empty() && license != "UNLICENSED" {
-                                    return Some(license.to_string());
-                                }
+                            if let Some(license) = license_from_manifest(&json) {
+                                return Some(license);
                             }
                         }
                     }
@@ -1724,12 +2171,80 @@ fn get_license_from_local_license_file(project_root: &Path, package_name: &str)
     None
 }
 
-fn get_nested_json_value<'a>(json: &'a Value, path: &[&str]) -> Option<&'a Value> {
-    let mut current = json;
-    for key in path {
-        current = current.get(key)?;
+/// The license a package.json (or a registry version document) declares
+///
+/// Besides the SPDX `license` string, this accepts the deprecated forms older packages still
+/// publish: a `license: {type, url}` object and a `licenses` array of such objects or strings.
+fn license_from_manifest(json: &Value) -> Option<String> {
+    match json.get("license") {
+        Some(Value::String(license)) if !license.trim().is_empty() => {
+            return Some(license.trim().to_string());
+        }
+        Some(legacy @ (Value::Object(_) | Value::Array(_))) => {
+            if let Some(license) = legacy_license_expression(legacy) {
+                return Some(license);
+            }
+        }
+        _ => {}
+    }
+    json.get("licenses").and_then(legacy_license_expression)
+}
+
+/// SPDX expression for a legacy `{type, url}` object or a `licenses` array
+///
+/// Several entries meant the package could be used under any of them, so they become an `OR`
+/// expression. Free-form names such as `Apache License, Version 2.0` are mapped to SPDX ids.
+fn legacy_license_expression(value: &Value) -> Option<String> {
+    let entries = match value {
+        Value::Array(entries) => entries.iter().collect(),
+        entry => vec![entry],
+    };
+
+    let mut licenses: Vec<String> = Vec::new();
+    for entry in entries {
+        let license = match entry {
+            Value::String(license) => license.as_str(),
+            Value::Object(fields) => match fields
+                .get("type")
+                .or_else(|| fields.get("name"))
+                .and_then(Value::as_str)
+            {
+                Some(license) => license,
+                None => continue,
+            },
+            _ => continue,
+        };
+        let license = license.trim();
+        if license.is_empty() {
+            continue;
+        }
+        let license = if license.contains(char::is_whitespace) && !spdx::is_compound(license) {
+            normalize_license_id(license)
+        } else {
+            license.to_string()
+        };
+        if !licenses.contains(&license) {
+            licenses.push(license);
+        }
+    }
+
+    match licenses.len() {
+        0 => None,
+        1 => licenses.pop(),
+        _ => Some(
+            licenses
+                .iter()
+                .map(|license| {
+                    if spdx::is_compound(license) {
+                        format!("({license})")
+                    } else {
+                        license.clone()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" OR "),
+        ),
     }
-    Some(current)
 }
 
 fn clean_version_string(version: &str) -> String {
@@ -1783,18 +2298,8 @@ fn analyze_pnpm_project_comprehensive(
 ) -> HashMap<String, String> {
     let mut all_deps = HashMap::new();
 
-    log(
-        LogLevel::Info,
-        "Method 1: Comprehensive pnpm-lock.yaml parsing",
-    );
-    if let Ok(lockfile_deps) = parse_pnpm_lockfile_comprehensive(project_root) {
-        log(
-            LogLevel::Info,
-            &format!(
-                "pnpm-lock.yaml parsing found {} dependencies",
-                lockfile_deps.len()
-            ),
-        );
+    log(LogLevel::Info, "Method 1: pnpm-lock.yaml packages");
+    if let Some(lockfile_deps) = parse_pnpm_lockfile(project_root, false) {
         all_deps.extend(lockfile_deps);
     }
 
@@ -1949,65 +2454,6 @@ fn analyze_pnpm_project_comprehensive(
     all_deps
 }
 
-fn parse_pnpm_lockfile_comprehensive(
-    project_root: &Path,
-) -> Result<HashMap<String, String>, String> {
-    let lockfile_path = project_root.join("pnpm-lock.yaml");
-    if !lockfile_path.exists() {
-        return Err("pnpm-lock.yaml not found".to_string());
-    }
-
-    log(LogLevel::Info, "Parsing pnpm-lock.yaml comprehensively");
-
-    let content = fs::read_to_string(&lockfile_path)
-        .map_err(|e| format!("Failed to read pnpm-lock.yaml: {e}"))?;
-
-    let mut deps = HashMap::new();
-    let mut in_packages_section = false;
-
-    for line in content.lines() {
-        let trimmed = line.trim();
-
-        if trimmed == "packages:" {
-            in_packages_section = true;
-            continue;
-        }
-
-        if !trimmed.is_empty()
-            && !trimmed.starts_with(' ')
-            && trimmed.ends_with(':')
-            && in_packages_section
-            && trimmed != "packages:"
-        {
-            in_packages_section = false;
-            continue;
-        }
-
-        if in_packages_section && trimmed.starts_with('/') && trimmed.contains(':') {
-            if let Some(pkg_info) = trimmed.strip_prefix('/').and_then(|s| s.strip_suffix(':')) {
-                if let Some((pkg_name, version)) = parse_pnpm_package_entry(pkg_info) {
-                    deps.insert(pkg_name, version);
-                }
-            }
-        }
-
-        if trimmed.contains('@') && trimmed.contains(':') && !trimmed.starts_with('#') {
-            if let Some((pkg_name, version)) = extract_package_from_lockfile_line(trimmed) {
-                deps.insert(pkg_name, version);
-            }
-        }
-    }
-
-    log(
-        LogLevel::Info,
-        &format!(
-            "Comprehensive pnpm-lock.yaml parsing found {} dependencies",
-            deps.len()
-        ),
-    );
-    Ok(deps)
-}
-
 fn parse_pnpm_package_entry(pkg_info: &str) -> Option<(String, String)> {
     let clean_info = pkg_info.split('(').next().unwrap_or(pkg_info);
     let clean_info = clean_info.split('_').next().unwrap_or(clean_info);
@@ -2028,35 +2474,6 @@ fn parse_pnpm_package_entry(pkg_info: &str) -> Option<(String, String)> {
     None
 }
 
-fn extract_package_from_lockfile_line(line: &str) -> Option<(String, String)> {
-    if line.contains("resolution:") {
-        return None;
-    }
-
-    if let Some(colon_pos) = line.find(':') {
-        let name_part = line[..colon_pos].trim();
-        let version_part = line[colon_pos + 1..].trim();
-
-        if name_part.is_empty() || version_part.is_empty() {
-            return None;
-        }
-
-        if name_part.contains('/') && !name_part.starts_with('@') {
-            return None;
-        }
-
-        if version_part
-            .chars()
-            .next()
-            .is_some_and(|c| c.is_ascii_digit())
-        {
-            return Some((name_part.to_string(), version_part.to_string()));
-        }
-    }
-
-    None
-}
-
 fn try_pnpm_list_comprehensive(project_root: &Path) -> Result<HashMap<String, String>, String> {
     log(
         LogLevel::Info,
@@ -2392,13 +2809,15 @@ fn parse_pnpm_lockfile_enhanced(project_root: &Path) -> Result<HashMap<String, S
 
     log(LogLevel::Info, "Enhanced parsing of pnpm-lock.yaml");
 
-    let content = fs::read_to_string(&lockfile_path)
+    let file = fs::File::open(&lockfile_path)
         .map_err(|e| format!("Failed to read pnpm-lock.yaml: {e}"))?;
 
     let mut deps = HashMap::new();
     let mut current_section = None;
 
-    for line in content.lines() {
+    // A line at a time, like `read_pnpm_packages`, so large lockfiles aren't held in memory
+    for line in io::BufReader::new(file).lines() {
+        let line = line.map_err(|e| format!("Failed to read pnpm-lock.yaml: {e}"))?;
         let trimmed = line.trim();
 
         if trimmed.ends_with(':') && !trimmed.starts_with(' ') {
@@ -2636,6 +3055,76 @@ mod tests {
         assert_eq!(result, Some("Apache-2.0".to_string()));
     }
 
+    #[test]
+    fn test_license_from_manifest_legacy_forms() {
+        let spdx = serde_json::json!({ "license": "MIT" });
+        assert_eq!(license_from_manifest(&spdx), Some("MIT".to_string()));
+
+        let object = serde_json::json!({
+            "license": { "type": "ISC", "url": "https://opensource.org/licenses/ISC" }
+        });
+        assert_eq!(license_from_manifest(&object), Some("ISC".to_string()));
+
+        let array = serde_json::json!({
+            "licenses": [
+                { "type": "MIT", "url": "https://example.com/MIT" },
+                { "type": "Apache License, Version 2.0", "url": "https://example.com/Apache" }
+            ]
+        });
+        assert_eq!(
+            license_from_manifest(&array),
+            Some("MIT OR Apache-2.0".to_string())
+        );
+
+        let single = serde_json::json!({ "licenses": { "type": "BSD-3-Clause" } });
+        assert_eq!(
+            license_from_manifest(&single),
+            Some("BSD-3-Clause".to_string())
+        );
+
+        let strings = serde_json::json!({ "licenses": ["MIT", "MIT", "GPL-2.0-or-later"] });
+        assert_eq!(
+            license_from_manifest(&strings),
+            Some("MIT OR GPL-2.0-or-later".to_string())
+        );
+
+        let empty = serde_json::json!({ "license": "", "licenses": [{ "url": "x" }] });
+        assert_eq!(license_from_manifest(&empty), None);
+    }
+
+    #[test]
+    fn test_get_license_from_package_json_legacy_licenses_array() {
+        let temp_dir = TempDir::new().unwrap();
+        let package_dir = temp_dir.path().join("node_modules").join("legacy-pkg");
+        fs::create_dir_all(&package_dir).unwrap();
+        fs::write(
+            package_dir.join("package.json"),
+            r#"{"name": "legacy-pkg", "licenses": [{"type": "MIT License", "url": "x"}, {"type": "GPL-2.0", "url": "y"}]}"#,
+        )
+        .unwrap();
+
+        let result = get_license_from_package_json(temp_dir.path(), "legacy-pkg", "1.0.0");
+        assert_eq!(result, Some("MIT OR GPL-2.0".to_string()));
+    }
+
+    #[test]
+    fn test_get_license_from_package_json_skips_other_installed_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let package_dir = temp_dir.path().join("node_modules").join("debug");
+        fs::create_dir_all(&package_dir).unwrap();
+        fs::write(
+            package_dir.join("package.json"),
+            r#"{"name": "debug", "version": "2.6.9", "license": "MIT"}"#,
+        )
+        .unwrap();
+
+        let license = |version| get_license_from_package_json(temp_dir.path(), "debug", version);
+        assert_eq!(license("2.6.9"), Some("MIT".to_string()));
+        assert_eq!(license("^2.6.0"), Some("MIT".to_string()));
+        // A nested copy at another version isn't the hoisted one
+        assert_eq!(license("4.3.4"), None);
+    }
+
     #[test]
     fn test_get_license_from_local_license_file_not_found() {
         let temp_dir = TempDir::new().unwrap();
@@ -2706,7 +3195,7 @@ mod tests {
         .unwrap();
 
         let attribution =
-            build_npm_workspace_attribution(root, root.join("package.json").to_str().unwrap());
+            build_npm_workspace_attribution(root, &read_manifest(&root.join("package.json")));
 
         let lodash = attribution.get("lodash").expect("lodash attributed");
         assert!(lodash.contains("monorepo-root"));
@@ -2748,7 +3237,7 @@ mod tests {
         .unwrap();
 
         let attribution =
-            build_npm_workspace_attribution(root, root.join("package.json").to_str().unwrap());
+            build_npm_workspace_attribution(root, &read_manifest(&root.join("package.json")));
         let vitest = attribution.get("vitest").expect("vitest attributed");
         assert_eq!(vitest.iter().next().unwrap(), "web");
     }
@@ -2764,11 +3253,328 @@ mod tests {
 
         let attribution = build_npm_workspace_attribution(
             temp.path(),
-            temp.path().join("package.json").to_str().unwrap(),
+            &read_manifest(&temp.path().join("package.json")),
         );
         assert!(attribution.is_empty());
     }
 
+    /// Root manifest as the analyzer parses it
+    fn read_manifest(path: &Path) -> Value {
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    /// Versions a lockfile pins for one package, in order
+    fn locked_versions<'a>(deps: &'a LockedPackages, name: &str) -> Vec<&'a str> {
+        deps.iter()
+            .filter(|(locked, _)| locked == name)
+            .map(|(_, version)| version.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_npm_lockfile_v3_transitive_tree() {
+        let temp = TempDir::new().unwrap();
+        let lockfile = r#"{
+  "name": "app",
+  "lockfileVersion": 3,
+  "packages": {
+    "": {"name": "app", "dependencies": {"express": "^4.18.0"}, "devDependencies": {"jest": "^29.0.0"}},
+    "node_modules/express": {"version": "4.18.2", "license": "MIT"},
+    "node_modules/debug": {"version": "2.6.9"},
+    "node_modules/send/node_modules/debug": {"version": "4.3.4"},
+    "node_modules/@types/node": {"version": "20.1.0"},
+    "node_modules/jest": {"version": "29.7.0", "dev": true},
+    "node_modules/web": {"resolved": "packages/web", "link": true},
+    "packages/web": {"name": "web", "version": "0.1.0"}
+  }
+}"#;
+        fs::write(temp.path().join("package-lock.json"), lockfile).unwrap();
+
+        let deps = parse_npm_lockfile(temp.path(), false).unwrap();
+        assert_eq!(locked_versions(&deps, "express"), vec!["4.18.2"]);
+        assert_eq!(locked_versions(&deps, "@types/node"), vec!["20.1.0"]);
+        // The nested copy is listed alongside the hoisted one
+        assert_eq!(locked_versions(&deps, "debug"), vec!["2.6.9", "4.3.4"]);
+        assert_eq!(locked_versions(&deps, "jest"), vec!["29.7.0"]);
+        assert!(locked_versions(&deps, "web").is_empty());
+        assert!(locked_versions(&deps, "app").is_empty());
+
+        let deps = parse_npm_lockfile(temp.path(), true).unwrap();
+        assert!(locked_versions(&deps, "jest").is_empty());
+        assert_eq!(deps.len(), 4);
+    }
+
+    #[test]
+    fn test_npm_lockfile_dependents() {
+        let temp = TempDir::new().unwrap();
+        let lockfile = r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "": {"name": "app", "dependencies": {"express": "^4.18.0", "debug": "^2.6.0"}, "devDependencies": {"jest": "^29.0.0"}},
+    "node_modules/express": {"version": "4.18.2", "dependencies": {"send": "0.18.0", "debug": "2.6.9"}},
+    "node_modules/send": {"version": "0.18.0", "dependencies": {"debug": "4.3.4", "@scope/ms": "2.1.3"}},
+    "node_modules/send/node_modules/debug": {"version": "4.3.4", "dependencies": {"@scope/ms": "2.1.3"}},
+    "node_modules/@scope/ms": {"version": "2.1.3"},
+    "node_modules/debug": {"version": "2.6.9"},
+    "node_modules/jest": {"version": "29.7.0", "dev": true, "dependencies": {"@scope/ms": "2.1.3"}}
+  }
+}"#;
+        fs::write(temp.path().join("package-lock.json"), lockfile).unwrap();
+
+        let dependents = npm_lockfile_dependents(temp.path(), false);
+        let direct = |name: &str| -> Vec<&str> {
+            dependents
+                .get(name)
+                .map(|set| set.iter().map(String::as_str).collect())
+                .unwrap_or_default()
+        };
+        assert_eq!(direct("send"), vec!["express"]);
+        assert_eq!(direct("@scope/ms"), vec!["express", "jest"]);
+        // Direct dependencies are never attributed to another one
+        assert!(direct("debug").is_empty());
+
+        let dependents = npm_lockfile_dependents(temp.path(), true);
+        assert_eq!(
+            dependents.get("@scope/ms").map(BTreeSet::len),
+            Some(1),
+            "dev-only roots are left out with no_dev"
+        );
+    }
+
+    #[test]
+    fn test_parse_npm_lockfile_v1_and_shrinkwrap_preference() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("package-lock.json"),
+            r#"{"lockfileVersion": 3, "packages": {"node_modules/ignored": {"version": "1.0.0"}}}"#,
+        )
+        .unwrap();
+        let shrinkwrap = r#"{
+  "lockfileVersion": 1,
+  "dependencies": {
+    "send": {
+      "version": "0.18.0",
+      "dependencies": {"ms": {"version": "2.1.3"}}
+    },
+    "ms": {"version": "2.0.0"},
+    "mocha": {"version": "10.2.0", "dev": true}
+  }
+}"#;
+        fs::write(temp.path().join("npm-shrinkwrap.json"), shrinkwrap).unwrap();
+
+        let deps = parse_npm_lockfile(temp.path(), true).unwrap();
+        assert_eq!(locked_versions(&deps, "send"), vec!["0.18.0"]);
+        assert_eq!(locked_versions(&deps, "ms"), vec!["2.0.0", "2.1.3"]);
+        assert!(locked_versions(&deps, "mocha").is_empty());
+        assert!(locked_versions(&deps, "ignored").is_empty());
+    }
+
+    #[test]
+    fn test_parse_yarn_lockfile_v1_and_berry() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("package.json"),
+            r#"{"dependencies": {"@babel/core": "^7.20.0", "debug": "^4.3.0"}}"#,
+        )
+        .unwrap();
+        let classic = r#"# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+"@babel/core@^7.0.0", "@babel/core@^7.20.0":
+  version "7.23.2"
+  resolved "https://registry.yarnpkg.com/@babel/core/-/core-7.23.2.tgz"
+  dependencies:
+    debug "^4.1.0"
+
+debug@^2.6.9:
+  version "2.6.9"
+
+debug@^4.1.0, debug@^4.3.0:
+  version "4.3.4"
+"#;
+        fs::write(temp.path().join("yarn.lock"), classic).unwrap();
+
+        let deps = parse_yarn_lockfile(temp.path()).unwrap();
+        assert_eq!(locked_versions(&deps, "@babel/core"), vec!["7.23.2"]);
+        // Both resolved versions of debug are kept
+        assert_eq!(locked_versions(&deps, "debug"), vec!["2.6.9", "4.3.4"]);
+        assert_eq!(deps.len(), 3);
+
+        let berry = r#"# This file is generated by running "yarn install" inside your project.
+
+__metadata:
+  version: 8
+  cacheKey: 10
+
+"@babel/core@npm:^7.20.0":
+  version: 7.23.2
+  resolution: "@babel/core@npm:7.23.2"
+  dependencies:
+    debug: "npm:^4.1.0"
+  languageName: node
+  linkType: hard
+
+"app@workspace:.":
+  version: 0.0.0-use.local
+  resolution: "app@workspace:."
+  languageName: unknown
+  linkType: soft
+
+"debug@npm:^4.1.0, debug@npm:^4.3.0":
+  version: 4.3.4
+  linkType: hard
+"#;
+        fs::write(temp.path().join("yarn.lock"), berry).unwrap();
+
+        let deps = parse_yarn_lockfile(temp.path()).unwrap();
+        assert_eq!(locked_versions(&deps, "@babel/core"), vec!["7.23.2"]);
+        assert_eq!(locked_versions(&deps, "debug"), vec!["4.3.4"]);
+        assert!(locked_versions(&deps, "app").is_empty());
+        assert!(locked_versions(&deps, "__metadata").is_empty());
+    }
+
+    #[test]
+    fn test_parse_pnpm_lockfile_key_formats() {
+        let temp = TempDir::new().unwrap();
+        let v6 = "\
+lockfileVersion: '6.0'
+packages:
+  /@babel/core@7.23.2:
+    resolution: {integrity: sha512-x}
+  /react-dom@18.2.0(react@18.2.0):
+    resolution: {integrity: sha512-y}
+  /jest@29.7.0:
+    resolution: {integrity: sha512-z}
+    dev: true
+  /semver@6.3.1:
+    resolution: {integrity: sha512-a}
+  /semver@7.5.4:
+    resolution: {integrity: sha512-b}
+  file:packages/ui:
+    resolution: {directory: packages/ui, type: directory}
+    name: ui
+    version: 0.1.0
+";
+        fs::write(temp.path().join("pnpm-lock.yaml"), v6).unwrap();
+
+        let deps = parse_pnpm_lockfile(temp.path(), false).unwrap();
+        assert_eq!(locked_versions(&deps, "@babel/core"), vec!["7.23.2"]);
+        assert_eq!(locked_versions(&deps, "react-dom"), vec!["18.2.0"]);
+        assert_eq!(locked_versions(&deps, "jest"), vec!["29.7.0"]);
+        assert_eq!(locked_versions(&deps, "semver"), vec!["6.3.1", "7.5.4"]);
+        assert!(locked_versions(&deps, "ui").is_empty());
+
+        let deps = parse_pnpm_lockfile(temp.path(), true).unwrap();
+        assert!(locked_versions(&deps, "jest").is_empty());
+
+        assert_eq!(
+            split_pnpm_package_key("/@types/node/20.1.0"),
+            Some(("@types/node".to_string(), "20.1.0".to_string()))
+        );
+        assert_eq!(
+            split_pnpm_package_key("/styled-jsx/5.1.1_react@18.2.0"),
+            Some(("styled-jsx".to_string(), "5.1.1".to_string()))
+        );
+        assert_eq!(
+            split_pnpm_package_key("@vue/shared@3.3.4"),
+            Some(("@vue/shared".to_string(), "3.3.4".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_read_pnpm_packages_layouts() {
+        let v9 = "\
+lockfileVersion: '9.0'
+
+importers:
+
+  .:
+    dependencies:
+      react:
+        specifier: ^18.2.0
+        version: 18.2.0
+
+packages:
+
+  '@babel/code-frame@7.22.13':
+    resolution: {integrity: sha512-x}
+    engines: {node: '>=6.9.0'}
+
+  react@18.2.0:
+    resolution: {integrity: sha512-y}
+    engines: {node: '>=0.10.0'}
+
+  ui@file:packages/ui:
+    resolution:
+      directory: packages/ui
+      type: directory
+    name: ui
+    version: '0.1.0'
+
+snapshots:
+
+  react@18.2.0:
+    dependencies:
+      loose-envify: 1.4.0
+";
+        let mut packages = Vec::new();
+        read_pnpm_packages(v9.as_bytes(), |package| packages.push(package)).unwrap();
+        assert_eq!(
+            packages,
+            vec![
+                PnpmPackage {
+                    key: "@babel/code-frame@7.22.13".to_string(),
+                    ..Default::default()
+                },
+                PnpmPackage {
+                    key: "react@18.2.0".to_string(),
+                    ..Default::default()
+                },
+                PnpmPackage {
+                    key: "ui@file:packages/ui".to_string(),
+                    name: Some("ui".to_string()),
+                    version: Some("0.1.0".to_string()),
+                    dev: false,
+                    local: true,
+                },
+            ]
+        );
+
+        let mut count = 0;
+        read_pnpm_packages("lockfileVersion: '9.0'\npackages: {}\n".as_bytes(), |_| {
+            count += 1
+        })
+        .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_detect_node_lockfile_prefers_package_manager_field() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(detect_node_lockfile(temp.path(), &Value::Null), None);
+
+        fs::write(temp.path().join("package-lock.json"), "{}").unwrap();
+        fs::write(temp.path().join("yarn.lock"), "").unwrap();
+        assert_eq!(
+            detect_node_lockfile(temp.path(), &Value::Null),
+            Some(NodeLockfile::Yarn)
+        );
+
+        let manifest = serde_json::json!({"packageManager": "npm@10.2.0"});
+        assert_eq!(
+            detect_node_lockfile(temp.path(), &manifest),
+            Some(NodeLockfile::Npm)
+        );
+
+        // A declared manager without its lockfile falls back to what is present
+        let manifest = serde_json::json!({"packageManager": "pnpm@8.15.0"});
+        assert_eq!(
+            detect_node_lockfile(temp.path(), &manifest),
+            Some(NodeLockfile::Yarn)
+        );
+    }
+
     #[test]
     fn test_parse_pnpm_lockfile_enhanced_strips_quotes_from_scoped_deps() {
         let temp = TempDir::new().unwrap();
@@ -0,0 +1,401 @@
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::config::FeludaConfig;
+use crate::debug::{log, log_error, record_parse_warning, LogLevel};
+use crate::licenses::{
+    detect_license_in_dir, fetch_licenses_from_github, is_license_restrictive,
+    LicenseCompatibility, LicenseInfo,
+};
+use crate::resolver::{resolve_all, resolve_license, LicenseQuery};
+
+#[derive(Debug, Clone)]
+struct PhpDependency {
+    name: String,
+    version: String,
+    /// License declared in `composer.lock`, when it carries one
+    license: Option<String>,
+    repository: Option<String>,
+}
+
+pub fn analyze_php_licenses(file_path: &str, config: &FeludaConfig) -> Vec<LicenseInfo> {
+    log(
+        LogLevel::Info,
+        &format!("Analyzing PHP dependencies from: {file_path}"),
+    );
+
+    let content = match fs::read_to_string(file_path) {
+        Ok(c) => c,
+        Err(e) => {
+            record_parse_warning(file_path, format!("failed to read Composer file: {e}"));
+            return Vec::new();
+        }
+    };
+    let json: Value = match serde_json::from_str(&content) {
+        Ok(json) => json,
+        Err(e) => {
+            record_parse_warning(file_path, format!("invalid Composer JSON: {e}"));
+            return Vec::new();
+        }
+    };
+
+    // `composer.lock` pins the full transitive set and records each package's license, so
+    // the registry is only asked about packages without one. A bare `composer.json` lists
+    // direct, constraint-versioned requirements only.
+    let deps = if file_path.ends_with("composer.lock") {
+        parse_composer_lock(&json, config.no_dev)
+    } else {
+        parse_composer_json(&json, config.no_dev)
+    };
+
+    if deps.is_empty() {
+        log(LogLevel::Warn, "No PHP dependencies found");
+        return Vec::new();
+    }
+
+    log(
+        LogLevel::Info,
+        &format!("Found {} PHP dependencies", deps.len()),
+    );
+
+    let known_licenses = match fetch_licenses_from_github() {
+        Ok(licenses) => licenses,
+        Err(err) => {
+            log_error("Failed to fetch licenses from GitHub", &err);
+            HashMap::new()
+        }
+    };
+    let vendor_dir = Path::new(file_path)
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join("vendor");
+
+    resolve_all(&deps, |dep| {
+        let license = match &dep.license {
+            Some(license) => license.clone(),
+            None => fetch_php_license(dep, &vendor_dir),
+        };
+        let is_restrictive =
+            is_license_restrictive(&Some(license.clone()), &known_licenses, config.strict);
+
+        LicenseInfo {
+            name: dep.name.clone(),
+            version: dep.version.clone(),
+            license: Some(license.clone()),
+            is_restrictive,
+            compatibility: LicenseCompatibility::Unknown,
+            osi_status: crate::licenses::get_osi_status(&license),
+            sub_project: None,
+            found_in: Vec::new(),
+            repository: dep.repository.clone(),
+            declared_in: None,
+            internal: false,
+            inherited_from: None,
+            ecosystem: None,
+            suggestions: Vec::new(),
+            update_policy: None,
+            introduced_by: Vec::new(),
+            purl: None,
+            vulnerabilities: None,
+            manifest: None,
+            data_license: None,
+            project_dirs: Vec::new(),
+        }
+    })
+}
+
+// =============================================================================
+// COMPOSER.LOCK / COMPOSER.JSON PARSING
+// =============================================================================
+
+/// Parse the locked packages of a `composer.lock`: `packages`, plus `packages-dev` unless
+/// dev dependencies are excluded.
+fn parse_composer_lock(json: &Value, no_dev: bool) -> Vec<PhpDependency> {
+    let sections: &[&str] = if no_dev {
+        &["packages"]
+    } else {
+        &["packages", "packages-dev"]
+    };
+
+    let mut deps: Vec<PhpDependency> = sections
+        .iter()
+        .filter_map(|section| json[section].as_array())
+        .flatten()
+        .filter_map(|package| {
+            let name = package["name"].as_str()?.to_string();
+            let version = package["version"].as_str().unwrap_or_default();
+            Some(PhpDependency {
+                name,
+                version: normalize_version(version),
+                license: license_field(&package["license"]),
+                repository: package["source"]["url"]
+                    .as_str()
+                    .map(|url| url.trim_end_matches(".git").to_string()),
+            })
+        })
+        .collect();
+
+    deps.sort_by(|a, b| a.name.cmp(&b.name));
+    deps.dedup_by(|a, b| a.name == b.name);
+    deps
+}
+
+/// Best-effort parse of the requirements declared in a `composer.json`. Platform
+/// requirements (`php`, `ext-*`, `lib-*`, ...) aren't packages and are skipped.
+fn parse_composer_json(json: &Value, no_dev: bool) -> Vec<PhpDependency> {
+    let sections: &[&str] = if no_dev {
+        &["require"]
+    } else {
+        &["require", "require-dev"]
+    };
+
+    let mut deps: Vec<PhpDependency> = sections
+        .iter()
+        .filter_map(|section| json[section].as_object())
+        .flatten()
+        .filter(|(name, _)| name.contains('/'))
+        .map(|(name, constraint)| PhpDependency {
+            name: name.clone(),
+            version: clean_constraint(constraint.as_str().unwrap_or_default()),
+            license: None,
+            repository: None,
+        })
+        .collect();
+
+    deps.sort_by(|a, b| a.name.cmp(&b.name));
+    deps.dedup_by(|a, b| a.name == b.name);
+    deps
+}
+
+/// Composer's `license` field: a string, or an array of alternatives the user may choose
+/// between, which becomes an `A OR B` expression
+fn license_field(value: &Value) -> Option<String> {
+    let names: Vec<&str> = match value {
+        Value::String(license) => vec![license.as_str()],
+        Value::Array(licenses) => licenses.iter().filter_map(|l| l.as_str()).collect(),
+        _ => Vec::new(),
+    };
+    let names: Vec<&str> = names
+        .into_iter()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect();
+
+    match names.as_slice() {
+        [] => None,
+        [only] => Some(only.to_string()),
+        _ if names.iter().any(|name| name.contains(' ')) => Some(
+            names
+                .iter()
+                .map(|name| format!("({name})"))
+                .collect::<Vec<_>>()
+                .join(" OR "),
+        ),
+        _ => Some(names.join(" OR ")),
+    }
+}
+
+/// Drop the `v` prefix tags often carry (`v6.4.1` -> `6.4.1`); branch versions like
+/// `dev-main` are kept as they are
+fn normalize_version(version: &str) -> String {
+    match version.strip_prefix('v') {
+        Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => rest.to_string(),
+        _ => version.to_string(),
+    }
+}
+
+/// Extract a concrete version from a Composer constraint (`^6.4`, `~2.1.0`, `>=1.0 <2.0`),
+/// or an empty string when there is none (`*`, `dev-main`)
+fn clean_constraint(constraint: &str) -> String {
+    let ver_re = Regex::new(r"[0-9][0-9A-Za-z.]*").unwrap();
+    ver_re
+        .find(constraint)
+        .map(|m| m.as_str().trim_end_matches('.').to_string())
+        .unwrap_or_default()
+}
+
+// =============================================================================
+// PACKAGIST LICENSE LOOKUP
+// =============================================================================
+
+fn fetch_php_license(dep: &PhpDependency, vendor_dir: &Path) -> String {
+    let local = |name: &str, _: &str| fetch_from_vendor(vendor_dir, name);
+    resolve_license(
+        &LicenseQuery::new("php", &dep.name, &dep.version)
+            .with_repository(dep.repository.as_deref()),
+        Some(&local),
+        Some(&fetch_license_from_packagist),
+    )
+    .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// An installed package's license, from `vendor/<name>/composer.json` or its license file
+fn fetch_from_vendor(vendor_dir: &Path, name: &str) -> Option<String> {
+    let package_dir = vendor_dir.join(name);
+    fs::read_to_string(package_dir.join("composer.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .and_then(|json| license_field(&json["license"]))
+        .or_else(|| detect_license_in_dir(&package_dir))
+}
+
+/// Look a package up in the Packagist metadata API: the matching version's license, or the
+/// newest release's when the version isn't listed
+fn fetch_license_from_packagist(name: &str, version: &str) -> Option<String> {
+    let url = format!("https://repo.packagist.org/p2/{name}.json");
+    log(
+        LogLevel::Info,
+        &format!("Fetching Packagist metadata: {url}"),
+    );
+
+    let response = crate::cancel::http_get(&url).ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let json: Value = response.json().ok()?;
+    packagist_license(&json, name, version)
+}
+
+fn packagist_license(json: &Value, name: &str, version: &str) -> Option<String> {
+    let releases = json["packages"][name].as_array()?;
+    // `version_normalized` pads to four parts (`1.0.0.0`), so compare without trailing zeros
+    let significant = |version: &str| version.trim_end_matches(".0").to_string();
+    let wanted = significant(version);
+    let matching = releases.iter().find(|release| {
+        release["version_normalized"]
+            .as_str()
+            .or_else(|| release["version"].as_str())
+            .is_some_and(|candidate| significant(&normalize_version(candidate)) == wanted)
+    });
+    // Releases are listed newest first; the minified format only repeats a field when it
+    // changes, so the newest release always carries the license
+    matching
+        .and_then(|release| license_field(&release["license"]))
+        .or_else(|| releases.first().and_then(|r| license_field(&r["license"])))
+}
+
+// TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_composer_lock() {
+        let lock = json!({
+            "packages": [
+                {
+                    "name": "symfony/console",
+                    "version": "v6.4.1",
+                    "license": ["MIT"],
+                    "source": {"type": "git", "url": "https://github.com/symfony/console.git"}
+                },
+                {"name": "acme/legacy", "version": "1.2.0"}
+            ],
+            "packages-dev": [
+                {"name": "phpunit/phpunit", "version": "10.5.2", "license": ["BSD-3-Clause"]}
+            ]
+        });
+
+        let deps = parse_composer_lock(&lock, false);
+        let names: Vec<&str> = deps.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["acme/legacy", "phpunit/phpunit", "symfony/console"]
+        );
+        let console = deps.iter().find(|d| d.name == "symfony/console").unwrap();
+        assert_eq!(console.version, "6.4.1");
+        assert_eq!(console.license.as_deref(), Some("MIT"));
+        assert_eq!(
+            console.repository.as_deref(),
+            Some("https://github.com/symfony/console")
+        );
+        assert!(deps[0].license.is_none());
+
+        let deps = parse_composer_lock(&lock, true);
+        assert!(deps.iter().all(|d| d.name != "phpunit/phpunit"));
+    }
+
+    #[test]
+    fn test_parse_composer_json() {
+        let manifest = json!({
+            "require": {
+                "php": ">=8.1",
+                "ext-json": "*",
+                "laravel/framework": "^10.10",
+                "guzzlehttp/guzzle": "~7.2.0"
+            },
+            "require-dev": {"mockery/mockery": "^1.4.4"}
+        });
+
+        let deps = parse_composer_json(&manifest, false);
+        let names: Vec<&str> = deps.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["guzzlehttp/guzzle", "laravel/framework", "mockery/mockery"]
+        );
+        assert_eq!(deps[0].version, "7.2.0");
+        assert_eq!(deps[1].version, "10.10");
+        assert_eq!(parse_composer_json(&manifest, true).len(), 2);
+    }
+
+    #[test]
+    fn test_license_field() {
+        assert_eq!(license_field(&json!("MIT")), Some("MIT".to_string()));
+        assert_eq!(
+            license_field(&json!(["LGPL-2.1-only", "GPL-3.0-or-later"])),
+            Some("LGPL-2.1-only OR GPL-3.0-or-later".to_string())
+        );
+        assert_eq!(license_field(&json!([])), None);
+        assert_eq!(license_field(&Value::Null), None);
+    }
+
+    #[test]
+    fn test_normalize_version() {
+        assert_eq!(normalize_version("v6.4.1"), "6.4.1");
+        assert_eq!(normalize_version("2.0.0"), "2.0.0");
+        assert_eq!(normalize_version("dev-main"), "dev-main");
+    }
+
+    #[test]
+    fn test_packagist_license() {
+        let metadata = json!({
+            "packages": {
+                "monolog/monolog": [
+                    {"version": "3.5.0", "version_normalized": "3.5.0.0", "license": ["MIT"]},
+                    {"version": "1.0.0", "version_normalized": "1.0.0.0", "license": ["BSD-2-Clause"]}
+                ]
+            }
+        });
+        assert_eq!(
+            packagist_license(&metadata, "monolog/monolog", "1.0.0"),
+            Some("BSD-2-Clause".to_string())
+        );
+        assert_eq!(
+            packagist_license(&metadata, "monolog/monolog", "9.9.9"),
+            Some("MIT".to_string())
+        );
+        assert_eq!(packagist_license(&metadata, "other/package", "1.0.0"), None);
+    }
+
+    #[test]
+    fn test_fetch_from_vendor() {
+        let dir = tempfile::tempdir().unwrap();
+        let package = dir.path().join("acme/legacy");
+        fs::create_dir_all(&package).unwrap();
+        fs::write(
+            package.join("composer.json"),
+            r#"{"name": "acme/legacy", "license": "Apache-2.0"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            fetch_from_vendor(dir.path(), "acme/legacy"),
+            Some("Apache-2.0".to_string())
+        );
+        assert_eq!(fetch_from_vendor(dir.path(), "acme/missing"), None);
+    }
+}
@@ -8,11 +8,12 @@ use std::process::Command;
 use toml::Value as TomlValue;
 
 use crate::config::FeludaConfig;
-use crate::debug::{log, log_debug, log_error, LogLevel};
+use crate::debug::{log, log_debug, log_error, record_parse_warning, LogLevel};
 use crate::licenses::{
     detect_license_in_dir, fetch_licenses_from_github, is_license_restrictive,
     LicenseCompatibility, LicenseInfo,
 };
+use crate::resolver::{known_license, resolve_all, resolve_license, LicenseQuery};
 
 /// Represents an environment marker in a Python requirement
 /// Environment markers follow PEP 508 and are used to specify conditional dependencies
@@ -159,7 +160,7 @@ pub fn analyze_python_licenses(package_file_path: &str, config: &FeludaConfig) -
         match fs::read_to_string(package_file_path) {
             Ok(content) => match toml::from_str::<TomlValue>(&content) {
                 Ok(toml_config) => {
-                    let mut direct_deps = extract_pep508_deps_from_toml(&toml_config);
+                    let mut direct_deps = declared_python_deps(&toml_config, config.no_dev);
                     let is_workspace = is_uv_workspace_root(&toml_config);
 
                     if is_workspace {
@@ -179,7 +180,7 @@ pub fn analyze_python_licenses(package_file_path: &str, config: &FeludaConfig) -
                             }
                             if let Ok(c) = fs::read_to_string(&member_pyproject) {
                                 if let Ok(member_toml) = toml::from_str::<TomlValue>(&c) {
-                                    let extra = extract_pep508_deps_from_toml(&member_toml);
+                                    let extra = declared_python_deps(&member_toml, config.no_dev);
                                     log(
                                         LogLevel::Info,
                                         &format!(
@@ -197,7 +198,9 @@ pub fn analyze_python_licenses(package_file_path: &str, config: &FeludaConfig) -
                         direct_deps.retain(|(n, _)| seen.insert(n.clone()));
                     }
 
-                    if direct_deps.is_empty() {
+                    let locked = read_python_lockfile(&project_root, &toml_config, config.no_dev);
+
+                    if direct_deps.is_empty() && locked.is_none() {
                         if is_workspace {
                             log(LogLevel::Warn, "uv workspace has no member dependencies");
                         } else if toml_config
@@ -222,24 +225,56 @@ pub fn analyze_python_licenses(package_file_path: &str, config: &FeludaConfig) -
                         );
                         log_debug("Direct dependencies", &direct_deps);
 
-                        // Try to resolve all dependencies (direct + transitive) using uv or fallback to PyPI
-                        let max_depth = config.dependencies.max_depth;
-                        log(
-                            LogLevel::Info,
-                            &format!("Using max dependency depth: {max_depth}"),
-                        );
-                        let all_deps =
-                            resolve_python_dependencies(&direct_deps, package_file_path, max_depth);
+                        let mut all_deps = match locked {
+                            // The lockfile pins the whole transitive tree
+                            Some((file_name, deps)) => {
+                                log(
+                                    LogLevel::Info,
+                                    &format!("Using {} packages pinned in {file_name}", deps.len()),
+                                );
+                                deps
+                            }
+                            None => {
+                                // Try to resolve all dependencies (direct + transitive) using uv or fallback to PyPI
+                                let max_depth = config.dependencies.max_depth;
+                                log(
+                                    LogLevel::Info,
+                                    &format!("Using max dependency depth: {max_depth}"),
+                                );
+                                resolve_python_dependencies(
+                                    &direct_deps,
+                                    package_file_path,
+                                    max_depth,
+                                )
+                            }
+                        };
+
+                        if config.direct_only {
+                            let direct: HashSet<String> = direct_deps
+                                .iter()
+                                .map(|(name, _)| normalize_package_name(name))
+                                .collect();
+                            all_deps
+                                .retain(|(name, _)| direct.contains(&normalize_package_name(name)));
+                            log(
+                                LogLevel::Info,
+                                &format!(
+                                    "Kept {} direct dependencies (--direct-only)",
+                                    all_deps.len()
+                                ),
+                            );
+                        }
 
                         // Process all resolved dependencies
-                        for (name, version) in all_deps {
+                        let fetched = resolve_all(&all_deps, |(name, version)| {
+                            fetch_license_for_python_dependency(name, version)
+                        });
+                        for ((name, version), license_result) in all_deps.into_iter().zip(fetched) {
                             log(
                                 LogLevel::Info,
                                 &format!("Processing dependency: {name} ({version})"),
                             );
 
-                            let license_result =
-                                fetch_license_for_python_dependency(&name, &version);
                             let license = Some(license_result);
                             let is_restrictive =
                                 is_license_restrictive(&license, &known_licenses, config.strict);
@@ -266,52 +301,53 @@ pub fn analyze_python_licenses(package_file_path: &str, config: &FeludaConfig) -
                                     None => crate::licenses::OsiStatus::Unknown,
                                 },
                                 sub_project,
+                                found_in: Vec::new(),
+                                repository: None,
+                                declared_in: None,
+                                internal: false,
+                                inherited_from: None,
+                                ecosystem: None,
+                                suggestions: Vec::new(),
+                                update_policy: None,
+                                introduced_by: Vec::new(),
+                                purl: None,
+                                vulnerabilities: None,
+                                manifest: None,
+                                data_license: None,
+                                project_dirs: Vec::new(),
                             });
                         }
                     }
                 }
                 Err(err) => {
-                    log_error("Failed to parse pyproject.toml", &err);
+                    record_parse_warning(
+                        package_file_path,
+                        format!("invalid pyproject.toml: {err}"),
+                    );
                 }
             },
             Err(err) => {
-                log_error("Failed to read pyproject.toml file", &err);
+                record_parse_warning(
+                    package_file_path,
+                    format!("failed to read pyproject.toml: {err}"),
+                );
             }
         }
     } else {
-        log(LogLevel::Info, "Processing requirements.txt format");
-
-        match File::open(package_file_path) {
-            Ok(file) => {
-                let reader = BufReader::new(file);
-                let mut direct_deps = Vec::new();
-
-                // Direct dependencies
-                for line_result in reader.lines() {
-                    match line_result {
-                        Ok(line) => {
-                            let line = line.trim();
-                            if line.is_empty() || line.starts_with('#') {
-                                continue;
-                            }
-
-                            // Parse requirement line (supporting various formats)
-                            if let Some((name, version)) = parse_requirement_line(line) {
-                                direct_deps.push((name, version));
-                            } else {
-                                log(LogLevel::Warn, &format!("Invalid requirement line: {line}"));
-                            }
-                        }
-                        Err(err) => {
-                            log_error("Failed to read line from requirements.txt", &err);
-                        }
-                    }
-                }
+        let direct_deps = if package_file_path.ends_with("Pipfile.lock") {
+            log(LogLevel::Info, "Processing Pipfile.lock format");
+            parse_pipfile_lock(package_file_path)
+        } else {
+            log(LogLevel::Info, "Processing requirements.txt format");
+            read_requirements_file(package_file_path)
+        };
 
+        match direct_deps {
+            Ok(direct_deps) => {
                 log(
                     LogLevel::Info,
                     &format!(
-                        "Found {} direct requirements in requirements.txt",
+                        "Found {} direct requirements in {package_file_path}",
                         direct_deps.len()
                     ),
                 );
@@ -326,13 +362,15 @@ pub fn analyze_python_licenses(package_file_path: &str, config: &FeludaConfig) -
                     resolve_python_dependencies(&direct_deps, package_file_path, max_depth);
 
                 // Process all resolved dependencies
-                for (name, version) in all_deps {
+                let fetched = resolve_all(&all_deps, |(name, version)| {
+                    fetch_license_for_python_dependency(name, version)
+                });
+                for ((name, version), license_result) in all_deps.into_iter().zip(fetched) {
                     log(
                         LogLevel::Info,
                         &format!("Processing dependency: {name} ({version})"),
                     );
 
-                    let license_result = fetch_license_for_python_dependency(&name, &version);
                     let license = Some(license_result);
                     let is_restrictive =
                         is_license_restrictive(&license, &known_licenses, config.strict);
@@ -355,6 +393,20 @@ pub fn analyze_python_licenses(package_file_path: &str, config: &FeludaConfig) -
                             None => crate::licenses::OsiStatus::Unknown,
                         },
                         sub_project: None,
+                        found_in: Vec::new(),
+                        repository: None,
+                        declared_in: None,
+                        internal: false,
+                        inherited_from: None,
+                        ecosystem: None,
+                        suggestions: Vec::new(),
+                        update_policy: None,
+                        introduced_by: Vec::new(),
+                        purl: None,
+                        vulnerabilities: None,
+                        manifest: None,
+                        data_license: None,
+                        project_dirs: Vec::new(),
                     });
                 }
 
@@ -366,9 +418,7 @@ pub fn analyze_python_licenses(package_file_path: &str, config: &FeludaConfig) -
                     ),
                 );
             }
-            Err(err) => {
-                log_error("Failed to open requirements.txt file", &err);
-            }
+            Err(reason) => record_parse_warning(package_file_path, reason),
         }
     }
 
@@ -379,17 +429,278 @@ pub fn analyze_python_licenses(package_file_path: &str, config: &FeludaConfig) -
     licenses
 }
 
-/// Fetch the license for a Python dependency, trying local sources first, then PyPI
-pub fn fetch_license_for_python_dependency(name: &str, version: &str) -> String {
-    if let Some(license) = get_license_from_local_site_packages(name) {
-        log(
-            LogLevel::Info,
-            &format!("Found license in local site-packages for {name}: {license}"),
-        );
-        return license;
+/// Read direct requirements from a `requirements.txt`-style file (also `pip_freeze.txt`)
+fn read_requirements_file(path: &str) -> Result<Vec<(String, String)>, String> {
+    let file = File::open(path).map_err(|e| format!("failed to open requirements file: {e}"))?;
+    let reader = BufReader::new(file);
+    let mut direct_deps = Vec::new();
+
+    for line_result in reader.lines() {
+        match line_result {
+            Ok(line) => {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+
+                // Parse requirement line (supporting various formats)
+                if let Some((name, version)) = parse_requirement_line(line) {
+                    direct_deps.push((name, version));
+                } else {
+                    record_parse_warning(path, format!("invalid requirement line: {line}"));
+                }
+            }
+            Err(err) => {
+                log_error("Failed to read line from requirements.txt", &err);
+            }
+        }
     }
 
-    fetch_license_from_pypi(name, version)
+    Ok(direct_deps)
+}
+
+/// Read the pinned runtime (`default`) packages from a `Pipfile.lock`
+///
+/// Pipenv pins every entry as `"version": "==X.Y.Z"`; VCS and path entries carry no
+/// version and are reported as `latest`. The `develop` section is dev-only and skipped.
+fn parse_pipfile_lock(path: &str) -> Result<Vec<(String, String)>, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("failed to read Pipfile.lock: {e}"))?;
+    let json: Value =
+        serde_json::from_str(&content).map_err(|e| format!("invalid Pipfile.lock: {e}"))?;
+
+    let Some(default) = json.get("default").and_then(|d| d.as_object()) else {
+        return Err("Pipfile.lock has no 'default' section".to_string());
+    };
+
+    Ok(default
+        .iter()
+        .map(|(name, entry)| {
+            let version = entry
+                .get("version")
+                .and_then(|v| v.as_str())
+                .map(|v| v.trim_start_matches("==").to_string())
+                .unwrap_or_else(|| "latest".to_string());
+            (name.clone(), version)
+        })
+        .collect())
+}
+
+/// Pick the lockfile that pins a pyproject.toml project's full dependency tree.
+///
+/// Poetry projects (a `[tool.poetry]` table) read `poetry.lock`, everything else `uv.lock`;
+/// when only one of the two exists, that one is used. Returns the lockfile's name with its
+/// packages, or `None` when there is no usable lockfile and the tree must be resolved.
+fn read_python_lockfile(
+    project_root: &Path,
+    toml_config: &TomlValue,
+    no_dev: bool,
+) -> Option<(&'static str, Vec<(String, String)>)> {
+    let poetry_lock = project_root.join("poetry.lock");
+    let uv_lock = project_root.join("uv.lock");
+    let is_poetry = toml_config
+        .get("tool")
+        .and_then(|t| t.get("poetry"))
+        .is_some();
+
+    let parsed = if poetry_lock.is_file() && (is_poetry || !uv_lock.is_file()) {
+        let main: HashSet<String> = declared_python_deps(toml_config, true)
+            .into_iter()
+            .map(|(name, _)| normalize_package_name(&name))
+            .collect();
+        (
+            "poetry.lock",
+            &poetry_lock,
+            parse_poetry_lock(&poetry_lock, &main, no_dev),
+        )
+    } else if uv_lock.is_file() {
+        ("uv.lock", &uv_lock, parse_uv_lock(&uv_lock, no_dev))
+    } else {
+        return None;
+    };
+
+    match parsed {
+        (file_name, _, Ok(deps)) => Some((file_name, deps)),
+        (_, path, Err(reason)) => {
+            record_parse_warning(path.display().to_string(), reason);
+            None
+        }
+    }
+}
+
+/// Read the packages a `poetry.lock` pins.
+///
+/// Packages installed from a local directory are the project's own and are skipped. With
+/// `no_dev`, Poetry 2 lockfiles drop packages outside the `main` group and Poetry 1.4 and
+/// older ones those of the `dev` category; lockfiles in between record neither, so only
+/// packages reachable from the `main` dependencies declared in pyproject.toml are kept.
+fn parse_poetry_lock(
+    lock_file: &Path,
+    main: &HashSet<String>,
+    no_dev: bool,
+) -> Result<Vec<(String, String)>, String> {
+    let content =
+        fs::read_to_string(lock_file).map_err(|e| format!("failed to read poetry.lock: {e}"))?;
+    let lock_data: TomlValue =
+        toml::from_str(&content).map_err(|e| format!("invalid poetry.lock: {e}"))?;
+
+    let packages: Vec<&TomlValue> = lock_data
+        .get("package")
+        .and_then(|p| p.as_array())
+        .map(|packages| packages.iter().collect())
+        .unwrap_or_default();
+
+    let reachable = no_dev.then(|| {
+        let graph: HashMap<String, Vec<String>> = packages
+            .iter()
+            .filter_map(|package| {
+                let name = normalize_package_name(package.get("name")?.as_str()?);
+                let requires = package
+                    .get("dependencies")
+                    .and_then(|d| d.as_table())
+                    .map(|deps| deps.keys().map(|dep| normalize_package_name(dep)).collect())
+                    .unwrap_or_default();
+                Some((name, requires))
+            })
+            .collect();
+        reachable_packages(main.iter().cloned(), |name| {
+            graph.get(name).cloned().unwrap_or_default()
+        })
+    });
+
+    Ok(packages
+        .into_iter()
+        .filter(|package| {
+            package
+                .get("source")
+                .and_then(|s| s.get("type"))
+                .and_then(|t| t.as_str())
+                != Some("directory")
+        })
+        .filter(|package| {
+            let Some(reachable) = &reachable else {
+                return true;
+            };
+            if let Some(groups) = package.get("groups").and_then(|g| g.as_array()) {
+                return groups.iter().any(|group| group.as_str() == Some("main"));
+            }
+            if let Some(category) = package.get("category").and_then(|c| c.as_str()) {
+                return category != "dev";
+            }
+            package
+                .get("name")
+                .and_then(|n| n.as_str())
+                .is_some_and(|name| reachable.contains(&normalize_package_name(name)))
+        })
+        .filter_map(|package| {
+            Some((
+                package.get("name")?.as_str()?.to_string(),
+                package.get("version")?.as_str()?.to_string(),
+            ))
+        })
+        .collect())
+}
+
+/// Read the packages a `uv.lock` pins.
+///
+/// The project and its workspace members are recorded with a local (`editable`, `virtual`,
+/// `workspace` or `directory`) source and are skipped. With `no_dev`, only packages reachable
+/// from the project's `dependencies` and extras are kept, leaving out its `dev-dependencies`
+/// groups.
+fn parse_uv_lock(lock_file: &Path, no_dev: bool) -> Result<Vec<(String, String)>, String> {
+    let content =
+        fs::read_to_string(lock_file).map_err(|e| format!("failed to read uv.lock: {e}"))?;
+    let lock_data: TomlValue =
+        toml::from_str(&content).map_err(|e| format!("invalid uv.lock: {e}"))?;
+
+    let packages: Vec<&TomlValue> = lock_data
+        .get("package")
+        .and_then(|p| p.as_array())
+        .map(|packages| packages.iter().collect())
+        .unwrap_or_default();
+    let is_local = |package: &TomlValue| {
+        package.get("source").is_some_and(|source| {
+            ["editable", "virtual", "workspace", "directory"]
+                .iter()
+                .any(|kind| source.get(kind).is_some())
+        })
+    };
+    let names = |list: Option<&TomlValue>| -> Vec<String> {
+        list.and_then(|l| l.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|dep| dep.get("name")?.as_str().map(normalize_package_name))
+            .collect()
+    };
+
+    let reachable = no_dev.then(|| {
+        let roots = packages.iter().filter(|p| is_local(p)).flat_map(|package| {
+            let extras = package
+                .get("optional-dependencies")
+                .and_then(|o| o.as_table())
+                .into_iter()
+                .flat_map(|extras| extras.values().flat_map(|list| names(Some(list))));
+            names(package.get("dependencies"))
+                .into_iter()
+                .chain(extras)
+                .collect::<Vec<_>>()
+        });
+        reachable_packages(roots, |name| {
+            packages
+                .iter()
+                .filter(|p| {
+                    p.get("name")
+                        .and_then(|n| n.as_str())
+                        .is_some_and(|n| normalize_package_name(n) == name)
+                })
+                .flat_map(|p| names(p.get("dependencies")))
+                .collect()
+        })
+    });
+
+    Ok(packages
+        .into_iter()
+        .filter(|package| !is_local(package))
+        .filter_map(|package| {
+            let name = package.get("name")?.as_str()?;
+            let version = package.get("version")?.as_str()?;
+            reachable
+                .as_ref()
+                .is_none_or(|reachable| reachable.contains(&normalize_package_name(name)))
+                .then(|| (name.to_string(), version.to_string()))
+        })
+        .collect())
+}
+
+/// Every package reachable from `roots` through `requires`, roots included
+fn reachable_packages(
+    roots: impl IntoIterator<Item = String>,
+    requires: impl Fn(&str) -> Vec<String>,
+) -> HashSet<String> {
+    let mut seen = HashSet::new();
+    let mut queue: Vec<String> = roots.into_iter().collect();
+    while let Some(name) = queue.pop() {
+        if seen.insert(name.clone()) {
+            queue.extend(requires(&name));
+        }
+    }
+    seen
+}
+
+/// PEP 503 normalized name, so `Typing_Extensions` and `typing-extensions` match
+fn normalize_package_name(name: &str) -> String {
+    name.to_lowercase().replace(['_', '.'], "-")
+}
+
+/// Fetch the license for a Python dependency through the resolver chain (local
+/// site-packages, then PyPI by default)
+pub fn fetch_license_for_python_dependency(name: &str, version: &str) -> String {
+    resolve_license(
+        &LicenseQuery::new("python", name, version),
+        Some(&|name: &str, _: &str| get_license_from_local_site_packages(name)),
+        Some(&|name: &str, version: &str| known_license(fetch_license_from_pypi(name, version))),
+    )
+    .unwrap_or_else(|| format!("Unknown license for {name}: {version}"))
 }
 
 fn get_license_from_local_site_packages(package_name: &str) -> Option<String> {
@@ -505,7 +816,7 @@ fn fetch_license_from_pypi(name: &str, version: &str) -> String {
         &format!("Fetching license from PyPI: {api_url}"),
     );
 
-    match reqwest::blocking::get(&api_url) {
+    match crate::cancel::http_get(&api_url) {
         Ok(response) => {
             let status = response.status();
             log(
@@ -633,6 +944,15 @@ fn resolve_python_dependencies(
     resolve_with_pypi(direct_deps, max_depth)
 }
 
+/// `uv`, kept to its cache under `--offline`
+fn uv_command() -> Command {
+    let mut command = Command::new("uv");
+    if crate::cancel::is_offline() {
+        command.env("UV_OFFLINE", "1");
+    }
+    command
+}
+
 /// Try to resolve dependencies using uv tool with depth limit
 fn resolve_with_uv(
     package_file_path: &str,
@@ -648,7 +968,7 @@ fn resolve_with_uv(
     );
 
     // Try uv lock command first (for uv-managed projects)
-    if let Ok(output) = Command::new("uv")
+    if let Ok(output) = uv_command()
         .args(["lock", "--dry-run"])
         .current_dir(project_dir)
         .output()
@@ -657,7 +977,7 @@ fn resolve_with_uv(
             // Parse uv.lock file if it exists
             let lock_file = project_dir.join("uv.lock");
             if lock_file.exists() {
-                if let Ok(deps) = parse_uv_lock(&lock_file, max_depth) {
+                if let Ok(deps) = parse_uv_lock(&lock_file, false) {
                     log(
                         LogLevel::Info,
                         &format!("Resolved {} dependencies from uv.lock", deps.len()),
@@ -669,7 +989,7 @@ fn resolve_with_uv(
     }
 
     // Try pip-compile style resolution using uv
-    if let Ok(output) = Command::new("uv")
+    if let Ok(output) = uv_command()
         .args(["pip", "compile", "--dry-run", package_file_path])
         .current_dir(project_dir)
         .output()
@@ -691,47 +1011,6 @@ fn resolve_with_uv(
     Err("uv resolution failed".to_string())
 }
 
-/// Parse uv.lock file to extract dependencies with depth awareness
-fn parse_uv_lock(lock_file: &Path, max_depth: u32) -> Result<Vec<(String, String)>, String> {
-    let content =
-        fs::read_to_string(lock_file).map_err(|e| format!("Failed to read uv.lock: {e}"))?;
-
-    // uv.lock is TOML format
-    let lock_data: TomlValue =
-        toml::from_str(&content).map_err(|e| format!("Failed to parse uv.lock: {e}"))?;
-
-    let mut deps = Vec::new();
-
-    log(
-        LogLevel::Info,
-        &format!("Parsing uv.lock with max depth {max_depth}"),
-    );
-
-    // Extract packages from uv.lock format
-    if let Some(packages) = lock_data.get("package").and_then(|p| p.as_array()) {
-        for package in packages {
-            if let Some(package_table) = package.as_table() {
-                if let (Some(name), Some(version)) = (
-                    package_table.get("name").and_then(|n| n.as_str()),
-                    package_table.get("version").and_then(|v| v.as_str()),
-                ) {
-                    deps.push((name.to_string(), version.to_string()));
-                }
-            }
-        }
-
-        log(
-            LogLevel::Info,
-            &format!(
-                "Extracted {} dependencies from uv.lock (all depths included)",
-                deps.len()
-            ),
-        );
-    }
-
-    Ok(deps)
-}
-
 /// Parse pip-compile style output to extract dependencies
 fn parse_pip_compile_output(output: &str) -> Vec<(String, String)> {
     let mut deps = Vec::new();
@@ -844,7 +1123,7 @@ fn resolve_with_pypi(direct_deps: &[(String, String)], max_depth: u32) -> Vec<(S
 fn fetch_pypi_dependencies(name: &str, version: &str) -> Result<Vec<(String, String)>, String> {
     let api_url = format!("https://pypi.org/pypi/{name}/{version}/json");
 
-    match reqwest::blocking::get(&api_url) {
+    match crate::cancel::http_get(&api_url) {
         Ok(response) => {
             if response.status().is_success() {
                 if let Ok(json) = response.json::<Value>() {
@@ -1011,7 +1290,88 @@ fn extract_pep508_deps_from_toml(toml_config: &TomlValue) -> Vec<(String, String
     deps
 }
 
+/// Direct deps a pyproject.toml declares: the PEP 621 `[project] dependencies` and Poetry's
+/// `[tool.poetry.dependencies]`, plus, unless `no_dev`, the PEP 735 `[dependency-groups]`,
+/// uv's `dev-dependencies` and Poetry's dependency groups.
+fn declared_python_deps(toml_config: &TomlValue, no_dev: bool) -> Vec<(String, String)> {
+    let mut deps = extract_pep508_deps_from_toml(toml_config);
+    let poetry = toml_config.get("tool").and_then(|t| t.get("poetry"));
+    deps.extend(poetry_deps(poetry.and_then(|p| p.get("dependencies"))));
+
+    if !no_dev {
+        let pep508_lists = toml_config
+            .get("dependency-groups")
+            .and_then(|g| g.as_table())
+            .into_iter()
+            .flat_map(|groups| groups.values())
+            .chain(
+                toml_config
+                    .get("tool")
+                    .and_then(|t| t.get("uv"))
+                    .and_then(|uv| uv.get("dev-dependencies")),
+            );
+        for list in pep508_lists {
+            // `{ include-group = "..." }` entries pull in another group, listed anyway
+            deps.extend(
+                list.as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|dep| dep.as_str())
+                    .map(split_pep508_dep),
+            );
+        }
+
+        deps.extend(poetry_deps(poetry.and_then(|p| p.get("dev-dependencies"))));
+        for group in poetry
+            .and_then(|p| p.get("group"))
+            .and_then(|g| g.as_table())
+            .into_iter()
+            .flat_map(|groups| groups.values())
+        {
+            deps.extend(poetry_deps(group.get("dependencies")));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    deps.retain(|(name, _)| seen.insert(normalize_package_name(name)));
+    deps
+}
+
+/// Entries of a Poetry dependency table. Constraints are `"^2.31"` or a table with a
+/// `version`; path dependencies are the project's own and `python` is the interpreter.
+fn poetry_deps(table: Option<&TomlValue>) -> Vec<(String, String)> {
+    let Some(table) = table.and_then(|t| t.as_table()) else {
+        return Vec::new();
+    };
+    table
+        .iter()
+        .filter(|(name, spec)| *name != "python" && spec.get("path").is_none())
+        .map(|(name, spec)| {
+            let constraint = match spec {
+                TomlValue::String(constraint) => Some(constraint.as_str()),
+                _ => spec.get("version").and_then(|v| v.as_str()),
+            };
+            let version = constraint
+                .map(|c| c.split(',').next().unwrap_or(c))
+                .map(|c| c.trim_start_matches(['^', '~', '=', '>', '<', '!']).trim())
+                .filter(|c| !c.is_empty() && *c != "*")
+                .unwrap_or("latest");
+            (name.clone(), version.to_string())
+        })
+        .collect()
+}
+
+/// Split a PEP 508 requirement into its name and version, dropping extras and markers
 fn split_pep508_dep(dep_str: &str) -> (String, String) {
+    let dep_str = dep_str.split(';').next().unwrap_or(dep_str);
+    let dep_str = match dep_str.split_once('[') {
+        Some((name, rest)) => {
+            let after = rest.split_once(']').map_or("", |(_, after)| after);
+            format!("{name}{after}")
+        }
+        None => dep_str.to_string(),
+    };
+    let dep_str = dep_str.as_str();
     if let Some((n, v)) = dep_str
         .split_once("==")
         .or_else(|| dep_str.split_once(">="))
@@ -1020,6 +1380,7 @@ fn split_pep508_dep(dep_str: &str) -> (String, String) {
         .or_else(|| dep_str.split_once("<="))
         .or_else(|| dep_str.split_once("<"))
     {
+        let v = v.split(',').next().unwrap_or(v);
         (n.trim().to_string(), v.trim().to_string())
     } else {
         (dep_str.trim().to_string(), "latest".to_string())
@@ -1274,6 +1635,37 @@ mod tests {
         assert!(result.contains("Unknown") || result.contains("nonexistent"));
     }
 
+    #[test]
+    fn test_parse_pipfile_lock_reads_default_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let lock_path = temp_dir.path().join("Pipfile.lock");
+        std::fs::write(
+            &lock_path,
+            r#"{
+                "_meta": {"hash": {"sha256": "abc"}},
+                "default": {
+                    "requests": {"version": "==2.31.0"},
+                    "mylib": {"git": "https://example.com/mylib.git"}
+                },
+                "develop": {"pytest": {"version": "==7.4.0"}}
+            }"#,
+        )
+        .unwrap();
+
+        let mut deps = parse_pipfile_lock(lock_path.to_str().unwrap()).unwrap();
+        deps.sort();
+        assert_eq!(
+            deps,
+            vec![
+                ("mylib".to_string(), "latest".to_string()),
+                ("requests".to_string(), "2.31.0".to_string()),
+            ]
+        );
+
+        std::fs::write(&lock_path, "not json").unwrap();
+        assert!(parse_pipfile_lock(lock_path.to_str().unwrap()).is_err());
+    }
+
     #[test]
     fn test_parse_requirement_line() {
         // Test various requirement formats
@@ -1451,6 +1843,220 @@ dependencies = ["fastapi==0.115.0", "click>=8.0", "no-version"]
         assert!(deps.iter().any(|(n, v)| n == "no-version" && v == "latest"));
     }
 
+    #[test]
+    fn test_declared_python_deps_poetry_and_groups() {
+        let parsed: TomlValue = toml::from_str(
+            r#"
+[project]
+name = "demo"
+dependencies = ["requests[socks]>=2.31,<3; python_version >= '3.8'"]
+
+[dependency-groups]
+test = ["pytest>=8.0", { include-group = "lint" }]
+
+[tool.poetry.dependencies]
+python = "^3.10"
+Django = "^4.2"
+httpx = { version = "~0.27", extras = ["http2"] }
+shared = { path = "../shared", develop = true }
+rich = "*"
+
+[tool.poetry.group.dev.dependencies]
+black = "^24.1"
+"#,
+        )
+        .unwrap();
+
+        let deps = declared_python_deps(&parsed, false);
+        let names: Vec<&str> = deps.iter().map(|(n, _)| n.as_str()).collect();
+        assert!(deps.contains(&("requests".to_string(), "2.31".to_string())));
+        assert!(deps.contains(&("Django".to_string(), "4.2".to_string())));
+        assert!(deps.contains(&("httpx".to_string(), "0.27".to_string())));
+        assert!(deps.contains(&("rich".to_string(), "latest".to_string())));
+        assert!(names.contains(&"pytest"));
+        assert!(names.contains(&"black"));
+        assert!(!names.contains(&"python"));
+        assert!(!names.contains(&"shared"));
+
+        let names: Vec<String> = declared_python_deps(&parsed, true)
+            .into_iter()
+            .map(|(n, _)| n)
+            .collect();
+        assert_eq!(names.len(), 4);
+        assert!(!names.contains(&"pytest".to_string()));
+        assert!(!names.contains(&"black".to_string()));
+    }
+
+    #[test]
+    fn test_parse_poetry_lock() {
+        let temp_dir = TempDir::new().unwrap();
+        let lock = temp_dir.path().join("poetry.lock");
+        fs::write(
+            &lock,
+            r#"
+[[package]]
+name = "requests"
+version = "2.31.0"
+optional = false
+python-versions = ">=3.7"
+
+[package.dependencies]
+certifi = ">=2017.4.17"
+
+[[package]]
+name = "certifi"
+version = "2024.2.2"
+
+[[package]]
+name = "pytest"
+version = "8.1.1"
+
+[[package]]
+name = "shared"
+version = "0.1.0"
+
+[package.source]
+type = "directory"
+url = "../shared"
+
+[metadata]
+lock-version = "2.0"
+"#,
+        )
+        .unwrap();
+        let main: HashSet<String> = ["requests".to_string()].into_iter().collect();
+
+        let deps = parse_poetry_lock(&lock, &main, false).unwrap();
+        assert_eq!(deps.len(), 3);
+        assert!(!deps.iter().any(|(n, _)| n == "shared"));
+
+        // Without group markers, dev packages are the ones main does not reach
+        let deps = parse_poetry_lock(&lock, &main, true).unwrap();
+        let names: Vec<&str> = deps.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["requests", "certifi"]);
+
+        fs::write(
+            &lock,
+            r#"
+[[package]]
+name = "requests"
+version = "2.31.0"
+groups = ["main"]
+
+[[package]]
+name = "pytest"
+version = "8.1.1"
+groups = ["dev"]
+"#,
+        )
+        .unwrap();
+        let deps = parse_poetry_lock(&lock, &HashSet::new(), true).unwrap();
+        assert_eq!(deps, vec![("requests".to_string(), "2.31.0".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_uv_lock_skips_project_and_dev_groups() {
+        let temp_dir = TempDir::new().unwrap();
+        let lock = temp_dir.path().join("uv.lock");
+        fs::write(
+            &lock,
+            r#"
+version = 1
+requires-python = ">=3.10"
+
+[[package]]
+name = "demo"
+version = "0.1.0"
+source = { editable = "." }
+dependencies = [
+    { name = "httpx" },
+]
+
+[package.optional-dependencies]
+cli = [
+    { name = "rich" },
+]
+
+[package.dev-dependencies]
+dev = [
+    { name = "pytest" },
+]
+
+[[package]]
+name = "httpx"
+version = "0.27.0"
+source = { registry = "https://pypi.org/simple" }
+dependencies = [
+    { name = "anyio" },
+]
+
+[[package]]
+name = "anyio"
+version = "4.3.0"
+source = { registry = "https://pypi.org/simple" }
+
+[[package]]
+name = "rich"
+version = "13.7.1"
+source = { registry = "https://pypi.org/simple" }
+
+[[package]]
+name = "pytest"
+version = "8.1.1"
+source = { registry = "https://pypi.org/simple" }
+dependencies = [
+    { name = "iniconfig" },
+]
+
+[[package]]
+name = "iniconfig"
+version = "2.0.0"
+source = { registry = "https://pypi.org/simple" }
+"#,
+        )
+        .unwrap();
+
+        let deps = parse_uv_lock(&lock, false).unwrap();
+        assert_eq!(deps.len(), 5);
+        assert!(!deps.iter().any(|(n, _)| n == "demo"));
+
+        let mut names: Vec<String> = parse_uv_lock(&lock, true)
+            .unwrap()
+            .into_iter()
+            .map(|(n, _)| n)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["anyio", "httpx", "rich"]);
+    }
+
+    #[test]
+    fn test_read_python_lockfile_prefers_poetry_for_poetry_projects() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let poetry: TomlValue = toml::from_str("[tool.poetry]\nname = \"demo\"\n").unwrap();
+        let pep621: TomlValue = toml::from_str("[project]\nname = \"demo\"\n").unwrap();
+        assert!(read_python_lockfile(root, &poetry, false).is_none());
+
+        fs::write(
+            root.join("poetry.lock"),
+            "[[package]]\nname = \"click\"\nversion = \"8.1.7\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("uv.lock"),
+            "[[package]]\nname = \"flask\"\nversion = \"3.0.3\"\nsource = { registry = \"https://pypi.org/simple\" }\n",
+        )
+        .unwrap();
+
+        let (file_name, deps) = read_python_lockfile(root, &poetry, false).unwrap();
+        assert_eq!(file_name, "poetry.lock");
+        assert_eq!(deps[0].0, "click");
+
+        let (file_name, deps) = read_python_lockfile(root, &pep621, false).unwrap();
+        assert_eq!(file_name, "uv.lock");
+        assert_eq!(deps[0].0, "flask");
+    }
+
     #[test]
     fn test_is_uv_workspace_root_detects_workspace() {
         let with_workspace = toml::from_str::<TomlValue>(
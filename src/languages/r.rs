@@ -5,11 +5,12 @@ use std::path::PathBuf;
 use std::process::Command;
 
 use crate::config::FeludaConfig;
-use crate::debug::{log, log_debug, log_error, LogLevel};
+use crate::debug::{log, log_debug, log_error, record_parse_warning, LogLevel};
 use crate::licenses::{
     detect_license_in_dir, fetch_licenses_from_github, is_license_restrictive, License,
     LicenseCompatibility, LicenseInfo,
 };
+use crate::resolver::{resolve_all, resolve_license, LicenseQuery};
 
 pub fn analyze_r_licenses(package_file_path: &str, config: &FeludaConfig) -> Vec<LicenseInfo> {
     let mut licenses = Vec::new();
@@ -73,18 +74,23 @@ fn parse_renv_lock(
                     );
                     log_debug("Packages", packages);
 
-                    for (name, pkg_info) in packages {
-                        let version = pkg_info["Version"]
-                            .as_str()
-                            .unwrap_or("unknown")
-                            .to_string();
-
+                    let deps: Vec<(String, String)> = packages
+                        .iter()
+                        .map(|(name, pkg_info)| {
+                            let version = pkg_info["Version"].as_str().unwrap_or("unknown");
+                            (name.clone(), version.to_string())
+                        })
+                        .collect();
+                    let fetched = resolve_all(&deps, |(name, version)| {
+                        fetch_license_for_r_dependency(name, version)
+                    });
+
+                    for ((name, version), license_result) in deps.into_iter().zip(fetched) {
                         log(
                             LogLevel::Info,
                             &format!("Processing R package: {name} ({version})"),
                         );
 
-                        let license_result = fetch_license_for_r_dependency(name, &version);
                         let license = Some(license_result);
                         let is_restrictive =
                             is_license_restrictive(&license, known_licenses, config.strict);
@@ -97,7 +103,7 @@ fn parse_renv_lock(
                         }
 
                         licenses.push(LicenseInfo {
-                            name: name.clone(),
+                            name,
                             version,
                             license: license.clone(),
                             is_restrictive,
@@ -107,6 +113,20 @@ fn parse_renv_lock(
                                 None => crate::licenses::OsiStatus::Unknown,
                             },
                             sub_project: None,
+                            found_in: Vec::new(),
+                            repository: None,
+                            declared_in: None,
+                            internal: false,
+                            inherited_from: None,
+                            ecosystem: None,
+                            suggestions: Vec::new(),
+                            update_policy: None,
+                            introduced_by: Vec::new(),
+                            purl: None,
+                            vulnerabilities: None,
+                            manifest: None,
+                            data_license: None,
+                            project_dirs: Vec::new(),
                         });
                     }
                 } else {
@@ -114,11 +134,11 @@ fn parse_renv_lock(
                 }
             }
             Err(err) => {
-                log_error("Failed to parse renv.lock JSON", &err);
+                record_parse_warning(lock_file_path, format!("invalid renv.lock JSON: {err}"));
             }
         },
         Err(err) => {
-            log_error("Failed to read renv.lock file", &err);
+            record_parse_warning(lock_file_path, format!("failed to read renv.lock: {err}"));
         }
     }
 
@@ -151,14 +171,16 @@ fn parse_description_file(
             );
 
             let all_deps = direct_deps;
+            let fetched = resolve_all(&all_deps, |(name, version)| {
+                fetch_license_for_r_dependency(name, version)
+            });
 
-            for (name, version) in all_deps {
+            for ((name, version), license_result) in all_deps.into_iter().zip(fetched) {
                 log(
                     LogLevel::Info,
                     &format!("Processing R package: {name} ({version})"),
                 );
 
-                let license_result = fetch_license_for_r_dependency(&name, &version);
                 let license = Some(license_result);
                 let is_restrictive =
                     is_license_restrictive(&license, known_licenses, config.strict);
@@ -181,11 +203,25 @@ fn parse_description_file(
                         None => crate::licenses::OsiStatus::Unknown,
                     },
                     sub_project: None,
+                    found_in: Vec::new(),
+                    repository: None,
+                    declared_in: None,
+                    internal: false,
+                    inherited_from: None,
+                    ecosystem: None,
+                    suggestions: Vec::new(),
+                    update_policy: None,
+                    introduced_by: Vec::new(),
+                    purl: None,
+                    vulnerabilities: None,
+                    manifest: None,
+                    data_license: None,
+                    project_dirs: Vec::new(),
                 });
             }
         }
         Err(err) => {
-            log_error("Failed to read DESCRIPTION file", &err);
+            record_parse_warning(desc_file_path, format!("failed to read DESCRIPTION: {err}"));
         }
     }
 
@@ -252,20 +288,13 @@ fn process_dependency_field(field: &str, value: &str, deps: &mut Vec<(String, St
 }
 
 pub fn fetch_license_for_r_dependency(name: &str, version: &str) -> String {
-    if let Some(license) = fetch_license_from_r_universe(name) {
-        return license;
-    }
-
-    // Local fallback: probe the installed package's bundled LICENSE/COPYING files.
-    if let Some(license) = fetch_from_local_r_library(name) {
-        return license;
-    }
-
-    log(
-        LogLevel::Warn,
-        &format!("No license found for {name} ({version})"),
-    );
-    format!("Unknown license for {name}: {version}")
+    resolve_license(
+        &LicenseQuery::new("r", name, version),
+        // Local: the installed package's bundled LICENSE/COPYING files
+        Some(&|name: &str, _: &str| fetch_from_local_r_library(name)),
+        Some(&|name: &str, _: &str| fetch_license_from_r_universe(name)),
+    )
+    .unwrap_or_else(|| format!("Unknown license for {name}: {version}"))
 }
 
 fn fetch_license_from_r_universe(name: &str) -> Option<String> {
@@ -275,7 +304,7 @@ fn fetch_license_from_r_universe(name: &str) -> Option<String> {
         &format!("Fetching license from R-universe: {search_url}"),
     );
 
-    let response = reqwest::blocking::get(&search_url).ok()?;
+    let response = crate::cancel::http_get(&search_url).ok()?;
     if !response.status().is_success() {
         log(
             LogLevel::Error,
@@ -296,7 +325,7 @@ fn fetch_license_from_r_universe(name: &str) -> Option<String> {
         &format!("Fetching package details from: {package_url}"),
     );
 
-    let pkg_json = reqwest::blocking::get(&package_url)
+    let pkg_json = crate::cancel::http_get(&package_url)
         .ok()?
         .json::<Value>()
         .ok()?;
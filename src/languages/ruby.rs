@@ -1,4 +1,3 @@
-use rayon::prelude::*;
 use regex::Regex;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -7,16 +6,29 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use crate::config::FeludaConfig;
-use crate::debug::{log, log_error, LogLevel};
+use crate::debug::{log, log_error, record_parse_warning, LogLevel};
 use crate::licenses::{
     detect_license_in_dir, fetch_licenses_from_github, is_license_restrictive,
     LicenseCompatibility, LicenseInfo,
 };
+use crate::resolver::{resolve_all, resolve_license, LicenseQuery};
 
 #[derive(Debug, Clone)]
 struct RubyDependency {
     name: String,
     version: String,
+    source: GemSource,
+}
+
+/// Where Bundler resolved a gem from, per the `Gemfile.lock` section it's listed in
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum GemSource {
+    /// A gem server (`GEM`), rubygems.org unless configured otherwise
+    Rubygems,
+    /// A Git checkout (`GIT`), with its remote URL
+    Git(String),
+    /// A directory in the repository (`PATH`), relative to the lockfile, such as a Rails engine
+    Path(String),
 }
 
 pub fn analyze_ruby_licenses(file_path: &str, config: &FeludaConfig) -> Vec<LicenseInfo> {
@@ -28,7 +40,7 @@ pub fn analyze_ruby_licenses(file_path: &str, config: &FeludaConfig) -> Vec<Lice
     let content = match fs::read_to_string(file_path) {
         Ok(c) => c,
         Err(e) => {
-            log_error(&format!("Failed to read Ruby file: {file_path}"), &e);
+            record_parse_warning(file_path, format!("failed to read Ruby file: {e}"));
             return Vec::new();
         }
     };
@@ -61,23 +73,47 @@ pub fn analyze_ruby_licenses(file_path: &str, config: &FeludaConfig) -> Vec<Lice
         }
     };
 
-    deps.par_iter()
-        .map(|dep| {
-            let license = fetch_ruby_license(&dep.name, &dep.version);
-            let is_restrictive =
-                is_license_restrictive(&Some(license.clone()), &known_licenses, config.strict);
-
-            LicenseInfo {
-                name: dep.name.clone(),
-                version: dep.version.clone(),
-                license: Some(license.clone()),
-                is_restrictive,
-                compatibility: LicenseCompatibility::Unknown,
-                osi_status: crate::licenses::get_osi_status(&license),
-                sub_project: None,
+    let lockfile_dir = Path::new(file_path).parent().unwrap_or(Path::new("."));
+
+    resolve_all(&deps, |dep| {
+        let license = match &dep.source {
+            GemSource::Rubygems => fetch_ruby_license(&dep.name, &dep.version),
+            GemSource::Git(remote) => fetch_git_gem_license(&dep.name, &dep.version, remote),
+            GemSource::Path(path) => {
+                fetch_path_gem_license(&dep.name, &dep.version, &lockfile_dir.join(path))
             }
-        })
-        .collect()
+        };
+        let repository = match &dep.source {
+            GemSource::Git(remote) => Some(remote.clone()),
+            _ => None,
+        };
+        let is_restrictive =
+            is_license_restrictive(&Some(license.clone()), &known_licenses, config.strict);
+
+        LicenseInfo {
+            name: dep.name.clone(),
+            version: dep.version.clone(),
+            license: Some(license.clone()),
+            is_restrictive,
+            compatibility: LicenseCompatibility::Unknown,
+            osi_status: crate::licenses::get_osi_status(&license),
+            sub_project: None,
+            found_in: Vec::new(),
+            repository,
+            declared_in: None,
+            internal: false,
+            inherited_from: None,
+            ecosystem: None,
+            suggestions: Vec::new(),
+            update_policy: None,
+            introduced_by: Vec::new(),
+            purl: None,
+            vulnerabilities: None,
+            manifest: None,
+            data_license: None,
+            project_dirs: Vec::new(),
+        }
+    })
 }
 
 // =============================================================================
@@ -89,13 +125,23 @@ pub fn analyze_ruby_licenses(file_path: &str, config: &FeludaConfig) -> Vec<Lice
 /// Every `specs:` block (under `GEM`, and any `GIT`/`PATH` sources) lists its
 /// resolved gems at 4-space indentation as `name (version)`. Lines indented
 /// deeper are that gem's own constraints and are skipped, since each such gem
-/// also appears as its own top-level spec.
+/// also appears as its own top-level spec. Each gem keeps the source section
+/// it's listed under, with that section's `remote:`.
 fn parse_gemfile_lock(content: &str) -> Vec<RubyDependency> {
     let spec_re = Regex::new(r"^    ([A-Za-z0-9._-]+) \(([^)]+)\)$").unwrap();
     let mut deps: Vec<RubyDependency> = Vec::new();
     let mut in_specs = false;
+    let mut section = "";
+    let mut remote = "";
 
     for line in content.lines() {
+        if !line.is_empty() && !line.starts_with(' ') {
+            section = line.trim();
+            remote = "";
+        } else if let Some(value) = line.strip_prefix("  remote: ") {
+            remote = value.trim();
+        }
+
         if line.trim() == "specs:" {
             in_specs = true;
             continue;
@@ -122,9 +168,15 @@ fn parse_gemfile_lock(content: &str) -> Vec<RubyDependency> {
         }
 
         if let Some(cap) = spec_re.captures(line) {
+            let source = match section {
+                "GIT" if !remote.is_empty() => GemSource::Git(remote.to_string()),
+                "PATH" if !remote.is_empty() => GemSource::Path(remote.to_string()),
+                _ => GemSource::Rubygems,
+            };
             deps.push(RubyDependency {
                 name: cap[1].to_string(),
                 version: strip_platform(&cap[2]),
+                source,
             });
         }
     }
@@ -163,7 +215,11 @@ fn parse_gemfile(content: &str) -> Vec<RubyDependency> {
             .get(2)
             .map(|m| clean_gem_version(m.as_str()))
             .unwrap_or_default();
-        deps.push(RubyDependency { name, version });
+        deps.push(RubyDependency {
+            name,
+            version,
+            source: GemSource::Rubygems,
+        });
     }
 
     deps.sort_by(|a, b| a.name.cmp(&b.name));
@@ -186,18 +242,92 @@ fn clean_gem_version(constraint: &str) -> String {
 // =============================================================================
 
 fn fetch_ruby_license(name: &str, version: &str) -> String {
-    if !version.is_empty() {
-        if let Some(license) = fetch_license_for_version(name, version) {
-            return license;
+    let registry = |name: &str, version: &str| {
+        if !version.is_empty() {
+            if let Some(license) = fetch_license_for_version(name, version) {
+                return Some(license);
+            }
         }
-    }
+        fetch_license_latest(name)
+    };
 
-    if let Some(license) = fetch_license_latest(name) {
-        return license;
-    }
+    resolve_license(
+        &LicenseQuery::new("ruby", name, version),
+        Some(&fetch_from_local_gem),
+        Some(&registry),
+    )
+    .unwrap_or_else(|| "Unknown".to_string())
+}
 
-    // Local fallback: probe the installed gem's bundled LICENSE/COPYING files.
-    fetch_from_local_gem(name, version).unwrap_or_else(|| "Unknown".to_string())
+/// A gem from a Git source: the registry's copy of a same-named gem may be a different
+/// codebase, so only installed copies and the repository itself are asked.
+fn fetch_git_gem_license(name: &str, version: &str, remote: &str) -> String {
+    resolve_license(
+        &LicenseQuery::new("ruby", name, version).with_repository(Some(remote)),
+        Some(&fetch_from_local_gem),
+        None,
+    )
+    .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// A gem from a `PATH` source lives in the repository, so its gemspec and license file are
+/// right there; nothing is published to look up.
+fn fetch_path_gem_license(name: &str, version: &str, dir: &Path) -> String {
+    let local =
+        |name: &str, _: &str| gemspec_license(dir, name).or_else(|| detect_license_in_dir(dir));
+    resolve_license(
+        &LicenseQuery::new("ruby", name, version),
+        Some(&local),
+        None,
+    )
+    .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// The `license`/`licenses` declared in `dir`'s gemspec: `<name>.gemspec`, or the only
+/// gemspec there
+fn gemspec_license(dir: &Path, name: &str) -> Option<String> {
+    let named = dir.join(format!("{name}.gemspec"));
+    let path = if named.is_file() {
+        named
+    } else {
+        let mut gemspecs = fs::read_dir(dir)
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "gemspec"));
+        let only = gemspecs.next()?;
+        if gemspecs.next().is_some() {
+            return None;
+        }
+        only
+    };
+    parse_gemspec_license(&fs::read_to_string(path).ok()?)
+}
+
+/// `spec.license = "MIT"` or `spec.licenses = ["MIT", "Apache-2.0"]`, joined as an `OR`
+/// expression like the RubyGems `licenses` field
+fn parse_gemspec_license(content: &str) -> Option<String> {
+    let assignment_re =
+        Regex::new(r#"\.licenses?\s*=\s*(\[[^\]]*\]|%w[\[(][^\])]*[\])]|["'][^"']*["'])"#).unwrap();
+    let value = assignment_re.captures(content)?.get(1)?.as_str();
+
+    let names: Vec<&str> = if let Some(words) = value.strip_prefix("%w") {
+        words[1..words.len() - 1].split_whitespace().collect()
+    } else {
+        Regex::new(r#"["']([^"']+)["']"#)
+            .unwrap()
+            .captures_iter(value)
+            .filter_map(|cap| cap.get(1))
+            .map(|m| m.as_str().trim())
+            .filter(|name| !name.is_empty())
+            .collect()
+    };
+
+    if names.is_empty() {
+        None
+    } else {
+        Some(names.join(" OR "))
+    }
 }
 
 /// Probe locally installed gems for a bundled license file.
@@ -292,7 +422,7 @@ fn fetch_license_latest(name: &str) -> Option<String> {
 /// SPDX string. Multiple licenses become an `A OR B` expression, which the
 /// compound-expression handling in `is_license_restrictive` understands.
 fn fetch_licenses_field(url: &str) -> Option<String> {
-    let response = reqwest::blocking::get(url).ok()?;
+    let response = crate::cancel::http_get(url).ok()?;
     if !response.status().is_success() {
         return None;
     }
@@ -379,6 +509,84 @@ GEM
         assert_eq!(names, vec!["bar", "foo"]);
     }
 
+    #[test]
+    fn test_parse_gemfile_lock_sources() {
+        let content = r#"GIT
+  remote: https://github.com/acme/sidekiq-pro-shim.git
+  revision: 2f1c6d0a9b
+  specs:
+    sidekiq-pro-shim (0.4.0)
+
+PATH
+  remote: engines/billing
+  specs:
+    billing (0.1.0)
+      rails (>= 7.0)
+
+GEM
+  remote: https://rubygems.org/
+  specs:
+    rails (7.1.3)
+
+PLATFORMS
+  ruby
+"#;
+        let deps = parse_gemfile_lock(content);
+        let source = |name: &str| {
+            deps.iter()
+                .find(|d| d.name == name)
+                .map(|d| d.source.clone())
+                .unwrap()
+        };
+        assert_eq!(
+            source("sidekiq-pro-shim"),
+            GemSource::Git("https://github.com/acme/sidekiq-pro-shim.git".to_string())
+        );
+        assert_eq!(
+            source("billing"),
+            GemSource::Path("engines/billing".to_string())
+        );
+        assert_eq!(source("rails"), GemSource::Rubygems);
+    }
+
+    #[test]
+    fn test_parse_gemspec_license() {
+        assert_eq!(
+            parse_gemspec_license("  spec.license = \"MIT\"\n"),
+            Some("MIT".to_string())
+        );
+        assert_eq!(
+            parse_gemspec_license("  s.licenses = ['MIT', 'Apache-2.0']\n"),
+            Some("MIT OR Apache-2.0".to_string())
+        );
+        assert_eq!(
+            parse_gemspec_license("  s.licenses = %w[BSD-2-Clause Ruby]\n"),
+            Some("BSD-2-Clause OR Ruby".to_string())
+        );
+        assert_eq!(parse_gemspec_license("  spec.name = \"billing\"\n"), None);
+    }
+
+    #[test]
+    fn test_fetch_path_gem_license_reads_gemspec() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("billing.gemspec"),
+            "Gem::Specification.new do |spec|\n  spec.name = \"billing\"\n  spec.license = \"Apache-2.0\"\nend\n",
+        )
+        .unwrap();
+        assert_eq!(
+            gemspec_license(dir.path(), "billing"),
+            Some("Apache-2.0".to_string())
+        );
+        assert_eq!(
+            gemspec_license(dir.path(), "other"),
+            Some("Apache-2.0".to_string())
+        );
+
+        let empty = tempfile::tempdir().unwrap();
+        assert_eq!(gemspec_license(empty.path(), "billing"), None);
+    }
+
     #[test]
     fn test_strip_platform() {
         assert_eq!(strip_platform("1.13.10-x86_64-linux"), "1.13.10");
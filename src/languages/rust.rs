@@ -1,12 +1,13 @@
-use cargo_metadata::{Metadata, Package, PackageId};
-use rayon::prelude::*;
+use cargo_metadata::{DependencyKind, Metadata, Node, Package, PackageId};
 use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 
+use crate::config::CargoConfig;
 use crate::debug::{log, log_debug, log_error, LogLevel};
 use crate::licenses::{
     detect_license_from_content, detect_license_in_dir, fetch_licenses_from_github,
     is_license_restrictive, LicenseCompatibility, LicenseInfo,
 };
+use crate::resolver::{resolve_all, resolve_license, LicenseQuery};
 
 /// Analyze the licenses of Rust dependencies from Cargo packages
 #[allow(dead_code)]
@@ -20,14 +21,35 @@ pub fn analyze_rust_licenses(packages: Vec<Package>) -> Vec<LicenseInfo> {
 /// In a multi-member Cargo workspace, every dependency is tagged with the workspace
 /// member(s) that pull it in, and workspace members themselves are excluded from the
 /// dep report. Single-crate projects fall through to the existing behavior.
+///
+/// Packages only reachable through dependency kinds the `[cargo]` settings (or `--no-dev`)
+/// leave out are dropped first, so dev tooling doesn't count against what ships.
 pub fn analyze_rust_licenses_with_metadata(
-    metadata: Metadata,
+    mut metadata: Metadata,
     config: &crate::config::FeludaConfig,
     no_local: bool,
 ) -> Vec<LicenseInfo> {
     let workspace_members: HashSet<PackageId> =
         metadata.workspace_members.iter().cloned().collect();
     let is_workspace = workspace_members.len() > 1;
+    let cargo = CargoConfig {
+        no_dev_deps: config.cargo.no_dev_deps || config.no_dev,
+        ..config.cargo.clone()
+    };
+
+    if let Some(shipped) = shipped_packages(&metadata, &workspace_members, &cargo) {
+        let total = metadata.packages.len();
+        metadata.packages.retain(|p| shipped.contains(&p.id));
+        if metadata.packages.len() < total {
+            log(
+                LogLevel::Info,
+                &format!(
+                    "Left out {} packages only dev- or build-dependencies pull in",
+                    total - metadata.packages.len()
+                ),
+            );
+        }
+    }
 
     log(
         LogLevel::Info,
@@ -38,15 +60,33 @@ pub fn analyze_rust_licenses_with_metadata(
         ),
     );
 
-    if !is_workspace {
+    let dependents = direct_dependents(&metadata, &workspace_members, &cargo);
+    let mut infos = if is_workspace {
+        analyze_workspace_packages(metadata, &workspace_members, &cargo, config, no_local)
+    } else {
         log(
             LogLevel::Info,
             "Single-crate project; no workspace attribution",
         );
-        return analyze_rust_licenses_with_config(metadata.packages, config, no_local);
+        analyze_rust_licenses_with_config(metadata.packages, config, no_local)
+    };
+    for info in &mut infos {
+        if let Some(direct) = dependents.get(&(info.name.clone(), info.version.clone())) {
+            info.introduced_by = direct.iter().cloned().collect();
+        }
     }
+    infos
+}
 
-    let attribution = build_workspace_attribution(&metadata, &workspace_members);
+/// Analyze a multi-member workspace's dependencies, tagging each with the members using it
+fn analyze_workspace_packages(
+    metadata: Metadata,
+    workspace_members: &HashSet<PackageId>,
+    cargo: &CargoConfig,
+    config: &crate::config::FeludaConfig,
+    no_local: bool,
+) -> Vec<LicenseInfo> {
+    let attribution = build_workspace_attribution(&metadata, workspace_members, cargo);
     log_debug("Workspace attribution map", &attribution);
 
     let dep_packages: Vec<Package> = metadata
@@ -75,10 +115,109 @@ pub fn analyze_rust_licenses_with_metadata(
     infos
 }
 
+/// Every package reachable from the workspace members through the dependency edges `cargo`
+/// keeps, the members included. `None` when cargo metadata has no resolve graph.
+fn shipped_packages(
+    metadata: &Metadata,
+    workspace_members: &HashSet<PackageId>,
+    cargo: &CargoConfig,
+) -> Option<HashSet<PackageId>> {
+    let resolve = metadata.resolve.as_ref()?;
+    let nodes_by_id: HashMap<&PackageId, &Node> =
+        resolve.nodes.iter().map(|n| (&n.id, n)).collect();
+
+    let mut reached: HashSet<PackageId> = workspace_members.clone();
+    let mut queue: VecDeque<&PackageId> = workspace_members.iter().collect();
+    while let Some(id) = queue.pop_front() {
+        let Some(node) = nodes_by_id.get(id) else {
+            continue;
+        };
+        for dep_id in kept_dependencies(node, cargo) {
+            if reached.insert(dep_id.clone()) {
+                queue.push_back(dep_id);
+            }
+        }
+    }
+    Some(reached)
+}
+
+/// A node's dependencies, minus those only reached as a kind `cargo` leaves out. Cargo
+/// before 1.41 reports no kinds, and those edges are kept.
+fn kept_dependencies<'a>(
+    node: &'a Node,
+    cargo: &'a CargoConfig,
+) -> impl Iterator<Item = &'a PackageId> + 'a {
+    node.deps
+        .iter()
+        .filter(|dep| {
+            dep.dep_kinds.is_empty()
+                || dep.dep_kinds.iter().any(|info| match info.kind {
+                    DependencyKind::Development => !cargo.no_dev_deps,
+                    DependencyKind::Build => !cargo.no_build_deps,
+                    _ => true,
+                })
+        })
+        .map(|dep| &dep.pkg)
+}
+
+/// Map each (name, version) reached only through other packages to the workspace's direct
+/// dependencies whose trees pull it in. Direct dependencies themselves get no entry.
+fn direct_dependents(
+    metadata: &Metadata,
+    workspace_members: &HashSet<PackageId>,
+    cargo: &CargoConfig,
+) -> HashMap<(String, String), BTreeSet<String>> {
+    let mut dependents: HashMap<(String, String), BTreeSet<String>> = HashMap::new();
+    let Some(resolve) = metadata.resolve.as_ref() else {
+        return dependents;
+    };
+    let nodes_by_id: HashMap<&PackageId, &Node> =
+        resolve.nodes.iter().map(|n| (&n.id, n)).collect();
+    let pkg_by_id: HashMap<&PackageId, &Package> =
+        metadata.packages.iter().map(|p| (&p.id, p)).collect();
+
+    let direct: BTreeSet<&PackageId> = workspace_members
+        .iter()
+        .filter_map(|id| nodes_by_id.get(id))
+        .flat_map(|node| kept_dependencies(node, cargo))
+        .filter(|id| !workspace_members.contains(*id))
+        .collect();
+
+    for direct_id in &direct {
+        let Some(direct_name) = pkg_by_id.get(direct_id).map(|p| p.name.to_string()) else {
+            continue;
+        };
+        let mut visited: HashSet<&PackageId> = HashSet::from([*direct_id]);
+        let mut queue: VecDeque<&PackageId> = VecDeque::from([*direct_id]);
+        while let Some(id) = queue.pop_front() {
+            let Some(node) = nodes_by_id.get(id) else {
+                continue;
+            };
+            for dep_id in kept_dependencies(node, cargo) {
+                if workspace_members.contains(dep_id) || !visited.insert(dep_id) {
+                    continue;
+                }
+                queue.push_back(dep_id);
+                if direct.contains(dep_id) {
+                    continue;
+                }
+                if let Some(pkg) = pkg_by_id.get(dep_id) {
+                    dependents
+                        .entry((pkg.name.to_string(), pkg.version.to_string()))
+                        .or_default()
+                        .insert(direct_name.clone());
+                }
+            }
+        }
+    }
+    dependents
+}
+
 /// Build a map from (dep name, version) -> set of workspace member names that depend on it.
 fn build_workspace_attribution(
     metadata: &Metadata,
     workspace_members: &HashSet<PackageId>,
+    cargo: &CargoConfig,
 ) -> HashMap<(String, String), BTreeSet<String>> {
     let mut attribution: HashMap<(String, String), BTreeSet<String>> = HashMap::new();
 
@@ -90,7 +229,7 @@ fn build_workspace_attribution(
         }
     };
 
-    let nodes_by_id: HashMap<&PackageId, &cargo_metadata::Node> =
+    let nodes_by_id: HashMap<&PackageId, &Node> =
         resolve.nodes.iter().map(|n| (&n.id, n)).collect();
     let pkg_by_id: HashMap<&PackageId, &Package> =
         metadata.packages.iter().map(|p| (&p.id, p)).collect();
@@ -111,7 +250,7 @@ fn build_workspace_attribution(
                 Some(n) => *n,
                 None => continue,
             };
-            for dep_id in &node.dependencies {
+            for dep_id in kept_dependencies(node, cargo) {
                 if !visited.insert(dep_id) {
                     continue;
                 }
@@ -164,48 +303,79 @@ pub fn analyze_rust_licenses_with_config(
         }
     };
 
-    packages
-        .par_iter()
-        .map(|package| {
-            log(
-                LogLevel::Info,
-                &format!("Analyzing package: {} ({})", package.name, package.version),
-            );
+    resolve_all(&packages, |package| {
+        log(
+            LogLevel::Info,
+            &format!("Analyzing package: {} ({})", package.name, package.version),
+        );
 
-            let license = package.license.clone().or_else(|| {
+        // Local: the license cargo metadata reported, then the crate's own manifest.
+        // Rust has no registry step; crates.io only repeats the manifest's license.
+        let local = |_: &str, _: &str| {
+            package.license.clone().or_else(|| {
                 if no_local {
                     None
                 } else {
                     get_license_from_manifest(&package.manifest_path)
                 }
-            });
+            })
+        };
+        let version = package.version.to_string();
+        let license = resolve_license(
+            &LicenseQuery::new("rust", &package.name, &version)
+                .with_repository(package.repository.as_deref()),
+            Some(&local),
+            None,
+        );
 
-            let is_restrictive = is_license_restrictive(&license, &known_licenses, config.strict);
+        let is_restrictive = is_license_restrictive(&license, &known_licenses, config.strict);
 
-            if is_restrictive {
-                log(
-                    LogLevel::Warn,
-                    &format!(
-                        "Restrictive license found: {:?} for {}",
-                        license, package.name
-                    ),
-                );
-            }
+        if is_restrictive {
+            log(
+                LogLevel::Warn,
+                &format!(
+                    "Restrictive license found: {:?} for {}",
+                    license, package.name
+                ),
+            );
+        }
 
-            LicenseInfo {
-                name: package.name.to_string(),
-                version: package.version.to_string(),
-                license,
-                is_restrictive,
-                compatibility: LicenseCompatibility::Unknown,
-                osi_status: match &package.license {
-                    Some(license) => crate::licenses::get_osi_status(license),
-                    None => crate::licenses::OsiStatus::Unknown,
-                },
-                sub_project: None,
-            }
-        })
-        .collect()
+        LicenseInfo {
+            name: package.name.to_string(),
+            version: package.version.to_string(),
+            license,
+            is_restrictive,
+            compatibility: LicenseCompatibility::Unknown,
+            osi_status: match &package.license {
+                Some(license) => crate::licenses::get_osi_status(license),
+                None => crate::licenses::OsiStatus::Unknown,
+            },
+            sub_project: None,
+            found_in: Vec::new(),
+            // A git dependency is whatever repo it's fetched from, which for a fork
+            // isn't the upstream the manifest's `repository` names
+            repository: git_source_url(package).or_else(|| package.repository.clone()),
+            declared_in: None,
+            internal: false,
+            inherited_from: None,
+            ecosystem: None,
+            suggestions: Vec::new(),
+            update_policy: None,
+            introduced_by: Vec::new(),
+            purl: None,
+            vulnerabilities: None,
+            manifest: None,
+            data_license: None,
+            project_dirs: Vec::new(),
+        }
+    })
+}
+
+/// Repository URL of a git dependency: `https://github.com/fork/serde` for a source of
+/// `git+https://github.com/fork/serde?branch=fix#abc123`
+fn git_source_url(package: &Package) -> Option<String> {
+    let url = package.source.as_ref()?.repr.strip_prefix("git+")?;
+    Some(url.split(['?', '#']).next().unwrap_or(url).to_string())
 }
 
 fn get_license_from_manifest<P: AsRef<std::path::Path>>(manifest_path: P) -> Option<String> {
@@ -449,4 +619,117 @@ license-file = "LICENSE"
         let result = get_license_from_manifest(&manifest_path);
         assert_eq!(result, Some("MIT".to_string()));
     }
+
+    #[test]
+    fn test_shipped_packages_drops_dev_and_build_only() {
+        let node = |id: &str, deps: &[(&str, &str)]| {
+            let deps: Vec<String> = deps
+                .iter()
+                .map(|(pkg, kind)| {
+                    format!(
+                        r#"{{"name":"{pkg}","pkg":"{pkg}","dep_kinds":[{{"kind":{kind},"target":null}}]}}"#
+                    )
+                })
+                .collect();
+            format!(
+                r#"{{"id":"{id}","dependencies":[],"deps":[{}],"features":[]}}"#,
+                deps.join(",")
+            )
+        };
+        let nodes = [
+            node(
+                "app",
+                &[
+                    ("serde", "null"),
+                    ("cc", "\"build\""),
+                    ("criterion", "\"dev\""),
+                ],
+            ),
+            node("serde", &[("serde_derive", "null")]),
+            node("serde_derive", &[]),
+            node("cc", &[("jobserver", "null")]),
+            node("jobserver", &[]),
+            node("criterion", &[("serde", "null"), ("plotters", "null")]),
+            node("plotters", &[]),
+        ];
+        let metadata: Metadata = serde_json::from_str(&format!(
+            r#"{{"packages":[],"workspace_members":["app"],"resolve":{{"root":"app","nodes":[{}]}},"workspace_root":"/tmp/app","target_directory":"/tmp/app/target","version":1}}"#,
+            nodes.join(",")
+        ))
+        .unwrap();
+        let members: HashSet<PackageId> = metadata.workspace_members.iter().cloned().collect();
+
+        let shipped = |cargo: CargoConfig| {
+            let mut ids: Vec<String> = shipped_packages(&metadata, &members, &cargo)
+                .unwrap()
+                .into_iter()
+                .map(|id| id.repr)
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        assert_eq!(shipped(CargoConfig::default()).len(), 7);
+        assert_eq!(
+            shipped(CargoConfig {
+                no_dev_deps: true,
+                ..Default::default()
+            }),
+            vec!["app", "cc", "jobserver", "serde", "serde_derive"]
+        );
+        assert_eq!(
+            shipped(CargoConfig {
+                no_dev_deps: true,
+                no_build_deps: true,
+                ..Default::default()
+            }),
+            vec!["app", "serde", "serde_derive"]
+        );
+    }
+
+    #[test]
+    fn test_direct_dependents_follow_kept_edges() {
+        let node = |id: &str, deps: &[&str]| {
+            let deps: Vec<String> = deps
+                .iter()
+                .map(|pkg| format!(r#"{{"name":"{pkg}","pkg":"{pkg}","dep_kinds":[]}}"#))
+                .collect();
+            format!(
+                r#"{{"id":"{id}","dependencies":[],"deps":[{}],"features":[]}}"#,
+                deps.join(",")
+            )
+        };
+        let package = |id: &str| {
+            format!(
+                r#"{{"name":"{id}","version":"1.0.0","id":"{id}","source":null,"dependencies":[],"targets":[],"features":{{}},"manifest_path":"/tmp/{id}/Cargo.toml"}}"#
+            )
+        };
+        let ids = ["app", "reqwest", "hyper", "tokio", "openssl"];
+        let nodes = [
+            node("app", &["reqwest", "tokio"]),
+            node("reqwest", &["hyper", "tokio", "openssl"]),
+            node("hyper", &["tokio"]),
+            node("tokio", &[]),
+            node("openssl", &[]),
+        ];
+        let metadata: Metadata = serde_json::from_str(&format!(
+            r#"{{"packages":[{}],"workspace_members":["app"],"resolve":{{"root":"app","nodes":[{}]}},"workspace_root":"/tmp/app","target_directory":"/tmp/app/target","version":1}}"#,
+            ids.map(package).join(","),
+            nodes.join(",")
+        ))
+        .unwrap();
+        let members: HashSet<PackageId> = metadata.workspace_members.iter().cloned().collect();
+
+        let dependents = direct_dependents(&metadata, &members, &CargoConfig::default());
+        let direct = |name: &str| {
+            dependents
+                .get(&(name.to_string(), "1.0.0".to_string()))
+                .map(|set| set.iter().cloned().collect::<Vec<_>>())
+        };
+        assert_eq!(direct("hyper"), Some(vec!["reqwest".to_string()]));
+        assert_eq!(direct("openssl"), Some(vec!["reqwest".to_string()]));
+        // tokio is a direct dependency too, so it isn't attributed to reqwest
+        assert_eq!(direct("tokio"), None);
+        assert_eq!(direct("reqwest"), None);
+    }
 }
@@ -0,0 +1,301 @@
+//! License lookups in the distro package database, shared by the C and C++ analyzers.
+//!
+//! `system` dependencies are libraries installed by the OS package manager, found through
+//! pkg-config. `pkg-config --variable=license` is rarely set, but every package database
+//! records a license, so each dependency is mapped to the package owning its `.pc` file and
+//! that package's license is read through a per-distro adapter:
+//!
+//! - **dpkg** (Debian, Ubuntu): the machine-readable `/usr/share/doc/<pkg>/copyright` (DEP-5)
+//! - **rpm** (Fedora, RHEL, openSUSE): the `LICENSE` tag
+//! - **pacman** (Arch): the `Licenses` field of `pacman -Qi`
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+use crate::debug::{log, LogLevel};
+use crate::licenses::detect_license_in_dir;
+
+/// A distro package database
+trait PackageDatabase: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// The installed package that owns `path`
+    fn owner_of(&self, path: &Path) -> Option<String>;
+
+    /// The license recorded for an installed package
+    fn license_of(&self, package: &str) -> Option<String>;
+}
+
+struct Dpkg;
+
+impl PackageDatabase for Dpkg {
+    fn name(&self) -> &'static str {
+        "dpkg"
+    }
+
+    fn owner_of(&self, path: &Path) -> Option<String> {
+        let stdout = run("dpkg-query", &["-S", &path.to_string_lossy()])?;
+        parse_dpkg_owner(&stdout)
+    }
+
+    fn license_of(&self, package: &str) -> Option<String> {
+        debian_package_license(Path::new("/usr/share/doc"), package)
+    }
+}
+
+struct Rpm;
+
+impl PackageDatabase for Rpm {
+    fn name(&self) -> &'static str {
+        "rpm"
+    }
+
+    fn owner_of(&self, path: &Path) -> Option<String> {
+        let stdout = run(
+            "rpm",
+            &["-qf", "--qf", "%{NAME}\n", &path.to_string_lossy()],
+        )?;
+        stdout.lines().next().map(str::to_string)
+    }
+
+    fn license_of(&self, package: &str) -> Option<String> {
+        let stdout = run("rpm", &["-q", "--qf", "%{LICENSE}\n", package])?;
+        let license = stdout.trim();
+        (!license.is_empty() && license != "(none)").then(|| license.to_string())
+    }
+}
+
+struct Pacman;
+
+impl PackageDatabase for Pacman {
+    fn name(&self) -> &'static str {
+        "pacman"
+    }
+
+    fn owner_of(&self, path: &Path) -> Option<String> {
+        let stdout = run("pacman", &["-Qqo", &path.to_string_lossy()])?;
+        stdout.lines().next().map(str::to_string)
+    }
+
+    fn license_of(&self, package: &str) -> Option<String> {
+        parse_pacman_licenses(&run("pacman", &["-Qi", package])?)
+    }
+}
+
+/// The package databases present on this machine, probed once
+fn package_databases() -> &'static [Box<dyn PackageDatabase>] {
+    static DATABASES: OnceLock<Vec<Box<dyn PackageDatabase>>> = OnceLock::new();
+    DATABASES.get_or_init(|| {
+        let candidates: Vec<(&str, Box<dyn PackageDatabase>)> = vec![
+            ("dpkg-query", Box::new(Dpkg)),
+            ("rpm", Box::new(Rpm)),
+            ("pacman", Box::new(Pacman)),
+        ];
+        candidates
+            .into_iter()
+            .filter(|(binary, _)| Command::new(binary).arg("--version").output().is_ok())
+            .map(|(_, database)| database)
+            .collect()
+    })
+}
+
+/// The license of a pkg-config dependency installed by the system package manager.
+///
+/// Tries the owning package in each available database (falling back to a package of the
+/// same name), then pkg-config's own `license` variable and the Debian doc directory.
+pub fn system_package_license(name: &str) -> Option<String> {
+    let pc_file = pkg_config_file(name);
+    for database in package_databases() {
+        let package = pc_file
+            .as_deref()
+            .and_then(|pc_file| database.owner_of(pc_file))
+            .unwrap_or_else(|| name.to_string());
+        if let Some(license) = database.license_of(&package) {
+            log(
+                LogLevel::Info,
+                &format!(
+                    "{} reports {license} for {package} ({name})",
+                    database.name()
+                ),
+            );
+            return Some(license);
+        }
+    }
+
+    run("pkg-config", &["--variable=license", name])
+        .map(|stdout| stdout.trim().to_string())
+        .filter(|license| !license.is_empty())
+        .or_else(|| detect_license_in_dir(&Path::new("/usr/share/doc").join(name)))
+}
+
+/// `<pcfiledir>/<name>.pc`, where pkg-config found the module
+fn pkg_config_file(name: &str) -> Option<PathBuf> {
+    let dir = run("pkg-config", &["--variable=pcfiledir", name])?;
+    let dir = dir.trim();
+    (!dir.is_empty()).then(|| Path::new(dir).join(format!("{name}.pc")))
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The license of a Debian package from `<doc_root>/<package>/copyright`: its DEP-5
+/// `License:` field, or whatever license text the doc directory holds
+pub fn debian_package_license(doc_root: &Path, package: &str) -> Option<String> {
+    let doc_dir = doc_root.join(package);
+    std::fs::read_to_string(doc_dir.join("copyright"))
+        .ok()
+        .and_then(|content| parse_dep5_license(&content))
+        .or_else(|| detect_license_in_dir(&doc_dir))
+}
+
+/// `zlib1g-dev:amd64: /usr/lib/x86_64-linux-gnu/pkgconfig/zlib.pc` → `zlib1g-dev`
+fn parse_dpkg_owner(stdout: &str) -> Option<String> {
+    let line = stdout.lines().next()?;
+    let (packages, _) = line.split_once(": ")?;
+    let package = packages.split(", ").next()?;
+    let package = package.split(':').next().unwrap_or(package).trim();
+    (!package.is_empty()).then(|| package.to_string())
+}
+
+/// The license of the `Files: *` paragraph of a DEP-5 copyright file, as SPDX
+fn parse_dep5_license(content: &str) -> Option<String> {
+    if !content.starts_with("Format:") {
+        return None;
+    }
+    content
+        .split("\n\n")
+        .find(|paragraph| paragraph.lines().any(|line| line.trim_end() == "Files: *"))?
+        .lines()
+        .find_map(|line| line.strip_prefix("License:"))
+        .map(|license| dep5_to_spdx(license.trim()))
+        .filter(|license| !license.is_empty())
+}
+
+/// DEP-5 short names (`GPL-2+`, `Expat`, `BSD-3-clause`) as SPDX, keeping `or`/`and`
+fn dep5_to_spdx(expression: &str) -> String {
+    expression
+        .split_whitespace()
+        .map(|token| match token {
+            "or" | "|" => "OR".to_string(),
+            "and" | "&" => "AND".to_string(),
+            "with" => "WITH".to_string(),
+            _ => dep5_id_to_spdx(token),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn dep5_id_to_spdx(id: &str) -> String {
+    match id {
+        "Expat" => return "MIT".to_string(),
+        "public-domain" => return "LicenseRef-public-domain".to_string(),
+        _ => {}
+    }
+    if let Some(rest) = id.strip_prefix("BSD-") {
+        return format!("BSD-{}", rest.replace("clause", "Clause"));
+    }
+    for family in ["AGPL", "LGPL", "GPL", "GFDL"] {
+        if let Some(version) = id.strip_prefix(family).and_then(|v| v.strip_prefix('-')) {
+            let (version, or_later) = match version.strip_suffix('+') {
+                Some(version) => (version, true),
+                None => (version, false),
+            };
+            let version = if version.contains('.') {
+                version.to_string()
+            } else {
+                format!("{version}.0")
+            };
+            let suffix = if or_later { "or-later" } else { "only" };
+            return format!("{family}-{version}-{suffix}");
+        }
+    }
+    id.to_string()
+}
+
+/// The `Licenses` field of `pacman -Qi` output; several licenses all apply
+fn parse_pacman_licenses(stdout: &str) -> Option<String> {
+    let value = stdout.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "Licenses").then(|| value.trim())
+    })?;
+    let licenses: Vec<&str> = value.split("  ").map(str::trim).collect();
+    match licenses.as_slice() {
+        [] | ["None"] => None,
+        [license] => Some(license.to_string()),
+        licenses => Some(licenses.join(" AND ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dpkg_owner() {
+        assert_eq!(
+            parse_dpkg_owner("zlib1g-dev:amd64: /usr/lib/x86_64-linux-gnu/pkgconfig/zlib.pc\n"),
+            Some("zlib1g-dev".to_string())
+        );
+        assert_eq!(
+            parse_dpkg_owner("libssl-dev, libssl3: /usr/include/openssl\n"),
+            Some("libssl-dev".to_string())
+        );
+        assert_eq!(parse_dpkg_owner(""), None);
+    }
+
+    #[test]
+    fn test_parse_dep5_license() {
+        let copyright = "Format: https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/
+Upstream-Name: zlib
+
+Files: *
+Copyright: 1995-2013 Jean-loup Gailly and Mark Adler
+License: Zlib
+
+Files: debian/*
+License: GPL-2+
+";
+        assert_eq!(parse_dep5_license(copyright), Some("Zlib".to_string()));
+        assert_eq!(
+            parse_dep5_license("This package was debianized by..."),
+            None
+        );
+
+        let dual = "Format: https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/
+
+Files: *
+License: GPL-2+ or Expat
+";
+        assert_eq!(
+            parse_dep5_license(dual),
+            Some("GPL-2.0-or-later OR MIT".to_string())
+        );
+    }
+
+    #[test]
+    fn test_dep5_to_spdx() {
+        assert_eq!(dep5_to_spdx("LGPL-2.1+"), "LGPL-2.1-or-later");
+        assert_eq!(dep5_to_spdx("GPL-3"), "GPL-3.0-only");
+        assert_eq!(dep5_to_spdx("BSD-3-clause"), "BSD-3-Clause");
+        assert_eq!(dep5_to_spdx("Apache-2.0"), "Apache-2.0");
+    }
+
+    #[test]
+    fn test_parse_pacman_licenses() {
+        let info = "Name            : openssl\nVersion         : 3.2.1-1\nLicenses        : Apache-2.0\nGroups          : None\n";
+        assert_eq!(parse_pacman_licenses(info), Some("Apache-2.0".to_string()));
+
+        let info = "Name            : curl\nLicenses        : MIT  custom:curl\n";
+        assert_eq!(
+            parse_pacman_licenses(info),
+            Some("MIT AND custom:curl".to_string())
+        );
+        assert_eq!(parse_pacman_licenses("Licenses        : None\n"), None);
+    }
+}
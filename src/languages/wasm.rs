@@ -0,0 +1,572 @@
+//! WebAssembly component dependencies: the WIT packages a component builds against
+//!
+//! `wkg.lock` (wasm-pkg-tools) pins each package's registry and version. `wit/deps.toml`
+//! (wit-deps) fetches packages from archive URLs, usually GitHub release tarballs, into
+//! `wit/deps/`. Without either, the versioned `use`/`import` references in `wit/*.wit` name
+//! the packages. Registries publish packages as OCI artifacts or through a warg log; OCI
+//! manifests carry the license as the `org.opencontainers.image.licenses` annotation.
+
+use regex::Regex;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use crate::config::FeludaConfig;
+use crate::debug::{log, log_error, record_parse_warning, LogLevel};
+use crate::image::registry::{ImageReference, RegistryClient};
+use crate::licenses::{
+    detect_license_in_dir, fetch_licenses_from_github, is_license_restrictive,
+    LicenseCompatibility, LicenseInfo,
+};
+use crate::resolver::{resolve_all, resolve_license, LicenseQuery, Lookup};
+
+/// Registry a namespace's packages come from when `wkg.lock` doesn't say
+const DEFAULT_REGISTRIES: [(&str, &str); 1] = [("wasi", "wasi.dev")];
+
+#[derive(Debug, Clone, PartialEq)]
+struct WasmDependency {
+    /// WIT package name, `namespace:name`
+    name: String,
+    version: String,
+    /// Registry the package is fetched from (`wasi.dev`, `ghcr.io`, ...)
+    registry: Option<String>,
+    /// Archive URL wit-deps fetches the package from
+    url: Option<String>,
+    /// Directory under `wit/deps/` holding the fetched package
+    local_dir: Option<String>,
+}
+
+/// How a registry serves packages, from its `/.well-known/wasm-pkg/registry.json`
+#[derive(Debug, Clone, PartialEq)]
+enum RegistryProtocol {
+    /// OCI registry host, and the prefix package repositories sit under
+    Oci { registry: String, prefix: String },
+    /// A warg registry; its signed package logs aren't queried
+    Warg,
+}
+
+/// Whether `dir` holds WIT packages (`wit/*.wit` or a `wit/deps.toml`)
+pub fn has_wit_packages(dir: &Path) -> bool {
+    let wit_dir = dir.join("wit");
+    wit_dir.join("deps.toml").is_file()
+        || fs::read_dir(&wit_dir).is_ok_and(|entries| {
+            entries
+                .flatten()
+                .any(|entry| entry.path().extension().is_some_and(|ext| ext == "wit"))
+        })
+}
+
+pub fn analyze_wasm_licenses(project_dir: &Path, config: &FeludaConfig) -> Vec<LicenseInfo> {
+    log(
+        LogLevel::Info,
+        &format!(
+            "Analyzing WebAssembly component dependencies in: {}",
+            project_dir.display()
+        ),
+    );
+
+    let deps = collect_wasm_dependencies(project_dir);
+    if deps.is_empty() {
+        log(
+            LogLevel::Warn,
+            "No WebAssembly component dependencies found",
+        );
+        return Vec::new();
+    }
+
+    log(
+        LogLevel::Info,
+        &format!("Found {} WIT package dependencies", deps.len()),
+    );
+
+    let known_licenses = match fetch_licenses_from_github() {
+        Ok(licenses) => licenses,
+        Err(err) => {
+            log_error("Failed to fetch licenses from GitHub", &err);
+            HashMap::new()
+        }
+    };
+
+    // One metadata lookup per registry, not per package
+    let mut protocols: HashMap<String, Option<RegistryProtocol>> = HashMap::new();
+    for registry in deps.iter().filter_map(|dep| dep.registry.as_deref()) {
+        if !protocols.contains_key(registry) {
+            protocols.insert(registry.to_string(), fetch_registry_protocol(registry));
+        }
+    }
+    let deps_dir = project_dir.join("wit").join("deps");
+
+    resolve_all(&deps, |dep| {
+        let protocol = dep
+            .registry
+            .as_ref()
+            .and_then(|registry| protocols.get(registry).cloned().flatten());
+        let license = fetch_wasm_license(dep, protocol.as_ref(), &deps_dir);
+        let is_restrictive =
+            is_license_restrictive(&Some(license.clone()), &known_licenses, config.strict);
+
+        LicenseInfo {
+            name: dep.name.clone(),
+            version: dep.version.clone(),
+            license: Some(license.clone()),
+            is_restrictive,
+            compatibility: LicenseCompatibility::Unknown,
+            osi_status: crate::licenses::get_osi_status(&license),
+            sub_project: None,
+            found_in: Vec::new(),
+            repository: dep.url.as_deref().and_then(github_repository),
+            declared_in: None,
+            internal: false,
+            inherited_from: None,
+            ecosystem: None,
+            suggestions: Vec::new(),
+            update_policy: None,
+            introduced_by: Vec::new(),
+            purl: None,
+            vulnerabilities: None,
+            manifest: None,
+            data_license: None,
+            project_dirs: Vec::new(),
+        }
+    })
+}
+
+/// `wkg.lock` when present, else `wit/deps.toml`, else the references in `wit/*.wit`
+fn collect_wasm_dependencies(project_dir: &Path) -> Vec<WasmDependency> {
+    let wkg_lock = project_dir.join("wkg.lock");
+    if let Ok(content) = fs::read_to_string(&wkg_lock) {
+        match parse_wkg_lock(&content) {
+            Ok(deps) => return deps,
+            Err(reason) => record_parse_warning(wkg_lock.display().to_string(), reason),
+        }
+    }
+
+    let wit_dir = project_dir.join("wit");
+    let deps_toml = wit_dir.join("deps.toml");
+    if let Ok(content) = fs::read_to_string(&deps_toml) {
+        match parse_wit_deps_toml(&content, &wit_dir.join("deps")) {
+            Ok(deps) => return deps,
+            Err(reason) => record_parse_warning(deps_toml.display().to_string(), reason),
+        }
+    }
+
+    let sources: Vec<String> = fs::read_dir(&wit_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "wit"))
+                .filter_map(|path| fs::read_to_string(path).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    parse_wit_references(&sources)
+}
+
+// =============================================================================
+// WKG.LOCK / WIT-DEPS / WIT PARSING
+// =============================================================================
+
+/// The `[[packages]]` of a `wkg.lock`, one dependency per locked version
+fn parse_wkg_lock(content: &str) -> Result<Vec<WasmDependency>, String> {
+    let lock: toml::Value =
+        toml::from_str(content).map_err(|e| format!("invalid wkg.lock: {e}"))?;
+
+    let mut deps = Vec::new();
+    for package in lock
+        .get("packages")
+        .and_then(|packages| packages.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let Some(name) = package.get("name").and_then(|name| name.as_str()) else {
+            continue;
+        };
+        let registry = package
+            .get("registry")
+            .and_then(|registry| registry.as_str())
+            .map(str::to_string)
+            .or_else(|| default_registry(name));
+        for locked in package
+            .get("versions")
+            .and_then(|versions| versions.as_array())
+            .into_iter()
+            .flatten()
+        {
+            let Some(version) = locked.get("version").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            deps.push(WasmDependency {
+                name: name.to_string(),
+                version: version.to_string(),
+                registry: registry.clone(),
+                url: None,
+                local_dir: None,
+            });
+        }
+    }
+    Ok(deps)
+}
+
+/// The entries of a wit-deps `deps.toml`: `name = "<url>"` or `name = { url = "<url>" }`.
+/// Path entries are the project's own packages. The WIT package name and version come from
+/// the fetched copy in `deps_dir` when there is one, else from the key and the archive URL.
+fn parse_wit_deps_toml(content: &str, deps_dir: &Path) -> Result<Vec<WasmDependency>, String> {
+    let manifest: BTreeMap<String, toml::Value> =
+        toml::from_str(content).map_err(|e| format!("invalid deps.toml: {e}"))?;
+    let version_re =
+        Regex::new(r"v?(\d+\.\d+\.\d+[0-9A-Za-z.\-+]*?)(?:\.tar\.gz|\.zip|/|$)").unwrap();
+
+    Ok(manifest
+        .into_iter()
+        .filter_map(|(key, entry)| {
+            let url = match &entry {
+                toml::Value::String(url) => url.clone(),
+                toml::Value::Table(table) => table.get("url")?.as_str()?.to_string(),
+                _ => return None,
+            };
+            let fetched = fetched_package(&deps_dir.join(&key));
+            let (name, version) = fetched.unwrap_or_else(|| {
+                let version = version_re
+                    .captures(&url)
+                    .map(|cap| cap[1].to_string())
+                    .unwrap_or_default();
+                (key.clone(), version)
+            });
+            Some(WasmDependency {
+                registry: None,
+                url: Some(url),
+                local_dir: Some(key),
+                name,
+                version,
+            })
+        })
+        .collect())
+}
+
+/// `package ns:name@version;` declared by the WIT files of a fetched package
+fn fetched_package(dir: &Path) -> Option<(String, String)> {
+    let package_re = package_declaration_re();
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "wit"))
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .find_map(|content| {
+            let cap = package_re.captures(&content)?;
+            Some((cap[1].to_string(), cap[2].to_string()))
+        })
+}
+
+fn package_declaration_re() -> Regex {
+    Regex::new(r"(?m)^\s*package\s+([a-z][a-z0-9-]*:[a-z][a-z0-9-:]*)@([0-9][0-9A-Za-z.\-+]*)\s*;")
+        .unwrap()
+}
+
+/// Foreign packages the WIT sources reference with a version, e.g.
+/// `import wasi:http/incoming-handler@0.2.0;`. Unversioned references can't be resolved.
+fn parse_wit_references(sources: &[String]) -> Vec<WasmDependency> {
+    let reference_re = Regex::new(
+        r"\b(?:use|import|export|include)\s+([a-z][a-z0-9-]*:[a-z][a-z0-9-]*)/[^@\s;{]+@([0-9][0-9A-Za-z.\-+]*)",
+    )
+    .unwrap();
+    let package_re = package_declaration_re();
+    let own: Vec<String> = sources
+        .iter()
+        .filter_map(|source| package_re.captures(source).map(|cap| cap[1].to_string()))
+        .collect();
+
+    let mut deps: Vec<WasmDependency> = sources
+        .iter()
+        .flat_map(|source| reference_re.captures_iter(source))
+        .filter(|cap| !own.contains(&cap[1].to_string()))
+        .map(|cap| WasmDependency {
+            name: cap[1].to_string(),
+            version: cap[2].to_string(),
+            registry: default_registry(&cap[1]),
+            url: None,
+            local_dir: None,
+        })
+        .collect();
+    deps.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+    deps.dedup_by(|a, b| a.name == b.name && a.version == b.version);
+    deps
+}
+
+fn default_registry(package: &str) -> Option<String> {
+    let namespace = package.split(':').next()?;
+    DEFAULT_REGISTRIES
+        .iter()
+        .find(|(ns, _)| *ns == namespace)
+        .map(|(_, registry)| registry.to_string())
+}
+
+/// `https://github.com/<owner>/<repo>` for a GitHub archive or release URL
+fn github_repository(url: &str) -> Option<String> {
+    let path = url.strip_prefix("https://github.com/")?;
+    let mut segments = path.split('/');
+    let owner = segments.next().filter(|s| !s.is_empty())?;
+    let repo = segments.next().filter(|s| !s.is_empty())?;
+    Some(format!(
+        "https://github.com/{owner}/{}",
+        repo.trim_end_matches(".git")
+    ))
+}
+
+// =============================================================================
+// REGISTRY LICENSE LOOKUP
+// =============================================================================
+
+fn fetch_wasm_license(
+    dep: &WasmDependency,
+    protocol: Option<&RegistryProtocol>,
+    deps_dir: &Path,
+) -> String {
+    let local = |_: &str, _: &str| {
+        dep.local_dir
+            .as_deref()
+            .and_then(|dir| detect_license_in_dir(&deps_dir.join(dir)))
+    };
+    let oci = |name: &str, version: &str| match protocol {
+        Some(RegistryProtocol::Oci { registry, prefix }) => {
+            fetch_license_from_oci(registry, prefix, name, version)
+        }
+        Some(RegistryProtocol::Warg) => {
+            log(
+                LogLevel::Info,
+                &format!("{name} comes from a warg registry, which isn't queried"),
+            );
+            None
+        }
+        None => None,
+    };
+    let registry: Option<Lookup> = protocol.map(|_| &oci as Lookup);
+    let repository = dep.url.as_deref().and_then(github_repository);
+
+    resolve_license(
+        &LicenseQuery::new("wasm", &dep.name, &dep.version).with_repository(repository.as_deref()),
+        Some(&local),
+        registry,
+    )
+    .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// Ask a registry how it serves packages
+fn fetch_registry_protocol(registry: &str) -> Option<RegistryProtocol> {
+    let url = format!("https://{registry}/.well-known/wasm-pkg/registry.json");
+    log(
+        LogLevel::Info,
+        &format!("Fetching registry metadata: {url}"),
+    );
+
+    let response = crate::cancel::http_get(&url).ok()?;
+    if !response.status().is_success() {
+        // A registry without metadata is an OCI registry serving packages at its root
+        return Some(RegistryProtocol::Oci {
+            registry: registry.to_string(),
+            prefix: String::new(),
+        });
+    }
+    let json: Value = response.json().ok()?;
+    registry_protocol(registry, &json)
+}
+
+fn registry_protocol(registry: &str, metadata: &Value) -> Option<RegistryProtocol> {
+    let oci = || {
+        let oci = &metadata["oci"];
+        RegistryProtocol::Oci {
+            registry: oci["registry"].as_str().unwrap_or(registry).to_string(),
+            prefix: oci["namespacePrefix"].as_str().unwrap_or("").to_string(),
+        }
+    };
+    match metadata["preferredProtocol"].as_str() {
+        Some("oci") => Some(oci()),
+        Some("warg") => Some(RegistryProtocol::Warg),
+        Some(_) => None,
+        None if metadata.get("oci").is_some() => Some(oci()),
+        None if metadata.get("warg").is_some() => Some(RegistryProtocol::Warg),
+        None => None,
+    }
+}
+
+/// The license annotation on a package version's OCI manifest. `ns:name` is published as
+/// the repository `<prefix><ns>/<name>`, tagged with the version.
+fn fetch_license_from_oci(
+    registry: &str,
+    prefix: &str,
+    name: &str,
+    version: &str,
+) -> Option<String> {
+    let (namespace, package) = name.split_once(':')?;
+    let reference = ImageReference {
+        registry: registry.to_string(),
+        repository: format!("{prefix}{namespace}/{}", package.replace(':', "/")),
+        reference: version.to_string(),
+    };
+    log(
+        LogLevel::Info,
+        &format!("Fetching component manifest: {reference}"),
+    );
+
+    let mut client = RegistryClient::new(&reference).ok()?;
+    let manifest = client.manifest(&reference.reference).ok()?;
+    manifest_license(&manifest)
+}
+
+fn manifest_license(manifest: &Value) -> Option<String> {
+    manifest["annotations"]["org.opencontainers.image.licenses"]
+        .as_str()
+        .map(str::trim)
+        .filter(|license| !license.is_empty())
+        .map(str::to_string)
+}
+
+// TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_wkg_lock() {
+        let lock = r#"# This file is automatically generated.
+version = 1
+
+[[packages]]
+name = "wasi:http"
+registry = "wasi.dev"
+
+[[packages.versions]]
+requirement = "=0.2.0"
+version = "0.2.0"
+digest = "sha256:5a568e6e2d60c1ce51220e1833cdd5b88db9f615720edc762a9b4a6f36b383bd"
+
+[[packages]]
+name = "wasi:io"
+
+[[packages.versions]]
+requirement = "^0.2"
+version = "0.2.1"
+digest = "sha256:abc"
+
+[[packages]]
+name = "acme:payments"
+registry = "ghcr.io"
+
+[[packages.versions]]
+requirement = "=1.4.0"
+version = "1.4.0"
+digest = "sha256:def"
+"#;
+        let deps = parse_wkg_lock(lock).unwrap();
+        assert_eq!(deps.len(), 3);
+        assert_eq!(deps[0].name, "wasi:http");
+        assert_eq!(deps[0].version, "0.2.0");
+        assert_eq!(deps[0].registry.as_deref(), Some("wasi.dev"));
+        // The wasi namespace defaults to wasi.dev
+        assert_eq!(deps[1].registry.as_deref(), Some("wasi.dev"));
+        assert_eq!(deps[2].registry.as_deref(), Some("ghcr.io"));
+
+        assert!(parse_wkg_lock("[[packages]").is_err());
+    }
+
+    #[test]
+    fn test_parse_wit_deps_toml() {
+        let temp_dir = TempDir::new().unwrap();
+        let deps_dir = temp_dir.path();
+        fs::create_dir_all(deps_dir.join("http")).unwrap();
+        fs::write(
+            deps_dir.join("http/types.wit"),
+            "package wasi:http@0.2.0;\n\ninterface types {}\n",
+        )
+        .unwrap();
+
+        let deps_toml = r#"
+http = "https://github.com/WebAssembly/wasi-http/archive/v0.2.0.tar.gz"
+logging = { url = "https://github.com/WebAssembly/wasi-logging/archive/v0.2.0-draft.tar.gz" }
+local = { path = "../shared/wit" }
+"#;
+        let deps = parse_wit_deps_toml(deps_toml, deps_dir).unwrap();
+        assert_eq!(deps.len(), 2);
+
+        let http = &deps[0];
+        assert_eq!(http.name, "wasi:http");
+        assert_eq!(http.version, "0.2.0");
+        assert_eq!(http.local_dir.as_deref(), Some("http"));
+        assert_eq!(
+            http.url.as_deref().and_then(github_repository).as_deref(),
+            Some("https://github.com/WebAssembly/wasi-http")
+        );
+
+        // Not fetched yet: the key and the archive URL stand in
+        let logging = &deps[1];
+        assert_eq!(logging.name, "logging");
+        assert_eq!(logging.version, "0.2.0-draft");
+    }
+
+    #[test]
+    fn test_parse_wit_references() {
+        let world = r#"package acme:app@0.1.0;
+
+world app {
+    import wasi:http/outgoing-handler@0.2.0;
+    import wasi:keyvalue/store@0.2.0-draft;
+    export wasi:http/incoming-handler@0.2.0;
+    import acme:app/internal@0.1.0;
+    use wasi:clocks/wall-clock.{datetime};
+}
+"#;
+        let deps = parse_wit_references(&[world.to_string()]);
+        let found: Vec<(&str, &str)> = deps
+            .iter()
+            .map(|dep| (dep.name.as_str(), dep.version.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![("wasi:http", "0.2.0"), ("wasi:keyvalue", "0.2.0-draft")]
+        );
+        assert_eq!(deps[0].registry.as_deref(), Some("wasi.dev"));
+    }
+
+    #[test]
+    fn test_registry_protocol_and_manifest_license() {
+        let wasi = json!({
+            "preferredProtocol": "oci",
+            "oci": {"registry": "ghcr.io", "namespacePrefix": "webassembly/"}
+        });
+        assert_eq!(
+            registry_protocol("wasi.dev", &wasi),
+            Some(RegistryProtocol::Oci {
+                registry: "ghcr.io".to_string(),
+                prefix: "webassembly/".to_string()
+            })
+        );
+        let warg = json!({"warg": {"url": "https://registry.example.com"}});
+        assert_eq!(
+            registry_protocol("example.com", &warg),
+            Some(RegistryProtocol::Warg)
+        );
+
+        let manifest = json!({
+            "schemaVersion": 2,
+            "annotations": {"org.opencontainers.image.licenses": "Apache-2.0 WITH LLVM-exception"}
+        });
+        assert_eq!(
+            manifest_license(&manifest),
+            Some("Apache-2.0 WITH LLVM-exception".to_string())
+        );
+        assert_eq!(manifest_license(&json!({"schemaVersion": 2})), None);
+    }
+
+    #[test]
+    fn test_has_wit_packages() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(!has_wit_packages(temp_dir.path()));
+        fs::create_dir(temp_dir.path().join("wit")).unwrap();
+        assert!(!has_wit_packages(temp_dir.path()));
+        fs::write(temp_dir.path().join("wit/world.wit"), "package a:b;\n").unwrap();
+        assert!(has_wit_packages(temp_dir.path()));
+    }
+}
@@ -0,0 +1,1742 @@
+//! Feluda: detect the licenses of a project's dependencies and flag the ones that restrict
+//! its use
+//!
+//! The `feluda` binary is the main interface. The library runs the same scan from Rust, for
+//! tools that check licenses without shelling out:
+//!
+//! ```no_run
+//! use feluda::{scan, ScanOptions};
+//!
+//! let mut options = ScanOptions::default();
+//! options.project_license = Some("MIT".to_string());
+//! options.offline = true;
+//!
+//! let result = scan("path/to/project", options)?;
+//! for dep in result.restrictive() {
+//!     println!("{} {}: {}", dep.name, dep.version, dep.get_license());
+//! }
+//! if result.has_restrictive() || result.has_incompatible() {
+//!     std::fs::write("licenses.json", result.to_json()?)?;
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! The items re-exported here are the library API and follow semver. The modules are the
+//! command line's internals and may change in any release.
+
+mod accept;
+mod api;
+mod artifact_scan;
+mod baseline;
+mod bench;
+mod bundle_scan;
+mod cache;
+mod cancel;
+mod cli;
+mod config;
+mod conflicts;
+mod data_licenses;
+mod debug;
+mod detect;
+mod diff;
+mod doctor;
+mod dry_run;
+mod forks;
+mod generate;
+mod history;
+mod ignore;
+mod image;
+mod init;
+mod languages;
+mod license_corpus;
+mod license_list;
+mod license_texts;
+mod licenses;
+mod manifest;
+mod notices;
+mod obligations;
+mod osv;
+mod outputs;
+mod parser;
+mod partial_scan;
+mod purl;
+mod rate_limit;
+mod report_archive;
+mod report_metadata;
+mod reporter;
+mod resolver;
+mod result_file;
+mod revision;
+mod rollup;
+mod sbom;
+mod source_scan;
+mod spdx;
+mod starlark;
+mod stdin_scan;
+mod suggestions;
+mod table;
+mod updates;
+mod utils;
+mod vendor_scan;
+mod watch;
+
+pub use api::{scan, ScanOptions, ScanResult};
+pub use config::{
+    CargoConfig, CargoLockMode, DependencyConfig, FeludaConfig, GithubConfig, IgnoreDependency,
+    InternalConfig, LicenseConfig, LicensePolicyList, LicenseRefClass, LicenseRule, PolicyVerdict,
+    ResolverConfig, SpecialLicensePolicy,
+};
+pub use data_licenses::DataLicenseClass;
+pub use debug::{FeludaError, FeludaResult, ParseWarning, ResolutionError};
+pub use ignore::IgnoredDependency;
+pub use licenses::{
+    is_license_compatible, LicenseCompatibility, LicenseInfo, OsiLicenseInfo, OsiStatus,
+};
+pub use manifest::ManifestLocation;
+pub use outputs::{OutputSink, SinkFormat};
+pub use partial_scan::PartialScan;
+pub use report_archive::load_report;
+pub use report_metadata::{CacheFreshness, GitState, ReportMetadata, ResolverUsage};
+pub use resolver::ResolverSource;
+pub use suggestions::FixSuggestion;
+pub use updates::UpdatePolicy;
+
+use accept::handle_accept_command;
+use clap::Parser;
+use cli::{print_version_info, Cli, Commands};
+use debug::{
+    log, log_debug, set_debug_mode, take_parse_warnings, take_resolution_errors, LogLevel,
+};
+use generate::handle_generate_command;
+use init::handle_init_command;
+use licenses::{detect_project_license, set_github_api_url, set_github_token};
+use parser::parse_root_with_config;
+use reporter::{generate_report, ReportConfig};
+use sbom::handle_sbom_command;
+use sbom::validate::handle_sbom_validate_command;
+use std::env;
+use std::path::Path;
+use std::process;
+use std::time::Duration;
+use table::App;
+use tempfile::TempDir;
+use utils::clone_repository;
+
+/// Configuration for the check command
+#[derive(Debug, Clone)]
+struct CheckConfig {
+    path: String,
+    json: bool,
+    yaml: bool,
+    verbose: bool,
+    restrictive: bool,
+    gui: bool,
+    language: Option<String>,
+    ci_format: Option<cli::CiFormat>,
+    output_file: Option<String>,
+    output_dir: Option<String>,
+    keep_reports: Option<usize>,
+    compress: Option<cli::ReportCompression>,
+    chart: bool,
+    fail_on_restrictive: bool,
+    incompatible: bool,
+    fail_on_incompatible: bool,
+    fail_on_resolution_errors: bool,
+    osi_only: bool,
+    project_license: Option<String>,
+    gist: bool,
+    osi: Option<cli::OsiFilter>,
+    strict: bool,
+    no_local: bool,
+    no_vendor_scan: bool,
+    /// Build output directories scanned for embedded licenses (`--artifacts`)
+    artifacts: Vec<String>,
+    /// Inherit upstream licenses for unlicensed GitHub forks (`--resolve-forks`)
+    resolve_forks: bool,
+    /// Look up known vulnerabilities on OSV.dev (`--vulnerabilities`)
+    vulnerabilities: bool,
+    strict_parse: bool,
+    cargo_lock: Option<config::CargoLockMode>,
+    no_dev: bool,
+    /// Leave transitive dependencies out (`--direct-only`)
+    direct_only: bool,
+    /// Resolve Go's full module graph (`--include-transitive`)
+    include_transitive: bool,
+    /// Check at most this many dependencies (`--limit`)
+    limit: Option<usize>,
+    /// Check this percentage of the dependencies (`--sample`)
+    sample: Option<u8>,
+    /// Cargo resolution flags (`--no-dev-deps`, `--no-build-deps`, `--features`, ...)
+    cargo: config::CargoConfig,
+    skip: Vec<String>,
+    changed_since: Option<String>,
+    /// Repository whose GitHub dependency graph replaces local parsing (`--github-sbom`)
+    github_sbom: Option<String>,
+    /// Previous report the TUI diffs against, or whose findings don't fail the build
+    /// (`--baseline`)
+    baseline: Option<String>,
+    /// Compare with the baseline despite a lockfile state mismatch (`--force`)
+    force_baseline: bool,
+    /// Where to record this scan as a baseline (`--write-baseline`)
+    write_baseline: Option<String>,
+    /// Container image whose contents replace the project tree (`feluda image`)
+    image: Option<image::ImageRequest>,
+    /// Write the results as an SBOM instead of a report (`--sbom`)
+    sbom: Option<cli::SbomExport>,
+    /// Write the results as a standalone document instead of a report (`--output-format`)
+    output_format: Option<cli::OutputFormat>,
+    /// Name of the scanned project, for documents that describe it
+    project_name: String,
+    /// Directory the dependencies' license texts are written to (`--licenses-dir`)
+    licenses_dir: Option<String>,
+    /// File the license obligations are written to as tasks (`--obligations`)
+    obligations: Option<String>,
+    /// Repository the obligations are opened as GitHub issues in (`--obligations-issues`)
+    obligations_issues: Option<String>,
+    /// Repository whose open update bot pull requests are checked (`--pending-updates`)
+    pending_updates: Option<String>,
+    /// File the machine-readable scan result is written to (`--result-file`)
+    result_file: Option<String>,
+}
+
+/// The `feluda` command line, run by the binary. Not part of the library API.
+#[doc(hidden)]
+pub fn main() {
+    // Check if --version or -V is passed alone
+    let args: Vec<String> = env::args().collect();
+    if args.len() == 2 && (args[1] == "--version" || args[1] == "-V") {
+        print_version_info();
+        return;
+    }
+
+    match run() {
+        Ok(_) => {}
+        Err(e) => {
+            e.log();
+            process::exit(1);
+        }
+    }
+}
+
+fn run() -> FeludaResult<()> {
+    let args = Cli::parse().expand_scan_command();
+
+    // Debug mode
+    if args.debug {
+        set_debug_mode(true);
+        log(
+            LogLevel::Info,
+            &format!("Starting Feluda with args: {args:?}"),
+        );
+    }
+
+    // Set GitHub API token for authenticated requests. `[github]` in the config can point
+    // lookups at a GitHub Enterprise Server; --github-token/GITHUB_TOKEN win over its token.
+    let github_config = config::load_config()
+        .map(|config| config.github)
+        .unwrap_or_default();
+    set_github_token(args.github_token.clone().or(github_config.token));
+    set_github_api_url(github_config.api);
+    cancel::set_offline(args.offline);
+
+    // --dry-run stops here, before anything is cloned or fetched
+    if args.dry_run && args.is_default_command() {
+        return dry_run::print_plan(&args, Path::new(&args.path));
+    }
+
+    // Handle repository cloning if --repo is provided
+    // With --github-sbom the dependency graph comes from the GitHub API, so nothing is cloned
+    let clone_url = args.repo.clone().filter(|_| !args.github_sbom);
+    let (analysis_path, _temp_dir) = match &clone_url {
+        // --stdin: the piped file is scanned from a scratch directory
+        _ if args.stdin && args.is_default_command() => {
+            let temp_dir = TempDir::new().map_err(|e| {
+                FeludaError::TempDir(format!("Failed to create temporary directory: {e}"))
+            })?;
+            let format = args.stdin_format.as_deref().unwrap_or_default();
+            stdin_scan::materialize_stdin(format, temp_dir.path())
+                .inspect_err(|e| eprintln!("❌ {e}"))?;
+            (temp_dir.path().to_path_buf(), Some(temp_dir))
+        }
+        Some(repo_url) => {
+            log(
+                LogLevel::Info,
+                &format!("Attempting to clone repository: {repo_url}"),
+            );
+            let temp_dir = TempDir::new().map_err(|e| {
+                FeludaError::TempDir(format!("Failed to create temporary directory: {e}"))
+            })?;
+            let repo_path = temp_dir.path();
+
+            // Clone the repository
+            if let Err(e) = clone_repository(&args, repo_path) {
+                log(LogLevel::Error, &format!("Repository cloning failed: {e}"));
+                return Err(e);
+            }
+            log(
+                LogLevel::Info,
+                &format!("Repository cloned to: {}", repo_path.display()),
+            );
+            (repo_path.to_path_buf(), Some(temp_dir))
+        }
+        None => {
+            let path = Path::new(&args.path).to_path_buf();
+            log(
+                LogLevel::Info,
+                &format!("Using local path for analysis: {}", path.display()),
+            );
+            (path, None)
+        }
+    };
+
+    // --rev: scan a revision checked out next to the working tree (or the clone)
+    let source_path = analysis_path.clone();
+    let (analysis_path, _rev_dir) = match &args.rev {
+        Some(rev) if args.is_default_command() => {
+            let rev_dir = TempDir::new().map_err(|e| {
+                FeludaError::TempDir(format!("Failed to create temporary directory: {e}"))
+            })?;
+            let path = revision::checkout_revision(&analysis_path, rev, rev_dir.path())
+                .inspect_err(|e| eprintln!("❌ {e}"))?;
+            (path, Some(rev_dir))
+        }
+        _ => (analysis_path, None),
+    };
+
+    log(
+        LogLevel::Info,
+        &format!("Analysing project at: {}", analysis_path.display()),
+    );
+
+    // Handle the command based on whether a subcommand was provided
+    if args.is_default_command() {
+        cancel::install(args.timeout.map(Duration::from_secs));
+
+        // Default behavior: license analysis
+        let cargo_lock = args.cargo_lock();
+        let cargo = args.cargo_options();
+        let config = CheckConfig {
+            path: analysis_path.to_string_lossy().to_string(),
+            json: args.json,
+            yaml: args.yaml,
+            verbose: args.verbose,
+            restrictive: args.restrictive,
+            gui: args.gui,
+            language: args.language,
+            ci_format: args.ci_format,
+            output_file: args.output_file,
+            output_dir: args.output_dir,
+            keep_reports: args.keep_reports.map(|keep| keep as usize),
+            compress: args.compress,
+            chart: args.chart,
+            fail_on_restrictive: args.fail_on_restrictive,
+            incompatible: args.incompatible,
+            fail_on_incompatible: args.fail_on_incompatible,
+            fail_on_resolution_errors: args.fail_on_resolution_errors,
+            osi_only: args.osi_only,
+            project_license: args.project_license,
+            gist: args.gist,
+            osi: args.osi,
+            strict: args.strict,
+            no_local: args.no_local,
+            no_vendor_scan: args.no_vendor_scan,
+            cargo_lock,
+            artifacts: args.artifacts,
+            resolve_forks: args.resolve_forks,
+            vulnerabilities: args.vulnerabilities,
+            strict_parse: args.strict_parse,
+            no_dev: args.no_dev,
+            direct_only: args.direct_only,
+            include_transitive: args.include_transitive,
+            limit: args.limit.map(|limit| limit as usize),
+            sample: args.sample,
+            cargo,
+            skip: args.skip,
+            changed_since: args.changed_since,
+            github_sbom: args.repo.clone().filter(|_| args.github_sbom),
+            baseline: args.baseline.clone(),
+            force_baseline: args.force,
+            write_baseline: args.write_baseline.clone(),
+            image: None,
+            sbom: args.sbom,
+            output_format: args.output_format,
+            project_name: if args.stdin {
+                "stdin".to_string()
+            } else {
+                project_name(args.repo.as_deref(), &source_path)
+            },
+            licenses_dir: args.licenses_dir,
+            obligations: args.obligations,
+            obligations_issues: args.obligations_issues,
+            pending_updates: args.pending_updates,
+            result_file: args.result_file,
+        };
+        handle_check_command(config)
+    } else {
+        // Handle subcommands
+        let command = args.get_command_args();
+        match command {
+            Commands::Generate {
+                path,
+                language,
+                project_license,
+                command,
+            } => match command {
+                Some(cli::GenerateCommand::Notice {
+                    path: notice_path,
+                    language: notice_language,
+                    output,
+                }) => {
+                    // Use the subcommand's path/language if provided, otherwise the parent's
+                    let path = if notice_path != "./" {
+                        notice_path
+                    } else {
+                        path
+                    };
+                    notices::handle_notice_command(
+                        &path,
+                        notice_language.or(language).as_deref(),
+                        output.as_deref(),
+                    )
+                }
+                None => {
+                    handle_generate_command(path, language, project_license);
+                    Ok(())
+                }
+            },
+            Commands::Sbom {
+                path,
+                format,
+                output,
+            } => {
+                // Determine which format to use
+                match format {
+                    Some(cli::SbomCommand::Spdx {
+                        path: fmt_path,
+                        output: fmt_output,
+                    }) => {
+                        // Use the subcommand path/output if provided, otherwise use the parent command's
+                        let final_path = if fmt_path != "./" {
+                            fmt_path
+                        } else {
+                            path.clone()
+                        };
+                        let final_output = fmt_output.or(output.clone());
+                        handle_sbom_command(final_path, &cli::SbomFormat::Spdx, final_output)
+                    }
+                    Some(cli::SbomCommand::Cyclonedx {
+                        path: fmt_path,
+                        output: fmt_output,
+                    }) => {
+                        let final_path = if fmt_path != "./" {
+                            fmt_path
+                        } else {
+                            path.clone()
+                        };
+                        let final_output = fmt_output.or(output.clone());
+                        handle_sbom_command(final_path, &cli::SbomFormat::Cyclonedx, final_output)
+                    }
+                    Some(cli::SbomCommand::Validate {
+                        sbom_file,
+                        output: validation_output,
+                        json,
+                    }) => handle_sbom_validate_command(sbom_file, validation_output, json),
+                    None => {
+                        // Default: generate both formats
+                        handle_sbom_command(path, &cli::SbomFormat::All, output)
+                    }
+                }
+            }
+            Commands::Cache { clear } => {
+                handle_cache_command(clear)?;
+                Ok(())
+            }
+            Commands::CheckCompat {
+                project_license,
+                dependency_license,
+                strict,
+                json,
+            } => license_list::handle_check_compat_command(
+                &project_license,
+                &dependency_license,
+                strict,
+                json,
+            ),
+            Commands::Explain {
+                package,
+                version,
+                json,
+            } => suggestions::handle_explain_command(&package, version.as_deref(), json),
+            Commands::Licenses { command } => match command {
+                cli::LicensesCommand::Update { version, rollback } => {
+                    license_list::handle_licenses_update_command(version, rollback)
+                }
+                cli::LicensesCommand::List {
+                    class,
+                    json,
+                    strict,
+                } => license_list::handle_licenses_list_command(class, json, strict),
+            },
+            Commands::Config { command } => match command {
+                cli::ConfigCommand::Show { effective } => config::handle_config_show_command(
+                    effective,
+                    config::ConfigFlags {
+                        strict: args.strict,
+                        cargo_lock: args.cargo_lock(),
+                        no_dev: args.no_dev,
+                        direct_only: args.direct_only,
+                        include_transitive: args.include_transitive,
+                        skip: args.skip.clone(),
+                        cargo: args.cargo_options(),
+                    },
+                ),
+            },
+            Commands::Detect { path, json } => detect::handle_detect_command(&path, json),
+            Commands::Doctor { path, json } => doctor::handle_doctor_command(&path, json),
+            Commands::Init {
+                path,
+                force,
+                no_pre_commit,
+            } => {
+                handle_init_command(path, force, no_pre_commit);
+                Ok(())
+            }
+            // `scan` expands into the default command; a second `scan` among its options
+            // (`feluda scan <url> scan <url>`) is still here after the expansion
+            Commands::Scan { .. } => {
+                eprintln!(
+                    "❌ Nested scan is not supported: pass a single target to `feluda scan`."
+                );
+                Err(FeludaError::InvalidData(
+                    "nested scan is not supported".to_string(),
+                ))
+            }
+            Commands::Watch { path, debounce } => {
+                if args.gui {
+                    eprintln!(
+                        "❌ Watch mode does not support --gui (TUI) output. \
+                        Run `feluda watch` without --gui."
+                    );
+                    return Err(FeludaError::InvalidData(
+                        "Watch mode does not support --gui (TUI) output".to_string(),
+                    ));
+                }
+                if args.timeout.is_some() {
+                    eprintln!("❌ Watch mode runs until you stop it; --timeout is not supported.");
+                    return Err(FeludaError::InvalidData(
+                        "Watch mode does not support --timeout".to_string(),
+                    ));
+                }
+                if args.repo.is_some() {
+                    eprintln!("❌ Watch mode operates on a local path; --repo is not supported.");
+                    return Err(FeludaError::InvalidData(
+                        "Watch mode operates on a local path; --repo is not supported".to_string(),
+                    ));
+                }
+
+                let name = project_name(None, Path::new(&path));
+                let config = CheckConfig {
+                    path,
+                    json: args.json,
+                    yaml: args.yaml,
+                    verbose: args.verbose,
+                    restrictive: args.restrictive,
+                    gui: false,
+                    language: args.language.clone(),
+                    ci_format: args.ci_format.clone(),
+                    output_file: args.output_file.clone(),
+                    output_dir: args.output_dir.clone(),
+                    keep_reports: args.keep_reports.map(|keep| keep as usize),
+                    compress: args.compress,
+                    chart: args.chart,
+                    fail_on_restrictive: false,
+                    incompatible: args.incompatible,
+                    fail_on_incompatible: false,
+                    fail_on_resolution_errors: false,
+                    osi_only: false,
+                    project_license: args.project_license.clone(),
+                    gist: args.gist,
+                    osi: args.osi.clone(),
+                    strict: args.strict,
+                    no_local: args.no_local,
+                    no_vendor_scan: args.no_vendor_scan,
+                    artifacts: args.artifacts.clone(),
+                    resolve_forks: args.resolve_forks,
+                    vulnerabilities: args.vulnerabilities,
+                    strict_parse: args.strict_parse,
+                    cargo_lock: args.cargo_lock(),
+                    no_dev: args.no_dev,
+                    direct_only: args.direct_only,
+                    include_transitive: args.include_transitive,
+                    limit: args.limit.map(|limit| limit as usize),
+                    sample: args.sample,
+                    cargo: args.cargo_options(),
+                    skip: args.skip.clone(),
+                    changed_since: args.changed_since.clone(),
+                    github_sbom: None,
+                    baseline: None,
+                    force_baseline: false,
+                    write_baseline: None,
+                    image: None,
+                    sbom: args.sbom,
+                    output_format: args.output_format,
+                    project_name: name,
+                    licenses_dir: args.licenses_dir.clone(),
+                    obligations: None,
+                    obligations_issues: None,
+                    pending_updates: None,
+                    result_file: None,
+                };
+                watch::handle_watch_command(config, debounce)
+            }
+            Commands::Tui {
+                report,
+                baseline,
+                force,
+            } => {
+                // Only the TUI filters apply; the report already holds the scan results
+                let config = CheckConfig {
+                    path: report.clone(),
+                    json: false,
+                    yaml: false,
+                    verbose: false,
+                    restrictive: args.restrictive,
+                    gui: true,
+                    language: None,
+                    ci_format: None,
+                    output_file: None,
+                    output_dir: None,
+                    keep_reports: None,
+                    compress: None,
+                    chart: false,
+                    fail_on_restrictive: false,
+                    incompatible: args.incompatible,
+                    fail_on_incompatible: false,
+                    fail_on_resolution_errors: false,
+                    osi_only: false,
+                    project_license: args.project_license.clone(),
+                    gist: false,
+                    osi: args.osi.clone(),
+                    strict: false,
+                    no_local: false,
+                    no_vendor_scan: true,
+                    artifacts: Vec::new(),
+                    resolve_forks: false,
+                    vulnerabilities: false,
+                    strict_parse: false,
+                    cargo_lock: None,
+                    no_dev: false,
+                    direct_only: false,
+                    include_transitive: false,
+                    limit: None,
+                    sample: None,
+                    cargo: config::CargoConfig::default(),
+                    skip: Vec::new(),
+                    changed_since: None,
+                    github_sbom: None,
+                    baseline,
+                    force_baseline: force,
+                    write_baseline: None,
+                    image: None,
+                    sbom: None,
+                    output_format: None,
+                    project_name: report,
+                    licenses_dir: None,
+                    obligations: None,
+                    obligations_issues: None,
+                    pending_updates: None,
+                    result_file: None,
+                };
+                handle_tui_command(config)
+            }
+            Commands::Diff {
+                old,
+                new,
+                json,
+                fail_on_restrictive,
+                fail_on_incompatible,
+                force,
+            } => diff::handle_diff_command(
+                &old,
+                &new,
+                json,
+                fail_on_restrictive || args.fail_on_restrictive,
+                fail_on_incompatible || args.fail_on_incompatible,
+                force,
+            ),
+            Commands::History { command } => {
+                history::handle_history_command(command, scan_template(&args))
+            }
+            Commands::Image {
+                reference,
+                platform,
+            } => {
+                cancel::install(args.timeout.map(Duration::from_secs));
+
+                let config = CheckConfig {
+                    path: reference.clone(),
+                    json: args.json,
+                    yaml: args.yaml,
+                    verbose: args.verbose,
+                    restrictive: args.restrictive,
+                    gui: args.gui,
+                    language: args.language.clone(),
+                    ci_format: args.ci_format.clone(),
+                    output_file: args.output_file.clone(),
+                    output_dir: args.output_dir.clone(),
+                    keep_reports: args.keep_reports.map(|keep| keep as usize),
+                    compress: args.compress,
+                    chart: args.chart,
+                    fail_on_restrictive: args.fail_on_restrictive,
+                    incompatible: args.incompatible,
+                    fail_on_incompatible: args.fail_on_incompatible,
+                    fail_on_resolution_errors: args.fail_on_resolution_errors,
+                    osi_only: args.osi_only,
+                    project_license: args.project_license.clone(),
+                    gist: args.gist,
+                    osi: args.osi.clone(),
+                    strict: args.strict,
+                    no_local: args.no_local,
+                    no_vendor_scan: true,
+                    artifacts: Vec::new(),
+                    resolve_forks: args.resolve_forks,
+                    vulnerabilities: args.vulnerabilities,
+                    strict_parse: args.strict_parse,
+                    cargo_lock: args.cargo_lock(),
+                    no_dev: args.no_dev,
+                    direct_only: args.direct_only,
+                    include_transitive: args.include_transitive,
+                    limit: args.limit.map(|limit| limit as usize),
+                    sample: args.sample,
+                    cargo: args.cargo_options(),
+                    skip: args.skip.clone(),
+                    changed_since: None,
+                    github_sbom: None,
+                    baseline: args.baseline.clone(),
+                    force_baseline: args.force,
+                    write_baseline: args.write_baseline.clone(),
+                    sbom: args.sbom,
+                    output_format: args.output_format,
+                    project_name: reference.clone(),
+                    licenses_dir: args.licenses_dir.clone(),
+                    obligations: args.obligations.clone(),
+                    obligations_issues: args.obligations_issues.clone(),
+                    pending_updates: args.pending_updates.clone(),
+                    result_file: args.result_file.clone(),
+                    image: Some(image::ImageRequest {
+                        reference,
+                        platform,
+                    }),
+                };
+                handle_check_command(config)
+            }
+            Commands::Accept {
+                package,
+                reason,
+                path,
+                branch,
+                commit,
+            } => handle_accept_command(&path, &package, &reason, branch.as_deref(), commit),
+            Commands::Bench {
+                packages,
+                iterations,
+                json,
+                baseline,
+                tolerance,
+                fixtures,
+            } => bench::handle_bench_command(
+                packages,
+                iterations,
+                json,
+                baseline.as_deref(),
+                tolerance,
+                fixtures.as_deref(),
+            ),
+            Commands::Rollup {
+                repos,
+                manifest,
+                out,
+                top,
+            } => {
+                // Each repository gets its own path and name; the rest applies to all of them
+                let template = scan_template(&args);
+                rollup::handle_rollup_command(&args, repos, manifest, out, top, template)
+            }
+        }
+    }
+}
+
+/// Scan settings shared by subcommands that run their own scans (`rollup`, `history record`):
+/// the global flags that shape the analysis, with path and project name left to the caller
+fn scan_template(args: &Cli) -> CheckConfig {
+    CheckConfig {
+        path: String::new(),
+        json: args.json,
+        yaml: false,
+        verbose: false,
+        restrictive: false,
+        gui: false,
+        language: args.language.clone(),
+        ci_format: None,
+        output_file: None,
+        output_dir: None,
+        keep_reports: None,
+        compress: None,
+        chart: false,
+        fail_on_restrictive: args.fail_on_restrictive,
+        incompatible: false,
+        fail_on_incompatible: args.fail_on_incompatible,
+        fail_on_resolution_errors: false,
+        osi_only: false,
+        project_license: args.project_license.clone(),
+        gist: false,
+        osi: None,
+        strict: args.strict,
+        no_local: args.no_local,
+        no_vendor_scan: args.no_vendor_scan,
+        artifacts: args.artifacts.clone(),
+        resolve_forks: args.resolve_forks,
+        vulnerabilities: args.vulnerabilities,
+        strict_parse: args.strict_parse,
+        cargo_lock: args.cargo_lock(),
+        no_dev: args.no_dev,
+        direct_only: args.direct_only,
+        include_transitive: args.include_transitive,
+        limit: args.limit.map(|limit| limit as usize),
+        sample: args.sample,
+        cargo: args.cargo_options(),
+        skip: args.skip.clone(),
+        changed_since: None,
+        github_sbom: None,
+        baseline: None,
+        force_baseline: false,
+        write_baseline: None,
+        image: None,
+        sbom: None,
+        output_format: None,
+        project_name: String::new(),
+        licenses_dir: None,
+        obligations: None,
+        obligations_issues: None,
+        pending_updates: None,
+        result_file: None,
+    }
+}
+
+/// Outcome of a single license analysis run.
+///
+/// Returned by [`report_analysis`] so callers (single-shot or watch) can decide
+/// what to do — e.g. set an exit code — without the analysis itself terminating
+/// the process.
+#[derive(Debug, Default, Clone, Copy)]
+struct ScanSummary {
+    has_restrictive: bool,
+    has_incompatible: bool,
+    /// An `[outputs]` sink couldn't be delivered to
+    outputs_failed: bool,
+}
+
+/// Everything the analysis front half hands to reporting.
+#[derive(Debug)]
+struct Analysis {
+    dependencies: Vec<LicenseInfo>,
+    project_license: Option<String>,
+    /// Manifests that could not be parsed (empty unless something was skipped)
+    warnings: Vec<ParseWarning>,
+    /// Registry/API lookups that failed (empty unless the network let us down)
+    resolution_errors: Vec<ResolutionError>,
+    /// Dependencies ignore rules left out, for the report's "ignored" section
+    ignored: Vec<IgnoredDependency>,
+}
+
+/// Detect the project license and parse + analyze dependencies.
+///
+/// This is the shared front half of the check pipeline, reused by both the
+/// single-shot command and `feluda watch`. It performs no terminal I/O beyond
+/// logging and never exits the process.
+///
+/// With `--strict-parse`, any manifest that failed to parse turns into an error
+/// instead of a warning carried along to the report.
+fn analyze_dependencies(config: &CheckConfig) -> FeludaResult<Analysis> {
+    log(
+        LogLevel::Info,
+        &format!("Executing check command with path: {}", config.path),
+    );
+    licenses::ensure_compatibility_matrix()?;
+    partial_scan::start(config.limit, config.sample);
+
+    if let Some(ref repo_url) = config.github_sbom {
+        let mut dependencies = cli::with_spinner("🔎: GitHub dependency graph", |indicator| {
+            let deps = sbom::github::analyze_dependency_graph(repo_url, config.strict);
+            if let Ok(ref deps) = deps {
+                indicator.update_progress(&format!("found {} dependencies", deps.len()));
+            }
+            deps
+        })?;
+        partial_scan::finish(&mut dependencies);
+        return Ok(Analysis {
+            dependencies,
+            project_license: config.project_license.clone(),
+            warnings: take_parse_warnings(),
+            resolution_errors: take_resolution_errors(),
+            ignored: ignore::take_ignored_dependencies(),
+        });
+    }
+
+    if let Some(ref request) = config.image {
+        let mut dependencies = cli::with_spinner("🐳: container image", |indicator| {
+            let deps = image::analyze_image(request, config);
+            if let Ok(ref deps) = deps {
+                indicator.update_progress(&format!("found {} packages", deps.len()));
+            }
+            deps
+        })?;
+        partial_scan::finish(&mut dependencies);
+        return Ok(Analysis {
+            dependencies,
+            project_license: config.project_license.clone(),
+            warnings: take_parse_warnings(),
+            resolution_errors: take_resolution_errors(),
+            ignored: ignore::take_ignored_dependencies(),
+        });
+    }
+
+    // Parse project dependencies
+    log(
+        LogLevel::Info,
+        &format!("Parsing dependencies in path: {}", config.path),
+    );
+
+    let mut project_license = config.project_license.clone();
+
+    // If no project license is provided via CLI, try to detect it
+    if let Some(ref license) = project_license {
+        log(
+            LogLevel::Info,
+            &format!("Using provided project license: {}", *license),
+        );
+    } else {
+        log(
+            LogLevel::Info,
+            "No project license specified, attempting to detect",
+        );
+        match detect_project_license(&config.path) {
+            Ok(Some(detected)) => {
+                log(
+                    LogLevel::Info,
+                    &format!("Detected project license: {detected}"),
+                );
+                project_license = Some(detected);
+            }
+            Ok(None) => {
+                log(LogLevel::Warn, "Could not detect project license");
+            }
+            Err(e) => {
+                log(
+                    LogLevel::Error,
+                    &format!("Error detecting project license: {e}"),
+                );
+            }
+        }
+    }
+
+    // With --changed-since, project roots none of the changed dependency files belong to are
+    // skipped
+    let changed = match config.changed_since {
+        Some(ref git_ref) => {
+            let changed = manifest::changed_dependency_files(&config.path, git_ref)?;
+            eprintln!(
+                "🔀 {} dependency file{} changed since {git_ref}; skipping unchanged project roots",
+                changed.len(),
+                if changed.len() == 1 { "" } else { "s" },
+            );
+            Some(changed)
+        }
+        None => None,
+    };
+
+    // Parse and analyze dependencies
+    let mut feluda_config = crate::config::load_config()?;
+    feluda_config.apply_flags(
+        config.strict,
+        config.cargo_lock,
+        config.no_dev,
+        config.direct_only,
+        config.include_transitive,
+        &config.skip,
+    );
+    feluda_config.apply_cargo_flags(&config.cargo);
+    let mut analyzed_data = parse_root_with_config(
+        &config.path,
+        config.language.as_deref(),
+        &feluda_config,
+        config.no_local,
+        changed.as_deref(),
+    )
+    .map_err(|e| match e {
+        FeludaError::Lockfile(_) => e,
+        e => FeludaError::Parser(format!("Failed to parse dependencies: {e}")),
+    })?;
+
+    log_debug("Analyzed dependencies", &analyzed_data);
+
+    // `--limit`/`--sample`: the packages left out were never looked up; drop their rows before
+    // fork and vulnerability lookups. The tree walks below still count them as known.
+    let left_out = partial_scan::finish(&mut analyzed_data);
+
+    if config.resolve_forks && !cancel::is_cancelled() {
+        let inherited = cli::with_spinner("🍴: upstream licenses of forks", |indicator| {
+            let inherited = forks::resolve_fork_licenses(
+                &mut analyzed_data,
+                project_license.as_deref(),
+                config.strict,
+            );
+            indicator.update_progress(&format!(
+                "{inherited} fork{} inherited a license",
+                if inherited == 1 { "" } else { "s" }
+            ));
+            inherited
+        });
+        log(
+            LogLevel::Info,
+            &format!("{inherited} dependencies inherited an upstream license"),
+        );
+    }
+
+    if config.vulnerabilities && !cancel::is_cancelled() {
+        let annotated = cli::with_spinner("🛡️: known vulnerabilities (OSV.dev)", |indicator| {
+            let annotated = osv::annotate_vulnerabilities(&mut analyzed_data);
+            if let Ok(vulnerable) = annotated {
+                indicator.update_progress(&format!(
+                    "{vulnerable} vulnerable dependenc{}",
+                    if vulnerable == 1 { "y" } else { "ies" }
+                ));
+            }
+            annotated
+        });
+        if let Err(err) = annotated {
+            log(LogLevel::Warn, &err);
+            eprintln!("⚠️  Vulnerability lookup skipped: {err}");
+        }
+    }
+
+    let warnings = take_parse_warnings();
+    let ignored = ignore::take_ignored_dependencies();
+    if config.strict_parse && !warnings.is_empty() {
+        for warning in &warnings {
+            eprintln!("❌ {}: {}", warning.file, warning.reason);
+        }
+        return Err(FeludaError::Parser(format!(
+            "{} manifest{} failed to parse (--strict-parse)",
+            warnings.len(),
+            if warnings.len() == 1 { "" } else { "s" }
+        )));
+    }
+
+    // Both tree walks below are skipped once the scan has been cancelled.
+    // Own-source header scan: flag project source files whose leading comments declare a
+    // license different from the project's (code pasted in by AI tools or copied from other
+    // projects without a manifest entry).
+    let own_source_findings = if cancel::is_cancelled() {
+        Vec::new()
+    } else {
+        cli::with_spinner("🔎: own source license headers", |indicator| {
+            let findings = source_scan::scan_own_source_headers(
+                Path::new(&config.path),
+                project_license.as_deref(),
+                config.strict,
+            );
+            indicator.update_progress(&format!(
+                "{} finding{}",
+                findings.len(),
+                if findings.len() == 1 { "" } else { "s" }
+            ));
+            findings
+        })
+    };
+    analyzed_data.extend(own_source_findings);
+
+    // Vendored/unmanaged scan: flag directories holding code no manifest records — libraries
+    // copied into `vendor/`/`third_party/`, plus stray licensed directories elsewhere in the
+    // tree. This walks the whole tree, so `--no-vendor-scan` opts large repos out.
+    if config.no_vendor_scan {
+        log(
+            LogLevel::Info,
+            "Skipping vendored/unmanaged dependency scan (--no-vendor-scan)",
+        );
+    } else if cancel::is_cancelled() {
+        log(
+            LogLevel::Info,
+            "Skipping vendored/unmanaged dependency scan (scan cancelled)",
+        );
+    } else {
+        let known_names = known_dependency_names(&analyzed_data, &ignored, &left_out);
+        let vendored_findings = cli::with_spinner("📦: vendored dependencies", |indicator| {
+            let findings = vendor_scan::scan_vendored_packages(
+                Path::new(&config.path),
+                &known_names,
+                project_license.as_deref(),
+                config.strict,
+            );
+            indicator.update_progress(&format!(
+                "{} finding{}",
+                findings.len(),
+                if findings.len() == 1 { "" } else { "s" }
+            ));
+            findings
+        });
+        analyzed_data.extend(vendored_findings);
+    }
+
+    // Bundle scan: libraries that browser-extension manifests and electron-builder configs ship
+    // outside package.json. Only those configs are read, so it runs even with --no-vendor-scan.
+    if !cancel::is_cancelled() {
+        let known_names = known_dependency_names(&analyzed_data, &ignored, &left_out);
+        let bundled_findings =
+            cli::with_spinner("🧩: extension and Electron bundles", |indicator| {
+                let findings = bundle_scan::scan_declared_bundles(
+                    Path::new(&config.path),
+                    &known_names,
+                    project_license.as_deref(),
+                    config.strict,
+                );
+                indicator.update_progress(&format!(
+                    "{} finding{}",
+                    findings.len(),
+                    if findings.len() == 1 { "" } else { "s" }
+                ));
+                findings
+            });
+        analyzed_data.extend(bundled_findings);
+    }
+
+    // Artifact scan: licenses embedded in build outputs (fat jars, wheels, statically linked
+    // binaries) that no manifest accounts for
+    if !config.artifacts.is_empty() && !cancel::is_cancelled() {
+        let dirs: Vec<_> = config
+            .artifacts
+            .iter()
+            .map(|dir| Path::new(&config.path).join(dir))
+            .collect();
+        for dir in dirs.iter().filter(|dir| !dir.is_dir()) {
+            log(
+                LogLevel::Warn,
+                &format!("Artifact directory {} does not exist", dir.display()),
+            );
+        }
+        let dirs: Vec<&Path> = dirs
+            .iter()
+            .map(|dir| dir.as_path())
+            .filter(|dir| dir.is_dir())
+            .collect();
+        let known_names = known_dependency_names(&analyzed_data, &ignored, &left_out);
+        let artifact_findings = cli::with_spinner("📦: shipped artifacts", |indicator| {
+            let findings = artifact_scan::scan_artifacts(
+                &dirs,
+                &known_names,
+                project_license.as_deref(),
+                config.strict,
+            );
+            indicator.update_progress(&format!(
+                "{} finding{}",
+                findings.len(),
+                if findings.len() == 1 { "" } else { "s" }
+            ));
+            findings
+        });
+        analyzed_data.extend(artifact_findings);
+    }
+
+    Ok(Analysis {
+        dependencies: analyzed_data,
+        project_license,
+        warnings,
+        resolution_errors: take_resolution_errors(),
+        ignored,
+    })
+}
+
+/// Names the tree-walking scans treat as already accounted for: every dependency found so far,
+/// plus the ones ignore rules removed, so a vendored copy of an ignored package stays ignored,
+/// and the ones a partial scan left out
+fn known_dependency_names(
+    analyzed_data: &[LicenseInfo],
+    ignored: &[IgnoredDependency],
+    left_out: &[String],
+) -> Vec<String> {
+    analyzed_data
+        .iter()
+        .map(|info| info.name.clone())
+        .chain(ignored.iter().map(|dep| dep.name.clone()))
+        .chain(left_out.iter().cloned())
+        .collect()
+}
+
+/// Annotate each dependency with license-compatibility information relative to
+/// the project license. A dependency of a part of a monorepo with its own
+/// `[project_licenses]` entry is checked against that license instead; one shared by
+/// several parts takes the worst result. Also records which dependencies are under data or
+/// content licenses. Mutates `analyzed_data` in place.
+fn annotate_compatibility(
+    analyzed_data: &mut [LicenseInfo],
+    project_license: &Option<String>,
+    strict: bool,
+) {
+    for info in analyzed_data.iter_mut() {
+        info.data_license = info
+            .license
+            .as_deref()
+            .and_then(data_licenses::expression_class);
+    }
+
+    if let Some(proj_license) = project_license {
+        log(
+            LogLevel::Info,
+            &format!("Checking license compatibility against project license: {proj_license}"),
+        );
+    } else if !licenses::has_project_licenses() {
+        // If no project license is known, mark all as unknown compatibility
+        log(
+            LogLevel::Warn,
+            "No project license specified or detected, marking all dependencies as unknown compatibility",
+        );
+
+        for info in analyzed_data.iter_mut() {
+            info.compatibility = LicenseCompatibility::Unknown;
+        }
+        licenses::apply_package_policy(analyzed_data);
+        return;
+    }
+
+    for info in analyzed_data.iter_mut() {
+        let mut against: Vec<Option<String>> = Vec::new();
+        for dir in &info.project_dirs {
+            let license =
+                licenses::configured_project_license(dir).or_else(|| project_license.clone());
+            if !against.contains(&license) {
+                against.push(license);
+            }
+        }
+        if against.is_empty() {
+            against.push(project_license.clone());
+        }
+
+        info.compatibility = against
+            .iter()
+            .map(|proj_license| match (proj_license, &info.license) {
+                (Some(proj_license), Some(dep_license)) => {
+                    is_license_compatible(dep_license, proj_license, strict)
+                }
+                (Some(_), None) if strict => LicenseCompatibility::Incompatible,
+                _ => LicenseCompatibility::Unknown,
+            })
+            .max_by_key(|compatibility| match compatibility {
+                LicenseCompatibility::Compatible => 0,
+                LicenseCompatibility::Unknown => 1,
+                LicenseCompatibility::Incompatible => 2,
+            })
+            .unwrap_or(LicenseCompatibility::Unknown);
+
+        log(
+            LogLevel::Info,
+            &format!(
+                "License compatibility for {} ({}) against {}: {:?}",
+                info.name,
+                info.license.as_deref().unwrap_or("no license info"),
+                against
+                    .iter()
+                    .map(|license| license.as_deref().unwrap_or("unknown project license"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                info.compatibility
+            ),
+        );
+    }
+
+    licenses::apply_package_policy(analyzed_data);
+}
+
+/// Open a report saved by an earlier scan in the TUI, so a slow scan can run in CI and
+/// its results be browsed locally.
+fn handle_tui_command(config: CheckConfig) -> FeludaResult<()> {
+    let (report, metadata) = report_archive::load_report_with_metadata(Path::new(&config.path))
+        .inspect_err(|e| {
+            eprintln!("❌ {e}");
+        })?;
+    if report.is_empty() {
+        println!("{} has no dependencies to show", config.path);
+        return Ok(());
+    }
+    let project_license = config.project_license.clone();
+    let lockfile_hash = metadata.and_then(|metadata| metadata.lockfile_hash);
+    run_gui(report, project_license, lockfile_hash.as_deref(), &config)
+}
+
+/// Render the interactive TUI table for the analyzed dependencies.
+///
+/// GUI mode is single-shot only (it takes over the terminal and `color_eyre`
+/// can only be installed once per process), so it is intentionally not used by
+/// `feluda watch`.
+fn run_gui(
+    mut analyzed_data: Vec<LicenseInfo>,
+    project_license: Option<String>,
+    lockfile_hash: Option<&str>,
+    config: &CheckConfig,
+) -> FeludaResult<()> {
+    // Read the baseline before taking over the terminal so errors print normally,
+    // and check its lockfile state before the filters below drop rows
+    let baseline = config
+        .baseline
+        .as_deref()
+        .map(|path| {
+            let (baseline, metadata) = baseline::load_baseline_with_metadata(Path::new(path))?;
+            baseline::check_lockfile_state(
+                &analyzed_data,
+                lockfile_hash,
+                &baseline,
+                metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.lockfile_hash.as_deref()),
+                Path::new(path),
+                config.force_baseline,
+            )
+            .inspect_err(|e| eprintln!("❌ {e}"))?;
+            Ok::<_, FeludaError>(baseline)
+        })
+        .transpose()?;
+
+    let original_count = analyzed_data.len();
+
+    // Filter for restrictive and incompatible
+    if config.restrictive || config.incompatible {
+        if project_license.is_some() {
+            log(
+                LogLevel::Info,
+                "Restrictive and incompatible mode enabled, filtering for restrictive and incompatible licenses",
+            );
+            analyzed_data.retain(|info| {
+                (config.restrictive && *info.is_restrictive())
+                    || (config.incompatible
+                        && info.compatibility == LicenseCompatibility::Incompatible)
+            });
+
+            log(
+                LogLevel::Info,
+                &format!(
+                    "Filtered for restrictive and incompatible licenses: {} of {} dependencies",
+                    analyzed_data.len(),
+                    original_count
+                ),
+            );
+        } else {
+            log(
+                LogLevel::Warn,
+                "Incompatible mode enabled but no project license specified, cannot filter for incompatible licenses",
+            );
+        }
+    } else if config.restrictive {
+        // Filter for restrictive
+        log(
+            LogLevel::Info,
+            "Restrictive mode enabled, filtering for restrictive licenses",
+        );
+        analyzed_data.retain(|info| *info.is_restrictive());
+
+        log(
+            LogLevel::Info,
+            &format!(
+                "Filtered for restrictive licenses: {} of {} dependencies",
+                analyzed_data.len(),
+                original_count
+            ),
+        );
+    } else if config.incompatible {
+        // Filter for incompatible if requested
+        if project_license.is_some() {
+            log(
+                LogLevel::Info,
+                "Incompatible mode enabled, filtering for incompatible licenses",
+            );
+            analyzed_data.retain(|info| info.compatibility == LicenseCompatibility::Incompatible);
+
+            log(
+                LogLevel::Info,
+                &format!(
+                    "Filtered for incompatible licenses: {} of {} dependencies",
+                    analyzed_data.len(),
+                    original_count
+                ),
+            );
+        } else {
+            log(
+                LogLevel::Warn,
+                "Incompatible mode enabled but no project license specified, cannot filter for incompatible licenses",
+            );
+        }
+    }
+
+    // Apply OSI filtering
+    if let Some(osi_filter) = &config.osi {
+        let before_count = analyzed_data.len();
+        match osi_filter {
+            cli::OsiFilter::Approved => {
+                analyzed_data.retain(|info| info.osi_status == licenses::OsiStatus::Approved);
+                log(
+                    LogLevel::Info,
+                    &format!(
+                        "Filtered for OSI approved licenses: {} of {} dependencies",
+                        analyzed_data.len(),
+                        before_count
+                    ),
+                );
+            }
+            cli::OsiFilter::NotApproved => {
+                analyzed_data.retain(|info| info.osi_status == licenses::OsiStatus::NotApproved);
+                log(
+                    LogLevel::Info,
+                    &format!(
+                        "Filtered for non-OSI approved licenses: {} of {} dependencies",
+                        analyzed_data.len(),
+                        before_count
+                    ),
+                );
+            }
+            cli::OsiFilter::Unknown => {
+                analyzed_data.retain(|info| info.osi_status == licenses::OsiStatus::Unknown);
+                log(
+                    LogLevel::Info,
+                    &format!(
+                        "Filtered for unknown OSI status licenses: {} of {} dependencies",
+                        analyzed_data.len(),
+                        before_count
+                    ),
+                );
+            }
+        }
+    }
+
+    log(LogLevel::Info, "Starting TUI mode");
+
+    // Initialize the terminal
+    color_eyre::install()
+        .map_err(|e| FeludaError::TuiInit(format!("Failed to initialize color_eyre: {e}")))?;
+
+    let terminal = ratatui::init();
+    cancel::set_tui_active(true);
+    log(LogLevel::Info, "Terminal initialized for TUI");
+
+    // TUI app with project license info
+    let mut app = App::new(analyzed_data, project_license);
+    if let Some(baseline) = baseline {
+        app = app.with_baseline(&baseline);
+    }
+    let app_result = app.run(terminal);
+    ratatui::restore();
+    cancel::set_tui_active(false);
+
+    // Handle any errors from the TUI
+    app_result.map_err(|e| FeludaError::TuiRuntime(format!("TUI error: {e}")))?;
+
+    log(LogLevel::Info, "TUI session completed successfully");
+
+    Ok(())
+}
+
+/// Generate a (non-interactive) dependency report and return the outcome.
+///
+/// Unlike the previous inline implementation, this never calls `process::exit`;
+/// the caller inspects the returned [`ScanSummary`] to decide on exit codes.
+/// This makes it safe to call repeatedly from `feluda watch`.
+/// Name of the scanned project: the repository's name for `--repo`, else its directory's
+fn project_name(repo: Option<&str>, path: &Path) -> String {
+    let from_repo = repo.and_then(|url| {
+        url.trim_end_matches('/')
+            .rsplit(['/', ':'])
+            .next()
+            .map(|name| name.trim_end_matches(".git").to_string())
+    });
+    from_repo
+        .filter(|name| !name.is_empty())
+        .or_else(|| {
+            path.canonicalize()
+                .ok()?
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| "project".to_string())
+}
+
+fn report_analysis(
+    analyzed_data: Vec<LicenseInfo>,
+    project_license: Option<String>,
+    warnings: Vec<ParseWarning>,
+    resolution_errors: Vec<ResolutionError>,
+    ignored: Vec<IgnoredDependency>,
+    metadata: ReportMetadata,
+    config: &CheckConfig,
+) -> ScanSummary {
+    log(LogLevel::Info, "Generating dependency report");
+
+    // Create ReportConfig from CLI arguments
+    let report_config = ReportConfig::new(
+        config.json,
+        config.yaml,
+        config.verbose,
+        config.restrictive,
+        config.incompatible,
+        config.ci_format.clone(),
+        config.output_file.clone(),
+        project_license,
+        config.gist,
+        config.osi.clone(),
+    )
+    .with_warnings(warnings)
+    .with_resolution_errors(resolution_errors)
+    .with_ignored(ignored)
+    .with_rate_limits(rate_limit::take_budgets())
+    .with_output_dir(
+        config.output_dir.clone(),
+        config.keep_reports,
+        config.compress,
+    )
+    .with_chart(config.chart)
+    .with_metadata(metadata)
+    .with_sbom(config.sbom, &config.project_name)
+    .with_output_format(config.output_format, &config.project_name)
+    .with_outputs(
+        config::load_config()
+            .map(|config| config.outputs)
+            .unwrap_or_default(),
+        &config.project_name,
+    )
+    .with_incomplete(cancel::cancel_reason().map(|reason| reason.to_string()));
+
+    // Generate a report based on the analyzed data
+    let summary = match generate_report(analyzed_data, report_config) {
+        Ok((has_restrictive, has_incompatible)) => ScanSummary {
+            has_restrictive,
+            has_incompatible,
+            outputs_failed: false,
+        },
+        Err(e) => {
+            e.log();
+            ScanSummary {
+                has_restrictive: false,
+                has_incompatible: false,
+                outputs_failed: true,
+            }
+        }
+    };
+
+    log(
+        LogLevel::Info,
+        &format!(
+            "Report generated, has_restrictive: {}, has_incompatible: {}",
+            summary.has_restrictive, summary.has_incompatible
+        ),
+    );
+
+    summary
+}
+
+fn handle_check_command(config: CheckConfig) -> FeludaResult<()> {
+    let Analysis {
+        dependencies: mut analyzed_data,
+        project_license,
+        warnings,
+        resolution_errors,
+        ignored,
+    } = analyze_dependencies(&config)?;
+    cancel::disarm_timeout();
+    let incomplete = cancel::cancel_reason();
+    let metadata = ReportMetadata::collect(Path::new(&config.path));
+
+    if analyzed_data.is_empty() {
+        log(LogLevel::Warn, "No dependencies found to analyze. Exiting.");
+        reporter::print_parse_warnings(&warnings);
+        ignore::print_ignored_dependencies(&ignored);
+        reporter::print_resolution_errors(&resolution_errors);
+        if let Some(ref path) = config.write_baseline {
+            baseline::write_baseline(Path::new(path), &[], project_license.as_deref(), &metadata)?;
+        }
+        if let Some(ref path) = config.result_file {
+            result_file::ScanResult::from_scan(&[], &ignored, &resolution_errors, &warnings, &[])
+                .with_metadata(metadata)
+                .with_exit(
+                    incomplete.map_or(0, |reason| reason.exit_code()),
+                    incomplete.map(|reason| reason.to_string()),
+                )
+                .write(Path::new(path))?;
+        }
+        if let Some(reason) = incomplete {
+            reporter::print_incomplete_notice(Some(&reason.to_string()));
+            process::exit(reason.exit_code());
+        }
+        return Ok(());
+    }
+
+    annotate_compatibility(&mut analyzed_data, &project_license, config.strict);
+    suggestions::annotate_suggestions(&mut analyzed_data);
+
+    if let Some(ref path) = config.write_baseline {
+        baseline::write_baseline(
+            Path::new(path),
+            &analyzed_data,
+            project_license.as_deref(),
+            &metadata,
+        )?;
+    }
+    // A cancelled scan skips the TUI and prints its partial results instead. The TUI diffs
+    // against the baseline; everywhere else it decides which findings are new.
+    let use_gui = config.gui && incomplete.is_none();
+    let known_findings = match config.baseline.as_deref() {
+        Some(path) if !use_gui => Some(baseline::KnownFindings::from_baseline(
+            &baseline::load_baseline(Path::new(path))?,
+        )),
+        _ => None,
+    };
+    let baseline_check = known_findings
+        .as_ref()
+        .map(|known| known.check(&analyzed_data));
+
+    if let Some(ref dir) = config.licenses_dir {
+        license_texts::write_license_texts(
+            &analyzed_data,
+            Path::new(dir),
+            Path::new(&config.path),
+        )?;
+    }
+
+    if let Some(ref file) = config.obligations {
+        obligations::write_obligations(
+            &analyzed_data,
+            Path::new(file),
+            &config.project_name,
+            project_license.as_deref(),
+        )?;
+    }
+    if let Some(ref repository) = config.obligations_issues {
+        obligations::open_obligation_issues(&analyzed_data, repository)?;
+    }
+    if let Some(ref repository) = config.pending_updates {
+        updates::report_pending_updates(&analyzed_data, repository, config.strict)?;
+    }
+
+    let osi_violations: Vec<_> = if config.osi_only {
+        analyzed_data
+            .iter()
+            .filter(|info| reporter::violates_osi_only(info))
+            .cloned()
+            .collect()
+    } else {
+        Vec::new()
+    };
+    // Tallied now, since reporting consumes the dependencies; the exit code comes last
+    let result = config.result_file.as_ref().map(|_| {
+        result_file::ScanResult::from_scan(
+            &analyzed_data,
+            &ignored,
+            &resolution_errors,
+            &warnings,
+            &osi_violations,
+        )
+        .with_metadata(metadata.clone())
+        .with_baseline(known_findings.as_ref())
+    });
+
+    // Either run the GUI or generate a report
+    if use_gui {
+        run_gui(
+            analyzed_data,
+            project_license,
+            metadata.lockfile_hash.as_deref(),
+            &config,
+        )?;
+        reporter::print_parse_warnings(&warnings);
+        ignore::print_ignored_dependencies(&ignored);
+        reporter::print_resolution_errors(&resolution_errors);
+        if let (Some(result), Some(path)) = (result, &config.result_file) {
+            result.with_exit(0, None).write(Path::new(path))?;
+        }
+    } else {
+        let has_resolution_errors = !resolution_errors.is_empty();
+        let summary = report_analysis(
+            analyzed_data,
+            project_license,
+            warnings,
+            resolution_errors,
+            ignored,
+            metadata,
+            &config,
+        );
+        // Findings the baseline already had are reported but don't fail the build
+        let (has_restrictive, has_incompatible) = match (&baseline_check, &config.baseline) {
+            (Some(check), Some(path)) => {
+                check.print(path);
+                (check.has_new_restrictive(), check.has_new_incompatible())
+            }
+            _ => (summary.has_restrictive, summary.has_incompatible),
+        };
+
+        // Exit code, plus the status recorded for it when it isn't a plain pass/fail
+        let exit = if let Some(reason) = incomplete {
+            log(
+                LogLevel::Warn,
+                &format!("Exiting with status {} (scan {reason})", reason.exit_code()),
+            );
+            Some((reason.exit_code(), Some(reason.to_string())))
+        } else if summary.outputs_failed {
+            log(
+                LogLevel::Warn,
+                "Exiting with non-zero status because an [outputs] sink failed",
+            );
+            Some((1, None))
+        } else if (config.fail_on_restrictive && has_restrictive)
+            || (config.fail_on_incompatible && has_incompatible)
+        {
+            log(
+                LogLevel::Warn,
+                "Exiting with non-zero status due to license issues",
+            );
+            Some((1, None))
+        } else if !osi_violations.is_empty() {
+            reporter::print_osi_only_violations(&osi_violations);
+            log(
+                LogLevel::Warn,
+                "Exiting with non-zero status due to non-OSI licenses (--osi-only)",
+            );
+            Some((1, None))
+        } else if config.fail_on_resolution_errors && has_resolution_errors {
+            log(
+                LogLevel::Warn,
+                "Exiting with non-zero status due to resolution errors",
+            );
+            Some((1, None))
+        } else {
+            None
+        };
+
+        if let (Some(result), Some(path)) = (result, &config.result_file) {
+            let (code, status) = exit.clone().unwrap_or((0, None));
+            result.with_exit(code, status).write(Path::new(path))?;
+        }
+        if let Some((code, _)) = exit {
+            process::exit(code);
+        }
+    }
+
+    log(LogLevel::Info, "Feluda completed successfully");
+
+    Ok(())
+}
+
+fn handle_cache_command(clear: bool) -> FeludaResult<()> {
+    if clear {
+        cache::clear_github_licenses_cache()?;
+        println!("✓ Cache cleared successfully\n");
+    } else {
+        let status = cache::get_cache_status()?;
+        status.print_status();
+    }
+    Ok(())
+}
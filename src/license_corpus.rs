@@ -0,0 +1,208 @@
+//! Embedded SPDX license texts, and the similarity matching that classifies a license file
+//! against them
+//!
+//! The marker rules in `licenses.rs` recognise a license by its title and a few phrases, which
+//! misses the many files that carry the bare text: a BSD license never names itself, an ISC
+//! file often starts straight at the copyright line, and MIT-0 reads like MIT up to its missing
+//! notice clause. Here a text is compared word by word with the reference texts instead.
+//! Copyright lines, case, punctuation and line wrapping are ignored, and the closest reference
+//! text wins when it is similar enough.
+//!
+//! To add a license, drop its SPDX text into `config/license_texts/<SPDX id>.txt` and list it
+//! in [`CORPUS`].
+
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// Minimum Sørensen–Dice similarity of the word sets for a text to count as a license
+///
+/// High enough that a text with an extra or missing clause (BSD-2-Clause against
+/// BSD-3-Clause, ISC against 0BSD) still prefers its own license, low enough that a reworded
+/// disclaimer or an added title still matches.
+pub const MATCH_THRESHOLD: f64 = 0.9;
+
+/// The reference texts, by SPDX id
+const CORPUS: &[(&str, &str)] = &[
+    ("0BSD", include_str!("../config/license_texts/0BSD.txt")),
+    (
+        "BSD-2-Clause",
+        include_str!("../config/license_texts/BSD-2-Clause.txt"),
+    ),
+    (
+        "BSD-3-Clause",
+        include_str!("../config/license_texts/BSD-3-Clause.txt"),
+    ),
+    (
+        "BSL-1.0",
+        include_str!("../config/license_texts/BSL-1.0.txt"),
+    ),
+    ("ISC", include_str!("../config/license_texts/ISC.txt")),
+    ("MIT", include_str!("../config/license_texts/MIT.txt")),
+    ("MIT-0", include_str!("../config/license_texts/MIT-0.txt")),
+    (
+        "Unlicense",
+        include_str!("../config/license_texts/Unlicense.txt"),
+    ),
+    ("WTFPL", include_str!("../config/license_texts/WTFPL.txt")),
+    ("Zlib", include_str!("../config/license_texts/Zlib.txt")),
+];
+
+/// The corpus license closest to a text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CorpusMatch {
+    pub license: &'static str,
+    /// Sørensen–Dice similarity of the word sets, from 0 to 1
+    pub similarity: f64,
+}
+
+fn corpus() -> &'static [(&'static str, HashSet<String>)] {
+    static WORDS: OnceLock<Vec<(&'static str, HashSet<String>)>> = OnceLock::new();
+    WORDS.get_or_init(|| {
+        CORPUS
+            .iter()
+            .map(|(license, text)| (*license, words(text)))
+            .collect()
+    })
+}
+
+/// Whether a line is a copyright statement rather than license terms that wrapped onto a line
+/// starting with "copyright"
+pub(crate) fn is_copyright_line(line: &str) -> bool {
+    let line = line.trim_start().to_lowercase();
+    match line.strip_prefix("copyright") {
+        Some(holder) => {
+            let holder = holder.trim_start();
+            holder.starts_with("(c)")
+                || holder.starts_with('©')
+                || holder.starts_with('<')
+                || holder.starts_with(|c: char| c.is_ascii_digit())
+        }
+        // Numbered clauses start with a digit too, so only the symbol marks a bare statement
+        None => line.starts_with("(c)") || line.starts_with('©'),
+    }
+}
+
+/// The distinct words of a license text, leaving out its copyright lines
+fn words(text: &str) -> HashSet<String> {
+    text.lines()
+        .filter(|line| !is_copyright_line(line))
+        .flat_map(|line| line.split(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .map(|word| match word.to_lowercase().as_str() {
+            "licence" => "license".to_string(),
+            word => word.to_string(),
+        })
+        .collect()
+}
+
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(b).count();
+    2.0 * shared as f64 / (a.len() + b.len()) as f64
+}
+
+/// The reference text of a corpus license
+pub fn reference_text(license: &str) -> Option<&'static str> {
+    CORPUS
+        .iter()
+        .find(|(id, _)| *id == license)
+        .map(|(_, text)| *text)
+}
+
+/// The corpus license `text` is closest to, if it's at least [`MATCH_THRESHOLD`] similar
+pub fn closest_license(text: &str) -> Option<CorpusMatch> {
+    let text = words(text);
+    corpus()
+        .iter()
+        .map(|(license, reference)| CorpusMatch {
+            license,
+            similarity: similarity(&text, reference),
+        })
+        .filter(|candidate| candidate.similarity >= MATCH_THRESHOLD)
+        .max_by(|a, b| a.similarity.total_cmp(&b.similarity))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closest(text: &str) -> Option<&'static str> {
+        closest_license(text).map(|found| found.license)
+    }
+
+    #[test]
+    fn test_corpus_texts_match_themselves() {
+        for (license, text) in CORPUS {
+            let found = closest_license(text).expect(license);
+            assert_eq!(found.license, *license);
+            assert!((found.similarity - 1.0).abs() < f64::EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_untitled_texts_are_classified() {
+        let bsd = "Copyright (c) 2016, The Regents of the University of California\n\
+            All rights reserved.\n\n\
+            Redistribution and use in source and binary forms, with or without modification, \
+            are permitted provided that the following conditions are met:\n\
+            * Redistributions of source code must retain the above copyright notice, this list \
+            of conditions and the following disclaimer.\n\
+            * Redistributions in binary form must reproduce the above copyright notice, this \
+            list of conditions and the following disclaimer in the documentation and/or other \
+            materials provided with the distribution.\n\
+            * Neither the name of the University nor the names of its contributors may be used \
+            to endorse or promote products derived from this software without specific prior \
+            written permission.\n\n\
+            THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS \"AS IS\" AND \
+            ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED \
+            WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. \
+            IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, \
+            INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT \
+            NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR \
+            PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, \
+            WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) \
+            ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE \
+            POSSIBILITY OF SUCH DAMAGE.";
+        assert_eq!(closest(bsd), Some("BSD-3-Clause"));
+
+        let isc = "Copyright (c) Isaac Z. Schlueter and Contributors\n\n\
+            Permission to use, copy, modify, and/or distribute this software for any purpose \
+            with or without fee is hereby granted, provided that the above copyright notice \
+            and this permission notice appear in all copies.\n\n\
+            THE SOFTWARE IS PROVIDED \"AS IS\" AND THE AUTHOR DISCLAIMS ALL WARRANTIES WITH \
+            REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF MERCHANTABILITY AND \
+            FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR ANY SPECIAL, DIRECT, INDIRECT, \
+            OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES WHATSOEVER RESULTING FROM LOSS OF USE, DATA \
+            OR PROFITS, WHETHER IN AN ACTION OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, \
+            ARISING OUT OF OR IN CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.";
+        assert_eq!(closest(isc), Some("ISC"));
+    }
+
+    #[test]
+    fn test_close_relatives_stay_apart() {
+        let bsd_2 = CORPUS
+            .iter()
+            .find(|(license, _)| *license == "BSD-2-Clause")
+            .map(|(_, text)| *text)
+            .unwrap();
+        assert_eq!(closest(bsd_2), Some("BSD-2-Clause"));
+
+        let mit_0 = CORPUS
+            .iter()
+            .find(|(license, _)| *license == "MIT-0")
+            .map(|(_, text)| text.replace("MIT No Attribution", ""))
+            .unwrap();
+        assert_eq!(closest(&mit_0), Some("MIT-0"));
+    }
+
+    #[test]
+    fn test_unrelated_text_has_no_match() {
+        assert_eq!(
+            closest("# left-pad\n\nPads the left side of a string."),
+            None
+        );
+        assert_eq!(closest(""), None);
+    }
+}
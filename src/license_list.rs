@@ -0,0 +1,627 @@
+//! SPDX license list data
+//!
+//! `feluda licenses update` downloads `json/licenses.json` from
+//! [spdx/license-list-data](https://github.com/spdx/license-list-data) into the cache, so
+//! license knowledge (identifiers, OSI/FSF flags, deprecations) can move ahead of feluda
+//! releases. A release can be pinned with `--version`, and `--rollback` restores the list
+//! that was cached before the last update.
+//!
+//! `feluda licenses list` combines that list with the GitHub license registry and the
+//! restrictive configuration to show how every known license will be classified.
+//!
+//! `feluda check-compat` answers a single compatibility question against the matrix and
+//! explains which rule produced the verdict.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+use crate::cache;
+use crate::cli::LicenseClass;
+use crate::data_licenses::{data_license_class, DataLicenseClass};
+use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
+use crate::licenses::{
+    fetch_licenses_from_github, get_osi_status, is_license_restrictive, License,
+    LicenseCompatibility, OsiStatus,
+};
+use crate::reporter::TableFormatter;
+
+const SPDX_LICENSE_LIST_URL: &str =
+    "https://raw.githubusercontent.com/spdx/license-list-data/{ref}/json/licenses.json";
+
+/// The subset of `licenses.json` feluda keeps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxLicenseList {
+    pub license_list_version: String,
+    #[serde(default)]
+    pub release_date: Option<String>,
+    pub licenses: Vec<SpdxLicenseEntry>,
+    /// Set when the list was fetched with an explicit `--version`
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl SpdxLicenseList {
+    /// One-line count of the licenses by OSI/FSF/deprecation flags
+    pub fn summary(&self) -> String {
+        let count =
+            |pred: fn(&SpdxLicenseEntry) -> bool| self.licenses.iter().filter(|l| pred(l)).count();
+        format!(
+            "{} licenses: {} OSI-approved, {} FSF libre, {} deprecated",
+            self.licenses.len(),
+            count(|l| l.is_osi_approved),
+            count(|l| l.is_fsf_libre),
+            count(|l| l.is_deprecated_license_id),
+        )
+    }
+}
+
+/// One license from the SPDX list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxLicenseEntry {
+    pub license_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub is_osi_approved: bool,
+    #[serde(default)]
+    pub is_fsf_libre: bool,
+    #[serde(default)]
+    pub is_deprecated_license_id: bool,
+}
+
+/// Load the SPDX license list stored by `feluda licenses update`, if any
+pub fn load_cached_license_list() -> Option<SpdxLicenseList> {
+    let content = match cache::load_spdx_license_list() {
+        Ok(content) => content?,
+        Err(e) => {
+            log(
+                LogLevel::Warn,
+                &format!("Failed to read SPDX license list: {e}"),
+            );
+            return None;
+        }
+    };
+    match serde_json::from_str(&content) {
+        Ok(list) => Some(list),
+        Err(e) => {
+            log(
+                LogLevel::Warn,
+                &format!("Cached SPDX license list is corrupt, ignoring it: {e}"),
+            );
+            None
+        }
+    }
+}
+
+/// The `isOsiApproved` snapshot of the SPDX license list shipped with feluda
+const EMBEDDED_OSI_STATUS_TOML: &str = include_str!("../config/spdx_osi_status.toml");
+
+#[derive(Debug, Deserialize)]
+struct OsiStatusSnapshot {
+    approved: Vec<String>,
+    not_approved: Vec<String>,
+}
+
+/// OSI approval by SPDX identifier: the embedded snapshot, overridden by the full list cached
+/// by `feluda licenses update` when there is one. Identifiers in neither are left out.
+pub fn osi_statuses() -> HashMap<String, OsiStatus> {
+    #[cfg_attr(test, allow(unused_mut))]
+    let mut statuses = embedded_osi_statuses();
+    // Tests stay independent of whatever list the machine has cached
+    #[cfg(not(test))]
+    if let Some(list) = load_cached_license_list() {
+        statuses.extend(list_osi_statuses(&list));
+    }
+    statuses
+}
+
+fn embedded_osi_statuses() -> HashMap<String, OsiStatus> {
+    let snapshot: OsiStatusSnapshot = match toml::from_str(EMBEDDED_OSI_STATUS_TOML) {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            log(
+                LogLevel::Error,
+                &format!("Embedded OSI status snapshot is invalid: {e}"),
+            );
+            return HashMap::new();
+        }
+    };
+    let approved = snapshot
+        .approved
+        .into_iter()
+        .map(|id| (id, OsiStatus::Approved));
+    let not_approved = snapshot
+        .not_approved
+        .into_iter()
+        .map(|id| (id, OsiStatus::NotApproved));
+    approved.chain(not_approved).collect()
+}
+
+fn list_osi_statuses(list: &SpdxLicenseList) -> impl Iterator<Item = (String, OsiStatus)> + '_ {
+    list.licenses.iter().map(|entry| {
+        let status = if entry.is_osi_approved {
+            OsiStatus::Approved
+        } else {
+            OsiStatus::NotApproved
+        };
+        (entry.license_id.clone(), status)
+    })
+}
+
+/// Handle `feluda licenses update`
+pub fn handle_licenses_update_command(version: Option<String>, rollback: bool) -> FeludaResult<()> {
+    if rollback {
+        if cache::rollback_spdx_license_list()? {
+            let restored = load_cached_license_list()
+                .map(|list| list.license_list_version)
+                .unwrap_or_else(|| "unknown".to_string());
+            println!(
+                "{} Rolled back to SPDX license list {}",
+                "✓".green().bold(),
+                restored.bold()
+            );
+        } else {
+            println!("No previous SPDX license list to roll back to.");
+        }
+        return Ok(());
+    }
+
+    let current = load_cached_license_list();
+    if version.is_none() {
+        if let Some(list) = current.as_ref().filter(|list| list.pinned) {
+            println!(
+                "SPDX license list is pinned to {}. Pass --version latest to unpin.",
+                list.license_list_version.bold()
+            );
+            return Ok(());
+        }
+    }
+
+    let git_ref = release_ref(version.as_deref());
+    let mut list = fetch_license_list(&git_ref)?;
+    list.pinned = git_ref != "main";
+
+    if let Some(current) = current {
+        if current.license_list_version == list.license_list_version
+            && current.pinned == list.pinned
+        {
+            println!(
+                "{} SPDX license list {} is already up to date ({} licenses)",
+                "✓".green().bold(),
+                list.license_list_version.bold(),
+                list.licenses.len()
+            );
+            return Ok(());
+        }
+    }
+
+    let json = serde_json::to_string(&list)
+        .map_err(|e| FeludaError::Serialization(format!("SPDX license list: {e}")))?;
+    let path = cache::save_spdx_license_list(&json)?;
+
+    println!(
+        "{} SPDX license list {}{}{} saved to {}",
+        "✓".green().bold(),
+        list.license_list_version.bold(),
+        list.release_date
+            .as_deref()
+            .map(|date| format!(" ({date})"))
+            .unwrap_or_default(),
+        if list.pinned { ", pinned" } else { "" },
+        path.display()
+    );
+    println!("   {}", list.summary());
+    println!("   Run `feluda licenses update --rollback` to restore the previous list.");
+    Ok(())
+}
+
+/// How Feluda classifies one license
+#[derive(Debug, Clone, Serialize)]
+pub struct KnownLicense {
+    pub id: String,
+    pub name: String,
+    pub restrictive: bool,
+    pub osi_status: OsiStatus,
+    /// `None` when no SPDX license list is cached
+    pub fsf_libre: Option<bool>,
+    pub deprecated: bool,
+    /// Conditions from the GitHub license registry (e.g. `disclose-source`)
+    pub conditions: Vec<String>,
+    /// Class of a data or content license, such as `attribution` for CC-BY-4.0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_class: Option<DataLicenseClass>,
+}
+
+impl KnownLicense {
+    fn is_in_class(&self, class: &LicenseClass) -> bool {
+        match class {
+            LicenseClass::Restrictive => self.restrictive,
+            LicenseClass::Permissive => !self.restrictive,
+            LicenseClass::Osi => self.osi_status == OsiStatus::Approved,
+            LicenseClass::Fsf => self.fsf_libre == Some(true),
+            LicenseClass::Deprecated => self.deprecated,
+            LicenseClass::Data => self.data_class.is_some(),
+        }
+    }
+}
+
+/// Handle `feluda licenses list`
+pub fn handle_licenses_list_command(
+    class: Option<LicenseClass>,
+    json: bool,
+    strict: bool,
+) -> FeludaResult<()> {
+    let registry = fetch_licenses_from_github()?;
+    let spdx_list = load_cached_license_list();
+
+    let mut licenses = build_known_licenses(
+        &registry,
+        spdx_list.as_ref(),
+        &|id| is_license_restrictive(&Some(id.to_string()), &registry, strict),
+        &get_osi_status,
+    );
+    if let Some(ref class) = class {
+        licenses.retain(|license| license.is_in_class(class));
+    }
+
+    if json {
+        let output = serde_json::to_string_pretty(&licenses)
+            .map_err(|e| FeludaError::Serialization(format!("license list: {e}")))?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    let mut formatter = TableFormatter::new(
+        [
+            "License",
+            "Name",
+            "Restrictive",
+            "OSI",
+            "FSF",
+            "Data",
+            "Conditions",
+        ]
+        .map(String::from)
+        .to_vec(),
+    );
+    let rows: Vec<Vec<String>> = licenses
+        .iter()
+        .map(|license| {
+            vec![
+                if license.deprecated {
+                    format!("{} (deprecated)", license.id)
+                } else {
+                    license.id.clone()
+                },
+                license.name.clone(),
+                license.restrictive.to_string(),
+                license.osi_status.to_string(),
+                match license.fsf_libre {
+                    Some(true) => "yes".to_string(),
+                    Some(false) => "no".to_string(),
+                    None => "-".to_string(),
+                },
+                license
+                    .data_class
+                    .map_or_else(|| "-".to_string(), |class| class.to_string()),
+                license.conditions.join(", "),
+            ]
+        })
+        .collect();
+    for row in &rows {
+        formatter.add_row(row);
+    }
+
+    println!("\n{}", formatter.render_header());
+    for (row, license) in rows.iter().zip(&licenses) {
+        println!("{}", formatter.render_row(row, license.restrictive));
+    }
+    println!("{}", formatter.render_footer());
+
+    println!(
+        "\n{} licenses{}",
+        licenses.len().to_string().bold(),
+        class
+            .map(|class| format!(" in class {class:?}").to_lowercase())
+            .unwrap_or_default()
+    );
+    match spdx_list {
+        Some(list) => println!("SPDX license list {}", list.license_list_version),
+        None => println!(
+            "{}",
+            "No SPDX license list cached; run `feluda licenses update` for OSI/FSF data on every SPDX license."
+                .dimmed()
+        ),
+    }
+    Ok(())
+}
+
+/// Merge the GitHub registry and the SPDX list into one sorted classification table
+fn build_known_licenses(
+    registry: &HashMap<String, License>,
+    spdx_list: Option<&SpdxLicenseList>,
+    is_restrictive: &dyn Fn(&str) -> bool,
+    osi_status: &dyn Fn(&str) -> OsiStatus,
+) -> Vec<KnownLicense> {
+    let mut merged: BTreeMap<String, KnownLicense> = BTreeMap::new();
+
+    for entry in spdx_list
+        .map(|list| list.licenses.as_slice())
+        .unwrap_or(&[])
+    {
+        merged.insert(
+            entry.license_id.clone(),
+            KnownLicense {
+                id: entry.license_id.clone(),
+                name: entry.name.clone(),
+                restrictive: false,
+                osi_status: if entry.is_osi_approved {
+                    OsiStatus::Approved
+                } else {
+                    OsiStatus::NotApproved
+                },
+                fsf_libre: Some(entry.is_fsf_libre),
+                deprecated: entry.is_deprecated_license_id,
+                conditions: Vec::new(),
+                data_class: None,
+            },
+        );
+    }
+
+    for (id, license) in registry {
+        let known = merged.entry(id.clone()).or_insert_with(|| KnownLicense {
+            id: id.clone(),
+            name: license.title.clone(),
+            restrictive: false,
+            osi_status: osi_status(id),
+            fsf_libre: spdx_list.map(|_| false),
+            deprecated: false,
+            conditions: Vec::new(),
+            data_class: None,
+        });
+        known.conditions = license.conditions.clone();
+    }
+
+    merged
+        .into_values()
+        .map(|mut known| {
+            known.restrictive = is_restrictive(&known.id);
+            known.data_class = data_license_class(&known.id);
+            known
+        })
+        .collect()
+}
+
+/// Map a `--version` value to a git ref in spdx/license-list-data
+fn release_ref(version: Option<&str>) -> String {
+    match version.map(str::trim) {
+        None | Some("") | Some("latest") => "main".to_string(),
+        Some(v) if v.starts_with('v') => v.to_string(),
+        Some(v) => format!("v{v}"),
+    }
+}
+
+fn fetch_license_list(git_ref: &str) -> FeludaResult<SpdxLicenseList> {
+    if crate::cancel::is_offline() {
+        return Err(FeludaError::Config(
+            "Cannot fetch the SPDX license list with --offline".to_string(),
+        ));
+    }
+    let url = SPDX_LICENSE_LIST_URL.replace("{ref}", git_ref);
+    log(
+        LogLevel::Info,
+        &format!("Fetching SPDX license list: {url}"),
+    );
+
+    crate::cli::with_spinner("Fetching SPDX license list", |indicator| {
+        indicator.update_progress(git_ref);
+        let request = crate::cancel::shared_client()?
+            .get(&url)
+            .timeout(crate::cancel::request_timeout(Duration::from_secs(60)));
+        let response = crate::cancel::http_send(request, &url)?;
+        if !response.status().is_success() {
+            return Err(FeludaError::InvalidData(format!(
+                "SPDX license list '{git_ref}' not found ({})",
+                response.status()
+            )));
+        }
+        parse_license_list(&response.text()?)
+    })
+}
+
+fn parse_license_list(content: &str) -> FeludaResult<SpdxLicenseList> {
+    let list: SpdxLicenseList = serde_json::from_str(content)
+        .map_err(|e| FeludaError::InvalidData(format!("Invalid SPDX license list: {e}")))?;
+    if list.licenses.is_empty() {
+        return Err(FeludaError::InvalidData(
+            "SPDX license list contains no licenses".to_string(),
+        ));
+    }
+    Ok(list)
+}
+
+/// Explain whether a dependency license fits a project license, without scanning anything
+pub fn handle_check_compat_command(
+    project_license: &str,
+    dependency_license: &str,
+    strict: bool,
+    json: bool,
+) -> FeludaResult<()> {
+    let config = crate::config::load_config()?;
+    crate::licenses::set_license_ref_classes(&config.licenses.refs);
+    crate::licenses::set_special_license_policies(&config.licenses);
+    crate::licenses::set_license_policy(&config.licenses);
+
+    let explanation =
+        crate::licenses::explain_license_compatibility(dependency_license, project_license, strict);
+
+    if json {
+        let output = serde_json::to_string_pretty(&explanation).map_err(|e| {
+            FeludaError::Serialization(format!("Failed to serialize compatibility result: {e}"))
+        })?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    let verdict = match explanation.verdict {
+        LicenseCompatibility::Compatible => "✅ Compatible".green().bold(),
+        LicenseCompatibility::Incompatible => "❌ Incompatible".red().bold(),
+        LicenseCompatibility::Unknown => "❓ Unknown".yellow().bold(),
+    };
+    println!(
+        "\n{verdict}: {} dependency in a {} project\n",
+        dependency_license.bold(),
+        project_license.bold()
+    );
+    for reason in &explanation.reasons {
+        println!("  • {reason}");
+    }
+    println!();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_ref() {
+        assert_eq!(release_ref(None), "main");
+        assert_eq!(release_ref(Some("latest")), "main");
+        assert_eq!(release_ref(Some("3.24")), "v3.24");
+        assert_eq!(release_ref(Some("v3.24")), "v3.24");
+    }
+
+    #[test]
+    fn test_embedded_osi_statuses() {
+        let statuses = embedded_osi_statuses();
+        assert_eq!(statuses.get("MIT"), Some(&OsiStatus::Approved));
+        assert_eq!(statuses.get("GPL-3.0-or-later"), Some(&OsiStatus::Approved));
+        assert_eq!(statuses.get("CC0-1.0"), Some(&OsiStatus::NotApproved));
+        assert_eq!(statuses.get("SSPL-1.0"), Some(&OsiStatus::NotApproved));
+        assert_eq!(statuses.get("LicenseRef-Proprietary"), None);
+    }
+
+    #[test]
+    fn test_list_osi_statuses() {
+        let list = parse_license_list(
+            r#"{
+                "licenseListVersion": "3.24",
+                "licenses": [
+                    {"licenseId": "MIT", "name": "MIT License", "isOsiApproved": true},
+                    {"licenseId": "CC0-1.0", "name": "Creative Commons Zero v1.0 Universal"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let statuses: HashMap<_, _> = list_osi_statuses(&list).collect();
+        assert_eq!(statuses["MIT"], OsiStatus::Approved);
+        assert_eq!(statuses["CC0-1.0"], OsiStatus::NotApproved);
+    }
+
+    #[test]
+    fn test_parse_license_list_upstream_format() {
+        let list = parse_license_list(
+            r#"{
+                "licenseListVersion": "3.24",
+                "licenses": [
+                    {"reference": "https://spdx.org/licenses/MIT.html", "licenseId": "MIT",
+                     "name": "MIT License", "isOsiApproved": true, "isFsfLibre": true,
+                     "isDeprecatedLicenseId": false, "seeAlso": []},
+                    {"licenseId": "GPL-2.0", "name": "GNU General Public License v2.0 only",
+                     "isOsiApproved": true, "isDeprecatedLicenseId": true}
+                ],
+                "releaseDate": "2024-05-22"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(list.license_list_version, "3.24");
+        assert_eq!(list.release_date.as_deref(), Some("2024-05-22"));
+        assert!(!list.pinned);
+        assert!(list.licenses[0].is_fsf_libre);
+        assert!(!list.licenses[1].is_fsf_libre);
+        assert!(list.licenses[1].is_deprecated_license_id);
+        assert_eq!(
+            list.summary(),
+            "2 licenses: 2 OSI-approved, 1 FSF libre, 1 deprecated"
+        );
+    }
+
+    #[test]
+    fn test_build_known_licenses_merges_sources() {
+        let mut registry = HashMap::new();
+        registry.insert(
+            "GPL-3.0".to_string(),
+            License {
+                title: "GNU General Public License v3.0".to_string(),
+                spdx_id: "GPL-3.0".to_string(),
+                permissions: vec![],
+                conditions: vec!["disclose-source".to_string()],
+                limitations: vec![],
+            },
+        );
+        let spdx_list = parse_license_list(
+            r#"{"licenseListVersion": "3.24", "licenses": [
+                {"licenseId": "MIT", "name": "MIT License", "isOsiApproved": true, "isFsfLibre": true},
+                {"licenseId": "GPL-3.0", "name": "GNU General Public License v3.0 only",
+                 "isOsiApproved": true, "isDeprecatedLicenseId": true}
+            ]}"#,
+        )
+        .unwrap();
+
+        let licenses = build_known_licenses(
+            &registry,
+            Some(&spdx_list),
+            &|id| id.starts_with("GPL"),
+            &|_| OsiStatus::Unknown,
+        );
+
+        assert_eq!(licenses.len(), 2);
+        assert_eq!(licenses[0].id, "GPL-3.0");
+        assert!(licenses[0].restrictive);
+        assert!(licenses[0].deprecated);
+        assert_eq!(licenses[0].conditions, vec!["disclose-source"]);
+        assert_eq!(licenses[0].osi_status, OsiStatus::Approved);
+        assert_eq!(licenses[1].id, "MIT");
+        assert_eq!(licenses[1].fsf_libre, Some(true));
+        assert!(licenses[1].is_in_class(&LicenseClass::Permissive));
+        assert!(!licenses[1].is_in_class(&LicenseClass::Restrictive));
+
+        let without_spdx =
+            build_known_licenses(&registry, None, &|_| true, &|_| OsiStatus::Approved);
+        assert_eq!(without_spdx.len(), 1);
+        assert_eq!(without_spdx[0].fsf_libre, None);
+        assert!(without_spdx[0].is_in_class(&LicenseClass::Osi));
+    }
+
+    #[test]
+    fn test_build_known_licenses_marks_data_licenses() {
+        let spdx_list = parse_license_list(
+            r#"{"licenseListVersion": "3.24", "licenses": [
+                {"licenseId": "CC-BY-SA-4.0", "name": "Creative Commons Attribution Share Alike 4.0 International"},
+                {"licenseId": "MIT", "name": "MIT License", "isOsiApproved": true}
+            ]}"#,
+        )
+        .unwrap();
+
+        let licenses = build_known_licenses(&HashMap::new(), Some(&spdx_list), &|_| false, &|_| {
+            OsiStatus::Unknown
+        });
+
+        assert_eq!(licenses[0].data_class, Some(DataLicenseClass::ShareAlike));
+        assert!(licenses[0].is_in_class(&LicenseClass::Data));
+        assert_eq!(licenses[1].data_class, None);
+        assert!(!licenses[1].is_in_class(&LicenseClass::Data));
+    }
+
+    #[test]
+    fn test_parse_license_list_rejects_empty() {
+        assert!(parse_license_list(r#"{"licenseListVersion": "3.24", "licenses": []}"#).is_err());
+        assert!(parse_license_list("not json").is_err());
+    }
+}
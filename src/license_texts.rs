@@ -0,0 +1,230 @@
+//! `--licenses-dir`: the license texts a release has to ship, one file per unique text
+//!
+//! Each dependency's license text is read from the local toolchain caches or fetched from its
+//! registry, the same way `feluda generate` finds it for THIRD_PARTY_LICENSES. Dependencies
+//! sharing an identical text share a file named after the license; a license whose texts differ
+//! (an MIT text carries its own copyright line) or a custom license gets a file per text.
+//! `index.json` maps every file to the packages using it and lists the texts that couldn't be
+//! found, so a packaging step can fail on them.
+
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::cli::with_spinner;
+use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
+use crate::generate::fetch_actual_license_content;
+use crate::licenses::LicenseInfo;
+
+/// Index of the written files, next to them in the directory
+const INDEX_FILE: &str = "index.json";
+
+/// One license text and the packages it covers
+#[derive(Debug, Serialize)]
+struct LicenseFile {
+    file: String,
+    license: String,
+    packages: Vec<String>,
+    #[serde(skip)]
+    text: String,
+}
+
+/// `index.json`: what was written, and what couldn't be
+#[derive(Debug, Serialize)]
+struct LicenseIndex<'a> {
+    files: &'a [LicenseFile],
+    missing: Vec<String>,
+}
+
+/// Fetch the license text of every dependency and write the unique texts into `dir`
+pub fn write_license_texts(
+    dependencies: &[LicenseInfo],
+    dir: &Path,
+    project_root: &Path,
+) -> FeludaResult<()> {
+    // The organisation's own packages have nothing to ship
+    let shipped: Vec<&LicenseInfo> = dependencies.iter().filter(|dep| !dep.internal).collect();
+
+    let texts = with_spinner(
+        &format!("Fetching license texts for {} dependencies", shipped.len()),
+        |indicator| {
+            shipped
+                .iter()
+                .enumerate()
+                .map(|(index, dep)| {
+                    indicator.update_progress(&format!("{}/{}", index + 1, shipped.len()));
+                    let text = fetch_actual_license_content(&dep.name, &dep.version, project_root);
+                    (*dep, text)
+                })
+                .collect::<Vec<_>>()
+        },
+    );
+    let (files, missing) = group_license_texts(texts);
+
+    fs::create_dir_all(dir).map_err(|e| {
+        FeludaError::FileWrite(format!(
+            "Failed to create licenses directory {}: {e}",
+            dir.display()
+        ))
+    })?;
+    for file in &files {
+        fs::write(dir.join(&file.file), &file.text).map_err(|e| {
+            FeludaError::FileWrite(format!("Failed to write license text {}: {e}", file.file))
+        })?;
+    }
+    let index = serde_json::to_string_pretty(&LicenseIndex {
+        files: &files,
+        missing: missing.clone(),
+    })
+    .map_err(|e| FeludaError::Serialization(format!("Failed to serialize {INDEX_FILE}: {e}")))?;
+    fs::write(dir.join(INDEX_FILE), index)
+        .map_err(|e| FeludaError::FileWrite(format!("Failed to write {INDEX_FILE}: {e}")))?;
+
+    log(
+        LogLevel::Info,
+        &format!(
+            "Wrote {} license texts to {} ({} missing)",
+            files.len(),
+            dir.display(),
+            missing.len()
+        ),
+    );
+    eprintln!(
+        "License texts written to: {} ({} files)",
+        dir.display(),
+        files.len()
+    );
+    if !missing.is_empty() {
+        eprintln!(
+            "No license text found for {} dependencies, listed under \"missing\" in {INDEX_FILE}: {}",
+            missing.len(),
+            missing.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Group dependencies by identical license text, naming each file after its license
+///
+/// Returns the files in first-seen order, and the `name@version` of every dependency whose
+/// text wasn't found.
+fn group_license_texts(
+    texts: Vec<(&LicenseInfo, Option<String>)>,
+) -> (Vec<LicenseFile>, Vec<String>) {
+    let mut files: Vec<LicenseFile> = Vec::new();
+    let mut missing = Vec::new();
+
+    for (dep, text) in texts {
+        let package = format!("{}@{}", dep.name, dep.version);
+        let Some(text) = text.map(|text| normalize_text(&text)) else {
+            missing.push(package);
+            continue;
+        };
+
+        if let Some(file) = files.iter_mut().find(|file| file.text == text) {
+            if !file.packages.contains(&package) {
+                file.packages.push(package);
+            }
+            continue;
+        }
+
+        let license = dep.get_license();
+        let stem = file_stem(&license);
+        let taken = files
+            .iter()
+            .filter(|file| file_stem(&file.license) == stem)
+            .count();
+        let file = if taken == 0 {
+            format!("{stem}.txt")
+        } else {
+            format!("{stem}-{}.txt", taken + 1)
+        };
+        files.push(LicenseFile {
+            file,
+            license,
+            packages: vec![package],
+            text,
+        });
+    }
+
+    (files, missing)
+}
+
+/// Line endings and trailing whitespace differ between copies of the same text
+fn normalize_text(text: &str) -> String {
+    let mut normalized: String = text
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string();
+    normalized.push('\n');
+    normalized
+}
+
+/// A license ID or expression as a file name: `MIT OR Apache-2.0` becomes `MIT_OR_Apache-2.0`
+fn file_stem(license: &str) -> String {
+    let stem: String = license
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let stem = stem.trim_matches(['_', '.']).to_string();
+    if stem.is_empty() {
+        "UNKNOWN".to_string()
+    } else {
+        stem
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_license_texts() {
+        let serde = LicenseInfo::fixture("serde", "1.0.0", "MIT");
+        let itoa = LicenseInfo::fixture("itoa", "1.0.0", "MIT");
+        let ryu = LicenseInfo::fixture("ryu", "1.0.0", "MIT");
+        let acme = LicenseInfo::fixture("acme-sdk", "1.0.0", "LicenseRef-Acme Proprietary");
+        let left_pad = LicenseInfo::fixture("left-pad", "1.0.0", "WTFPL");
+
+        let (files, missing) = group_license_texts(vec![
+            (
+                &serde,
+                Some("MIT License\r\nCopyright Serde  \r\n".to_string()),
+            ),
+            (&itoa, Some("MIT License\nCopyright Serde\n".to_string())),
+            (&ryu, Some("MIT License\nCopyright Ryu\n".to_string())),
+            (&acme, Some("Acme terms".to_string())),
+            (&left_pad, None),
+        ]);
+
+        let names: Vec<&str> = files.iter().map(|file| file.file.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["MIT.txt", "MIT-2.txt", "LicenseRef-Acme_Proprietary.txt"]
+        );
+        assert_eq!(files[0].packages, vec!["serde@1.0.0", "itoa@1.0.0"]);
+        assert_eq!(files[0].text, "MIT License\nCopyright Serde\n");
+        assert_eq!(files[1].packages, vec!["ryu@1.0.0"]);
+        assert_eq!(missing, vec!["left-pad@1.0.0"]);
+    }
+
+    #[test]
+    fn test_file_stem() {
+        assert_eq!(file_stem("Apache-2.0"), "Apache-2.0");
+        assert_eq!(file_stem("MIT OR Apache-2.0"), "MIT_OR_Apache-2.0");
+        assert_eq!(file_stem("GPL-2.0+"), "GPL-2.0+");
+        assert_eq!(file_stem("../../etc/passwd"), "etc_passwd");
+        assert_eq!(file_stem("  "), "UNKNOWN");
+    }
+}
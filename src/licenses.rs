@@ -4,38 +4,123 @@ use crate::spdx;
 use ignore::WalkBuilder;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::sync::OnceLock;
+use std::sync::{Mutex, OnceLock, RwLock};
 use std::time::Duration;
 use toml::Value as TomlValue;
 
 use crate::cache;
 use crate::cli;
 use crate::config;
+use crate::data_licenses::{self, DataLicenseClass};
 use crate::debug::{log, log_debug, log_error, FeludaResult, LogLevel};
+use crate::license_corpus;
+use crate::manifest::ManifestLocation;
+use crate::suggestions::FixSuggestion;
+use crate::updates::UpdatePolicy;
 
-static GITHUB_TOKEN: OnceLock<Option<String>> = OnceLock::new();
+static GITHUB_TOKEN: RwLock<Option<String>> = RwLock::new(None);
 
 /// Set the GitHub API token for authenticated requests
 pub fn set_github_token(token: Option<String>) {
-    let _ = GITHUB_TOKEN.set(token);
+    *GITHUB_TOKEN.write().unwrap_or_else(|e| e.into_inner()) = token;
 }
 
 /// Get the GitHub API token if set
-fn get_github_token() -> Option<&'static str> {
-    GITHUB_TOKEN.get().and_then(|t| t.as_deref())
+pub(crate) fn get_github_token() -> Option<String> {
+    GITHUB_TOKEN
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// The public GitHub REST API, used unless `[github] api` names a GitHub Enterprise Server
+const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// `(API base URL, web host)` of the configured GitHub, `None` for github.com
+static GITHUB_API: RwLock<Option<(String, String)>> = RwLock::new(None);
+
+/// Send GitHub API requests to a GitHub Enterprise Server (`https://github.example.com/api/v3`)
+/// instead of github.com, or back to github.com with `None`
+pub fn set_github_api_url(api_url: Option<String>) {
+    let api = api_url.map(|api_url| {
+        let api_url = api_url.trim_end_matches('/').to_string();
+        let host = github_web_host(&api_url);
+        (api_url, host)
+    });
+    *GITHUB_API.write().unwrap_or_else(|e| e.into_inner()) = api;
+}
+
+/// Base URL of the GitHub REST API, without a trailing slash
+pub(crate) fn github_api_url() -> String {
+    GITHUB_API
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map_or_else(
+            || GITHUB_API_URL.to_string(),
+            |(api_url, _)| api_url.clone(),
+        )
+}
+
+/// Host that repository URLs on the configured GitHub point at: `github.com`, or the
+/// Enterprise Server's own host
+pub(crate) fn github_host() -> String {
+    GITHUB_API
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map_or_else(|| "github.com".to_string(), |(_, host)| host.clone())
+}
+
+/// Puts the previous GitHub API URL and token back when dropped, see [`scope_github`]
+pub(crate) struct GithubScopeGuard {
+    api: Option<(String, String)>,
+    token: Option<String>,
+}
+
+impl Drop for GithubScopeGuard {
+    fn drop(&mut self) {
+        *GITHUB_API.write().unwrap_or_else(|e| e.into_inner()) = self.api.take();
+        *GITHUB_TOKEN.write().unwrap_or_else(|e| e.into_inner()) = self.token.take();
+    }
+}
+
+/// Until the guard is dropped, send GitHub API requests to `api_url` (github.com when `None`)
+/// with `token`
+pub(crate) fn scope_github(api_url: Option<String>, token: Option<String>) -> GithubScopeGuard {
+    let guard = GithubScopeGuard {
+        api: GITHUB_API.read().unwrap_or_else(|e| e.into_inner()).clone(),
+        token: get_github_token(),
+    };
+    set_github_api_url(api_url);
+    set_github_token(token);
+    guard
+}
+
+/// Web host of a GitHub API: Enterprise Server serves it under `/api/v3` on its own host,
+/// while github.com and GHE.com use an `api.` subdomain
+fn github_web_host(api_url: &str) -> String {
+    let host = api_url
+        .split_once("://")
+        .map_or(api_url, |(_, rest)| rest)
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    host.strip_prefix("api.").unwrap_or(host).to_string()
 }
 
 /// License compatibility enum
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum LicenseCompatibility {
     Compatible,
     Incompatible,
+    #[default]
     Unknown,
 }
 
@@ -49,53 +134,26 @@ impl std::fmt::Display for LicenseCompatibility {
     }
 }
 
-/// Structure for deserializing license compatibility matrix from TOML
-#[derive(Deserialize, Debug, Clone)]
-struct LicenseCompatibilityMatrix {
-    #[serde(rename = "MIT")]
-    mit: Option<LicenseEntry>,
-    #[serde(rename = "Apache-2_0")]
-    apache_2_0: Option<LicenseEntry>,
-    #[serde(rename = "GPL-3_0")]
-    gpl_3_0: Option<LicenseEntry>,
-    #[serde(rename = "GPL-2_0")]
-    gpl_2_0: Option<LicenseEntry>,
-    #[serde(rename = "AGPL-3_0")]
-    agpl_3_0: Option<LicenseEntry>,
-    #[serde(rename = "LGPL-3_0")]
-    lgpl_3_0: Option<LicenseEntry>,
-    #[serde(rename = "LGPL-2_1")]
-    lgpl_2_1: Option<LicenseEntry>,
-    #[serde(rename = "MPL-2_0")]
-    mpl_2_0: Option<LicenseEntry>,
-    #[serde(rename = "BSD-3-Clause")]
-    bsd_3_clause: Option<LicenseEntry>,
-    #[serde(rename = "BSD-2-Clause")]
-    bsd_2_clause: Option<LicenseEntry>,
-    #[serde(rename = "ISC")]
-    isc: Option<LicenseEntry>,
-    #[serde(rename = "_0BSD")]
-    bsd_0: Option<LicenseEntry>,
-    #[serde(rename = "Unlicense")]
-    unlicense: Option<LicenseEntry>,
-    #[serde(rename = "WTFPL")]
-    wtfpl: Option<LicenseEntry>,
-}
+/// Structure for deserializing license compatibility matrix from TOML: one section per
+/// project license
+type LicenseCompatibilityMatrix = HashMap<String, LicenseEntry>;
 
 #[derive(Deserialize, Debug, Clone)]
 struct LicenseEntry {
     compatible_with: Vec<String>,
 }
 
-/// Static cache for the compatibility matrix
+/// Static cache for the compatibility matrix, or why it couldn't be loaded
 #[cfg(not(test))]
-static COMPATIBILITY_MATRIX: OnceLock<HashMap<String, Vec<String>>> = OnceLock::new();
+static COMPATIBILITY_MATRIX: OnceLock<Result<HashMap<String, Vec<String>>, String>> =
+    OnceLock::new();
 
 /// OSI license status
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum OsiStatus {
     Approved,
     NotApproved,
+    #[default]
     Unknown,
 }
 
@@ -118,7 +176,8 @@ pub struct OsiLicenseInfo {
 }
 
 /// License Info of dependencies
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[non_exhaustive]
 pub struct LicenseInfo {
     pub name: String,                        // The name of the software or library
     pub version: String,                     // The version of the software or library
@@ -126,11 +185,54 @@ pub struct LicenseInfo {
     pub is_restrictive: bool,    // A boolean indicating whether the license is restrictive or not
     pub compatibility: LicenseCompatibility, // Compatibility with project license
     pub osi_status: OsiStatus,   // OSI approval status
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub sub_project: Option<String>, // Workspace member that brought in this dependency (None for non-monorepos)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub found_in: Vec<String>, // Project roots that reported this dependency (empty for single-root scans)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>, // Source repository, or the registry page when the manifest names none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub declared_in: Option<ManifestLocation>, // Manifest line declaring a direct dependency
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub internal: bool, // Matches an `[internal] namespaces` pattern: the organisation's own package
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inherited_from: Option<String>, // Upstream repo whose license a fork inherited (--resolve-forks)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ecosystem: Option<String>, // Project root type that reported this dependency (rust, node, ...)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<FixSuggestion>, // Remediations for a restrictive/incompatible dependency
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_policy: Option<UpdatePolicy>, // How the repo's Dependabot/Renovate config treats it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub introduced_by: Vec<String>, // Direct dependencies whose tree pulls this one in (empty for direct deps)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub purl: Option<String>, // Package URL, e.g. pkg:npm/lodash@4.17.21 (None where the ecosystem has no purl type)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vulnerabilities: Option<Vec<String>>, // OSV advisory ids affecting this version (--vulnerabilities; None when not checked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<String>, // Manifest or lockfile of the project root that reported it, relative to the scan root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_license: Option<DataLicenseClass>, // Class of a data or content license (CC-BY, ODbL, CC0, ...); None for software licenses
+    #[serde(skip)]
+    pub project_dirs: Vec<String>, // Directories of the project roots that reported it, relative to the scan root; picks the [project_licenses] entry
 }
 
 impl LicenseInfo {
+    /// A dependency that hasn't been checked yet: not restrictive, compatibility and OSI status
+    /// unknown, and none of the optional details set
+    pub fn new(
+        name: impl Into<String>,
+        version: impl Into<String>,
+        license: Option<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            license,
+            ..Default::default()
+        }
+    }
+
     pub fn get_license(&self) -> String {
         match &self.license {
             Some(license_name) => String::from(license_name),
@@ -146,6 +248,10 @@ impl LicenseInfo {
         &self.version
     }
 
+    pub fn license(&self) -> Option<&str> {
+        self.license.as_deref()
+    }
+
     pub fn is_restrictive(&self) -> &bool {
         &self.is_restrictive
     }
@@ -162,6 +268,58 @@ impl LicenseInfo {
         self.sub_project.as_deref()
     }
 
+    pub fn found_in(&self) -> &[String] {
+        &self.found_in
+    }
+
+    pub fn repository(&self) -> Option<&str> {
+        self.repository.as_deref()
+    }
+
+    pub fn declared_in(&self) -> Option<&ManifestLocation> {
+        self.declared_in.as_ref()
+    }
+
+    pub fn is_internal(&self) -> bool {
+        self.internal
+    }
+
+    pub fn inherited_from(&self) -> Option<&str> {
+        self.inherited_from.as_deref()
+    }
+
+    pub fn ecosystem(&self) -> Option<&str> {
+        self.ecosystem.as_deref()
+    }
+
+    pub fn suggestions(&self) -> &[FixSuggestion] {
+        &self.suggestions
+    }
+
+    pub fn update_policy(&self) -> Option<UpdatePolicy> {
+        self.update_policy
+    }
+
+    pub fn introduced_by(&self) -> &[String] {
+        &self.introduced_by
+    }
+
+    pub fn vulnerabilities(&self) -> Option<&[String]> {
+        self.vulnerabilities.as_deref()
+    }
+
+    pub fn purl(&self) -> Option<&str> {
+        self.purl.as_deref()
+    }
+
+    pub fn manifest(&self) -> Option<&str> {
+        self.manifest.as_deref()
+    }
+
+    pub fn data_license(&self) -> Option<DataLicenseClass> {
+        self.data_license
+    }
+
     #[allow(dead_code)]
     pub fn osi_info(&self) -> Option<OsiLicenseInfo> {
         self.license.as_ref().map(|license| OsiLicenseInfo {
@@ -172,6 +330,38 @@ impl LicenseInfo {
     }
 }
 
+/// Rows for unit tests: the fields a test cares about, everything else left at its default
+#[cfg(test)]
+impl LicenseInfo {
+    pub(crate) fn fixture<'a>(
+        name: &str,
+        version: &str,
+        license: impl Into<Option<&'a str>>,
+    ) -> Self {
+        Self::new(name, version, license.into().map(str::to_string))
+    }
+
+    pub(crate) fn restrictive(mut self) -> Self {
+        self.is_restrictive = true;
+        self
+    }
+
+    pub(crate) fn with_compatibility(mut self, compatibility: LicenseCompatibility) -> Self {
+        self.compatibility = compatibility;
+        self
+    }
+
+    pub(crate) fn with_ecosystem(mut self, ecosystem: &str) -> Self {
+        self.ecosystem = Some(ecosystem.to_string());
+        self
+    }
+
+    pub(crate) fn with_repository(mut self, repository: &str) -> Self {
+        self.repository = Some(repository.to_string());
+        self
+    }
+}
+
 /// License Info structure for GitHub API data
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct License {
@@ -182,9 +372,16 @@ pub struct License {
     pub limitations: Vec<String>, // A list of limitations imposed by the license
 }
 
+/// Held while the license list is loaded, so analyzers running side by side on a cold cache
+/// wait for the first fetch to fill it instead of each fetching the list again
+static LICENSE_LIST_FETCH: Mutex<()> = Mutex::new(());
+
 /// Fetch license data from GitHub's official Licenses API
 /// Attempts to load from cache first, falls back to GitHub API if cache miss or stale
 pub fn fetch_licenses_from_github() -> FeludaResult<HashMap<String, License>> {
+    let _fetching = LICENSE_LIST_FETCH
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
     log(LogLevel::Info, "Fetching licenses from GitHub Licenses API");
 
     match cache::load_github_licenses_from_cache() {
@@ -206,6 +403,14 @@ pub fn fetch_licenses_from_github() -> FeludaResult<HashMap<String, License>> {
         }
     }
 
+    if crate::cancel::is_offline() {
+        log(
+            LogLevel::Warn,
+            "No cached GitHub license data and --offline is set, continuing without it",
+        );
+        return Ok(HashMap::new());
+    }
+
     let licenses_map = cli::with_spinner("Fetching licenses from GitHub API", |indicator| {
         let rt = match tokio::runtime::Builder::new_current_thread()
             .enable_all()
@@ -218,7 +423,15 @@ pub fn fetch_licenses_from_github() -> FeludaResult<HashMap<String, License>> {
             }
         };
 
-        rt.block_on(fetch_licenses_concurrent(indicator))
+        rt.block_on(async {
+            tokio::select! {
+                licenses = fetch_licenses_concurrent(indicator) => licenses,
+                _ = crate::cancel::cancelled() => {
+                    log(LogLevel::Warn, "License fetch cancelled, continuing without GitHub data");
+                    HashMap::new()
+                }
+            }
+        })
     });
 
     if !licenses_map.is_empty() {
@@ -244,7 +457,7 @@ async fn fetch_licenses_concurrent(
     // Create async HTTP client with optional authentication
     let mut client_builder = reqwest::Client::builder()
         .user_agent("feluda-license-checker/1.0")
-        .timeout(Duration::from_secs(30));
+        .timeout(crate::cancel::request_timeout(Duration::from_secs(30)));
 
     if let Some(token) = get_github_token() {
         log(
@@ -272,9 +485,13 @@ async fn fetch_licenses_concurrent(
     indicator.update_progress("fetching license list");
 
     // First, get the list of available licenses
-    let licenses_list_url = "https://api.github.com/licenses";
-    let response = match client.get(licenses_list_url).send().await {
-        Ok(response) => response,
+    let licenses_list_url = format!("{}/licenses", github_api_url());
+    let response = match client.get(&licenses_list_url).send().await {
+        Ok(response) => {
+            let source = crate::cancel::registry_source(&licenses_list_url);
+            crate::rate_limit::record(&source, response.headers());
+            response
+        }
         Err(err) => {
             log_error("Failed to fetch licenses list from GitHub API", &err);
             return licenses_map;
@@ -324,10 +541,14 @@ async fn fetch_licenses_concurrent(
                 &format!("Fetching detailed license info: {license_key}"),
             );
 
-            let license_url = format!("https://api.github.com/licenses/{license_key}");
+            let license_url = format!("{}/licenses/{license_key}", github_api_url());
 
             match client.get(&license_url).send().await {
                 Ok(license_response) => {
+                    crate::rate_limit::record(
+                        &crate::cancel::registry_source(&license_url),
+                        license_response.headers(),
+                    );
                     if license_response.status().is_success() {
                         match license_response.json::<serde_json::Value>().await {
                             Ok(license_data) => {
@@ -453,135 +674,22 @@ async fn fetch_licenses_concurrent(
     licenses_map
 }
 
-/// Static cache for OSI approved licenses
-#[cfg(not(test))]
+/// OSI approval by SPDX identifier, from the SPDX license list
 static OSI_LICENSES: OnceLock<HashMap<String, OsiStatus>> = OnceLock::new();
 
-/// Fetch OSI approved licenses from official API (single request, no async needed)
-pub fn fetch_osi_licenses() -> FeludaResult<HashMap<String, OsiStatus>> {
-    log(LogLevel::Info, "Fetching OSI approved licenses");
-
-    let osi_map = cli::with_spinner("Fetching OSI approved licenses", |indicator| {
-        let client = match reqwest::blocking::Client::builder()
-            .user_agent("feluda-license-checker/1.0")
-            .timeout(Duration::from_secs(30))
-            .build()
-        {
-            Ok(client) => client,
-            Err(err) => {
-                log_error("Failed to create HTTP client", &err);
-                return HashMap::new();
-            }
-        };
-
-        indicator.update_progress("fetching OSI licenses");
-
-        let response = match client.get("https://api.opensource.org/licenses/").send() {
-            Ok(response) => response,
-            Err(err) => {
-                log_error("Failed to fetch OSI licenses from API", &err);
-                return HashMap::new();
-            }
-        };
-
-        if !response.status().is_success() {
-            log(
-                LogLevel::Error,
-                &format!("OSI API returned error status: {}", response.status()),
-            );
-            return HashMap::new();
-        }
-
-        let osi_licenses: Vec<serde_json::Value> = match response.json() {
-            Ok(licenses) => licenses,
-            Err(err) => {
-                log_error("Failed to parse OSI licenses JSON", &err);
-                return HashMap::new();
-            }
-        };
-
-        let total_licenses = osi_licenses.len();
-        indicator.update_progress(&format!("found {total_licenses} OSI licenses"));
-
-        let mut osi_map = HashMap::new();
-        for license_data in osi_licenses {
-            if let Some(id) = license_data.get("id").and_then(|id| id.as_str()) {
-                osi_map.insert(id.to_string(), OsiStatus::Approved);
-            }
-        }
-
-        indicator.update_progress(&format!("processed {total_licenses} OSI licenses"));
-        log(
-            LogLevel::Info,
-            &format!("Fetched {total_licenses} OSI approved licenses"),
-        );
-
-        osi_map
-    });
-
-    Ok(osi_map)
-}
-
-/// Get the OSI licenses map, loading it if not already cached
 fn get_osi_licenses() -> &'static HashMap<String, OsiStatus> {
-    #[cfg(not(test))]
-    {
-        OSI_LICENSES.get_or_init(|| {
-            fetch_osi_licenses().unwrap_or_else(|e| {
-                log(LogLevel::Warn, &format!("Failed to load OSI licenses: {e}"));
-                log(LogLevel::Warn, "Continuing without OSI license information");
-                HashMap::new()
-            })
-        })
-    }
-
-    #[cfg(test)]
-    {
-        use std::cell::RefCell;
-        thread_local! {
-            static OSI_MAP: RefCell<Option<HashMap<String, OsiStatus>>> = const { RefCell::new(None) };
-        }
-
-        OSI_MAP.with(|m| {
-            let mut map = m.borrow_mut();
-            if map.is_none() {
-                match fetch_osi_licenses() {
-                    Ok(loaded_map) => {
-                        *map = Some(loaded_map);
-                    }
-                    Err(_) => {
-                        *map = Some(HashMap::new());
-                    }
-                }
-            }
-
-            // Leak the memory to get a static reference (only for tests)
-            let leaked: &'static HashMap<String, OsiStatus> =
-                Box::leak(Box::new(map.as_ref().unwrap().clone()));
-            leaked
-        })
-    }
+    OSI_LICENSES.get_or_init(crate::license_list::osi_statuses)
 }
 
-/// Check OSI approval status for a license ID (single, non-compound).
+/// Check OSI approval status for a license ID (single, non-compound). The SPDX list's
+/// `isOsiApproved` flag decides; identifiers it doesn't know are `Unknown`.
 fn get_osi_status_single(license_id: &str) -> OsiStatus {
-    let normalized_id = normalize_license_id(license_id);
     let osi_licenses = get_osi_licenses();
-
-    if let Some(status) = osi_licenses.get(&normalized_id) {
-        return *status;
-    }
-
-    if let Some(status) = osi_licenses.get(license_id) {
-        return *status;
-    }
-
-    match normalized_id.as_str() {
-        "MIT" | "Apache-2.0" | "BSD-3-Clause" | "BSD-2-Clause" | "GPL-3.0" | "GPL-2.0"
-        | "LGPL-3.0" | "LGPL-2.1" | "MPL-2.0" | "ISC" | "0BSD" => OsiStatus::Approved,
-        "No License" => OsiStatus::NotApproved,
-        _ => OsiStatus::Unknown,
-    }
+    osi_licenses
+        .get(license_id.trim())
+        .or_else(|| osi_licenses.get(&normalize_license_id(license_id)))
+        .copied()
+        .unwrap_or(OsiStatus::Unknown)
 }
 
 /// Check OSI approval status for a license string, which may be a compound SPDX expression.
@@ -600,6 +708,23 @@ fn is_single_license_restrictive(
     config: &config::FeludaConfig,
     strict: bool,
 ) -> bool {
+    if let Some(verdict) = license_policy(license_str) {
+        return verdict == config::PolicyVerdict::Deny;
+    }
+    if let Some(class) = license_ref_class(license_str) {
+        return class == config::LicenseRefClass::Restrictive;
+    }
+    if let Some((_, policy)) = special_license_policy(license_str) {
+        return match policy {
+            config::SpecialLicensePolicy::Unknown => strict,
+            config::SpecialLicensePolicy::Permissive => false,
+            config::SpecialLicensePolicy::Restrictive => true,
+        };
+    }
+    if let Some(class) = data_licenses::data_license_class(license_str) {
+        return class.is_restrictive(strict);
+    }
+
     // Registry keys are bare ids (`GPL-2.0`), so strip an SPDX `-only`/`-or-later`/`+`
     // modifier before the fallback lookup — suffixed ids must classify like their base
     // license (`GPL-2.0-or-later` is exactly as copyleft as `GPL-2.0`).
@@ -681,9 +806,12 @@ pub fn is_license_restrictive(
 
         if spdx::is_compound(license_str) {
             let expr = spdx::parse(license_str);
-            let result = spdx::expression_is_restrictive(&expr, &|id| {
-                is_single_license_restrictive(id, known_licenses, &config, strict)
-            });
+            let result = spdx::expression_is_restrictive(
+                &expr,
+                strict,
+                &|id| is_single_license_restrictive(id, known_licenses, &config, strict),
+                &license_policy,
+            );
             log(
                 LogLevel::Info,
                 &format!("Compound expression '{license_str}' is_restrictive={result}"),
@@ -770,95 +898,49 @@ pub fn is_license_ignored(license: Option<&str>) -> bool {
 const EMBEDDED_LICENSE_COMPATIBILITY_TOML: &str =
     include_str!("../config/license_compatibility.toml");
 
-/// Load license compatibility matrix from external TOML file if available
-/// Looks for the file in the following order:
-/// 1. .feluda/license_compatibility.toml (user-specific config directory)
-/// 2. Embedded configuration
+/// Project additions to the embedded matrix, relative to where Feluda runs
+pub const COMPATIBILITY_OVERRIDE_PATH: &str = ".feluda/license_compatibility.toml";
+
+/// Load the embedded license compatibility matrix, with the sections of
+/// `.feluda/license_compatibility.toml` replacing the embedded sections of the same name
 fn load_compatibility_matrix() -> FeludaResult<HashMap<String, Vec<String>>> {
     log(
         LogLevel::Info,
         "Loading license compatibility matrix from TOML file",
     );
 
-    // Only check for user-specific config in .feluda directory
-    let config_paths = vec![Path::new(".feluda/license_compatibility.toml").to_path_buf()];
-
-    let mut config_content = None;
-    let mut used_path = None;
-
-    for path in &config_paths {
-        if path.exists() {
+    let mut result =
+        parse_compatibility_matrix(EMBEDDED_LICENSE_COMPATIBILITY_TOML).map_err(|e| {
             log(
-                LogLevel::Info,
-                &format!("Found license compatibility config at: {}", path.display()),
+                LogLevel::Error,
+                &format!("Failed to parse license compatibility embedded configuration: {e}"),
             );
-            match fs::read_to_string(path) {
-                Ok(content) => {
-                    config_content = Some(content);
-                    used_path = Some(path);
-                    break;
-                }
-                Err(e) => {
-                    log(
-                        LogLevel::Warn,
-                        &format!("Failed to read {}: {}", path.display(), e),
-                    );
-                    continue;
-                }
-            }
-        }
-    }
+            std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+        })?;
 
-    // Use embedded configuration as fallback if no external file is found
-    let config_content = match config_content {
-        Some(content) => content,
-        None => {
+    let path = Path::new(COMPATIBILITY_OVERRIDE_PATH);
+    if path.exists() {
+        log(
+            LogLevel::Info,
+            &format!("Found license compatibility config at: {}", path.display()),
+        );
+        let content = fs::read_to_string(path)?;
+        let overrides = parse_compatibility_matrix(&content).map_err(|e| {
             log(
-                LogLevel::Info,
-                "No external license compatibility config found, using embedded configuration",
+                LogLevel::Error,
+                &format!(
+                    "Failed to parse license compatibility external config file ({}): {e}",
+                    path.display()
+                ),
             );
-            EMBEDDED_LICENSE_COMPATIBILITY_TOML.to_string()
-        }
-    };
-
-    let matrix: LicenseCompatibilityMatrix = toml::from_str(&config_content).map_err(|e| {
-        let source = match &used_path {
-            Some(path) => format!("external config file ({})", path.display()),
-            None => "embedded configuration".to_string(),
-        };
+            std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+        })?;
         log(
-            LogLevel::Error,
-            &format!("Failed to parse license compatibility {source}: {e}"),
+            LogLevel::Info,
+            &format!("Overriding {} compatibility sections", overrides.len()),
         );
-        std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())
-    })?;
-
-    // Convert TOML structure to HashMap
-    let entries = [
-        ("MIT", &matrix.mit),
-        ("Apache-2.0", &matrix.apache_2_0),
-        ("GPL-3.0", &matrix.gpl_3_0),
-        ("GPL-2.0", &matrix.gpl_2_0),
-        ("AGPL-3.0", &matrix.agpl_3_0),
-        ("LGPL-3.0", &matrix.lgpl_3_0),
-        ("LGPL-2.1", &matrix.lgpl_2_1),
-        ("MPL-2.0", &matrix.mpl_2_0),
-        ("BSD-3-Clause", &matrix.bsd_3_clause),
-        ("BSD-2-Clause", &matrix.bsd_2_clause),
-        ("ISC", &matrix.isc),
-        ("0BSD", &matrix.bsd_0),
-        ("Unlicense", &matrix.unlicense),
-        ("WTFPL", &matrix.wtfpl),
-    ];
-
-    let result: HashMap<String, Vec<String>> = entries
-        .iter()
-        .filter_map(|(key, option_entry)| {
-            option_entry
-                .as_ref()
-                .map(|entry| (key.to_string(), entry.compatible_with.clone()))
-        })
-        .collect();
+        result.extend(overrides);
+    }
 
     log(
         LogLevel::Info,
@@ -867,17 +949,129 @@ fn load_compatibility_matrix() -> FeludaResult<HashMap<String, Vec<String>>> {
     Ok(result)
 }
 
-/// Get the compatibility matrix, loading it if not already cached
+/// Parse a matrix file into rows keyed by [`matrix_key`]
+fn parse_compatibility_matrix(content: &str) -> Result<HashMap<String, Vec<String>>, String> {
+    let matrix: LicenseCompatibilityMatrix = toml::from_str(content).map_err(|e| e.to_string())?;
+    Ok(matrix
+        .into_iter()
+        .map(|(section, entry)| (matrix_key(&section), entry.compatible_with))
+        .collect())
+}
+
+/// How an SPDX ID pins the license version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VersionVariant {
+    /// `GPL-2.0`, deprecated in SPDX but common in metadata
+    Bare,
+    /// `GPL-2.0-only`
+    Only,
+    /// `GPL-2.0-or-later`, `GPL-2.0+`
+    OrLater,
+}
+
+/// Split a license ID into its normalized version (`GPL-2.0`) and how it pins that version
+fn license_variant(license_id: &str) -> (String, VersionVariant) {
+    let id = license_id.trim();
+    let upper = id.to_uppercase();
+    let (base, variant) = if upper.ends_with("-OR-LATER") {
+        (&id[..id.len() - "-or-later".len()], VersionVariant::OrLater)
+    } else if upper.ends_with('+') {
+        (&id[..id.len() - 1], VersionVariant::OrLater)
+    } else if upper.ends_with("-ONLY") {
+        (&id[..id.len() - "-only".len()], VersionVariant::Only)
+    } else {
+        (id, VersionVariant::Bare)
+    };
+    (normalize_license_id(base).trim().to_string(), variant)
+}
+
+/// The key a matrix section or project license is looked up by: the normalized version, plus
+/// `-or-later` when it allows later versions. Accepts the underscore spelling of older matrix
+/// files (`Apache-2_0`, `_0BSD`); SPDX IDs never contain underscores.
+fn matrix_key(license_id: &str) -> String {
+    let id = license_id.trim_start_matches('_').replace('_', ".");
+    match license_variant(&id) {
+        (base, VersionVariant::OrLater) => format!("{base}-or-later"),
+        (base, _) => base,
+    }
+}
+
+/// The matrix row for a project license: its exact row (`GPL-2.0-or-later`) if there is one,
+/// otherwise the row of its version (`GPL-2.0`)
+fn matrix_row<'a>(
+    matrix: &'a HashMap<String, Vec<String>>,
+    project_license: &str,
+) -> Option<(String, &'a Vec<String>)> {
+    let key = matrix_key(project_license);
+    if let Some(row) = matrix.get(&key) {
+        return Some((key, row));
+    }
+    let (base, _) = license_variant(project_license);
+    matrix.get(&base).map(|row| (base, row))
+}
+
+/// The `compatible_with` entry of a row that admits a dependency license, if any. A bare or
+/// `-only` entry admits every variant of its version; an `-or-later` entry only `-or-later`
+/// dependencies. An `-or-later` dependency is also admitted by later versions of its license.
+fn admitting_entry<'a>(compatible_with: &'a [String], dependency_license: &str) -> Option<&'a str> {
+    let (dep_base, dep_variant) = license_variant(dependency_license);
+    compatible_with
+        .iter()
+        .find(|entry| {
+            let (base, variant) = license_variant(entry);
+            (base == dep_base
+                && (variant != VersionVariant::OrLater || dep_variant == VersionVariant::OrLater))
+                || (dep_variant == VersionVariant::OrLater && is_later_version(&dep_base, &base))
+        })
+        .map(String::as_str)
+}
+
+/// Whether `later` is a later version of the same license as `earlier` (`GPL-3.0` of `GPL-2.0`)
+fn is_later_version(earlier: &str, later: &str) -> bool {
+    let split = |id: &str| {
+        let (family, version) = id.rsplit_once('-')?;
+        let version: Vec<u32> = version
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<_>>()?;
+        Some((family.to_string(), version))
+    };
+    match (split(earlier), split(later)) {
+        (Some((family, earlier)), Some((later_family, later))) => {
+            family == later_family && later > earlier
+        }
+        _ => false,
+    }
+}
+
+/// Load the compatibility matrix, so a scan can fail up front when it is unreadable instead of
+/// every verdict coming out unknown
+pub(crate) fn ensure_compatibility_matrix() -> FeludaResult<()> {
+    compatibility_matrix().map(|_| ())
+}
+
+/// Get the compatibility matrix, or an empty one (every verdict unknown) when it can't be
+/// loaded; [`ensure_compatibility_matrix`] reports that as an error
 fn get_compatibility_matrix() -> &'static HashMap<String, Vec<String>> {
+    static NO_MATRIX: OnceLock<HashMap<String, Vec<String>>> = OnceLock::new();
+    compatibility_matrix().unwrap_or_else(|e| {
+        log(LogLevel::Error, &e.to_string());
+        NO_MATRIX.get_or_init(HashMap::new)
+    })
+}
+
+/// The compatibility matrix, loading it if not already cached
+fn compatibility_matrix() -> FeludaResult<&'static HashMap<String, Vec<String>>> {
     #[cfg(not(test))]
     {
-        COMPATIBILITY_MATRIX.get_or_init(|| {
-            load_compatibility_matrix().unwrap_or_else(|e| {
-                log(LogLevel::Error, &format!("Failed to load license compatibility matrix: {e}"));
-                log(LogLevel::Error, "This is a critical error. The application cannot function without license compatibility data.");
-                std::process::exit(1);
+        COMPATIBILITY_MATRIX
+            .get_or_init(|| load_compatibility_matrix().map_err(|e| e.to_string()))
+            .as_ref()
+            .map_err(|e| {
+                crate::debug::FeludaError::Config(format!(
+                    "Failed to load license compatibility matrix: {e}"
+                ))
             })
-        })
     }
 
     #[cfg(test)]
@@ -908,7 +1102,7 @@ fn get_compatibility_matrix() -> &'static HashMap<String, Vec<String>> {
             // Leak the memory to get a static reference (only for tests)
             let leaked: &'static HashMap<String, Vec<String>> =
                 Box::leak(Box::new(matrix.as_ref().unwrap().clone()));
-            leaked
+            Ok(leaked)
         })
     }
 }
@@ -919,13 +1113,38 @@ fn is_single_license_compatible(
     project_license: &str,
     strict: bool,
 ) -> LicenseCompatibility {
-    let compatibility_matrix = get_compatibility_matrix();
-    let norm_dep = normalize_license_id(dependency_license);
-    let norm_proj = normalize_license_id(project_license);
+    match license_policy(dependency_license) {
+        Some(config::PolicyVerdict::Allow) => return LicenseCompatibility::Compatible,
+        Some(config::PolicyVerdict::Deny) => return LicenseCompatibility::Incompatible,
+        None => {}
+    }
+    match license_ref_class(dependency_license) {
+        Some(config::LicenseRefClass::Permissive) => return LicenseCompatibility::Compatible,
+        Some(config::LicenseRefClass::Restrictive) => return LicenseCompatibility::Incompatible,
+        None => {}
+    }
+    match special_license_policy(dependency_license) {
+        Some((_, config::SpecialLicensePolicy::Permissive)) => {
+            return LicenseCompatibility::Compatible
+        }
+        Some((_, config::SpecialLicensePolicy::Restrictive)) => {
+            return LicenseCompatibility::Incompatible
+        }
+        Some((_, config::SpecialLicensePolicy::Unknown)) if strict => {
+            return LicenseCompatibility::Incompatible
+        }
+        Some((_, config::SpecialLicensePolicy::Unknown)) => return LicenseCompatibility::Unknown,
+        None => {}
+    }
+    if data_licenses::data_license_class(dependency_license)
+        .is_some_and(|class| class.is_compatible_with_any())
+    {
+        return LicenseCompatibility::Compatible;
+    }
 
-    match compatibility_matrix.get(&norm_proj) {
-        Some(compatible_licenses) => {
-            if compatible_licenses.contains(&norm_dep) {
+    match matrix_row(get_compatibility_matrix(), project_license) {
+        Some((_, compatible_licenses)) => {
+            if admitting_entry(compatible_licenses, dependency_license).is_some() {
                 LicenseCompatibility::Compatible
             } else {
                 LicenseCompatibility::Incompatible
@@ -960,10 +1179,13 @@ pub fn is_license_compatible(
 
     if spdx::is_compound(dependency_license) {
         let expr = spdx::parse(dependency_license);
-        let result =
-            spdx::expression_compatibility(&expr, project_license, strict, &|dep, proj, s| {
-                is_single_license_compatible(dep, proj, s)
-            });
+        let result = spdx::expression_compatibility(
+            &expr,
+            project_license,
+            strict,
+            &is_single_license_compatible,
+            &license_policy,
+        );
         log(
             LogLevel::Info,
             &format!("Compound expression '{dependency_license}' compatibility={result}"),
@@ -979,8 +1201,132 @@ pub fn is_license_compatible(
     result
 }
 
+/// Whether the compatibility matrix mentions a license (or, for an SPDX expression, every
+/// license in it), as a project license row or as an allowed dependency license
+pub(crate) fn is_known_to_matrix(license: &str) -> bool {
+    let matrix = get_compatibility_matrix();
+    let known = |id: &str| {
+        matrix_row(matrix, id).is_some()
+            || matrix
+                .values()
+                .any(|allowed| admitting_entry(allowed, id).is_some())
+    };
+    if spdx::is_compound(license) {
+        spdx::parse(license)
+            .license_ids()
+            .iter()
+            .all(|id| known(id))
+    } else {
+        known(license)
+    }
+}
+
+/// A compatibility verdict together with the rules that produced it
+#[derive(Debug, Clone, Serialize)]
+pub struct CompatibilityExplanation {
+    pub dependency_license: String,
+    pub project_license: String,
+    pub verdict: LicenseCompatibility,
+    pub reasons: Vec<String>,
+}
+
+/// Check compatibility like [`is_license_compatible`] and explain which matrix rules applied.
+pub fn explain_license_compatibility(
+    dependency_license: &str,
+    project_license: &str,
+    strict: bool,
+) -> CompatibilityExplanation {
+    let mut reasons = Vec::new();
+
+    if spdx::is_compound(dependency_license) {
+        reasons.push(format!(
+            "'{dependency_license}' is an SPDX expression: OR needs any alternative to be \
+             compatible, AND needs every component to be compatible"
+        ));
+        let expr = spdx::parse(dependency_license);
+        for id in expr.license_ids() {
+            reasons.push(explain_single_compatibility(&id, project_license, strict));
+        }
+        for (license, exception) in expr.exceptions() {
+            let policy = license_policy(&format!("{license} WITH {exception}"))
+                .or_else(|| license_policy(&license));
+            reasons.push(if let Some(policy) = policy {
+                format!("{license} WITH {exception}: [{policy}] covers it, whatever the exception")
+            } else if spdx::is_linking_exception(&exception) {
+                format!(
+                    "{exception}: linking exception, so {license} WITH {exception} is compatible"
+                )
+            } else {
+                format!("{exception}: not a linking exception, {license} is judged on its own")
+            });
+        }
+    } else {
+        reasons.push(explain_single_compatibility(
+            dependency_license,
+            project_license,
+            strict,
+        ));
+    }
+
+    CompatibilityExplanation {
+        dependency_license: dependency_license.to_string(),
+        project_license: project_license.to_string(),
+        verdict: is_license_compatible(dependency_license, project_license, strict),
+        reasons,
+    }
+}
+
+/// Describe the matrix lookup [`is_single_license_compatible`] performs for one license ID
+fn explain_single_compatibility(
+    dependency_license: &str,
+    project_license: &str,
+    strict: bool,
+) -> String {
+    let verdict = is_single_license_compatible(dependency_license, project_license, strict);
+
+    if let Some(policy) = license_policy(dependency_license) {
+        return format!("{dependency_license}: {verdict} ([{policy}] covers it)");
+    }
+    if let Some(class) = license_ref_class(dependency_license) {
+        return format!(
+            "{dependency_license}: {verdict} ([licenses.refs] classifies it as {class})"
+        );
+    }
+    if let Some((value, policy)) = special_license_policy(dependency_license) {
+        return format!("{value}: {verdict} ([licenses] treats {value} as {policy})");
+    }
+    if let Some(class) = data_licenses::data_license_class(dependency_license)
+        .filter(|class| class.is_compatible_with_any())
+    {
+        return format!(
+            "{dependency_license}: {verdict} ({class} data license, compatible with any project license)"
+        );
+    }
+
+    let norm_dep = matrix_key(dependency_license);
+    match matrix_row(get_compatibility_matrix(), project_license) {
+        Some((row, compatible)) => match admitting_entry(compatible, dependency_license) {
+            Some(entry) => {
+                format!("{norm_dep}: {verdict} ([{row}] compatible_with lists {entry})")
+            }
+            None => {
+                format!("{norm_dep}: {verdict} ([{row}] compatible_with does not list {norm_dep})")
+            }
+        },
+        None => format!(
+            "{norm_dep}: {verdict} (no [{}] entry in the compatibility matrix{})",
+            matrix_key(project_license),
+            if strict {
+                "; strict mode treats unknown as incompatible"
+            } else {
+                ""
+            }
+        ),
+    }
+}
+
 /// Normalize license identifier to a standard format
-fn normalize_license_id(license_id: &str) -> String {
+pub(crate) fn normalize_license_id(license_id: &str) -> String {
     let trimmed = license_id.trim().to_uppercase();
 
     // Handle common variations and aliases
@@ -1192,15 +1538,372 @@ fn match_license_content(content: &str) -> Option<&'static str> {
     None
 }
 
+/// A `[[licenses.rules]]` entry from `.feluda.toml`, compiled for matching
+struct CustomLicenseRule {
+    license: String,
+    matcher: CustomRuleMatcher,
+}
+
+enum CustomRuleMatcher {
+    Pattern(regex::Regex),
+    /// Lowercased; every keyword must appear
+    Keywords(Vec<String>),
+}
+
+impl CustomLicenseRule {
+    fn matches(&self, text: &str) -> bool {
+        match &self.matcher {
+            CustomRuleMatcher::Pattern(pattern) => pattern.is_match(text),
+            CustomRuleMatcher::Keywords(keywords) => {
+                let text = text.to_lowercase();
+                keywords
+                    .iter()
+                    .all(|keyword| text.contains(keyword.as_str()))
+            }
+        }
+    }
+}
+
+static CUSTOM_LICENSE_RULES: RwLock<Vec<CustomLicenseRule>> = RwLock::new(Vec::new());
+
+/// Use the configured `[[licenses.rules]]` for every detection from now on
+pub fn set_custom_license_rules(rules: &[config::LicenseRule]) {
+    let compiled = compile_custom_rules(rules);
+    if let Ok(mut current) = CUSTOM_LICENSE_RULES.write() {
+        *current = compiled;
+    }
+}
+
+fn compile_custom_rules(rules: &[config::LicenseRule]) -> Vec<CustomLicenseRule> {
+    rules
+        .iter()
+        .filter_map(|rule| {
+            let matcher = match &rule.pattern {
+                Some(pattern) => match regex::Regex::new(pattern) {
+                    Ok(pattern) => CustomRuleMatcher::Pattern(pattern),
+                    Err(err) => {
+                        log_error(
+                            &format!("Skipping license rule for '{}'", rule.license),
+                            &err,
+                        );
+                        return None;
+                    }
+                },
+                None => CustomRuleMatcher::Keywords(
+                    rule.keywords.iter().map(|k| k.to_lowercase()).collect(),
+                ),
+            };
+            Some(CustomLicenseRule {
+                license: rule.license.clone(),
+                matcher,
+            })
+        })
+        .collect()
+}
+
+/// License ID of the first custom rule matching `text`
+fn match_custom_rules(text: &str) -> Option<String> {
+    let rules = CUSTOM_LICENSE_RULES.read().ok()?;
+    let rule = rules.iter().find(|rule| rule.matches(text))?;
+    log(
+        LogLevel::Info,
+        &format!("Custom license rule matched: {}", rule.license),
+    );
+    Some(rule.license.clone())
+}
+
+/// Map license metadata (a manifest or registry `license` field) through the custom rules,
+/// e.g. "Proprietary - Acme Corp" to "LicenseRef-Acme-Proprietary". Unmatched metadata is
+/// returned unchanged.
+pub fn apply_custom_license_rules(license: String) -> String {
+    match_custom_rules(&license).unwrap_or(license)
+}
+
+static LICENSE_REF_CLASSES: RwLock<BTreeMap<String, config::LicenseRefClass>> =
+    RwLock::new(BTreeMap::new());
+
+/// Use the configured `[licenses.refs]` classifications from now on
+pub fn set_license_ref_classes(refs: &BTreeMap<String, config::LicenseRefClass>) {
+    if let Ok(mut current) = LICENSE_REF_CLASSES.write() {
+        *current = refs.clone();
+    }
+}
+
+/// Whether `license` is a custom SPDX reference (`LicenseRef-*` or `DocumentRef-*:LicenseRef-*`)
+pub fn is_license_ref(license: &str) -> bool {
+    let license = license.trim();
+    license.starts_with("LicenseRef-") || license.starts_with("DocumentRef-")
+}
+
+/// The configured classification of a `LicenseRef-*` identifier. An exact key wins over
+/// prefix keys (`LicenseRef-Acme-*`), and the longest matching prefix wins among those.
+pub fn license_ref_class(license: &str) -> Option<config::LicenseRefClass> {
+    let license = license.trim();
+    if !is_license_ref(license) {
+        return None;
+    }
+    let refs = LICENSE_REF_CLASSES.read().ok()?;
+    if let Some(class) = refs.get(license) {
+        return Some(*class);
+    }
+    refs.iter()
+        .filter_map(|(key, class)| {
+            let prefix = key.strip_suffix('*')?;
+            license
+                .starts_with(prefix)
+                .then_some((prefix.len(), *class))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, class)| class)
+}
+
+static PROJECT_LICENSES: RwLock<BTreeMap<String, String>> = RwLock::new(BTreeMap::new());
+
+/// Use the configured `[project_licenses]` from now on
+pub fn set_project_licenses(project_licenses: &BTreeMap<String, String>) {
+    if let Ok(mut current) = PROJECT_LICENSES.write() {
+        *current = project_licenses
+            .iter()
+            .map(|(dir, license)| {
+                (
+                    config::normalize_project_dir(dir),
+                    license.trim().to_string(),
+                )
+            })
+            .collect();
+    }
+}
+
+/// The `[project_licenses]` entry for `dir` (relative to the scan root): its own, or that of
+/// the nearest configured parent directory
+pub fn configured_project_license(dir: &str) -> Option<String> {
+    let project_licenses = PROJECT_LICENSES.read().ok()?;
+    let mut dir = config::normalize_project_dir(dir);
+    while !dir.is_empty() {
+        if let Some(license) = project_licenses.get(&dir) {
+            return Some(license.clone());
+        }
+        dir = dir
+            .rsplit_once('/')
+            .map(|(parent, _)| parent.to_string())
+            .unwrap_or_default();
+    }
+    None
+}
+
+/// Whether any `[project_licenses]` are configured
+pub fn has_project_licenses() -> bool {
+    PROJECT_LICENSES
+        .read()
+        .map(|project_licenses| !project_licenses.is_empty())
+        .unwrap_or(false)
+}
+
+/// The SPDX special values a license field can hold instead of a license expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpdxSpecialValue {
+    /// `NOASSERTION`: whoever produced the data didn't determine the license
+    NoAssertion,
+    /// `NONE`: the package explicitly comes without a license (npm spells it `UNLICENSED`)
+    None,
+}
+
+impl std::fmt::Display for SpdxSpecialValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoAssertion => write!(f, "NOASSERTION"),
+            Self::None => write!(f, "NONE"),
+        }
+    }
+}
+
+/// Recognise `NOASSERTION` and `NONE`/`UNLICENSED` (not to be confused with `Unlicense`)
+pub fn spdx_special_value(license: &str) -> Option<SpdxSpecialValue> {
+    let license = license.trim();
+    if license.eq_ignore_ascii_case("NOASSERTION") {
+        Some(SpdxSpecialValue::NoAssertion)
+    } else if license.eq_ignore_ascii_case("NONE") || license.eq_ignore_ascii_case("UNLICENSED") {
+        Some(SpdxSpecialValue::None)
+    } else {
+        None
+    }
+}
+
+static SPECIAL_LICENSE_POLICIES: RwLock<(
+    config::SpecialLicensePolicy,
+    config::SpecialLicensePolicy,
+)> = RwLock::new((
+    config::SpecialLicensePolicy::Unknown,
+    config::SpecialLicensePolicy::Restrictive,
+));
+
+/// Use the configured `noassertion` and `none` policies from now on
+pub fn set_special_license_policies(licenses: &config::LicenseConfig) {
+    if let Ok(mut current) = SPECIAL_LICENSE_POLICIES.write() {
+        *current = (licenses.noassertion, licenses.none);
+    }
+}
+
+/// The configured policy for `license` when it's `NOASSERTION` or `NONE`
+fn special_license_policy(
+    license: &str,
+) -> Option<(SpdxSpecialValue, config::SpecialLicensePolicy)> {
+    let value = spdx_special_value(license)?;
+    let (noassertion, none) = *SPECIAL_LICENSE_POLICIES.read().ok()?;
+    Some(match value {
+        SpdxSpecialValue::NoAssertion => (value, noassertion),
+        SpdxSpecialValue::None => (value, none),
+    })
+}
+
+/// The `[licenses.allow]` and `[licenses.deny]` tables in effect
+#[derive(Debug, Default)]
+struct LicensePolicy {
+    allow: config::LicensePolicyList,
+    deny: config::LicensePolicyList,
+}
+
+impl LicensePolicy {
+    /// The verdict of the most specific pattern `matches` accepts: exact patterns before
+    /// wildcards, then the longest, with deny winning a tie
+    fn decide(
+        &self,
+        patterns: fn(&config::LicensePolicyList) -> &Vec<String>,
+        matches: impl Fn(&str) -> bool,
+    ) -> Option<config::PolicyVerdict> {
+        let best = |list: &config::LicensePolicyList| {
+            patterns(list)
+                .iter()
+                .filter(|pattern| matches(pattern.trim()))
+                .map(|pattern| (!pattern.contains('*'), pattern.replace('*', "").len()))
+                .max()
+        };
+        match (best(&self.allow), best(&self.deny)) {
+            (Some(allow), Some(deny)) if allow > deny => Some(config::PolicyVerdict::Allow),
+            (_, Some(_)) => Some(config::PolicyVerdict::Deny),
+            (Some(_), None) => Some(config::PolicyVerdict::Allow),
+            (None, None) => None,
+        }
+    }
+
+    fn license_verdict(&self, license: &str) -> Option<config::PolicyVerdict> {
+        let license = license.trim().to_uppercase();
+        let base = license
+            .trim_end_matches('+')
+            .trim_end_matches("-ONLY")
+            .trim_end_matches("-OR-LATER");
+        self.decide(
+            |list| &list.licenses,
+            |pattern| {
+                let pattern = pattern.to_uppercase();
+                config::matches_namespace(&pattern, &license)
+                    || config::matches_namespace(&pattern, base)
+            },
+        )
+    }
+
+    fn package_verdict(&self, name: &str, version: &str) -> Option<config::PolicyVerdict> {
+        self.decide(
+            |list| &list.packages,
+            |pattern| match pattern.rfind('@').filter(|&at| at > 0) {
+                Some(at) => {
+                    pattern[at + 1..] == *version && config::matches_namespace(&pattern[..at], name)
+                }
+                None => config::matches_namespace(pattern, name),
+            },
+        )
+    }
+}
+
+static LICENSE_POLICY: RwLock<LicensePolicy> = RwLock::new(LicensePolicy {
+    allow: config::LicensePolicyList {
+        licenses: Vec::new(),
+        packages: Vec::new(),
+    },
+    deny: config::LicensePolicyList {
+        licenses: Vec::new(),
+        packages: Vec::new(),
+    },
+});
+
+/// Use the configured `[licenses.allow]` and `[licenses.deny]` tables from now on
+pub fn set_license_policy(licenses: &config::LicenseConfig) {
+    if let Ok(mut current) = LICENSE_POLICY.write() {
+        *current = LicensePolicy {
+            allow: licenses.allow.clone(),
+            deny: licenses.deny.clone(),
+        };
+    }
+}
+
+/// The policy decision for a single license ID, if a `licenses` pattern covers it
+fn license_policy(license: &str) -> Option<config::PolicyVerdict> {
+    LICENSE_POLICY.read().ok()?.license_verdict(license)
+}
+
+/// Apply the per-dependency `packages` entries of `[licenses.allow]`/`[licenses.deny]`, which
+/// take precedence over whatever the dependency's license decided
+pub fn apply_package_policy(deps: &mut [LicenseInfo]) {
+    let Ok(policy) = LICENSE_POLICY.read() else {
+        return;
+    };
+    for dep in deps.iter_mut() {
+        let Some(verdict) = policy.package_verdict(&dep.name, &dep.version) else {
+            continue;
+        };
+        log(
+            LogLevel::Info,
+            &format!("{}@{} decided by [{verdict}]", dep.name, dep.version),
+        );
+        (dep.is_restrictive, dep.compatibility) = match verdict {
+            config::PolicyVerdict::Allow => (false, LicenseCompatibility::Compatible),
+            config::PolicyVerdict::Deny => (true, LicenseCompatibility::Incompatible),
+        };
+    }
+}
+
+/// A `LicenseRef-*` license that `[licenses.refs]` doesn't classify. Compound expressions
+/// count when any of their identifiers is one.
+pub fn is_unclassified_license_ref(license: &str) -> bool {
+    let ids: Vec<String> = if spdx::is_compound(license) {
+        spdx::parse(license).license_ids()
+    } else {
+        vec![license.to_string()]
+    };
+    ids.iter().any(|id| {
+        is_license_ref(id)
+            && license_ref_class(id).is_none()
+            && data_licenses::data_license(id).is_none()
+    })
+}
+
 /// Detect a license's SPDX identifier from the **text content** of a license file
 /// (`LICENSE`, `COPYING`, …) or any blob of license text.
 ///
 /// Returns a canonical SPDX id (e.g. `"MIT"`, `"Apache-2.0"`) when the content matches a
 /// known license, or `None` otherwise. This is the single shared implementation that every
 /// language analyzer's local-license-file fallback routes through, so detection stays
-/// consistent (and SPDX-correct) across ecosystems.
+/// consistent (and SPDX-correct) across ecosystems. Custom `[[licenses.rules]]` are checked
+/// first, so an internal license marker wins over e.g. an MIT preamble. Then the text is
+/// compared with the embedded SPDX license texts (see [`license_corpus`]), which recognises
+/// untitled texts such as a bare BSD license; the marker rules catch whatever is left.
 pub fn detect_license_from_content(content: &str) -> Option<String> {
-    match_license_content(content).map(str::to_string)
+    match_custom_rules(content)
+        .or_else(|| match_license_corpus(content))
+        .or_else(|| match_license_content(content).map(str::to_string))
+}
+
+fn match_license_corpus(content: &str) -> Option<String> {
+    let found = license_corpus::closest_license(content)?;
+    log(
+        LogLevel::Info,
+        &format!(
+            "License text matches {} ({:.0}% similar)",
+            found.license,
+            found.similarity * 100.0
+        ),
+    );
+    Some(found.license.to_string())
 }
 
 /// The standardised SPDX source-header marker (SPDX spec, Annex E).
@@ -1381,8 +2084,8 @@ pub fn detect_license_in_dir(dir: &Path) -> Option<String> {
 
         match fs::read_to_string(&license_path) {
             Ok(content) => {
-                if let Some(spdx) = match_license_content(&content) {
-                    return Some(spdx.to_string());
+                if let Some(spdx) = detect_license_from_content(&content) {
+                    return Some(spdx);
                 }
             }
             Err(err) => {
@@ -1437,6 +2140,19 @@ pub fn read_license_text_in_dir(dir: &Path) -> Option<String> {
     None
 }
 
+/// Read the NOTICE file in `dir`, or `None` if there isn't a non-empty one.
+///
+/// Apache-2.0 packages ship their attribution notices in it, which redistributors have to pass
+/// on alongside the license text.
+pub fn read_notice_text_in_dir(dir: &Path) -> Option<String> {
+    ["NOTICE", "NOTICE.txt", "NOTICE.md"]
+        .iter()
+        .find_map(|filename| match fs::read_to_string(dir.join(filename)) {
+            Ok(content) if !content.trim().is_empty() => Some(content),
+            _ => None,
+        })
+}
+
 /// Whether a path's filename looks like a license file (`LICENSE*`, `LICENCE*`, `COPYING*`),
 /// excluding source files so we never mistake code (e.g. `license.go`) for license text.
 fn looks_like_license_file(path: &Path) -> bool {
@@ -1634,10 +2350,9 @@ mod tests {
             name: "test_package".to_string(),
             version: "1.0.0".to_string(),
             license: Some("MIT".to_string()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         };
 
         assert_eq!(info.name(), "test_package");
@@ -1652,16 +2367,23 @@ mod tests {
         let info = LicenseInfo {
             name: "test_package".to_string(),
             version: "1.0.0".to_string(),
-            license: None,
             is_restrictive: true,
-            compatibility: LicenseCompatibility::Unknown,
-            osi_status: OsiStatus::Unknown,
-            sub_project: None,
+            ..Default::default()
         };
 
         assert_eq!(info.get_license(), "No License");
     }
 
+    #[test]
+    fn test_github_web_host() {
+        assert_eq!(github_web_host("https://api.github.com"), "github.com");
+        assert_eq!(
+            github_web_host("https://github.example.com/api/v3"),
+            "github.example.com"
+        );
+        assert_eq!(github_web_host("https://api.acme.ghe.com"), "acme.ghe.com");
+    }
+
     #[test]
     fn test_normalize_license_id() {
         assert_eq!(normalize_license_id("MIT"), "MIT");
@@ -1678,6 +2400,23 @@ mod tests {
         assert_eq!(normalize_license_id("  MIT  "), "MIT");
     }
 
+    #[test]
+    fn test_explain_license_compatibility() {
+        let explanation = explain_license_compatibility("Apache-2.0", "MIT", false);
+        assert_eq!(explanation.verdict, LicenseCompatibility::Compatible);
+        assert_eq!(explanation.reasons.len(), 1);
+        assert!(explanation.reasons[0].contains("[MIT] compatible_with lists Apache-2.0"));
+
+        let explanation = explain_license_compatibility("GPL-3.0 OR MIT", "MIT", false);
+        assert_eq!(explanation.verdict, LicenseCompatibility::Compatible);
+        assert_eq!(explanation.reasons.len(), 3);
+        assert!(explanation.reasons[1].contains("does not list GPL-3.0"));
+
+        let explanation = explain_license_compatibility("MIT", "Made-Up-1.0", true);
+        assert_eq!(explanation.verdict, LicenseCompatibility::Incompatible);
+        assert!(explanation.reasons[0].contains("strict mode"));
+    }
+
     #[test]
     #[ignore] // Skip this test due to static initialization issues in test runner
     fn test_is_license_compatible_mit_project() {
@@ -1711,6 +2450,86 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_compatibility_version_variants() {
+        let check = |dep: &str, project: &str| is_license_compatible(dep, project, false);
+        use LicenseCompatibility::{Compatible, Incompatible};
+
+        // GPL-2.0-only code can't move to GPL-3.0, "or later" code can
+        assert_eq!(check("GPL-2.0-only", "GPL-3.0-only"), Incompatible);
+        assert_eq!(check("GPL-2.0", "GPL-3.0"), Incompatible);
+        assert_eq!(check("GPL-2.0-or-later", "GPL-3.0-or-later"), Compatible);
+        assert_eq!(check("GPL-2.0+", "AGPL-3.0-only"), Compatible);
+        assert_eq!(check("GPL-2.0-or-later", "GPL-2.0-only"), Compatible);
+        // An "or later" project has its own row; an "only" one uses its version's
+        assert_eq!(check("GPL-3.0-only", "GPL-2.0-or-later"), Compatible);
+        assert_eq!(check("GPL-3.0-only", "GPL-2.0-only"), Incompatible);
+        assert_eq!(check("LGPL-2.1-or-later", "LGPL-3.0-only"), Compatible);
+    }
+
+    #[test]
+    fn test_compatibility_extended_licenses() {
+        let check = |dep: &str, project: &str| is_license_compatible(dep, project, false);
+        use LicenseCompatibility::{Compatible, Incompatible};
+
+        assert_eq!(check("EPL-1.0", "EPL-2.0"), Compatible);
+        assert_eq!(check("EPL-2.0", "EPL-1.0"), Incompatible);
+        assert_eq!(check("CDDL-1.0", "CDDL-1.1"), Compatible);
+        assert_eq!(check("GPL-2.0-only", "CDDL-1.0"), Incompatible);
+        assert_eq!(check("MIT", "EUPL-1.2"), Compatible);
+        assert_eq!(check("EUPL-1.2", "GPL-3.0-or-later"), Compatible);
+        assert_eq!(check("Artistic-2.0", "GPL-2.0-only"), Compatible);
+        assert_eq!(check("MIT", "Artistic-2.0"), Compatible);
+        assert_eq!(check("0BSD", "CC0-1.0"), Compatible);
+        assert_eq!(check("MIT", "CC0-1.0"), Incompatible);
+        assert_eq!(check("ISC", "Zlib"), Compatible);
+    }
+
+    #[test]
+    fn test_compatibility_matrix_sections() {
+        let legacy = parse_compatibility_matrix(
+            "[Apache-2_0]\ncompatible_with = [\"MIT\"]\n\n[_0BSD]\ncompatible_with = []\n",
+        )
+        .unwrap();
+        assert_eq!(legacy.get("Apache-2.0"), Some(&vec!["MIT".to_string()]));
+        assert!(legacy.contains_key("0BSD"));
+
+        let quoted =
+            parse_compatibility_matrix("[\"GPL-2.0+\"]\ncompatible_with = [\"GPL-3.0\"]\n")
+                .unwrap();
+        assert!(quoted.contains_key("GPL-2.0-or-later"));
+
+        assert!(parse_compatibility_matrix("[MIT]\ncompatible = 1\n").is_err());
+
+        let embedded = parse_compatibility_matrix(EMBEDDED_LICENSE_COMPATIBILITY_TOML).unwrap();
+        for row in [
+            "MIT",
+            "GPL-2.0-or-later",
+            "EPL-2.0",
+            "CDDL-1.0",
+            "EUPL-1.2",
+            "CC0-1.0",
+        ] {
+            assert!(embedded.contains_key(row), "missing [{row}]");
+        }
+    }
+
+    #[test]
+    fn test_spdx_exceptions_in_compatibility() {
+        assert_eq!(
+            is_license_compatible("GPL-2.0-only WITH Classpath-exception-2.0", "MIT", false),
+            LicenseCompatibility::Compatible
+        );
+        assert_eq!(
+            is_license_compatible("MIT OR GPL-3.0-only", "MIT", false),
+            LicenseCompatibility::Compatible
+        );
+        assert_eq!(
+            is_license_compatible("GPL-3.0-only WITH openvpn-openssl-exception", "MIT", false),
+            LicenseCompatibility::Incompatible
+        );
+    }
+
     #[test]
     fn test_detect_project_license_mit_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -1848,6 +2667,211 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_custom_license_rules() {
+        let rules = compile_custom_rules(&[
+            config::LicenseRule {
+                license: "LicenseRef-Acme-Proprietary".to_string(),
+                pattern: Some(r"(?i)acme corp(oration)? proprietary".to_string()),
+                keywords: Vec::new(),
+            },
+            config::LicenseRule {
+                license: "LicenseRef-Acme-Internal".to_string(),
+                pattern: None,
+                keywords: vec!["ACME".to_string(), "Internal Use Only".to_string()],
+            },
+            config::LicenseRule {
+                license: "Broken".to_string(),
+                pattern: Some("(unclosed".to_string()),
+                keywords: Vec::new(),
+            },
+        ]);
+        assert_eq!(rules.len(), 2);
+
+        let first_match = |text: &str| {
+            rules
+                .iter()
+                .find(|rule| rule.matches(text))
+                .map(|rule| rule.license.as_str())
+        };
+        assert_eq!(
+            first_match("Copyright 2024 ACME Corporation Proprietary License"),
+            Some("LicenseRef-Acme-Proprietary")
+        );
+        assert_eq!(
+            first_match("acme tooling, for internal use only"),
+            Some("LicenseRef-Acme-Internal")
+        );
+        assert_eq!(first_match("ACME public release"), None);
+    }
+
+    #[test]
+    fn test_license_ref_classes() {
+        set_license_ref_classes(&BTreeMap::from([
+            (
+                "LicenseRef-Test-*".to_string(),
+                config::LicenseRefClass::Restrictive,
+            ),
+            (
+                "LicenseRef-Test-Open".to_string(),
+                config::LicenseRefClass::Permissive,
+            ),
+        ]));
+
+        assert!(is_license_ref("LicenseRef-Anything"));
+        assert!(is_license_ref("DocumentRef-sbom:LicenseRef-Anything"));
+        assert!(!is_license_ref("MIT"));
+
+        assert_eq!(
+            license_ref_class("LicenseRef-Test-Open"),
+            Some(config::LicenseRefClass::Permissive)
+        );
+        assert_eq!(
+            license_ref_class("LicenseRef-Test-Commercial"),
+            Some(config::LicenseRefClass::Restrictive)
+        );
+        assert_eq!(license_ref_class("LicenseRef-Other"), None);
+
+        assert_eq!(
+            is_license_compatible("LicenseRef-Test-Open", "GPL-3.0", true),
+            LicenseCompatibility::Compatible
+        );
+        assert_eq!(
+            is_license_compatible("LicenseRef-Test-Commercial", "MIT", false),
+            LicenseCompatibility::Incompatible
+        );
+        let config = config::FeludaConfig::default();
+        assert!(is_single_license_restrictive(
+            "LicenseRef-Test-Commercial",
+            &HashMap::new(),
+            &config,
+            false
+        ));
+        assert!(!is_single_license_restrictive(
+            "LicenseRef-Test-Open",
+            &HashMap::new(),
+            &config,
+            true
+        ));
+
+        assert!(is_unclassified_license_ref("MIT OR LicenseRef-Other"));
+        assert!(!is_unclassified_license_ref("MIT AND LicenseRef-Test-Open"));
+        assert!(!is_unclassified_license_ref("Unknown"));
+    }
+
+    #[test]
+    fn test_license_policy_verdicts() {
+        use config::PolicyVerdict::{Allow, Deny};
+
+        let list = |licenses: &[&str], packages: &[&str]| config::LicensePolicyList {
+            licenses: licenses.iter().map(|s| s.to_string()).collect(),
+            packages: packages.iter().map(|s| s.to_string()).collect(),
+        };
+        let policy = LicensePolicy {
+            allow: list(&["MIT", "BSD-*", "GPL-2.0"], &["readline@8.2", "@acme/*"]),
+            deny: list(&["GPL-*", "AGPL-3.0"], &["left-pad"]),
+        };
+
+        assert_eq!(policy.license_verdict("mit"), Some(Allow));
+        assert_eq!(policy.license_verdict("BSD-3-Clause"), Some(Allow));
+        // An exact pattern beats a wildcard, and covers the -only/-or-later spellings
+        assert_eq!(policy.license_verdict("GPL-2.0-only"), Some(Allow));
+        assert_eq!(policy.license_verdict("GPL-3.0-or-later"), Some(Deny));
+        assert_eq!(policy.license_verdict("AGPL-3.0-only"), Some(Deny));
+        assert_eq!(policy.license_verdict("Apache-2.0"), None);
+
+        assert_eq!(policy.package_verdict("readline", "8.2"), Some(Allow));
+        assert_eq!(policy.package_verdict("readline", "8.1"), None);
+        assert_eq!(policy.package_verdict("@acme/ui", "1.0.0"), Some(Allow));
+        assert_eq!(policy.package_verdict("left-pad", "1.3.0"), Some(Deny));
+
+        // Deny wins a tie between equally specific patterns
+        let tie = LicensePolicy {
+            allow: list(&["LGPL-*"], &[]),
+            deny: list(&["LGPL-*"], &[]),
+        };
+        assert_eq!(tie.license_verdict("LGPL-3.0"), Some(Deny));
+    }
+
+    #[test]
+    fn test_spdx_special_values() {
+        assert_eq!(
+            spdx_special_value("NOASSERTION"),
+            Some(SpdxSpecialValue::NoAssertion)
+        );
+        assert_eq!(spdx_special_value(" none "), Some(SpdxSpecialValue::None));
+        assert_eq!(
+            spdx_special_value("UNLICENSED"),
+            Some(SpdxSpecialValue::None)
+        );
+        assert_eq!(spdx_special_value("Unlicense"), None);
+
+        // Defaults: NOASSERTION is unknown, NONE grants no rights
+        let config = config::FeludaConfig::default();
+        assert!(!is_single_license_restrictive(
+            "NOASSERTION",
+            &HashMap::new(),
+            &config,
+            false
+        ));
+        assert!(is_single_license_restrictive(
+            "NOASSERTION",
+            &HashMap::new(),
+            &config,
+            true
+        ));
+        assert!(is_single_license_restrictive(
+            "NONE",
+            &HashMap::new(),
+            &config,
+            false
+        ));
+        assert_eq!(
+            is_license_compatible("NOASSERTION", "MIT", false),
+            LicenseCompatibility::Unknown
+        );
+        assert_eq!(
+            is_license_compatible("NOASSERTION", "MIT", true),
+            LicenseCompatibility::Incompatible
+        );
+        assert_eq!(
+            is_license_compatible("UNLICENSED", "MIT", false),
+            LicenseCompatibility::Incompatible
+        );
+    }
+
+    #[test]
+    fn test_data_licenses() {
+        let config = config::FeludaConfig::default();
+        let restrictive =
+            |license: &str| is_single_license_restrictive(license, &HashMap::new(), &config, false);
+        assert!(!restrictive("CC0-1.0"));
+        assert!(!restrictive("CC-BY-4.0"));
+        assert!(!restrictive("ODC-By-1.0"));
+        assert!(!restrictive("ODbL-1.0"));
+        assert!(restrictive("CC-BY-NC-4.0"));
+        assert!(restrictive("CC-BY-ND-4.0"));
+        // Share-alike data licenses are restrictive where share-alike code licenses are
+        assert!(is_single_license_restrictive(
+            "ODbL-1.0",
+            &HashMap::new(),
+            &config,
+            true
+        ));
+
+        // Attribution-only data is fine whatever the project's license
+        assert_eq!(
+            is_license_compatible("CC-BY-4.0", "GPL-3.0", false),
+            LicenseCompatibility::Compatible
+        );
+        assert_eq!(
+            is_license_compatible("CDLA-Permissive-2.0", "MIT", true),
+            LicenseCompatibility::Compatible
+        );
+        let explanation = explain_license_compatibility("CC-BY-4.0", "MIT", false);
+        assert!(explanation.reasons[0].contains("attribution data license"));
+    }
+
     #[test]
     fn test_detect_license_from_content_no_match() {
         assert_eq!(detect_license_from_content("Some random content"), None);
@@ -11,8 +11,11 @@
 //! [`discover_dependency_files`] walks the whole tree (gitignore-aware) to build
 //! the set of files to monitor.
 
-use crate::languages::Language;
+use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
+use crate::languages::{Language, ANDROID_PATHS, DOTNET_PATHS, JAVA_PATHS, PYTHON_PATHS};
 use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
 
 /// What kind of dependency descriptor a file is.
@@ -31,14 +34,18 @@ pub enum DepFileKind {
 /// already recognised there (e.g. `Pipfile.lock`, `renv.lock`) are intentionally
 /// omitted to avoid double-counting.
 const LOCK_FILES: &[&str] = &[
-    "Cargo.lock",         // Rust
-    "package-lock.json",  // npm
-    "yarn.lock",          // Yarn
-    "pnpm-lock.yaml",     // pnpm
-    "go.sum",             // Go modules
-    "go.work.sum",        // Go workspaces
-    "uv.lock",            // Python (uv)
-    "packages.lock.json", // .NET
+    "Cargo.lock",          // Rust
+    "package-lock.json",   // npm
+    "npm-shrinkwrap.json", // npm (published lockfile)
+    "yarn.lock",           // Yarn
+    "pnpm-lock.yaml",      // pnpm
+    "go.sum",              // Go modules
+    "go.work.sum",         // Go workspaces
+    "uv.lock",             // Python (uv)
+    "poetry.lock",         // Python (Poetry)
+    "packages.lock.json",  // .NET
+    "gradle.lockfile",     // Gradle dependency locking
+    "libs.versions.toml",  // Gradle version catalog
 ];
 
 /// Directory names that are pruned from both discovery and change detection.
@@ -60,6 +67,25 @@ pub fn classify(file_name: &str) -> Option<DepFileKind> {
     }
 }
 
+/// The ecosystem a dependency descriptor belongs to, lockfiles included.
+pub fn language_for_file(file_name: &str) -> Option<Language> {
+    if let Some(language) = Language::from_file_name(file_name) {
+        return Some(language);
+    }
+    match file_name {
+        "Cargo.lock" => Some(Language::Rust("Cargo.toml")),
+        "package-lock.json" | "npm-shrinkwrap.json" | "yarn.lock" | "pnpm-lock.yaml" => {
+            Some(Language::Node("package.json"))
+        }
+        "go.sum" | "go.work.sum" => Some(Language::Go("go.mod")),
+        "uv.lock" | "poetry.lock" => Some(Language::Python(&PYTHON_PATHS[..])),
+        "packages.lock.json" => Some(Language::DotNet(&DOTNET_PATHS[..])),
+        "gradle.lockfile" => Some(Language::Java(&JAVA_PATHS[..])),
+        "libs.versions.toml" => Some(Language::Android(&ANDROID_PATHS[..])),
+        _ => None,
+    }
+}
+
 /// Whether a file name is any kind of dependency descriptor.
 pub fn is_dependency_file(file_name: &str) -> bool {
     classify(file_name).is_some()
@@ -122,12 +148,694 @@ pub fn discover_dependency_files(root: impl AsRef<Path>) -> Vec<PathBuf> {
     found
 }
 
+/// Dependency files under `root` that differ between `git_ref` and the working tree.
+///
+/// Covers committed, staged, unstaged and untracked changes, so a PR check sees the same
+/// set whether or not the branch has been committed yet. Paths are relative to `root`.
+pub fn changed_dependency_files(
+    root: impl AsRef<Path>,
+    git_ref: &str,
+) -> FeludaResult<Vec<PathBuf>> {
+    let root = root.as_ref().canonicalize()?;
+    let repo = git2::Repository::discover(&root).map_err(|e| {
+        FeludaError::InvalidData(format!(
+            "{} is not inside a git repository: {e}",
+            root.display()
+        ))
+    })?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| FeludaError::InvalidData("bare repositories are not supported".into()))?
+        .canonicalize()?;
+
+    let tree = repo
+        .revparse_single(git_ref)
+        .and_then(|object| object.peel_to_tree())
+        .map_err(|e| {
+            FeludaError::InvalidData(format!(
+                "cannot resolve git ref '{git_ref}' (is the history fetched?): {e}"
+            ))
+        })?;
+
+    let mut options = git2::DiffOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+    let diff = repo
+        .diff_tree_to_workdir_with_index(Some(&tree), Some(&mut options))
+        .map_err(|e| {
+            FeludaError::InvalidData(format!("git diff against '{git_ref}' failed: {e}"))
+        })?;
+
+    let mut changed = Vec::new();
+    for delta in diff.deltas() {
+        // Deleted manifests count too: their dependencies just left the project.
+        for file in [delta.old_file(), delta.new_file()] {
+            let Some(path) = file.path() else { continue };
+            let Ok(relative) = workdir
+                .join(path)
+                .strip_prefix(&root)
+                .map(Path::to_path_buf)
+            else {
+                continue;
+            };
+            if is_relevant_change(&relative) {
+                changed.push(relative);
+            }
+        }
+    }
+
+    changed.sort();
+    changed.dedup();
+    log(
+        LogLevel::Info,
+        &format!(
+            "{} dependency file(s) changed since {git_ref}",
+            changed.len()
+        ),
+    );
+    Ok(changed)
+}
+
+/// Whether a dependency file pins resolved versions: the [`DepFileKind::Lock`] files plus the
+/// lockfiles that double as project entry points (`Gemfile.lock`, `Cartfile.resolved`, ...).
+pub fn is_lockfile(file_name: &str) -> bool {
+    classify(file_name) == Some(DepFileKind::Lock)
+        || ((file_name.ends_with(".lock") || file_name == "Cartfile.resolved")
+            && is_dependency_file(file_name))
+}
+
+/// Content hash of every lockfile under `root`, as `sha256:<hex>`.
+///
+/// Each lockfile contributes its path relative to `root` and its bytes, in path order, so the
+/// hash changes when a lockfile is edited, added, moved or removed. `None` when the tree has
+/// no lockfile, i.e. nothing pins the resolved dependencies.
+pub fn lockfile_hash(root: impl AsRef<Path>) -> Option<String> {
+    let root = root.as_ref();
+    let lockfiles: Vec<PathBuf> = discover_dependency_files(root)
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(is_lockfile)
+        })
+        .collect();
+    if lockfiles.is_empty() {
+        return None;
+    }
+
+    let mut hasher = Sha256::new();
+    for path in &lockfiles {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let content = match std::fs::read(path) {
+            Ok(content) => content,
+            Err(e) => {
+                log(
+                    LogLevel::Warn,
+                    &format!("Cannot hash lockfile {}: {e}", path.display()),
+                );
+                continue;
+            }
+        };
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(&content);
+    }
+
+    let hash = format!("sha256:{:x}", hasher.finalize());
+    log(
+        LogLevel::Info,
+        &format!("{} lockfile(s) hash to {hash}", lockfiles.len()),
+    );
+    Some(hash)
+}
+
+/// Where a direct dependency is declared, relative to the scanned directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestLocation {
+    pub file: String,
+    pub line: usize,
+}
+
+/// The manifests declaration lines are looked up in, in order, for the ecosystems that
+/// have line-oriented manifests listing direct dependencies.
+pub fn declaring_manifests(project_label: &str) -> &'static [&'static str] {
+    match project_label {
+        "rust" => &["Cargo.toml"],
+        "node" => &["package.json"],
+        "go" => &["go.mod"],
+        "python" => &["requirements.txt", "pyproject.toml"],
+        "ruby" => &["Gemfile"],
+        "php" => &["composer.json"],
+        "elixir" => &["mix.exs"],
+        "carthage" => &["Cartfile", "Cartfile.private"],
+        "android" => &["gradle/libs.versions.toml"],
+        _ => &[],
+    }
+}
+
+/// 1-based line where `dependency` is declared in `content`, the text of the manifest
+/// named `file_name`. Transitive dependencies are not declared anywhere and get `None`.
+pub fn declaration_line(file_name: &str, content: &str, dependency: &str) -> Option<usize> {
+    let index = match file_name {
+        "package.json" => package_json_declaration(content, dependency),
+        "Cargo.toml" => cargo_toml_declaration(content, dependency),
+        "go.mod" => go_mod_declaration(content, dependency),
+        "requirements.txt" => requirements_declaration(content, dependency),
+        "pyproject.toml" => pyproject_declaration(content, dependency),
+        "Gemfile" => gemfile_declaration(content, dependency),
+        "composer.json" => composer_json_declaration(content, dependency),
+        "mix.exs" => mix_exs_declaration(content, dependency),
+        "Cartfile" | "Cartfile.private" => content.lines().position(|line| {
+            crate::languages::carthage::parse_cartfile_line(line)
+                .is_some_and(|dep| dep.name == dependency)
+        }),
+        "gradle/libs.versions.toml" => version_catalog_declaration(content, dependency),
+        _ => None,
+    }?;
+    Some(index + 1)
+}
+
+/// Found from the byte offset of the key rather than line by line, so a package.json written
+/// on a single line (minified, or by a tool) still places its dependencies.
+fn package_json_declaration(content: &str, dependency: &str) -> Option<usize> {
+    let offset = package_json_key_offset(content, dependency)?;
+    Some(content[..offset].matches('\n').count())
+}
+
+/// Byte offset of `dependency`'s key in one of package.json's top-level dependency objects:
+/// dependencies, devDependencies, peerDependencies, optionalDependencies
+fn package_json_key_offset(content: &str, dependency: &str) -> Option<usize> {
+    let bytes = content.as_bytes();
+    let mut depth = 0;
+    // Depth inside the dependency object being walked, and whether the next `{` opens one
+    let mut section: Option<usize> = None;
+    let mut opens_section = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                let start = i;
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                let text = content.get(start + 1..i)?;
+                let is_key = content
+                    .get(i + 1..)
+                    .is_some_and(|rest| rest.trim_start().starts_with(':'));
+                if is_key && depth == 1 && text.ends_with("ependencies") {
+                    opens_section = true;
+                } else if is_key && section == Some(depth) && text == dependency {
+                    return Some(start);
+                }
+            }
+            b'{' | b'[' => {
+                depth += 1;
+                if opens_section && bytes[i] == b'{' && depth == 2 {
+                    section = Some(depth);
+                }
+                opens_section = false;
+            }
+            b'}' | b']' => {
+                if section == Some(depth) {
+                    section = None;
+                }
+                depth = depth.saturating_sub(1);
+            }
+            b',' => opens_section = false,
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+fn composer_json_declaration(content: &str, dependency: &str) -> Option<usize> {
+    let key = format!("\"{dependency}\"");
+    let mut in_require = false;
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        // require and require-dev; any other top-level key ends the section
+        if trimmed.starts_with("\"require\"") || trimmed.starts_with("\"require-dev\"") {
+            in_require = true;
+            continue;
+        }
+        if trimmed.starts_with('}') {
+            in_require = false;
+            continue;
+        }
+        if in_require
+            && trimmed
+                .strip_prefix(&key)
+                .is_some_and(|rest| rest.trim_start().starts_with(':'))
+        {
+            return Some(index);
+        }
+    }
+    None
+}
+
+fn cargo_toml_declaration(content: &str, dependency: &str) -> Option<usize> {
+    let mut in_dependencies = false;
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if let Some(section) = trimmed
+            .strip_prefix('[')
+            .and_then(|rest| rest.split(']').next())
+        {
+            // [dependencies.serde] style tables declare the dependency in the header
+            if section.ends_with(&format!("dependencies.{dependency}")) {
+                return Some(index);
+            }
+            // [dependencies], [dev-dependencies], [target.'cfg(unix)'.dependencies], ...
+            in_dependencies = section.ends_with("dependencies");
+            continue;
+        }
+        if in_dependencies {
+            let key = trimmed
+                .split('=')
+                .next()
+                .unwrap_or("")
+                .trim()
+                .trim_matches('"');
+            if key == dependency && trimmed.contains('=') {
+                return Some(index);
+            }
+        }
+    }
+    None
+}
+
+fn go_mod_declaration(content: &str, dependency: &str) -> Option<usize> {
+    let mut in_require_block = false;
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        let module = if in_require_block {
+            if trimmed.starts_with(')') {
+                in_require_block = false;
+                continue;
+            }
+            trimmed
+        } else if trimmed.starts_with("require") && trimmed.ends_with('(') {
+            in_require_block = true;
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix("require ") {
+            rest.trim_start()
+        } else {
+            continue;
+        };
+
+        if module.split_whitespace().next() == Some(dependency) {
+            return Some(index);
+        }
+    }
+    None
+}
+
+/// PEP 503 normalized name: case, `_` and `.` don't distinguish Python packages
+fn normalize_python_name(name: &str) -> String {
+    name.trim().to_ascii_lowercase().replace(['_', '.'], "-")
+}
+
+/// The package name a PEP 508 requirement (`requests[socks]>=2.31 ; python_version>"3.8"`)
+/// starts with
+fn requirement_name(requirement: &str) -> &str {
+    let requirement = requirement.trim_start();
+    let end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(requirement.len());
+    &requirement[..end]
+}
+
+fn requirements_declaration(content: &str, dependency: &str) -> Option<usize> {
+    let wanted = normalize_python_name(dependency);
+    content.lines().position(|line| {
+        let line = line.trim();
+        // Options (`-r other.txt`, `--index-url ...`) and comments declare nothing
+        !line.starts_with(['-', '#'])
+            && !requirement_name(line).is_empty()
+            && normalize_python_name(requirement_name(line)) == wanted
+    })
+}
+
+/// Quoted strings on a line, and whether the line closes an array outside of them
+fn quoted_strings(line: &str) -> (Vec<&str>, bool) {
+    let mut strings = Vec::new();
+    let mut closes_array = false;
+    let mut quote: Option<(char, usize)> = None;
+    for (index, c) in line.char_indices() {
+        match quote {
+            Some((open, start)) if c == open => {
+                strings.push(&line[start..index]);
+                quote = None;
+            }
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some((c, index + 1)),
+            None if c == '#' => break,
+            None if c == ']' => closes_array = true,
+            None => {}
+        }
+    }
+    (strings, closes_array)
+}
+
+fn pyproject_declaration(content: &str, dependency: &str) -> Option<usize> {
+    let wanted = normalize_python_name(dependency);
+    let mut section = String::new();
+    let mut in_array = false;
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if !in_array {
+            if let Some(header) = trimmed
+                .strip_prefix('[')
+                .and_then(|rest| rest.split(']').next())
+            {
+                section = header.trim_matches('[').trim().to_string();
+                continue;
+            }
+        }
+        // [project.optional-dependencies], [dependency-groups] and Poetry's
+        // [tool.poetry.dependencies] / [tool.poetry.group.<name>.dependencies]
+        let dependency_table = section.ends_with("dependencies") || section == "dependency-groups";
+
+        let rest = if in_array {
+            trimmed
+        } else {
+            let Some((key, value)) = trimmed.split_once('=') else {
+                continue;
+            };
+            let key = key.trim().trim_matches('"');
+            let value = value.trim_start();
+            if value.starts_with('[') && (dependency_table || key.ends_with("dependencies")) {
+                in_array = true;
+                value
+            } else if dependency_table
+                && section.starts_with("tool.poetry")
+                && normalize_python_name(key) == wanted
+            {
+                return Some(index);
+            } else {
+                continue;
+            }
+        };
+
+        let (strings, closes_array) = quoted_strings(rest);
+        if strings
+            .iter()
+            .any(|requirement| normalize_python_name(requirement_name(requirement)) == wanted)
+        {
+            return Some(index);
+        }
+        if closes_array {
+            in_array = false;
+        }
+    }
+    None
+}
+
+fn gemfile_declaration(content: &str, dependency: &str) -> Option<usize> {
+    content.lines().position(|line| {
+        line.trim()
+            .strip_prefix("gem ")
+            .map(|rest| quoted_strings(rest).0)
+            .is_some_and(|strings| strings.first() == Some(&dependency))
+    })
+}
+
+fn mix_exs_declaration(content: &str, dependency: &str) -> Option<usize> {
+    let atom = format!(":{dependency}");
+    content.lines().position(|line| {
+        line.trim()
+            .strip_prefix('{')
+            .and_then(|rest| rest.trim_start().strip_prefix(&atom))
+            .is_some_and(|rest| rest.trim_start().starts_with(','))
+    })
+}
+
+/// The `[libraries]` entry of a Gradle version catalog for a `group:artifact` dependency,
+/// in its `"g:a:v"`, `module = "g:a"` or `group = "g", name = "a"` form
+fn version_catalog_declaration(content: &str, dependency: &str) -> Option<usize> {
+    let (group, artifact) = dependency.split_once(':')?;
+    let coordinate = format!("\"{dependency}");
+    let group_key = format!("\"{group}\"");
+    let name_key = format!("\"{artifact}\"");
+    let mut in_libraries = false;
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_libraries = trimmed == "[libraries]";
+            continue;
+        }
+        if in_libraries
+            && (trimmed.contains(&format!("{coordinate}\""))
+                || trimmed.contains(&format!("{coordinate}:"))
+                || (trimmed.contains(&group_key) && trimmed.contains(&name_key)))
+        {
+            return Some(index);
+        }
+    }
+    None
+}
+
+/// The package names the manifests in `dir` declare: the `name` of a `package.json`, the
+/// `[project]` or `[package]` name of a `pyproject.toml` or `Cargo.toml`, the `module` of a
+/// `go.mod`. These are the names workspace members are attributed under.
+pub fn package_names(dir: &Path) -> Vec<String> {
+    let mut names = Vec::new();
+    if let Ok(content) = std::fs::read_to_string(dir.join("package.json")) {
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+            names.extend(json["name"].as_str().map(str::to_string));
+        }
+    }
+    for (manifest, table) in [("pyproject.toml", "project"), ("Cargo.toml", "package")] {
+        let Ok(content) = std::fs::read_to_string(dir.join(manifest)) else {
+            continue;
+        };
+        if let Ok(value) = toml::from_str::<toml::Value>(&content) {
+            names.extend(
+                value
+                    .get(table)
+                    .and_then(|table| table.get("name"))
+                    .and_then(|name| name.as_str())
+                    .map(str::to_string),
+            );
+        }
+    }
+    names.extend(crate::languages::go::read_go_module_name(
+        &dir.join("go.mod"),
+    ));
+    names
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_declaration_line_package_json() {
+        let content = r#"{
+  "name": "app",
+  "version": "1.0.0",
+  "dependencies": {
+    "express": "^4.18.0"
+  },
+  "devDependencies": {
+    "jest": "^29.0.0"
+  }
+}"#;
+        assert_eq!(
+            declaration_line("package.json", content, "express"),
+            Some(5)
+        );
+        assert_eq!(declaration_line("package.json", content, "jest"), Some(8));
+        assert_eq!(declaration_line("package.json", content, "name"), None);
+        assert_eq!(declaration_line("package.json", content, "lodash"), None);
+    }
+
+    #[test]
+    fn test_declaration_line_minified_package_json() {
+        let content = r#"{"name":"app","scripts":{"express":"node ."},"dependencies":{"express":"^4.18.0","left-pad":"1.3.0"},"devDependencies":{"jest":"^29.0.0"},"bundledDependencies":["lodash"]}"#;
+        assert_eq!(
+            declaration_line("package.json", content, "express"),
+            Some(1)
+        );
+        assert_eq!(declaration_line("package.json", content, "jest"), Some(1));
+        assert_eq!(declaration_line("package.json", content, "lodash"), None);
+        assert_eq!(declaration_line("package.json", content, "name"), None);
+    }
+
+    #[test]
+    fn test_declaration_line_cargo_toml() {
+        let content = r#"[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+rand = "0.8"
+
+[dev-dependencies.tempfile]
+version = "3"
+"#;
+        assert_eq!(declaration_line("Cargo.toml", content, "serde"), Some(6));
+        assert_eq!(declaration_line("Cargo.toml", content, "rand"), Some(7));
+        assert_eq!(declaration_line("Cargo.toml", content, "tempfile"), Some(9));
+        assert_eq!(declaration_line("Cargo.toml", content, "name"), None);
+    }
+
+    #[test]
+    fn test_declaration_line_go_mod() {
+        let content = "module example.com/app\n\ngo 1.21\n\nrequire github.com/pkg/errors v0.9.1\n\nrequire (\n\tgolang.org/x/text v0.14.0\n\tgithub.com/stretchr/testify v1.8.4 // indirect\n)\n";
+        assert_eq!(
+            declaration_line("go.mod", content, "github.com/pkg/errors"),
+            Some(5)
+        );
+        assert_eq!(
+            declaration_line("go.mod", content, "golang.org/x/text"),
+            Some(8)
+        );
+        assert_eq!(
+            declaration_line("go.mod", content, "github.com/stretchr/testify"),
+            Some(9)
+        );
+        assert_eq!(declaration_line("go.mod", content, "example.com/app"), None);
+    }
+
+    #[test]
+    fn test_declaration_line_cartfile() {
+        let content = "# Networking\ngithub \"Alamofire/Alamofire\" ~> 5.9\ngit \"https://github.com/realm/realm-swift.git\" \"v10.49.1\"\n";
+        assert_eq!(declaration_line("Cartfile", content, "Alamofire"), Some(2));
+        assert_eq!(
+            declaration_line("Cartfile", content, "realm-swift"),
+            Some(3)
+        );
+        assert_eq!(declaration_line("Cartfile", content, "Nimble"), None);
+    }
+
+    #[test]
+    fn test_declaration_line_requirements_txt() {
+        let content = "-r base.txt\n# HTTP\nrequests[socks]>=2.31 ; python_version > \"3.8\"\nDjango==5.0\nzope.interface\n";
+        assert_eq!(
+            declaration_line("requirements.txt", content, "requests"),
+            Some(3)
+        );
+        assert_eq!(
+            declaration_line("requirements.txt", content, "django"),
+            Some(4)
+        );
+        assert_eq!(
+            declaration_line("requirements.txt", content, "zope-interface"),
+            Some(5)
+        );
+        assert_eq!(declaration_line("requirements.txt", content, "base"), None);
+    }
+
+    #[test]
+    fn test_declaration_line_pyproject_toml() {
+        let content = r#"[project]
+name = "app"
+keywords = ["httpx"]
+dependencies = [
+    "requests>=2.31",
+    "uvicorn[standard]",  # ASGI server
+    "pydantic",
+]
+
+[project.optional-dependencies]
+docs = ["sphinx>=7"]
+
+[tool.poetry.group.dev.dependencies]
+pytest = "^8.0"
+"#;
+        let line = |name| declaration_line("pyproject.toml", content, name);
+        assert_eq!(line("requests"), Some(5));
+        assert_eq!(line("uvicorn"), Some(6));
+        assert_eq!(line("pydantic"), Some(7));
+        assert_eq!(line("sphinx"), Some(11));
+        assert_eq!(line("pytest"), Some(14));
+        assert_eq!(line("httpx"), None);
+        assert_eq!(line("app"), None);
+    }
+
+    #[test]
+    fn test_declaration_line_gemfile() {
+        let content = "source 'https://rubygems.org'\n\ngem 'rails', '~> 7.1'\ngem \"pg\"\n";
+        assert_eq!(declaration_line("Gemfile", content, "rails"), Some(3));
+        assert_eq!(declaration_line("Gemfile", content, "pg"), Some(4));
+        assert_eq!(declaration_line("Gemfile", content, "rubygems"), None);
+    }
+
+    #[test]
+    fn test_declaration_line_version_catalog() {
+        let content = r#"[versions]
+coreKtx = "1.13.1"
+
+[libraries]
+androidx-core-ktx = { group = "androidx.core", name = "core-ktx", version.ref = "coreKtx" }
+okhttp = { module = "com.squareup.okhttp3:okhttp", version = "4.12.0" }
+junit = "junit:junit:4.13.2"
+
+[plugins]
+kotlin = { id = "org.jetbrains.kotlin.android", version = "2.0.0" }
+"#;
+        let file = "gradle/libs.versions.toml";
+        assert_eq!(
+            declaration_line(file, content, "androidx.core:core-ktx"),
+            Some(5)
+        );
+        assert_eq!(
+            declaration_line(file, content, "com.squareup.okhttp3:okhttp"),
+            Some(6)
+        );
+        assert_eq!(declaration_line(file, content, "junit:junit"), Some(7));
+        assert_eq!(declaration_line(file, content, "androidx.core:core"), None);
+    }
+
+    #[test]
+    fn test_declaration_line_mix_exs() {
+        let content = "  defp deps do\n    [\n      {:phoenix, \"~> 1.7.10\"},\n      {:phoenix_html, \"~> 3.3\"}\n    ]\n  end\n";
+        assert_eq!(declaration_line("mix.exs", content, "phoenix"), Some(3));
+        assert_eq!(
+            declaration_line("mix.exs", content, "phoenix_html"),
+            Some(4)
+        );
+        assert_eq!(declaration_line("mix.exs", content, "ecto"), None);
+    }
+
+    #[test]
+    fn test_declaration_line_composer_json() {
+        let content = r#"{
+    "name": "acme/app",
+    "require": {
+        "php": ">=8.1",
+        "laravel/framework": "^10.10"
+    },
+    "require-dev": {
+        "phpunit/phpunit": "^10.1"
+    },
+    "extra": {
+        "acme/tooling": "not a requirement"
+    }
+}"#;
+        assert_eq!(
+            declaration_line("composer.json", content, "laravel/framework"),
+            Some(5)
+        );
+        assert_eq!(
+            declaration_line("composer.json", content, "phpunit/phpunit"),
+            Some(8)
+        );
+        assert_eq!(
+            declaration_line("composer.json", content, "acme/tooling"),
+            None
+        );
+    }
+
     #[test]
     fn classifies_manifests_via_language_authority() {
         assert_eq!(classify("Cargo.toml"), Some(DepFileKind::Manifest));
@@ -143,6 +851,55 @@ mod tests {
         assert_eq!(classify("package-lock.json"), Some(DepFileKind::Lock));
         assert_eq!(classify("go.sum"), Some(DepFileKind::Lock));
         assert_eq!(classify("uv.lock"), Some(DepFileKind::Lock));
+        assert_eq!(classify("gradle.lockfile"), Some(DepFileKind::Lock));
+    }
+
+    #[test]
+    fn maps_lockfiles_to_their_ecosystem() {
+        assert!(matches!(
+            language_for_file("Cargo.lock"),
+            Some(Language::Rust(_))
+        ));
+        assert!(matches!(
+            language_for_file("pnpm-lock.yaml"),
+            Some(Language::Node(_))
+        ));
+        assert!(matches!(
+            language_for_file("requirements.txt"),
+            Some(Language::Python(_))
+        ));
+        assert!(language_for_file("README.md").is_none());
+    }
+
+    #[test]
+    fn changed_dependency_files_since_ref() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let repo = git2::Repository::init(root).unwrap();
+
+        fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(root.join("package.json"), "{}").unwrap();
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+
+        fs::write(root.join("package.json"), r#"{"dependencies": {}}"#).unwrap();
+        fs::write(root.join("go.sum"), "").unwrap();
+        fs::write(root.join("README.md"), "# hi").unwrap();
+
+        let changed = changed_dependency_files(root, "HEAD").unwrap();
+        assert_eq!(
+            changed,
+            vec![PathBuf::from("go.sum"), PathBuf::from("package.json")]
+        );
+
+        assert!(changed_dependency_files(root, "no-such-ref").is_err());
     }
 
     #[test]
@@ -195,4 +952,53 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_lockfile_hash() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+        assert_eq!(lockfile_hash(root), None);
+
+        fs::write(root.join("Cargo.lock"), "version = 3\n").unwrap();
+        fs::create_dir(root.join("web")).unwrap();
+        fs::write(root.join("web/yarn.lock"), "# yarn lockfile v1\n").unwrap();
+        let hash = lockfile_hash(root).unwrap();
+        assert!(hash.starts_with("sha256:"));
+
+        // Manifests do not count, lockfiles do
+        fs::write(root.join("Cargo.toml"), "[package]\nname = \"renamed\"\n").unwrap();
+        assert_eq!(lockfile_hash(root).as_ref(), Some(&hash));
+        fs::write(
+            root.join("web/yarn.lock"),
+            "# yarn lockfile v1\n\nleft-pad@1.0.0:\n",
+        )
+        .unwrap();
+        assert_ne!(lockfile_hash(root).as_ref(), Some(&hash));
+
+        fs::write(root.join("Gemfile.lock"), "GEM\n").unwrap();
+        assert!(is_lockfile("Gemfile.lock"));
+        assert!(!is_lockfile("Gemfile"));
+        assert!(is_lockfile("Cartfile.resolved"));
+        assert!(!is_lockfile("Cartfile"));
+    }
+
+    #[test]
+    fn test_package_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        assert!(package_names(dir).is_empty());
+
+        fs::write(dir.join("package.json"), r#"{ "name": "@acme/api" }"#).unwrap();
+        fs::write(
+            dir.join("pyproject.toml"),
+            "[project]\nname = \"api-tools\"\n",
+        )
+        .unwrap();
+        fs::write(dir.join("go.mod"), "module example.com/api\n\ngo 1.22\n").unwrap();
+        assert_eq!(
+            package_names(dir),
+            vec!["@acme/api", "api-tools", "example.com/api"]
+        );
+    }
 }
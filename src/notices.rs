@@ -0,0 +1,364 @@
+//! `feluda generate notice`: the THIRD-PARTY-NOTICES file a release ships
+//!
+//! Dependencies are grouped by license. Each group lists its packages with the copyright lines
+//! from their license files, followed by the license text, printed once for every distinct
+//! wording (MIT texts that differ only in their copyright line share one). Apache-2.0 packages
+//! also get their NOTICE file reproduced, as section 4(d) of the license requires. Texts come from
+//! the local toolchain caches first and the registries after, the same way `feluda generate`
+//! finds them for THIRD_PARTY_LICENSES. A package whose text can't be found falls back to the
+//! SPDX text Feluda embeds, and is listed at the end when there's none.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use colored::*;
+
+use crate::cli::with_spinner;
+use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
+use crate::generate::{fetch_actual_license_content, fetch_notice_content};
+use crate::license_corpus;
+use crate::licenses::LicenseInfo;
+use crate::parser::parse_root;
+
+/// Written to the project directory unless `--output` says otherwise
+pub const NOTICE_FILE: &str = "THIRD-PARTY-NOTICES";
+
+const RULE: &str =
+    "================================================================================";
+const THIN_RULE: &str =
+    "--------------------------------------------------------------------------------";
+
+/// A dependency and the texts found for it
+#[derive(Debug, Clone)]
+struct NoticeEntry {
+    name: String,
+    version: String,
+    license: String,
+    text: Option<String>,
+    notice: Option<String>,
+}
+
+/// Packages sharing one license wording
+#[derive(Debug)]
+struct TextGroup<'a> {
+    /// `None` when no package's text was found and Feluda embeds no text for the license
+    body: Option<String>,
+    entries: Vec<&'a NoticeEntry>,
+}
+
+pub fn handle_notice_command(
+    path: &str,
+    language: Option<&str>,
+    output: Option<&str>,
+) -> FeludaResult<()> {
+    let dependencies = parse_root(path, language, &[], false, false, None, false)?;
+    // The organisation's own packages have nothing to attribute
+    let shipped: Vec<&LicenseInfo> = dependencies.iter().filter(|dep| !dep.internal).collect();
+    if shipped.is_empty() {
+        eprintln!("❌ No dependencies found. Cannot generate notices without dependency data.");
+        return Err(FeludaError::InvalidData(
+            "No dependencies to generate notices for".to_string(),
+        ));
+    }
+
+    let project_root = Path::new(path);
+    let entries = with_spinner(
+        &format!(
+            "Collecting license texts for {} dependencies",
+            shipped.len()
+        ),
+        |indicator| {
+            shipped
+                .iter()
+                .enumerate()
+                .map(|(index, dep)| {
+                    indicator.update_progress(&format!("{}/{}", index + 1, shipped.len()));
+                    NoticeEntry {
+                        name: dep.name.clone(),
+                        version: dep.version.clone(),
+                        license: dep.get_license(),
+                        text: fetch_actual_license_content(&dep.name, &dep.version, project_root),
+                        notice: fetch_notice_content(&dep.name, &dep.version, project_root),
+                    }
+                })
+                .collect::<Vec<_>>()
+        },
+    );
+
+    let content = notice_content(&entries);
+    let output = match output {
+        Some(output) => Path::new(output).to_path_buf(),
+        None => project_root.join(NOTICE_FILE),
+    };
+    fs::write(&output, content).map_err(|e| {
+        FeludaError::FileWrite(format!("Failed to write {}: {e}", output.display()))
+    })?;
+
+    let missing = missing_texts(&entries);
+    log(
+        LogLevel::Info,
+        &format!(
+            "Wrote notices for {} dependencies to {} ({} without a license text)",
+            entries.len(),
+            output.display(),
+            missing.len()
+        ),
+    );
+    println!(
+        "{} Third-party notices written to {} ({} dependencies)",
+        "✅".green().bold(),
+        output.display().to_string().blue(),
+        entries.len()
+    );
+    if !missing.is_empty() {
+        println!(
+            "{} No license text found for {} dependencies, listed at the end of the file: {}",
+            "⚠️".yellow().bold(),
+            missing.len(),
+            missing.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Whether a copyright line is a template placeholder rather than a real notice
+fn is_placeholder(line: &str) -> bool {
+    let line = line.to_lowercase();
+    line.contains('<') || line.contains("[yyyy]") || line.contains("{yyyy}")
+}
+
+/// The copyright lines of a license text, in order and without repeats
+fn copyright_lines(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        if !license_corpus::is_copyright_line(line) || is_placeholder(line) {
+            continue;
+        }
+        let line = line.trim();
+        let line = line
+            .strip_suffix("All rights reserved.")
+            .unwrap_or(line)
+            .trim_end()
+            .to_string();
+        if !lines.contains(&line) {
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+/// A license text without its copyright lines, so copies differing only in those compare equal
+fn license_body(text: &str) -> String {
+    let mut body = String::new();
+    let mut blank = false;
+    for line in text.lines() {
+        let line = line.trim_end();
+        if license_corpus::is_copyright_line(line)
+            || line.trim().eq_ignore_ascii_case("all rights reserved.")
+        {
+            continue;
+        }
+        if line.trim().is_empty() {
+            blank = !body.is_empty();
+            continue;
+        }
+        if blank {
+            body.push('\n');
+            blank = false;
+        }
+        body.push_str(line);
+        body.push('\n');
+    }
+    body
+}
+
+/// Group the packages of one license by the wording of their license text
+fn text_groups<'a>(license: &str, entries: &[&'a NoticeEntry]) -> Vec<TextGroup<'a>> {
+    let mut groups: Vec<TextGroup<'a>> = Vec::new();
+    for entry in entries {
+        let body = entry
+            .text
+            .as_deref()
+            .or_else(|| license_corpus::reference_text(license))
+            .map(license_body);
+        match groups.iter_mut().find(|group| group.body == body) {
+            Some(group) => group.entries.push(entry),
+            None => groups.push(TextGroup {
+                body,
+                entries: vec![entry],
+            }),
+        }
+    }
+    // A missing text is listed last
+    groups.sort_by_key(|group| group.body.is_none());
+    groups
+}
+
+/// The `name@version` of every package with neither its own nor an embedded license text
+fn missing_texts(entries: &[NoticeEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .filter(|entry| {
+            entry.text.is_none() && license_corpus::reference_text(&entry.license).is_none()
+        })
+        .map(|entry| format!("{}@{}", entry.name, entry.version))
+        .collect()
+}
+
+fn notice_content(entries: &[NoticeEntry]) -> String {
+    let mut by_license: BTreeMap<&str, Vec<&NoticeEntry>> = BTreeMap::new();
+    for entry in entries {
+        by_license.entry(&entry.license).or_default().push(entry);
+    }
+    for group in by_license.values_mut() {
+        group.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+    }
+
+    let mut content = String::new();
+    content.push_str("THIRD-PARTY SOFTWARE NOTICES AND INFORMATION\n\n");
+    content.push_str(
+        "This software includes the third-party components listed below. Each is provided under\n\
+         its own license, whose copyright notices and license text are reproduced here.\n\n",
+    );
+    let width = by_license.keys().map(|license| license.len()).max();
+    for (license, group) in &by_license {
+        content.push_str(&format!(
+            "  {license:<width$}  {} {}\n",
+            group.len(),
+            if group.len() == 1 {
+                "component"
+            } else {
+                "components"
+            },
+            width = width.unwrap_or_default()
+        ));
+    }
+
+    for (license, group) in &by_license {
+        content.push_str(&format!("\n{RULE}\n{license}\n{RULE}\n"));
+        for text_group in text_groups(license, group) {
+            content.push('\n');
+            for entry in &text_group.entries {
+                content.push_str(&format!("{} {}\n", entry.name, entry.version));
+                for line in entry
+                    .text
+                    .as_deref()
+                    .map(copyright_lines)
+                    .unwrap_or_default()
+                {
+                    content.push_str(&format!("    {line}\n"));
+                }
+            }
+            content.push('\n');
+            match &text_group.body {
+                Some(body) => content.push_str(body),
+                None => content.push_str(&format!(
+                    "No license text was found for these components. Obtain the {license} text\n\
+                     from their source distributions before release.\n"
+                )),
+            }
+        }
+
+        for entry in group {
+            let Some(notice) = &entry.notice else {
+                continue;
+            };
+            content.push_str(&format!(
+                "\n{THIN_RULE}\nNOTICE file of {} {}\n{THIN_RULE}\n\n{}\n",
+                entry.name,
+                entry.version,
+                notice.trim_end()
+            ));
+        }
+    }
+
+    let missing = missing_texts(entries);
+    if !missing.is_empty() {
+        content.push_str(&format!(
+            "\n{RULE}\nComponents without a license text\n{RULE}\n\n"
+        ));
+        for package in missing {
+            content.push_str(&format!("{package}\n"));
+        }
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, license: &str, text: Option<&str>, notice: Option<&str>) -> NoticeEntry {
+        NoticeEntry {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            license: license.to_string(),
+            text: text.map(str::to_string),
+            notice: notice.map(str::to_string),
+        }
+    }
+
+    fn mit(holder: &str) -> String {
+        license_corpus::reference_text("MIT")
+            .unwrap()
+            .replace("<year> <copyright holders>", holder)
+    }
+
+    #[test]
+    fn test_copyright_lines() {
+        let text = "Copyright (c) 2014 Erick Tryzelaar. All rights reserved.\n\
+                    Copyright (c) 2014 David Tolnay\n\
+                    copyright notice and this permission notice appear in all copies.\n\
+                    Copyright (c) 2014 David Tolnay\n\
+                    Copyright [yyyy] [name of copyright owner]\n\
+                    1. Redistributions of source code must retain the above copyright notice,\n";
+        assert_eq!(
+            copyright_lines(text),
+            vec![
+                "Copyright (c) 2014 Erick Tryzelaar.",
+                "Copyright (c) 2014 David Tolnay"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_texts_differing_in_copyright_share_a_group() {
+        let serde = entry("serde", "MIT", Some(&mit("2014 David Tolnay")), None);
+        let tokio = entry("tokio", "MIT", Some(&mit("2023 Tokio Contributors")), None);
+        let left_pad = entry("left-pad", "MIT", None, None);
+        let groups = text_groups("MIT", &[&serde, &tokio, &left_pad]);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].entries.len(), 3);
+        assert!(!groups[0].body.as_ref().unwrap().contains("Tolnay"));
+    }
+
+    #[test]
+    fn test_notice_content() {
+        let entries = vec![
+            entry("tokio", "MIT", Some(&mit("2023 Tokio Contributors")), None),
+            entry(
+                "hyper",
+                "Apache-2.0",
+                Some("Apache License\nVersion 2.0, January 2004\n"),
+                Some("Hyper\nCopyright 2014 Sean McArthur\n"),
+            ),
+            entry("serde", "MIT", Some(&mit("2014 David Tolnay")), None),
+            entry("internal-fork", "LicenseRef-Custom", None, None),
+        ];
+        let content = notice_content(&entries);
+
+        assert!(content.contains("  MIT                2 components\n"));
+        assert!(content.contains("serde 1.0.0\n    Copyright (c) 2014 David Tolnay\ntokio 1.0.0\n"));
+        assert_eq!(content.matches("Permission is hereby granted").count(), 1);
+        assert!(content.contains("NOTICE file of hyper 1.0.0"));
+        assert!(content.contains("Copyright 2014 Sean McArthur"));
+        assert!(content.contains("Components without a license text"));
+        assert!(content.ends_with("internal-fork@1.0.0\n"));
+        assert!(content.find("\nApache-2.0\n").unwrap() < content.find("\nMIT\n").unwrap());
+        assert_eq!(
+            missing_texts(&entries),
+            vec!["internal-fork@1.0.0".to_string()]
+        );
+    }
+}
@@ -0,0 +1,612 @@
+//! `--obligations`: what the dependencies' licenses ask of the project, as tasks
+//!
+//! Each license is mapped to the obligations it carries (keep its text and copyright notices,
+//! ship the NOTICE file, publish the source of a copyleft component, ...) and the scan turns
+//! them into actionable items: one per license for obligations met once for every dependency
+//! under it, one per dependency for those met component by component. The tasks are written
+//! as JSON for a tracker to import, or opened as GitHub issues with `--obligations-issues`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::data_licenses::data_license;
+use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
+use crate::licenses::{spdx_special_value, LicenseCompatibility, LicenseInfo};
+use crate::resolver::known_license;
+use crate::spdx::{self, SpdxExpression};
+
+/// Label on the issues `--obligations-issues` opens, used to find them again
+const ISSUE_LABEL: &str = "feluda-obligation";
+
+/// Most open obligation issues read back when checking for duplicates
+const MAX_ISSUE_PAGES: usize = 10;
+
+/// How long one GitHub issues request may take
+const ISSUE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What a license asks of a project shipping it, least demanding first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ObligationKind {
+    /// Keep the license text and copyright notices with the distribution
+    Attribution,
+    /// Ship the upstream NOTICE file contents (Apache-2.0)
+    Notice,
+    /// Publish the source of modified files (MPL, EPL, CDDL)
+    ModifiedSource,
+    /// Share adapted data or content under the same license (CC-BY-SA, ODbL)
+    ShareAlike,
+    /// Let users relink against a modified library (LGPL)
+    Relinking,
+    /// Publish the complete corresponding source (GPL)
+    SourceDisclosure,
+    /// Offer the source to users interacting over a network (AGPL)
+    NetworkDisclosure,
+    /// Ship the data or content only as received (CC-BY-ND)
+    NoDerivatives,
+    /// Keep the data or content out of commercial use (CC-BY-NC)
+    NonCommercial,
+    /// Find out which license applies before anything can be said
+    IdentifyLicense,
+    /// The license conflicts with the project's; the dependency can't ship as it is
+    ResolveIncompatibility,
+}
+
+impl ObligationKind {
+    /// Whether a task covers every dependency under a license, or one dependency each
+    fn per_license(self) -> bool {
+        matches!(
+            self,
+            Self::Attribution | Self::Notice | Self::ModifiedSource
+        )
+    }
+}
+
+/// One actionable item
+#[derive(Debug, Clone, Serialize)]
+pub struct Obligation {
+    /// Stable across scans (`notice:Apache-2.0`, `source-disclosure:readline`), so trackers
+    /// can match a task to the one imported last time
+    pub id: String,
+    pub kind: ObligationKind,
+    pub title: String,
+    pub description: String,
+    pub licenses: Vec<String>,
+    /// `name@version` of every dependency the task covers
+    pub dependencies: Vec<String>,
+}
+
+/// The `--obligations` file
+#[derive(Debug, Serialize)]
+struct ObligationsDocument<'a> {
+    generated_at: String,
+    project: &'a str,
+    project_license: Option<&'a str>,
+    obligations: &'a [Obligation],
+}
+
+/// Obligations by license identifier; licenses without any (0BSD, CC0, ...) map to none
+fn license_obligations(id: &str) -> Vec<ObligationKind> {
+    use ObligationKind::*;
+
+    if let Some(terms) = data_license(id) {
+        return [
+            (terms.attribution, Attribution),
+            (terms.share_alike, ShareAlike),
+            (terms.no_derivatives, NoDerivatives),
+            (terms.non_commercial, NonCommercial),
+        ]
+        .into_iter()
+        .filter_map(|(applies, kind)| applies.then_some(kind))
+        .collect();
+    }
+    let id = id.to_ascii_uppercase();
+    if ["0BSD", "UNLICENSE", "CC0-1.0", "WTFPL", "MIT-0"].contains(&id.as_str()) {
+        return Vec::new();
+    }
+    if id.starts_with("APACHE-") {
+        vec![Attribution, Notice]
+    } else if id.starts_with("AGPL-") {
+        vec![Attribution, SourceDisclosure, NetworkDisclosure]
+    } else if id.starts_with("LGPL-") {
+        vec![Attribution, Relinking]
+    } else if id.starts_with("GPL-") || id.starts_with("EUPL-") {
+        vec![Attribution, SourceDisclosure]
+    } else if id.starts_with("MPL-") || id.starts_with("EPL-") || id.starts_with("CDDL-") {
+        vec![Attribution, ModifiedSource]
+    } else {
+        vec![Attribution]
+    }
+}
+
+/// `(obligation, license it comes from)` for an SPDX expression: every license of an `AND`,
+/// the least demanding alternative of an `OR`
+fn expression_obligations(expression: &SpdxExpression) -> Vec<(ObligationKind, String)> {
+    match expression {
+        SpdxExpression::License(id) | SpdxExpression::With { license: id, .. } => {
+            license_obligations(id)
+                .into_iter()
+                .map(|kind| (kind, id.clone()))
+                .collect()
+        }
+        SpdxExpression::And(a, b) => {
+            let mut obligations = expression_obligations(a);
+            for obligation in expression_obligations(b) {
+                if !obligations.contains(&obligation) {
+                    obligations.push(obligation);
+                }
+            }
+            obligations
+        }
+        SpdxExpression::Or(a, b) => {
+            let (a, b) = (expression_obligations(a), expression_obligations(b));
+            let demand = |obligations: &[(ObligationKind, String)]| {
+                (
+                    obligations.iter().map(|(kind, _)| *kind).max(),
+                    obligations.len(),
+                )
+            };
+            if demand(&b) < demand(&a) {
+                b
+            } else {
+                a
+            }
+        }
+    }
+}
+
+/// The tasks the licenses of `dependencies` call for, most demanding first
+pub fn collect_obligations(dependencies: &[LicenseInfo]) -> Vec<Obligation> {
+    // (kind, license or dependency) -> (licenses, dependencies)
+    let mut grouped: BTreeMap<(ObligationKind, String), (Vec<String>, Vec<String>)> =
+        BTreeMap::new();
+    let mut add = |kind: ObligationKind, license: &str, dep: &LicenseInfo| {
+        let label = format!("{}@{}", dep.name, dep.version);
+        let key = if kind.per_license() {
+            license.to_string()
+        } else if kind == ObligationKind::IdentifyLicense {
+            String::new()
+        } else {
+            dep.name.clone()
+        };
+        let (licenses, deps) = grouped.entry((kind, key)).or_default();
+        if !license.is_empty() && !licenses.iter().any(|known| known == license) {
+            licenses.push(license.to_string());
+        }
+        if !deps.contains(&label) {
+            deps.push(label);
+        }
+    };
+
+    // The organisation's own packages carry no obligations towards itself
+    for dep in dependencies.iter().filter(|dep| !dep.internal) {
+        let license = dep
+            .license
+            .clone()
+            .and_then(known_license)
+            .filter(|license| spdx_special_value(license).is_none());
+        let Some(license) = license else {
+            add(ObligationKind::IdentifyLicense, "", dep);
+            continue;
+        };
+        if dep.compatibility == LicenseCompatibility::Incompatible {
+            add(ObligationKind::ResolveIncompatibility, &license, dep);
+        }
+        for (kind, id) in expression_obligations(&spdx::parse(&license)) {
+            add(kind, &id, dep);
+        }
+    }
+
+    let mut obligations: Vec<Obligation> = grouped
+        .into_iter()
+        .map(|((kind, key), (licenses, mut dependencies))| {
+            dependencies.sort();
+            let (title, description) = describe(kind, &key, &licenses, &dependencies);
+            let id = if key.is_empty() {
+                kind_name(kind).to_string()
+            } else {
+                format!("{}:{key}", kind_name(kind))
+            };
+            Obligation {
+                id,
+                kind,
+                title,
+                description,
+                licenses,
+                dependencies,
+            }
+        })
+        .collect();
+    obligations.sort_by(|a, b| b.kind.cmp(&a.kind).then_with(|| a.id.cmp(&b.id)));
+    obligations
+}
+
+fn kind_name(kind: ObligationKind) -> &'static str {
+    match kind {
+        ObligationKind::Attribution => "attribution",
+        ObligationKind::Notice => "notice",
+        ObligationKind::ModifiedSource => "modified-source",
+        ObligationKind::ShareAlike => "share-alike",
+        ObligationKind::Relinking => "relinking",
+        ObligationKind::SourceDisclosure => "source-disclosure",
+        ObligationKind::NetworkDisclosure => "network-disclosure",
+        ObligationKind::NoDerivatives => "no-derivatives",
+        ObligationKind::NonCommercial => "non-commercial",
+        ObligationKind::IdentifyLicense => "identify-license",
+        ObligationKind::ResolveIncompatibility => "resolve-incompatibility",
+    }
+}
+
+/// Title and description of a task; `key` is the license or the dependency it's grouped by
+fn describe(
+    kind: ObligationKind,
+    key: &str,
+    licenses: &[String],
+    dependencies: &[String],
+) -> (String, String) {
+    let count = dependencies.len();
+    let plural = if count == 1 {
+        "dependency"
+    } else {
+        "dependencies"
+    };
+    let licenses = licenses.join(", ");
+    match kind {
+        ObligationKind::Attribution => (
+            format!("Include the {key} license text for {count} {plural}"),
+            format!(
+                "Distributions must keep the {key} license text and the copyright notices of \
+                 these dependencies. `feluda generate` writes them to THIRD_PARTY_LICENSES."
+            ),
+        ),
+        ObligationKind::Notice => (
+            format!("Add a NOTICE file for {count} {key} {plural}"),
+            format!(
+                "{key} requires redistributing the NOTICE file contents of these dependencies \
+                 alongside the project. `feluda generate` writes a NOTICE file."
+            ),
+        ),
+        ObligationKind::ModifiedSource => (
+            format!("Publish modified {key} files from {count} {plural}"),
+            format!(
+                "{key} is file-level copyleft: any modified file of these dependencies must be \
+                 published under {key} with the distribution."
+            ),
+        ),
+        ObligationKind::ShareAlike => (
+            format!("Share adaptations of {key} alike ({licenses})"),
+            format!(
+                "{key} is under {licenses}. Anything adapted from it (a cleaned or extended \
+                 dataset, a derived database) must be shared under the same license, with \
+                 credit to the source."
+            ),
+        ),
+        ObligationKind::Relinking => (
+            format!("Allow relinking against {key} ({licenses})"),
+            format!(
+                "{key} is under {licenses}. Link it dynamically, or ship what users need to \
+                 relink the project against a modified version, and publish any changes made \
+                 to the library itself."
+            ),
+        ),
+        ObligationKind::SourceDisclosure => (
+            format!("Publish source for {key} ({licenses})"),
+            format!(
+                "{key} is under {licenses}. Distributing it requires offering the complete \
+                 corresponding source of the work that includes it under the same license."
+            ),
+        ),
+        ObligationKind::NetworkDisclosure => (
+            format!("Offer source to network users of {key} ({licenses})"),
+            format!(
+                "{key} is under {licenses}. Users interacting with the software over a network \
+                 must be offered its complete corresponding source, even without a \
+                 distribution."
+            ),
+        ),
+        ObligationKind::NoDerivatives => (
+            format!("Ship {key} unmodified ({licenses})"),
+            format!(
+                "{key} is under {licenses}, which forbids sharing adapted versions. Redistribute \
+                 it only as received, or get permission from the licensor."
+            ),
+        ),
+        ObligationKind::NonCommercial => (
+            format!("Keep {key} out of commercial use ({licenses})"),
+            format!(
+                "{key} is under {licenses}, which only permits non-commercial use. Replace it, \
+                 or get a commercial license from the licensor, before using it in a product."
+            ),
+        ),
+        ObligationKind::IdentifyLicense => (
+            format!("Identify the license of {count} {plural}"),
+            "No license could be determined for these dependencies, so their obligations are \
+             unknown. Check their repositories, then add an override or an exception."
+                .to_string(),
+        ),
+        ObligationKind::ResolveIncompatibility => (
+            format!("Resolve incompatible license of {key} ({licenses})"),
+            format!(
+                "{key} is under {licenses}, which is incompatible with the project license. \
+                 Replace it, get a licensing exception, or record an accepted exception with \
+                 `feluda accept`."
+            ),
+        ),
+    }
+}
+
+/// Write the obligations of `dependencies` as JSON to `path`
+pub fn write_obligations(
+    dependencies: &[LicenseInfo],
+    path: &Path,
+    project: &str,
+    project_license: Option<&str>,
+) -> FeludaResult<()> {
+    let obligations = collect_obligations(dependencies);
+    let document = ObligationsDocument {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        project,
+        project_license,
+        obligations: &obligations,
+    };
+    let json = serde_json::to_string_pretty(&document)
+        .map_err(|e| FeludaError::Serialization(format!("Failed to serialize obligations: {e}")))?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, json).map_err(|e| {
+        FeludaError::FileWrite(format!(
+            "Failed to write obligations to {}: {e}",
+            path.display()
+        ))
+    })?;
+    eprintln!(
+        "📋 Wrote {} license obligations to {}",
+        obligations.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Marker in an issue body naming the obligation it tracks
+fn issue_marker(id: &str) -> String {
+    format!("<!-- feluda-obligation: {id} -->")
+}
+
+fn issue_body(obligation: &Obligation) -> String {
+    let mut body = format!("{}\n\n**Dependencies:**\n\n", obligation.description);
+    for dep in &obligation.dependencies {
+        body.push_str(&format!("- `{dep}`\n"));
+    }
+    body.push_str(&format!(
+        "\n_Opened by `feluda --obligations-issues`._\n{}\n",
+        issue_marker(&obligation.id)
+    ));
+    body
+}
+
+/// Open a GitHub issue in `repository` (`owner/name`) for every obligation of `dependencies`
+/// that doesn't have an open one yet
+pub fn open_obligation_issues(dependencies: &[LicenseInfo], repository: &str) -> FeludaResult<()> {
+    let Some((owner, name)) = repository
+        .split_once('/')
+        .filter(|(owner, name)| !owner.is_empty() && !name.is_empty() && !name.contains('/'))
+    else {
+        return Err(FeludaError::InvalidData(format!(
+            "--obligations-issues expects OWNER/REPO, got '{repository}'"
+        )));
+    };
+    let Some(token) = crate::licenses::get_github_token() else {
+        return Err(FeludaError::InvalidData(
+            "--obligations-issues needs a GitHub token (--github-token or GITHUB_TOKEN)"
+                .to_string(),
+        ));
+    };
+
+    let client = crate::cancel::shared_client()?;
+    let api = format!(
+        "{}/repos/{owner}/{name}/issues",
+        crate::licenses::github_api_url()
+    );
+
+    let existing = open_issue_bodies(client, &api, &token)?;
+    let mut opened = 0;
+    let mut skipped = 0;
+    for obligation in collect_obligations(dependencies) {
+        let marker = issue_marker(&obligation.id);
+        if existing.iter().any(|body| body.contains(&marker)) {
+            skipped += 1;
+            continue;
+        }
+
+        let request = client
+            .post(&api)
+            .bearer_auth(&token)
+            .timeout(crate::cancel::request_timeout(ISSUE_TIMEOUT))
+            .json(&json!({
+                "title": obligation.title,
+                "body": issue_body(&obligation),
+                "labels": [ISSUE_LABEL],
+            }));
+        let response = crate::cancel::http_send(request, &api)
+            .map_err(|e| FeludaError::InvalidData(format!("Failed to open issue: {e}")))?;
+        if !response.status().is_success() {
+            return Err(FeludaError::InvalidData(format!(
+                "GitHub refused to open an issue in {repository}: HTTP {}",
+                response.status()
+            )));
+        }
+        let issue: Value = response.json()?;
+        log(
+            LogLevel::Info,
+            &format!("Opened issue for obligation {}", obligation.id),
+        );
+        eprintln!(
+            "📝 Opened {}: {}",
+            issue["html_url"].as_str().unwrap_or(repository),
+            obligation.title
+        );
+        opened += 1;
+    }
+
+    eprintln!("📋 Obligation issues in {repository}: {opened} opened, {skipped} already open");
+    Ok(())
+}
+
+/// Bodies of the open issues carrying the obligation label
+fn open_issue_bodies(
+    client: &reqwest::blocking::Client,
+    api: &str,
+    token: &str,
+) -> FeludaResult<Vec<String>> {
+    let mut bodies = Vec::new();
+    for page in 1..=MAX_ISSUE_PAGES {
+        let url = format!("{api}?state=open&labels={ISSUE_LABEL}&per_page=100&page={page}");
+        let request = client
+            .get(&url)
+            .bearer_auth(token)
+            .timeout(crate::cancel::request_timeout(ISSUE_TIMEOUT));
+        let response = crate::cancel::http_send(request, &url)
+            .map_err(|e| FeludaError::InvalidData(format!("Failed to list issues: {e}")))?;
+        if !response.status().is_success() {
+            return Err(FeludaError::InvalidData(format!(
+                "Failed to list issues at {url}: HTTP {}",
+                response.status()
+            )));
+        }
+        let issues: Vec<Value> = response.json()?;
+        let last_page = issues.len() < 100;
+        bodies.extend(
+            issues
+                .iter()
+                .filter_map(|issue| issue["body"].as_str().map(str::to_string)),
+        );
+        if last_page {
+            break;
+        }
+    }
+    Ok(bodies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expression_obligations_picks_lightest_alternative() {
+        let kinds = |license: &str| -> Vec<(ObligationKind, String)> {
+            expression_obligations(&spdx::parse(license))
+        };
+        assert_eq!(
+            kinds("MIT OR GPL-3.0-only"),
+            vec![(ObligationKind::Attribution, "MIT".to_string())]
+        );
+        assert_eq!(
+            kinds("GPL-3.0-only OR Apache-2.0"),
+            vec![
+                (ObligationKind::Attribution, "Apache-2.0".to_string()),
+                (ObligationKind::Notice, "Apache-2.0".to_string()),
+            ]
+        );
+        assert_eq!(kinds("MIT AND LGPL-2.1-only").len(), 3);
+        assert!(kinds("0BSD").is_empty());
+    }
+
+    #[test]
+    fn test_data_license_obligations() {
+        use ObligationKind::*;
+
+        assert!(license_obligations("CC0-1.0").is_empty());
+        assert!(license_obligations("PDDL-1.0").is_empty());
+        assert_eq!(license_obligations("CC-BY-4.0"), vec![Attribution]);
+        assert_eq!(
+            license_obligations("ODbL-1.0"),
+            vec![Attribution, ShareAlike]
+        );
+        assert_eq!(
+            license_obligations("CC-BY-NC-SA-4.0"),
+            vec![Attribution, ShareAlike, NonCommercial]
+        );
+        assert_eq!(
+            license_obligations("CC-BY-ND-4.0"),
+            vec![Attribution, NoDerivatives]
+        );
+
+        let obligations = collect_obligations(&[
+            LicenseInfo::fixture("osm-extract", "1.0.0", "ODbL-1.0"),
+            LicenseInfo::fixture("imagenet-weights", "1.0.0", "CC-BY-NC-4.0"),
+        ]);
+        let ids: Vec<&str> = obligations.iter().map(|o| o.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "non-commercial:imagenet-weights",
+                "share-alike:osm-extract",
+                "attribution:CC-BY-NC-4.0",
+                "attribution:ODbL-1.0",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_obligations_groups_tasks() {
+        let mut incompatible = LicenseInfo::fixture("readline", "1.0.0", "GPL-3.0-only");
+        incompatible.compatibility = LicenseCompatibility::Incompatible;
+        let mut internal = LicenseInfo::fixture("acme-core", "1.0.0", "LicenseRef-Acme");
+        internal.internal = true;
+        let deps = vec![
+            LicenseInfo::fixture("serde", "1.0.0", "MIT OR Apache-2.0"),
+            LicenseInfo::fixture("tokio", "1.0.0", "MIT"),
+            LicenseInfo::fixture("log4j", "1.0.0", "Apache-2.0"),
+            incompatible,
+            internal,
+            LicenseInfo::fixture("mystery", "1.0.0", None),
+        ];
+
+        let obligations = collect_obligations(&deps);
+        let ids: Vec<&str> = obligations.iter().map(|o| o.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "resolve-incompatibility:readline",
+                "identify-license",
+                "source-disclosure:readline",
+                "notice:Apache-2.0",
+                "attribution:Apache-2.0",
+                "attribution:GPL-3.0-only",
+                "attribution:MIT",
+            ]
+        );
+
+        let mit = obligations
+            .iter()
+            .find(|o| o.id == "attribution:MIT")
+            .unwrap();
+        assert_eq!(mit.dependencies, vec!["serde@1.0.0", "tokio@1.0.0"]);
+        assert_eq!(mit.title, "Include the MIT license text for 2 dependencies");
+        let unknown = obligations
+            .iter()
+            .find(|o| o.id == "identify-license")
+            .unwrap();
+        assert_eq!(unknown.dependencies, vec!["mystery@1.0.0"]);
+        assert!(unknown.licenses.is_empty());
+    }
+
+    #[test]
+    fn test_issue_body_carries_marker() {
+        let obligations =
+            collect_obligations(&[LicenseInfo::fixture("readline", "1.0.0", "GPL-3.0-only")]);
+        let source = obligations
+            .iter()
+            .find(|o| o.kind == ObligationKind::SourceDisclosure)
+            .unwrap();
+        let body = issue_body(source);
+        assert!(body.contains("- `readline@1.0.0`"));
+        assert!(body.ends_with("<!-- feluda-obligation: source-disclosure:readline -->\n"));
+    }
+}
@@ -0,0 +1,231 @@
+//! Known vulnerabilities from OSV.dev (`--vulnerabilities`)
+//!
+//! Every dependency's package URL goes to the OSV.dev batch query API, which answers with the
+//! advisories (GHSA, RUSTSEC, PYSEC, GO, ...) affecting that exact version. Their ids are
+//! recorded on the dependency, so one report covers both its license and its security posture.
+//! Dependencies without a versioned purl (C/C++, unpinned versions) aren't looked up and keep
+//! `vulnerabilities: None`. The lookup is opt-in because it sends the dependency list to a
+//! third-party service.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::debug::{log, LogLevel};
+use crate::licenses::LicenseInfo;
+
+const QUERY_BATCH_URL: &str = "https://api.osv.dev/v1/querybatch";
+
+/// Queries per request, the most OSV.dev accepts in one batch
+const BATCH_SIZE: usize = 1000;
+
+/// Pages followed for one package before giving up on the rest of its advisories
+const MAX_PAGES: usize = 10;
+
+/// One package's answer in a `querybatch` response
+#[derive(Debug, PartialEq)]
+struct QueryResult {
+    ids: Vec<String>,
+    /// Set when the package has more advisories than fit in one page
+    next_page_token: Option<String>,
+}
+
+/// Record the OSV advisories affecting each dependency. Returns how many dependencies have at
+/// least one; on failure no dependency is annotated.
+pub fn annotate_vulnerabilities(deps: &mut [LicenseInfo]) -> Result<usize, String> {
+    let purls = versioned_purls(deps);
+    if purls.is_empty() {
+        return Ok(0);
+    }
+
+    let mut advisories: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for batch in purls.chunks(BATCH_SIZE) {
+        for (purl, ids) in batch.iter().zip(query_all_pages(batch)?) {
+            advisories.insert(purl, ids);
+        }
+    }
+
+    let mut vulnerable = 0;
+    for dep in deps.iter_mut() {
+        let Some(ids) = versioned_purl(dep).and_then(|purl| advisories.get(purl.as_str())) else {
+            continue;
+        };
+        if !ids.is_empty() {
+            vulnerable += 1;
+        }
+        dep.vulnerabilities = Some(ids.clone());
+    }
+    log(
+        LogLevel::Info,
+        &format!(
+            "OSV.dev: {vulnerable} of {} looked-up dependencies have known vulnerabilities",
+            purls.len()
+        ),
+    );
+    Ok(vulnerable)
+}
+
+/// A purl OSV.dev can match against affected versions: one that names a version
+fn versioned_purl(dep: &LicenseInfo) -> Option<String> {
+    // Scoped npm names encode their `@`, so a literal one always starts the version
+    crate::purl::of(dep).filter(|purl| purl.contains('@'))
+}
+
+/// The distinct versioned purls of the dependencies, in a stable order
+fn versioned_purls(deps: &[LicenseInfo]) -> Vec<String> {
+    deps.iter()
+        .filter_map(versioned_purl)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Advisory ids for each query, following `next_page_token` for packages with many advisories
+fn query_all_pages(purls: &[String]) -> Result<Vec<Vec<String>>, String> {
+    let mut ids: Vec<BTreeSet<String>> = vec![BTreeSet::new(); purls.len()];
+    let mut pending: Vec<(usize, Option<String>)> = (0..purls.len()).map(|i| (i, None)).collect();
+
+    for _ in 0..MAX_PAGES {
+        if pending.is_empty() {
+            break;
+        }
+        let page: Vec<(&str, Option<String>)> = pending
+            .iter()
+            .map(|(index, token)| (purls[*index].as_str(), token.clone()))
+            .collect();
+        let results = parse_batch_response(&post_batch(&page)?)?;
+        if results.len() != page.len() {
+            return Err(format!(
+                "OSV.dev answered {} of {} queries",
+                results.len(),
+                page.len()
+            ));
+        }
+
+        let mut next = Vec::new();
+        for ((index, _), result) in pending.iter().zip(results) {
+            ids[*index].extend(result.ids);
+            if let Some(token) = result.next_page_token {
+                next.push((*index, Some(token)));
+            }
+        }
+        pending = next;
+    }
+    Ok(ids
+        .into_iter()
+        .map(|ids| ids.into_iter().collect())
+        .collect())
+}
+
+fn post_batch(queries: &[(&str, Option<String>)]) -> Result<Value, String> {
+    let body = json!({
+        "queries": queries
+            .iter()
+            .map(|(purl, token)| match token {
+                Some(token) => json!({ "package": { "purl": purl }, "page_token": token }),
+                None => json!({ "package": { "purl": purl } }),
+            })
+            .collect::<Vec<_>>(),
+    });
+    log(
+        LogLevel::Info,
+        &format!("Querying OSV.dev for {} packages", queries.len()),
+    );
+
+    let request = crate::cancel::shared_client()
+        .map_err(|e| format!("Failed to build HTTP client: {e}"))?
+        .post(QUERY_BATCH_URL)
+        .json(&body)
+        .timeout(crate::cancel::request_timeout(Duration::from_secs(60)));
+    let response = crate::cancel::http_send(request, QUERY_BATCH_URL)
+        .map_err(|e| format!("OSV.dev query failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("OSV.dev query failed: HTTP {}", response.status()));
+    }
+    response
+        .json()
+        .map_err(|e| format!("Invalid OSV.dev response: {e}"))
+}
+
+/// The advisory ids and next page token of each result in a `querybatch` response
+fn parse_batch_response(response: &Value) -> Result<Vec<QueryResult>, String> {
+    let results = response["results"]
+        .as_array()
+        .ok_or("Invalid OSV.dev response: no results")?;
+    Ok(results
+        .iter()
+        .map(|result| {
+            let ids = result["vulns"]
+                .as_array()
+                .map(|vulns| {
+                    vulns
+                        .iter()
+                        .filter_map(|vuln| vuln["id"].as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let next_page_token = result["next_page_token"]
+                .as_str()
+                .filter(|token| !token.is_empty())
+                .map(str::to_string);
+            QueryResult {
+                ids,
+                next_page_token,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versioned_purls() {
+        let deps = vec![
+            LicenseInfo::fixture("lodash", "4.17.20", "MIT").with_ecosystem("node"),
+            LicenseInfo::fixture("@babel/core", "latest", "MIT").with_ecosystem("node"),
+            LicenseInfo::fixture("lodash", "4.17.20", "MIT").with_ecosystem("node"),
+            LicenseInfo::fixture("zlib", "1.3", "MIT").with_ecosystem("cpp"),
+            LicenseInfo::fixture("serde", "1.0.200", "MIT").with_ecosystem("rust"),
+        ];
+        assert_eq!(
+            versioned_purls(&deps),
+            vec!["pkg:cargo/serde@1.0.200", "pkg:npm/lodash@4.17.20"]
+        );
+    }
+
+    #[test]
+    fn test_parse_batch_response() {
+        let response = json!({
+            "results": [
+                {
+                    "vulns": [
+                        { "id": "GHSA-35jh-r3h4-6jhm", "modified": "2024-01-01T00:00:00Z" },
+                        { "id": "GHSA-p6mc-m468-83gw", "modified": "2024-01-01T00:00:00Z" }
+                    ],
+                    "next_page_token": "page-2"
+                },
+                {}
+            ]
+        });
+        assert_eq!(
+            parse_batch_response(&response).unwrap(),
+            vec![
+                QueryResult {
+                    ids: vec![
+                        "GHSA-35jh-r3h4-6jhm".to_string(),
+                        "GHSA-p6mc-m468-83gw".to_string()
+                    ],
+                    next_page_token: Some("page-2".to_string()),
+                },
+                QueryResult {
+                    ids: Vec::new(),
+                    next_page_token: None,
+                },
+            ]
+        );
+        assert!(parse_batch_response(&json!({ "error": "bad request" })).is_err());
+    }
+}
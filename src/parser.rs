@@ -1,21 +1,35 @@
 //! Core parsing coordination and project discovery functionality
 
 use crate::cli;
-use crate::debug::{log, log_debug, FeludaResult, LogLevel};
+use crate::config::CargoLockMode;
+use crate::debug::{log, log_debug, record_parse_warning, FeludaError, FeludaResult, LogLevel};
 use crate::languages::{
-    c::analyze_c_licenses, cpp::analyze_cpp_licenses, dotnet::analyze_dotnet_licenses,
-    go::analyze_go_licenses, java::analyze_java_licenses, node::analyze_js_licenses_with_no_local,
-    python::analyze_python_licenses, r::analyze_r_licenses, ruby::analyze_ruby_licenses,
+    android::{analyze_android_licenses, is_android_project},
+    c::analyze_c_licenses,
+    carthage::analyze_carthage_licenses,
+    cpp::analyze_cpp_licenses,
+    dotnet::analyze_dotnet_licenses,
+    elixir::analyze_elixir_licenses,
+    go::analyze_go_licenses,
+    java::analyze_java_licenses,
+    node::analyze_js_licenses_with_no_local,
+    php::analyze_php_licenses,
+    python::analyze_python_licenses,
+    r::analyze_r_licenses,
+    ruby::analyze_ruby_licenses,
     rust::analyze_rust_licenses_with_metadata,
+    wasm::{analyze_wasm_licenses, has_wit_packages},
 };
 use crate::languages::{
-    Language, CPP_PATHS, C_PATHS, DOTNET_PATHS, JAVA_PATHS, PYTHON_PATHS, RUBY_PATHS, R_PATHS,
+    Language, ANDROID_PATHS, CARTHAGE_PATHS, CPP_PATHS, C_PATHS, DOTNET_PATHS, ELIXIR_PATHS,
+    JAVA_PATHS, PHP_PATHS, PYTHON_PATHS, RUBY_PATHS, R_PATHS, WASM_PATHS,
 };
 use crate::licenses::{
     detect_project_license, is_license_compatible, LicenseCompatibility, LicenseInfo,
 };
-use cargo_metadata::MetadataCommand;
+use cargo_metadata::{CargoOpt, MetadataCommand};
 use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 /// Project root information
@@ -82,6 +96,32 @@ fn find_project_roots(root_path: impl AsRef<Path>) -> FeludaResult<Vec<ProjectRo
         }
     }
 
+    // A settings file makes an Android/KMP root only for builds that are one; the Android
+    // analyzer then covers the directory's Gradle build files, unless a pom.xml is the Java
+    // root's manifest
+    project_roots.retain(|project| {
+        !matches!(project.project_type, Language::Android(_)) || is_android_project(&project.path)
+    });
+    if project_roots
+        .iter()
+        .any(|project| matches!(project.project_type, Language::Android(_)))
+        && !root.join("pom.xml").exists()
+    {
+        project_roots.retain(|project| !matches!(project.project_type, Language::Java(_)));
+    }
+
+    // A `wit/` directory makes a WebAssembly component root without wasm-pkg-tools files
+    if !project_roots
+        .iter()
+        .any(|project| matches!(project.project_type, Language::Wasm(_)))
+        && has_wit_packages(root)
+    {
+        project_roots.push(ProjectRoot {
+            path: root.to_path_buf(),
+            project_type: Language::Wasm(&WASM_PATHS),
+        });
+    }
+
     log(
         LogLevel::Info,
         &format!("Found {} project roots", project_roots.len()),
@@ -137,14 +177,39 @@ fn check_which_cpp_file_exists(project_path: impl AsRef<Path>) -> Option<String>
     None
 }
 
-/// Check which Python project file exists in the given path
-fn check_which_python_file_exists(project_path: impl AsRef<Path>) -> Option<String> {
-    for &path in PYTHON_PATHS.iter() {
+/// Find every Python manifest present in the given path, in `PYTHON_PATHS` order
+fn find_python_files(project_path: impl AsRef<Path>) -> Vec<String> {
+    let found: Vec<String> = PYTHON_PATHS
+        .iter()
+        .filter(|path| project_path.as_ref().join(path).exists())
+        .map(|path| path.to_string())
+        .collect();
+
+    if found.is_empty() {
+        log(
+            LogLevel::Warn,
+            &format!(
+                "No Python project file found in: {}",
+                project_path.as_ref().display()
+            ),
+        );
+    } else {
+        log(
+            LogLevel::Info,
+            &format!("Found Python project files: {}", found.join(", ")),
+        );
+    }
+    found
+}
+
+/// Check which R project file exists in the given path
+fn check_which_r_file_exists(project_path: impl AsRef<Path>) -> Option<String> {
+    for &path in R_PATHS.iter() {
         let full_path = Path::new(project_path.as_ref()).join(path);
         if full_path.exists() {
             log(
                 LogLevel::Info,
-                &format!("Found Python project file: {}", full_path.display()),
+                &format!("Found R project file: {}", full_path.display()),
             );
             return Some(path.to_string());
         }
@@ -153,21 +218,21 @@ fn check_which_python_file_exists(project_path: impl AsRef<Path>) -> Option<Stri
     log(
         LogLevel::Warn,
         &format!(
-            "No Python project file found in: {}",
+            "No R project file found in: {}",
             project_path.as_ref().display()
         ),
     );
     None
 }
 
-/// Check which R project file exists in the given path
-fn check_which_r_file_exists(project_path: impl AsRef<Path>) -> Option<String> {
-    for &path in R_PATHS.iter() {
+/// Check which Java project file exists in the given path
+fn check_which_java_file_exists(project_path: impl AsRef<Path>) -> Option<String> {
+    for &path in JAVA_PATHS.iter() {
         let full_path = Path::new(project_path.as_ref()).join(path);
         if full_path.exists() {
             log(
                 LogLevel::Info,
-                &format!("Found R project file: {}", full_path.display()),
+                &format!("Found Java project file: {}", full_path.display()),
             );
             return Some(path.to_string());
         }
@@ -176,21 +241,20 @@ fn check_which_r_file_exists(project_path: impl AsRef<Path>) -> Option<String> {
     log(
         LogLevel::Warn,
         &format!(
-            "No R project file found in: {}",
+            "No Java project file found in: {}",
             project_path.as_ref().display()
         ),
     );
     None
 }
 
-/// Check which Java project file exists in the given path
-fn check_which_java_file_exists(project_path: impl AsRef<Path>) -> Option<String> {
-    for &path in JAVA_PATHS.iter() {
+fn check_which_android_file_exists(project_path: impl AsRef<Path>) -> Option<String> {
+    for &path in ANDROID_PATHS.iter() {
         let full_path = Path::new(project_path.as_ref()).join(path);
         if full_path.exists() {
             log(
                 LogLevel::Info,
-                &format!("Found Java project file: {}", full_path.display()),
+                &format!("Found Android project file: {}", full_path.display()),
             );
             return Some(path.to_string());
         }
@@ -199,7 +263,7 @@ fn check_which_java_file_exists(project_path: impl AsRef<Path>) -> Option<String
     log(
         LogLevel::Warn,
         &format!(
-            "No Java project file found in: {}",
+            "No Android project file found in: {}",
             project_path.as_ref().display()
         ),
     );
@@ -228,6 +292,72 @@ fn check_which_ruby_file_exists(project_path: impl AsRef<Path>) -> Option<String
     None
 }
 
+fn check_which_php_file_exists(project_path: impl AsRef<Path>) -> Option<String> {
+    for &path in PHP_PATHS.iter() {
+        let full_path = Path::new(project_path.as_ref()).join(path);
+        if full_path.exists() {
+            log(
+                LogLevel::Info,
+                &format!("Found PHP project file: {}", full_path.display()),
+            );
+            return Some(path.to_string());
+        }
+    }
+
+    log(
+        LogLevel::Warn,
+        &format!(
+            "No PHP project file found in: {}",
+            project_path.as_ref().display()
+        ),
+    );
+    None
+}
+
+fn check_which_carthage_file_exists(project_path: impl AsRef<Path>) -> Option<String> {
+    for &path in CARTHAGE_PATHS.iter() {
+        let full_path = Path::new(project_path.as_ref()).join(path);
+        if full_path.exists() {
+            log(
+                LogLevel::Info,
+                &format!("Found Carthage project file: {}", full_path.display()),
+            );
+            return Some(path.to_string());
+        }
+    }
+
+    log(
+        LogLevel::Warn,
+        &format!(
+            "No Carthage project file found in: {}",
+            project_path.as_ref().display()
+        ),
+    );
+    None
+}
+
+fn check_which_elixir_file_exists(project_path: impl AsRef<Path>) -> Option<String> {
+    for &path in ELIXIR_PATHS.iter() {
+        let full_path = Path::new(project_path.as_ref()).join(path);
+        if full_path.exists() {
+            log(
+                LogLevel::Info,
+                &format!("Found Elixir project file: {}", full_path.display()),
+            );
+            return Some(path.to_string());
+        }
+    }
+
+    log(
+        LogLevel::Warn,
+        &format!(
+            "No Elixir project file found in: {}",
+            project_path.as_ref().display()
+        ),
+    );
+    None
+}
+
 fn check_which_dotnet_file_exists(project_path: impl AsRef<Path>) -> Option<String> {
     for &path in DOTNET_PATHS.iter() {
         if path.starts_with('.') {
@@ -270,20 +400,27 @@ fn check_which_dotnet_file_exists(project_path: impl AsRef<Path>) -> Option<Stri
 pub fn parse_root(
     root_path: impl AsRef<Path>,
     language: Option<&str>,
+    skip: &[String],
     strict: bool,
     no_local: bool,
+    cargo_lock: Option<CargoLockMode>,
+    no_dev: bool,
 ) -> FeludaResult<Vec<LicenseInfo>> {
     let mut config = crate::config::load_config()?;
-    config.strict = strict;
-    parse_root_with_config(root_path, language, &config, no_local)
+    config.apply_flags(strict, cargo_lock, no_dev, false, false, skip);
+    parse_root_with_config(root_path, language, &config, no_local, None)
 }
 
-/// Main entry point for parsing project dependencies
+/// Main entry point for parsing project dependencies.
+///
+/// With `changed` (`--changed-since`), only the project roots one of those dependency files
+/// belongs to are analyzed, see [`changed_roots`].
 pub fn parse_root_with_config(
     root_path: impl AsRef<Path>,
     language: Option<&str>,
     config: &crate::config::FeludaConfig,
     no_local: bool,
+    changed: Option<&[PathBuf]>,
 ) -> FeludaResult<Vec<LicenseInfo>> {
     log(
         LogLevel::Info,
@@ -292,8 +429,26 @@ pub fn parse_root_with_config(
     if let Some(lang) = language {
         log(LogLevel::Info, &format!("Filtering by language: {lang}"));
     }
+    for ecosystem in &config.skip {
+        if !is_known_language(ecosystem) {
+            log(
+                LogLevel::Warn,
+                &format!("Unknown ecosystem '{ecosystem}' in skip list, ignoring it"),
+            );
+        }
+    }
+
+    crate::resolver::set_resolver_order(config.resolvers.order.clone());
+    crate::resolver::set_lookup_jobs(config.resolvers.jobs);
+    crate::licenses::set_custom_license_rules(&config.licenses.rules);
+    crate::licenses::set_license_ref_classes(&config.licenses.refs);
+    crate::licenses::set_special_license_policies(&config.licenses);
+    crate::licenses::set_license_policy(&config.licenses);
+    crate::resolver::set_internal_namespaces(config.internal.clone());
+    crate::licenses::set_project_licenses(&config.project_licenses);
 
     let project_roots = find_project_roots(&root_path)?;
+    let scan_root = root_path.as_ref();
 
     if project_roots.is_empty() {
         log(
@@ -301,15 +456,18 @@ pub fn parse_root_with_config(
             "No project files found in the specified path",
         );
         println!(
-            "❌ No supported project files found.\n\
-            Feluda supports: C, C++, .NET, Java/Maven/Gradle, Rust, Node.js, Go, Python, R"
+            "❌ No supported project files found.\n{}",
+            supported_ecosystems_hint()
         );
         return Ok(Vec::new());
     }
 
+    let changed_roots = changed.map(|changed| changed_roots(&project_roots, scan_root, changed));
+
     let licenses: Vec<LicenseInfo> = project_roots
         .into_par_iter()
-        .filter_map(|root| {
+        .enumerate()
+        .filter_map(|(index, root)| {
             if let Some(language) = language {
                 if !matches_language(root.project_type, language) {
                     log(
@@ -323,8 +481,50 @@ pub fn parse_root_with_config(
                 }
             }
 
-            match parse_dependencies(&root, config, no_local) {
-                Ok(deps) => {
+            if config
+                .skip
+                .iter()
+                .any(|skipped| matches_language(root.project_type, skipped))
+            {
+                log(
+                    LogLevel::Info,
+                    &format!("Skipping {:?} project (skip list)", root.project_type),
+                );
+                return None;
+            }
+
+            if changed_roots
+                .as_ref()
+                .is_some_and(|changed| !changed.contains(&index))
+            {
+                log(
+                    LogLevel::Info,
+                    &format!(
+                        "Skipping {:?} project (no dependency file changed)",
+                        root.project_type
+                    ),
+                );
+                return None;
+            }
+
+            Some(match parse_dependencies(&root, config, no_local) {
+                Ok(mut deps) => {
+                    let label = project_label(root.project_type);
+                    let manifest = root_manifest(&root, scan_root);
+                    for dep in &mut deps {
+                        dep.found_in = vec![label.to_string()];
+                        dep.ecosystem = Some(label.to_string());
+                        if dep.manifest.is_none() {
+                            dep.manifest = manifest.clone();
+                        }
+                        if dep.repository.is_none() {
+                            dep.repository = registry_page(label, &dep.name);
+                        }
+                        if dep.purl.is_none() {
+                            dep.purl = crate::purl::package_url(label, &dep.name, &dep.version);
+                        }
+                    }
+                    locate_declarations(&mut deps, &root, scan_root, label);
                     log(
                         LogLevel::Info,
                         &format!(
@@ -333,8 +533,11 @@ pub fn parse_root_with_config(
                             root.path.display()
                         ),
                     );
-                    Some(deps)
+                    Ok(deps)
                 }
+                // A lockfile the user asked us not to touch is a hard failure, not a
+                // skipped project
+                Err(err @ FeludaError::Lockfile(_)) => Err(err),
                 Err(err) => {
                     log(
                         LogLevel::Error,
@@ -344,20 +547,30 @@ pub fn parse_root_with_config(
                             err
                         ),
                     );
-                    None
+                    Ok(Vec::new())
                 }
-            }
+            })
         })
+        .collect::<FeludaResult<Vec<_>>>()?
+        .into_iter()
         .flatten()
         .collect();
 
+    crate::resolver::flush_package_cache();
+
     log(
         LogLevel::Info,
         &format!("Total dependencies found: {}", licenses.len()),
     );
 
+    let mut licenses = dedupe_across_roots(licenses);
+    assign_project_dirs(&mut licenses, scan_root, &config.project_licenses);
+    for dep in &mut licenses {
+        dep.internal = config.internal.is_internal(&dep.name);
+    }
+    crate::updates::annotate_update_policies(&mut licenses, scan_root);
+
     // Filter out ignored licenses
-    let mut licenses = licenses;
     let ignored_count = licenses.len();
     licenses.retain(|license| !crate::licenses::is_license_ignored(license.license.as_deref()));
     let filtered_count = licenses.len();
@@ -372,24 +585,11 @@ pub fn parse_root_with_config(
         );
     }
 
-    // Filter out ignored dependencies based on configuration
-    let ignored_count = licenses.len();
-    licenses.retain(|dep| {
-        !config
-            .dependencies
-            .should_ignore_dependency(&dep.name, Some(&dep.version))
-    });
-    let filtered_count = licenses.len();
-    if ignored_count != filtered_count {
-        log(
-            LogLevel::Info,
-            &format!(
-                "Filtered out {} ignored dependencies, {} remaining",
-                ignored_count - filtered_count,
-                filtered_count
-            ),
-        );
-    }
+    // Filter out ignored dependencies from the configuration, `.feludaignore` and
+    // suppression comments in the manifests
+    let mut ignore_rules = crate::ignore::load_ignore_file(scan_root);
+    ignore_rules.extend(crate::ignore::inline_suppressions(&licenses, scan_root));
+    crate::ignore::remove_ignored(&mut licenses, &config.dependencies, &ignore_rules);
 
     // Set license compatibility based on project license
     let project_license =
@@ -412,11 +612,473 @@ fn set_license_compatibility(licenses: &mut [LicenseInfo], project_license: &Opt
     }
 }
 
+/// Collapse the same package@version reported by several project roots into one entry.
+///
+/// The surviving entry lists every root it was found in. Provenance is cleared again when
+/// only one root was scanned, so single-project reports stay unchanged.
+fn dedupe_across_roots(deps: Vec<LicenseInfo>) -> Vec<LicenseInfo> {
+    let mut deduped: Vec<LicenseInfo> = Vec::with_capacity(deps.len());
+    let mut index: HashMap<(Option<String>, String, String), usize> = HashMap::new();
+    let mut roots: HashSet<String> = HashSet::new();
+
+    for dep in deps {
+        roots.extend(dep.found_in.iter().cloned());
+        let key = dedupe_key(&dep);
+        match index.get(&key) {
+            Some(&i) => {
+                let existing = &mut deduped[i];
+                let dep_known = has_known_license(&dep);
+                for root in dep.found_in {
+                    if !existing.found_in.contains(&root) {
+                        existing.found_in.push(root);
+                    }
+                }
+                existing.sub_project = merge_labels(existing.sub_project.take(), dep.sub_project);
+                for direct in dep.introduced_by {
+                    if !existing.introduced_by.contains(&direct) {
+                        existing.introduced_by.push(direct);
+                    }
+                }
+                if !has_known_license(existing) && dep_known {
+                    existing.license = dep.license;
+                    existing.is_restrictive = dep.is_restrictive;
+                    existing.osi_status = dep.osi_status;
+                }
+            }
+            None => {
+                index.insert(key, deduped.len());
+                deduped.push(dep);
+            }
+        }
+    }
+
+    if roots.len() <= 1 {
+        for dep in &mut deduped {
+            dep.found_in.clear();
+        }
+    } else {
+        let shared = deduped.iter().filter(|d| d.found_in.len() > 1).count();
+        log(
+            LogLevel::Info,
+            &format!(
+                "{shared} dependencies are shared across {} project roots",
+                roots.len()
+            ),
+        );
+    }
+
+    deduped
+}
+
+/// Packages are the same when name and version match within one package namespace: a
+/// `requests` from npm isn't the one from PyPI. C and C++ roots draw on the same native
+/// libraries, so they share one.
+fn dedupe_key(dep: &LicenseInfo) -> (Option<String>, String, String) {
+    let namespace = dep.ecosystem.as_deref().map(|ecosystem| match ecosystem {
+        "cpp" => "c".to_string(),
+        other => other.to_string(),
+    });
+    (namespace, dep.name.clone(), dep.version.clone())
+}
+
+/// Record which parts of a monorepo each dependency belongs to, so it can be checked against
+/// their `[project_licenses]` entry. Workspace members are matched to the configured
+/// directories, and their direct subdirectories, by the package name their manifest declares.
+/// A dependency of the root manifest, or one no configured member declares, belongs to the
+/// root (`.`).
+fn assign_project_dirs(
+    deps: &mut [LicenseInfo],
+    scan_root: &Path,
+    project_licenses: &std::collections::BTreeMap<String, String>,
+) {
+    if project_licenses.is_empty() {
+        return;
+    }
+
+    let mut member_dirs: HashMap<String, String> = HashMap::new();
+    for dir in project_licenses
+        .keys()
+        .map(|dir| crate::config::normalize_project_dir(dir))
+    {
+        let path = scan_root.join(&dir);
+        let mut candidates = vec![(path.clone(), dir.clone())];
+        if let Ok(entries) = std::fs::read_dir(&path) {
+            for entry in entries.flatten().filter(|entry| entry.path().is_dir()) {
+                let name = entry.file_name().to_string_lossy().to_string();
+                candidates.push((entry.path(), format!("{dir}/{name}")));
+            }
+        }
+        for (path, dir) in candidates {
+            for name in crate::manifest::package_names(&path) {
+                member_dirs.entry(name).or_insert_with(|| dir.clone());
+            }
+        }
+    }
+    log_debug("Workspace member directories", &member_dirs);
+
+    for dep in deps {
+        let mut dirs: Vec<String> = Vec::new();
+        let members = dep.sub_project.as_deref().unwrap_or_default();
+        for member in members.split(", ").filter(|member| !member.is_empty()) {
+            let dir = member_dirs
+                .get(member)
+                .cloned()
+                .unwrap_or_else(|| ".".to_string());
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+        if let Some(declared_in) = &dep.declared_in {
+            let dir = match declared_in.file.rsplit_once('/') {
+                Some((parent, _)) => parent.to_string(),
+                None => ".".to_string(),
+            };
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+        if dirs.is_empty() {
+            dirs.push(".".to_string());
+        }
+        dep.project_dirs = dirs;
+    }
+}
+
+/// Union two comma-separated workspace member labels, keeping first-seen order
+fn merge_labels(left: Option<String>, right: Option<String>) -> Option<String> {
+    match (left, right) {
+        (Some(left), Some(right)) => {
+            let mut members: Vec<&str> = left.split(", ").collect();
+            for member in right.split(", ") {
+                if !members.contains(&member) {
+                    members.push(member);
+                }
+            }
+            Some(members.join(", "))
+        }
+        (left, right) => left.or(right),
+    }
+}
+
+/// Record the manifest line declaring each direct dependency, so CI annotations can point
+/// at it. Paths are relative to the scanned directory.
+fn locate_declarations(
+    deps: &mut [LicenseInfo],
+    root: &ProjectRoot,
+    scan_root: &Path,
+    label: &str,
+) {
+    for manifest_name in crate::manifest::declaring_manifests(label) {
+        let manifest_path = root.path.join(manifest_name);
+        let Ok(content) = std::fs::read_to_string(&manifest_path) else {
+            continue;
+        };
+        let file = relative_path(&manifest_path, scan_root);
+
+        // The first manifest declaring a dependency is the one it points at
+        for dep in deps.iter_mut().filter(|dep| dep.declared_in.is_none()) {
+            if let Some(line) =
+                crate::manifest::declaration_line(manifest_name, &content, &dep.name)
+            {
+                dep.declared_in = Some(crate::manifest::ManifestLocation {
+                    file: file.clone(),
+                    line,
+                });
+            }
+        }
+    }
+}
+
+/// The project file that made `root` a project root, relative to the scanned directory: the
+/// first of the language's project files present, in the order the language lists them
+fn root_manifest(root: &ProjectRoot, scan_root: &Path) -> Option<String> {
+    let names: Vec<String> = match root.project_type {
+        Language::Rust(name) | Language::Node(name) | Language::Go(name) => {
+            vec![name.to_string()]
+        }
+        // .NET patterns are extensions: take the first matching file, by name
+        Language::DotNet(extensions) => {
+            let mut files: Vec<String> = std::fs::read_dir(&root.path)
+                .ok()?
+                .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                .collect();
+            files.sort();
+            extensions
+                .iter()
+                .filter_map(|extension| files.iter().find(|file| file.ends_with(extension)))
+                .cloned()
+                .collect()
+        }
+        Language::Android(names)
+        | Language::C(names)
+        | Language::Carthage(names)
+        | Language::Cpp(names)
+        | Language::Elixir(names)
+        | Language::Java(names)
+        | Language::Python(names)
+        | Language::Php(names)
+        | Language::R(names)
+        | Language::Ruby(names)
+        | Language::Wasm(names) => names.iter().map(|name| name.to_string()).collect(),
+    };
+    names
+        .iter()
+        .map(|name| root.path.join(name))
+        .find(|path| path.is_file())
+        .map(|path| relative_path(&path, scan_root))
+}
+
+/// `path` relative to the scanned directory, with forward slashes
+fn relative_path(path: &Path, scan_root: &Path) -> String {
+    path.strip_prefix(scan_root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Package page on the ecosystem's public registry, used when the analyzer found no
+/// repository URL. C/C++ have no central registry.
+fn registry_page(label: &str, name: &str) -> Option<String> {
+    match label {
+        "rust" => Some(format!("https://crates.io/crates/{name}")),
+        "node" => Some(format!("https://www.npmjs.com/package/{name}")),
+        "go" => Some(format!("https://pkg.go.dev/{name}")),
+        "python" => Some(format!("https://pypi.org/project/{name}/")),
+        "r" => Some(format!("https://cran.r-project.org/package={name}")),
+        "ruby" => Some(format!("https://rubygems.org/gems/{name}")),
+        "php" => Some(format!("https://packagist.org/packages/{name}")),
+        "elixir" => Some(format!("https://hex.pm/packages/{name}")),
+        "wasm" => Some(format!("https://wa.dev/{name}")),
+        "dotnet" => Some(format!("https://www.nuget.org/packages/{name}")),
+        "java" | "android" => {
+            let (group, artifact) = name.split_once(':')?;
+            Some(format!(
+                "https://central.sonatype.com/artifact/{group}/{artifact}"
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Short label for a project root, matching the `--language` filter names
+fn project_label(project_type: Language) -> &'static str {
+    match project_type {
+        Language::Android(_) => "android",
+        Language::C(_) => "c",
+        Language::Carthage(_) => "carthage",
+        Language::Cpp(_) => "cpp",
+        Language::DotNet(_) => "dotnet",
+        Language::Java(_) => "java",
+        Language::Rust(_) => "rust",
+        Language::Node(_) => "node",
+        Language::Go(_) => "go",
+        Language::Python(_) => "python",
+        Language::Php(_) => "php",
+        Language::Elixir(_) => "elixir",
+        Language::R(_) => "r",
+        Language::Ruby(_) => "ruby",
+        Language::Wasm(_) => "wasm",
+    }
+}
+
+/// Every ecosystem Feluda can scan
+fn all_languages() -> [Language; 15] {
+    [
+        Language::Android(&ANDROID_PATHS),
+        Language::C(&C_PATHS),
+        Language::Carthage(&CARTHAGE_PATHS),
+        Language::Cpp(&CPP_PATHS),
+        Language::DotNet(&DOTNET_PATHS),
+        Language::Java(&JAVA_PATHS),
+        Language::Rust("Cargo.toml"),
+        Language::Node("package.json"),
+        Language::Go("go.mod"),
+        Language::Python(&PYTHON_PATHS),
+        Language::Php(&PHP_PATHS),
+        Language::Elixir(&ELIXIR_PATHS),
+        Language::R(&R_PATHS),
+        Language::Ruby(&RUBY_PATHS),
+        Language::Wasm(&WASM_PATHS),
+    ]
+}
+
+/// Name of an ecosystem as users know it, with the build tools it covers
+fn ecosystem_name(project_type: Language) -> &'static str {
+    match project_type {
+        Language::Android(_) => "Android/Kotlin Multiplatform",
+        Language::C(_) => "C",
+        Language::Carthage(_) => "Carthage",
+        Language::Cpp(_) => "C++",
+        Language::DotNet(_) => ".NET",
+        Language::Java(_) => "Java/Maven/Gradle",
+        Language::Rust(_) => "Rust",
+        Language::Node(_) => "Node.js",
+        Language::Go(_) => "Go",
+        Language::Python(_) => "Python",
+        Language::Php(_) => "PHP/Composer",
+        Language::Elixir(_) => "Elixir",
+        Language::R(_) => "R",
+        Language::Ruby(_) => "Ruby",
+        Language::Wasm(_) => "WebAssembly (WIT)",
+    }
+}
+
+/// Hint listing every ecosystem, for a scan that found no project files
+fn supported_ecosystems_hint() -> String {
+    let names: Vec<&str> = all_languages().into_iter().map(ecosystem_name).collect();
+    format!("Feluda supports: {}", names.join(", "))
+}
+
+/// Check if a name is accepted by `--language` / `--skip` for any supported ecosystem
+fn is_known_language(language: &str) -> bool {
+    all_languages()
+        .into_iter()
+        .any(|project_type| matches_language(project_type, language))
+}
+
+/// Indices of the `roots` that `changed` dependency files (relative to `scan_root`) belong to.
+/// Each file goes to the nearest root of its ecosystem: the one whose directory is the deepest
+/// holding the file. A file no root holds, or whose ecosystem has no root, changes nothing.
+fn changed_roots(roots: &[ProjectRoot], scan_root: &Path, changed: &[PathBuf]) -> HashSet<usize> {
+    let mut owners = HashSet::new();
+    for file in changed {
+        let Some(language) = file
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(crate::manifest::language_for_file)
+        else {
+            continue;
+        };
+        let path = scan_root.join(file);
+        let owner = roots
+            .iter()
+            .enumerate()
+            .filter(|(_, root)| {
+                std::mem::discriminant(&root.project_type) == std::mem::discriminant(&language)
+                    // Android builds are driven by Gradle build files and lockfiles too
+                    || matches!(
+                        (language, root.project_type),
+                        (Language::Java(_), Language::Android(_))
+                    )
+            })
+            .filter(|(_, root)| path.starts_with(&root.path))
+            .max_by_key(|(_, root)| root.path.components().count());
+        if let Some((index, _)) = owner {
+            owners.insert(index);
+        }
+    }
+    owners
+}
+
+/// A project root found under the scan path, as `--dry-run` and `feluda detect` report it
+#[derive(Debug)]
+pub struct PlannedRoot {
+    /// `--language` name of the ecosystem
+    pub ecosystem: &'static str,
+    /// The ecosystem's manifests and lockfiles in the root, relative to it
+    pub files: Vec<String>,
+    /// The files among them the ecosystem's analyzer starts from
+    pub reads: Vec<String>,
+    /// Why the root would be left out of the scan, if it would be
+    pub skipped: Option<String>,
+}
+
+/// The project roots a scan of `root_path` would analyze, without parsing any of them
+pub fn plan_roots(
+    root_path: impl AsRef<Path>,
+    language: Option<&str>,
+    skip: &[String],
+) -> FeludaResult<Vec<PlannedRoot>> {
+    let root = root_path.as_ref();
+    let mut names: Vec<String> = std::fs::read_dir(root)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_ok_and(|ft| ft.is_file()))
+                .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+
+    Ok(find_project_roots(root)?
+        .into_iter()
+        .map(|project| {
+            let files = names
+                .iter()
+                .filter(|name| {
+                    crate::manifest::language_for_file(name).is_some_and(|language| {
+                        std::mem::discriminant(&language)
+                            == std::mem::discriminant(&project.project_type)
+                    })
+                })
+                .cloned()
+                .collect();
+            let skipped =
+                if language.is_some_and(|lang| !matches_language(project.project_type, lang)) {
+                    Some("--language filter".to_string())
+                } else if skip
+                    .iter()
+                    .any(|skipped| matches_language(project.project_type, skipped))
+                {
+                    Some("skip list".to_string())
+                } else {
+                    None
+                };
+            PlannedRoot {
+                ecosystem: project_label(project.project_type),
+                files,
+                reads: analyzer_inputs(&project),
+                skipped,
+            }
+        })
+        .collect())
+}
+
+/// The files [`parse_dependencies`] would hand the root's analyzer, picked the same way
+fn analyzer_inputs(project: &ProjectRoot) -> Vec<String> {
+    let path = &project.path;
+    let first = |found: Option<String>| found.into_iter().collect();
+    match project.project_type {
+        Language::Android(_) => first(check_which_android_file_exists(path)),
+        Language::C(_) => first(check_which_c_file_exists(path)),
+        Language::Carthage(_) => first(check_which_carthage_file_exists(path)),
+        Language::Cpp(_) => first(check_which_cpp_file_exists(path)),
+        Language::DotNet(_) => first(check_which_dotnet_file_exists(path)),
+        Language::Java(_) => first(check_which_java_file_exists(path)),
+        Language::Php(_) => first(check_which_php_file_exists(path)),
+        Language::Elixir(_) => first(check_which_elixir_file_exists(path)),
+        Language::R(_) => first(check_which_r_file_exists(path)),
+        Language::Ruby(_) => first(check_which_ruby_file_exists(path)),
+        Language::Rust(_) => vec!["Cargo.toml".to_string()],
+        Language::Node(_) => std::iter::once("package.json")
+            .chain(crate::languages::node::node_lockfile_name(path))
+            .map(str::to_string)
+            .collect(),
+        Language::Go(_) if path.join("go.work").exists() => vec!["go.work".to_string()],
+        Language::Go(_) => vec!["go.mod".to_string()],
+        Language::Python(_) => find_python_files(path),
+        Language::Wasm(_) => {
+            let mut inputs: Vec<String> = WASM_PATHS
+                .iter()
+                .filter(|name| path.join(name).exists())
+                .map(|name| name.to_string())
+                .collect();
+            if has_wit_packages(path) {
+                inputs.push("wit/".to_string());
+            }
+            inputs
+        }
+    }
+}
+
 /// Check if a project type matches the given language filter
 fn matches_language(project_type: Language, language: &str) -> bool {
     matches!(
         (project_type, language.to_lowercase().as_str()),
-        (Language::C(_), "c")
+        (Language::Android(_), "android" | "kmp")
+            | (Language::C(_), "c")
+            | (Language::Carthage(_), "carthage" | "ios")
             | (Language::Cpp(_), "cpp" | "c++")
             | (
                 Language::DotNet(_),
@@ -429,6 +1091,9 @@ fn matches_language(project_type: Language, language: &str) -> bool {
             | (Language::Python(_), "python")
             | (Language::R(_), "r")
             | (Language::Ruby(_), "ruby")
+            | (Language::Php(_), "php" | "composer")
+            | (Language::Elixir(_), "elixir" | "hex" | "mix")
+            | (Language::Wasm(_), "wasm" | "wit" | "component" | "wkg")
     )
 }
 
@@ -440,9 +1105,53 @@ fn parse_dependencies(
 ) -> FeludaResult<Vec<LicenseInfo>> {
     let project_path = &root.path;
     let project_type = root.project_type;
+    let mut lockfile_error = None;
 
     let licenses = cli::with_spinner(&format!("🔎: {}", project_path.display()), |indicator| {
         match project_type {
+            Language::Android(_) => match check_which_android_file_exists(project_path) {
+                Some(android_file) => {
+                    let project_path = Path::new(project_path).join(&android_file);
+                    log(
+                        LogLevel::Info,
+                        &format!("Parsing Android project: {}", project_path.display()),
+                    );
+
+                    indicator.update_progress(&format!("analyzing {android_file}"));
+
+                    match project_path.to_str() {
+                        Some(path_str) => {
+                            let deps = analyze_android_licenses(path_str, config);
+                            indicator
+                                .update_progress(&format!("found {} dependencies", deps.len()));
+                            deps
+                        }
+                        None => {
+                            log(LogLevel::Error, "Failed to convert Android path to string");
+                            Vec::new()
+                        }
+                    }
+                }
+                None => {
+                    log(LogLevel::Error, "Android project file not found");
+                    Vec::new()
+                }
+            },
+            Language::Wasm(_) => {
+                log(
+                    LogLevel::Info,
+                    &format!(
+                        "Parsing WebAssembly component project: {}",
+                        project_path.display()
+                    ),
+                );
+
+                indicator.update_progress("analyzing WIT packages");
+
+                let deps = analyze_wasm_licenses(project_path, config);
+                indicator.update_progress(&format!("found {} dependencies", deps.len()));
+                deps
+            }
             Language::Rust(_) => {
                 let project_path = Path::new(project_path).join("Cargo.toml");
                 log(
@@ -452,10 +1161,26 @@ fn parse_dependencies(
 
                 indicator.update_progress("analyzing Cargo.toml");
 
-                match MetadataCommand::new()
-                    .manifest_path(Path::new(&project_path))
-                    .exec()
-                {
+                let mut command = MetadataCommand::new();
+                command.manifest_path(Path::new(&project_path));
+                let mut options: Vec<String> = config
+                    .cargo_lock
+                    .cargo_flag()
+                    .map(str::to_string)
+                    .into_iter()
+                    .collect();
+                if crate::cancel::is_offline() && config.cargo_lock != CargoLockMode::Frozen {
+                    options.push("--offline".to_string());
+                }
+                command.other_options(options);
+                if config.cargo.no_default_features {
+                    command.features(CargoOpt::NoDefaultFeatures);
+                }
+                if !config.cargo.features.is_empty() {
+                    command.features(CargoOpt::SomeFeatures(config.cargo.features.clone()));
+                }
+
+                match command.exec() {
                     Ok(metadata) => {
                         log(
                             LogLevel::Info,
@@ -472,10 +1197,18 @@ fn parse_dependencies(
                         analyze_rust_licenses_with_metadata(metadata, config, no_local)
                     }
                     Err(err) => {
-                        log(
-                            LogLevel::Error,
-                            &format!("Failed to fetch cargo metadata: {err}"),
-                        );
+                        match lockfile_failure(config.cargo_lock, &err.to_string()) {
+                            Some(reason) => {
+                                lockfile_error = Some(FeludaError::Lockfile(format!(
+                                    "{}: {reason}",
+                                    project_path.display()
+                                )));
+                            }
+                            None => record_parse_warning(
+                                project_path.display().to_string(),
+                                format!("cargo metadata failed: {err}"),
+                            ),
+                        }
                         Vec::new()
                     }
                 }
@@ -537,8 +1270,16 @@ fn parse_dependencies(
                     }
                 }
             }
-            Language::Python(_) => match check_which_python_file_exists(project_path) {
-                Some(python_package_file) => {
+            Language::Python(_) => {
+                let python_package_files = find_python_files(project_path);
+                if python_package_files.is_empty() {
+                    log(LogLevel::Error, "Python package file not found");
+                }
+
+                // A directory often carries both a requirements file and a lockfile. Analyze
+                // each of them; duplicates are merged below so nothing is counted twice.
+                let mut deps = Vec::new();
+                for python_package_file in python_package_files {
                     let project_path = Path::new(project_path).join(&python_package_file);
                     log(
                         LogLevel::Info,
@@ -548,23 +1289,15 @@ fn parse_dependencies(
                     indicator.update_progress(&format!("analyzing {python_package_file}"));
 
                     match project_path.to_str() {
-                        Some(path_str) => {
-                            let deps = analyze_python_licenses(path_str, config);
-                            indicator
-                                .update_progress(&format!("found {} dependencies", deps.len()));
-                            deps
-                        }
+                        Some(path_str) => deps.extend(analyze_python_licenses(path_str, config)),
                         None => {
                             log(LogLevel::Error, "Failed to convert Python path to string");
-                            Vec::new()
                         }
                     }
                 }
-                None => {
-                    log(LogLevel::Error, "Python package file not found");
-                    Vec::new()
-                }
-            },
+                indicator.update_progress(&format!("found {} dependencies", deps.len()));
+                deps
+            }
             Language::C(_) => match check_which_c_file_exists(project_path) {
                 Some(c_build_file) => {
                     let project_path = Path::new(project_path).join(&c_build_file);
@@ -733,10 +1466,203 @@ fn parse_dependencies(
                     Vec::new()
                 }
             },
+            Language::Php(_) => match check_which_php_file_exists(project_path) {
+                Some(php_file) => {
+                    let project_path = Path::new(project_path).join(&php_file);
+                    log(
+                        LogLevel::Info,
+                        &format!("Parsing PHP project: {}", project_path.display()),
+                    );
+
+                    indicator.update_progress(&format!("analyzing {php_file}"));
+
+                    match project_path.to_str() {
+                        Some(path_str) => {
+                            let deps = analyze_php_licenses(path_str, config);
+                            indicator
+                                .update_progress(&format!("found {} dependencies", deps.len()));
+                            deps
+                        }
+                        None => {
+                            log(LogLevel::Error, "Failed to convert PHP path to string");
+                            Vec::new()
+                        }
+                    }
+                }
+                None => {
+                    log(LogLevel::Error, "PHP project file not found");
+                    Vec::new()
+                }
+            },
+            Language::Carthage(_) => match check_which_carthage_file_exists(project_path) {
+                Some(carthage_file) => {
+                    let project_path = Path::new(project_path).join(&carthage_file);
+                    log(
+                        LogLevel::Info,
+                        &format!("Parsing Carthage project: {}", project_path.display()),
+                    );
+
+                    indicator.update_progress(&format!("analyzing {carthage_file}"));
+
+                    match project_path.to_str() {
+                        Some(path_str) => {
+                            let deps = analyze_carthage_licenses(path_str, config);
+                            indicator
+                                .update_progress(&format!("found {} dependencies", deps.len()));
+                            deps
+                        }
+                        None => {
+                            log(LogLevel::Error, "Failed to convert Carthage path to string");
+                            Vec::new()
+                        }
+                    }
+                }
+                None => {
+                    log(LogLevel::Error, "Carthage project file not found");
+                    Vec::new()
+                }
+            },
+            Language::Elixir(_) => match check_which_elixir_file_exists(project_path) {
+                Some(elixir_file) => {
+                    let project_path = Path::new(project_path).join(&elixir_file);
+                    log(
+                        LogLevel::Info,
+                        &format!("Parsing Elixir project: {}", project_path.display()),
+                    );
+
+                    indicator.update_progress(&format!("analyzing {elixir_file}"));
+
+                    match project_path.to_str() {
+                        Some(path_str) => {
+                            let deps = analyze_elixir_licenses(path_str, config);
+                            indicator
+                                .update_progress(&format!("found {} dependencies", deps.len()));
+                            deps
+                        }
+                        None => {
+                            log(LogLevel::Error, "Failed to convert Elixir path to string");
+                            Vec::new()
+                        }
+                    }
+                }
+                None => {
+                    log(LogLevel::Error, "Elixir project file not found");
+                    Vec::new()
+                }
+            },
         }
     });
 
-    Ok(licenses)
+    if let Some(err) = lockfile_error {
+        return Err(err);
+    }
+    Ok(merge_manifest_dependencies(licenses))
+}
+
+/// Explain a `cargo metadata` failure caused by `--locked`/`--frozen`, if it was one.
+///
+/// Cargo names the flag when it refuses to touch the lockfile or the network, e.g.
+/// "the lock file ... needs to be updated but --locked was passed to prevent this".
+fn lockfile_failure(mode: CargoLockMode, error: &str) -> Option<String> {
+    let flag = mode.cargo_flag()?;
+    if !error.contains(flag) {
+        return None;
+    }
+    Some(if error.contains("lock file") {
+        format!(
+            "Cargo.lock is missing or out of date and {flag} prevents updating it; \
+             run `cargo generate-lockfile` and commit Cargo.lock"
+        )
+    } else {
+        format!("cargo needs network access, which {flag} prevents; run `cargo fetch` first")
+    })
+}
+
+/// Merge duplicate dependencies reported by manifests that share a directory.
+///
+/// A requirements file and its lockfile (or a manifest and its lock) describe the same packages.
+/// Rows are keyed on ecosystem, normalized name and version, so two resolved versions of a
+/// package (`syn` 1.x and 2.x) stay apart; a range or `latest` folds into the row of the same
+/// name, taking its pinned version. A resolved license is preferred over an unknown one.
+fn merge_manifest_dependencies(deps: Vec<LicenseInfo>) -> Vec<LicenseInfo> {
+    type NameKey = (Option<String>, String);
+
+    let mut merged: Vec<LicenseInfo> = Vec::with_capacity(deps.len());
+    let mut by_version: HashMap<(NameKey, String), usize> = HashMap::new();
+    // First row of each name, which unpinned requirements fold into
+    let mut by_name: HashMap<NameKey, usize> = HashMap::new();
+    // Rows still carrying an unpinned version, which the first pinned one fills in
+    let mut unpinned: HashMap<NameKey, usize> = HashMap::new();
+
+    for dep in deps {
+        let name_key = (dep.ecosystem.clone(), normalize_dependency_name(&dep.name));
+        let pinned = version_specificity(&dep.version) == 2;
+        let existing = if pinned {
+            by_version
+                .get(&(name_key.clone(), dep.version.clone()))
+                .copied()
+                .or_else(|| unpinned.remove(&name_key))
+        } else {
+            by_name.get(&name_key).copied()
+        };
+
+        let Some(i) = existing else {
+            let i = merged.len();
+            if pinned {
+                by_version.insert((name_key.clone(), dep.version.clone()), i);
+            } else {
+                unpinned.insert(name_key.clone(), i);
+            }
+            by_name.entry(name_key).or_insert(i);
+            merged.push(dep);
+            continue;
+        };
+
+        let existing = &mut merged[i];
+        log(
+            LogLevel::Info,
+            &format!(
+                "Merging duplicate dependency {} ({} / {})",
+                dep.name, existing.version, dep.version
+            ),
+        );
+        if version_specificity(&dep.version) > version_specificity(&existing.version) {
+            existing.version = dep.version.clone();
+            if pinned {
+                by_version.insert((name_key, dep.version.clone()), i);
+            }
+        }
+        if !has_known_license(existing) && has_known_license(&dep) {
+            existing.license = dep.license;
+            existing.is_restrictive = dep.is_restrictive;
+            existing.osi_status = dep.osi_status;
+        }
+    }
+
+    merged
+}
+
+/// Normalize a package name the way PyPI does, which is harmless for other ecosystems
+fn normalize_dependency_name(name: &str) -> String {
+    name.trim().to_lowercase().replace(['_', '.'], "-")
+}
+
+/// Rank a version string: pinned versions beat ranges, ranges beat "latest" or empty
+fn version_specificity(version: &str) -> u8 {
+    let version = version.trim();
+    if version.is_empty() || version == "latest" || version == "*" {
+        0
+    } else if version.contains(['^', '~', '<', '>', '=', '*', ',', ' ']) {
+        1
+    } else {
+        2
+    }
+}
+
+fn has_known_license(dep: &LicenseInfo) -> bool {
+    dep.license
+        .as_deref()
+        .is_some_and(|license| !license.is_empty() && !license.starts_with("Unknown"))
 }
 
 #[cfg(test)]
@@ -780,17 +1706,150 @@ mod tests {
     }
 
     #[test]
-    fn test_check_which_python_file_exists() {
+    fn test_changed_roots() {
+        let scan_root = Path::new("/repo");
+        let root = |project_type| ProjectRoot {
+            path: scan_root.to_path_buf(),
+            project_type,
+        };
+        let roots = vec![
+            root(Language::Rust("Cargo.toml")),
+            root(Language::Node("package.json")),
+            root(Language::Python(&PYTHON_PATHS)),
+            root(Language::Android(&ANDROID_PATHS)),
+            ProjectRoot {
+                path: scan_root.join("web"),
+                project_type: Language::Node("package.json"),
+            },
+        ];
+
+        let changed = changed_roots(
+            &roots,
+            scan_root,
+            &[
+                PathBuf::from("Cargo.lock"),
+                PathBuf::from("web/package.json"),
+            ],
+        );
+        // web/package.json belongs to the web root, not the one at the top
+        assert_eq!(changed, HashSet::from([0, 4]));
+
+        let changed = changed_roots(
+            &roots,
+            scan_root,
+            &[
+                PathBuf::from("docs/package.json"),
+                PathBuf::from("app/build.gradle"),
+                PathBuf::from("go.mod"),
+            ],
+        );
+        assert_eq!(changed, HashSet::from([1, 3]));
+
+        assert!(changed_roots(&roots, scan_root, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_is_known_language() {
+        assert!(is_known_language("rust"));
+        assert!(is_known_language("Python"));
+        assert!(is_known_language("c#"));
+        assert!(!is_known_language("cobol"));
+    }
+
+    #[test]
+    fn test_supported_ecosystems_hint() {
+        let hint = supported_ecosystems_hint();
+        for name in [
+            "Rust",
+            "PHP/Composer",
+            "Ruby",
+            "Elixir",
+            "Android",
+            "WebAssembly",
+            "Carthage",
+        ] {
+            assert!(hint.contains(name), "{name} missing from {hint}");
+        }
+    }
+
+    #[test]
+    fn test_plan_roots() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("Cargo.toml"), "[package]").unwrap();
+        std::fs::write(temp_dir.path().join("Cargo.lock"), "").unwrap();
+        std::fs::write(temp_dir.path().join("package.json"), "{}").unwrap();
+        std::fs::write(temp_dir.path().join("yarn.lock"), "").unwrap();
+
+        let mut roots = plan_roots(temp_dir.path(), None, &["node".to_string()]).unwrap();
+        roots.sort_by_key(|root| root.ecosystem);
+        assert_eq!(roots.len(), 2);
+        assert_eq!(roots[0].ecosystem, "node");
+        assert_eq!(roots[0].files, vec!["package.json", "yarn.lock"]);
+        assert_eq!(roots[0].skipped.as_deref(), Some("skip list"));
+        assert_eq!(roots[1].ecosystem, "rust");
+        assert_eq!(roots[1].files, vec!["Cargo.lock", "Cargo.toml"]);
+        assert_eq!(roots[1].skipped, None);
+
+        let roots = plan_roots(temp_dir.path(), Some("node"), &[]).unwrap();
+        let rust = roots.iter().find(|root| root.ecosystem == "rust").unwrap();
+        assert_eq!(rust.skipped.as_deref(), Some("--language filter"));
+    }
+
+    #[test]
+    fn test_registry_page() {
+        assert_eq!(
+            registry_page("node", "@babel/core").as_deref(),
+            Some("https://www.npmjs.com/package/@babel/core")
+        );
+        assert_eq!(
+            registry_page("java", "org.slf4j:slf4j-api").as_deref(),
+            Some("https://central.sonatype.com/artifact/org.slf4j/slf4j-api")
+        );
+        assert_eq!(registry_page("java", "slf4j-api"), None);
+        assert_eq!(registry_page("cpp", "fmt"), None);
+    }
+
+    #[test]
+    fn test_lockfile_failure() {
+        let stale = "error: the lock file /repo/Cargo.lock needs to be updated but --locked \
+                     was passed to prevent this";
+        assert!(lockfile_failure(CargoLockMode::Locked, stale)
+            .unwrap()
+            .contains("Cargo.lock is missing or out of date"));
+        assert_eq!(lockfile_failure(CargoLockMode::Update, stale), None);
+
+        let offline = "error: attempting to make an HTTP request, but --frozen was specified";
+        assert!(lockfile_failure(CargoLockMode::Frozen, offline)
+            .unwrap()
+            .contains("network access"));
+
+        let broken = "error: failed to parse manifest at `/repo/Cargo.toml`";
+        assert_eq!(lockfile_failure(CargoLockMode::Locked, broken), None);
+    }
+
+    #[test]
+    fn test_parse_root_with_config_skips_ecosystems() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("requirements.txt"), "requests==2.31.0").unwrap();
+
+        let config = crate::config::FeludaConfig {
+            skip: vec!["python".to_string()],
+            ..Default::default()
+        };
+        let result = parse_root_with_config(temp_dir.path(), None, &config, true, None).unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_find_python_files() {
         let temp_dir = tempfile::TempDir::new().unwrap();
 
         // Test when no Python files exist
-        let result = check_which_python_file_exists(temp_dir.path());
-        assert_eq!(result, None);
+        assert!(find_python_files(temp_dir.path()).is_empty());
 
         // Test when requirements.txt exists
         std::fs::write(temp_dir.path().join("requirements.txt"), "requests==2.28.1").unwrap();
-        let result = check_which_python_file_exists(temp_dir.path());
-        assert_eq!(result, Some("requirements.txt".to_string()));
+        assert_eq!(find_python_files(temp_dir.path()), vec!["requirements.txt"]);
 
         // Test when multiple Python files exist
         std::fs::write(
@@ -799,8 +1858,185 @@ mod tests {
         )
         .unwrap();
         std::fs::write(temp_dir.path().join("Pipfile.lock"), "{}").unwrap();
-        let result = check_which_python_file_exists(temp_dir.path());
-        assert_eq!(result, Some("requirements.txt".to_string()));
+        assert_eq!(
+            find_python_files(temp_dir.path()),
+            vec!["requirements.txt", "Pipfile.lock", "pyproject.toml"]
+        );
+    }
+
+    #[test]
+    fn test_locate_declarations() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("web");
+        std::fs::create_dir(&project_dir).unwrap();
+        std::fs::write(
+            project_dir.join("package.json"),
+            "{\n  \"name\": \"web\",\n  \"dependencies\": {\n    \"express\": \"^4.18.0\"\n  }\n}\n",
+        )
+        .unwrap();
+
+        let root = ProjectRoot {
+            path: project_dir,
+            project_type: Language::Node("package.json"),
+        };
+        let mut deps = vec![
+            LicenseInfo::fixture("express", "4.18.2", "MIT"),
+            LicenseInfo::fixture("body-parser", "1.20.1", "MIT"),
+        ];
+        locate_declarations(&mut deps, &root, temp_dir.path(), "node");
+
+        assert_eq!(
+            deps[0].declared_in,
+            Some(crate::manifest::ManifestLocation {
+                file: "web/package.json".to_string(),
+                line: 4,
+            })
+        );
+        assert_eq!(deps[1].declared_in, None);
+    }
+
+    fn rooted(name: &str, version: &str, root: &str, member: Option<&str>) -> LicenseInfo {
+        LicenseInfo {
+            found_in: vec![root.to_string()],
+            sub_project: member.map(String::from),
+            ..LicenseInfo::fixture(name, version, "MIT")
+        }
+    }
+
+    #[test]
+    fn test_dedupe_across_roots_records_provenance() {
+        let deduped = dedupe_across_roots(vec![
+            rooted("zlib", "1.3", "c", None),
+            rooted("openssl", "3.0.0", "c", None),
+            rooted("zlib", "1.3", "cpp", None),
+            rooted("zlib", "1.2", "cpp", None),
+        ]);
+
+        assert_eq!(deduped.len(), 3);
+        assert_eq!(deduped[0].found_in, vec!["c", "cpp"]);
+        assert_eq!(deduped[1].found_in, vec!["c"]);
+        assert_eq!(deduped[2].version, "1.2");
+    }
+
+    #[test]
+    fn test_dedupe_across_roots_keeps_ecosystems_apart() {
+        let in_ecosystem = |root: &str| LicenseInfo {
+            ecosystem: Some(root.to_string()),
+            ..rooted("requests", "2.31.0", root, None)
+        };
+        let deduped = dedupe_across_roots(vec![
+            in_ecosystem("node"),
+            in_ecosystem("python"),
+            in_ecosystem("c"),
+            in_ecosystem("cpp"),
+        ]);
+
+        assert_eq!(deduped.len(), 3);
+        assert_eq!(deduped[0].found_in, vec!["node"]);
+        assert_eq!(deduped[1].found_in, vec!["python"]);
+        assert_eq!(deduped[2].found_in, vec!["c", "cpp"]);
+    }
+
+    #[test]
+    fn test_dedupe_across_roots_single_root_keeps_report_shape() {
+        let deduped = dedupe_across_roots(vec![
+            rooted("serde", "1.0.0", "rust", Some("core")),
+            rooted("serde", "1.0.0", "rust", Some("cli, core")),
+        ]);
+
+        assert_eq!(deduped.len(), 1);
+        assert!(deduped[0].found_in.is_empty());
+        assert_eq!(deduped[0].sub_project.as_deref(), Some("core, cli"));
+    }
+
+    #[test]
+    fn test_assign_project_dirs() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for (dir, name) in [("services/api", "api"), ("packages/sdk", "@acme/sdk")] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+            std::fs::write(
+                root.join(dir).join("package.json"),
+                format!("{{ \"name\": \"{name}\" }}"),
+            )
+            .unwrap();
+        }
+        let project_licenses = std::collections::BTreeMap::from([
+            ("services/api".to_string(), "AGPL-3.0".to_string()),
+            ("packages".to_string(), "Apache-2.0".to_string()),
+        ]);
+
+        let mut deps = vec![
+            rooted("express", "4.18.2", "node", Some("api")),
+            rooted("lodash", "4.17.21", "node", Some("@acme/sdk, monorepo")),
+            rooted("debug", "4.3.4", "node", None),
+            LicenseInfo {
+                declared_in: Some(crate::manifest::ManifestLocation {
+                    file: "package.json".to_string(),
+                    line: 3,
+                }),
+                ..rooted("typescript", "5.4.0", "node", Some("monorepo"))
+            },
+        ];
+        assign_project_dirs(&mut deps, root, &project_licenses);
+
+        assert_eq!(deps[0].project_dirs, vec!["services/api"]);
+        assert_eq!(deps[1].project_dirs, vec!["packages/sdk", "."]);
+        assert_eq!(deps[2].project_dirs, vec!["."]);
+        assert_eq!(deps[3].project_dirs, vec!["."]);
+
+        let mut deps = vec![rooted("express", "4.18.2", "node", Some("api"))];
+        assign_project_dirs(&mut deps, root, &std::collections::BTreeMap::new());
+        assert!(deps[0].project_dirs.is_empty());
+    }
+
+    #[test]
+    fn test_merge_manifest_dependencies_prefers_pinned_version() {
+        let merged = merge_manifest_dependencies(vec![
+            LicenseInfo::fixture("requests", "latest", "Apache-2.0"),
+            LicenseInfo::fixture("Flask", ">=2.0", "BSD-3-Clause"),
+            LicenseInfo::fixture("requests", "2.31.0", "Apache-2.0"),
+            LicenseInfo::fixture("flask", "2.3.3", "BSD-3-Clause"),
+        ]);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].name, "requests");
+        assert_eq!(merged[0].version, "2.31.0");
+        assert_eq!(merged[1].name, "Flask");
+        assert_eq!(merged[1].version, "2.3.3");
+    }
+
+    #[test]
+    fn test_merge_manifest_dependencies_keeps_distinct_versions() {
+        let merged = merge_manifest_dependencies(vec![
+            LicenseInfo::fixture("syn", "1.0.109", "MIT OR Apache-2.0"),
+            LicenseInfo::fixture("syn", "2.0.48", "MIT OR Apache-2.0"),
+            LicenseInfo::fixture("syn", "1.0.109", "MIT OR Apache-2.0"),
+            LicenseInfo::fixture("syn", "^2.0", None),
+        ]);
+
+        let versions: Vec<&str> = merged.iter().map(|d| d.version.as_str()).collect();
+        assert_eq!(versions, vec!["1.0.109", "2.0.48"]);
+
+        // The same name in another ecosystem is another package
+        let mut npm = LicenseInfo::fixture("debug", "4.3.4", "MIT");
+        npm.ecosystem = Some("node".to_string());
+        let mut pypi = LicenseInfo::fixture("debug", "4.3.4", "Unknown");
+        pypi.ecosystem = Some("python".to_string());
+        assert_eq!(merge_manifest_dependencies(vec![npm, pypi]).len(), 2);
+    }
+
+    #[test]
+    fn test_merge_manifest_dependencies_normalizes_names_and_fills_license() {
+        let merged = merge_manifest_dependencies(vec![
+            LicenseInfo::fixture("typing_extensions", "4.8.0", "Unknown"),
+            LicenseInfo::fixture("typing-extensions", "4.8.0", "PSF-2.0"),
+            LicenseInfo::fixture("zope.interface", "6.0", None),
+        ]);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].license, Some("PSF-2.0".to_string()));
+        assert_eq!(merged[1].name, "zope.interface");
     }
 
     #[test]
@@ -824,6 +2060,81 @@ mod tests {
         assert_eq!(result[0].path, root_path);
     }
 
+    #[test]
+    fn test_root_manifest() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let scan_root = temp_dir.path();
+        let root = |project_type| ProjectRoot {
+            path: scan_root.join("api"),
+            project_type,
+        };
+        std::fs::create_dir_all(scan_root.join("api")).unwrap();
+        std::fs::write(scan_root.join("api/pyproject.toml"), "[project]").unwrap();
+        std::fs::write(scan_root.join("api/requirements.txt"), "requests").unwrap();
+        std::fs::write(scan_root.join("api/Web.csproj"), "<Project />").unwrap();
+        std::fs::write(scan_root.join("api/App.sln"), "").unwrap();
+
+        // The language's own order decides, not the directory listing
+        assert_eq!(
+            root_manifest(&root(Language::Python(&PYTHON_PATHS)), scan_root).as_deref(),
+            Some("api/requirements.txt")
+        );
+        assert_eq!(
+            root_manifest(&root(Language::DotNet(&DOTNET_PATHS)), scan_root).as_deref(),
+            Some("api/App.sln")
+        );
+        assert_eq!(
+            root_manifest(&root(Language::Rust("Cargo.toml")), scan_root),
+            None
+        );
+    }
+
+    #[test]
+    fn test_find_project_roots_android_supersedes_gradle() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root_path = temp_dir.path();
+        std::fs::write(root_path.join("settings.gradle.kts"), "include(\":app\")").unwrap();
+        std::fs::write(root_path.join("build.gradle.kts"), "plugins {}").unwrap();
+
+        // Without a version catalog or Android plugin it's a plain Gradle build
+        let result = find_project_roots(root_path).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].project_type, Language::Java(&JAVA_PATHS));
+
+        std::fs::create_dir_all(root_path.join("gradle")).unwrap();
+        std::fs::write(root_path.join("gradle/libs.versions.toml"), "[libraries]").unwrap();
+        let result = find_project_roots(root_path).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].project_type, Language::Android(&ANDROID_PATHS));
+    }
+
+    #[test]
+    fn test_find_project_roots_wit_directory() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root_path = temp_dir.path();
+        std::fs::write(
+            root_path.join("Cargo.toml"),
+            "[package]\nname = \"component\"",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root_path.join("wit")).unwrap();
+        std::fs::write(
+            root_path.join("wit/world.wit"),
+            "package acme:component;\n\nworld app {\n    import wasi:http/outgoing-handler@0.2.0;\n}\n",
+        )
+        .unwrap();
+
+        let result = find_project_roots(root_path).unwrap();
+        assert_eq!(result.len(), 2);
+        assert!(result
+            .iter()
+            .any(|root| root.project_type == Language::Wasm(&WASM_PATHS)));
+
+        // wkg.toml already makes the root; the wit/ directory doesn't add a second one
+        std::fs::write(root_path.join("wkg.toml"), "").unwrap();
+        assert_eq!(find_project_roots(root_path).unwrap().len(), 2);
+    }
+
     #[test]
     fn test_parse_root_with_language_filter() {
         let temp_dir = tempfile::TempDir::new().unwrap();
@@ -835,35 +2146,35 @@ mod tests {
         std::fs::write(root_path.join("requirements.txt"), "# No dependencies").unwrap();
 
         // Test filtering by node
-        let result = parse_root(root_path, Some("node"), false, false);
+        let result = parse_root(root_path, Some("node"), &[], false, false, None, false);
         assert!(result.is_ok());
 
         // Test filtering by go
-        let result = parse_root(root_path, Some("go"), false, false);
+        let result = parse_root(root_path, Some("go"), &[], false, false, None, false);
         assert!(result.is_ok());
 
         // Test filtering by python
-        let result = parse_root(root_path, Some("python"), false, false);
+        let result = parse_root(root_path, Some("python"), &[], false, false, None, false);
         assert!(result.is_ok());
 
         // Test filtering by non-existent language
-        let result = parse_root(root_path, Some("java"), false, false);
+        let result = parse_root(root_path, Some("java"), &[], false, false, None, false);
         assert!(result.is_ok());
         let licenses = result.unwrap();
         assert!(licenses.is_empty());
 
         // Test case-insensitive filtering
-        let result = parse_root(root_path, Some("NODE"), false, false);
+        let result = parse_root(root_path, Some("NODE"), &[], false, false, None, false);
         assert!(result.is_ok());
 
-        let result = parse_root(root_path, Some("Python"), false, false);
+        let result = parse_root(root_path, Some("Python"), &[], false, false, None, false);
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_parse_root_no_projects() {
         let temp_dir = tempfile::TempDir::new().unwrap();
-        let result = parse_root(temp_dir.path(), None, false, false).unwrap();
+        let result = parse_root(temp_dir.path(), None, &[], false, false, None, false).unwrap();
         assert!(result.is_empty());
     }
 
@@ -886,7 +2197,7 @@ mod tests {
         std::fs::write(root_path.join("go.mod"), "module test\n\ngo 1.19").unwrap();
         std::fs::write(root_path.join("requirements.txt"), "# No dependencies").unwrap();
 
-        let result = parse_root(root_path, None, false, false);
+        let result = parse_root(root_path, None, &[], false, false, None, false);
         assert!(result.is_ok());
     }
 
@@ -991,7 +2302,15 @@ mod tests {
 
     #[test]
     fn test_parse_root_invalid_path() {
-        let result = parse_root("/definitely/nonexistent/path", None, false, false);
+        let result = parse_root(
+            "/definitely/nonexistent/path",
+            None,
+            &[],
+            false,
+            false,
+            None,
+            false,
+        );
         assert!(result.is_ok());
         let licenses = result.unwrap();
         assert!(licenses.is_empty());
@@ -0,0 +1,195 @@
+//! Seat-belt mode for huge scans: `--limit` and `--sample`
+//!
+//! Both cut the dependency set down for a quick smoke check. The cut is made before a package
+//! is looked up, so the packages left out never reach a registry: [`admit`] is asked before
+//! every lookup and refuses once `--limit` packages got in, or for the packages `--sample`
+//! didn't pick. After parsing, [`finish`] drops the rows that were refused, so the report
+//! holds only packages that were looked up, and records a [`PartialScan`] that the report
+//! metadata carries as its "partial scan" marker.
+//!
+//! `--sample` picks packages by a hash of their name and version, seeded per run, so a
+//! package listed by several manifests is kept or left out everywhere. `--limit` admits
+//! packages in the order they are looked up, which varies with parallel lookups.
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::debug::{log, LogLevel};
+use crate::licenses::LicenseInfo;
+
+static GATE: Mutex<Option<Gate>> = Mutex::new(None);
+static SUMMARY: Mutex<Option<PartialScan>> = Mutex::new(None);
+
+/// What a partial scan left out, for the report metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartialScan {
+    /// Dependencies the scan found
+    pub found: usize,
+    /// Dependencies kept in the report
+    pub kept: usize,
+    /// `--limit`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// `--sample`, in percent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sample: Option<u8>,
+}
+
+impl std::fmt::Display for PartialScan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} of {} dependencies", self.kept, self.found)?;
+        match (self.limit, self.sample) {
+            (Some(limit), Some(sample)) => write!(f, " (--sample {sample} --limit {limit})"),
+            (Some(limit), None) => write!(f, " (--limit {limit})"),
+            (None, Some(sample)) => write!(f, " (--sample {sample})"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+struct Gate {
+    limit: Option<usize>,
+    sample: Option<u8>,
+    seed: RandomState,
+    admitted: HashSet<String>,
+}
+
+impl Gate {
+    fn new(limit: Option<usize>, sample: Option<u8>) -> Self {
+        Self {
+            limit,
+            sample,
+            seed: RandomState::new(),
+            admitted: HashSet::new(),
+        }
+    }
+
+    fn admit(&mut self, name: &str, version: &str) -> bool {
+        let key = format!("{name}@{version}");
+        if self.admitted.contains(&key) {
+            return true;
+        }
+        if let Some(percent) = self.sample {
+            if self.seed.hash_one(&key) % 100 >= u64::from(percent) {
+                return false;
+            }
+        }
+        if self.limit.is_some_and(|limit| self.admitted.len() >= limit) {
+            return false;
+        }
+        self.admitted.insert(key);
+        true
+    }
+}
+
+/// Start a scan that keeps at most `limit` packages, or about `sample` percent of them.
+/// Clears what the previous scan admitted and recorded.
+pub fn start(limit: Option<usize>, sample: Option<u8>) {
+    let gate = (limit.is_some() || sample.is_some()).then(|| Gate::new(limit, sample));
+    if let Ok(mut current) = GATE.lock() {
+        *current = gate;
+    }
+    if let Ok(mut summary) = SUMMARY.lock() {
+        *summary = None;
+    }
+}
+
+/// Whether the package may be looked up and reported. Always true outside a partial scan.
+pub fn admit(name: &str, version: &str) -> bool {
+    match GATE.lock() {
+        Ok(mut gate) => gate.as_mut().is_none_or(|gate| gate.admit(name, version)),
+        Err(_) => true,
+    }
+}
+
+/// Drop the dependencies the scan left out and close the gate, so lookups made after parsing
+/// aren't counted. Returns the names dropped, which the tree-walking scans still treat as
+/// known dependencies rather than unmanaged code.
+pub fn finish(dependencies: &mut Vec<LicenseInfo>) -> Vec<String> {
+    let Some(mut gate) = GATE.lock().ok().and_then(|mut gate| gate.take()) else {
+        return Vec::new();
+    };
+
+    let found = dependencies.len();
+    let mut dropped = Vec::new();
+    dependencies.retain(|info| {
+        let keep = gate.admit(&info.name, &info.version);
+        if !keep {
+            dropped.push(info.name.clone());
+        }
+        keep
+    });
+
+    let partial = PartialScan {
+        found,
+        kept: dependencies.len(),
+        limit: gate.limit,
+        sample: gate.sample,
+    };
+    log(LogLevel::Info, &format!("Partial scan: kept {partial}"));
+    eprintln!("✂️  Partial scan: {partial}; the rest were not checked");
+    if let Ok(mut summary) = SUMMARY.lock() {
+        *summary = Some(partial);
+    }
+    dropped
+}
+
+/// The partial scan marker of the last scan, `None` for a full scan. Takes it, so call it once
+/// per scan.
+pub fn take_summary() -> Option<PartialScan> {
+    SUMMARY.lock().ok().and_then(|mut summary| summary.take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gate_limit() {
+        let mut gate = Gate::new(Some(2), None);
+        assert!(gate.admit("serde", "1.0.0"));
+        assert!(gate.admit("rand", "0.8.5"));
+        assert!(!gate.admit("tokio", "1.40.0"));
+        // Packages already in stay in, whichever manifest lists them again
+        assert!(gate.admit("serde", "1.0.0"));
+        assert!(!gate.admit("serde", "2.0.0"));
+    }
+
+    #[test]
+    fn test_gate_sample() {
+        let mut gate = Gate::new(None, Some(50));
+        let admitted: Vec<bool> = (0..1000)
+            .map(|i| gate.admit(&format!("pkg-{i}"), "1.0.0"))
+            .collect();
+        let kept = admitted.iter().filter(|admitted| **admitted).count();
+        assert!((300..700).contains(&kept), "kept {kept} of 1000");
+        // The decision is the same each time a package comes up
+        for (i, admitted) in admitted.iter().enumerate() {
+            assert_eq!(gate.admit(&format!("pkg-{i}"), "1.0.0"), *admitted);
+        }
+
+        let mut gate = Gate::new(Some(10), Some(50));
+        let kept = (0..1000)
+            .filter(|i| gate.admit(&format!("pkg-{i}"), "1.0.0"))
+            .count();
+        assert_eq!(kept, 10);
+    }
+
+    #[test]
+    fn test_partial_scan_display() {
+        let partial = PartialScan {
+            found: 1200,
+            kept: 100,
+            limit: Some(100),
+            sample: None,
+        };
+        assert_eq!(
+            partial.to_string(),
+            "100 of 1200 dependencies (--limit 100)"
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Package URLs (purl) for dependencies
+//!
+//! A purl names a package the same way across tools (`pkg:npm/%40babel/core@7.23.0`), so
+//! Feluda's results can be joined with vulnerability scanners, SBOM tooling and registries.
+//! Each ecosystem maps to its purl type and the normalisation the purl spec asks of it.
+
+use crate::licenses::LicenseInfo;
+
+/// Versions Feluda records when the real one is unknown; the purl is left without a version
+const PLACEHOLDER_VERSIONS: [&str; 3] = ["latest", "unknown", "*"];
+
+/// Package URL of a dependency reported by an `ecosystem` project root
+///
+/// Covers the ecosystems with a purl type: cargo, npm, pypi, golang, maven, nuget, gem,
+/// composer, hex and cran. C/C++, Carthage and WebAssembly packages have none and get `None`,
+/// as do Maven names without a group.
+pub fn package_url(ecosystem: &str, name: &str, version: &str) -> Option<String> {
+    let (purl_type, path) = match ecosystem {
+        "rust" => ("cargo", encode_segment(name)),
+        "node" => {
+            // Scoped packages keep the scope as the namespace, `@` included
+            let path = match name.split_once('/') {
+                Some((scope, package)) if scope.starts_with('@') => {
+                    format!("{}/{}", encode_segment(scope), encode_segment(package))
+                }
+                _ => encode_segment(name),
+            };
+            ("npm", path)
+        }
+        // PyPI names are case-insensitive and treat `_` like `-`
+        "python" => (
+            "pypi",
+            encode_segment(&name.to_lowercase().replace('_', "-")),
+        ),
+        "go" => ("golang", encode_path(name)),
+        "java" | "android" => {
+            let (group, artifact) = name.split_once(':')?;
+            (
+                "maven",
+                format!("{}/{}", encode_segment(group), encode_segment(artifact)),
+            )
+        }
+        "dotnet" => ("nuget", encode_segment(name)),
+        "ruby" => ("gem", encode_segment(name)),
+        // Packagist and Hex names are case-insensitive
+        "php" => ("composer", encode_path(&name.to_lowercase())),
+        "elixir" => ("hex", encode_segment(&name.to_lowercase())),
+        "r" => ("cran", encode_segment(name)),
+        _ => return None,
+    };
+
+    let version = version.trim();
+    if version.is_empty() || PLACEHOLDER_VERSIONS.contains(&version) {
+        Some(format!("pkg:{purl_type}/{path}"))
+    } else {
+        Some(format!(
+            "pkg:{purl_type}/{path}@{}",
+            encode_segment(version)
+        ))
+    }
+}
+
+/// The dependency's purl: the one recorded during the scan, else one derived from its ecosystem
+///
+/// Reports written before purls were recorded still carry the ecosystem, so they get one too.
+pub fn of(dependency: &LicenseInfo) -> Option<String> {
+    dependency.purl.clone().or_else(|| {
+        dependency
+            .ecosystem()
+            .and_then(|ecosystem| package_url(ecosystem, &dependency.name, &dependency.version))
+    })
+}
+
+/// Percent-encode everything but the characters a purl segment may hold as-is
+fn encode_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'.' | b'-' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Encode a `/`-separated namespace and name segment by segment
+fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(encode_segment)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_url() {
+        assert_eq!(
+            package_url("rust", "serde", "1.0.200").as_deref(),
+            Some("pkg:cargo/serde@1.0.200")
+        );
+        assert_eq!(
+            package_url("node", "@babel/core", "7.23.0").as_deref(),
+            Some("pkg:npm/%40babel/core@7.23.0")
+        );
+        assert_eq!(
+            package_url("python", "Typing_Extensions", "4.9.0").as_deref(),
+            Some("pkg:pypi/typing-extensions@4.9.0")
+        );
+        assert_eq!(
+            package_url("go", "github.com/spf13/cobra", "v1.8.0").as_deref(),
+            Some("pkg:golang/github.com/spf13/cobra@v1.8.0")
+        );
+        assert_eq!(
+            package_url("rust", "semver", "1.0.0+build.1").as_deref(),
+            Some("pkg:cargo/semver@1.0.0%2Bbuild.1")
+        );
+        assert_eq!(
+            package_url("node", "lodash", "").as_deref(),
+            Some("pkg:npm/lodash")
+        );
+        assert_eq!(package_url("cpp", "zlib", "1.3"), None);
+    }
+
+    #[test]
+    fn test_package_url_other_ecosystems() {
+        assert_eq!(
+            package_url("java", "org.slf4j:slf4j-api", "2.0.9").as_deref(),
+            Some("pkg:maven/org.slf4j/slf4j-api@2.0.9")
+        );
+        assert_eq!(package_url("java", "slf4j-api", "2.0.9"), None);
+        assert_eq!(
+            package_url("dotnet", "Newtonsoft.Json", "13.0.3").as_deref(),
+            Some("pkg:nuget/Newtonsoft.Json@13.0.3")
+        );
+        assert_eq!(
+            package_url("ruby", "rails", "7.1.2").as_deref(),
+            Some("pkg:gem/rails@7.1.2")
+        );
+        assert_eq!(
+            package_url("php", "Symfony/Console", "v6.4.1").as_deref(),
+            Some("pkg:composer/symfony/console@v6.4.1")
+        );
+        assert_eq!(
+            package_url("elixir", "Phoenix", "1.7.10").as_deref(),
+            Some("pkg:hex/phoenix@1.7.10")
+        );
+        assert_eq!(
+            package_url("r", "ggplot2", "latest").as_deref(),
+            Some("pkg:cran/ggplot2")
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Rate-limit budgets of the APIs and registries a scan talks to
+//!
+//! Responses report their `x-ratelimit-*` headers (GitHub and most registries) or the IETF
+//! `ratelimit-*` ones here. `--verbose` lists each source's remaining budget after the report,
+//! and the first time a source is being drained fast enough to run dry before its window
+//! resets, the scan warns once and suggests a way around it.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use colored::*;
+use reqwest::header::HeaderMap;
+
+use crate::debug::{log, LogLevel};
+
+/// Requests to a source before its pace is trusted enough to project from
+const MIN_REQUESTS_FOR_PACE: u64 = 10;
+
+/// `reset` values below this are seconds from now rather than a Unix time
+const RELATIVE_RESET_CUTOFF: u64 = 1_000_000_000;
+
+static BUDGETS: Mutex<Vec<Tracker>> = Mutex::new(Vec::new());
+
+/// What a source last reported about its rate limit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitBudget {
+    /// Where the requests went, e.g. "GitHub API" or "npm registry"
+    pub source: String,
+    pub limit: u64,
+    pub remaining: u64,
+    /// Unix time the window resets, when the source says
+    pub reset: Option<u64>,
+    /// Requests this scan sent to the source
+    pub used: u64,
+}
+
+struct Tracker {
+    budget: RateLimitBudget,
+    first_request: Instant,
+    warned: bool,
+}
+
+/// Record the rate-limit headers of a response from `source`
+pub fn record(source: &str, headers: &HeaderMap) {
+    let now = unix_now();
+    let Some((limit, remaining, reset)) = parse_headers(headers, now) else {
+        return;
+    };
+    let Ok(mut budgets) = BUDGETS.lock() else {
+        return;
+    };
+
+    let tracker = match budgets.iter().position(|t| t.budget.source == source) {
+        Some(index) => &mut budgets[index],
+        None => {
+            budgets.push(Tracker {
+                budget: RateLimitBudget {
+                    source: source.to_string(),
+                    limit,
+                    remaining,
+                    reset,
+                    used: 0,
+                },
+                first_request: Instant::now(),
+                warned: false,
+            });
+            budgets.last_mut().expect("just pushed")
+        }
+    };
+    let budget = &mut tracker.budget;
+    budget.limit = limit;
+    budget.remaining = remaining;
+    budget.reset = reset;
+    budget.used += 1;
+
+    let resets_in = reset.map(|reset| reset.saturating_sub(now));
+    if !tracker.warned
+        && will_exhaust(
+            remaining,
+            resets_in,
+            budget.used,
+            tracker.first_request.elapsed(),
+        )
+    {
+        tracker.warned = true;
+        warn_exhaustion(budget);
+    }
+}
+
+/// Every source's budget as last reported, restarting the per-scan request counts
+pub fn take_budgets() -> Vec<RateLimitBudget> {
+    let Ok(mut budgets) = BUDGETS.lock() else {
+        return Vec::new();
+    };
+    let snapshot = budgets.iter().map(|t| t.budget.clone()).collect();
+    for tracker in budgets.iter_mut() {
+        tracker.budget.used = 0;
+        tracker.first_request = Instant::now();
+        tracker.warned = false;
+    }
+    snapshot
+}
+
+/// `(limit, remaining, reset)` from `x-ratelimit-*` or `ratelimit-*` headers
+fn parse_headers(headers: &HeaderMap, now: u64) -> Option<(u64, u64, Option<u64>)> {
+    let value = |name: &str| {
+        let raw = headers.get(name)?.to_str().ok()?;
+        // Structured forms like `100;w=60` lead with the number
+        let digits: String = raw
+            .trim()
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        digits.parse::<u64>().ok()
+    };
+
+    ["x-ratelimit-", "ratelimit-"].iter().find_map(|prefix| {
+        let limit = value(&format!("{prefix}limit"))?;
+        let remaining = value(&format!("{prefix}remaining"))?;
+        let reset = value(&format!("{prefix}reset")).map(|reset| {
+            if reset < RELATIVE_RESET_CUTOFF {
+                now + reset
+            } else {
+                reset
+            }
+        });
+        Some((limit, remaining, reset))
+    })
+}
+
+/// Whether `used` requests in `elapsed` is a pace that spends `remaining` before the window
+/// resets in `resets_in` seconds
+fn will_exhaust(remaining: u64, resets_in: Option<u64>, used: u64, elapsed: Duration) -> bool {
+    if remaining == 0 {
+        return true;
+    }
+    let Some(resets_in) = resets_in else {
+        return false;
+    };
+    if used < MIN_REQUESTS_FOR_PACE {
+        return false;
+    }
+    let per_second = used as f64 / elapsed.as_secs_f64().max(1.0);
+    (remaining as f64 / per_second) < resets_in as f64
+}
+
+fn warn_exhaustion(budget: &RateLimitBudget) {
+    let hint = if budget.source == "GitHub API" && crate::licenses::get_github_token().is_none() {
+        "pass --github-token (or set GITHUB_TOKEN) for a higher limit"
+    } else {
+        "remove it from [resolvers] order in .feluda.toml, or re-run once it resets to finish from the cache"
+    };
+    log(
+        LogLevel::Warn,
+        &format!(
+            "{} rate limit: {} of {} requests left",
+            budget.source, budget.remaining, budget.limit
+        ),
+    );
+    eprintln!(
+        "{} {}",
+        "⚠️".yellow().bold(),
+        format!(
+            "{} rate limit will run out at this pace ({} of {} left{}); {hint}",
+            budget.source,
+            budget.remaining,
+            budget.limit,
+            resets_at(budget.reset)
+                .map(|time| format!(", resets at {time}"))
+                .unwrap_or_default(),
+        )
+        .yellow()
+    );
+}
+
+/// Local wall-clock time of a Unix `reset`
+pub fn resets_at(reset: Option<u64>) -> Option<String> {
+    let reset = chrono::DateTime::from_timestamp(i64::try_from(reset?).ok()?, 0)?;
+    Some(
+        reset
+            .with_timezone(&chrono::Local)
+            .format("%H:%M")
+            .to_string(),
+    )
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, HeaderValue::from_static(value));
+        }
+        map
+    }
+
+    #[test]
+    fn test_parse_github_headers() {
+        let map = headers(&[
+            ("x-ratelimit-limit", "5000"),
+            ("x-ratelimit-remaining", "4990"),
+            ("x-ratelimit-reset", "1760000000"),
+        ]);
+        assert_eq!(
+            parse_headers(&map, 1_759_999_000),
+            Some((5000, 4990, Some(1_760_000_000)))
+        );
+    }
+
+    #[test]
+    fn test_parse_ietf_headers_with_relative_reset() {
+        let map = headers(&[
+            ("ratelimit-limit", "100;w=60"),
+            ("ratelimit-remaining", "42"),
+            ("ratelimit-reset", "30"),
+        ]);
+        assert_eq!(
+            parse_headers(&map, 1_000_000_000),
+            Some((100, 42, Some(1_000_000_030)))
+        );
+        assert_eq!(parse_headers(&HeaderMap::new(), 0), None);
+    }
+
+    #[test]
+    fn test_will_exhaust() {
+        // 60 requests in a minute leaves 50 for a window that resets in an hour
+        assert!(will_exhaust(50, Some(3600), 60, Duration::from_secs(60)));
+        // Same pace with plenty left
+        assert!(!will_exhaust(4000, Some(60), 60, Duration::from_secs(60)));
+        // Too few requests to judge, but an empty budget is always a problem
+        assert!(!will_exhaust(5, Some(3600), 3, Duration::from_secs(1)));
+        assert!(will_exhaust(0, None, 1, Duration::from_secs(1)));
+    }
+}
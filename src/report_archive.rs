@@ -0,0 +1,294 @@
+//! Report files on disk
+//!
+//! [`write_report_file`] compresses a report when its path ends in `.gz` or `.zst`, so
+//! large monorepo reports and SBOMs can be stored as artifacts directly.
+//!
+//! With `--output-dir` every run writes `feluda-report-<UTC timestamp>.<ext>` into the
+//! directory, so CI jobs can archive each scan instead of overwriting a single
+//! `--output-file`. With `--keep-reports N` only the newest N reports are kept.
+//!
+//! [`load_report`] reads such a report back, e.g. for `feluda tui <REPORT>`.
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
+use crate::licenses::LicenseInfo;
+use crate::report_metadata::{ReportMetadata, StoredReport};
+
+const REPORT_PREFIX: &str = "feluda-report-";
+
+/// Compressed suffixes [`write_report_file`] understands
+const COMPRESSED_EXTENSIONS: [&str; 2] = [".gz", ".zst"];
+
+/// Write a report, gzip- or zstd-compressing it based on the path's extension
+pub fn write_report_file(path: &Path, content: &[u8]) -> io::Result<()> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("gz") => {
+            let mut encoder = flate2::write::GzEncoder::new(
+                fs::File::create(path)?,
+                flate2::Compression::default(),
+            );
+            encoder.write_all(content)?;
+            encoder.finish()?;
+            Ok(())
+        }
+        Some("zst") => {
+            let mut encoder = zstd::Encoder::new(fs::File::create(path)?, 0)?;
+            encoder.write_all(content)?;
+            encoder.finish()?;
+            Ok(())
+        }
+        _ => fs::write(path, content),
+    }
+}
+
+/// Read a report written by [`write_report_file`], decompressing it based on the path's
+/// extension
+pub fn read_report_file(path: &Path) -> io::Result<Vec<u8>> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("gz") => {
+            let mut content = Vec::new();
+            flate2::read::GzDecoder::new(fs::File::open(path)?).read_to_end(&mut content)?;
+            Ok(content)
+        }
+        Some("zst") => zstd::decode_all(fs::File::open(path)?),
+        _ => fs::read(path),
+    }
+}
+
+/// Load the dependencies of a `--json` or `--yaml` report, compressed or not
+pub fn load_report(path: &Path) -> FeludaResult<Vec<LicenseInfo>> {
+    load_report_with_metadata(path).map(|(report, _)| report)
+}
+
+/// [`load_report`], with the scan metadata the report carries
+pub fn load_report_with_metadata(
+    path: &Path,
+) -> FeludaResult<(Vec<LicenseInfo>, Option<ReportMetadata>)> {
+    let content = read_report_file(path).map_err(|e| {
+        FeludaError::InvalidData(format!("Failed to read report {}: {e}", path.display()))
+    })?;
+    let (base, _) = split_compressed_suffix(path.to_str().unwrap_or_default());
+    let parsed = if base.ends_with(".yaml") || base.ends_with(".yml") {
+        serde_yaml::from_slice(&content).map_err(|e| e.to_string())
+    } else {
+        serde_json::from_slice(&content).map_err(|e| e.to_string())
+    };
+    let report: StoredReport = parsed.map_err(|e| {
+        FeludaError::InvalidData(format!(
+            "{} is not a `feluda --json` or `--yaml` report: {e}",
+            path.display()
+        ))
+    })?;
+    let (report, metadata) = report.into_parts();
+
+    log(
+        LogLevel::Info,
+        &format!(
+            "Loaded report with {} dependencies from {}",
+            report.len(),
+            path.display()
+        ),
+    );
+    Ok((report, metadata))
+}
+
+/// Split `report.json.gz` into (`report.json`, `.gz`); uncompressed paths get an empty suffix
+pub fn split_compressed_suffix(path: &str) -> (&str, &str) {
+    COMPRESSED_EXTENSIONS
+        .iter()
+        .find_map(|suffix| path.strip_suffix(suffix).map(|base| (base, *suffix)))
+        .unwrap_or((path, ""))
+}
+
+/// Path for a new report in `dir`, creating the directory if needed.
+///
+/// Timestamps have second precision; a second run within the same second gets a
+/// `-2`, `-3`, ... suffix instead of overwriting the first report.
+pub fn next_report_path(dir: &Path, extension: &str) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+
+    let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut path = dir.join(format!("{REPORT_PREFIX}{timestamp}.{extension}"));
+    let mut attempt = 2;
+    while path.exists() {
+        path = dir.join(format!("{REPORT_PREFIX}{timestamp}-{attempt}.{extension}"));
+        attempt += 1;
+    }
+    Ok(path)
+}
+
+/// Delete the oldest reports in `dir` so at most `keep` remain. Returns how many were
+/// removed. Only files named like feluda reports are touched.
+pub fn prune_reports(dir: &Path, keep: usize) -> io::Result<usize> {
+    let mut reports: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(REPORT_PREFIX))
+        })
+        .collect();
+
+    if reports.len() <= keep {
+        return Ok(0);
+    }
+
+    reports.sort_by_key(|path| report_order(path));
+    let excess = reports.len() - keep;
+    for old in &reports[..excess] {
+        log(
+            LogLevel::Info,
+            &format!("Removing old report: {}", old.display()),
+        );
+        fs::remove_file(old)?;
+    }
+    Ok(excess)
+}
+
+/// Chronological sort key for a report path: its timestamp, then the `-N` suffix
+/// `next_report_path` adds (a report without one is the first of its second)
+fn report_order(path: &Path) -> (String, u32) {
+    let stem = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.split('.').next())
+        .unwrap_or_default();
+    match stem.rsplit_once('-') {
+        Some((timestamp, attempt)) if timestamp.ends_with('Z') => match attempt.parse() {
+            Ok(attempt) => (timestamp.to_string(), attempt),
+            Err(_) => (stem.to_string(), 1),
+        },
+        _ => (stem.to_string(), 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_report_file_compression() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let content = br#"[{"name": "serde"}]"#;
+
+        let plain = temp_dir.path().join("report.json");
+        write_report_file(&plain, content).unwrap();
+        assert_eq!(fs::read(&plain).unwrap(), content);
+
+        let gzip = temp_dir.path().join("report.json.gz");
+        write_report_file(&gzip, content).unwrap();
+        let mut decoded = Vec::new();
+        io::Read::read_to_end(
+            &mut flate2::read::GzDecoder::new(fs::File::open(&gzip).unwrap()),
+            &mut decoded,
+        )
+        .unwrap();
+        assert_eq!(decoded, content);
+
+        let zstd_path = temp_dir.path().join("report.json.zst");
+        write_report_file(&zstd_path, content).unwrap();
+        let decoded = zstd::decode_all(fs::File::open(&zstd_path).unwrap()).unwrap();
+        assert_eq!(decoded, content);
+    }
+
+    #[test]
+    fn test_load_report_compressed_and_yaml() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let json = br#"[{"name": "serde", "version": "1.0.0", "license": "MIT",
+            "is_restrictive": false, "compatibility": "Compatible", "osi_status": "Approved"}]"#;
+
+        let gzip = temp_dir.path().join("report.json.gz");
+        write_report_file(&gzip, json).unwrap();
+        let report = load_report(&gzip).unwrap();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].name, "serde");
+
+        // Current reports wrap the dependencies next to the scan metadata
+        let yaml = temp_dir.path().join("report.yaml");
+        let current = crate::report_metadata::Report::new(None, None, &report);
+        fs::write(&yaml, serde_yaml::to_string(&current).unwrap()).unwrap();
+        assert_eq!(load_report(&yaml).unwrap()[0].version, "1.0.0");
+
+        let broken = temp_dir.path().join("broken.json");
+        fs::write(&broken, "{}").unwrap();
+        assert!(load_report(&broken).is_err());
+        assert!(load_report(&temp_dir.path().join("missing.json")).is_err());
+    }
+
+    #[test]
+    fn test_split_compressed_suffix() {
+        assert_eq!(
+            split_compressed_suffix("sbom.json.gz"),
+            ("sbom.json", ".gz")
+        );
+        assert_eq!(split_compressed_suffix("sbom.zst"), ("sbom", ".zst"));
+        assert_eq!(split_compressed_suffix("sbom.json"), ("sbom.json", ""));
+    }
+
+    #[test]
+    fn test_next_report_path_is_unique() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path().join("reports");
+
+        let first = next_report_path(&dir, "json").unwrap();
+        assert!(dir.is_dir());
+        let name = first.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with(REPORT_PREFIX));
+        assert!(name.ends_with(".json"));
+
+        fs::write(&first, "[]").unwrap();
+        let second = next_report_path(&dir, "json").unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_prune_reports_keeps_newest() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        for name in [
+            "feluda-report-20260101T000000Z-2.json",
+            "feluda-report-20260101T000000Z.json",
+            "feluda-report-20260102T000000Z.sarif",
+            "notes.txt",
+        ] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        assert_eq!(prune_reports(dir, 2).unwrap(), 1);
+        assert!(!dir.join("feluda-report-20260101T000000Z.json").exists());
+        assert!(dir.join("feluda-report-20260101T000000Z-2.json").exists());
+        assert!(dir.join("feluda-report-20260102T000000Z.sarif").exists());
+        assert!(dir.join("notes.txt").exists());
+
+        assert_eq!(prune_reports(dir, 5).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_prune_reports_orders_numeric_suffixes() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        fs::write(dir.join("feluda-report-20260101T000000Z.json"), "").unwrap();
+        for attempt in 2..=12 {
+            fs::write(
+                dir.join(format!("feluda-report-20260101T000000Z-{attempt}.json")),
+                "",
+            )
+            .unwrap();
+        }
+
+        assert_eq!(prune_reports(dir, 3).unwrap(), 9);
+        for attempt in 10..=12 {
+            assert!(dir
+                .join(format!("feluda-report-20260101T000000Z-{attempt}.json"))
+                .exists());
+        }
+        assert!(!dir.join("feluda-report-20260101T000000Z-9.json").exists());
+        assert!(!dir.join("feluda-report-20260101T000000Z.json").exists());
+    }
+}
@@ -1,9 +1,19 @@
-use crate::cli::{CiFormat, OsiFilter};
-use crate::debug::{log, log_debug, log_error, LogLevel};
-use crate::licenses::{LicenseCompatibility, LicenseInfo, OsiStatus};
+use crate::cli::{CiFormat, OsiFilter, OutputFormat, ReportCompression, SbomExport};
+use crate::conflicts::{find_license_conflicts, LicenseConflict};
+use crate::debug::{
+    log, log_debug, log_error, resolution_error_counts, FeludaResult, LogLevel, ParseWarning,
+    ResolutionError,
+};
+use crate::ignore::{print_ignored_dependencies, IgnoredDependency};
+use crate::licenses::{LicenseCompatibility, LicenseInfo, OsiStatus, SpdxSpecialValue};
+use crate::outputs::{self, OutputSink};
+use crate::rate_limit::{self, RateLimitBudget};
+use crate::report_archive;
+use crate::report_metadata::{Report, ReportMetadata};
+use crate::resolver::known_license;
 use colored::*;
-use std::collections::HashMap;
-use std::fs;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 
 // ReportConfig struct
 #[derive(Debug)]
@@ -18,6 +28,22 @@ pub struct ReportConfig {
     project_license: Option<String>,
     gist: bool,
     osi: Option<OsiFilter>,
+    warnings: Vec<ParseWarning>,
+    output_dir: Option<String>,
+    keep_reports: Option<usize>,
+    compress: Option<ReportCompression>,
+    chart: bool,
+    incomplete: Option<String>,
+    resolution_errors: Vec<ResolutionError>,
+    ignored: Vec<IgnoredDependency>,
+    rate_limits: Vec<RateLimitBudget>,
+    /// `--sbom` format, with the name of the project the SBOM describes
+    sbom: Option<(SbomExport, String)>,
+    /// `--output-format` document, with the name of the project it reports on
+    output_format: Option<(OutputFormat, String)>,
+    metadata: Option<ReportMetadata>,
+    /// `[outputs]` sinks, with the name of the project the report is about
+    outputs: (BTreeMap<String, OutputSink>, String),
 }
 
 impl ReportConfig {
@@ -45,17 +71,143 @@ impl ReportConfig {
             project_license,
             gist,
             osi,
+            warnings: Vec::new(),
+            output_dir: None,
+            keep_reports: None,
+            compress: None,
+            chart: false,
+            incomplete: None,
+            resolution_errors: Vec::new(),
+            ignored: Vec::new(),
+            rate_limits: Vec::new(),
+            sbom: None,
+            output_format: None,
+            metadata: None,
+            outputs: (BTreeMap::new(), String::new()),
+        }
+    }
+
+    /// Attach manifest parse warnings so every output format can surface them
+    pub fn with_warnings(mut self, warnings: Vec<ParseWarning>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    /// Also write each report to a timestamped file in `dir`, keeping the newest `keep`
+    pub fn with_output_dir(
+        mut self,
+        dir: Option<String>,
+        keep: Option<usize>,
+        compress: Option<ReportCompression>,
+    ) -> Self {
+        self.output_dir = dir;
+        self.keep_reports = keep;
+        self.compress = compress;
+        self
+    }
+
+    /// Attach failed registry/API lookups for the "resolution errors" section
+    pub fn with_resolution_errors(mut self, errors: Vec<ResolutionError>) -> Self {
+        self.resolution_errors = errors;
+        self
+    }
+
+    /// Attach the dependencies ignore rules removed, listed in their own "ignored" section
+    pub fn with_ignored(mut self, ignored: Vec<IgnoredDependency>) -> Self {
+        self.ignored = ignored;
+        self
+    }
+
+    /// Attach the rate-limit budgets the scan's lookups reported, listed with `--verbose`
+    pub fn with_rate_limits(mut self, budgets: Vec<RateLimitBudget>) -> Self {
+        self.rate_limits = budgets;
+        self
+    }
+
+    /// Write the results as an SBOM describing `project_name` instead of a report
+    pub fn with_sbom(mut self, format: Option<SbomExport>, project_name: &str) -> Self {
+        self.sbom = format.map(|format| (format, project_name.to_string()));
+        self
+    }
+
+    /// Write the results as a standalone document about `project_name` instead of a report
+    pub fn with_output_format(mut self, format: Option<OutputFormat>, project_name: &str) -> Self {
+        self.output_format = format.map(|format| (format, project_name.to_string()));
+        self
+    }
+
+    /// Mark the report as partial, e.g. `Some("interrupted by Ctrl+C")`
+    pub fn with_incomplete(mut self, reason: Option<String>) -> Self {
+        self.incomplete = reason;
+        self
+    }
+
+    /// Embed the scan's metadata in the machine-readable formats
+    pub fn with_metadata(mut self, metadata: ReportMetadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Also send the report about `project_name` to the `[outputs]` sinks
+    pub fn with_outputs(
+        mut self,
+        outputs: BTreeMap<String, OutputSink>,
+        project_name: &str,
+    ) -> Self {
+        self.outputs = (outputs, project_name.to_string());
+        self
+    }
+
+    /// Add a license distribution bar chart to the summary output
+    pub fn with_chart(mut self, chart: bool) -> Self {
+        self.chart = chart;
+        self
+    }
+
+    /// The `--json`/`--yaml` document for `dependencies`
+    fn report<'a>(&'a self, dependencies: &'a [LicenseInfo]) -> Report<'a> {
+        Report::new(
+            self.metadata.as_ref(),
+            self.project_license.as_deref(),
+            dependencies,
+        )
+        .with_warnings(&self.warnings)
+    }
+
+    /// Send the report of `dependencies` to the `[outputs]` sinks
+    fn deliver_outputs(&self, dependencies: &[LicenseInfo]) -> FeludaResult<()> {
+        let (outputs, project_name) = &self.outputs;
+        outputs::deliver(outputs, &self.report(dependencies), project_name)
+    }
+
+    /// File extension of the report `--output-dir` archives for this configuration
+    fn archive_extension(&self) -> String {
+        let format = match self.ci_format {
+            Some(CiFormat::Sarif) => "sarif",
+            Some(CiFormat::Gitlab) => "json",
+            Some(CiFormat::Jenkins) => "xml",
+            Some(CiFormat::Github) => "txt",
+            None => match (&self.sbom, &self.output_format) {
+                (Some((sbom, _)), _) => sbom.extension(),
+                (None, Some((format, _))) => format.extension(),
+                (None, None) if self.yaml => "yaml",
+                (None, None) => "json",
+            },
+        };
+        match self.compress {
+            Some(compression) => format!("{format}.{}", compression.extension()),
+            None => format.to_string(),
         }
     }
 }
 
-struct TableFormatter {
+pub(crate) struct TableFormatter {
     column_widths: Vec<usize>,
     headers: Vec<String>,
 }
 
 impl TableFormatter {
-    fn new(headers: Vec<String>) -> Self {
+    pub(crate) fn new(headers: Vec<String>) -> Self {
         let column_widths = headers.iter().map(|h| h.len()).collect();
         Self {
             column_widths,
@@ -63,7 +215,7 @@ impl TableFormatter {
         }
     }
 
-    fn add_row(&mut self, row: &[String]) {
+    pub(crate) fn add_row(&mut self, row: &[String]) {
         for (i, item) in row.iter().enumerate() {
             if i < self.column_widths.len() {
                 self.column_widths[i] = self.column_widths[i].max(item.len());
@@ -71,7 +223,7 @@ impl TableFormatter {
         }
     }
 
-    fn render_header(&self) -> String {
+    pub(crate) fn render_header(&self) -> String {
         let header_row = self
             .headers
             .iter()
@@ -91,7 +243,7 @@ impl TableFormatter {
         )
     }
 
-    fn render_row(&self, row: &[String], is_problematic: bool) -> String {
+    pub(crate) fn render_row(&self, row: &[String], is_problematic: bool) -> String {
         let formatted_row = row
             .iter()
             .enumerate()
@@ -112,14 +264,16 @@ impl TableFormatter {
         }
     }
 
-    fn render_footer(&self) -> String {
+    pub(crate) fn render_footer(&self) -> String {
         let footer_width =
             self.column_widths.iter().sum::<usize>() + (3 * self.column_widths.len()) - 1;
         format!("└{}┘", "─".repeat(footer_width))
     }
 }
 
-pub fn generate_report(data: Vec<LicenseInfo>, config: ReportConfig) -> (bool, bool) {
+/// Print or write the report, returning whether it has restrictive and incompatible licenses.
+/// Fails when an `[outputs]` sink couldn't be reached, after the report is complete.
+pub fn generate_report(data: Vec<LicenseInfo>, config: ReportConfig) -> FeludaResult<(bool, bool)> {
     log(
         LogLevel::Info,
         &format!("Generating report with config: {config:?}"),
@@ -132,9 +286,12 @@ pub fn generate_report(data: Vec<LicenseInfo>, config: ReportConfig) -> (bool, b
     );
 
     let has_restrictive = data.iter().any(|info| *info.is_restrictive());
-    let has_incompatible = data
-        .iter()
-        .any(|info| info.compatibility == LicenseCompatibility::Incompatible);
+    // Project-level conflicts look at the whole dependency set, before any filtering
+    let conflicts = find_license_conflicts(&data);
+    let has_incompatible = !conflicts.is_empty()
+        || data
+            .iter()
+            .any(|info| info.compatibility == LicenseCompatibility::Incompatible);
 
     log(
         LogLevel::Info,
@@ -149,7 +306,13 @@ pub fn generate_report(data: Vec<LicenseInfo>, config: ReportConfig) -> (bool, b
     if config.gist {
         log(LogLevel::Info, "Generating gist summary");
         print_gist_summary(&data, total_packages, config.project_license.as_deref());
-        return (has_restrictive, has_incompatible);
+        let delivered = config.deliver_outputs(&data);
+        print_license_conflicts(&conflicts);
+        print_parse_warnings(&config.warnings);
+        print_ignored_dependencies(&config.ignored);
+        print_resolution_errors(&config.resolution_errors);
+        print_incomplete_notice(config.incomplete.as_deref());
+        return delivered.map(|()| (has_restrictive, has_incompatible));
     }
 
     // Filter data if in restrictive or/and incompatible mode to show only restrictive or/and incompatible licenses
@@ -215,14 +378,79 @@ pub fn generate_report(data: Vec<LicenseInfo>, config: ReportConfig) -> (bool, b
     );
     log_debug("Filtered license data", &filtered_data);
 
+    let mut config = config;
+    if let Some(dir) = config.output_dir.clone() {
+        archive_report(&filtered_data, &mut config, Path::new(&dir));
+    }
+    let delivered = config.deliver_outputs(&filtered_data);
+
     // SARIF always produces output (empty results = clean scan), so bypass the early return.
     if matches!(config.ci_format, Some(CiFormat::Sarif)) {
         output_sarif_format(
             &filtered_data,
             config.output_file.as_deref(),
             config.project_license.as_deref(),
+            &config.warnings,
+            config.incomplete.as_deref(),
+            &config.resolution_errors,
+            &conflicts,
+            config.metadata.as_ref(),
         );
-        return (has_restrictive, has_incompatible);
+        return delivered.map(|()| (has_restrictive, has_incompatible));
+    }
+
+    // GitLab compares every dependency against its license policies, so the report lists them
+    // all and is written even when nothing is flagged
+    if matches!(config.ci_format, Some(CiFormat::Gitlab)) {
+        output_gitlab_format(&filtered_data, config.output_file.as_deref());
+        print_license_conflicts(&conflicts);
+        print_parse_warnings(&config.warnings);
+        print_ignored_dependencies(&config.ignored);
+        print_resolution_errors(&config.resolution_errors);
+        print_incomplete_notice(config.incomplete.as_deref());
+        return delivered.map(|()| (has_restrictive, has_incompatible));
+    }
+
+    // An SBOM with no dependencies still describes the project, so it is always written
+    if let Some((format, ref project_name)) = config.sbom {
+        if let Err(err) = crate::sbom::write_scan_sbom(
+            format,
+            project_name,
+            config.project_license.as_deref(),
+            &filtered_data,
+            config.output_file.as_deref(),
+            config.metadata.as_ref(),
+        ) {
+            log_error("Failed to write SBOM", &err);
+            eprintln!("Error: Failed to write SBOM: {err}");
+        }
+        print_license_conflicts(&conflicts);
+        print_parse_warnings(&config.warnings);
+        print_ignored_dependencies(&config.ignored);
+        print_resolution_errors(&config.resolution_errors);
+        print_incomplete_notice(config.incomplete.as_deref());
+        return delivered.map(|()| (has_restrictive, has_incompatible));
+    }
+
+    // A report with nothing to flag is still worth sharing, so it is always written too
+    if let Some((format, ref project_name)) = config.output_format {
+        match format {
+            OutputFormat::Html => output_html_format(
+                &filtered_data,
+                config.output_file.as_deref(),
+                project_name,
+                config.project_license.as_deref(),
+                config.incomplete.as_deref(),
+                &config.resolution_errors,
+                &conflicts,
+            ),
+        }
+        print_license_conflicts(&conflicts);
+        print_parse_warnings(&config.warnings);
+        print_ignored_dependencies(&config.ignored);
+        print_resolution_errors(&config.resolution_errors);
+        print_incomplete_notice(config.incomplete.as_deref());
+        return delivered.map(|()| (has_restrictive, has_incompatible));
     }
 
     if filtered_data.is_empty() {
@@ -232,28 +460,43 @@ pub fn generate_report(data: Vec<LicenseInfo>, config: ReportConfig) -> (bool, b
                 .green()
                 .bold()
         );
-        return (false, false);
+        print_license_conflicts(&conflicts);
+        print_parse_warnings(&config.warnings);
+        print_ignored_dependencies(&config.ignored);
+        print_resolution_errors(&config.resolution_errors);
+        print_incomplete_notice(config.incomplete.as_deref());
+        return delivered.map(|()| (false, !conflicts.is_empty()));
     }
 
+    let is_ci_format = config.ci_format.is_some();
     if let Some(format) = config.ci_format {
         match format {
             CiFormat::Github => output_github_format(
                 &filtered_data,
                 config.output_file.as_deref(),
                 config.project_license.as_deref(),
+                &config.warnings,
+                config.incomplete.as_deref(),
+                &config.resolution_errors,
+                &conflicts,
             ),
             CiFormat::Jenkins => output_jenkins_format(
                 &filtered_data,
                 config.output_file.as_deref(),
                 config.project_license.as_deref(),
+                &config.warnings,
+                config.incomplete.as_deref(),
+                &config.resolution_errors,
+                &conflicts,
+                config.metadata.as_ref(),
             ),
-            CiFormat::Sarif => unreachable!("handled above"),
+            CiFormat::Sarif | CiFormat::Gitlab => unreachable!("handled above"),
         }
     } else if config.json {
         // JSON output
         log(LogLevel::Info, "Generating JSON output");
-        match serde_json::to_string_pretty(&filtered_data) {
-            Ok(json_output) => println!("{json_output}"),
+        match serde_json::to_string_pretty(&config.report(&filtered_data)) {
+            Ok(json_output) => output_report(&json_output, config.output_file.as_deref(), "JSON"),
             Err(err) => {
                 log_error("Failed to serialize data to JSON", &err);
                 println!("Error: Failed to generate JSON output");
@@ -262,8 +505,8 @@ pub fn generate_report(data: Vec<LicenseInfo>, config: ReportConfig) -> (bool, b
     } else if config.yaml {
         // YAML output
         log(LogLevel::Info, "Generating YAML output");
-        match serde_yaml::to_string(&filtered_data) {
-            Ok(yaml_output) => println!("{yaml_output}"),
+        match serde_yaml::to_string(&config.report(&filtered_data)) {
+            Ok(yaml_output) => output_report(&yaml_output, config.output_file.as_deref(), "YAML"),
             Err(err) => {
                 log_error("Failed to serialize data to YAML", &err);
                 println!("Error: Failed to generate YAML output");
@@ -276,6 +519,9 @@ pub fn generate_report(data: Vec<LicenseInfo>, config: ReportConfig) -> (bool, b
             config.restrictive,
             config.project_license.as_deref(),
         );
+        print_fix_suggestions(&filtered_data);
+        print_top_offenders(&filtered_data);
+        print_vulnerabilities(&filtered_data);
     } else {
         log(LogLevel::Info, "Generating summary table");
         print_summary_table(
@@ -285,9 +531,237 @@ pub fn generate_report(data: Vec<LicenseInfo>, config: ReportConfig) -> (bool, b
             config.incompatible,
             config.project_license.as_deref(),
         );
+        print_fix_suggestions(&filtered_data);
+        print_top_offenders(&filtered_data);
+        print_vulnerabilities(&filtered_data);
+        if config.chart {
+            print_license_chart(&filtered_data);
+        }
+    }
+
+    // CI formats carry their warnings inline; everything else gets the section on stderr so
+    // JSON/YAML on stdout stays machine-readable.
+    if !is_ci_format {
+        print_license_conflicts(&conflicts);
+        print_parse_warnings(&config.warnings);
+        print_ignored_dependencies(&config.ignored);
+        print_resolution_errors(&config.resolution_errors);
+        if config.verbose {
+            print_rate_limits(&config.rate_limits);
+        }
+        print_incomplete_notice(config.incomplete.as_deref());
+    }
+
+    delivered.map(|()| (has_restrictive, has_incompatible))
+}
+
+/// Write this run's report into the `--output-dir` archive.
+///
+/// CI formats are redirected there through `output_file`; the other modes keep their
+/// terminal output and additionally archive the data as JSON (YAML with `--yaml`).
+fn archive_report(data: &[LicenseInfo], config: &mut ReportConfig, dir: &Path) {
+    let path = match report_archive::next_report_path(dir, &config.archive_extension()) {
+        Ok(path) => path,
+        Err(err) => {
+            log_error(
+                &format!("Failed to prepare report directory: {}", dir.display()),
+                &err,
+            );
+            eprintln!(
+                "Error: Failed to prepare report directory {}",
+                dir.display()
+            );
+            return;
+        }
+    };
+
+    // Make room for the report about to be written. The name is picked first, so a pruned
+    // report's name from this same second isn't handed out again.
+    if let Some(keep) = config.keep_reports {
+        if let Err(err) = report_archive::prune_reports(dir, keep.saturating_sub(1)) {
+            log_error("Failed to prune old reports", &err);
+        }
+    }
+
+    if config.ci_format.is_some() || config.sbom.is_some() || config.output_format.is_some() {
+        config.output_file = Some(path.display().to_string());
+        return;
+    }
+
+    let report = config.report(data);
+    let serialized = if config.yaml {
+        serde_yaml::to_string(&report).map_err(|e| e.to_string())
+    } else {
+        serde_json::to_string_pretty(&report).map_err(|e| e.to_string())
+    };
+    let result = serialized.and_then(|content| {
+        report_archive::write_report_file(&path, content.as_bytes()).map_err(|e| e.to_string())
+    });
+    match result {
+        Ok(()) => eprintln!("Report written to: {}", path.display()),
+        Err(err) => {
+            log(
+                LogLevel::Error,
+                &format!("Failed to write report {}: {err}", path.display()),
+            );
+            eprintln!("Error: Failed to write report {}", path.display());
+        }
+    }
+}
+
+/// Print the registry/API lookups that failed, grouped by source.
+///
+/// Written to stderr like the parse warnings. Prints nothing when every lookup completed.
+pub fn print_resolution_errors(errors: &[ResolutionError]) {
+    if errors.is_empty() {
+        return;
+    }
+
+    eprintln!(
+        "{} {}",
+        "⚠️".yellow().bold(),
+        format!(
+            "Resolution errors: {} registry lookup{} failed; affected licenses may show as unknown",
+            errors.len(),
+            if errors.len() == 1 { "" } else { "s" }
+        )
+        .yellow()
+        .bold()
+    );
+    for (source, count) in resolution_error_counts(errors) {
+        eprintln!("  • {}: {count}", source.as_str().bold());
+    }
+    eprintln!();
+}
+
+/// Print the remaining rate-limit budget of every source the scan's lookups reported one for.
+///
+/// Written to stderr with the other notices; only shown with `--verbose`.
+pub fn print_rate_limits(budgets: &[RateLimitBudget]) {
+    if budgets.is_empty() {
+        return;
+    }
+
+    eprintln!("{}", "Rate limits".bold());
+    for budget in budgets {
+        let left = format!("{}/{} left", budget.remaining, budget.limit);
+        let left = if budget.remaining * 10 <= budget.limit {
+            left.yellow().to_string()
+        } else {
+            left
+        };
+        let reset = rate_limit::resets_at(budget.reset)
+            .map(|time| format!(", resets at {time}"))
+            .unwrap_or_default();
+        eprintln!(
+            "  • {}: {left}{reset} ({} used by this scan)",
+            budget.source.as_str().bold(),
+            budget.used
+        );
+    }
+    eprintln!();
+}
+
+/// Whether a dependency breaks the `--osi-only` policy: its license is not OSI approved
+/// (including unknown licenses). Internal packages are exempt.
+pub fn violates_osi_only(info: &LicenseInfo) -> bool {
+    !info.is_internal() && info.osi_status != OsiStatus::Approved
+}
+
+/// List the dependencies that break `--osi-only`, on stderr like the other policy notices.
+pub fn print_osi_only_violations(violations: &[LicenseInfo]) {
+    if violations.is_empty() {
+        return;
+    }
+
+    eprintln!(
+        "{} {}",
+        "❌".red().bold(),
+        format!(
+            "--osi-only: {} dependenc{} without an OSI-approved license",
+            violations.len(),
+            if violations.len() == 1 { "y" } else { "ies" }
+        )
+        .red()
+        .bold()
+    );
+    for info in violations {
+        eprintln!(
+            "  • {} {} ({}, OSI {})",
+            info.name().bold(),
+            info.version(),
+            info.get_license(),
+            info.osi_status()
+        );
+    }
+    eprintln!();
+}
+
+/// List project-level license conflicts between dependencies.
+///
+/// Written to stderr like the parse warnings. Prints nothing when the licenses are consistent.
+pub fn print_license_conflicts(conflicts: &[LicenseConflict]) {
+    if conflicts.is_empty() {
+        return;
+    }
+
+    eprintln!(
+        "{} {}",
+        "❌".red().bold(),
+        format!(
+            "License conflicts: {} pair{} of dependency licenses cannot be combined",
+            conflicts.len(),
+            if conflicts.len() == 1 { "" } else { "s" }
+        )
+        .red()
+        .bold()
+    );
+    for conflict in conflicts {
+        eprintln!("  • {conflict}");
+    }
+    eprintln!();
+}
+
+/// Tell the reader the scan stopped early and the report only covers part of it.
+///
+/// Like the parse warnings this goes to stderr, so JSON/YAML on stdout stays valid.
+pub fn print_incomplete_notice(reason: Option<&str>) {
+    let Some(reason) = reason else {
+        return;
+    };
+    eprintln!(
+        "{} {}\n",
+        "⏹️".yellow().bold(),
+        format!("Incomplete results: scan {reason}; unfetched licenses are reported as unknown")
+            .yellow()
+            .bold()
+    );
+}
+
+/// Print the manifests that could not be parsed during the scan.
+///
+/// Written to stderr so it never corrupts JSON/YAML on stdout. Prints nothing when
+/// every manifest parsed cleanly.
+pub fn print_parse_warnings(warnings: &[ParseWarning]) {
+    if warnings.is_empty() {
+        return;
     }
 
-    (has_restrictive, has_incompatible)
+    eprintln!(
+        "{} {}",
+        "⚠️".yellow().bold(),
+        format!(
+            "Warnings: {} manifest{} could not be parsed (use --strict-parse to fail instead)",
+            warnings.len(),
+            if warnings.len() == 1 { "" } else { "s" }
+        )
+        .yellow()
+        .bold()
+    );
+    for warning in warnings {
+        eprintln!("  • {}: {}", warning.file.as_str().bold(), warning.reason);
+    }
+    eprintln!();
 }
 
 fn print_verbose_table(
@@ -298,6 +772,8 @@ fn print_verbose_table(
     log(LogLevel::Info, "Printing verbose table");
 
     let has_workspace = license_info.iter().any(|i| i.sub_project().is_some());
+    let has_provenance = license_info.iter().any(|i| !i.found_in().is_empty());
+    let has_update_policy = license_info.iter().any(|i| i.update_policy().is_some());
 
     let mut headers = vec![
         "Name".to_string(),
@@ -318,6 +794,14 @@ fn print_verbose_table(
         headers.push("Sub-project".to_string());
     }
 
+    if has_provenance {
+        headers.push("Found in".to_string());
+    }
+
+    if has_update_policy {
+        headers.push("Updates".to_string());
+    }
+
     let mut formatter = TableFormatter::new(headers);
 
     let rows: Vec<_> = license_info
@@ -326,7 +810,10 @@ fn print_verbose_table(
             let mut row = vec![
                 info.name().to_string(),
                 info.version().to_string(),
-                info.get_license(),
+                match info.inherited_from() {
+                    Some(upstream) => format!("{} (from {upstream})", info.get_license()),
+                    None => info.get_license(),
+                },
                 info.is_restrictive().to_string(),
             ];
 
@@ -342,6 +829,17 @@ fn print_verbose_table(
                 row.push(info.sub_project().unwrap_or("-").to_string());
             }
 
+            if has_provenance {
+                row.push(info.found_in().join(", "));
+            }
+
+            if has_update_policy {
+                row.push(
+                    info.update_policy()
+                        .map_or("-".to_string(), |policy| policy.to_string()),
+                );
+            }
+
             row
         })
         .collect();
@@ -482,7 +980,9 @@ fn print_summary_table(
         format!("Total dependencies scanned: {total_packages}").bold()
     );
 
+    print_ecosystem_breakdown(license_info);
     print_workspace_breakdown(license_info);
+    print_shared_dependencies(license_info);
 
     if !restrictive_licenses.is_empty() {
         print_restrictive_licenses_table(&restrictive_licenses);
@@ -506,6 +1006,68 @@ fn print_summary_table(
     }
 }
 
+/// Widest bar in the license distribution chart, in characters
+const CHART_WIDTH: usize = 40;
+
+/// (license, dependency count, bar length) for each license, most common first
+fn license_chart_rows(license_info: &[LicenseInfo]) -> Vec<(String, usize, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for info in license_info {
+        *counts.entry(info.get_license()).or_default() += 1;
+    }
+
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let max = counts.first().map(|(_, count)| *count).unwrap_or(0);
+    counts
+        .into_iter()
+        .map(|(license, count)| {
+            // Every license gets at least one block so rare ones stay visible
+            let bar = (count * CHART_WIDTH).div_ceil(max.max(1)).max(1);
+            (license, count, bar)
+        })
+        .collect()
+}
+
+/// Print an ASCII bar chart of how many dependencies use each license
+fn print_license_chart(license_info: &[LicenseInfo]) {
+    let rows = license_chart_rows(license_info);
+    if rows.is_empty() {
+        return;
+    }
+
+    let restrictive: std::collections::HashSet<String> = license_info
+        .iter()
+        .filter(|info| *info.is_restrictive())
+        .map(LicenseInfo::get_license)
+        .collect();
+    let label_width = rows
+        .iter()
+        .map(|(license, _, _)| license.len())
+        .max()
+        .unwrap_or(0);
+    let total = license_info.len();
+
+    println!(
+        "\n{} {}\n",
+        "📊".bold(),
+        "License Distribution".bold().underline()
+    );
+    for (license, count, bar) in rows {
+        let bar_text = "█".repeat(bar);
+        let bar_text = if restrictive.contains(&license) {
+            bar_text.red()
+        } else {
+            bar_text.green()
+        };
+        println!(
+            "  {license:<label_width$} {bar_text} {count} ({:.0}%)",
+            count as f64 * 100.0 / total as f64
+        );
+    }
+}
+
 /// Print a breakdown of dep counts per workspace member when the scan covers a monorepo.
 /// Silent for single-project scans.
 fn print_workspace_breakdown(license_info: &[LicenseInfo]) {
@@ -535,6 +1097,139 @@ fn print_workspace_breakdown(license_info: &[LicenseInfo]) {
     }
 }
 
+/// Print how many dependencies were deduplicated across project roots. The per-root list is
+/// shown in the verbose table's "Found in" column. Silent for single-root scans.
+fn print_shared_dependencies(license_info: &[LicenseInfo]) {
+    let shared = license_info
+        .iter()
+        .filter(|info| info.found_in().len() > 1)
+        .count();
+
+    if shared == 0 {
+        return;
+    }
+
+    println!(
+        "\n{} {} {}",
+        "🔗".bold(),
+        format!(
+            "{shared} dependenc{} shared across project roots, reported once.",
+            if shared == 1 { "y" } else { "ies" }
+        )
+        .bold(),
+        "Use --verbose to see where each one was found.".dimmed()
+    );
+}
+
+/// Dependency and violation counts for one ecosystem (`rust`, `node`, ...)
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct EcosystemCount {
+    pub ecosystem: String,
+    pub dependencies: usize,
+    pub restrictive: usize,
+    pub incompatible: usize,
+}
+
+/// Per-ecosystem counts, largest first. Dependencies no project root reported are left out.
+pub fn ecosystem_counts(license_info: &[LicenseInfo]) -> Vec<EcosystemCount> {
+    let mut by_ecosystem: HashMap<&str, EcosystemCount> = HashMap::new();
+    for info in license_info {
+        let Some(ecosystem) = info.ecosystem() else {
+            continue;
+        };
+        let count = by_ecosystem
+            .entry(ecosystem)
+            .or_insert_with(|| EcosystemCount {
+                ecosystem: ecosystem.to_string(),
+                dependencies: 0,
+                restrictive: 0,
+                incompatible: 0,
+            });
+        count.dependencies += 1;
+        count.restrictive += usize::from(info.is_restrictive);
+        count.incompatible += usize::from(info.compatibility == LicenseCompatibility::Incompatible);
+    }
+
+    let mut counts: Vec<EcosystemCount> = by_ecosystem.into_values().collect();
+    counts.sort_by(|a, b| {
+        b.dependencies
+            .cmp(&a.dependencies)
+            .then_with(|| a.ecosystem.cmp(&b.ecosystem))
+    });
+    counts
+}
+
+/// How an ecosystem label reads in a summary: `Node`, `C++`, `.NET`
+fn ecosystem_display_name(ecosystem: &str) -> String {
+    match ecosystem {
+        "cpp" => "C++".to_string(),
+        "dotnet" => ".NET".to_string(),
+        "php" => "PHP".to_string(),
+        "wasm" => "WebAssembly".to_string(),
+        _ => {
+            let mut chars = ecosystem.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+    }
+}
+
+/// `Node: 540, Rust: 120 (3 restrictive), Go: 80`
+fn ecosystem_summary_line(counts: &[EcosystemCount]) -> String {
+    counts
+        .iter()
+        .map(|count| {
+            let mut flagged = Vec::new();
+            if count.restrictive > 0 {
+                flagged.push(format!("{} restrictive", count.restrictive));
+            }
+            if count.incompatible > 0 {
+                flagged.push(format!("{} incompatible", count.incompatible));
+            }
+            let name = ecosystem_display_name(&count.ecosystem);
+            if flagged.is_empty() {
+                format!("{name}: {}", count.dependencies)
+            } else {
+                format!("{name}: {} ({})", count.dependencies, flagged.join(", "))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Print dependency counts per ecosystem so polyglot scans show where license debt lives.
+/// Silent for single-ecosystem scans.
+fn print_ecosystem_breakdown(license_info: &[LicenseInfo]) {
+    let counts = ecosystem_counts(license_info);
+    if counts.len() < 2 {
+        return;
+    }
+
+    println!("\n{} {}", "🗂️".bold(), "By ecosystem:".bold().underline());
+    for count in &counts {
+        let mut line = format!(
+            "  • {} {}",
+            count.dependencies.to_string().cyan().bold(),
+            ecosystem_display_name(&count.ecosystem)
+        );
+        if count.restrictive > 0 {
+            line.push_str(&format!(
+                ", {}",
+                format!("{} restrictive", count.restrictive).yellow()
+            ));
+        }
+        if count.incompatible > 0 {
+            line.push_str(&format!(
+                ", {}",
+                format!("{} incompatible", count.incompatible).red()
+            ));
+        }
+        println!("{line}");
+    }
+}
+
 fn print_restrictive_licenses_table(restrictive_licenses: &[&LicenseInfo]) {
     log(
         LogLevel::Info,
@@ -634,40 +1329,313 @@ fn print_incompatible_licenses_table(
     println!("{}\n", formatter.render_footer());
 }
 
-fn print_summary_footer(license_info: &[LicenseInfo], project_license: Option<&str>) {
-    log(LogLevel::Info, "Printing summary footer");
-
-    let total = license_info.len();
-    let restrictive_count = license_info.iter().filter(|i| *i.is_restrictive()).count();
-    let permissive_count = total - restrictive_count;
+/// Table of remediations for the restrictive/incompatible dependencies that have any
+fn print_fix_suggestions(license_info: &[LicenseInfo]) {
+    let with_suggestions: Vec<&LicenseInfo> = license_info
+        .iter()
+        .filter(|info| !info.suggestions().is_empty())
+        .collect();
+    if with_suggestions.is_empty() {
+        return;
+    }
 
-    // Calculate compatibility counts if project license is available
-    let (compatible_count, incompatible_count, unknown_count) = if project_license.is_some() {
-        (
-            license_info
-                .iter()
-                .filter(|i| i.compatibility == LicenseCompatibility::Compatible)
-                .count(),
-            license_info
-                .iter()
-                .filter(|i| i.compatibility == LicenseCompatibility::Incompatible)
-                .count(),
-            license_info
-                .iter()
-                .filter(|i| i.compatibility == LicenseCompatibility::Unknown)
-                .count(),
-        )
-    } else {
-        (0, 0, 0)
-    };
+    log(
+        LogLevel::Info,
+        &format!(
+            "Printing fix suggestions for {} dependencies",
+            with_suggestions.len()
+        ),
+    );
 
-    println!("{}", "🔍 License Summary:".bold());
     println!(
-        "  • {} {}",
-        permissive_count.to_string().green().bold(),
-        "permissive licenses".green()
+        "\n{} {}\n",
+        "💡".bold(),
+        "Suggested fixes".bold().underline()
     );
-    println!(
+
+    let headers = vec![
+        "Package".to_string(),
+        "Version".to_string(),
+        "License".to_string(),
+        "Suggestion".to_string(),
+    ];
+    let mut formatter = TableFormatter::new(headers);
+
+    let rows: Vec<_> = with_suggestions
+        .iter()
+        .map(|info| {
+            let suggestions: Vec<String> = info
+                .suggestions()
+                .iter()
+                .map(|suggestion| suggestion.to_string())
+                .collect();
+            vec![
+                info.name().to_string(),
+                info.version().to_string(),
+                info.get_license(),
+                suggestions.join("; "),
+            ]
+        })
+        .collect();
+
+    for row in &rows {
+        formatter.add_row(row);
+    }
+
+    println!("{}", formatter.render_header());
+    for row in &rows {
+        println!("{}", formatter.render_row(row, false));
+    }
+    println!("{}\n", formatter.render_footer());
+}
+
+/// Direct dependencies listed in the "Top offenders" table
+const TOP_OFFENDERS: usize = 10;
+
+/// A direct dependency and the restrictive or incompatible packages its tree pulls in
+#[derive(Debug, PartialEq)]
+struct Offender {
+    name: String,
+    /// `name@version` of each flagged package below it
+    flagged: Vec<String>,
+    /// How many of those no other direct dependency pulls in: removing it drops them
+    sole: usize,
+}
+
+/// Direct dependencies ranked by the flagged transitive packages removing them would drop,
+/// then by all the flagged packages they pull in
+fn top_offenders(license_info: &[LicenseInfo]) -> Vec<Offender> {
+    let mut by_direct: HashMap<&str, Offender> = HashMap::new();
+    for info in license_info {
+        let flagged =
+            info.is_restrictive || info.compatibility == LicenseCompatibility::Incompatible;
+        if !flagged {
+            continue;
+        }
+        let sole = info.introduced_by().len() == 1;
+        for direct in info.introduced_by() {
+            let offender = by_direct.entry(direct).or_insert_with(|| Offender {
+                name: direct.clone(),
+                flagged: Vec::new(),
+                sole: 0,
+            });
+            offender
+                .flagged
+                .push(format!("{}@{}", info.name, info.version));
+            offender.sole += usize::from(sole);
+        }
+    }
+
+    let mut offenders: Vec<Offender> = by_direct.into_values().collect();
+    for offender in &mut offenders {
+        offender.flagged.sort();
+    }
+    offenders.sort_by(|a, b| {
+        b.sole
+            .cmp(&a.sole)
+            .then_with(|| b.flagged.len().cmp(&a.flagged.len()))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    offenders
+}
+
+/// Rank the direct dependencies that bring in restrictive or incompatible transitive
+/// packages, so the highest-leverage removals come first. Silent when none do or the
+/// scan has no dependency graph.
+fn print_top_offenders(license_info: &[LicenseInfo]) {
+    let offenders = top_offenders(license_info);
+    if offenders.is_empty() {
+        return;
+    }
+
+    println!(
+        "\n{} {}\n",
+        "🎯".bold(),
+        "Top offenders: direct dependencies pulling in flagged licenses"
+            .bold()
+            .underline()
+    );
+
+    let headers = vec![
+        "Direct dependency".to_string(),
+        "Flagged".to_string(),
+        "Removed with it".to_string(),
+        "Packages".to_string(),
+    ];
+    let mut formatter = TableFormatter::new(headers);
+
+    let rows: Vec<Vec<String>> = offenders
+        .iter()
+        .take(TOP_OFFENDERS)
+        .map(|offender| {
+            let mut packages = offender.flagged.iter().take(3).cloned().collect::<Vec<_>>();
+            if offender.flagged.len() > 3 {
+                packages.push(format!("+{} more", offender.flagged.len() - 3));
+            }
+            vec![
+                offender.name.clone(),
+                offender.flagged.len().to_string(),
+                offender.sole.to_string(),
+                packages.join(", "),
+            ]
+        })
+        .collect();
+
+    for row in &rows {
+        formatter.add_row(row);
+    }
+
+    println!("{}", formatter.render_header());
+    for row in &rows {
+        println!("{}", formatter.render_row(row, false));
+    }
+    println!("{}", formatter.render_footer());
+    if offenders.len() > TOP_OFFENDERS {
+        println!(
+            "  {}",
+            format!(
+                "{} more direct dependencies pull in flagged packages",
+                offenders.len() - TOP_OFFENDERS
+            )
+            .dimmed()
+        );
+    }
+    println!();
+}
+
+/// Advisory ids listed per package before the rest are counted
+const LISTED_ADVISORIES: usize = 3;
+
+/// Rows of the "Known vulnerabilities" table: dependencies with OSV advisories, most first
+fn vulnerability_rows(license_info: &[LicenseInfo]) -> Vec<Vec<String>> {
+    let mut vulnerable: Vec<(&LicenseInfo, &[String])> = license_info
+        .iter()
+        .filter_map(|info| {
+            info.vulnerabilities()
+                .filter(|ids| !ids.is_empty())
+                .map(|ids| (info, ids))
+        })
+        .collect();
+    vulnerable.sort_by(|(a, a_ids), (b, b_ids)| {
+        b_ids
+            .len()
+            .cmp(&a_ids.len())
+            .then_with(|| a.name().cmp(b.name()))
+    });
+
+    vulnerable
+        .into_iter()
+        .map(|(info, ids)| {
+            let mut listed = ids
+                .iter()
+                .take(LISTED_ADVISORIES)
+                .cloned()
+                .collect::<Vec<_>>();
+            if ids.len() > LISTED_ADVISORIES {
+                listed.push(format!("+{} more", ids.len() - LISTED_ADVISORIES));
+            }
+            vec![
+                info.name().to_string(),
+                info.version().to_string(),
+                info.get_license(),
+                ids.len().to_string(),
+                listed.join(", "),
+            ]
+        })
+        .collect()
+}
+
+fn print_vulnerabilities(license_info: &[LicenseInfo]) {
+    let checked = license_info
+        .iter()
+        .filter(|info| info.vulnerabilities().is_some())
+        .count();
+    if checked == 0 {
+        return;
+    }
+
+    let rows = vulnerability_rows(license_info);
+    if rows.is_empty() {
+        println!(
+            "\n{} {}\n",
+            "🛡️".bold(),
+            format!("No known vulnerabilities in {checked} dependencies checked on OSV.dev")
+                .green()
+        );
+        return;
+    }
+
+    log(
+        LogLevel::Info,
+        &format!("Printing {} vulnerable dependencies", rows.len()),
+    );
+    println!(
+        "\n{} {}\n",
+        "🛡️".bold(),
+        "Known vulnerabilities (OSV.dev)".bold().underline()
+    );
+
+    let headers = vec![
+        "Package".to_string(),
+        "Version".to_string(),
+        "License".to_string(),
+        "Advisories".to_string(),
+        "Ids".to_string(),
+    ];
+    let mut formatter = TableFormatter::new(headers);
+    for row in &rows {
+        formatter.add_row(row);
+    }
+
+    println!("{}", formatter.render_header());
+    for row in &rows {
+        println!("{}", formatter.render_row(row, false));
+    }
+    println!("{}", formatter.render_footer());
+    println!(
+        "  {}\n",
+        format!(
+            "{} of {checked} checked dependencies have known vulnerabilities",
+            rows.len()
+        )
+        .dimmed()
+    );
+}
+
+fn print_summary_footer(license_info: &[LicenseInfo], project_license: Option<&str>) {
+    log(LogLevel::Info, "Printing summary footer");
+
+    let total = license_info.len();
+    let restrictive_count = license_info.iter().filter(|i| *i.is_restrictive()).count();
+    let permissive_count = total - restrictive_count;
+
+    // Calculate compatibility counts if project license is available
+    let (compatible_count, incompatible_count, unknown_count) = if project_license.is_some() {
+        (
+            license_info
+                .iter()
+                .filter(|i| i.compatibility == LicenseCompatibility::Compatible)
+                .count(),
+            license_info
+                .iter()
+                .filter(|i| i.compatibility == LicenseCompatibility::Incompatible)
+                .count(),
+            license_info
+                .iter()
+                .filter(|i| i.compatibility == LicenseCompatibility::Unknown)
+                .count(),
+        )
+    } else {
+        (0, 0, 0)
+    };
+
+    println!("{}", "🔍 License Summary:".bold());
+    println!(
+        "  • {} {}",
+        permissive_count.to_string().green().bold(),
+        "permissive licenses".green()
+    );
+    println!(
         "  • {} {}",
         restrictive_count.to_string().yellow().bold(),
         "restrictive licenses".yellow()
@@ -692,7 +1660,65 @@ fn print_summary_footer(license_info: &[LicenseInfo], project_license: Option<&s
         );
     }
 
+    let internal_count = license_info.iter().filter(|i| i.is_internal()).count();
+    if internal_count > 0 {
+        println!(
+            "  • {} {}",
+            internal_count.to_string().cyan().bold(),
+            "internal packages".cyan()
+        );
+    }
+
+    let unclassified_refs = license_info
+        .iter()
+        .filter(|i| {
+            i.license
+                .as_deref()
+                .is_some_and(crate::licenses::is_unclassified_license_ref)
+        })
+        .count();
+    if unclassified_refs > 0 {
+        println!(
+            "  • {} {} {}",
+            unclassified_refs.to_string().magenta().bold(),
+            "unclassified LicenseRef licenses".magenta(),
+            "(classify them in [licenses.refs])".dimmed()
+        );
+    }
+
+    let special_count = |value: SpdxSpecialValue| {
+        license_info
+            .iter()
+            .filter(|i| {
+                i.license
+                    .as_deref()
+                    .and_then(crate::licenses::spdx_special_value)
+                    == Some(value)
+            })
+            .count()
+    };
+    let noassertion_count = special_count(SpdxSpecialValue::NoAssertion);
+    if noassertion_count > 0 {
+        println!(
+            "  • {} {}",
+            noassertion_count.to_string().blue().bold(),
+            "NOASSERTION (license not determined)".blue()
+        );
+    }
+    let none_count = special_count(SpdxSpecialValue::None);
+    if none_count > 0 {
+        println!(
+            "  • {} {}",
+            none_count.to_string().red().bold(),
+            "NONE (explicitly unlicensed)".red()
+        );
+    }
+
     println!("  • {total} total dependencies");
+    let ecosystems = ecosystem_counts(license_info);
+    if ecosystems.len() > 1 {
+        println!("    {}", ecosystem_summary_line(&ecosystems).dimmed());
+    }
 
     if restrictive_count > 0 {
         println!("\n{} {}: Review these dependencies for compliance with your project's licensing requirements.",
@@ -725,6 +1751,10 @@ fn output_github_format(
     license_info: &[LicenseInfo],
     output_path: Option<&str>,
     project_license: Option<&str>,
+    warnings: &[ParseWarning],
+    incomplete: Option<&str>,
+    resolution_errors: &[ResolutionError],
+    conflicts: &[LicenseConflict],
 ) {
     log(
         LogLevel::Info,
@@ -745,7 +1775,8 @@ fn output_github_format(
     for info in license_info {
         if *info.is_restrictive() {
             let warning = format!(
-                "::warning title=Restrictive License::Dependency '{}@{}' has restrictive license: {}\n",
+                "::warning {}title=Restrictive License::Dependency '{}@{}' has restrictive license: {}\n",
+                github_location(info),
                 info.name(),
                 info.version(),
                 info.get_license()
@@ -762,7 +1793,8 @@ fn output_github_format(
         if let Some(license) = project_license {
             if info.compatibility == LicenseCompatibility::Incompatible {
                 let warning = format!(
-                    "::error title=Incompatible License::Dependency '{}@{}' has license {} which may be incompatible with project license {}\n",
+                    "::error {}title=Incompatible License::Dependency '{}@{}' has license {} which may be incompatible with project license {}\n",
+                    github_location(info),
                     info.name(),
                     info.version(),
                     info.get_license(),
@@ -805,6 +1837,38 @@ fn output_github_format(
 
     output.push_str(&summary);
 
+    let ecosystems = ecosystem_counts(license_info);
+    if !ecosystems.is_empty() {
+        output.push_str(&format!(
+            "::notice title=Dependencies by Ecosystem::{}\n",
+            ecosystem_summary_line(&ecosystems)
+        ));
+    }
+
+    for conflict in conflicts {
+        output.push_str(&format!("::error title=License Conflict::{conflict}\n"));
+    }
+
+    for warning in warnings {
+        output.push_str(&format!(
+            "::warning file={},title=Manifest Parse Warning::{}\n",
+            warning.file, warning.reason
+        ));
+    }
+
+    for (source, count) in resolution_error_counts(resolution_errors) {
+        output.push_str(&format!(
+            "::warning title=Resolution Errors::{count} lookup{} to {source} failed; affected licenses may show as unknown\n",
+            if count == 1 { "" } else { "s" }
+        ));
+    }
+
+    if let Some(reason) = incomplete {
+        output.push_str(&format!(
+            "::error title=Scan Incomplete::License scan {reason}; results are partial\n"
+        ));
+    }
+
     log(
         LogLevel::Info,
         &format!(
@@ -822,7 +1886,7 @@ fn output_github_format(
             &format!("Writing GitHub Actions output to file: {path}"),
         );
 
-        match fs::write(path, &output) {
+        match report_archive::write_report_file(Path::new(path), output.as_bytes()) {
             Ok(_) => println!("GitHub Actions output written to: {path}"),
             Err(err) => {
                 log_error(
@@ -839,10 +1903,51 @@ fn output_github_format(
     }
 }
 
+/// `file=...,line=...,` parameters for a GitHub workflow command, empty when the
+/// dependency's declaration is unknown (e.g. transitive dependencies)
+fn github_location(info: &LicenseInfo) -> String {
+    info.declared_in()
+        .map(|location| format!("file={},line={},", location.file, location.line))
+        .unwrap_or_default()
+}
+
+/// SARIF `locations` pointing at the manifest line that declares the dependency
+fn sarif_locations(info: &LicenseInfo) -> serde_json::Value {
+    match info.declared_in() {
+        Some(location) => serde_json::json!([{
+            "physicalLocation": {
+                "artifactLocation": { "uri": location.file },
+                "region": { "startLine": location.line }
+            }
+        }]),
+        None => serde_json::json!([]),
+    }
+}
+
+/// SARIF result `properties` carrying the dependency's fix suggestions
+fn sarif_properties(info: &LicenseInfo) -> serde_json::Value {
+    serde_json::json!({ "suggestions": info.suggestions() })
+}
+
+/// Escape text for inclusion in XML attribute values and element content
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[allow(clippy::too_many_arguments)]
 fn output_jenkins_format(
     license_info: &[LicenseInfo],
     output_path: Option<&str>,
     project_license: Option<&str>,
+    warnings: &[ParseWarning],
+    incomplete: Option<&str>,
+    resolution_errors: &[ResolutionError],
+    conflicts: &[LicenseConflict],
+    metadata: Option<&ReportMetadata>,
 ) {
     log(
         LogLevel::Info,
@@ -918,11 +2023,22 @@ fn output_jenkins_format(
                 r#"    <testcase classname="feluda.licenses" name="{test_case_name}" time="0" />"#
             ));
         } else {
+            let location = info
+                .declared_in()
+                .map(|location| {
+                    format!(
+                        r#" file="{}" line="{}""#,
+                        xml_escape(&location.file),
+                        location.line
+                    )
+                })
+                .unwrap_or_default();
             test_cases.push(format!(
-                r#"    <testcase classname="feluda.licenses" name="{}" time="0">
+                r#"    <testcase classname="feluda.licenses" name="{}" time="0"{}>
 {}
     </testcase>"#,
                 test_case_name,
+                location,
                 failures.join("\n")
             ));
         }
@@ -938,7 +2054,53 @@ fn output_jenkins_format(
         0
     };
 
-    let failure_count = restrictive_count + incompatible_count;
+    // Project-level conflicts fail like incompatible dependencies
+    for conflict in conflicts {
+        test_cases.push(format!(
+            r#"    <testcase classname="feluda.conflicts" name="{} + {}" time="0">
+        <failure message="License conflict" type="conflict">{}</failure>
+    </testcase>"#,
+            xml_escape(&conflict.copyleft),
+            xml_escape(&conflict.conflicting),
+            xml_escape(&conflict.to_string())
+        ));
+    }
+
+    let failure_count = restrictive_count + incompatible_count + conflicts.len();
+
+    // Unparsed manifests are reported as skipped test cases: visible in Jenkins without
+    // failing the build on their own (`--strict-parse` is the way to make them fatal).
+    for warning in warnings {
+        test_cases.push(format!(
+            r#"    <testcase classname="feluda.manifests" name="{}" time="0">
+        <skipped message="Manifest could not be parsed">{}</skipped>
+    </testcase>"#,
+            xml_escape(&warning.file),
+            xml_escape(&warning.reason)
+        ));
+    }
+
+    // Failed lookups are skipped cases too, one per source
+    let resolution_counts = resolution_error_counts(resolution_errors);
+    for (source, count) in &resolution_counts {
+        test_cases.push(format!(
+            r#"    <testcase classname="feluda.resolution" name="{}" time="0">
+        <skipped message="Registry lookups failed">{count} lookup{} failed; affected licenses may show as unknown</skipped>
+    </testcase>"#,
+            xml_escape(source),
+            if *count == 1 { "" } else { "s" }
+        ));
+    }
+
+    if let Some(reason) = incomplete {
+        test_cases.push(format!(
+            r#"    <testcase classname="feluda.scan" name="complete" time="0">
+        <error message="Scan incomplete">License scan {}; results are partial</error>
+    </testcase>"#,
+            xml_escape(reason)
+        ));
+    }
+    let error_count = usize::from(incomplete.is_some());
 
     log(
         LogLevel::Info,
@@ -949,15 +2111,56 @@ fn output_jenkins_format(
         ),
     );
 
+    // Per-ecosystem counts ride along as suite properties, which Jenkins shows with the suite
+    let properties: String = ecosystem_counts(license_info)
+        .iter()
+        .map(|count| {
+            format!(
+                "      <property name=\"ecosystem.{0}.dependencies\" value=\"{1}\"/>\n      <property name=\"ecosystem.{0}.restrictive\" value=\"{2}\"/>\n      <property name=\"ecosystem.{0}.incompatible\" value=\"{3}\"/>\n",
+                xml_escape(&count.ecosystem),
+                count.dependencies,
+                count.restrictive,
+                count.incompatible
+            )
+        })
+        .collect();
+    // So does the scan's metadata
+    let properties: String = metadata
+        .map(ReportMetadata::properties)
+        .unwrap_or_default()
+        .iter()
+        .map(|(name, value)| {
+            format!(
+                "      <property name=\"{}\" value=\"{}\"/>\n",
+                xml_escape(name),
+                xml_escape(value)
+            )
+        })
+        .chain(std::iter::once(properties))
+        .collect();
+    let properties = if properties.is_empty() {
+        properties
+    } else {
+        format!("    <properties>\n{properties}    </properties>\n")
+    };
+
     let junit_xml = format!(
         r#"<?xml version="1.0" encoding="UTF-8"?>
 <testsuites>
-  <testsuite name="Feluda License Check" tests="{}" failures="{}" errors="0" skipped="0">
-{}
+  <testsuite name="Feluda License Check" tests="{}" failures="{}" errors="{}" skipped="{}">
+{}{}
   </testsuite>
 </testsuites>"#,
-        license_info.len() + (if project_license.is_some() { 1 } else { 0 }),
+        license_info.len()
+            + (if project_license.is_some() { 1 } else { 0 })
+            + conflicts.len()
+            + warnings.len()
+            + resolution_counts.len()
+            + error_count,
         failure_count,
+        error_count,
+        warnings.len() + resolution_counts.len(),
+        properties,
         test_cases.join("\n")
     );
 
@@ -968,7 +2171,7 @@ fn output_jenkins_format(
             &format!("Writing Jenkins JUnit XML to file: {path}"),
         );
 
-        match fs::write(path, &junit_xml) {
+        match report_archive::write_report_file(Path::new(path), junit_xml.as_bytes()) {
             Ok(_) => println!("Jenkins JUnit XML output written to: {path}"),
             Err(err) => {
                 log_error(
@@ -985,10 +2188,16 @@ fn output_jenkins_format(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn output_sarif_format(
     license_info: &[LicenseInfo],
     output_path: Option<&str>,
     project_license: Option<&str>,
+    warnings: &[ParseWarning],
+    incomplete: Option<&str>,
+    resolution_errors: &[ResolutionError],
+    conflicts: &[LicenseConflict],
+    metadata: Option<&ReportMetadata>,
 ) {
     log(LogLevel::Info, "Generating SARIF 2.1.0 output");
 
@@ -1031,7 +2240,8 @@ fn output_sarif_format(
                         info.name(), info.version(), info.get_license()
                     )
                 },
-                "locations": []
+                "locations": sarif_locations(info),
+                "properties": sarif_properties(info)
             }));
 
             log(
@@ -1054,7 +2264,8 @@ fn output_sarif_format(
                             info.name(), info.version(), info.get_license(), proj_license
                         )
                     },
-                    "locations": []
+                    "locations": sarif_locations(info),
+                    "properties": sarif_properties(info)
                 }));
 
                 log(
@@ -1068,6 +2279,28 @@ fn output_sarif_format(
         }
     }
 
+    if !conflicts.is_empty() {
+        rules.push(serde_json::json!({
+            "id": "feluda/license-conflict",
+            "name": "LicenseConflict",
+            "shortDescription": { "text": "Dependency licenses conflict with each other" },
+            "fullDescription": {
+                "text": "A copyleft dependency license covers the combined work, and another dependency's license is incompatible with it, so the dependencies cannot be distributed together."
+            },
+            "helpUri": "https://github.com/anistark/feluda",
+            "defaultConfiguration": { "level": "error" }
+        }));
+    }
+    for conflict in conflicts {
+        results.push(serde_json::json!({
+            "ruleId": "feluda/license-conflict",
+            "level": "error",
+            "message": { "text": conflict.to_string() },
+            "locations": [],
+            "properties": conflict
+        }));
+    }
+
     log(
         LogLevel::Info,
         &format!(
@@ -1077,7 +2310,38 @@ fn output_sarif_format(
         ),
     );
 
-    let sarif = serde_json::json!({
+    // Manifests that failed to parse are tool-execution problems rather than findings.
+    let mut notifications: Vec<serde_json::Value> = warnings
+        .iter()
+        .map(|warning| {
+            serde_json::json!({
+                "level": "warning",
+                "message": { "text": warning.reason },
+                "locations": [{
+                    "physicalLocation": { "artifactLocation": { "uri": warning.file } }
+                }]
+            })
+        })
+        .collect();
+    for (source, count) in resolution_error_counts(resolution_errors) {
+        notifications.push(serde_json::json!({
+            "level": "warning",
+            "message": {
+                "text": format!(
+                    "{count} lookup{} to {source} failed; affected licenses may show as unknown",
+                    if count == 1 { "" } else { "s" }
+                )
+            }
+        }));
+    }
+    if let Some(reason) = incomplete {
+        notifications.push(serde_json::json!({
+            "level": "error",
+            "message": { "text": format!("License scan {reason}; results are partial") }
+        }));
+    }
+
+    let mut sarif = serde_json::json!({
         "$schema": "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/master/Schemata/sarif-schema-2.1.0.json",
         "version": "2.1.0",
         "runs": [{
@@ -1089,9 +2353,17 @@ fn output_sarif_format(
                     "rules": rules
                 }
             },
-            "results": results
+            "invocations": [{
+                "executionSuccessful": incomplete.is_none(),
+                "toolExecutionNotifications": notifications
+            }],
+            "results": results,
+            "properties": { "ecosystems": ecosystem_counts(license_info) }
         }]
     });
+    if let Some(metadata) = metadata {
+        sarif["runs"][0]["properties"]["metadata"] = serde_json::json!(metadata);
+    }
 
     let output = match serde_json::to_string_pretty(&sarif) {
         Ok(s) => s,
@@ -1107,7 +2379,7 @@ fn output_sarif_format(
             LogLevel::Info,
             &format!("Writing SARIF output to file: {path}"),
         );
-        match fs::write(path, &output) {
+        match report_archive::write_report_file(Path::new(path), output.as_bytes()) {
             Ok(_) => println!("SARIF output written to: {path}"),
             Err(err) => {
                 log_error(&format!("Failed to write SARIF output file: {path}"), &err);
@@ -1121,6 +2393,525 @@ fn output_sarif_format(
     }
 }
 
+/// Version of GitLab's license scanning report schema the report follows
+const GITLAB_REPORT_VERSION: &str = "2.1";
+
+/// GitLab's `package_manager` for a project root type
+fn gitlab_package_manager(ecosystem: Option<&str>) -> &str {
+    match ecosystem {
+        Some("rust") => "cargo",
+        Some("node") => "npm",
+        Some("python") => "pip",
+        Some("go") => "go",
+        Some("java") => "maven",
+        Some("android") => "gradle",
+        Some("dotnet") => "nuget",
+        Some("ruby") => "bundler",
+        Some("php") => "composer",
+        Some("elixir") => "mix",
+        Some("c" | "cpp") => "conan",
+        Some(ecosystem) => ecosystem,
+        None => "unknown",
+    }
+}
+
+/// GitLab license scanning report (`gl-license-scanning-report.json`) of the dependencies
+///
+/// A compound expression lists each of its licenses, which is how GitLab's own scanner reports
+/// them; a license that couldn't be found is GitLab's `unknown`.
+fn gitlab_license_report(license_info: &[LicenseInfo]) -> serde_json::Value {
+    let mut licenses: BTreeMap<String, serde_json::Value> = BTreeMap::new();
+    let dependencies: Vec<serde_json::Value> = license_info
+        .iter()
+        .map(|info| {
+            let mut ids: Vec<String> = Vec::new();
+            if let Some(license) = info.license.clone().and_then(known_license) {
+                for id in crate::spdx::parse(&license).license_ids() {
+                    if !ids.contains(&id) {
+                        ids.push(id);
+                    }
+                }
+            }
+            if ids.is_empty() {
+                ids.push("unknown".to_string());
+            }
+            for id in &ids {
+                licenses
+                    .entry(id.clone())
+                    .or_insert_with(|| match id.as_str() {
+                        "unknown" => serde_json::json!({ "id": "unknown", "name": "unknown" }),
+                        id => serde_json::json!({
+                            "id": id,
+                            "name": id,
+                            "url": format!("https://spdx.org/licenses/{id}.html"),
+                        }),
+                    });
+            }
+
+            let path = match info.declared_in() {
+                Some(location) => location.file.clone(),
+                None => info
+                    .ecosystem()
+                    .and_then(|ecosystem| crate::manifest::declaring_manifests(ecosystem).first())
+                    .map(|manifest| manifest.to_string())
+                    .unwrap_or_default(),
+            };
+            serde_json::json!({
+                "name": info.name(),
+                "version": info.version(),
+                "package_manager": gitlab_package_manager(info.ecosystem()),
+                "path": path,
+                "licenses": ids,
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "version": GITLAB_REPORT_VERSION,
+        "licenses": licenses.into_values().collect::<Vec<_>>(),
+        "dependencies": dependencies,
+    })
+}
+
+fn output_gitlab_format(license_info: &[LicenseInfo], output_path: Option<&str>) {
+    log(LogLevel::Info, "Generating GitLab license scanning report");
+
+    let output = match serde_json::to_string_pretty(&gitlab_license_report(license_info)) {
+        Ok(s) => s,
+        Err(err) => {
+            log_error("Failed to serialize GitLab report", &err);
+            println!("Error: Failed to generate GitLab license scanning report");
+            return;
+        }
+    };
+
+    if let Some(path) = output_path {
+        log(
+            LogLevel::Info,
+            &format!("Writing GitLab report to file: {path}"),
+        );
+        match report_archive::write_report_file(Path::new(path), output.as_bytes()) {
+            Ok(_) => println!("GitLab license scanning report written to: {path}"),
+            Err(err) => {
+                log_error(&format!("Failed to write GitLab report file: {path}"), &err);
+                println!("Error: Failed to write GitLab report file");
+                println!("{output}");
+            }
+        }
+    } else {
+        log(LogLevel::Info, "Writing GitLab report to stdout");
+        println!("{output}");
+    }
+}
+
+const HTML_STYLE: &str = r#"
+:root { --ok: #1a7f37; --bad: #cf222e; --warn: #9a6700; --muted: #656d76; --line: #d0d7de; }
+* { box-sizing: border-box; }
+body { margin: 0; padding: 2rem; font: 14px/1.5 -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; color: #1f2328; background: #f6f8fa; }
+main { max-width: 1200px; margin: 0 auto; }
+h1 { margin: 0 0 0.25rem; font-size: 1.6rem; }
+h2 { margin: 2rem 0 0.75rem; font-size: 1.15rem; }
+.meta { color: var(--muted); margin: 0; }
+.banner { margin: 1rem 0; padding: 0.75rem 1rem; border-radius: 6px; background: #fff8c5; border: 1px solid #d4a72c; }
+.cards { display: grid; grid-template-columns: repeat(auto-fit, minmax(160px, 1fr)); gap: 0.75rem; margin-top: 1.5rem; }
+.card { background: #fff; border: 1px solid var(--line); border-radius: 6px; padding: 0.75rem 1rem; }
+.card .value { display: block; font-size: 1.6rem; font-weight: 600; }
+.card .label { color: var(--muted); }
+.card.bad .value { color: var(--bad); }
+.card.ok .value { color: var(--ok); }
+.chart { background: #fff; border: 1px solid var(--line); border-radius: 6px; padding: 1rem; }
+.bar-row { display: grid; grid-template-columns: minmax(120px, 220px) 1fr 90px; gap: 0.75rem; align-items: center; margin: 0.2rem 0; }
+.bar-row .name { overflow: hidden; text-overflow: ellipsis; white-space: nowrap; }
+.bar { height: 14px; border-radius: 3px; background: var(--ok); }
+.bar.restrictive { background: var(--bad); }
+.bar-row .count { color: var(--muted); text-align: right; }
+.pie-row { display: flex; flex-wrap: wrap; gap: 1.5rem; align-items: center; margin-bottom: 1rem; }
+.pie-row .legend { margin: 0; padding: 0; border: 0; background: none; list-style: none; }
+.swatch { display: inline-block; width: 0.8rem; height: 0.8rem; border-radius: 2px; margin-right: 0.4rem; vertical-align: middle; }
+.filters { display: flex; flex-wrap: wrap; gap: 0.75rem; align-items: center; margin-bottom: 0.75rem; }
+.filters input[type=search] { flex: 1; min-width: 220px; padding: 0.4rem 0.6rem; border: 1px solid var(--line); border-radius: 6px; }
+.filters select { padding: 0.35rem; border: 1px solid var(--line); border-radius: 6px; }
+table { width: 100%; border-collapse: collapse; background: #fff; border: 1px solid var(--line); }
+th, td { padding: 0.45rem 0.7rem; text-align: left; border-bottom: 1px solid var(--line); }
+th { background: #f6f8fa; cursor: pointer; user-select: none; white-space: nowrap; }
+th[aria-sort=ascending]::after { content: " \25B2"; }
+th[aria-sort=descending]::after { content: " \25BC"; }
+tr.restrictive td:first-child { border-left: 3px solid var(--warn); }
+.compat-compatible { color: var(--ok); font-weight: 600; }
+.compat-incompatible { color: var(--bad); font-weight: 600; }
+.compat-unknown { color: var(--muted); }
+tr.incompatible { background: #ffebe9; }
+ul { background: #fff; border: 1px solid var(--line); border-radius: 6px; padding: 0.75rem 2rem; }
+footer { margin-top: 2rem; color: var(--muted); }
+"#;
+
+const HTML_SCRIPT: &str = r#"
+(function () {
+  var table = document.getElementById("dependencies");
+  var body = table.tBodies[0];
+  var rows = Array.prototype.slice.call(body.rows);
+  var search = document.getElementById("search");
+  var compatibility = document.getElementById("compatibility");
+  var restrictive = document.getElementById("restrictive-only");
+  var shown = document.getElementById("shown");
+
+  function filter() {
+    var text = search.value.toLowerCase();
+    var count = 0;
+    rows.forEach(function (row) {
+      var visible = row.textContent.toLowerCase().indexOf(text) !== -1
+        && (!compatibility.value || row.dataset.compatibility === compatibility.value)
+        && (!restrictive.checked || row.dataset.restrictive === "true");
+      row.hidden = !visible;
+      if (visible) count++;
+    });
+    shown.textContent = count;
+  }
+
+  Array.prototype.forEach.call(table.tHead.rows[0].cells, function (header, column) {
+    header.addEventListener("click", function () {
+      var ascending = header.getAttribute("aria-sort") !== "ascending";
+      Array.prototype.forEach.call(table.tHead.rows[0].cells, function (cell) {
+        cell.removeAttribute("aria-sort");
+      });
+      header.setAttribute("aria-sort", ascending ? "ascending" : "descending");
+      rows.sort(function (a, b) {
+        var order = a.cells[column].textContent.localeCompare(
+          b.cells[column].textContent, undefined, { numeric: true, sensitivity: "base" });
+        return ascending ? order : -order;
+      });
+      rows.forEach(function (row) { body.appendChild(row); });
+    });
+  });
+
+  [search, compatibility, restrictive].forEach(function (control) {
+    control.addEventListener("input", filter);
+  });
+  filter();
+})();
+"#;
+
+/// Pie slice colors in the HTML report, reused in order when there are more licenses
+const PIE_COLORS: [&str; 8] = [
+    "#0969da", "#1a7f37", "#8250df", "#bf8700", "#cf222e", "#1b7c83", "#bc4c00", "#6e7781",
+];
+
+/// An inline SVG pie chart with a slice per license chart row, and its legend
+fn license_pie_chart(rows: &[(String, usize, usize)], total: usize) -> String {
+    // Angles as fractions of a turn, clockwise from the top of a unit circle
+    let point = |turn: f64| {
+        let angle = turn * std::f64::consts::TAU;
+        (angle.sin(), -angle.cos())
+    };
+
+    let mut slices = String::new();
+    let mut legend = String::new();
+    let mut start = 0.0;
+    for (index, (license, count, _)) in rows.iter().enumerate() {
+        let color = PIE_COLORS[index % PIE_COLORS.len()];
+        let fraction = *count as f64 / total.max(1) as f64;
+        let title = format!("<title>{} ({count})</title>", xml_escape(license));
+        if fraction >= 1.0 {
+            // An arc can't start and end at the same point
+            slices.push_str(&format!(
+                "<circle r=\"1\" fill=\"{color}\">{title}</circle>\n"
+            ));
+        } else {
+            let (x1, y1) = point(start);
+            let (x2, y2) = point(start + fraction);
+            slices.push_str(&format!(
+                "<path d=\"M 0 0 L {x1:.4} {y1:.4} A 1 1 0 {} 1 {x2:.4} {y2:.4} Z\" fill=\"{color}\">{title}</path>\n",
+                u8::from(fraction > 0.5)
+            ));
+        }
+        start += fraction;
+        legend.push_str(&format!(
+            "<li><span class=\"swatch\" style=\"background: {color}\"></span>{}</li>\n",
+            xml_escape(license)
+        ));
+    }
+
+    format!(
+        "<div class=\"pie-row\">\n<svg class=\"pie\" viewBox=\"-1 -1 2 2\" width=\"180\" height=\"180\" role=\"img\" aria-label=\"License distribution\">\n{slices}</svg>\n<ul class=\"legend\">\n{legend}</ul>\n</div>\n"
+    )
+}
+
+/// A standalone HTML page of the scan results, for people who won't run Feluda themselves
+///
+/// Everything the page needs is inline: the dependency table sorts and filters with a few lines
+/// of script, and the license charts are plain markup and SVG, so it can be mailed or attached
+/// to a ticket.
+fn html_report(
+    license_info: &[LicenseInfo],
+    project_name: &str,
+    project_license: Option<&str>,
+    incomplete: Option<&str>,
+    resolution_errors: &[ResolutionError],
+    conflicts: &[LicenseConflict],
+) -> String {
+    let restrictive_count = license_info.iter().filter(|i| *i.is_restrictive()).count();
+    let incompatible_count = license_info
+        .iter()
+        .filter(|i| i.compatibility == LicenseCompatibility::Incompatible)
+        .count();
+    let unlicensed_count = license_info.iter().filter(|i| i.license.is_none()).count();
+    let title = if project_name.is_empty() {
+        "License report".to_string()
+    } else {
+        format!("License report: {project_name}")
+    };
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str(&format!("<title>{}</title>\n", xml_escape(&title)));
+    html.push_str(&format!(
+        "<style>{HTML_STYLE}</style>\n</head>\n<body>\n<main>\n"
+    ));
+    html.push_str(&format!("<h1>{}</h1>\n", xml_escape(&title)));
+    html.push_str(&format!(
+        "<p class=\"meta\">Project license: <strong>{}</strong> &middot; Generated {} by Feluda {}</p>\n",
+        xml_escape(project_license.unwrap_or("Not detected")),
+        chrono::Utc::now().format("%Y-%m-%d %H:%M UTC"),
+        env!("CARGO_PKG_VERSION")
+    ));
+    if let Some(reason) = incomplete {
+        html.push_str(&format!(
+            "<p class=\"banner\">Incomplete results: scan {}; unfetched licenses are reported as unknown.</p>\n",
+            xml_escape(reason)
+        ));
+    }
+
+    let card = |value: usize, label: &str, flagged: bool| {
+        let class = match (flagged, value) {
+            (false, _) => "card",
+            (true, 0) => "card ok",
+            (true, _) => "card bad",
+        };
+        format!(
+            "<div class=\"{class}\"><span class=\"value\">{value}</span><span class=\"label\">{label}</span></div>\n"
+        )
+    };
+    html.push_str("<section class=\"cards\">\n");
+    html.push_str(&card(license_info.len(), "Dependencies", false));
+    html.push_str(&card(restrictive_count, "Restrictive", true));
+    if project_license.is_some() {
+        html.push_str(&card(incompatible_count, "Incompatible", true));
+    }
+    html.push_str(&card(unlicensed_count, "Without a license", true));
+    let vulnerable: Vec<&LicenseInfo> = license_info
+        .iter()
+        .filter(|info| info.vulnerabilities().is_some_and(|ids| !ids.is_empty()))
+        .collect();
+    // Only a scan run with --vulnerabilities has looked them up
+    if license_info
+        .iter()
+        .any(|info| info.vulnerabilities().is_some())
+    {
+        html.push_str(&card(vulnerable.len(), "Vulnerable", true));
+    }
+    html.push_str(&card(
+        ecosystem_counts(license_info).len(),
+        "Ecosystems",
+        false,
+    ));
+    html.push_str("</section>\n");
+
+    let rows = license_chart_rows(license_info);
+    if !rows.is_empty() {
+        let restrictive: std::collections::HashSet<String> = license_info
+            .iter()
+            .filter(|info| *info.is_restrictive())
+            .map(LicenseInfo::get_license)
+            .collect();
+        html.push_str("<h2>License distribution</h2>\n<section class=\"chart\">\n");
+        html.push_str(&license_pie_chart(&rows, license_info.len()));
+        for (license, count, bar) in rows {
+            html.push_str(&format!(
+                "<div class=\"bar-row\"><span class=\"name\" title=\"{0}\">{0}</span><div class=\"bar{1}\" style=\"width: {2:.1}%\"></div><span class=\"count\">{3} ({4:.0}%)</span></div>\n",
+                xml_escape(&license),
+                if restrictive.contains(&license) { " restrictive" } else { "" },
+                bar as f64 * 100.0 / CHART_WIDTH as f64,
+                count,
+                count as f64 * 100.0 / license_info.len() as f64
+            ));
+        }
+        html.push_str("</section>\n");
+    }
+
+    if !conflicts.is_empty() {
+        html.push_str("<h2>License conflicts</h2>\n<ul>\n");
+        for conflict in conflicts {
+            html.push_str(&format!("<li>{}</li>\n", xml_escape(&conflict.to_string())));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    html.push_str("<h2>Dependencies</h2>\n<div class=\"filters\">\n");
+    html.push_str(
+        "<input type=\"search\" id=\"search\" placeholder=\"Filter by name, license or ecosystem\">\n",
+    );
+    html.push_str(
+        "<select id=\"compatibility\"><option value=\"\">Any compatibility</option>\
+         <option value=\"compatible\">Compatible</option>\
+         <option value=\"incompatible\">Incompatible</option>\
+         <option value=\"unknown\">Unknown</option></select>\n",
+    );
+    html.push_str(
+        "<label><input type=\"checkbox\" id=\"restrictive-only\"> Restrictive only</label>\n",
+    );
+    html.push_str(&format!(
+        "<span class=\"meta\"><span id=\"shown\">{0}</span> of {0} shown</span>\n</div>\n",
+        license_info.len()
+    ));
+    html.push_str(
+        "<table id=\"dependencies\">\n<thead><tr><th>Dependency</th><th>Version</th>\
+         <th>License</th><th>Ecosystem</th><th>Restrictive</th><th>Compatibility</th>\
+         <th>OSI</th></tr></thead>\n<tbody>\n",
+    );
+    for info in license_info {
+        let compatibility = info.compatibility.to_string().to_lowercase();
+        let mut classes = Vec::new();
+        if *info.is_restrictive() {
+            classes.push("restrictive");
+        }
+        if info.compatibility == LicenseCompatibility::Incompatible {
+            classes.push("incompatible");
+        }
+        let name = match &info.repository {
+            Some(url) if url.starts_with("https://") || url.starts_with("http://") => format!(
+                "<a href=\"{}\">{}</a>",
+                xml_escape(url),
+                xml_escape(info.name())
+            ),
+            _ => xml_escape(info.name()),
+        };
+        html.push_str(&format!(
+            "<tr class=\"{}\" data-compatibility=\"{compatibility}\" data-restrictive=\"{}\"><td>{name}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"compat-{compatibility}\">{}</td><td>{}</td></tr>\n",
+            classes.join(" "),
+            info.is_restrictive(),
+            xml_escape(info.version()),
+            xml_escape(&info.get_license()),
+            xml_escape(
+                &info
+                    .ecosystem
+                    .as_deref()
+                    .map(ecosystem_display_name)
+                    .unwrap_or_default()
+            ),
+            if *info.is_restrictive() { "Yes" } else { "No" },
+            info.compatibility,
+            info.osi_status
+        ));
+    }
+    html.push_str("</tbody>\n</table>\n");
+
+    if !vulnerable.is_empty() {
+        html.push_str("<h2>Known vulnerabilities</h2>\n<ul>\n");
+        for info in &vulnerable {
+            let advisories: Vec<String> = info
+                .vulnerabilities()
+                .unwrap_or_default()
+                .iter()
+                .map(|id| {
+                    format!(
+                        "<a href=\"https://osv.dev/vulnerability/{0}\">{0}</a>",
+                        xml_escape(id)
+                    )
+                })
+                .collect();
+            html.push_str(&format!(
+                "<li>{} {}: {}</li>\n",
+                xml_escape(info.name()),
+                xml_escape(info.version()),
+                advisories.join(", ")
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    if !resolution_errors.is_empty() {
+        html.push_str(&format!(
+            "<h2>Failed lookups</h2>\n<p class=\"meta\">{} registry/API lookups failed, so some licenses may be missing.</p>\n<ul>\n",
+            resolution_errors.len()
+        ));
+        for error in resolution_errors {
+            html.push_str(&format!(
+                "<li>{}: {} ({})</li>\n",
+                xml_escape(&error.source),
+                xml_escape(&error.target),
+                xml_escape(&error.reason)
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    html.push_str(
+        "<footer>Generated by <a href=\"https://github.com/anistark/feluda\">Feluda</a>.</footer>\n",
+    );
+    html.push_str(&format!(
+        "</main>\n<script>{HTML_SCRIPT}</script>\n</body>\n</html>\n"
+    ));
+    html
+}
+
+fn output_html_format(
+    license_info: &[LicenseInfo],
+    output_path: Option<&str>,
+    project_name: &str,
+    project_license: Option<&str>,
+    incomplete: Option<&str>,
+    resolution_errors: &[ResolutionError],
+    conflicts: &[LicenseConflict],
+) {
+    log(LogLevel::Info, "Generating HTML report");
+    let output = html_report(
+        license_info,
+        project_name,
+        project_license,
+        incomplete,
+        resolution_errors,
+        conflicts,
+    );
+
+    if let Some(path) = output_path {
+        log(
+            LogLevel::Info,
+            &format!("Writing HTML report to file: {path}"),
+        );
+        match report_archive::write_report_file(Path::new(path), output.as_bytes()) {
+            Ok(_) => eprintln!("HTML report written to: {path}"),
+            Err(err) => {
+                log_error(&format!("Failed to write HTML report: {path}"), &err);
+                eprintln!("Error: Failed to write HTML report {path}");
+            }
+        }
+    } else {
+        log(LogLevel::Info, "Writing HTML report to stdout");
+        print!("{output}");
+    }
+}
+
+/// Print a `--json` or `--yaml` report, or write it to `--output-file`
+fn output_report(output: &str, output_path: Option<&str>, kind: &str) {
+    let Some(path) = output_path else {
+        println!("{output}");
+        return;
+    };
+    log(
+        LogLevel::Info,
+        &format!("Writing {kind} report to file: {path}"),
+    );
+    let content = format!("{}\n", output.trim_end());
+    match report_archive::write_report_file(Path::new(path), content.as_bytes()) {
+        Ok(_) => eprintln!("{kind} report written to: {path}"),
+        Err(err) => {
+            log_error(&format!("Failed to write {kind} report: {path}"), &err);
+            eprintln!("Error: Failed to write {kind} report {path}");
+        }
+    }
+}
+
 // Add gist report function to reporter.rs
 fn print_gist_summary(
     license_info: &[LicenseInfo],
@@ -1151,6 +2942,17 @@ fn print_gist_summary(
         total_packages.to_string().cyan()
     );
 
+    let ecosystems = ecosystem_counts(license_info);
+    if ecosystems.len() > 1 {
+        for count in &ecosystems {
+            println!(
+                "│ {:30} │ {}",
+                format!("  {}", ecosystem_display_name(&count.ecosystem)),
+                count.dependencies.to_string().cyan()
+            );
+        }
+    }
+
     println!("{}", "━".repeat(50).cyan());
 
     let restrictive_status = if restrictive_count > 0 {
@@ -1188,9 +2990,33 @@ fn print_gist_summary(
         incompatible_status
     );
 
+    // Only a scan run with --vulnerabilities has looked them up
+    let checked: Vec<&[String]> = license_info
+        .iter()
+        .filter_map(|i| i.vulnerabilities())
+        .collect();
+    let vulnerable_count = checked.iter().filter(|ids| !ids.is_empty()).count();
+    if !checked.is_empty() {
+        let vulnerable_status = if vulnerable_count > 0 {
+            format!(
+                "{} {}",
+                "🛡️".red(),
+                vulnerable_count.to_string().red().bold()
+            )
+        } else {
+            format!("{} {}", "✅".green(), "0".green().bold())
+        };
+        println!(
+            "│ {:30} │ {}",
+            "Vulnerable dependencies".bold(),
+            vulnerable_status
+        );
+    }
+
     println!("{}", "━".repeat(50).cyan());
 
-    let overall_status = if restrictive_count > 0 || incompatible_count > 0 {
+    let overall_status = if restrictive_count > 0 || incompatible_count > 0 || vulnerable_count > 0
+    {
         format!("{} {}", "⚠️".yellow(), "NEEDS ATTENTION".yellow().bold())
     } else {
         format!("{} {}", "✨".green(), "ALL GOOD".green().bold())
@@ -1205,6 +3031,7 @@ fn print_gist_summary(
 mod tests {
     use super::*;
     use crate::licenses::LicenseCompatibility;
+    use std::fs;
     use tempfile::TempDir;
 
     fn setup() -> TempDir {
@@ -1217,10 +3044,9 @@ mod tests {
                 name: "crate1".to_string(),
                 version: "1.0.0".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "crate2".to_string(),
@@ -1229,25 +3055,21 @@ mod tests {
                 is_restrictive: true,
                 compatibility: LicenseCompatibility::Incompatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "crate3".to_string(),
                 version: "3.0.0".to_string(),
                 license: Some("Apache-2.0".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "crate4".to_string(),
                 version: "4.0.0".to_string(),
                 license: Some("Unknown".to_string()),
-                is_restrictive: false,
-                compatibility: LicenseCompatibility::Unknown,
-                osi_status: crate::licenses::OsiStatus::Unknown,
-                sub_project: None,
+                ..Default::default()
             },
         ]
     }
@@ -1258,19 +3080,16 @@ mod tests {
                 name: "crate1".to_string(),
                 version: "1.0.0".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
-                compatibility: LicenseCompatibility::Unknown,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "crate2".to_string(),
                 version: "2.0.0".to_string(),
                 license: Some("GPL-3.0".to_string()),
                 is_restrictive: true,
-                compatibility: LicenseCompatibility::Unknown,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ]
     }
@@ -1281,7 +3100,7 @@ mod tests {
         let config = ReportConfig::new(
             false, false, false, false, false, None, None, None, false, None,
         );
-        let result = generate_report(data, config);
+        let result = generate_report(data, config).unwrap();
         assert_eq!(result, (false, false)); // No restrictive or incompatible licenses
     }
 
@@ -1300,7 +3119,7 @@ mod tests {
             false,
             None,
         );
-        let result = generate_report(data, config);
+        let result = generate_report(data, config).unwrap();
         assert_eq!(result, (true, true)); // Has both restrictive and incompatible licenses
     }
 
@@ -1319,7 +3138,7 @@ mod tests {
             false,
             None,
         );
-        let result = generate_report(data, config);
+        let result = generate_report(data, config).unwrap();
         assert_eq!(result, (true, true)); // In strict mode, still has both restrictive and incompatible
     }
 
@@ -1338,7 +3157,7 @@ mod tests {
             false,
             None,
         );
-        let result = generate_report(data, config);
+        let result = generate_report(data, config).unwrap();
         assert_eq!(result, (true, true));
     }
 
@@ -1357,7 +3176,7 @@ mod tests {
             false,
             None,
         );
-        let result = generate_report(data, config);
+        let result = generate_report(data, config).unwrap();
         assert_eq!(result, (true, true));
     }
 
@@ -1376,7 +3195,7 @@ mod tests {
             false,
             None,
         );
-        let result = generate_report(data, config);
+        let result = generate_report(data, config).unwrap();
         assert_eq!(result, (true, true));
     }
 
@@ -1386,8 +3205,72 @@ mod tests {
         let config = ReportConfig::new(
             false, false, false, false, false, None, None, None, false, None,
         );
-        let result = generate_report(data, config);
-        assert_eq!(result, (true, false)); // Has restrictive but no incompatible since no project license
+        let result = generate_report(data, config).unwrap();
+        assert_eq!(result, (true, false)); // Has restrictive but no incompatible since no project license
+    }
+
+    #[test]
+    fn test_generate_report_output_dir() {
+        let temp_dir = setup();
+        let dir = temp_dir.path().join("reports");
+        let report_files = || {
+            let mut names: Vec<String> = fs::read_dir(&dir)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+                .collect();
+            names.sort();
+            names
+        };
+
+        let config = ReportConfig::new(
+            false, false, false, false, false, None, None, None, false, None,
+        )
+        .with_output_dir(Some(dir.to_string_lossy().to_string()), Some(2), None);
+        generate_report(get_test_data(), config).unwrap();
+
+        let files = report_files();
+        assert_eq!(files.len(), 1);
+        assert!(files[0].starts_with("feluda-report-") && files[0].ends_with(".json"));
+        let archived: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join(&files[0])).unwrap()).unwrap();
+        assert_eq!(
+            archived["dependencies"].as_array().unwrap().len(),
+            get_test_data().len()
+        );
+
+        for _ in 0..2 {
+            let config = ReportConfig::new(
+                false, false, false, false, false, None, None, None, false, None,
+            )
+            .with_output_dir(Some(dir.to_string_lossy().to_string()), Some(2), None);
+            generate_report(get_test_data(), config).unwrap();
+        }
+        let files_after = report_files();
+        assert_eq!(files_after.len(), 2);
+        assert!(!files_after.contains(&files[0]));
+
+        // CI formats are written to the directory with their own extension, compressed if asked
+        let config = ReportConfig::new(
+            false,
+            false,
+            false,
+            false,
+            false,
+            Some(CiFormat::Sarif),
+            None,
+            None,
+            false,
+            None,
+        )
+        .with_output_dir(
+            Some(dir.to_string_lossy().to_string()),
+            None,
+            Some(ReportCompression::Gzip),
+        );
+        generate_report(get_test_data(), config).unwrap();
+        assert!(report_files()
+            .iter()
+            .any(|name| name.ends_with(".sarif.gz")));
     }
 
     #[test]
@@ -1408,7 +3291,7 @@ mod tests {
             None,
         );
 
-        let result = generate_report(data, config);
+        let result = generate_report(data, config).unwrap();
         assert_eq!(result, (true, true));
 
         let content = match fs::read_to_string(&output_path) {
@@ -1422,6 +3305,8 @@ mod tests {
         assert!(content.contains("::error title=Incompatible License::"));
         assert!(content.contains("::notice title=Project License::"));
         assert!(content.contains("::notice title=License Check Summary::"));
+        // The test data carries no ecosystem, so there is nothing to break down
+        assert!(!content.contains("Dependencies by Ecosystem"));
     }
 
     #[test]
@@ -1442,7 +3327,7 @@ mod tests {
             None,
         );
 
-        let result = generate_report(data, config);
+        let result = generate_report(data, config).unwrap();
         assert_eq!(result, (true, true));
 
         let content = match fs::read_to_string(&output_path) {
@@ -1477,7 +3362,7 @@ mod tests {
             None,
         );
 
-        let result = generate_report(data, config);
+        let result = generate_report(data, config).unwrap();
         assert_eq!(result, (true, false)); // Has restrictive but no incompatible
 
         let content = match fs::read_to_string(&output_path) {
@@ -1494,6 +3379,98 @@ mod tests {
         assert!(!content.contains("Project is using"));
     }
 
+    #[test]
+    fn test_license_chart_rows() {
+        let mut data = get_test_data();
+        data.push(data[0].clone());
+
+        let rows = license_chart_rows(&data);
+        assert_eq!(rows[0], ("MIT".to_string(), 2, CHART_WIDTH));
+        // Ties are ordered by license name
+        assert_eq!(rows[1], ("Apache-2.0".to_string(), 1, CHART_WIDTH / 2));
+        assert_eq!(rows.len(), 4);
+
+        assert!(license_chart_rows(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_ecosystem_counts_and_summary_line() {
+        let mut data = get_test_data();
+        for (info, ecosystem) in data.iter_mut().zip(["node", "rust", "node", "cpp"]) {
+            info.ecosystem = Some(ecosystem.to_string());
+        }
+        data.push(get_test_data()[0].clone());
+
+        let counts = ecosystem_counts(&data);
+        let names: Vec<(&str, usize)> = counts
+            .iter()
+            .map(|count| (count.ecosystem.as_str(), count.dependencies))
+            .collect();
+        assert_eq!(names, vec![("node", 2), ("cpp", 1), ("rust", 1)]);
+
+        let line = ecosystem_summary_line(&counts);
+        assert!(line.starts_with("Node: 2"));
+        assert!(line.contains("C++: 1"));
+        assert!(ecosystem_counts(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_top_offenders_rank_by_sole_removals() {
+        let mut data = get_test_data();
+        let via = |direct: &[&str]| direct.iter().map(|d| d.to_string()).collect();
+        for info in &mut data {
+            info.is_restrictive = false;
+            info.compatibility = LicenseCompatibility::Compatible;
+        }
+        data[0].is_restrictive = true;
+        data[0].introduced_by = via(&["express", "webpack"]);
+        data[1].compatibility = LicenseCompatibility::Incompatible;
+        data[1].introduced_by = via(&["webpack"]);
+        data[2].is_restrictive = true;
+        data[2].introduced_by = via(&["lodash-wrapper"]);
+        // Unflagged packages don't count, whatever pulls them in
+        data[3].introduced_by = via(&["express"]);
+
+        let offenders = top_offenders(&data);
+        let ranked: Vec<(&str, usize, usize)> = offenders
+            .iter()
+            .map(|o| (o.name.as_str(), o.flagged.len(), o.sole))
+            .collect();
+        assert_eq!(
+            ranked,
+            vec![
+                ("webpack", 2, 1),
+                ("lodash-wrapper", 1, 1),
+                ("express", 1, 0)
+            ]
+        );
+        assert!(top_offenders(&get_test_data()).is_empty());
+    }
+
+    #[test]
+    fn test_vulnerability_rows() {
+        let mut data = get_test_data();
+        let ids = |count: usize| (1..=count).map(|n| format!("GHSA-{n}")).collect();
+        data[0].vulnerabilities = Some(ids(1));
+        data[1].vulnerabilities = Some(ids(5));
+        data[2].vulnerabilities = Some(Vec::new());
+
+        let rows = vulnerability_rows(&data);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][0], data[1].name);
+        assert_eq!(rows[0][3], "5");
+        assert_eq!(rows[0][4], "GHSA-1, GHSA-2, GHSA-3, +2 more");
+        assert_eq!(rows[1][4], "GHSA-1");
+        assert!(vulnerability_rows(&get_test_data()).is_empty());
+
+        let html = html_report(&data, "", None, None, &[], &[]);
+        assert!(
+            html.contains("<span class=\"value\">2</span><span class=\"label\">Vulnerable</span>")
+        );
+        assert!(html.contains("<a href=\"https://osv.dev/vulnerability/GHSA-5\">GHSA-5</a>"));
+        assert!(!html_report(&get_test_data(), "", None, None, &[], &[]).contains("Vulnerable"));
+    }
+
     #[test]
     fn test_table_formatter() {
         let headers = vec![
@@ -1601,19 +3578,17 @@ mod tests {
                 name: "package1".to_string(),
                 version: "1.0.0".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "package2".to_string(),
                 version: "2.0.0".to_string(),
                 license: Some("BSD-3-Clause".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ];
 
@@ -1629,7 +3604,7 @@ mod tests {
             false,
             None,
         );
-        let (has_restrictive, has_incompatible) = generate_report(data, config);
+        let (has_restrictive, has_incompatible) = generate_report(data, config).unwrap();
 
         assert!(!has_restrictive);
         assert!(!has_incompatible);
@@ -1642,10 +3617,9 @@ mod tests {
                 name: "good_package".to_string(),
                 version: "1.0.0".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "bad_package".to_string(),
@@ -1654,7 +3628,7 @@ mod tests {
                 is_restrictive: true,
                 compatibility: LicenseCompatibility::Incompatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ];
 
@@ -1670,7 +3644,7 @@ mod tests {
             false,
             None,
         );
-        let (has_restrictive, has_incompatible) = generate_report(data, config);
+        let (has_restrictive, has_incompatible) = generate_report(data, config).unwrap();
 
         assert!(has_restrictive);
         assert!(has_incompatible);
@@ -1683,10 +3657,9 @@ mod tests {
                 name: "permissive_package".to_string(),
                 version: "1.0.0".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "restrictive_package".to_string(),
@@ -1695,7 +3668,7 @@ mod tests {
                 is_restrictive: true,
                 compatibility: LicenseCompatibility::Incompatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ];
 
@@ -1711,7 +3684,7 @@ mod tests {
             false,
             None,
         );
-        let (has_restrictive, has_incompatible) = generate_report(data, config);
+        let (has_restrictive, has_incompatible) = generate_report(data, config).unwrap();
 
         assert!(has_restrictive);
         assert!(has_incompatible);
@@ -1723,16 +3696,15 @@ mod tests {
             name: "test_package".to_string(),
             version: "1.0.0".to_string(),
             license: Some("MIT".to_string()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: crate::licenses::OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         }];
 
         let config = ReportConfig::new(
             true, false, false, false, false, None, None, None, false, None,
         );
-        let (has_restrictive, has_incompatible) = generate_report(data, config);
+        let (has_restrictive, has_incompatible) = generate_report(data, config).unwrap();
 
         assert!(!has_restrictive);
         assert!(!has_incompatible);
@@ -1744,16 +3716,15 @@ mod tests {
             name: "test_package".to_string(),
             version: "1.0.0".to_string(),
             license: Some("MIT".to_string()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: crate::licenses::OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         }];
 
         let config = ReportConfig::new(
             false, true, false, false, false, None, None, None, false, None,
         );
-        let (has_restrictive, has_incompatible) = generate_report(data, config);
+        let (has_restrictive, has_incompatible) = generate_report(data, config).unwrap();
 
         assert!(!has_restrictive);
         assert!(!has_incompatible);
@@ -1765,10 +3736,9 @@ mod tests {
             name: "test_package".to_string(),
             version: "1.0.0".to_string(),
             license: Some("MIT".to_string()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: crate::licenses::OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         }];
 
         let config = ReportConfig::new(
@@ -1783,7 +3753,7 @@ mod tests {
             false,
             None,
         );
-        let (has_restrictive, has_incompatible) = generate_report(data, config);
+        let (has_restrictive, has_incompatible) = generate_report(data, config).unwrap();
 
         assert!(!has_restrictive);
         assert!(!has_incompatible);
@@ -1798,7 +3768,7 @@ mod tests {
             is_restrictive: true,
             compatibility: LicenseCompatibility::Incompatible,
             osi_status: crate::licenses::OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         }];
 
         let config = ReportConfig::new(
@@ -1814,7 +3784,7 @@ mod tests {
             None,
         );
 
-        let (has_restrictive, has_incompatible) = generate_report(data, config);
+        let (has_restrictive, has_incompatible) = generate_report(data, config).unwrap();
         assert!(has_restrictive);
         assert!(has_incompatible);
     }
@@ -1837,7 +3807,7 @@ mod tests {
             None,
         );
 
-        let result = generate_report(data, config);
+        let result = generate_report(data, config).unwrap();
         assert_eq!(result, (true, true));
 
         let content = fs::read_to_string(&output_path).expect("Failed to read SARIF output file");
@@ -1861,16 +3831,427 @@ mod tests {
         assert!(rule_ids.contains(&"feluda/incompatible-license"));
     }
 
+    #[test]
+    fn test_gitlab_output_format_to_file() {
+        let mut data = get_test_data();
+        data[0].license = Some("MIT OR Apache-2.0".to_string());
+        data[0].ecosystem = Some("rust".to_string());
+        data[1].license = Some("Unknown (failed to retrieve)".to_string());
+        data[1].ecosystem = Some("node".to_string());
+        data[1].declared_in = Some(crate::manifest::ManifestLocation {
+            file: "web/package.json".to_string(),
+            line: 4,
+        });
+        let temp_dir = setup();
+        let output_path = temp_dir.path().join("gl-license-scanning-report.json");
+        let config = ReportConfig::new(
+            false,
+            false,
+            false,
+            false,
+            false,
+            Some(CiFormat::Gitlab),
+            Some(output_path.to_str().unwrap().to_string()),
+            Some("MIT".to_string()),
+            false,
+            None,
+        );
+
+        assert_eq!(generate_report(data.clone(), config).unwrap(), (true, true));
+
+        let content = fs::read_to_string(&output_path).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(parsed["version"], "2.1");
+        let dependencies = parsed["dependencies"].as_array().unwrap();
+        assert_eq!(dependencies.len(), data.len());
+        assert_eq!(
+            dependencies[0]["licenses"],
+            serde_json::json!(["MIT", "Apache-2.0"])
+        );
+        assert_eq!(dependencies[0]["package_manager"], "cargo");
+        assert_eq!(dependencies[0]["path"], "Cargo.toml");
+        assert_eq!(dependencies[1]["licenses"], serde_json::json!(["unknown"]));
+        assert_eq!(dependencies[1]["package_manager"], "npm");
+        assert_eq!(dependencies[1]["path"], "web/package.json");
+
+        let licenses = parsed["licenses"].as_array().unwrap();
+        let ids: Vec<&str> = licenses
+            .iter()
+            .map(|license| license["id"].as_str().unwrap())
+            .collect();
+        assert!(ids.contains(&"Apache-2.0") && ids.contains(&"unknown"));
+        assert_eq!(
+            ids.iter().filter(|id| **id == "MIT").count(),
+            1,
+            "each license is listed once"
+        );
+        assert!(licenses
+            .iter()
+            .any(|license| license["url"] == "https://spdx.org/licenses/Apache-2.0.html"));
+    }
+
+    #[test]
+    fn test_ci_outputs_point_at_manifest_lines() {
+        let mut data = get_test_data();
+        for info in &mut data {
+            info.declared_in = Some(crate::manifest::ManifestLocation {
+                file: "web/package.json".to_string(),
+                line: 12,
+            });
+        }
+
+        let sarif = sarif_locations(&data[0]);
+        assert_eq!(
+            sarif[0]["physicalLocation"]["artifactLocation"]["uri"],
+            "web/package.json"
+        );
+        assert_eq!(sarif[0]["physicalLocation"]["region"]["startLine"], 12);
+        assert_eq!(github_location(&data[0]), "file=web/package.json,line=12,");
+
+        let temp_dir = setup();
+        let output_path = temp_dir.path().join("github_output.txt");
+        let config = ReportConfig::new(
+            false,
+            false,
+            false,
+            false,
+            false,
+            Some(CiFormat::Github),
+            Some(output_path.to_str().unwrap().to_string()),
+            Some("MIT".to_string()),
+            false,
+            None,
+        );
+        generate_report(data, config).unwrap();
+        let content = fs::read_to_string(&output_path).unwrap();
+        assert!(
+            content.contains("::warning file=web/package.json,line=12,title=Restrictive License::")
+        );
+
+        let undeclared = get_test_data();
+        assert_eq!(sarif_locations(&undeclared[0]), serde_json::json!([]));
+        assert_eq!(github_location(&undeclared[0]), "");
+    }
+
+    #[test]
+    fn test_sarif_properties_carry_suggestions() {
+        let mut data = get_test_data();
+        assert_eq!(
+            sarif_properties(&data[0]),
+            serde_json::json!({ "suggestions": [] })
+        );
+
+        data[0].suggestions = vec![crate::suggestions::FixSuggestion::VendorException];
+        assert_eq!(
+            sarif_properties(&data[0])["suggestions"][0]["kind"],
+            "vendor-exception"
+        );
+        print_fix_suggestions(&data);
+    }
+
+    #[test]
+    fn test_sarif_output_includes_parse_warnings() {
+        let temp_dir = setup();
+        let output_path = temp_dir.path().join("results.sarif");
+        let config = ReportConfig::new(
+            false,
+            false,
+            false,
+            false,
+            false,
+            Some(CiFormat::Sarif),
+            Some(output_path.to_str().unwrap().to_string()),
+            None,
+            false,
+            None,
+        )
+        .with_warnings(vec![ParseWarning {
+            file: "web/package.json".to_string(),
+            reason: "invalid package.json: EOF while parsing".to_string(),
+        }]);
+
+        generate_report(get_test_data(), config).unwrap();
+
+        let content = fs::read_to_string(&output_path).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&content).unwrap();
+        let notifications = parsed["runs"][0]["invocations"][0]["toolExecutionNotifications"]
+            .as_array()
+            .unwrap();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(
+            notifications[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "web/package.json"
+        );
+    }
+
+    #[test]
+    fn test_incomplete_scan_is_marked_in_ci_output() {
+        let temp_dir = setup();
+        let sarif_path = temp_dir.path().join("results.sarif");
+        let config = ReportConfig::new(
+            false,
+            false,
+            false,
+            false,
+            false,
+            Some(CiFormat::Sarif),
+            Some(sarif_path.to_str().unwrap().to_string()),
+            None,
+            false,
+            None,
+        )
+        .with_incomplete(Some("interrupted by Ctrl+C".to_string()));
+        generate_report(get_test_data(), config).unwrap();
+
+        let parsed: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&sarif_path).unwrap()).unwrap();
+        let invocation = &parsed["runs"][0]["invocations"][0];
+        assert_eq!(invocation["executionSuccessful"], false);
+        assert_eq!(
+            invocation["toolExecutionNotifications"][0]["level"],
+            "error"
+        );
+
+        let github_path = temp_dir.path().join("github.txt");
+        output_github_format(
+            &get_test_data(),
+            github_path.to_str(),
+            None,
+            &[],
+            Some("timed out after 60s (--timeout)"),
+            &[],
+            &[],
+        );
+        let github = fs::read_to_string(&github_path).unwrap();
+        assert!(github.contains("::error title=Scan Incomplete::License scan timed out"));
+
+        let jenkins_path = temp_dir.path().join("junit.xml");
+        output_jenkins_format(
+            &get_test_data(),
+            jenkins_path.to_str(),
+            None,
+            &[],
+            Some("interrupted by Ctrl+C"),
+            &[],
+            &[],
+            None,
+        );
+        let jenkins = fs::read_to_string(&jenkins_path).unwrap();
+        assert!(jenkins.contains(r#"errors="1""#));
+        assert!(jenkins.contains(r#"<error message="Scan incomplete">"#));
+    }
+
+    #[test]
+    fn test_ci_output_summarizes_resolution_errors() {
+        let temp_dir = setup();
+        let error = |target: &str| ResolutionError {
+            source: "PyPI".to_string(),
+            target: target.to_string(),
+            reason: "operation timed out".to_string(),
+        };
+        let errors = vec![
+            error("https://pypi.org/pypi/requests/2.31.0/json"),
+            error("https://pypi.org/pypi/flask/3.0.0/json"),
+        ];
+
+        let github_path = temp_dir.path().join("github.txt");
+        output_github_format(
+            &get_test_data(),
+            github_path.to_str(),
+            None,
+            &[],
+            None,
+            &errors,
+            &[],
+        );
+        let github = fs::read_to_string(&github_path).unwrap();
+        assert!(github.contains("::warning title=Resolution Errors::2 lookups to PyPI failed"));
+
+        let jenkins_path = temp_dir.path().join("junit.xml");
+        output_jenkins_format(
+            &get_test_data(),
+            jenkins_path.to_str(),
+            None,
+            &[],
+            None,
+            &errors,
+            &[],
+            None,
+        );
+        let jenkins = fs::read_to_string(&jenkins_path).unwrap();
+        assert!(jenkins.contains(r#"classname="feluda.resolution" name="PyPI""#));
+        assert!(jenkins.contains(r#"skipped="1""#));
+
+        let sarif_path = temp_dir.path().join("results.sarif");
+        output_sarif_format(
+            &get_test_data(),
+            sarif_path.to_str(),
+            None,
+            &[],
+            None,
+            &errors,
+            &[],
+            None,
+        );
+        let parsed: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&sarif_path).unwrap()).unwrap();
+        let notifications = parsed["runs"][0]["invocations"][0]["toolExecutionNotifications"]
+            .as_array()
+            .unwrap();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(
+            parsed["runs"][0]["invocations"][0]["executionSuccessful"],
+            true
+        );
+    }
+
+    #[test]
+    fn test_github_and_jenkins_output_include_parse_warnings() {
+        let temp_dir = setup();
+        let warnings = vec![ParseWarning {
+            file: "go.mod".to_string(),
+            reason: "failed to read go.mod: <denied>".to_string(),
+        }];
+
+        let github_path = temp_dir.path().join("github.txt");
+        output_github_format(
+            &get_test_data(),
+            github_path.to_str(),
+            Some("MIT"),
+            &warnings,
+            None,
+            &[],
+            &[],
+        );
+        let github = fs::read_to_string(&github_path).unwrap();
+        assert!(github.contains("::warning file=go.mod,title=Manifest Parse Warning::"));
+
+        let jenkins_path = temp_dir.path().join("junit.xml");
+        output_jenkins_format(
+            &get_test_data(),
+            jenkins_path.to_str(),
+            Some("MIT"),
+            &warnings,
+            None,
+            &[],
+            &[],
+            None,
+        );
+        let jenkins = fs::read_to_string(&jenkins_path).unwrap();
+        assert!(jenkins.contains(r#"skipped="1""#));
+        assert!(jenkins.contains("&lt;denied&gt;"));
+    }
+
+    #[test]
+    fn test_html_output_format_to_file() {
+        let data = get_test_data();
+        let temp_dir = setup();
+        let output_path = temp_dir.path().join("report.html");
+        let config = ReportConfig::new(
+            false,
+            false,
+            false,
+            false,
+            false,
+            None,
+            Some(output_path.to_str().unwrap().to_string()),
+            Some("MIT".to_string()),
+            false,
+            None,
+        )
+        .with_output_format(Some(OutputFormat::Html), "web-app");
+
+        assert_eq!(generate_report(data, config).unwrap(), (true, true));
+
+        let content = fs::read_to_string(&output_path).unwrap();
+        assert!(content.starts_with("<!DOCTYPE html>"));
+        assert!(content.contains("<title>License report: web-app</title>"));
+        assert!(content.contains("Project license: <strong>MIT</strong>"));
+        assert!(content.contains("<span class=\"label\">Restrictive</span>"));
+        assert!(content.contains("data-compatibility=\"incompatible\" data-restrictive=\"true\""));
+        assert!(content.contains("<td class=\"compat-incompatible\">Incompatible</td>"));
+        assert!(content.contains("<h2>License distribution</h2>"));
+        assert!(content.contains("<svg class=\"pie\""));
+        assert!(content.contains("<path d=\"M 0 0 L 0.0000 -1.0000 A 1 1 0 "));
+        assert!(content.contains("<span class=\"swatch\" style=\"background: #0969da\">"));
+        // Self-contained: nothing is loaded from elsewhere
+        assert!(!content.contains("<link"));
+        assert!(!content.contains("src="));
+    }
+
+    #[test]
+    fn test_json_and_yaml_output_to_file() {
+        let temp_dir = setup();
+        let json_path = temp_dir.path().join("report.json");
+        let yaml_path = temp_dir.path().join("report.yaml.gz");
+        for (json, yaml, path) in [(true, false, &json_path), (false, true, &yaml_path)] {
+            let config = ReportConfig::new(
+                json,
+                yaml,
+                false,
+                false,
+                false,
+                None,
+                Some(path.to_str().unwrap().to_string()),
+                Some("MIT".to_string()),
+                false,
+                None,
+            );
+            generate_report(get_test_data(), config).unwrap();
+        }
+
+        let stored = crate::report_archive::load_report(&json_path).unwrap();
+        assert_eq!(stored.len(), get_test_data().len());
+        let stored = crate::report_archive::load_report(&yaml_path).unwrap();
+        assert_eq!(stored.len(), get_test_data().len());
+    }
+
+    #[test]
+    fn test_license_pie_chart() {
+        let rows = vec![
+            ("MIT".to_string(), 3, 40),
+            ("GPL-3.0 <or later>".to_string(), 1, 14),
+        ];
+        let chart = license_pie_chart(&rows, 4);
+        assert_eq!(chart.matches("<path ").count(), 2);
+        // Three quarters take the long way round; the last slice closes the circle at the top
+        assert!(chart.contains("A 1 1 0 1 1 -1.0000 0.0000 Z"));
+        assert!(chart.contains("A 1 1 0 0 1 -0.0000 -1.0000 Z"));
+        assert!(chart.contains("<title>GPL-3.0 &lt;or later&gt; (1)</title>"));
+
+        // A single license is the whole pie
+        let chart = license_pie_chart(&rows[..1], 3);
+        assert!(chart.contains("<circle r=\"1\" fill=\"#0969da\"><title>MIT (3)</title></circle>"));
+        assert!(!chart.contains("<path "));
+    }
+
+    #[test]
+    fn test_html_report_escapes_dependency_data() {
+        let mut data = get_test_data();
+        data[0].name = "<script>alert(1)</script>".to_string();
+        data[0].repository = Some("https://example.com/?a=1&b=\"2\"".to_string());
+        data[1].repository = Some("javascript:alert(1)".to_string());
+
+        let html = html_report(&data, "", None, Some("interrupted by Ctrl+C"), &[], &[]);
+        assert!(!html.contains("<script>alert"));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(html.contains("href=\"https://example.com/?a=1&amp;b=&quot;2&quot;\""));
+        assert!(!html.contains("href=\"javascript:"));
+        assert!(html.contains("<title>License report</title>"));
+        assert!(html.contains("Incomplete results: scan interrupted by Ctrl+C;"));
+        // Without a project license there is nothing to be incompatible with
+        assert!(!html.contains("<span class=\"label\">Incompatible</span>"));
+    }
+
     #[test]
     fn test_sarif_output_clean_scan() {
         let data = vec![LicenseInfo {
             name: "clean-pkg".to_string(),
             version: "1.0.0".to_string(),
             license: Some("MIT".to_string()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: crate::licenses::OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         }];
         let temp_dir = setup();
         let output_path = temp_dir.path().join("clean.sarif");
@@ -1887,7 +4268,7 @@ mod tests {
             None,
         );
 
-        let (has_restrictive, has_incompatible) = generate_report(data, config);
+        let (has_restrictive, has_incompatible) = generate_report(data, config).unwrap();
         assert!(!has_restrictive);
         assert!(!has_incompatible);
 
@@ -1903,6 +4284,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_conflicting_dependencies_fail_and_reach_sarif() {
+        // Each dependency is fine on its own (no project license), but GPL-2.0-only and
+        // Apache-2.0 cannot be combined
+        let mut data = get_test_data();
+        data[1].license = Some("GPL-2.0-only".to_string());
+        data[1].is_restrictive = false;
+        for info in &mut data {
+            info.compatibility = LicenseCompatibility::Unknown;
+        }
+
+        let temp_dir = setup();
+        let output_path = temp_dir.path().join("conflicts.sarif");
+        let config = ReportConfig::new(
+            false,
+            false,
+            false,
+            false,
+            false,
+            Some(CiFormat::Sarif),
+            Some(output_path.to_str().unwrap().to_string()),
+            None,
+            false,
+            None,
+        );
+        let (_, has_incompatible) = generate_report(data, config).unwrap();
+        assert!(has_incompatible);
+
+        let content = fs::read_to_string(&output_path).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&content).unwrap();
+        let results = parsed["runs"][0]["results"].as_array().unwrap();
+        let conflict = results
+            .iter()
+            .find(|r| r["ruleId"] == "feluda/license-conflict")
+            .expect("conflict result");
+        assert_eq!(conflict["properties"]["copyleft"], "GPL-2.0-only");
+        assert_eq!(conflict["properties"]["conflicting"], "Apache-2.0");
+    }
+
     #[test]
     fn test_sarif_output_stdout() {
         let data = get_test_data();
@@ -1918,7 +4338,7 @@ mod tests {
             false,
             None,
         );
-        let (has_restrictive, has_incompatible) = generate_report(data, config);
+        let (has_restrictive, has_incompatible) = generate_report(data, config).unwrap();
         assert!(has_restrictive);
         assert!(has_incompatible);
     }
@@ -1941,7 +4361,7 @@ mod tests {
             None,
         );
 
-        let (has_restrictive, _) = generate_report(data, config);
+        let (has_restrictive, _) = generate_report(data, config).unwrap();
         assert!(has_restrictive);
 
         let content = fs::read_to_string(&output_path).expect("Failed to read SARIF output");
@@ -1966,16 +4386,19 @@ mod tests {
             name: "test_package".to_string(),
             version: "1.0.0".to_string(),
             license: Some("MIT".to_string()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: crate::licenses::OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         }];
 
         output_github_format(
             &data,
             Some("/invalid/path/that/does/not/exist/output.txt"),
             Some("MIT"),
+            &[],
+            None,
+            &[],
+            &[],
         );
     }
 
@@ -1985,16 +4408,20 @@ mod tests {
             name: "test_package".to_string(),
             version: "1.0.0".to_string(),
             license: Some("MIT".to_string()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: crate::licenses::OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         }];
 
         output_jenkins_format(
             &data,
             Some("/invalid/path/that/does/not/exist/output.xml"),
             Some("MIT"),
+            &[],
+            None,
+            &[],
+            &[],
+            None,
         );
     }
 
@@ -2008,7 +4435,7 @@ mod tests {
                 is_restrictive: true,
                 compatibility: LicenseCompatibility::Incompatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "restrictive2".to_string(),
@@ -2017,7 +4444,7 @@ mod tests {
                 is_restrictive: true,
                 compatibility: LicenseCompatibility::Incompatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ];
 
@@ -2072,10 +4499,9 @@ mod tests {
             name: "foo".into(),
             version: "1.0".into(),
             license: Some("MIT".into()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: crate::licenses::OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         }];
         print_workspace_breakdown(&data);
     }
@@ -2089,19 +4515,19 @@ mod tests {
                 name: "shared-dep".into(),
                 version: "1.0".into(),
                 license: Some("MIT".into()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
                 sub_project: Some("api, worker".into()),
+                ..Default::default()
             },
             LicenseInfo {
                 name: "api-only".into(),
                 version: "2.0".into(),
                 license: Some("Apache-2.0".into()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
                 sub_project: Some("api".into()),
+                ..Default::default()
             },
         ];
         print_workspace_breakdown(&data);
@@ -2115,11 +4541,62 @@ mod tests {
             name: "hyper".into(),
             version: "1.0".into(),
             license: Some("MIT".into()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: crate::licenses::OsiStatus::Approved,
             sub_project: Some("api".into()),
+            ..Default::default()
         }];
         print_verbose_table(&data, false, Some("MIT"));
     }
+
+    #[test]
+    fn test_shared_dependencies_render_found_in() {
+        // Smoke test for cross-root provenance: summary note and verbose "Found in" column.
+        let data = vec![LicenseInfo {
+            name: "zlib".into(),
+            version: "1.3".into(),
+            license: Some("Zlib".into()),
+            compatibility: LicenseCompatibility::Compatible,
+            osi_status: crate::licenses::OsiStatus::Approved,
+            found_in: vec!["c".into(), "cpp".into()],
+            ..Default::default()
+        }];
+        print_shared_dependencies(&data);
+        print_verbose_table(&data, false, Some("MIT"));
+
+        let json = serde_json::to_string(&data).unwrap();
+        assert!(json.contains(r#""found_in":["c","cpp"]"#));
+    }
+
+    #[test]
+    fn test_violates_osi_only() {
+        let dep = |license: &str, osi_status, internal| LicenseInfo {
+            name: "pkg".into(),
+            version: "1.0".into(),
+            license: Some(license.into()),
+            compatibility: LicenseCompatibility::Compatible,
+            osi_status,
+            internal,
+            ..Default::default()
+        };
+
+        assert!(!violates_osi_only(&dep("MIT", OsiStatus::Approved, false)));
+        assert!(violates_osi_only(&dep(
+            "CC0-1.0",
+            OsiStatus::NotApproved,
+            false
+        )));
+        assert!(violates_osi_only(&dep(
+            "LicenseRef-Custom",
+            OsiStatus::Unknown,
+            false
+        )));
+        assert!(!violates_osi_only(&dep(
+            "LicenseRef-Internal",
+            OsiStatus::Unknown,
+            true
+        )));
+
+        print_osi_only_violations(&[dep("CC0-1.0", OsiStatus::NotApproved, false)]);
+    }
 }
@@ -0,0 +1,851 @@
+//! Ordered chain of license sources
+//!
+//! A dependency's license is looked up in these sources, first answer wins:
+//!
+//! - `local`: manifests and license files already on disk (node_modules, site-packages,
+//!   the Go module cache, ...)
+//! - `cache`: licenses an earlier scan found remotely (`package_licenses.json`)
+//! - `registry`: the ecosystem's package registry (npm, PyPI, Maven Central, ...)
+//! - `clearlydefined`: the ClearlyDefined definitions API
+//! - `github`: the license GitHub detected for the dependency's repository
+//!
+//! `[resolvers] order` in `.feluda.toml` reorders the chain; a source left out of the list
+//! is disabled. A new source is a [`LicenseResolver`] impl plus a [`ResolverSource`] variant.
+//!
+//! Analyzers run their lookups through [`resolve_all`] (or [`with_lookup_pool`]), one worker pool of
+//! `[resolvers] jobs` threads shared by every ecosystem in the scan. Lookups mostly wait on
+//! the network, so the pool is sized for concurrent requests rather than for CPU cores.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::cache::{self, PackageLicense};
+use crate::config::InternalConfig;
+use crate::debug::{log, LogLevel};
+
+/// Order used when `.feluda.toml` doesn't set one
+pub const DEFAULT_ORDER: [ResolverSource; 5] = [
+    ResolverSource::Local,
+    ResolverSource::Cache,
+    ResolverSource::Registry,
+    ResolverSource::ClearlyDefined,
+    ResolverSource::Github,
+];
+
+/// Concurrent lookups when `.feluda.toml` doesn't set `[resolvers] jobs`
+pub const DEFAULT_JOBS: usize = 16;
+
+static ORDER: RwLock<Option<Vec<ResolverSource>>> = RwLock::new(None);
+static JOBS: AtomicUsize = AtomicUsize::new(DEFAULT_JOBS);
+static LOOKUP_POOL: Mutex<Option<Arc<rayon::ThreadPool>>> = Mutex::new(None);
+static INTERNAL: RwLock<Option<InternalConfig>> = RwLock::new(None);
+static PACKAGE_CACHE: Mutex<Option<PackageCache>> = Mutex::new(None);
+/// Packages each source answered, indexed by `ResolverSource as usize`
+static ANSWERS: [AtomicUsize; DEFAULT_ORDER.len()] =
+    [const { AtomicUsize::new(0) }; DEFAULT_ORDER.len()];
+/// When the oldest cached license used was looked up, `u64::MAX` when none was
+static OLDEST_CACHE_HIT: AtomicU64 = AtomicU64::new(u64::MAX);
+
+/// A place a license can be looked up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResolverSource {
+    Local,
+    Cache,
+    Registry,
+    ClearlyDefined,
+    Github,
+}
+
+impl ResolverSource {
+    /// Whether answers from this source are worth keeping in the package cache
+    pub(crate) fn is_remote(self) -> bool {
+        matches!(
+            self,
+            ResolverSource::Registry | ResolverSource::ClearlyDefined | ResolverSource::Github
+        )
+    }
+}
+
+impl std::fmt::Display for ResolverSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ResolverSource::Local => "local",
+            ResolverSource::Cache => "cache",
+            ResolverSource::Registry => "registry",
+            ResolverSource::ClearlyDefined => "clearlydefined",
+            ResolverSource::Github => "github",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The package whose license is being looked up
+#[derive(Debug, Clone, Copy)]
+pub struct LicenseQuery<'a> {
+    /// `--language` name of the ecosystem, e.g. "node"
+    pub ecosystem: &'a str,
+    pub name: &'a str,
+    pub version: &'a str,
+    /// Source repository URL, when the analyzer knows it
+    pub repository: Option<&'a str>,
+}
+
+impl<'a> LicenseQuery<'a> {
+    pub fn new(ecosystem: &'a str, name: &'a str, version: &'a str) -> Self {
+        Self {
+            ecosystem,
+            name,
+            version,
+            repository: None,
+        }
+    }
+
+    pub fn with_repository(mut self, repository: Option<&'a str>) -> Self {
+        self.repository = repository;
+        self
+    }
+
+    fn cache_key(&self) -> String {
+        format!("{}:{}@{}", self.ecosystem, self.name, self.version)
+    }
+}
+
+/// One source in the chain
+pub trait LicenseResolver: Send + Sync {
+    fn source(&self) -> ResolverSource;
+
+    /// The package's license, or `None` to let the next source try
+    fn resolve(&self, query: &LicenseQuery) -> Option<String>;
+}
+
+/// An ecosystem's own lookup by package name and version
+pub type Lookup<'a> = &'a (dyn Fn(&str, &str) -> Option<String> + Send + Sync);
+
+/// An ecosystem's own lookup (its local files or its registry) as a chain step
+pub struct LookupResolver<'a> {
+    source: ResolverSource,
+    lookup: Lookup<'a>,
+}
+
+impl<'a> LookupResolver<'a> {
+    pub fn new(source: ResolverSource, lookup: Lookup<'a>) -> Self {
+        Self { source, lookup }
+    }
+}
+
+impl LicenseResolver for LookupResolver<'_> {
+    fn source(&self) -> ResolverSource {
+        self.source
+    }
+
+    fn resolve(&self, query: &LicenseQuery) -> Option<String> {
+        (self.lookup)(query.name, query.version)
+    }
+}
+
+/// Licenses earlier scans found remotely
+pub struct CacheResolver;
+
+impl LicenseResolver for CacheResolver {
+    fn source(&self) -> ResolverSource {
+        ResolverSource::Cache
+    }
+
+    fn resolve(&self, query: &LicenseQuery) -> Option<String> {
+        let entry = with_package_cache(|cache| {
+            cache
+                .entries
+                .get(&query.cache_key())
+                .and_then(|entry| Some((entry.license.clone()?, entry.timestamp)))
+        });
+        let (license, timestamp) = entry?;
+        OLDEST_CACHE_HIT.fetch_min(timestamp, Ordering::Relaxed);
+        Some(license)
+    }
+}
+
+/// The ClearlyDefined definitions API (https://clearlydefined.io)
+pub struct ClearlyDefinedResolver;
+
+impl LicenseResolver for ClearlyDefinedResolver {
+    fn source(&self) -> ResolverSource {
+        ResolverSource::ClearlyDefined
+    }
+
+    fn resolve(&self, query: &LicenseQuery) -> Option<String> {
+        let coordinates = clearlydefined_coordinates(query)?;
+        let url = format!("https://api.clearlydefined.io/definitions/{coordinates}");
+        log(
+            LogLevel::Info,
+            &format!("Fetching license from ClearlyDefined: {url}"),
+        );
+
+        let response = crate::cancel::http_get(&url).ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        let json: Value = response.json().ok()?;
+        json["licensed"]["declared"]
+            .as_str()
+            .and_then(|declared| known_license(declared.to_string()))
+    }
+}
+
+/// The license GitHub detected for the dependency's repository
+pub struct GithubResolver;
+
+impl LicenseResolver for GithubResolver {
+    fn source(&self) -> ResolverSource {
+        ResolverSource::Github
+    }
+
+    fn resolve(&self, query: &LicenseQuery) -> Option<String> {
+        let (owner, repo) = github_repository(query)?;
+        let json = github_api_get(&format!("repos/{owner}/{repo}/license"))?;
+        json["license"]["spdx_id"]
+            .as_str()
+            .and_then(|spdx_id| known_license(spdx_id.to_string()))
+    }
+}
+
+/// GET a GitHub REST API path (e.g. `repos/owner/repo`), authenticated when a token is set
+pub fn github_api_get(path: &str) -> Option<Value> {
+    let url = format!("{}/{path}", crate::licenses::github_api_url());
+    log(LogLevel::Info, &format!("Fetching from GitHub: {url}"));
+
+    let mut request = crate::cancel::shared_client()
+        .ok()?
+        .get(&url)
+        .timeout(crate::cancel::request_timeout(Duration::from_secs(30)));
+    if let Some(token) = crate::licenses::get_github_token() {
+        request = request.bearer_auth(token);
+    }
+
+    let response = crate::cancel::http_send(request, &url).ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response.json().ok()
+}
+
+/// Sources in lookup order, built for one ecosystem
+pub struct ResolverChain<'a> {
+    resolvers: Vec<Box<dyn LicenseResolver + 'a>>,
+    internal: Option<InternalConfig>,
+}
+
+impl<'a> ResolverChain<'a> {
+    /// Chain in the configured order around an ecosystem's own lookups. Pass `None` for a
+    /// source the ecosystem doesn't have.
+    pub fn for_ecosystem(local: Option<Lookup<'a>>, registry: Option<Lookup<'a>>) -> Self {
+        Self::with_order(&configured_order(), local, registry).with_internal(configured_internal())
+    }
+
+    pub fn with_order(
+        order: &[ResolverSource],
+        local: Option<Lookup<'a>>,
+        registry: Option<Lookup<'a>>,
+    ) -> Self {
+        let resolvers = order
+            .iter()
+            .filter_map(|source| -> Option<Box<dyn LicenseResolver + 'a>> {
+                match source {
+                    ResolverSource::Local => Some(Box::new(LookupResolver::new(*source, local?))),
+                    ResolverSource::Registry => {
+                        Some(Box::new(LookupResolver::new(*source, registry?)))
+                    }
+                    ResolverSource::Cache => Some(Box::new(CacheResolver)),
+                    ResolverSource::ClearlyDefined => Some(Box::new(ClearlyDefinedResolver)),
+                    ResolverSource::Github => Some(Box::new(GithubResolver)),
+                }
+            })
+            .collect();
+        Self {
+            resolvers,
+            internal: None,
+        }
+    }
+
+    /// Treat packages in these namespaces as internal: only the local lookup runs, and the
+    /// assumed license stands in when it finds nothing
+    pub fn with_internal(mut self, internal: Option<InternalConfig>) -> Self {
+        self.internal = internal;
+        self
+    }
+
+    /// Ask each source in turn; remote answers are remembered in the package cache. So is a
+    /// package every remote source answered without a license, and the remote sources are
+    /// skipped for it until that miss expires.
+    pub fn resolve(&self, query: &LicenseQuery) -> Option<String> {
+        self.resolve_with_source(query).map(|(license, _)| license)
+    }
+
+    pub fn resolve_with_source(&self, query: &LicenseQuery) -> Option<(String, ResolverSource)> {
+        let internal = self
+            .internal
+            .as_ref()
+            .filter(|internal| internal.is_internal(query.name));
+        let known_miss = is_cached_miss(query);
+        let errors_before = crate::debug::thread_resolution_error_count();
+        let mut asked_remote = false;
+
+        for resolver in &self.resolvers {
+            let source = resolver.source();
+            if source.is_remote() && (crate::cancel::is_cancelled() || crate::cancel::is_offline())
+            {
+                continue;
+            }
+            // Internal packages aren't published anywhere worth asking
+            if internal.is_some() && source != ResolverSource::Local {
+                continue;
+            }
+            if source.is_remote() {
+                if known_miss {
+                    continue;
+                }
+                asked_remote = true;
+            }
+            let Some(license) = resolver.resolve(query).and_then(known_license) else {
+                continue;
+            };
+
+            log(
+                LogLevel::Info,
+                &format!(
+                    "Resolved {} {} via {source}: {license}",
+                    query.name, query.version
+                ),
+            );
+            if source.is_remote() {
+                remember(query, &license, source);
+            }
+            // Custom rules apply to the metadata as found, so the cache keeps the raw value
+            let license = crate::licenses::apply_custom_license_rules(license);
+            count_answer(source);
+            return Some((license, source));
+        }
+
+        if let Some(internal) = internal {
+            log(
+                LogLevel::Info,
+                &format!("{} is internal, assuming {}", query.name, internal.license),
+            );
+            count_answer(ResolverSource::Local);
+            return Some((internal.license.clone(), ResolverSource::Local));
+        }
+
+        if known_miss {
+            log(
+                LogLevel::Info,
+                &format!(
+                    "Skipped remote sources for {} {}: none had a license on a recent scan",
+                    query.name, query.version
+                ),
+            );
+        } else if asked_remote
+            && !crate::cancel::is_cancelled()
+            && crate::debug::thread_resolution_error_count() == errors_before
+        {
+            remember_miss(query);
+        }
+
+        log(
+            LogLevel::Warn,
+            &format!(
+                "No resolver found a license for {} {}",
+                query.name, query.version
+            ),
+        );
+        None
+    }
+}
+
+/// Look a package up through the configured chain around an ecosystem's own lookups
+pub fn resolve_license(
+    query: &LicenseQuery,
+    local: Option<Lookup>,
+    registry: Option<Lookup>,
+) -> Option<String> {
+    // Packages a `--limit` or `--sample` scan leaves out are never looked up
+    if !crate::partial_scan::admit(query.name, query.version) {
+        return None;
+    }
+    ResolverChain::for_ecosystem(local, registry).resolve(query)
+}
+
+/// `None` for the "Unknown ..." placeholders lookups return when they come up empty
+pub fn known_license(license: String) -> Option<String> {
+    let trimmed = license.trim();
+    if trimmed.is_empty()
+        || trimmed.starts_with("Unknown")
+        || trimmed == "NOASSERTION"
+        || trimmed == "OTHER"
+    {
+        None
+    } else {
+        Some(license)
+    }
+}
+
+/// Use `order` for every chain built from now on (the `[resolvers]` config)
+pub fn set_resolver_order(order: Vec<ResolverSource>) {
+    if let Ok(mut current) = ORDER.write() {
+        *current = Some(order);
+    }
+}
+
+/// Run lookups on `jobs` worker threads from now on (the `[resolvers] jobs` config)
+pub fn set_lookup_jobs(jobs: usize) {
+    let jobs = jobs.max(1);
+    if JOBS.swap(jobs, Ordering::Relaxed) != jobs {
+        if let Ok(mut pool) = LOOKUP_POOL.lock() {
+            *pool = None;
+        }
+    }
+}
+
+/// Run `op` on the shared lookup pool, so the parallel iterators inside it fetch at most
+/// `[resolvers] jobs` licenses at once, however many ecosystems are being scanned
+pub fn with_lookup_pool<R: Send>(op: impl FnOnce() -> R + Send) -> R {
+    match lookup_pool() {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+/// `resolve` applied to each of `items` concurrently on the shared lookup pool, in order
+pub fn resolve_all<T: Sync, R: Send>(
+    items: &[T],
+    resolve: impl Fn(&T) -> R + Sync + Send,
+) -> Vec<R> {
+    with_lookup_pool(|| items.par_iter().map(resolve).collect())
+}
+
+fn lookup_pool() -> Option<Arc<rayon::ThreadPool>> {
+    let mut pool = LOOKUP_POOL.lock().ok()?;
+    if pool.is_none() {
+        let jobs = JOBS.load(Ordering::Relaxed);
+        match rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .thread_name(|index| format!("feluda-lookup-{index}"))
+            .build()
+        {
+            Ok(built) => *pool = Some(Arc::new(built)),
+            Err(err) => log(
+                LogLevel::Warn,
+                &format!("Failed to start {jobs} lookup workers, using the global pool: {err}"),
+            ),
+        }
+    }
+    pool.clone()
+}
+
+/// Use these internal namespaces for every chain built from now on (the `[internal]` config)
+pub fn set_internal_namespaces(internal: InternalConfig) {
+    if let Ok(mut current) = INTERNAL.write() {
+        *current = (!internal.namespaces.is_empty()).then_some(internal);
+    }
+}
+
+fn configured_internal() -> Option<InternalConfig> {
+    INTERNAL.read().ok().and_then(|internal| internal.clone())
+}
+
+/// What the resolver chain relied on during a scan
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolverStats {
+    /// Packages each source answered: the configured sources in order, then any other that
+    /// answered (the local fallback for internal packages)
+    pub answered: Vec<(ResolverSource, usize)>,
+    /// When the oldest license taken from the package cache was looked up (Unix seconds)
+    pub oldest_cache_hit: Option<u64>,
+}
+
+fn count_answer(source: ResolverSource) {
+    ANSWERS[source as usize].fetch_add(1, Ordering::Relaxed);
+}
+
+/// The resolver stats since the last call, resetting them so each watch-mode scan reports
+/// its own
+pub fn take_stats() -> ResolverStats {
+    let order = configured_order();
+    let extra = DEFAULT_ORDER
+        .into_iter()
+        .filter(|source| !order.contains(source));
+    let answered = order
+        .iter()
+        .copied()
+        .map(|source| (source, ANSWERS[source as usize].swap(0, Ordering::Relaxed)))
+        .chain(extra.filter_map(|source| {
+            let count = ANSWERS[source as usize].swap(0, Ordering::Relaxed);
+            (count > 0).then_some((source, count))
+        }))
+        .collect();
+    let oldest = OLDEST_CACHE_HIT.swap(u64::MAX, Ordering::Relaxed);
+    ResolverStats {
+        answered,
+        oldest_cache_hit: (oldest != u64::MAX).then_some(oldest),
+    }
+}
+
+fn configured_order() -> Vec<ResolverSource> {
+    ORDER
+        .read()
+        .ok()
+        .and_then(|order| order.clone())
+        .unwrap_or_else(|| DEFAULT_ORDER.to_vec())
+}
+
+/// `type/provider/namespace/name/revision` for the ClearlyDefined API
+fn clearlydefined_coordinates(query: &LicenseQuery) -> Option<String> {
+    let version = query.version.trim_start_matches('=');
+    if !is_exact_version(version) {
+        return None;
+    }
+
+    let (kind, provider, namespace, name) = match query.ecosystem {
+        "node" => match query.name.strip_prefix('@').and_then(|n| n.split_once('/')) {
+            Some((scope, name)) => ("npm", "npmjs", format!("@{scope}"), name.to_string()),
+            None => ("npm", "npmjs", "-".to_string(), query.name.to_string()),
+        },
+        "python" => ("pypi", "pypi", "-".to_string(), query.name.to_string()),
+        "rust" => ("crate", "cratesio", "-".to_string(), query.name.to_string()),
+        "ruby" => ("gem", "rubygems", "-".to_string(), query.name.to_string()),
+        "php" => {
+            let (vendor, name) = query.name.split_once('/')?;
+            (
+                "composer",
+                "packagist",
+                vendor.to_string(),
+                name.to_string(),
+            )
+        }
+        "dotnet" => ("nuget", "nuget", "-".to_string(), query.name.to_string()),
+        "elixir" => ("hex", "hexpm", "-".to_string(), query.name.to_string()),
+        "java" => {
+            let (group, artifact) = query.name.split_once(':')?;
+            (
+                "maven",
+                "mavencentral",
+                group.to_string(),
+                artifact.to_string(),
+            )
+        }
+        "go" => {
+            let (namespace, name) = query.name.rsplit_once('/')?;
+            (
+                "go",
+                "golang",
+                namespace.replace('/', "%2F"),
+                name.to_string(),
+            )
+        }
+        _ => return None,
+    };
+    Some(format!("{kind}/{provider}/{namespace}/{name}/{version}"))
+}
+
+/// Ranges, "latest" and friends can't be looked up by revision
+fn is_exact_version(version: &str) -> bool {
+    !version.is_empty()
+        && !matches!(version, "unknown" | "latest" | "system" | "git" | "archive")
+        && !version.contains(['^', '~', '>', '<', '*', ' ', '|', ','])
+}
+
+/// GitHub owner and repo, from the repository URL or a `github.com/...` Go module path
+fn github_repository(query: &LicenseQuery) -> Option<(String, String)> {
+    parse_github_repository(query.repository.unwrap_or(query.name))
+}
+
+/// Owner and repo from anything naming a GitHub repository: `https://github.com/o/r.git`,
+/// `git@github.com:o/r`, `github.com/o/r/v2`, or npm's `github:o/r#ref` shorthand. With a
+/// GitHub Enterprise Server configured, URLs on its host are matched instead of github.com.
+pub fn parse_github_repository(location: &str) -> Option<(String, String)> {
+    let path = match location.strip_prefix("github:") {
+        Some(shorthand) => shorthand,
+        None => location
+            .split(crate::licenses::github_host().as_str())
+            .nth(1)?
+            .trim_start_matches([':', '/']),
+    };
+    let path = path.split(['#', '?']).next().unwrap_or(path);
+
+    let mut parts = path.split('/');
+    let owner = parts.next().filter(|owner| !owner.is_empty())?;
+    let repo = parts
+        .next()
+        .map(|repo| repo.trim_end_matches(".git"))
+        .filter(|repo| !repo.is_empty())?;
+    Some((owner.to_string(), repo.to_string()))
+}
+
+#[derive(Default)]
+struct PackageCache {
+    entries: HashMap<String, PackageLicense>,
+    dirty: bool,
+}
+
+fn with_package_cache<T>(f: impl FnOnce(&mut PackageCache) -> T) -> T {
+    let mut guard = PACKAGE_CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let cache = guard.get_or_insert_with(|| PackageCache {
+        entries: cache::load_package_licenses().unwrap_or_default(),
+        dirty: false,
+    });
+    f(cache)
+}
+
+fn is_cached_miss(query: &LicenseQuery) -> bool {
+    with_package_cache(|cache| {
+        cache
+            .entries
+            .get(&query.cache_key())
+            .is_some_and(|entry| entry.license.is_none())
+    })
+}
+
+/// Remember that the remote sources answered, and none of them had a license
+fn remember_miss(query: &LicenseQuery) {
+    with_package_cache(|cache| {
+        cache.entries.insert(
+            query.cache_key(),
+            PackageLicense {
+                license: None,
+                source: "none".to_string(),
+                timestamp: cache::now_secs(),
+            },
+        );
+        cache.dirty = true;
+    });
+}
+
+fn remember(query: &LicenseQuery, license: &str, source: ResolverSource) {
+    with_package_cache(|cache| {
+        cache.entries.insert(
+            query.cache_key(),
+            PackageLicense {
+                license: Some(license.to_string()),
+                source: source.to_string(),
+                timestamp: cache::now_secs(),
+            },
+        );
+        cache.dirty = true;
+    });
+}
+
+/// Write licenses resolved during this scan to the package cache
+pub fn flush_package_cache() {
+    let mut guard = PACKAGE_CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let Some(cache) = guard.as_mut().filter(|cache| cache.dirty) else {
+        return;
+    };
+    match cache::save_package_licenses(&cache.entries) {
+        Ok(()) => cache.dirty = false,
+        Err(err) => log(
+            LogLevel::Warn,
+            &format!("Failed to save package license cache: {err}"),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_chain_follows_order() {
+        let local = |_: &str, _: &str| Some("MIT".to_string());
+        let registry = |_: &str, _: &str| Some("Apache-2.0".to_string());
+        let query = LicenseQuery::new("made-up", "pkg", "1.0.0");
+
+        let chain = ResolverChain::with_order(
+            &[ResolverSource::Local, ResolverSource::Registry],
+            Some(&local),
+            Some(&registry),
+        );
+        assert_eq!(
+            chain.resolve_with_source(&query),
+            Some(("MIT".to_string(), ResolverSource::Local))
+        );
+
+        let chain = ResolverChain::with_order(
+            &[ResolverSource::Registry, ResolverSource::Local],
+            Some(&local),
+            Some(&registry),
+        );
+        assert_eq!(chain.resolve(&query), Some("Apache-2.0".to_string()));
+    }
+
+    #[test]
+    fn test_chain_skips_disabled_and_unknown_sources() {
+        let registry_calls = AtomicUsize::new(0);
+        let local = |_: &str, _: &str| Some("Unknown (failed to retrieve)".to_string());
+        let registry = |_: &str, _: &str| {
+            registry_calls.fetch_add(1, Ordering::SeqCst);
+            Some("MIT".to_string())
+        };
+        let query = LicenseQuery::new("made-up", "pkg", "1.0.0");
+
+        let chain =
+            ResolverChain::with_order(&[ResolverSource::Local], Some(&local), Some(&registry));
+        assert_eq!(chain.resolve(&query), None);
+        assert_eq!(registry_calls.load(Ordering::SeqCst), 0);
+
+        let chain = ResolverChain::with_order(&[], Some(&local), Some(&registry));
+        assert_eq!(chain.resolve(&query), None);
+
+        // An ecosystem without a registry lookup just skips that step
+        let chain = ResolverChain::with_order(&DEFAULT_ORDER, Some(&local), None);
+        assert_eq!(chain.resolvers.len(), 4);
+    }
+
+    #[test]
+    fn test_internal_packages_stay_local() {
+        let registry_calls = AtomicUsize::new(0);
+        let local = |name: &str, _: &str| (name == "@myorg/licensed").then(|| "MIT".to_string());
+        let registry = |_: &str, _: &str| {
+            registry_calls.fetch_add(1, Ordering::SeqCst);
+            Some("Apache-2.0".to_string())
+        };
+        let internal = InternalConfig {
+            namespaces: vec!["@myorg/*".to_string()],
+            license: "LicenseRef-Myorg".to_string(),
+        };
+        let chain = ResolverChain::with_order(
+            &[ResolverSource::Registry, ResolverSource::Local],
+            Some(&local),
+            Some(&registry),
+        )
+        .with_internal(Some(internal));
+
+        let query = |name| LicenseQuery::new("node", name, "1.0.0");
+        assert_eq!(
+            chain.resolve(&query("@myorg/licensed")),
+            Some("MIT".to_string())
+        );
+        assert_eq!(
+            chain.resolve(&query("@myorg/ui")),
+            Some("LicenseRef-Myorg".to_string())
+        );
+        assert_eq!(registry_calls.load(Ordering::SeqCst), 0);
+
+        assert_eq!(
+            chain.resolve(&query("left-pad")),
+            Some("Apache-2.0".to_string())
+        );
+        assert_eq!(registry_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_misses_skip_remote_sources() {
+        let registry_calls = AtomicUsize::new(0);
+        let local = |_: &str, _: &str| None;
+        let registry = |_: &str, _: &str| {
+            registry_calls.fetch_add(1, Ordering::SeqCst);
+            None
+        };
+        let query = LicenseQuery::new("made-up", "unlicensed-pkg", "0.1.0");
+        let chain = ResolverChain::with_order(
+            &[ResolverSource::Local, ResolverSource::Registry],
+            Some(&local),
+            Some(&registry),
+        );
+
+        assert_eq!(chain.resolve(&query), None);
+        assert_eq!(registry_calls.load(Ordering::SeqCst), 1);
+        assert!(is_cached_miss(&query));
+
+        assert_eq!(chain.resolve(&query), None);
+        assert_eq!(registry_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_clearlydefined_coordinates() {
+        let coordinates = |ecosystem, name, version| {
+            clearlydefined_coordinates(&LicenseQuery::new(ecosystem, name, version))
+        };
+        assert_eq!(
+            coordinates("node", "@babel/core", "7.24.0").as_deref(),
+            Some("npm/npmjs/@babel/core/7.24.0")
+        );
+        assert_eq!(
+            coordinates("python", "requests", "2.31.0").as_deref(),
+            Some("pypi/pypi/-/requests/2.31.0")
+        );
+        assert_eq!(
+            coordinates("java", "org.slf4j:slf4j-api", "2.0.9").as_deref(),
+            Some("maven/mavencentral/org.slf4j/slf4j-api/2.0.9")
+        );
+        assert_eq!(
+            coordinates("go", "github.com/spf13/cobra", "v1.8.0").as_deref(),
+            Some("go/golang/github.com%2Fspf13/cobra/v1.8.0")
+        );
+        assert_eq!(coordinates("node", "lodash", "^4.17.0"), None);
+        assert_eq!(coordinates("c", "zlib", "1.3"), None);
+    }
+
+    #[test]
+    fn test_github_repository() {
+        let query = LicenseQuery::new("rust", "serde", "1.0.0")
+            .with_repository(Some("https://github.com/serde-rs/serde.git"));
+        assert_eq!(
+            github_repository(&query),
+            Some(("serde-rs".to_string(), "serde".to_string()))
+        );
+
+        let query = LicenseQuery::new("go", "github.com/spf13/cobra", "v1.8.0");
+        assert_eq!(
+            github_repository(&query),
+            Some(("spf13".to_string(), "cobra".to_string()))
+        );
+
+        let query = LicenseQuery::new("node", "lodash", "4.17.21");
+        assert_eq!(github_repository(&query), None);
+
+        assert_eq!(
+            parse_github_repository("github:someone/left-pad#fix-1"),
+            Some(("someone".to_string(), "left-pad".to_string()))
+        );
+        assert_eq!(
+            parse_github_repository("git+ssh://git@github.com:someone/left-pad.git#abc123"),
+            Some(("someone".to_string(), "left-pad".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_known_license() {
+        assert_eq!(known_license("MIT".to_string()), Some("MIT".to_string()));
+        assert_eq!(known_license("Unknown".to_string()), None);
+        assert_eq!(known_license("NOASSERTION".to_string()), None);
+        assert_eq!(known_license("  ".to_string()), None);
+    }
+
+    #[test]
+    fn test_lookups_run_on_the_shared_pool() {
+        let names = resolve_all(&[0, 1, 2, 3], |index| {
+            let thread = std::thread::current()
+                .name()
+                .unwrap_or_default()
+                .to_string();
+            (*index, thread)
+        });
+        assert_eq!(
+            names.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+            [0, 1, 2, 3]
+        );
+        assert!(names
+            .iter()
+            .all(|(_, thread)| thread.starts_with("feluda-lookup-")));
+    }
+}
@@ -0,0 +1,231 @@
+//! `--result-file`: a small machine-readable summary of how the scan ended
+//!
+//! Written alongside whatever the main output is (a table, SARIF, JUnit, the TUI), so a CI
+//! step can branch on the exit code, the counts or specific violations with `jq` instead of
+//! parsing human-oriented output.
+
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::baseline::KnownFindings;
+use crate::debug::{log, FeludaError, FeludaResult, LogLevel, ParseWarning, ResolutionError};
+use crate::ignore::IgnoredDependency;
+use crate::licenses::{LicenseCompatibility, LicenseInfo};
+use crate::report_metadata::ReportMetadata;
+use crate::reporter::{ecosystem_counts, EcosystemCount};
+use crate::resolver::known_license;
+
+/// File name used when `--result-file` is given without a path
+pub const DEFAULT_RESULT_FILE: &str = "feluda-result.json";
+
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct ResultCounts {
+    pub dependencies: usize,
+    pub restrictive: usize,
+    pub incompatible: usize,
+    pub unknown_license: usize,
+    pub ignored: usize,
+    pub resolution_errors: usize,
+    pub parse_warnings: usize,
+    /// The same counts per ecosystem, largest first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ecosystems: Vec<EcosystemCount>,
+}
+
+/// One policy violation, with an id stable across runs (`restrictive:left-pad@1.3.0`)
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Violation {
+    pub id: String,
+    pub kind: &'static str,
+    pub name: String,
+    pub version: String,
+    pub license: String,
+    /// Already in the `--baseline`, so it doesn't fail the build
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub baselined: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScanResult {
+    pub exit_code: i32,
+    /// `passed`, `failed`, or the reason an incomplete scan stopped (`timed out`, ...)
+    pub status: String,
+    pub counts: ResultCounts,
+    pub violations: Vec<Violation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ReportMetadata>,
+}
+
+impl ScanResult {
+    /// Tally a finished scan; the exit code is filled in once it's known
+    pub fn from_scan(
+        dependencies: &[LicenseInfo],
+        ignored: &[IgnoredDependency],
+        resolution_errors: &[ResolutionError],
+        warnings: &[ParseWarning],
+        osi_violations: &[LicenseInfo],
+    ) -> Self {
+        let mut violations = Vec::new();
+        let mut push = |kind: &'static str, dep: &LicenseInfo| {
+            violations.push(Violation {
+                id: format!("{kind}:{}@{}", dep.name, dep.version),
+                kind,
+                name: dep.name.clone(),
+                version: dep.version.clone(),
+                license: dep.get_license(),
+                baselined: false,
+            });
+        };
+        for dep in dependencies {
+            if dep.is_restrictive {
+                push("restrictive", dep);
+            }
+            if dep.compatibility == LicenseCompatibility::Incompatible {
+                push("incompatible", dep);
+            }
+        }
+        for dep in osi_violations {
+            push("osi", dep);
+        }
+
+        let counts = ResultCounts {
+            dependencies: dependencies.len(),
+            restrictive: dependencies.iter().filter(|d| d.is_restrictive).count(),
+            incompatible: dependencies
+                .iter()
+                .filter(|d| d.compatibility == LicenseCompatibility::Incompatible)
+                .count(),
+            unknown_license: dependencies
+                .iter()
+                .filter(|d| d.license.clone().and_then(known_license).is_none())
+                .count(),
+            ignored: ignored.len(),
+            resolution_errors: resolution_errors.len(),
+            parse_warnings: warnings.len(),
+            ecosystems: ecosystem_counts(dependencies),
+        };
+
+        Self {
+            exit_code: 0,
+            status: "passed".to_string(),
+            counts,
+            violations,
+            metadata: None,
+        }
+    }
+
+    pub fn with_metadata(mut self, metadata: ReportMetadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Mark the violations the `--baseline` already had
+    pub fn with_baseline(mut self, known: Option<&KnownFindings>) -> Self {
+        if let Some(known) = known {
+            for violation in &mut self.violations {
+                violation.baselined = known.knows(violation.kind, &violation.name);
+            }
+        }
+        self
+    }
+
+    /// Record how the scan exits: `failed` for a policy failure, or `status` as given
+    pub fn with_exit(mut self, exit_code: i32, status: Option<String>) -> Self {
+        self.exit_code = exit_code;
+        self.status = match status {
+            Some(status) => status,
+            None if exit_code == 0 => "passed".to_string(),
+            None => "failed".to_string(),
+        };
+        self
+    }
+
+    pub fn write(&self, path: &Path) -> FeludaResult<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| FeludaError::Serialization(format!("Failed to serialize result: {e}")))?;
+        fs::write(path, json + "\n").map_err(|e| {
+            FeludaError::FileWrite(format!("Failed to write {}: {e}", path.display()))
+        })?;
+        log(
+            LogLevel::Info,
+            &format!("Wrote scan result to {}", path.display()),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_result_counts_and_violations() {
+        let mut gpl = LicenseInfo::fixture("readline", "1.0.0", "GPL-3.0-only").restrictive();
+        gpl.compatibility = LicenseCompatibility::Incompatible;
+        let deps = vec![
+            LicenseInfo::fixture("serde", "1.0.0", "MIT"),
+            gpl,
+            LicenseInfo::fixture("mystery", "1.0.0", "Unknown license"),
+        ];
+
+        let result = ScanResult::from_scan(&deps, &[], &[], &[], &[]).with_exit(1, None);
+        assert_eq!(result.status, "failed");
+        assert_eq!(
+            result.counts,
+            ResultCounts {
+                dependencies: 3,
+                restrictive: 1,
+                incompatible: 1,
+                unknown_license: 1,
+                ..Default::default()
+            }
+        );
+        let ids: Vec<&str> = result.violations.iter().map(|v| v.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            ["restrictive:readline@1.0.0", "incompatible:readline@1.0.0"]
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DEFAULT_RESULT_FILE);
+        result.write(&path).unwrap();
+        let written: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["exit_code"], 1);
+        assert_eq!(written["counts"]["restrictive"], 1);
+        assert!(written["counts"].get("ecosystems").is_none());
+    }
+
+    #[test]
+    fn test_scan_result_counts_per_ecosystem() {
+        let mut deps = vec![
+            LicenseInfo::fixture("serde", "1.0.0", "MIT"),
+            LicenseInfo::fixture("readline", "1.0.0", "GPL-3.0-only").restrictive(),
+            LicenseInfo::fixture("left-pad", "1.0.0", "MIT"),
+        ];
+        deps[0].ecosystem = Some("rust".to_string());
+        deps[1].ecosystem = Some("rust".to_string());
+        deps[2].ecosystem = Some("node".to_string());
+
+        let result = ScanResult::from_scan(&deps, &[], &[], &[], &[]);
+        assert_eq!(
+            result.counts.ecosystems,
+            vec![
+                EcosystemCount {
+                    ecosystem: "rust".to_string(),
+                    dependencies: 2,
+                    restrictive: 1,
+                    incompatible: 0,
+                },
+                EcosystemCount {
+                    ecosystem: "node".to_string(),
+                    dependencies: 1,
+                    restrictive: 0,
+                    incompatible: 0,
+                },
+            ]
+        );
+    }
+}
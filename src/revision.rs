@@ -0,0 +1,163 @@
+//! `--rev`: scan a git revision instead of the working tree
+//!
+//! The revision's tree is checked out with libgit2 into a scratch directory, leaving the
+//! working tree, `HEAD` and the index alone, and the scan runs there. CI can scan the base and
+//! the head of a pull request from one checkout and compare the reports with `feluda diff`.
+//! The whole tree is written, not only the manifests and lockfiles, because analyzers such as
+//! `cargo metadata` need the crate's sources to exist. Submodules aren't populated.
+
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
+use crate::report_metadata::GitState;
+
+/// The commit checked out for this scan, recorded in the report metadata
+static SCANNED_REVISION: RwLock<Option<GitState>> = RwLock::new(None);
+
+/// The revision `--rev` checked out, if any
+pub fn scanned_revision() -> Option<GitState> {
+    SCANNED_REVISION.read().ok()?.clone()
+}
+
+/// Check out `rev` of the repository containing `path` into `dest`. Returns the directory that
+/// corresponds to `path` in the checkout, so scanning a subdirectory of a repository works.
+pub fn checkout_revision(path: &Path, rev: &str, dest: &Path) -> FeludaResult<PathBuf> {
+    let path = path.canonicalize()?;
+    let repo = git2::Repository::discover(&path).map_err(|e| {
+        FeludaError::InvalidData(format!(
+            "--rev needs a git repository, but {} is not inside one: {e}",
+            path.display()
+        ))
+    })?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| FeludaError::InvalidData("bare repositories are not supported".into()))?
+        .canonicalize()?;
+
+    let commit = repo
+        .revparse_single(rev)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|e| {
+            FeludaError::InvalidData(format!(
+                "cannot resolve git revision '{rev}' (is the history fetched?): {e}"
+            ))
+        })?;
+
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    checkout
+        .target_dir(dest)
+        .update_index(false)
+        .recreate_missing(true)
+        .force();
+    repo.checkout_tree(commit.as_object(), Some(&mut checkout))
+        .map_err(|e| {
+            FeludaError::InvalidData(format!("failed to check out revision '{rev}': {e}"))
+        })?;
+
+    let id = commit.id().to_string();
+    log(
+        LogLevel::Info,
+        &format!("Checked out {rev} ({}) into {}", &id[..12], dest.display()),
+    );
+    let branch = repo
+        .find_branch(rev, git2::BranchType::Local)
+        .or_else(|_| repo.find_branch(rev, git2::BranchType::Remote))
+        .is_ok()
+        .then(|| rev.to_string());
+    if let Ok(mut scanned) = SCANNED_REVISION.write() {
+        *scanned = Some(GitState {
+            commit: id,
+            branch,
+            dirty: false,
+        });
+    }
+
+    let relative = path.strip_prefix(&workdir).unwrap_or(Path::new(""));
+    Ok(dest.join(relative))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn commit_all(repo: &git2::Repository, message: &str) {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_checkout_revision() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let repo = git2::Repository::init(root).unwrap();
+
+        fs::create_dir_all(root.join("web")).unwrap();
+        fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(root.join("web/package.json"), r#"{"dependencies": {}}"#).unwrap();
+        commit_all(&repo, "base");
+        fs::write(
+            root.join("web/package.json"),
+            r#"{"dependencies": {"left-pad": "1.0.0"}}"#,
+        )
+        .unwrap();
+        fs::write(root.join("web/yarn.lock"), "").unwrap();
+        commit_all(&repo, "head");
+        // Uncommitted edits stay out of the checkout
+        fs::write(root.join("Cargo.toml"), "[workspace]").unwrap();
+
+        let out = TempDir::new().unwrap();
+        let scanned = checkout_revision(&root.join("web"), "HEAD~1", out.path()).unwrap();
+        assert_eq!(scanned, out.path().join("web"));
+        assert_eq!(
+            fs::read_to_string(scanned.join("package.json")).unwrap(),
+            r#"{"dependencies": {}}"#
+        );
+        assert!(!scanned.join("yarn.lock").exists());
+        assert_eq!(
+            fs::read_to_string(out.path().join("Cargo.toml")).unwrap(),
+            "[package]"
+        );
+
+        // The working tree and the index are untouched
+        assert_eq!(
+            fs::read_to_string(root.join("web/package.json")).unwrap(),
+            r#"{"dependencies": {"left-pad": "1.0.0"}}"#
+        );
+        assert!(root.join("web/yarn.lock").exists());
+        let statuses = repo.statuses(None).unwrap();
+        assert_eq!(statuses.len(), 1);
+
+        let revision = scanned_revision().unwrap();
+        assert_eq!(
+            revision.commit,
+            repo.revparse_single("HEAD~1").unwrap().id().to_string()
+        );
+        assert_eq!(revision.branch, None);
+
+        assert!(checkout_revision(root, "no-such-rev", out.path()).is_err());
+    }
+}
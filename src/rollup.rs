@@ -0,0 +1,640 @@
+//! `feluda rollup`: one compliance picture across many repositories
+//!
+//! Each repository is scanned the way `feluda --path <repo> --json` would scan it, and its
+//! results are written to `<out>/repos/<name>.json`. `<out>/rollup.json` then ranks the
+//! repositories worst offender first and totals the organisation's restrictive and incompatible
+//! dependencies by license, so a compliance program can track the same numbers run over run.
+//!
+//! Repositories come from the command line or a manifest file with one per line, either a path
+//! (relative to the manifest) or a Git URL that is cloned for the scan:
+//!
+//! ```text
+//! # Platform team
+//! ../billing-service
+//! https://github.com/acme/web-app.git
+//! ```
+//!
+//! Every repository is judged against the same `.feluda.toml` (the one Feluda loads from the
+//! current directory), so the rollup reflects a single organisation-wide policy.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use colored::*;
+use serde::Serialize;
+use tempfile::TempDir;
+
+use crate::cli::Cli;
+use crate::debug::{
+    log, take_parse_warnings, take_resolution_errors, FeludaError, FeludaResult, LogLevel,
+};
+use crate::ignore::take_ignored_dependencies;
+use crate::licenses::{spdx_special_value, LicenseCompatibility, LicenseInfo};
+use crate::reporter::TableFormatter;
+use crate::utils::clone_repository;
+use crate::{analyze_dependencies, annotate_compatibility, Analysis, CheckConfig};
+
+/// Organisation summary written next to the per-repository reports
+const ROLLUP_FILE: &str = "rollup.json";
+
+/// Directory under `--out` holding one report per repository
+const REPOS_DIR: &str = "repos";
+
+/// A repository to include in the rollup
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RepoSource {
+    Path(PathBuf),
+    Git(String),
+}
+
+impl RepoSource {
+    fn from_entry(entry: &str, base: &Path) -> Self {
+        if is_git_url(entry) {
+            RepoSource::Git(entry.to_string())
+        } else {
+            RepoSource::Path(base.join(entry))
+        }
+    }
+
+    /// Short name for the repository: its directory, or the last segment of its URL
+    fn name(&self) -> String {
+        let name = match self {
+            RepoSource::Path(path) => path
+                .canonicalize()
+                .ok()
+                .and_then(|path| {
+                    path.file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                })
+                .or_else(|| {
+                    path.file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                }),
+            RepoSource::Git(url) => url
+                .trim_end_matches('/')
+                .rsplit(['/', ':'])
+                .next()
+                .map(|name| name.trim_end_matches(".git").to_string()),
+        };
+        name.filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "repository".to_string())
+    }
+
+    fn display(&self) -> String {
+        match self {
+            RepoSource::Path(path) => path.display().to_string(),
+            RepoSource::Git(url) => url.clone(),
+        }
+    }
+}
+
+fn is_git_url(entry: &str) -> bool {
+    ["https://", "http://", "ssh://", "git://", "git@"]
+        .iter()
+        .any(|prefix| entry.starts_with(prefix))
+}
+
+/// One repository's line in the rollup
+#[derive(Debug, Clone, Default, Serialize)]
+struct RepoSummary {
+    name: String,
+    source: String,
+    /// Per-repository report, relative to the rollup directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    report: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    project_license: Option<String>,
+    dependencies: usize,
+    restrictive: usize,
+    incompatible: usize,
+    /// Dependencies with no license, or NOASSERTION/NONE
+    unknown: usize,
+    resolution_errors: usize,
+    parse_warnings: usize,
+    ignored: usize,
+    /// Why the repository couldn't be scanned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl RepoSummary {
+    fn failed(name: String, source: String, error: String) -> Self {
+        Self {
+            name,
+            source,
+            error: Some(error),
+            ..Default::default()
+        }
+    }
+}
+
+/// Totals across every repository in the rollup
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+struct RollupTotals {
+    repositories: usize,
+    failed: usize,
+    dependencies: usize,
+    restrictive: usize,
+    incompatible: usize,
+    unknown: usize,
+}
+
+/// A restrictive or incompatible license and where the organisation depends on it
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct LicenseUsage {
+    license: String,
+    dependencies: usize,
+    repositories: Vec<String>,
+}
+
+/// `rollup.json`
+#[derive(Debug, Serialize)]
+struct Rollup {
+    generated_at: String,
+    totals: RollupTotals,
+    /// Ranked worst offender first
+    repositories: Vec<RepoSummary>,
+    /// Restrictive and incompatible licenses, most used first
+    licenses: Vec<LicenseUsage>,
+}
+
+/// Scan every repository, write the per-repository reports and the rollup into `out`
+pub fn handle_rollup_command(
+    args: &Cli,
+    paths: Vec<String>,
+    manifest: Option<String>,
+    out: String,
+    top: usize,
+    template: CheckConfig,
+) -> FeludaResult<()> {
+    let mut sources: Vec<RepoSource> = paths
+        .iter()
+        .map(|path| RepoSource::from_entry(path, Path::new("")))
+        .collect();
+    if let Some(manifest) = manifest {
+        sources.extend(read_manifest(Path::new(&manifest))?);
+    }
+    if sources.is_empty() {
+        return Err(FeludaError::InvalidData(
+            "No repositories to roll up: pass their paths or --manifest FILE".to_string(),
+        ));
+    }
+
+    let out = PathBuf::from(out);
+    let repos_dir = out.join(REPOS_DIR);
+    fs::create_dir_all(&repos_dir).map_err(|e| {
+        FeludaError::FileWrite(format!(
+            "Failed to create rollup directory {}: {e}",
+            repos_dir.display()
+        ))
+    })?;
+
+    let names = unique_names(&sources);
+    let mut summaries = Vec::new();
+    let mut usages: Vec<(String, Vec<LicenseInfo>)> = Vec::new();
+    for (index, (source, name)) in sources.iter().zip(names).enumerate() {
+        eprintln!(
+            "{} {}",
+            format!("[{}/{}]", index + 1, sources.len()).dimmed(),
+            format!("Scanning {name} ({})", source.display()).bold()
+        );
+        match scan_repository(args, source, &name, &template) {
+            Ok((mut summary, dependencies)) => {
+                let report = format!("{REPOS_DIR}/{name}.json");
+                write_json(&out.join(&report), &dependencies)?;
+                summary.report = Some(report);
+                summaries.push(summary);
+                usages.push((name, dependencies));
+            }
+            Err(err) => {
+                log(
+                    LogLevel::Error,
+                    &format!("Rollup scan of {} failed: {err}", source.display()),
+                );
+                eprintln!("❌ {name}: {err}");
+                summaries.push(RepoSummary::failed(name, source.display(), err.to_string()));
+            }
+        }
+    }
+
+    rank_repositories(&mut summaries);
+    let rollup = Rollup {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        totals: totals(&summaries),
+        licenses: license_usage(&usages),
+        repositories: summaries,
+    };
+    let rollup_path = out.join(ROLLUP_FILE);
+    write_json(&rollup_path, &rollup)?;
+
+    if template.json {
+        let json = serde_json::to_string_pretty(&rollup)
+            .map_err(|e| FeludaError::Serialization(format!("Failed to serialize rollup: {e}")))?;
+        println!("{json}");
+    } else {
+        print_rollup(&rollup, top);
+    }
+    eprintln!("Rollup written to: {}", rollup_path.display());
+
+    if (template.fail_on_restrictive && rollup.totals.restrictive > 0)
+        || (template.fail_on_incompatible && rollup.totals.incompatible > 0)
+    {
+        log(
+            LogLevel::Warn,
+            "Exiting with non-zero status due to license issues in the rollup",
+        );
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// Read a manifest of repositories, one path or Git URL per line
+fn read_manifest(path: &Path) -> FeludaResult<Vec<RepoSource>> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        FeludaError::InvalidData(format!(
+            "Failed to read rollup manifest {}: {e}",
+            path.display()
+        ))
+    })?;
+    let base = path.parent().unwrap_or(Path::new(""));
+    Ok(parse_manifest(&content, base))
+}
+
+/// Manifest entries, skipping blank lines and `#` comments; paths are relative to `base`
+fn parse_manifest(content: &str, base: &Path) -> Vec<RepoSource> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| RepoSource::from_entry(line, base))
+        .collect()
+}
+
+/// Report file names, with `-2`, `-3`, ... added when two repositories share a name
+fn unique_names(sources: &[RepoSource]) -> Vec<String> {
+    let mut seen: BTreeMap<String, usize> = BTreeMap::new();
+    sources
+        .iter()
+        .map(|source| {
+            let name = source.name();
+            let count = seen.entry(name.clone()).or_default();
+            *count += 1;
+            if *count == 1 {
+                name
+            } else {
+                format!("{name}-{count}")
+            }
+        })
+        .collect()
+}
+
+/// Scan one repository, cloning it first when it's a Git URL
+fn scan_repository(
+    args: &Cli,
+    source: &RepoSource,
+    name: &str,
+    template: &CheckConfig,
+) -> FeludaResult<(RepoSummary, Vec<LicenseInfo>)> {
+    let (path, _clone) = match source {
+        RepoSource::Path(path) => {
+            if !path.is_dir() {
+                return Err(FeludaError::InvalidData(format!(
+                    "{} is not a directory",
+                    path.display()
+                )));
+            }
+            (path.clone(), None)
+        }
+        RepoSource::Git(url) => {
+            let temp_dir = TempDir::new().map_err(|e| {
+                FeludaError::TempDir(format!("Failed to create temporary directory: {e}"))
+            })?;
+            let mut clone_args = args.clone();
+            clone_args.repo = Some(url.clone());
+            clone_repository(&clone_args, temp_dir.path())?;
+            (temp_dir.path().to_path_buf(), Some(temp_dir))
+        }
+    };
+
+    let config = CheckConfig {
+        path: path.to_string_lossy().to_string(),
+        project_name: name.to_string(),
+        ..template.clone()
+    };
+    let analysis = analyze_dependencies(&config);
+    // A failed scan leaves its notices behind; don't let them leak into the next repository
+    let Analysis {
+        dependencies: mut analyzed_data,
+        project_license,
+        warnings,
+        resolution_errors,
+        ignored,
+    } = analysis.inspect_err(|_| {
+        take_parse_warnings();
+        take_resolution_errors();
+        take_ignored_dependencies();
+    })?;
+    annotate_compatibility(&mut analyzed_data, &project_license, config.strict);
+    crate::suggestions::annotate_suggestions(&mut analyzed_data);
+
+    let summary = RepoSummary {
+        name: name.to_string(),
+        source: source.display(),
+        report: None,
+        project_license,
+        dependencies: analyzed_data.len(),
+        restrictive: analyzed_data
+            .iter()
+            .filter(|dep| dep.is_restrictive)
+            .count(),
+        incompatible: analyzed_data
+            .iter()
+            .filter(|dep| dep.compatibility == LicenseCompatibility::Incompatible)
+            .count(),
+        unknown: analyzed_data.iter().filter(|dep| is_unknown(dep)).count(),
+        resolution_errors: resolution_errors.len(),
+        parse_warnings: warnings.len(),
+        ignored: ignored.len(),
+        error: None,
+    };
+    Ok((summary, analyzed_data))
+}
+
+fn is_unknown(dep: &LicenseInfo) -> bool {
+    dep.license
+        .as_deref()
+        .is_none_or(|license| spdx_special_value(license).is_some())
+}
+
+/// Worst offender first: incompatible, then restrictive, then unknown licenses, then failed
+/// lookups. Repositories that couldn't be scanned go last.
+fn rank_repositories(summaries: &mut [RepoSummary]) {
+    summaries.sort_by(|a, b| {
+        a.error
+            .is_some()
+            .cmp(&b.error.is_some())
+            .then(b.incompatible.cmp(&a.incompatible))
+            .then(b.restrictive.cmp(&a.restrictive))
+            .then(b.unknown.cmp(&a.unknown))
+            .then(b.resolution_errors.cmp(&a.resolution_errors))
+            .then(a.name.cmp(&b.name))
+    });
+}
+
+fn totals(summaries: &[RepoSummary]) -> RollupTotals {
+    summaries
+        .iter()
+        .fold(RollupTotals::default(), |mut totals, summary| {
+            totals.repositories += 1;
+            totals.failed += usize::from(summary.error.is_some());
+            totals.dependencies += summary.dependencies;
+            totals.restrictive += summary.restrictive;
+            totals.incompatible += summary.incompatible;
+            totals.unknown += summary.unknown;
+            totals
+        })
+}
+
+/// Restrictive and incompatible licenses across the repositories, most dependencies first
+fn license_usage(repos: &[(String, Vec<LicenseInfo>)]) -> Vec<LicenseUsage> {
+    let mut usage: BTreeMap<String, (usize, Vec<String>)> = BTreeMap::new();
+    for (repo, dependencies) in repos {
+        for dep in dependencies.iter().filter(|dep| {
+            dep.is_restrictive || dep.compatibility == LicenseCompatibility::Incompatible
+        }) {
+            let (count, repositories) = usage.entry(dep.get_license()).or_default();
+            *count += 1;
+            if !repositories.contains(repo) {
+                repositories.push(repo.clone());
+            }
+        }
+    }
+
+    let mut licenses: Vec<LicenseUsage> = usage
+        .into_iter()
+        .map(|(license, (dependencies, repositories))| LicenseUsage {
+            license,
+            dependencies,
+            repositories,
+        })
+        .collect();
+    licenses.sort_by_key(|license| std::cmp::Reverse(license.dependencies));
+    licenses
+}
+
+fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> FeludaResult<()> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| {
+        FeludaError::Serialization(format!("Failed to serialize {}: {e}", path.display()))
+    })?;
+    fs::write(path, json)
+        .map_err(|e| FeludaError::FileWrite(format!("Failed to write {}: {e}", path.display())))
+}
+
+/// The ranking table (the `top` worst repositories), the totals, and the licenses behind them
+fn print_rollup(rollup: &Rollup, top: usize) {
+    println!(
+        "\n{} {}\n",
+        "🏢".bold(),
+        "Organization Rollup".bold().underline()
+    );
+
+    let headers = [
+        "#",
+        "Repository",
+        "Dependencies",
+        "Restrictive",
+        "Incompatible",
+        "Unknown",
+        "Lookup errors",
+    ]
+    .map(String::from)
+    .to_vec();
+    let rows: Vec<(Vec<String>, bool)> = rollup
+        .repositories
+        .iter()
+        .take(top)
+        .enumerate()
+        .map(|(index, repo)| {
+            let row = match &repo.error {
+                Some(_) => vec![
+                    (index + 1).to_string(),
+                    repo.name.clone(),
+                    "scan failed".to_string(),
+                    "-".to_string(),
+                    "-".to_string(),
+                    "-".to_string(),
+                    "-".to_string(),
+                ],
+                None => vec![
+                    (index + 1).to_string(),
+                    repo.name.clone(),
+                    repo.dependencies.to_string(),
+                    repo.restrictive.to_string(),
+                    repo.incompatible.to_string(),
+                    repo.unknown.to_string(),
+                    repo.resolution_errors.to_string(),
+                ],
+            };
+            let problematic = repo.error.is_some() || repo.restrictive > 0 || repo.incompatible > 0;
+            (row, problematic)
+        })
+        .collect();
+
+    let mut formatter = TableFormatter::new(headers);
+    for (row, _) in &rows {
+        formatter.add_row(row);
+    }
+    println!("{}", formatter.render_header());
+    for (row, problematic) in &rows {
+        println!("{}", formatter.render_row(row, *problematic));
+    }
+    println!("{}", formatter.render_footer());
+    if rollup.repositories.len() > top {
+        println!(
+            "{}",
+            format!(
+                "… and {} more in {ROLLUP_FILE}",
+                rollup.repositories.len() - top
+            )
+            .dimmed()
+        );
+    }
+
+    let totals = &rollup.totals;
+    println!(
+        "\n{} {}",
+        "📦".bold(),
+        format!(
+            "{} dependencies across {} repositories: {} restrictive, {} incompatible, {} unknown",
+            totals.dependencies,
+            totals.repositories,
+            totals.restrictive,
+            totals.incompatible,
+            totals.unknown
+        )
+        .bold()
+    );
+    if totals.failed > 0 {
+        println!(
+            "{}",
+            format!("❌ {} repositories could not be scanned", totals.failed).red()
+        );
+    }
+
+    for usage in &rollup.licenses {
+        println!(
+            "  • {} in {} dependencies ({})",
+            usage.license.as_str().red().bold(),
+            usage.dependencies,
+            usage.repositories.join(", ")
+        );
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(name: &str, restrictive: usize, incompatible: usize) -> RepoSummary {
+        RepoSummary {
+            name: name.to_string(),
+            restrictive,
+            incompatible,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let sources = parse_manifest(
+            "# Platform team\n\n  ../billing  \nhttps://github.com/acme/web-app.git\ngit@github.com:acme/cli.git\n",
+            Path::new("/org"),
+        );
+        assert_eq!(
+            sources,
+            vec![
+                RepoSource::Path(PathBuf::from("/org/../billing")),
+                RepoSource::Git("https://github.com/acme/web-app.git".to_string()),
+                RepoSource::Git("git@github.com:acme/cli.git".to_string()),
+            ]
+        );
+        assert_eq!(sources[1].name(), "web-app");
+        assert_eq!(sources[2].name(), "cli");
+    }
+
+    #[test]
+    fn test_unique_names() {
+        let sources = vec![
+            RepoSource::Git("https://github.com/acme/api.git".to_string()),
+            RepoSource::Git("https://gitlab.com/acme/api".to_string()),
+            RepoSource::Path(PathBuf::from("/nonexistent/web")),
+        ];
+        assert_eq!(unique_names(&sources), vec!["api", "api-2", "web"]);
+    }
+
+    #[test]
+    fn test_rank_repositories() {
+        let mut summaries = vec![
+            summary("clean", 0, 0),
+            RepoSummary::failed("broken".to_string(), String::new(), "boom".to_string()),
+            summary("gpl-heavy", 5, 0),
+            summary("incompatible", 1, 2),
+        ];
+        rank_repositories(&mut summaries);
+        let ranked: Vec<&str> = summaries.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(ranked, vec!["incompatible", "gpl-heavy", "clean", "broken"]);
+
+        assert_eq!(
+            totals(&summaries),
+            RollupTotals {
+                repositories: 4,
+                failed: 1,
+                dependencies: 0,
+                restrictive: 6,
+                incompatible: 2,
+                unknown: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_license_usage() {
+        let repos = vec![
+            (
+                "api".to_string(),
+                vec![
+                    LicenseInfo::fixture("readline", "1.0.0", "GPL-3.0").restrictive(),
+                    LicenseInfo::fixture("serde", "1.0.0", "MIT"),
+                ],
+            ),
+            (
+                "web".to_string(),
+                vec![
+                    LicenseInfo::fixture("readline", "1.0.0", "GPL-3.0").restrictive(),
+                    LicenseInfo::fixture("ghostscript", "1.0.0", "AGPL-3.0").restrictive(),
+                    LicenseInfo::fixture("mystery", "1.0.0", None),
+                ],
+            ),
+        ];
+        assert_eq!(
+            license_usage(&repos),
+            vec![
+                LicenseUsage {
+                    license: "GPL-3.0".to_string(),
+                    dependencies: 2,
+                    repositories: vec!["api".to_string(), "web".to_string()],
+                },
+                LicenseUsage {
+                    license: "AGPL-3.0".to_string(),
+                    dependencies: 1,
+                    repositories: vec!["web".to_string()],
+                },
+            ]
+        );
+        assert!(is_unknown(&repos[1].1[2]));
+        assert!(!is_unknown(&repos[0].1[1]));
+    }
+}
@@ -1,10 +1,17 @@
-use chrono::{DateTime, Utc};
+use std::io;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
 use crate::sbom::spdx::SpdxDocument;
 
+/// XML namespace of the CycloneDX 1.5 schema
+const CYCLONEDX_XML_NAMESPACE: &str = "http://cyclonedx.org/schema/bom/1.5";
+
 /// CycloneDX v1.5 BOM structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -30,6 +37,10 @@ pub struct CycloneDxBom {
     /// List of components (optional)
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub components: Vec<CycloneDxComponent>,
+
+    /// Dependency graph between components, by `bom-ref` (optional)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<CycloneDxDependency>,
 }
 
 /// CycloneDX metadata structure
@@ -51,6 +62,17 @@ pub struct CycloneDxMetadata {
     /// Component that represents the BOM (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub component: Option<CycloneDxComponent>,
+
+    /// Name-value pairs describing how the BOM was made (optional)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub properties: Vec<CycloneDxProperty>,
+}
+
+/// CycloneDX property: a name-value pair outside the standard fields
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CycloneDxProperty {
+    pub name: String,
+    pub value: String,
 }
 
 /// CycloneDX tools structure (v1.5 format)
@@ -112,6 +134,10 @@ pub struct CycloneDxComponent {
     #[serde(rename = "type")]
     pub component_type: String, // "library", "application", "framework", etc.
 
+    /// Reference other parts of the BOM use for this component (optional)
+    #[serde(rename = "bom-ref", skip_serializing_if = "Option::is_none")]
+    pub bom_ref: Option<String>,
+
     /// Component name (required)
     pub name: String,
 
@@ -170,6 +196,19 @@ pub struct CycloneDxLicense {
     pub url: Option<String>,
 }
 
+/// CycloneDX dependency entry: the components one component directly depends on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CycloneDxDependency {
+    /// `bom-ref` of the depending component (required)
+    #[serde(rename = "ref")]
+    pub dependency_ref: String,
+
+    /// `bom-ref`s of its direct dependencies (optional)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+}
+
 /// CycloneDX external reference structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CycloneDxExternalReference {
@@ -206,8 +245,10 @@ impl CycloneDxBom {
                 }),
                 authors: vec![],
                 component: None,
+                properties: Vec::new(),
             }),
             components: Vec::new(),
+            dependencies: Vec::new(),
         }
     }
 
@@ -223,7 +264,7 @@ impl Default for CycloneDxBom {
 }
 
 /// Convert SPDX license to CycloneDX license format
-fn convert_spdx_license_to_cyclonedx(spdx_license: &str) -> CycloneDxLicenseChoice {
+pub fn convert_spdx_license_to_cyclonedx(spdx_license: &str) -> CycloneDxLicenseChoice {
     // Check if it looks like an SPDX expression (contains AND, OR, WITH)
     if spdx_license.contains(" AND ")
         || spdx_license.contains(" OR ")
@@ -261,13 +302,18 @@ pub fn convert_spdx_to_cyclonedx(spdx_doc: &SpdxDocument) -> CycloneDxBom {
     for spdx_package in &spdx_doc.packages {
         let mut component = CycloneDxComponent {
             component_type: "library".to_string(), // Default to library for dependencies
+            bom_ref: None,
             name: spdx_package.name.clone(),
             version: spdx_package.version_info.clone(),
             description: None,
             scope: Some("required".to_string()), // Default scope
             licenses: Vec::new(),
             copyright: spdx_package.copyright_text.clone(),
-            purl: None, // Could be enhanced in the future
+            purl: spdx_package
+                .external_refs
+                .iter()
+                .find(|reference| reference.reference_type == "purl")
+                .map(|reference| reference.reference_locator.clone()),
             external_references: Vec::new(),
         };
 
@@ -296,6 +342,211 @@ pub fn convert_spdx_to_cyclonedx(spdx_doc: &SpdxDocument) -> CycloneDxBom {
     bom
 }
 
+/// Serialize a BOM as CycloneDX JSON
+pub fn cyclonedx_json(bom: &CycloneDxBom) -> FeludaResult<String> {
+    serde_json::to_string_pretty(bom)
+        .map_err(|e| FeludaError::Serialization(format!("Failed to serialize CycloneDX BOM: {e}")))
+}
+
+/// Serialize a BOM as CycloneDX XML
+///
+/// The XML schema nests and names things differently from the JSON one (attributes,
+/// `<reference>` and `<dependency>` children), so it's written by hand rather than via serde.
+pub fn cyclonedx_xml(bom: &CycloneDxBom) -> FeludaResult<String> {
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    write_bom_xml(&mut writer, bom).map_err(|e| {
+        FeludaError::Serialization(format!("Failed to serialize CycloneDX BOM as XML: {e}"))
+    })?;
+    String::from_utf8(writer.into_inner()).map_err(|e| {
+        FeludaError::Serialization(format!("Failed to serialize CycloneDX BOM as XML: {e}"))
+    })
+}
+
+fn write_bom_xml(writer: &mut Writer<Vec<u8>>, bom: &CycloneDxBom) -> io::Result<()> {
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+
+    let version = bom.version.unwrap_or(1).to_string();
+    let mut attributes = vec![("xmlns", CYCLONEDX_XML_NAMESPACE)];
+    if let Some(ref serial_number) = bom.serial_number {
+        attributes.push(("serialNumber", serial_number.as_str()));
+    }
+    attributes.push(("version", version.as_str()));
+    start_element(writer, "bom", &attributes)?;
+
+    if let Some(ref metadata) = bom.metadata {
+        start_element(writer, "metadata", &[])?;
+        if let Some(timestamp) = metadata.timestamp {
+            text_element(
+                writer,
+                "timestamp",
+                &timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+            )?;
+        }
+        if let Some(ref tools) = metadata.tools {
+            start_element(writer, "tools", &[])?;
+            start_element(writer, "components", &[])?;
+            for tool in &tools.components {
+                start_element(
+                    writer,
+                    "component",
+                    &[("type", tool.component_type.as_str())],
+                )?;
+                text_element(writer, "name", &tool.name)?;
+                if let Some(ref version) = tool.version {
+                    text_element(writer, "version", version)?;
+                }
+                end_element(writer, "component")?;
+            }
+            end_element(writer, "components")?;
+            end_element(writer, "tools")?;
+        }
+        if let Some(ref component) = metadata.component {
+            write_component_xml(writer, component)?;
+        }
+        if !metadata.properties.is_empty() {
+            start_element(writer, "properties", &[])?;
+            for property in &metadata.properties {
+                start_element(writer, "property", &[("name", property.name.as_str())])?;
+                writer.write_event(Event::Text(BytesText::new(&property.value)))?;
+                end_element(writer, "property")?;
+            }
+            end_element(writer, "properties")?;
+        }
+        end_element(writer, "metadata")?;
+    }
+
+    if !bom.components.is_empty() {
+        start_element(writer, "components", &[])?;
+        for component in &bom.components {
+            write_component_xml(writer, component)?;
+        }
+        end_element(writer, "components")?;
+    }
+
+    if !bom.dependencies.is_empty() {
+        start_element(writer, "dependencies", &[])?;
+        for dependency in &bom.dependencies {
+            let attributes = [("ref", dependency.dependency_ref.as_str())];
+            if dependency.depends_on.is_empty() {
+                empty_element(writer, "dependency", &attributes)?;
+                continue;
+            }
+            start_element(writer, "dependency", &attributes)?;
+            for depends_on in &dependency.depends_on {
+                empty_element(writer, "dependency", &[("ref", depends_on.as_str())])?;
+            }
+            end_element(writer, "dependency")?;
+        }
+        end_element(writer, "dependencies")?;
+    }
+
+    end_element(writer, "bom")
+}
+
+fn write_component_xml(
+    writer: &mut Writer<Vec<u8>>,
+    component: &CycloneDxComponent,
+) -> io::Result<()> {
+    let mut attributes = vec![("type", component.component_type.as_str())];
+    if let Some(ref bom_ref) = component.bom_ref {
+        attributes.push(("bom-ref", bom_ref.as_str()));
+    }
+    start_element(writer, "component", &attributes)?;
+
+    // Child order is fixed by the schema's xs:sequence
+    text_element(writer, "name", &component.name)?;
+    for (name, value) in [
+        ("version", &component.version),
+        ("description", &component.description),
+        ("scope", &component.scope),
+    ] {
+        if let Some(value) = value {
+            text_element(writer, name, value)?;
+        }
+    }
+
+    if !component.licenses.is_empty() {
+        start_element(writer, "licenses", &[])?;
+        for choice in &component.licenses {
+            match choice {
+                CycloneDxLicenseChoice::License { license } => {
+                    start_element(writer, "license", &[])?;
+                    for (name, value) in [
+                        ("id", &license.id),
+                        ("name", &license.name),
+                        ("url", &license.url),
+                    ] {
+                        if let Some(value) = value {
+                            text_element(writer, name, value)?;
+                        }
+                    }
+                    end_element(writer, "license")?;
+                }
+                CycloneDxLicenseChoice::Expression { expression } => {
+                    text_element(writer, "expression", expression)?;
+                }
+            }
+        }
+        end_element(writer, "licenses")?;
+    }
+
+    if let Some(ref copyright) = component.copyright {
+        text_element(writer, "copyright", copyright)?;
+    }
+    if let Some(ref purl) = component.purl {
+        text_element(writer, "purl", purl)?;
+    }
+
+    if !component.external_references.is_empty() {
+        start_element(writer, "externalReferences", &[])?;
+        for reference in &component.external_references {
+            start_element(
+                writer,
+                "reference",
+                &[("type", reference.ref_type.as_str())],
+            )?;
+            text_element(writer, "url", &reference.url)?;
+            if let Some(ref comment) = reference.comment {
+                text_element(writer, "comment", comment)?;
+            }
+            end_element(writer, "reference")?;
+        }
+        end_element(writer, "externalReferences")?;
+    }
+
+    end_element(writer, "component")
+}
+
+fn start_element(
+    writer: &mut Writer<Vec<u8>>,
+    name: &str,
+    attributes: &[(&str, &str)],
+) -> io::Result<()> {
+    let mut element = BytesStart::new(name);
+    element.extend_attributes(attributes.iter().copied());
+    writer.write_event(Event::Start(element))
+}
+
+fn empty_element(
+    writer: &mut Writer<Vec<u8>>,
+    name: &str,
+    attributes: &[(&str, &str)],
+) -> io::Result<()> {
+    let mut element = BytesStart::new(name);
+    element.extend_attributes(attributes.iter().copied());
+    writer.write_event(Event::Empty(element))
+}
+
+fn end_element(writer: &mut Writer<Vec<u8>>, name: &str) -> io::Result<()> {
+    writer.write_event(Event::End(BytesEnd::new(name)))
+}
+
+fn text_element(writer: &mut Writer<Vec<u8>>, name: &str, text: &str) -> io::Result<()> {
+    start_element(writer, name, &[])?;
+    writer.write_event(Event::Text(BytesText::new(text)))?;
+    end_element(writer, name)
+}
+
 pub fn generate_cyclonedx_output(
     spdx_doc: &SpdxDocument,
     output_file: Option<String>,
@@ -306,23 +557,25 @@ pub fn generate_cyclonedx_output(
     let cyclonedx_bom = convert_spdx_to_cyclonedx(spdx_doc);
 
     // Serialize to JSON
-    let json_output = serde_json::to_string_pretty(&cyclonedx_bom).map_err(|e| {
-        FeludaError::Serialization(format!("Failed to serialize CycloneDX BOM: {e}"))
-    })?;
+    let json_output = cyclonedx_json(&cyclonedx_bom)?;
 
     // Output to file or stdout
     if let Some(file_path) = output_file {
-        let cyclonedx_file = if file_path.ends_with(".json") {
-            file_path
+        let (base, compression) = crate::report_archive::split_compressed_suffix(&file_path);
+        let cyclonedx_file = if base.ends_with(".json") {
+            format!("{base}{compression}")
         } else {
             format!(
-                "{}.cyclonedx.json",
-                file_path.trim_end_matches(".cyclonedx")
+                "{}.cyclonedx.json{compression}",
+                base.trim_end_matches(".cyclonedx")
             )
         };
 
-        std::fs::write(&cyclonedx_file, &json_output)
-            .map_err(|e| FeludaError::FileWrite(format!("Failed to write CycloneDX file: {e}")))?;
+        crate::report_archive::write_report_file(
+            std::path::Path::new(&cyclonedx_file),
+            json_output.as_bytes(),
+        )
+        .map_err(|e| FeludaError::FileWrite(format!("Failed to write CycloneDX file: {e}")))?;
 
         println!("🧪 CycloneDX BOM written to: {cyclonedx_file} (EXPERIMENTAL)");
         log(
@@ -359,6 +612,7 @@ mod tests {
         let mut bom = CycloneDxBom::new();
         let component = CycloneDxComponent {
             component_type: "library".to_string(),
+            bom_ref: None,
             name: "test-package".to_string(),
             version: Some("1.0.0".to_string()),
             description: None,
@@ -449,6 +703,7 @@ mod tests {
     fn test_cyclonedx_component_serialization() {
         let component = CycloneDxComponent {
             component_type: "library".to_string(),
+            bom_ref: None,
             name: "test-lib".to_string(),
             version: Some("2.1.0".to_string()),
             description: Some("A test library".to_string()),
@@ -517,6 +772,47 @@ mod tests {
         assert_eq!(tool.component_type, "application");
     }
 
+    #[test]
+    fn test_cyclonedx_xml() {
+        let mut bom = CycloneDxBom::new();
+        bom.add_component(CycloneDxComponent {
+            component_type: "library".to_string(),
+            bom_ref: Some("pkg:cargo/serde@1.0.0".to_string()),
+            name: "serde".to_string(),
+            version: Some("1.0.0".to_string()),
+            description: Some("Fish & chips <serialised>".to_string()),
+            scope: Some("required".to_string()),
+            licenses: vec![
+                convert_spdx_license_to_cyclonedx("MIT OR Apache-2.0"),
+                convert_spdx_license_to_cyclonedx("MIT"),
+            ],
+            copyright: None,
+            purl: Some("pkg:cargo/serde@1.0.0".to_string()),
+            external_references: Vec::new(),
+        });
+        bom.dependencies.push(CycloneDxDependency {
+            dependency_ref: "pkg:cargo/serde@1.0.0".to_string(),
+            depends_on: Vec::new(),
+        });
+
+        let xml = cyclonedx_xml(&bom).unwrap();
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+        assert!(xml.contains("<bom xmlns=\"http://cyclonedx.org/schema/bom/1.5\""));
+        assert!(xml.contains("<name>feluda</name>"));
+        assert!(xml.contains("<component type=\"library\" bom-ref=\"pkg:cargo/serde@1.0.0\">"));
+        assert!(xml.contains("<description>Fish &amp; chips &lt;serialised&gt;</description>"));
+        assert!(xml.contains("<expression>MIT OR Apache-2.0</expression>"));
+        assert!(xml.contains("<id>MIT</id>"));
+        assert!(xml.contains("<dependency ref=\"pkg:cargo/serde@1.0.0\"/>"));
+
+        // Elements follow the schema's sequence
+        let position = |tag: &str| xml.find(tag).unwrap();
+        assert!(position("<name>serde") < position("<version>1.0.0"));
+        assert!(position("<scope>") < position("<licenses>"));
+        assert!(position("<licenses>") < position("<purl>"));
+        assert!(position("<components>\n    <component") < position("<dependencies>"));
+    }
+
     #[test]
     fn test_complex_spdx_to_cyclonedx_conversion() {
         let mut spdx_doc = SpdxDocument::new("complex-project");
@@ -0,0 +1,276 @@
+//! GitHub dependency graph ingestion
+//!
+//! Pulls the SPDX SBOM GitHub already computes for a repository
+//! (`GET /repos/{owner}/{repo}/dependency-graph/sbom`) and turns its packages into
+//! [`LicenseInfo`] rows, so feluda's policy runs without cloning or resolving anything
+//! locally. Useful when local resolution is impossible, e.g. closed-source submodules.
+
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::debug::{log, log_debug, FeludaError, FeludaResult, LogLevel};
+use crate::licenses::{
+    fetch_licenses_from_github, get_github_token, get_osi_status, github_api_url, github_host,
+    is_license_ignored, is_license_restrictive, LicenseCompatibility, LicenseInfo,
+};
+
+/// Split a GitHub repository reference into `(owner, repo)`.
+///
+/// Accepts `https://github.com/owner/repo(.git)`, `git@github.com:owner/repo.git`
+/// and the bare `owner/repo` shorthand, on the Enterprise Server host when one is configured.
+pub fn parse_github_repo(repo_url: &str) -> Option<(String, String)> {
+    let trimmed = repo_url.trim().trim_end_matches('/');
+    let trimmed = trimmed.strip_suffix(".git").unwrap_or(trimmed);
+    let host = github_host();
+    let path = if let Some(rest) = trimmed.strip_prefix(&format!("git@{host}:")) {
+        rest
+    } else if let Some(index) = trimmed.find(&format!("{host}/")) {
+        &trimmed[index + host.len() + 1..]
+    } else if trimmed.contains("://") || trimmed.contains('@') {
+        return None;
+    } else {
+        trimmed
+    };
+
+    let mut parts = path.split('/').filter(|part| !part.is_empty());
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(owner), Some(repo), None) => Some((owner.to_string(), repo.to_string())),
+        _ => None,
+    }
+}
+
+/// Fetch the dependency graph SBOM of a GitHub repository and analyze its packages
+pub fn analyze_dependency_graph(repo_url: &str, strict: bool) -> FeludaResult<Vec<LicenseInfo>> {
+    let (owner, repo) = parse_github_repo(repo_url).ok_or_else(|| {
+        FeludaError::InvalidData(format!(
+            "--github-sbom needs a GitHub repository, got '{repo_url}'"
+        ))
+    })?;
+
+    let sbom = fetch_dependency_graph_sbom(&owner, &repo)?;
+    let known_licenses = fetch_licenses_from_github()?;
+    let config = crate::config::load_config()?;
+
+    let mut dependencies = parse_dependency_graph_sbom(&sbom, &format!("{owner}/{repo}"));
+    for dep in &mut dependencies {
+        dep.is_restrictive = is_license_restrictive(&dep.license, &known_licenses, strict);
+        dep.osi_status = dep
+            .license
+            .as_deref()
+            .map(get_osi_status)
+            .unwrap_or(crate::licenses::OsiStatus::Unknown);
+    }
+
+    dependencies.retain(|dep| !is_license_ignored(dep.license.as_deref()));
+    crate::ignore::remove_ignored(&mut dependencies, &config.dependencies, &[]);
+
+    log(
+        LogLevel::Info,
+        &format!(
+            "Dependency graph of {owner}/{repo} lists {} packages",
+            dependencies.len()
+        ),
+    );
+    Ok(dependencies)
+}
+
+fn fetch_dependency_graph_sbom(owner: &str, repo: &str) -> FeludaResult<Value> {
+    let url = format!(
+        "{}/repos/{owner}/{repo}/dependency-graph/sbom",
+        github_api_url()
+    );
+    log(LogLevel::Info, &format!("Fetching dependency graph: {url}"));
+
+    let failed = |e: crate::cancel::FetchError| {
+        FeludaError::InvalidData(format!(
+            "GitHub dependency graph request for {owner}/{repo} failed: {e}"
+        ))
+    };
+    let mut request = crate::cancel::shared_client()
+        .map_err(failed)?
+        .get(&url)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .header("X-GitHub-Api-Version", "2022-11-28")
+        .timeout(crate::cancel::request_timeout(Duration::from_secs(60)));
+    if let Some(token) = get_github_token() {
+        request = request.bearer_auth(token);
+    }
+
+    let response = crate::cancel::http_send(request, &url).map_err(failed)?;
+    let status = response.status();
+    if !status.is_success() {
+        let hint = match status.as_u16() {
+            401 | 403 => " (private repositories need --github-token with read access)",
+            404 => " (repository not found, or the dependency graph is disabled)",
+            _ => "",
+        };
+        return Err(FeludaError::InvalidData(format!(
+            "GitHub dependency graph request for {owner}/{repo} failed with {status}{hint}"
+        )));
+    }
+
+    Ok(response.json::<Value>()?)
+}
+
+/// Convert the `sbom` document returned by the dependency graph API into dependencies.
+///
+/// The repository's own package (named after `repo_name`) is skipped. The concluded license
+/// wins over the declared one unless it is `NOASSERTION`; `NOASSERTION` and `NONE` are kept
+/// as-is so they're reported with their own semantics.
+fn parse_dependency_graph_sbom(response: &Value, repo_name: &str) -> Vec<LicenseInfo> {
+    let Some(packages) = response
+        .get("sbom")
+        .unwrap_or(response)
+        .get("packages")
+        .and_then(|p| p.as_array())
+    else {
+        log(LogLevel::Warn, "Dependency graph SBOM has no packages");
+        return Vec::new();
+    };
+
+    let license_of = |package: &Value, field: &str| {
+        package
+            .get(field)
+            .and_then(|l| l.as_str())
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(str::to_string)
+    };
+
+    let dependencies: Vec<LicenseInfo> = packages
+        .iter()
+        .filter_map(|package| {
+            let name = package.get("name")?.as_str()?;
+            if name.ends_with(repo_name) {
+                return None;
+            }
+            let version = package
+                .get("versionInfo")
+                .and_then(|v| v.as_str())
+                .unwrap_or("latest");
+
+            Some(LicenseInfo {
+                name: package_name(package).unwrap_or_else(|| name.to_string()),
+                version: version.to_string(),
+                license: match license_of(package, "licenseConcluded") {
+                    Some(concluded) if concluded != "NOASSERTION" => Some(concluded),
+                    concluded => license_of(package, "licenseDeclared").or(concluded),
+                },
+                is_restrictive: false,
+                compatibility: LicenseCompatibility::Unknown,
+                osi_status: crate::licenses::OsiStatus::Unknown,
+                sub_project: None,
+                found_in: Vec::new(),
+                repository: None,
+                declared_in: None,
+                internal: false,
+                inherited_from: None,
+                ecosystem: None,
+                suggestions: Vec::new(),
+                update_policy: None,
+                introduced_by: Vec::new(),
+                purl: package_purl(package).map(str::to_string),
+                vulnerabilities: None,
+                manifest: None,
+                data_license: None,
+                project_dirs: Vec::new(),
+            })
+        })
+        .collect();
+
+    log_debug("Dependency graph packages", &dependencies);
+    dependencies
+}
+
+/// The purl GitHub records for a package in its externalRefs
+fn package_purl(package: &Value) -> Option<&str> {
+    package
+        .get("externalRefs")?
+        .as_array()?
+        .iter()
+        .find(|r| r.get("referenceType").and_then(|t| t.as_str()) == Some("purl"))?
+        .get("referenceLocator")?
+        .as_str()
+}
+
+/// Prefer the package name from the purl; GitHub prefixes `name` with the ecosystem
+/// (e.g. `npm:lodash`).
+fn package_name(package: &Value) -> Option<String> {
+    let purl = package_purl(package)?;
+
+    let without_version = purl.split('@').next()?;
+    let path = without_version.split_once('/')?.1;
+    let decoded = path.replace("%40", "@");
+    // Maven purls are `namespace/name`; keep the group like the Maven analyzer does.
+    Some(if purl.starts_with("pkg:maven/") {
+        decoded.replacen('/', ":", 1)
+    } else {
+        decoded
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_github_repo_forms() {
+        let expected = Some(("anistark".to_string(), "feluda".to_string()));
+        assert_eq!(
+            parse_github_repo("https://github.com/anistark/feluda"),
+            expected
+        );
+        assert_eq!(
+            parse_github_repo("https://github.com/anistark/feluda.git/"),
+            expected
+        );
+        assert_eq!(
+            parse_github_repo("git@github.com:anistark/feluda.git"),
+            expected
+        );
+        assert_eq!(parse_github_repo("anistark/feluda"), expected);
+        assert_eq!(parse_github_repo("https://gitlab.com/a/b"), None);
+        assert_eq!(parse_github_repo("feluda"), None);
+    }
+
+    #[test]
+    fn test_parse_dependency_graph_sbom() {
+        let response: Value = serde_json::from_str(
+            r#"{"sbom": {"packages": [
+                {"name": "com.github.acme/app", "versionInfo": "main"},
+                {"name": "npm:lodash", "versionInfo": "4.17.21",
+                 "licenseConcluded": "MIT",
+                 "externalRefs": [{"referenceType": "purl",
+                                   "referenceLocator": "pkg:npm/lodash@4.17.21"}]},
+                {"name": "npm:@babel/core", "versionInfo": "7.23.0",
+                 "licenseConcluded": "NOASSERTION", "licenseDeclared": "MIT",
+                 "externalRefs": [{"referenceType": "purl",
+                                   "referenceLocator": "pkg:npm/%40babel/core@7.23.0"}]},
+                {"name": "maven:org.slf4j:slf4j-api", "versionInfo": "2.0.9",
+                 "licenseConcluded": "NOASSERTION",
+                 "externalRefs": [{"referenceType": "purl",
+                                   "referenceLocator": "pkg:maven/org.slf4j/slf4j-api@2.0.9"}]},
+                {"name": "npm:left-pad", "versionInfo": "1.3.0",
+                 "licenseConcluded": "NONE", "licenseDeclared": "MIT"}
+            ]}}"#,
+        )
+        .unwrap();
+
+        let deps = parse_dependency_graph_sbom(&response, "acme/app");
+        assert_eq!(deps.len(), 4);
+        assert_eq!(deps[0].name, "lodash");
+        assert_eq!(deps[0].license.as_deref(), Some("MIT"));
+        assert_eq!(deps[1].name, "@babel/core");
+        assert_eq!(deps[1].license.as_deref(), Some("MIT"));
+        assert_eq!(deps[2].name, "org.slf4j:slf4j-api");
+        assert_eq!(deps[2].version, "2.0.9");
+        assert_eq!(deps[2].license.as_deref(), Some("NOASSERTION"));
+        assert_eq!(deps[3].license.as_deref(), Some("NONE"));
+        assert_eq!(
+            deps[1].purl.as_deref(),
+            Some("pkg:npm/%40babel/core@7.23.0")
+        );
+        assert_eq!(deps[3].purl, None);
+    }
+}
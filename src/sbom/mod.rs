@@ -1,14 +1,22 @@
 pub mod cyclonedx;
+pub mod github;
 pub mod spdx;
 pub mod validate;
 
-use crate::cli::SbomFormat;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::cli::{SbomExport, SbomFormat};
 use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
-use crate::licenses::LicenseCompatibility;
+use crate::licenses::{LicenseCompatibility, LicenseInfo};
 use crate::parser::parse_root;
+use crate::report_metadata::ReportMetadata;
 
-use cyclonedx::generate_cyclonedx_output;
-use spdx::{generate_spdx_output, SpdxDocument, SpdxPackage};
+use cyclonedx::{
+    convert_spdx_license_to_cyclonedx, cyclonedx_json, cyclonedx_xml, generate_cyclonedx_output,
+    CycloneDxBom, CycloneDxComponent, CycloneDxDependency, CycloneDxProperty,
+};
+use spdx::{generate_spdx_output, spdx_json, SpdxDocument, SpdxPackage};
 
 pub fn handle_sbom_command(
     path: String,
@@ -18,7 +26,7 @@ pub fn handle_sbom_command(
     log(LogLevel::Info, &format!("Generating SBOM for path: {path}"));
 
     // Parse project dependencies using existing parser
-    let analyzed_data = parse_root(&path, None, false, false)
+    let analyzed_data = parse_root(&path, None, &[], false, false, None, false)
         .map_err(|e| FeludaError::Parser(format!("Failed to parse dependencies: {e}")))?;
 
     log(
@@ -37,7 +45,8 @@ pub fn handle_sbom_command(
 
     for dependency in analyzed_data {
         let mut package = SpdxPackage::new(dependency.name.clone(), &spdx_doc.document_namespace)
-            .with_version(dependency.version.clone());
+            .with_version(dependency.version.clone())
+            .with_purl(crate::purl::of(&dependency));
 
         let force_noassertion = std::env::var("FELUDA_FORCE_NOASSERTION_LICENSES")
             .map(|v| v.eq_ignore_ascii_case("true"))
@@ -95,3 +104,376 @@ pub fn handle_sbom_command(
 
     Ok(())
 }
+
+/// Write a scan's results as an SBOM (`--sbom`), to `output_file` or stdout. The scan's
+/// metadata goes in the CycloneDX metadata properties or the SPDX creation comment.
+pub fn write_scan_sbom(
+    format: SbomExport,
+    project_name: &str,
+    project_license: Option<&str>,
+    dependencies: &[LicenseInfo],
+    output_file: Option<&str>,
+    metadata: Option<&ReportMetadata>,
+) -> FeludaResult<()> {
+    let (label, content) = match format {
+        SbomExport::Spdx => {
+            let mut doc = scan_spdx_document(project_name, project_license, dependencies);
+            doc.creation_info.comment = metadata.map(|metadata| {
+                metadata
+                    .properties()
+                    .into_iter()
+                    .map(|(name, value)| format!("{name}: {value}"))
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            });
+            ("SPDX", spdx_json(&doc)?)
+        }
+        SbomExport::Cyclonedx | SbomExport::CyclonedxXml => {
+            let mut bom = scan_cyclonedx_bom(project_name, project_license, dependencies);
+            if let (Some(metadata), Some(bom_metadata)) = (metadata, bom.metadata.as_mut()) {
+                bom_metadata.properties = metadata
+                    .properties()
+                    .into_iter()
+                    .map(|(name, value)| CycloneDxProperty { name, value })
+                    .collect();
+            }
+            let content = if format == SbomExport::CyclonedxXml {
+                cyclonedx_xml(&bom)?
+            } else {
+                cyclonedx_json(&bom)?
+            };
+            ("CycloneDX", content)
+        }
+    };
+
+    match output_file {
+        Some(path) => {
+            crate::report_archive::write_report_file(Path::new(path), content.as_bytes()).map_err(
+                |e| FeludaError::FileWrite(format!("Failed to write {label} SBOM: {e}")),
+            )?;
+            eprintln!("{label} SBOM written to: {path}");
+        }
+        None => println!("{content}"),
+    }
+    Ok(())
+}
+
+/// SPDX document for a scan: it describes the project, which contains its workspace members
+/// and depends on every dependency, through the member that pulled it in when there is one
+fn scan_spdx_document(
+    project_name: &str,
+    project_license: Option<&str>,
+    dependencies: &[LicenseInfo],
+) -> SpdxDocument {
+    let mut doc = SpdxDocument::new(project_name);
+    let root = SpdxPackage::new(project_name, &doc.document_namespace)
+        .with_license(project_license.unwrap_or("NOASSERTION"));
+    let root_id = root.spdx_id.clone();
+    doc.add_package(root);
+
+    // Workspace member name to its package's SPDX ID
+    let mut members: HashMap<String, String> = HashMap::new();
+    for dependency in dependencies {
+        let package = SpdxPackage::new(dependency.name.clone(), &doc.document_namespace)
+            .with_version(dependency.version.clone())
+            .with_license(dependency.license.as_deref().unwrap_or("NOASSERTION"))
+            .with_purl(crate::purl::of(dependency));
+        let package_id = package.spdx_id.clone();
+
+        let owners = workspace_owners(dependency);
+        if owners.is_empty() {
+            doc.add_related_package(&root_id, "DEPENDS_ON", package);
+            continue;
+        }
+
+        doc.packages.push(package);
+        for owner in owners {
+            let owner_id = match members.get(owner) {
+                Some(id) => id.clone(),
+                None => {
+                    let member = SpdxPackage::new(owner, &doc.document_namespace);
+                    let id = member.spdx_id.clone();
+                    doc.add_related_package(&root_id, "CONTAINS", member);
+                    members.insert(owner.to_string(), id.clone());
+                    id
+                }
+            };
+            doc.add_relationship(&owner_id, "DEPENDS_ON", &package_id);
+        }
+    }
+
+    doc
+}
+
+/// CycloneDX BOM for a scan, with the same shape as [`scan_spdx_document`]: the project is the
+/// metadata component, workspace members are application components, and the dependency graph
+/// runs from the project through its members to every dependency
+fn scan_cyclonedx_bom(
+    project_name: &str,
+    project_license: Option<&str>,
+    dependencies: &[LicenseInfo],
+) -> CycloneDxBom {
+    let mut bom = CycloneDxBom::new();
+    let root_ref = project_name.to_string();
+    if let Some(metadata) = bom.metadata.as_mut() {
+        metadata.component = Some(CycloneDxComponent {
+            component_type: "application".to_string(),
+            bom_ref: Some(root_ref.clone()),
+            name: project_name.to_string(),
+            version: None,
+            description: None,
+            scope: None,
+            licenses: project_license
+                .map(|license| vec![convert_spdx_license_to_cyclonedx(license)])
+                .unwrap_or_default(),
+            copyright: None,
+            purl: None,
+            external_references: Vec::new(),
+        });
+    }
+
+    // Depending component's bom-ref to its direct dependencies, in first-seen order
+    let mut graph: Vec<(String, Vec<String>)> = vec![(root_ref.clone(), Vec::new())];
+
+    let mut members: Vec<String> = Vec::new();
+    for dependency in dependencies {
+        let purl = crate::purl::of(dependency);
+        let component_ref = purl
+            .clone()
+            .unwrap_or_else(|| format!("{}@{}", dependency.name, dependency.version));
+
+        bom.add_component(CycloneDxComponent {
+            component_type: "library".to_string(),
+            bom_ref: Some(component_ref.clone()),
+            name: dependency.name.clone(),
+            version: Some(dependency.version.clone()),
+            description: None,
+            scope: Some("required".to_string()),
+            licenses: vec![convert_spdx_license_to_cyclonedx(
+                dependency.license.as_deref().unwrap_or("NOASSERTION"),
+            )],
+            copyright: None,
+            purl,
+            external_references: Vec::new(),
+        });
+
+        let owners = workspace_owners(dependency);
+        if owners.is_empty() {
+            add_edge(&mut graph, &root_ref, &component_ref);
+        }
+        for owner in owners {
+            let member_ref = format!("{root_ref}/{owner}");
+            if !members.contains(&member_ref) {
+                members.push(member_ref.clone());
+                add_edge(&mut graph, &root_ref, &member_ref);
+                bom.add_component(CycloneDxComponent {
+                    component_type: "application".to_string(),
+                    bom_ref: Some(member_ref.clone()),
+                    name: owner.to_string(),
+                    version: None,
+                    description: None,
+                    scope: None,
+                    licenses: Vec::new(),
+                    copyright: None,
+                    purl: None,
+                    external_references: Vec::new(),
+                });
+            }
+            add_edge(&mut graph, &member_ref, &component_ref);
+        }
+    }
+
+    // Every component gets an entry, leaves included, so consumers can tell "no dependencies"
+    // from "not analysed"
+    for component in &bom.components {
+        if let Some(ref bom_ref) = component.bom_ref {
+            if !graph.iter().any(|(node, _)| node == bom_ref) {
+                graph.push((bom_ref.clone(), Vec::new()));
+            }
+        }
+    }
+    bom.dependencies = graph
+        .into_iter()
+        .map(|(dependency_ref, depends_on)| CycloneDxDependency {
+            dependency_ref,
+            depends_on,
+        })
+        .collect();
+
+    bom
+}
+
+/// Record that `from` directly depends on `to` in a CycloneDX dependency graph
+fn add_edge(graph: &mut Vec<(String, Vec<String>)>, from: &str, to: &str) {
+    let index = match graph.iter().position(|(node, _)| node == from) {
+        Some(index) => index,
+        None => {
+            graph.push((from.to_string(), Vec::new()));
+            graph.len() - 1
+        }
+    };
+    graph[index].1.push(to.to_string());
+}
+
+/// Workspace members that pulled a dependency in; one shared by several lists them
+/// comma-separated
+fn workspace_owners(dependency: &LicenseInfo) -> Vec<&str> {
+    dependency
+        .sub_project()
+        .map(|owners| {
+            owners
+                .split(',')
+                .map(str::trim)
+                .filter(|owner| !owner.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relationships(doc: &SpdxDocument, relationship_type: &str) -> Vec<(String, String)> {
+        let name = |id: &str| {
+            doc.packages
+                .iter()
+                .find(|package| package.spdx_id == id)
+                .map_or(id.to_string(), |package| package.name.clone())
+        };
+        doc.relationships
+            .iter()
+            .filter(|r| r.relationship_type == relationship_type)
+            .map(|r| (name(&r.spdx_element_id), name(&r.related_spdx_element)))
+            .collect()
+    }
+
+    #[test]
+    fn test_scan_spdx_document_relationships() {
+        let doc = scan_spdx_document(
+            "app",
+            Some("MIT"),
+            &[
+                LicenseInfo::fixture("serde", "1.0.0", "MIT OR Apache-2.0"),
+                LicenseInfo {
+                    sub_project: Some("api, worker".to_string()),
+                    ..LicenseInfo::fixture("tokio", "1.0.0", None)
+                },
+            ],
+        );
+
+        assert_eq!(doc.spdx_version, "SPDX-2.3");
+        assert_eq!(doc.packages.len(), 5);
+        assert_eq!(
+            relationships(&doc, "DESCRIBES"),
+            vec![("SPDXRef-DOCUMENT".to_string(), "app".to_string())]
+        );
+        assert_eq!(
+            relationships(&doc, "CONTAINS"),
+            vec![
+                ("app".to_string(), "api".to_string()),
+                ("app".to_string(), "worker".to_string())
+            ]
+        );
+        assert_eq!(
+            relationships(&doc, "DEPENDS_ON"),
+            vec![
+                ("app".to_string(), "serde".to_string()),
+                ("api".to_string(), "tokio".to_string()),
+                ("worker".to_string(), "tokio".to_string())
+            ]
+        );
+
+        let root = &doc.packages[0];
+        assert_eq!(root.license_declared.as_deref(), Some("MIT"));
+        let tokio = doc.packages.iter().find(|p| p.name == "tokio").unwrap();
+        assert_eq!(tokio.version_info.as_deref(), Some("1.0.0"));
+        assert_eq!(tokio.license_declared.as_deref(), Some("NOASSERTION"));
+    }
+
+    #[test]
+    fn test_scan_spdx_json_is_valid() {
+        let mut left_pad = LicenseInfo::fixture("left-pad", "1.0.0", "WTFPL");
+        left_pad.purl = Some("pkg:npm/left-pad@1.0.0".to_string());
+        let doc = scan_spdx_document(
+            "app",
+            None,
+            &[left_pad, LicenseInfo::fixture("zlib", "1.0.0", None)],
+        );
+        let json: serde_json::Value = serde_json::from_str(&spdx_json(&doc).unwrap()).unwrap();
+        assert_eq!(json["spdxVersion"], "SPDX-2.3");
+        assert_eq!(json["packages"][1]["licenseDeclared"], "WTFPL");
+        assert_eq!(
+            json["packages"][1]["externalRefs"],
+            serde_json::json!([{
+                "referenceCategory": "PACKAGE-MANAGER",
+                "referenceType": "purl",
+                "referenceLocator": "pkg:npm/left-pad@1.0.0"
+            }])
+        );
+        assert!(json["packages"][2].get("externalRefs").is_none());
+        assert_eq!(json["relationships"][1]["relationshipType"], "DEPENDS_ON");
+    }
+
+    #[test]
+    fn test_scan_cyclonedx_bom() {
+        let mut serde = LicenseInfo::fixture("serde", "1.0.0", "MIT OR Apache-2.0");
+        serde.ecosystem = Some("rust".to_string());
+        let bom = scan_cyclonedx_bom(
+            "app",
+            Some("MIT"),
+            &[
+                serde,
+                LicenseInfo {
+                    sub_project: Some("api, worker".to_string()),
+                    ..LicenseInfo::fixture("zlib", "1.0.0", None)
+                },
+            ],
+        );
+
+        let root = bom.metadata.as_ref().unwrap().component.as_ref().unwrap();
+        assert_eq!(root.name, "app");
+        assert_eq!(root.component_type, "application");
+
+        let names: Vec<&str> = bom.components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["serde", "zlib", "api", "worker"]);
+        assert_eq!(
+            bom.components[0].purl.as_deref(),
+            Some("pkg:cargo/serde@1.0.0")
+        );
+        assert_eq!(bom.components[1].purl, None);
+        assert_eq!(bom.components[1].bom_ref.as_deref(), Some("zlib@1.0.0"));
+
+        let edges = |from: &str| {
+            bom.dependencies
+                .iter()
+                .find(|d| d.dependency_ref == from)
+                .map(|d| d.depends_on.clone())
+                .unwrap()
+        };
+        assert_eq!(
+            edges("app"),
+            vec!["pkg:cargo/serde@1.0.0", "app/api", "app/worker"]
+        );
+        assert_eq!(edges("app/api"), vec!["zlib@1.0.0"]);
+        assert_eq!(edges("app/worker"), vec!["zlib@1.0.0"]);
+        assert!(edges("pkg:cargo/serde@1.0.0").is_empty());
+    }
+
+    #[test]
+    fn test_scan_cyclonedx_outputs() {
+        let mut left_pad = LicenseInfo::fixture("left-pad", "1.0.0", "WTFPL");
+        left_pad.ecosystem = Some("node".to_string());
+        let bom = scan_cyclonedx_bom("app", None, &[left_pad]);
+
+        let json: serde_json::Value = serde_json::from_str(&cyclonedx_json(&bom).unwrap()).unwrap();
+        assert_eq!(json["bomFormat"], "CycloneDX");
+        assert_eq!(json["components"][0]["purl"], "pkg:npm/left-pad@1.0.0");
+        assert_eq!(json["components"][0]["bom-ref"], "pkg:npm/left-pad@1.0.0");
+        assert_eq!(json["dependencies"][0]["ref"], "app");
+
+        let xml = cyclonedx_xml(&bom).unwrap();
+        assert!(xml.contains("<purl>pkg:npm/left-pad@1.0.0</purl>"));
+        assert!(xml.contains("<dependency ref=\"app\">"));
+    }
+}
@@ -100,13 +100,15 @@ pub fn convert_to_spdx_license_expression(license: &str) -> String {
     }
 
     let trimmed = license.trim();
+    // NONE is a statement that no license applies, not missing data
+    if let Some(value) = crate::licenses::spdx_special_value(trimmed) {
+        return value.to_string();
+    }
     if trimmed.is_empty()
         || trimmed.eq_ignore_ascii_case("null")
         || trimmed.eq_ignore_ascii_case("undefined")
-        || trimmed.eq_ignore_ascii_case("none")
         || trimmed == "-"
         || trimmed == "n/a"
-        || trimmed.eq_ignore_ascii_case("unlicensed")
         || trimmed.eq_ignore_ascii_case("proprietary")
         || !trimmed.is_ascii()
     {
@@ -313,6 +315,10 @@ pub struct CreationInfo {
     /// License list version (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub license_list_version: Option<String>,
+
+    /// Free-form note about how the document was created (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
 }
 
 /// SPDX 2.3 compliant package structure
@@ -410,6 +416,7 @@ impl SpdxDocument {
                 created: Utc::now(),
                 creators: vec![format!("Tool: Feluda-{}", env!("CARGO_PKG_VERSION"))],
                 license_list_version: None,
+                comment: None,
             },
             packages: Vec::new(),
             relationships: Vec::new(),
@@ -419,15 +426,29 @@ impl SpdxDocument {
 
     pub fn add_package(&mut self, package: SpdxPackage) {
         // Add relationship: document describes package
-        let relationship = Relationship {
-            spdx_element_id: self.spdx_id.clone(),
-            relationship_type: "DESCRIBES".to_string(),
-            related_spdx_element: package.spdx_id.clone(),
-            comment: None,
-        };
+        let document_id = self.spdx_id.clone();
+        self.add_related_package(&document_id, "DESCRIBES", package);
+    }
 
+    /// Add a package that `element` has `relationship_type` (e.g. `DEPENDS_ON`) to
+    pub fn add_related_package(
+        &mut self,
+        element: &str,
+        relationship_type: &str,
+        package: SpdxPackage,
+    ) {
+        self.add_relationship(element, relationship_type, &package.spdx_id);
         self.packages.push(package);
-        self.relationships.push(relationship);
+    }
+
+    /// Record that `element` has `relationship_type` to `related`
+    pub fn add_relationship(&mut self, element: &str, relationship_type: &str, related: &str) {
+        self.relationships.push(Relationship {
+            spdx_element_id: element.to_string(),
+            relationship_type: relationship_type.to_string(),
+            related_spdx_element: related.to_string(),
+            comment: None,
+        });
     }
 
     #[allow(dead_code)]
@@ -611,19 +632,26 @@ impl SpdxPackage {
         self
     }
 
+    /// Adds the package's purl, when it has one, as a package manager reference
+    pub fn with_purl(self, purl: Option<String>) -> Self {
+        match purl {
+            Some(purl) => self.add_external_ref("PACKAGE-MANAGER", "purl", purl),
+            None => self,
+        }
+    }
+
     /// Adds an external reference for package metadata
     ///
     /// External references link a package to external sources of information.
     /// Per SPDX 2.3 spec, common reference categories include:
-    /// - "SECURITY_OTHER" for security-related references
-    /// - "PACKAGE_MANAGER" for package manager records
+    /// - "SECURITY" for security-related references
+    /// - "PACKAGE-MANAGER" for package manager records, such as a purl
     /// - "OTHER" for miscellaneous references
     ///
     /// Example:
     /// ```ignore
-    /// package.add_external_ref("PACKAGE_MANAGER", "npm", "lodash@4.17.21");
+    /// package.add_external_ref("PACKAGE-MANAGER", "purl", "pkg:npm/lodash@4.17.21");
     /// ```
-    #[allow(dead_code)]
     pub fn add_external_ref(
         mut self,
         category: impl Into<String>,
@@ -847,6 +875,37 @@ pub fn generate_spdx_output(
     spdx_doc: &SpdxDocument,
     output_file: Option<String>,
 ) -> FeludaResult<()> {
+    let json_output = spdx_json(spdx_doc)?;
+
+    if let Some(file_path) = output_file {
+        let (base, compression) = crate::report_archive::split_compressed_suffix(&file_path);
+        let spdx_file = if base.ends_with(".json") {
+            format!("{base}{compression}")
+        } else {
+            format!("{}.spdx.json{compression}", base.trim_end_matches(".spdx"))
+        };
+
+        crate::report_archive::write_report_file(
+            std::path::Path::new(&spdx_file),
+            json_output.as_bytes(),
+        )
+        .map_err(|e| FeludaError::FileWrite(format!("Failed to write SPDX file: {e}")))?;
+
+        println!("SPDX SBOM written to: {spdx_file}");
+        log(
+            LogLevel::Info,
+            &format!("SPDX SBOM written to: {spdx_file}"),
+        );
+    } else {
+        println!("=== SPDX SBOM ===");
+        println!("{json_output}");
+    }
+
+    Ok(())
+}
+
+/// Sanitize a document's packages and serialize it as SPDX 2.3 JSON
+pub fn spdx_json(spdx_doc: &SpdxDocument) -> FeludaResult<String> {
     log(LogLevel::Info, "Generating SPDX 2.3 compliant output");
 
     let mut safe_doc = spdx_doc.clone();
@@ -875,27 +934,7 @@ pub fn generate_spdx_output(
         ));
     }
 
-    if let Some(file_path) = output_file {
-        let spdx_file = if file_path.ends_with(".json") {
-            file_path
-        } else {
-            format!("{}.spdx.json", file_path.trim_end_matches(".spdx"))
-        };
-
-        std::fs::write(&spdx_file, &json_output)
-            .map_err(|e| FeludaError::FileWrite(format!("Failed to write SPDX file: {e}")))?;
-
-        println!("SPDX SBOM written to: {spdx_file}");
-        log(
-            LogLevel::Info,
-            &format!("SPDX SBOM written to: {spdx_file}"),
-        );
-    } else {
-        println!("=== SPDX SBOM ===");
-        println!("{json_output}");
-    }
-
-    Ok(())
+    Ok(json_output)
 }
 
 #[cfg(test)]
@@ -1033,8 +1072,8 @@ mod tests {
         );
 
         // Test other invalid patterns
-        assert_eq!(convert_to_spdx_license_expression("none"), "NOASSERTION");
-        assert_eq!(convert_to_spdx_license_expression("NONE"), "NOASSERTION");
+        assert_eq!(convert_to_spdx_license_expression("none"), "NONE");
+        assert_eq!(convert_to_spdx_license_expression("NONE"), "NONE");
         assert_eq!(convert_to_spdx_license_expression("-"), "NOASSERTION");
         assert_eq!(convert_to_spdx_license_expression("n/a"), "NOASSERTION");
 
@@ -1078,10 +1117,7 @@ mod tests {
         );
 
         // Test additional invalid patterns
-        assert_eq!(
-            convert_to_spdx_license_expression("unlicensed"),
-            "NOASSERTION"
-        );
+        assert_eq!(convert_to_spdx_license_expression("unlicensed"), "NONE");
         assert_eq!(
             convert_to_spdx_license_expression("proprietary"),
             "NOASSERTION"
@@ -223,6 +223,20 @@ pub fn scan_own_source_headers(
                 compatibility: LicenseCompatibility::Unknown,
                 osi_status,
                 sub_project: None,
+                found_in: Vec::new(),
+                repository: None,
+                declared_in: None,
+                internal: false,
+                inherited_from: None,
+                ecosystem: None,
+                suggestions: Vec::new(),
+                update_policy: None,
+                introduced_by: Vec::new(),
+                purl: None,
+                vulnerabilities: None,
+                manifest: None,
+                data_license: None,
+                project_dirs: Vec::new(),
             }
         })
         .collect()
@@ -6,7 +6,13 @@
 //! Operator semantics used by Feluda:
 //!   - `OR`  — user may choose any alternative; compatible/non-restrictive if ANY component qualifies.
 //!   - `AND` — all licenses apply simultaneously; compatible/non-restrictive only if ALL qualify.
-//!   - `WITH`— exception modifier; treated as an annotation on the base license.
+//!   - `WITH`— exception modifier. A linking exception (see [`LINKING_EXCEPTIONS`]) lifts the
+//!     base license's copyleft for code that merely links or uses the component, so the
+//!     component stays compatible and is restrictive only as weak copyleft (strict mode). Other
+//!     exceptions leave the base license's verdict unchanged. `[licenses.allow]` and
+//!     `[licenses.deny]` decide before either: a denied base license stays denied.
+
+use crate::config::PolicyVerdict;
 
 /// A parsed SPDX expression tree.
 #[derive(Debug, Clone, PartialEq)]
@@ -19,7 +25,6 @@ pub enum SpdxExpression {
 
 impl SpdxExpression {
     /// Returns all individual license IDs mentioned in the expression (no exceptions).
-    #[allow(dead_code)]
     pub fn license_ids(&self) -> Vec<String> {
         match self {
             Self::License(id) => vec![id.clone()],
@@ -31,6 +36,19 @@ impl SpdxExpression {
             }
         }
     }
+
+    /// Returns the `(license, exception)` pairs of every `WITH` in the expression.
+    pub fn exceptions(&self) -> Vec<(String, String)> {
+        match self {
+            Self::License(_) => Vec::new(),
+            Self::With { license, exception } => vec![(license.clone(), exception.clone())],
+            Self::Or(a, b) | Self::And(a, b) => {
+                let mut exceptions = a.exceptions();
+                exceptions.extend(b.exceptions());
+                exceptions
+            }
+        }
+    }
 }
 
 /// Parse an SPDX expression string into an [`SpdxExpression`] tree.
@@ -201,26 +219,80 @@ fn parse_primary(tokens: &[Token], pos: &mut usize) -> Option<SpdxExpression> {
 
 // ── Compatibility / restrictiveness evaluation ────────────────────────────────
 
+/// SPDX exceptions that let code link against or use the covered component without taking on
+/// its copyleft terms (or, for `LLVM-exception`, its GPL-2.0 incompatibility)
+pub const LINKING_EXCEPTIONS: &[&str] = &[
+    "Autoconf-exception-2.0",
+    "Autoconf-exception-3.0",
+    "Bison-exception-2.2",
+    "Classpath-exception-2.0",
+    "eCos-exception-2.0",
+    "FLTK-exception",
+    "Font-exception-2.0",
+    "GCC-exception-2.0",
+    "GCC-exception-3.1",
+    "GPL-3.0-linking-exception",
+    "GPL-3.0-linking-source-exception",
+    "LGPL-3.0-linking-exception",
+    "Libtool-exception",
+    "Linux-syscall-note",
+    "LLVM-exception",
+    "OCaml-LGPL-linking-exception",
+    "OpenJDK-assembly-exception-1.0",
+    "Qt-LGPL-exception-1.1",
+    "Swift-exception",
+    "u-boot-exception-2.0",
+    "WxWindows-exception-3.1",
+];
+
+/// Whether an SPDX exception id is one of the [`LINKING_EXCEPTIONS`]
+pub fn is_linking_exception(exception: &str) -> bool {
+    LINKING_EXCEPTIONS
+        .iter()
+        .any(|known| known.eq_ignore_ascii_case(exception))
+}
+
+/// The `[licenses.allow]`/`[licenses.deny]` decision for `license WITH exception`: a pattern
+/// naming the whole expression first, then one covering the base license. An exception never
+/// lifts a policy decision.
+fn with_policy(
+    license: &str,
+    exception: &str,
+    policy_fn: &dyn Fn(&str) -> Option<PolicyVerdict>,
+) -> Option<PolicyVerdict> {
+    policy_fn(&format!("{license} WITH {exception}")).or_else(|| policy_fn(license))
+}
+
 /// Evaluate compatibility of an SPDX expression against the project license.
 ///
 /// - `OR`  → compatible if ANY branch is compatible.
 /// - `AND` → compatible only if ALL branches are compatible.
-/// - Plain or `WITH` → delegate to the base license check.
+/// - `WITH` → the license policy (`policy_fn`) first; then a linking exception → compatible,
+///   any other exception → the base license check.
+/// - Plain → delegate to the base license check.
 pub fn expression_compatibility(
     expr: &SpdxExpression,
     project_license: &str,
     strict: bool,
     check_fn: &dyn Fn(&str, &str, bool) -> crate::licenses::LicenseCompatibility,
+    policy_fn: &dyn Fn(&str) -> Option<PolicyVerdict>,
 ) -> crate::licenses::LicenseCompatibility {
     use crate::licenses::LicenseCompatibility;
 
     match expr {
         SpdxExpression::License(id) => check_fn(id, project_license, strict),
-        SpdxExpression::With { license, .. } => check_fn(license, project_license, strict),
+        SpdxExpression::With { license, exception } => {
+            match with_policy(license, exception, policy_fn) {
+                Some(PolicyVerdict::Allow) => LicenseCompatibility::Compatible,
+                Some(PolicyVerdict::Deny) => LicenseCompatibility::Incompatible,
+                None if is_linking_exception(exception) => LicenseCompatibility::Compatible,
+                None => check_fn(license, project_license, strict),
+            }
+        }
 
         SpdxExpression::Or(a, b) => {
-            let ca = expression_compatibility(a, project_license, strict, check_fn);
-            let cb = expression_compatibility(b, project_license, strict, check_fn);
+            let ca = expression_compatibility(a, project_license, strict, check_fn, policy_fn);
+            let cb = expression_compatibility(b, project_license, strict, check_fn, policy_fn);
             match (ca, cb) {
                 (LicenseCompatibility::Compatible, _) | (_, LicenseCompatibility::Compatible) => {
                     LicenseCompatibility::Compatible
@@ -233,8 +305,8 @@ pub fn expression_compatibility(
         }
 
         SpdxExpression::And(a, b) => {
-            let ca = expression_compatibility(a, project_license, strict, check_fn);
-            let cb = expression_compatibility(b, project_license, strict, check_fn);
+            let ca = expression_compatibility(a, project_license, strict, check_fn, policy_fn);
+            let cb = expression_compatibility(b, project_license, strict, check_fn, policy_fn);
             match (ca, cb) {
                 (LicenseCompatibility::Incompatible, _)
                 | (_, LicenseCompatibility::Incompatible) => LicenseCompatibility::Incompatible,
@@ -251,15 +323,31 @@ pub fn expression_compatibility(
 ///
 /// - `OR`  → not restrictive if ANY branch is not restrictive (user can choose the permissive option).
 /// - `AND` → restrictive if ANY branch is restrictive (all licenses apply).
-pub fn expression_is_restrictive(expr: &SpdxExpression, check_fn: &dyn Fn(&str) -> bool) -> bool {
+/// - `WITH` → the license policy (`policy_fn`) first; then a linking exception → weak copyleft
+///   at most: restrictive only when `strict` and the base license is restrictive. Any other
+///   exception → the base license check.
+pub fn expression_is_restrictive(
+    expr: &SpdxExpression,
+    strict: bool,
+    check_fn: &dyn Fn(&str) -> bool,
+    policy_fn: &dyn Fn(&str) -> Option<PolicyVerdict>,
+) -> bool {
     match expr {
         SpdxExpression::License(id) => check_fn(id),
-        SpdxExpression::With { license, .. } => check_fn(license),
+        SpdxExpression::With { license, exception } => {
+            match with_policy(license, exception, policy_fn) {
+                Some(verdict) => verdict == PolicyVerdict::Deny,
+                None if is_linking_exception(exception) => strict && check_fn(license),
+                None => check_fn(license),
+            }
+        }
         SpdxExpression::Or(a, b) => {
-            expression_is_restrictive(a, check_fn) && expression_is_restrictive(b, check_fn)
+            expression_is_restrictive(a, strict, check_fn, policy_fn)
+                && expression_is_restrictive(b, strict, check_fn, policy_fn)
         }
         SpdxExpression::And(a, b) => {
-            expression_is_restrictive(a, check_fn) || expression_is_restrictive(b, check_fn)
+            expression_is_restrictive(a, strict, check_fn, policy_fn)
+                || expression_is_restrictive(b, strict, check_fn, policy_fn)
         }
     }
 }
@@ -430,13 +518,19 @@ mod tests {
         use crate::licenses::LicenseCompatibility;
 
         let expr = parse("MIT OR GPL-3.0");
-        let result = expression_compatibility(&expr, "MIT", false, &|dep, proj, _| {
-            if dep == "MIT" && proj == "MIT" {
-                LicenseCompatibility::Compatible
-            } else {
-                LicenseCompatibility::Incompatible
-            }
-        });
+        let result = expression_compatibility(
+            &expr,
+            "MIT",
+            false,
+            &|dep, proj, _| {
+                if dep == "MIT" && proj == "MIT" {
+                    LicenseCompatibility::Compatible
+                } else {
+                    LicenseCompatibility::Incompatible
+                }
+            },
+            &|_| None,
+        );
         assert_eq!(result, LicenseCompatibility::Compatible);
     }
 
@@ -445,20 +539,26 @@ mod tests {
         use crate::licenses::LicenseCompatibility;
 
         let expr = parse("MIT AND GPL-3.0");
-        let result = expression_compatibility(&expr, "MIT", false, &|dep, proj, _| {
-            if dep == "MIT" && proj == "MIT" {
-                LicenseCompatibility::Compatible
-            } else {
-                LicenseCompatibility::Incompatible
-            }
-        });
+        let result = expression_compatibility(
+            &expr,
+            "MIT",
+            false,
+            &|dep, proj, _| {
+                if dep == "MIT" && proj == "MIT" {
+                    LicenseCompatibility::Compatible
+                } else {
+                    LicenseCompatibility::Incompatible
+                }
+            },
+            &|_| None,
+        );
         assert_eq!(result, LicenseCompatibility::Incompatible);
     }
 
     #[test]
     fn test_expression_is_restrictive_or_one_permissive() {
         let expr = parse("MIT OR GPL-3.0");
-        let result = expression_is_restrictive(&expr, &|id| id == "GPL-3.0");
+        let result = expression_is_restrictive(&expr, false, &|id| id == "GPL-3.0", &|_| None);
         assert!(
             !result,
             "OR with one permissive option should not be restrictive"
@@ -468,13 +568,116 @@ mod tests {
     #[test]
     fn test_expression_is_restrictive_and_one_restrictive() {
         let expr = parse("MIT AND GPL-3.0");
-        let result = expression_is_restrictive(&expr, &|id| id == "GPL-3.0");
+        let result = expression_is_restrictive(&expr, false, &|id| id == "GPL-3.0", &|_| None);
         assert!(
             result,
             "AND with one restrictive component should be restrictive"
         );
     }
 
+    #[test]
+    fn test_license_policy_applies_through_linking_exception() {
+        use crate::licenses::LicenseCompatibility;
+
+        let classpath = parse("GPL-2.0-only WITH Classpath-exception-2.0");
+        let restrictive = |id: &str| id.starts_with("GPL");
+        let compatible = |_: &str, _: &str, _: bool| LicenseCompatibility::Compatible;
+
+        // deny = ["GPL-*"]: the exception doesn't lift a denied base license
+        let deny_gpl = |id: &str| id.starts_with("GPL-").then_some(PolicyVerdict::Deny);
+        assert!(expression_is_restrictive(
+            &classpath,
+            false,
+            &restrictive,
+            &deny_gpl
+        ));
+        assert_eq!(
+            expression_compatibility(&classpath, "MIT", false, &compatible, &deny_gpl),
+            LicenseCompatibility::Incompatible
+        );
+        // ... while a permissive alternative still passes
+        assert!(!expression_is_restrictive(
+            &parse("MIT OR GPL-2.0-only WITH Classpath-exception-2.0"),
+            false,
+            &restrictive,
+            &deny_gpl
+        ));
+
+        // A pattern naming the whole expression beats one covering the base license
+        let policy = |id: &str| match id {
+            "GPL-2.0-only WITH Classpath-exception-2.0" => Some(PolicyVerdict::Allow),
+            "GPL-2.0-only" => Some(PolicyVerdict::Deny),
+            _ => None,
+        };
+        assert!(!expression_is_restrictive(
+            &classpath,
+            true,
+            &restrictive,
+            &policy
+        ));
+        assert!(expression_is_restrictive(
+            &parse("GPL-2.0-only WITH openvpn-openssl-exception"),
+            false,
+            &|_| false,
+            &policy
+        ));
+    }
+
+    #[test]
+    fn test_linking_exception_is_honored() {
+        use crate::licenses::LicenseCompatibility;
+
+        let restrictive = |id: &str| id.starts_with("GPL");
+        let classpath = parse("GPL-2.0-only WITH Classpath-exception-2.0");
+        let no_policy = |_: &str| None;
+        assert!(!expression_is_restrictive(
+            &classpath,
+            false,
+            &restrictive,
+            &no_policy
+        ));
+        // Strict mode still counts the component's own copyleft, like LGPL
+        assert!(expression_is_restrictive(
+            &classpath,
+            true,
+            &restrictive,
+            &no_policy
+        ));
+        assert!(!expression_is_restrictive(
+            &parse("Apache-2.0 WITH LLVM-exception"),
+            true,
+            &restrictive,
+            &no_policy
+        ));
+
+        // Exceptions that don't cover linking leave the base license's verdict alone
+        let other = parse("GPL-2.0-only WITH openvpn-openssl-exception");
+        assert!(expression_is_restrictive(
+            &other,
+            false,
+            &restrictive,
+            &no_policy
+        ));
+
+        let incompatible = |_: &str, _: &str, _: bool| LicenseCompatibility::Incompatible;
+        assert_eq!(
+            expression_compatibility(&classpath, "MIT", false, &incompatible, &no_policy),
+            LicenseCompatibility::Compatible
+        );
+        assert_eq!(
+            expression_compatibility(&other, "MIT", false, &incompatible, &no_policy),
+            LicenseCompatibility::Incompatible
+        );
+        assert!(is_linking_exception("classpath-exception-2.0"));
+        assert_eq!(
+            parse("MIT OR GPL-2.0-only WITH Classpath-exception-2.0").exceptions(),
+            vec![(
+                "GPL-2.0-only".to_string(),
+                "Classpath-exception-2.0".to_string()
+            )]
+        );
+    }
+
     #[test]
     fn test_expression_osi_status_or_one_approved() {
         use crate::licenses::OsiStatus;
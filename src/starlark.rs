@@ -0,0 +1,471 @@
+//! Starlark call extraction for Bazel files.
+//!
+//! `MODULE.bazel` and `WORKSPACE` are Starlark programs, but the declarations Feluda cares
+//! about are plain top-level calls with literal arguments:
+//!
+//! ```text
+//! bazel_dep(
+//!     name = "rules_cc",   # kwargs in any order, one per line
+//!     version = "0.0.9",
+//!     dev_dependency = True,
+//! )
+//! ```
+//!
+//! This module tokenises the file (comments, all string forms, implicit line joining inside
+//! brackets) and returns every statement-level call with its literal arguments. Arguments that
+//! aren't literals (variables, concatenations, nested calls) are kept as [`Value::Other`]
+//! rather than failing the whole file.
+
+use std::collections::BTreeMap;
+
+/// A literal argument value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Str(String),
+    Int(i64),
+    Bool(bool),
+    None,
+    List(Vec<Value>),
+    /// Anything that isn't a literal
+    Other,
+}
+
+/// A statement-level call such as `bazel_dep(...)`, `ext.tag(...)` or `x = use_repo(...)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    /// The callee, dotted for method calls (`maven.install`)
+    pub name: String,
+    /// Keyword arguments; positional ones aren't needed for declarations and are skipped
+    pub kwargs: BTreeMap<String, Value>,
+    /// 1-based line of the callee
+    pub line: usize,
+}
+
+impl Call {
+    pub fn kwarg_str(&self, key: &str) -> Option<&str> {
+        match self.kwargs.get(key) {
+            Some(Value::Str(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn kwarg_bool(&self, key: &str) -> Option<bool> {
+        match self.kwargs.get(key) {
+            Some(Value::Bool(value)) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+/// Extract every statement-level call from a Starlark source file.
+///
+/// Returns an error (with a line number) for unterminated strings and unbalanced brackets,
+/// the two mistakes that make the rest of the file meaningless.
+pub fn parse_calls(source: &str) -> Result<Vec<Call>, String> {
+    let tokens = tokenize(source)?;
+    let mut calls = Vec::new();
+    let mut pos = 0;
+
+    while pos < tokens.len() {
+        if let Some(call) = parse_call_statement(&tokens, &mut pos) {
+            calls.push(call);
+        }
+        // Skip whatever is left of the statement
+        while pos < tokens.len() && tokens[pos].kind != TokenKind::Newline {
+            pos += 1;
+        }
+        pos += 1;
+    }
+    Ok(calls)
+}
+
+// ── Tokeniser ────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Ident(String),
+    Str(String),
+    Int(i64),
+    Punct(char),
+    /// End of a logical line (never emitted inside brackets)
+    Newline,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    kind: TokenKind,
+    line: usize,
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut brackets: Vec<(char, usize)> = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+
+    let push = |tokens: &mut Vec<Token>, kind: TokenKind, line: usize| {
+        tokens.push(Token { kind, line });
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\n' => {
+                if brackets.is_empty()
+                    && tokens
+                        .last()
+                        .is_some_and(|t: &Token| t.kind != TokenKind::Newline)
+                {
+                    push(&mut tokens, TokenKind::Newline, line);
+                }
+                line += 1;
+                i += 1;
+            }
+            '#' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '\\' if chars.get(i + 1) == Some(&'\n') => {
+                line += 1;
+                i += 2;
+            }
+            c if c.is_whitespace() => i += 1,
+            '"' | '\'' => {
+                let (value, end, lines) = read_string(&chars, i, false, line)?;
+                push(&mut tokens, TokenKind::Str(value), line);
+                line += lines;
+                i = end;
+            }
+            'r' | 'b' if matches!(chars.get(i + 1), Some('"' | '\'')) => {
+                let (value, end, lines) = read_string(&chars, i + 1, c == 'r', line)?;
+                push(&mut tokens, TokenKind::Str(value), line);
+                line += lines;
+                i = end;
+            }
+            c if c.is_ascii_digit() => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let literal: String = chars[start..i].iter().filter(|c| **c != '_').collect();
+                match literal.parse() {
+                    Ok(value) => push(&mut tokens, TokenKind::Int(value), line),
+                    // Hex, octal or floats: not something a dependency declaration needs
+                    Err(_) => push(&mut tokens, TokenKind::Ident(literal), line),
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                push(
+                    &mut tokens,
+                    TokenKind::Ident(chars[start..i].iter().collect()),
+                    line,
+                );
+            }
+            '(' | '[' | '{' => {
+                brackets.push((c, line));
+                push(&mut tokens, TokenKind::Punct(c), line);
+                i += 1;
+            }
+            ')' | ']' | '}' => {
+                let expected = match c {
+                    ')' => '(',
+                    ']' => '[',
+                    _ => '{',
+                };
+                match brackets.pop() {
+                    Some((open, _)) if open == expected => {}
+                    _ => return Err(format!("line {line}: unexpected '{c}'")),
+                }
+                push(&mut tokens, TokenKind::Punct(c), line);
+                i += 1;
+            }
+            _ => {
+                push(&mut tokens, TokenKind::Punct(c), line);
+                i += 1;
+            }
+        }
+    }
+
+    if let Some((open, open_line)) = brackets.pop() {
+        return Err(format!("line {open_line}: '{open}' is never closed"));
+    }
+    push(&mut tokens, TokenKind::Newline, line);
+    Ok(tokens)
+}
+
+/// Read a string literal starting at the quote at `start`. Returns the value, the index just
+/// past the closing quote, and how many newlines the literal spans.
+fn read_string(
+    chars: &[char],
+    start: usize,
+    raw: bool,
+    line: usize,
+) -> Result<(String, usize, usize), String> {
+    let quote = chars[start];
+    let triple = chars.get(start + 1) == Some(&quote) && chars.get(start + 2) == Some(&quote);
+    let mut i = start + if triple { 3 } else { 1 };
+    let mut value = String::new();
+    let mut lines = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == quote
+            && (!triple || (chars.get(i + 1) == Some(&quote) && chars.get(i + 2) == Some(&quote)))
+        {
+            return Ok((value, i + if triple { 3 } else { 1 }, lines));
+        }
+        match c {
+            '\n' if !triple => break,
+            '\n' => {
+                lines += 1;
+                value.push(c);
+            }
+            '\\' if i + 1 < chars.len() => {
+                i += 1;
+                let escaped = chars[i];
+                if raw {
+                    value.push('\\');
+                    value.push(escaped);
+                } else {
+                    match escaped {
+                        'n' => value.push('\n'),
+                        't' => value.push('\t'),
+                        '\n' => {}
+                        other => value.push(other),
+                    }
+                }
+                if escaped == '\n' {
+                    lines += 1;
+                }
+            }
+            _ => value.push(c),
+        }
+        i += 1;
+    }
+    Err(format!("line {line}: unterminated string"))
+}
+
+// ── Parser ───────────────────────────────────────────────────────────────────
+
+/// `[target =] name[.attr]* ( args )` at the start of a statement
+fn parse_call_statement(tokens: &[Token], pos: &mut usize) -> Option<Call> {
+    let mut i = *pos;
+    // `x = call(...)` binds the result; the call is what matters
+    if matches!(tokens.get(i).map(|t| &t.kind), Some(TokenKind::Ident(_)))
+        && tokens.get(i + 1).map(|t| &t.kind) == Some(&TokenKind::Punct('='))
+        && tokens.get(i + 2).map(|t| &t.kind) != Some(&TokenKind::Punct('='))
+    {
+        i += 2;
+    }
+
+    let line = tokens.get(i)?.line;
+    let mut name = match &tokens.get(i)?.kind {
+        TokenKind::Ident(ident) => ident.clone(),
+        _ => return None,
+    };
+    i += 1;
+    while tokens.get(i).map(|t| &t.kind) == Some(&TokenKind::Punct('.')) {
+        match &tokens.get(i + 1)?.kind {
+            TokenKind::Ident(attr) => {
+                name.push('.');
+                name.push_str(attr);
+            }
+            _ => return None,
+        }
+        i += 2;
+    }
+    if tokens.get(i)?.kind != TokenKind::Punct('(') {
+        return None;
+    }
+    i += 1;
+
+    let mut call = Call {
+        name,
+        kwargs: BTreeMap::new(),
+        line,
+    };
+    loop {
+        match &tokens.get(i)?.kind {
+            TokenKind::Punct(')') => {
+                i += 1;
+                break;
+            }
+            TokenKind::Punct(',') => i += 1,
+            TokenKind::Ident(key)
+                if tokens.get(i + 1).map(|t| &t.kind) == Some(&TokenKind::Punct('='))
+                    && tokens.get(i + 2).map(|t| &t.kind) != Some(&TokenKind::Punct('=')) =>
+            {
+                let key = key.clone();
+                i += 2;
+                let value = parse_argument(tokens, &mut i);
+                call.kwargs.insert(key, value);
+            }
+            _ => {
+                parse_argument(tokens, &mut i);
+            }
+        }
+    }
+
+    *pos = i;
+    Some(call)
+}
+
+/// One argument, up to (not including) the `,` or `)` that ends it
+fn parse_argument(tokens: &[Token], pos: &mut usize) -> Value {
+    let start = *pos;
+    let value = parse_literal(tokens, pos);
+    if value.is_some() && is_argument_end(tokens, *pos) {
+        return value.unwrap_or(Value::Other);
+    }
+
+    // Not a plain literal: skip the whole expression, brackets included
+    *pos = start;
+    let mut depth = 0usize;
+    while let Some(token) = tokens.get(*pos) {
+        match token.kind {
+            TokenKind::Punct('(' | '[' | '{') => depth += 1,
+            TokenKind::Punct(')' | ']' | '}') if depth == 0 => break,
+            TokenKind::Punct(')' | ']' | '}') => depth -= 1,
+            TokenKind::Punct(',') if depth == 0 => break,
+            TokenKind::Newline => break,
+            _ => {}
+        }
+        *pos += 1;
+    }
+    Value::Other
+}
+
+fn is_argument_end(tokens: &[Token], pos: usize) -> bool {
+    matches!(
+        tokens.get(pos).map(|t| &t.kind),
+        Some(TokenKind::Punct(',' | ')' | ']'))
+    )
+}
+
+fn parse_literal(tokens: &[Token], pos: &mut usize) -> Option<Value> {
+    let value = match &tokens.get(*pos)?.kind {
+        TokenKind::Str(value) => {
+            // Adjacent string literals concatenate
+            let mut value = value.clone();
+            *pos += 1;
+            while let Some(TokenKind::Str(next)) = tokens.get(*pos).map(|t| &t.kind) {
+                value.push_str(next);
+                *pos += 1;
+            }
+            return Some(Value::Str(value));
+        }
+        TokenKind::Int(value) => Value::Int(*value),
+        TokenKind::Ident(ident) if ident == "True" => Value::Bool(true),
+        TokenKind::Ident(ident) if ident == "False" => Value::Bool(false),
+        TokenKind::Ident(ident) if ident == "None" => Value::None,
+        TokenKind::Punct('[') => {
+            *pos += 1;
+            let mut items = Vec::new();
+            loop {
+                match tokens.get(*pos)?.kind {
+                    TokenKind::Punct(']') => break,
+                    TokenKind::Punct(',') => *pos += 1,
+                    _ => items.push(parse_argument(tokens, pos)),
+                }
+            }
+            Value::List(items)
+        }
+        _ => return None,
+    };
+    *pos += 1;
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_calls_multiline_and_kwargs() {
+        let calls = parse_calls(
+            r#"
+module(name = "app", version = "1.0")
+
+# A comment with bazel_dep(name = "fake", version = "0") in it
+bazel_dep(
+    version = "1.2.13",   # version before name
+    name = 'zlib',
+    repo_name = "net_zlib",
+)
+bazel_dep(name = "rules_cc", version = "0.0.9", dev_dependency = True)
+ext = use_extension("//:ext.bzl", "ext")
+ext.tag(names = ["a", "b"], count = 2, opt = None)
+"#,
+        )
+        .unwrap();
+
+        let names: Vec<&str> = calls.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "module",
+                "bazel_dep",
+                "bazel_dep",
+                "use_extension",
+                "ext.tag"
+            ]
+        );
+
+        let zlib = &calls[1];
+        assert_eq!(zlib.kwarg_str("name"), Some("zlib"));
+        assert_eq!(zlib.kwarg_str("version"), Some("1.2.13"));
+        assert_eq!(zlib.kwarg_bool("dev_dependency"), None);
+        assert_eq!(zlib.line, 5);
+
+        assert_eq!(calls[2].kwarg_bool("dev_dependency"), Some(true));
+        assert!(calls[3].kwargs.is_empty());
+        assert_eq!(
+            calls[4].kwargs.get("names"),
+            Some(&Value::List(vec![
+                Value::Str("a".to_string()),
+                Value::Str("b".to_string())
+            ]))
+        );
+        assert_eq!(calls[4].kwargs.get("count"), Some(&Value::Int(2)));
+        assert_eq!(calls[4].kwargs.get("opt"), Some(&Value::None));
+    }
+
+    #[test]
+    fn test_non_literal_arguments() {
+        let calls = parse_calls(
+            r#"
+VERSION = "1.0"
+bazel_dep(name = "abseil-cpp", version = VERSION + ".1", max_compatibility_level = f(1, [2]))
+http_archive(name = "x", urls = ["https://example.com/" + VERSION], sha256 = """abc
+def""")
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].kwarg_str("name"), Some("abseil-cpp"));
+        assert_eq!(calls[0].kwargs.get("version"), Some(&Value::Other));
+        assert_eq!(
+            calls[0].kwargs.get("max_compatibility_level"),
+            Some(&Value::Other)
+        );
+        assert_eq!(calls[1].kwarg_str("sha256"), Some("abc\ndef"));
+        assert_eq!(
+            calls[1].kwargs.get("urls"),
+            Some(&Value::List(vec![Value::Other]))
+        );
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        assert!(parse_calls("bazel_dep(name = \"zlib\"\n").is_err());
+        assert!(parse_calls("bazel_dep(name = \"zlib)\n").is_err());
+        assert!(parse_calls("x = [1, 2)\n").is_err());
+    }
+}
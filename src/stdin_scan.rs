@@ -0,0 +1,154 @@
+//! `--stdin`: scan a manifest or lockfile piped in instead of a project directory
+//!
+//! The input is written under its `--stdin-format` file name into a scratch directory that is
+//! then scanned like any project, so every analyzer that works from a single file works here.
+//! A `package-lock.json` on its own gets a `package.json` rebuilt from its root entry, since
+//! the Node analyzer starts from the manifest.
+
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use serde_json::{json, Map, Value};
+
+use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
+use crate::languages::Language;
+
+/// `package.json` fields a lockfile's root entry carries over
+const ROOT_FIELDS: [&str; 7] = [
+    "name",
+    "version",
+    "license",
+    "dependencies",
+    "devDependencies",
+    "optionalDependencies",
+    "peerDependencies",
+];
+
+/// Read all of stdin and write it into `dir` as `format`, ready to be scanned
+pub fn materialize_stdin(format: &str, dir: &Path) -> FeludaResult<()> {
+    check_format(format)?;
+
+    let mut content = String::new();
+    std::io::stdin()
+        .read_to_string(&mut content)
+        .map_err(|e| FeludaError::InvalidData(format!("Failed to read stdin: {e}")))?;
+    if content.trim().is_empty() {
+        return Err(FeludaError::InvalidData(format!(
+            "--stdin got no input; pipe the {format} in, e.g. `feluda --stdin --stdin-format {format} < {format}`"
+        )));
+    }
+    log(
+        LogLevel::Info,
+        &format!("Read {} bytes of {format} from stdin", content.len()),
+    );
+
+    write_input(format, &content, dir)
+}
+
+/// `format` must be a file name an analyzer can scan on its own
+fn check_format(format: &str) -> FeludaResult<()> {
+    let standalone = format == "package-lock.json"
+        || (Language::from_file_name(format).is_some() && format != "Cargo.toml");
+    if format.contains(['/', '\\']) || !standalone {
+        return Err(FeludaError::InvalidData(format!(
+            "--stdin-format '{format}' is not supported; use a manifest or lockfile name such as \
+             package.json, package-lock.json, requirements.txt, pyproject.toml, go.mod, \
+             Gemfile.lock, composer.lock or pom.xml (Rust needs the crate, so scan its directory)"
+        )));
+    }
+    Ok(())
+}
+
+fn write_input(format: &str, content: &str, dir: &Path) -> FeludaResult<()> {
+    fs::write(dir.join(format), content)?;
+    if format == "package-lock.json" {
+        fs::write(
+            dir.join("package.json"),
+            package_json_from_lock(content)?.to_string(),
+        )?;
+    }
+    Ok(())
+}
+
+/// A `package.json` with the root project's dependencies, from a v2/v3 lockfile's
+/// `packages[""]` entry
+fn package_json_from_lock(lock: &str) -> FeludaResult<Value> {
+    let lock: Value = serde_json::from_str(lock)
+        .map_err(|e| FeludaError::InvalidData(format!("package-lock.json from stdin: {e}")))?;
+    let Some(root) = lock["packages"][""].as_object() else {
+        return Err(FeludaError::InvalidData(
+            "package-lock.json from stdin has no root package entry; lockfileVersion 2 or later \
+             is needed"
+                .to_string(),
+        ));
+    };
+
+    let mut manifest: Map<String, Value> = ROOT_FIELDS
+        .iter()
+        .filter_map(|field| Some((field.to_string(), root.get(*field)?.clone())))
+        .collect();
+    if !manifest.contains_key("name") {
+        if let Some(name) = lock.get("name") {
+            manifest.insert("name".to_string(), name.clone());
+        }
+    }
+    manifest.entry("version").or_insert_with(|| json!("0.0.0"));
+    Ok(Value::Object(manifest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_format() {
+        assert!(check_format("package.json").is_ok());
+        assert!(check_format("package-lock.json").is_ok());
+        assert!(check_format("Gemfile.lock").is_ok());
+        assert!(check_format("requirements.txt").is_ok());
+        assert!(check_format("Cargo.toml").is_err());
+        assert!(check_format("yarn.lock").is_err());
+        assert!(check_format("../package.json").is_err());
+    }
+
+    #[test]
+    fn test_package_lock_gets_a_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let lock = r#"{
+  "name": "web-app",
+  "lockfileVersion": 3,
+  "packages": {
+    "": {
+      "name": "web-app",
+      "version": "1.2.0",
+      "license": "MIT",
+      "dependencies": { "react": "^18.2.0" },
+      "devDependencies": { "jest": "^29.0.0" }
+    },
+    "node_modules/react": { "version": "18.2.0" }
+  }
+}"#;
+        write_input("package-lock.json", lock, temp_dir.path()).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("package-lock.json")).unwrap(),
+            lock
+        );
+        let manifest: Value = serde_json::from_str(
+            &fs::read_to_string(temp_dir.path().join("package.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(manifest["name"], "web-app");
+        assert_eq!(manifest["license"], "MIT");
+        assert_eq!(manifest["dependencies"]["react"], "^18.2.0");
+        assert_eq!(manifest["devDependencies"]["jest"], "^29.0.0");
+    }
+
+    #[test]
+    fn test_package_lock_v1_is_rejected() {
+        let lock = r#"{ "name": "old", "lockfileVersion": 1, "dependencies": {} }"#;
+        assert!(package_json_from_lock(lock).is_err());
+    }
+}
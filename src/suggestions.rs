@@ -0,0 +1,433 @@
+//! Fix suggestions for license violations
+//!
+//! Every restrictive or incompatible dependency gets at least one remediation in the report:
+//! a permissively licensed alternative package or a newer release under a different license,
+//! both from the curated mapping in `config/license_alternatives.toml`, and otherwise a note
+//! that shipping it needs a vendor exception.
+//!
+//! A project extends the mapping in `.feluda/license_alternatives.toml`, next to the
+//! compatibility override. Its entries for a package replace the built-in ones for that
+//! package. `feluda explain <package>` shows what the merged mapping knows about a package.
+
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
+use crate::licenses::{LicenseCompatibility, LicenseInfo};
+
+/// The curated mapping shipped with feluda
+const EMBEDDED_ALTERNATIVES_TOML: &str = include_str!("../config/license_alternatives.toml");
+
+/// Project additions to the curated mapping, in the same format
+pub const ALTERNATIVES_OVERRIDE_PATH: &str = ".feluda/license_alternatives.toml";
+
+static ALTERNATIVES: OnceLock<Alternatives> = OnceLock::new();
+
+/// A remediation for a dependency whose license violates the policy
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum FixSuggestion {
+    /// Replace the dependency with a permissively licensed package
+    Alternative { package: String, license: String },
+    /// Upgrade to a release published under a different license
+    Upgrade { version: String, license: String },
+    /// No known fix; shipping the dependency needs a documented exception
+    VendorException,
+}
+
+impl std::fmt::Display for FixSuggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Alternative { package, license } => {
+                write!(f, "replace with {package} ({license})")
+            }
+            Self::Upgrade { version, license } => {
+                write!(f, "upgrade to {version} or later ({license})")
+            }
+            Self::VendorException => write!(f, "vendor exception required"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Alternatives {
+    #[serde(default)]
+    alternative: Vec<AlternativeEntry>,
+    #[serde(default)]
+    relicensed: Vec<RelicensedEntry>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct AlternativeEntry {
+    package: String,
+    alternative: String,
+    license: String,
+    /// Set for entries from the project's override file
+    #[serde(skip_deserializing, skip_serializing_if = "std::ops::Not::not")]
+    project: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct RelicensedEntry {
+    package: String,
+    since: String,
+    license: String,
+    #[serde(skip_deserializing, skip_serializing_if = "std::ops::Not::not")]
+    project: bool,
+}
+
+impl Alternatives {
+    /// Put the project's entries in front, dropping built-in ones for the packages it covers
+    fn merge(mut self, mut project: Alternatives) -> Self {
+        let covered: Vec<String> = project
+            .alternative
+            .iter()
+            .map(|entry| &entry.package)
+            .chain(project.relicensed.iter().map(|entry| &entry.package))
+            .map(|package| package.to_lowercase())
+            .collect();
+        let is_covered = |package: &str| covered.contains(&package.to_lowercase());
+        self.alternative.retain(|entry| !is_covered(&entry.package));
+        self.relicensed.retain(|entry| !is_covered(&entry.package));
+
+        project
+            .alternative
+            .iter_mut()
+            .for_each(|entry| entry.project = true);
+        project
+            .relicensed
+            .iter_mut()
+            .for_each(|entry| entry.project = true);
+        project.alternative.append(&mut self.alternative);
+        project.relicensed.append(&mut self.relicensed);
+        project
+    }
+
+    fn for_package(&self, package: &str) -> (Vec<&AlternativeEntry>, Vec<&RelicensedEntry>) {
+        (
+            self.alternative
+                .iter()
+                .filter(|entry| entry.package.eq_ignore_ascii_case(package))
+                .collect(),
+            self.relicensed
+                .iter()
+                .filter(|entry| entry.package.eq_ignore_ascii_case(package))
+                .collect(),
+        )
+    }
+}
+
+fn alternatives() -> &'static Alternatives {
+    ALTERNATIVES.get_or_init(|| {
+        let embedded = toml::from_str(EMBEDDED_ALTERNATIVES_TOML).unwrap_or_else(|e| {
+            log(
+                LogLevel::Error,
+                &format!("Embedded license alternatives are invalid: {e}"),
+            );
+            Alternatives::default()
+        });
+        match load_project_alternatives(Path::new(ALTERNATIVES_OVERRIDE_PATH)) {
+            Some(project) => embedded.merge(project),
+            None => embedded,
+        }
+    })
+}
+
+/// The project's override file, if there is one and it parses
+fn load_project_alternatives(path: &Path) -> Option<Alternatives> {
+    let content = fs::read_to_string(path).ok()?;
+    match toml::from_str::<Alternatives>(&content) {
+        Ok(project) => {
+            log(
+                LogLevel::Info,
+                &format!(
+                    "Loaded {} alternatives and {} relicensed entries from {}",
+                    project.alternative.len(),
+                    project.relicensed.len(),
+                    path.display()
+                ),
+            );
+            Some(project)
+        }
+        Err(e) => {
+            log(
+                LogLevel::Warn,
+                &format!("Ignoring invalid {}: {e}", path.display()),
+            );
+            None
+        }
+    }
+}
+
+/// Whether a dependency needs a fix: restrictive or incompatible, and not one of our own
+pub fn is_violation(info: &LicenseInfo) -> bool {
+    !info.is_internal()
+        && (info.is_restrictive || info.compatibility == LicenseCompatibility::Incompatible)
+}
+
+/// Attach fix suggestions to every violation. Run after compatibility has been annotated.
+pub fn annotate_suggestions(deps: &mut [LicenseInfo]) {
+    let alternatives = alternatives();
+    for dep in deps.iter_mut() {
+        dep.suggestions = if is_violation(dep) {
+            suggestions_for(dep, alternatives)
+        } else {
+            Vec::new()
+        };
+    }
+}
+
+fn suggestions_for(dep: &LicenseInfo, alternatives: &Alternatives) -> Vec<FixSuggestion> {
+    let (alternative, relicensed) = alternatives.for_package(&dep.name);
+    let mut suggestions: Vec<FixSuggestion> = relicensed
+        .into_iter()
+        .filter(|entry| version_below(&dep.version, &entry.since))
+        .map(|entry| FixSuggestion::Upgrade {
+            version: entry.since.clone(),
+            license: entry.license.clone(),
+        })
+        .collect();
+
+    suggestions.extend(
+        alternative
+            .into_iter()
+            .map(|entry| FixSuggestion::Alternative {
+                package: entry.alternative.clone(),
+                license: entry.license.clone(),
+            }),
+    );
+
+    if suggestions.is_empty() {
+        suggestions.push(FixSuggestion::VendorException);
+    }
+    suggestions
+}
+
+/// What the curated mapping knows about a package, for `feluda explain`
+#[derive(Debug, Serialize)]
+struct Explanation<'a> {
+    package: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    alternatives: Vec<&'a AlternativeEntry>,
+    relicensed: Vec<&'a RelicensedEntry>,
+}
+
+/// `feluda explain <package>`: the alternatives and relicensed releases the curated mapping
+/// (with the project's overrides) lists for a package
+pub fn handle_explain_command(
+    package: &str,
+    version: Option<&str>,
+    json: bool,
+) -> FeludaResult<()> {
+    let (alternatives, relicensed) = alternatives().for_package(package);
+    let explanation = Explanation {
+        package: package.to_string(),
+        version: version.map(str::to_string),
+        alternatives,
+        relicensed,
+    };
+
+    if json {
+        let output = serde_json::to_string_pretty(&explanation).map_err(|e| {
+            FeludaError::Serialization(format!("Failed to serialize explanation: {e}"))
+        })?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    print_explanation(&explanation);
+    Ok(())
+}
+
+fn print_explanation(explanation: &Explanation) {
+    let origin = |project: bool| {
+        if project {
+            format!(" [{ALTERNATIVES_OVERRIDE_PATH}]").dimmed()
+        } else {
+            " [built-in]".dimmed()
+        }
+    };
+
+    match &explanation.version {
+        Some(version) => println!("{}", format!("{}@{version}", explanation.package).bold()),
+        None => println!("{}", explanation.package.bold()),
+    }
+    if explanation.alternatives.is_empty() && explanation.relicensed.is_empty() {
+        println!("  No curated alternative or relicensed release is known for this package.");
+        println!(
+            "  {}",
+            format!("Add one to {ALTERNATIVES_OVERRIDE_PATH} to suggest it in reports.").dimmed()
+        );
+        return;
+    }
+
+    for entry in &explanation.relicensed {
+        let applies = match &explanation.version {
+            Some(version) if version_below(version, &entry.since) => " ← upgrade to fix".green(),
+            Some(_) => " (already there)".dimmed(),
+            None => "".normal(),
+        };
+        println!(
+            "  • {} since {} ({}){applies}{}",
+            "Relicensed".bold(),
+            entry.since,
+            entry.license,
+            origin(entry.project)
+        );
+    }
+    for entry in &explanation.alternatives {
+        println!(
+            "  • {} {} ({}){}",
+            "Replace with".bold(),
+            entry.alternative.cyan(),
+            entry.license,
+            origin(entry.project)
+        );
+    }
+}
+
+/// Numeric comparison of dotted versions (`1.1.1w` < `3.0.0`). Versions that don't start with
+/// a number (git refs, `*`) are never below.
+fn version_below(version: &str, since: &str) -> bool {
+    fn components(version: &str) -> Option<Vec<u64>> {
+        let parts: Vec<u64> = version
+            .trim()
+            .trim_start_matches(['v', '='])
+            .split(['.', '-', '+'])
+            .map_while(|part| {
+                let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+                digits.parse().ok()
+            })
+            .collect();
+        (!parts.is_empty()).then_some(parts)
+    }
+
+    match (components(version), components(since)) {
+        (Some(mut version), Some(mut since)) => {
+            let len = version.len().max(since.len());
+            version.resize(len, 0);
+            since.resize(len, 0);
+            version < since
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_alternatives_parse() {
+        let alternatives: Alternatives = toml::from_str(EMBEDDED_ALTERNATIVES_TOML).unwrap();
+        assert!(!alternatives.alternative.is_empty());
+        assert!(!alternatives.relicensed.is_empty());
+    }
+
+    #[test]
+    fn test_project_alternatives_replace_built_in_entries() {
+        let embedded: Alternatives = toml::from_str(EMBEDDED_ALTERNATIVES_TOML).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("license_alternatives.toml");
+        fs::write(
+            &path,
+            r#"
+[[alternative]]
+package = "Chardet"
+alternative = "cchardet-free"
+license = "BSD-3-Clause"
+
+[[alternative]]
+package = "mysql"
+alternative = "pg"
+license = "MIT"
+"#,
+        )
+        .unwrap();
+
+        let merged = embedded.merge(load_project_alternatives(&path).unwrap());
+        let (alternatives, _) = merged.for_package("chardet");
+        let names: Vec<(&str, bool)> = alternatives
+            .iter()
+            .map(|entry| (entry.alternative.as_str(), entry.project))
+            .collect();
+        assert_eq!(names, vec![("cchardet-free", true)]);
+        assert_eq!(merged.for_package("mysql").0[0].alternative, "pg");
+        // Packages the project doesn't mention keep their built-in entries
+        assert!(!merged.for_package("openssl").1.is_empty());
+
+        fs::write(&path, "[[alternative]]\npackage = 1\n").unwrap();
+        assert!(load_project_alternatives(&path).is_none());
+    }
+
+    #[test]
+    fn test_annotate_suggestions() {
+        let mut deps = vec![
+            LicenseInfo::fixture("chardet", "5.2.0", "GPL-3.0").restrictive(),
+            LicenseInfo::fixture("openssl", "1.1.1w", "GPL-3.0").restrictive(),
+            LicenseInfo::fixture("openssl", "3.2.0", "GPL-3.0").restrictive(),
+            LicenseInfo::fixture("some-gpl-lib", "1.0.0", "GPL-3.0").restrictive(),
+            LicenseInfo::fixture("serde", "1.0.0", "GPL-3.0"),
+        ];
+        annotate_suggestions(&mut deps);
+
+        assert_eq!(
+            deps[0].suggestions,
+            vec![FixSuggestion::Alternative {
+                package: "charset-normalizer".to_string(),
+                license: "MIT".to_string(),
+            }]
+        );
+        assert_eq!(
+            deps[1].suggestions,
+            vec![FixSuggestion::Upgrade {
+                version: "3.0.0".to_string(),
+                license: "Apache-2.0".to_string(),
+            }]
+        );
+        assert_eq!(deps[2].suggestions, vec![FixSuggestion::VendorException]);
+        assert_eq!(deps[3].suggestions, vec![FixSuggestion::VendorException]);
+        assert!(deps[4].suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_internal_and_incompatible_violations() {
+        let mut incompatible = LicenseInfo::fixture("some-lib", "1.0.0", "GPL-3.0");
+        incompatible.compatibility = LicenseCompatibility::Incompatible;
+        assert!(is_violation(&incompatible));
+
+        let mut internal = LicenseInfo::fixture("acme-core", "1.0.0", "GPL-3.0").restrictive();
+        internal.internal = true;
+        assert!(!is_violation(&internal));
+    }
+
+    #[test]
+    fn test_version_below() {
+        assert!(version_below("1.1.1w", "3.0.0"));
+        assert!(version_below("v8.0", "9.0.0"));
+        assert!(!version_below("3.0", "3.0.0"));
+        assert!(!version_below("3.0.1", "3.0.0"));
+        assert!(!version_below("github:owner/repo#main", "3.0.0"));
+    }
+
+    #[test]
+    fn test_suggestion_serialization() {
+        let json = serde_json::to_string(&vec![
+            FixSuggestion::Upgrade {
+                version: "3.0.0".to_string(),
+                license: "Apache-2.0".to_string(),
+            },
+            FixSuggestion::VendorException,
+        ])
+        .unwrap();
+        assert_eq!(
+            json,
+            r#"[{"kind":"upgrade","version":"3.0.0","license":"Apache-2.0"},{"kind":"vendor-exception"}]"#
+        );
+    }
+}
@@ -1,8 +1,9 @@
+use crate::baseline::{diff_against_baseline, BaselineDiff, RowChange};
 use crate::debug::{log, log_debug, LogLevel};
 use crate::licenses::{LicenseCompatibility, LicenseInfo};
 use color_eyre::Result;
 use ratatui::{
-    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
     layout::{Constraint, Flex, Layout, Position, Rect},
     style::{self, Color, Modifier, Style, Stylize},
     text::{Line, Span, Text},
@@ -12,24 +13,27 @@ use ratatui::{
     },
     DefaultTerminal, Frame,
 };
+use std::time::Duration;
 use style::palette::tailwind;
 use unicode_width::UnicodeWidthStr;
 
-const HELP_TEXT: [&str; 14] = [
+const HELP_TEXT: [&str; 16] = [
     "Navigation",
     "  ↑/k  move up        ↓/j  move down",
     "  ←/h  column left    →/l  column right",
     "  Enter  package details",
+    "  o  open repository / registry page in browser",
     "",
     "Filters (toggle)",
     "  r  restrictive      i  incompatible     c  compatible",
     "  a  osi-approved     n  osi-not-approved u  osi-unknown",
+    "  d  changes since baseline (--baseline)",
     "  x  clear all filters",
     "",
     "Sorting",
     "  s  enter sort mode (←→ pick column, Enter apply/toggle, Esc exit)",
     "",
-    "  ?  toggle this help    Esc/q  quit",
+    "  ?  toggle this help    Esc/q/Ctrl+C  quit",
 ];
 
 const ITEM_HEIGHT: usize = 1;
@@ -40,6 +44,9 @@ const MAX_NAME_WIDTH: u16 = 35;
 const MAX_VERSION_WIDTH: u16 = 20;
 const MAX_LICENSE_WIDTH: u16 = 50;
 
+/// How long the event loop waits for a key before checking for an outside interrupt
+const INTERRUPT_POLL: Duration = Duration::from_millis(250);
+
 // ============================================================================
 // KEY BINDINGS CONFIGURATION
 // ============================================================================
@@ -75,6 +82,7 @@ pub mod keybindings_normal {
     pub const FILTER_OSI_APPROVED: char = 'a';
     pub const FILTER_OSI_NOT_APPROVED: char = 'n';
     pub const FILTER_OSI_UNKNOWN: char = 'u';
+    pub const FILTER_CHANGES: char = 'd';
     pub const FILTER_CLEAR_ALL: char = 'x';
 
     /// Sort mode
@@ -85,6 +93,9 @@ pub mod keybindings_normal {
 
     /// Package detail popup
     pub const SHOW_DETAILS: KeyCode = KeyCode::Enter;
+
+    /// Open the selected package's repository in the browser
+    pub const OPEN_REPOSITORY: char = 'o';
 }
 
 /// Sort mode key bindings
@@ -117,6 +128,7 @@ struct FilterState {
     show_osi_approved_only: bool,
     show_osi_not_approved_only: bool,
     show_osi_unknown_only: bool,
+    show_changes_only: bool,
 }
 
 impl FilterState {
@@ -127,6 +139,7 @@ impl FilterState {
             || self.show_osi_approved_only
             || self.show_osi_not_approved_only
             || self.show_osi_unknown_only
+            || self.show_changes_only
     }
 
     fn clear_all(&mut self) {
@@ -136,6 +149,7 @@ impl FilterState {
         self.show_osi_approved_only = false;
         self.show_osi_not_approved_only = false;
         self.show_osi_unknown_only = false;
+        self.show_changes_only = false;
     }
 
     fn matches(&self, item: &LicenseInfo) -> bool {
@@ -200,6 +214,9 @@ struct TableColors {
     osi_unknown_color: Color,
     restrictive_color: Color,
     non_restrictive_color: Color,
+    added_color: Color,
+    removed_color: Color,
+    changed_color: Color,
     glass_tint: Color,
     glass_sheen: Color,
     glass_border: Color,
@@ -228,6 +245,9 @@ impl TableColors {
             osi_unknown_color: tailwind::GRAY.c500,
             restrictive_color: tailwind::RED.c500,
             non_restrictive_color: tailwind::SLATE.c500,
+            added_color: tailwind::GREEN.c400,
+            removed_color: tailwind::RED.c400,
+            changed_color: tailwind::AMBER.c400,
             glass_tint: tailwind::SLATE.c900,
             glass_sheen: tailwind::SLATE.c700,
             glass_border: tailwind::SLATE.c400,
@@ -300,6 +320,8 @@ pub struct App {
     sort_column_selection: usize, // Index in SortColumn::all()
     show_help: bool,
     show_detail: bool,
+    status: Option<String>, // One-off message shown in the footer until the next key press
+    baseline: Option<BaselineDiff>,
 }
 
 impl App {
@@ -326,13 +348,42 @@ impl App {
             sort_column_selection: 0,
             show_help: false,
             show_detail: false,
+            status: None,
+            baseline: None,
         }
     }
 
+    /// Compare against a previous report: removed packages are appended as rows and
+    /// every row gets a +/-/~ marker
+    pub fn with_baseline(mut self, baseline: &[LicenseInfo]) -> Self {
+        let diff = diff_against_baseline(&self.items, baseline);
+        let (added, removed, changed) = diff.counts();
+        log(
+            LogLevel::Info,
+            &format!("Baseline diff: {added} added, {removed} removed, {changed} changed"),
+        );
+
+        self.items.extend(diff.removed.iter().cloned());
+        self.longest_item_lens = constraint_len_calculator(&self.items);
+        // Room for the marker in front of the name
+        self.longest_item_lens.0 += 2;
+        self.baseline = Some(diff);
+        self.update_scroll_state();
+        self
+    }
+
+    fn row_change(&self, item: &LicenseInfo) -> Option<&RowChange> {
+        self.baseline.as_ref().map(|diff| diff.change_for(item))
+    }
+
     fn get_filtered_items(&self) -> Vec<&LicenseInfo> {
         self.items
             .iter()
             .filter(|item| self.filters.matches(item))
+            .filter(|item| {
+                !self.filters.show_changes_only
+                    || !matches!(self.row_change(item), None | Some(RowChange::Unchanged))
+            })
             .collect()
     }
 
@@ -454,6 +505,20 @@ impl App {
         self.state.select(Some(0));
     }
 
+    pub fn toggle_changes_filter(&mut self) {
+        if self.baseline.is_none() {
+            self.status = Some("No baseline loaded (run with --baseline <FILE>)".to_string());
+            return;
+        }
+        self.filters.show_changes_only = !self.filters.show_changes_only;
+        log(
+            LogLevel::Info,
+            &format!("Changes filter: {}", self.filters.show_changes_only),
+        );
+        self.update_scroll_state();
+        self.state.select(Some(0));
+    }
+
     pub fn clear_filters(&mut self) {
         self.filters.clear_all();
         log(LogLevel::Info, "All filters cleared");
@@ -634,6 +699,31 @@ impl App {
         }
     }
 
+    /// Open the selected package's repository (or registry page) in the browser
+    pub fn open_selected_repository(&mut self) {
+        let Some(item) = self
+            .state
+            .selected()
+            .and_then(|selected| self.get_filtered_items().get(selected).copied())
+        else {
+            return;
+        };
+
+        self.status = Some(match item.repository() {
+            Some(url) => match crate::utils::open_in_browser(url) {
+                Ok(()) => {
+                    log(LogLevel::Info, &format!("Opened {url} in browser"));
+                    format!("Opened {url}")
+                }
+                Err(e) => {
+                    log(LogLevel::Error, &format!("Failed to open {url}: {e}"));
+                    format!("Could not open a browser for {url}")
+                }
+            },
+            None => format!("No repository URL known for {}", item.name),
+        });
+    }
+
     pub fn set_colors(&mut self) {
         self.colors = TableColors::new(&TABLE_COLOUR);
     }
@@ -645,9 +735,26 @@ impl App {
             // Render the current state
             terminal.draw(|frame| self.draw(frame))?;
 
+            // A SIGINT delivered from outside the terminal (raw mode swallows Ctrl+C
+            // keypresses) cancels the session; poll so the loop notices it
+            if crate::cancel::is_cancelled() {
+                log(LogLevel::Info, "Quitting TUI application (interrupted)");
+                return Ok(());
+            }
+            if !event::poll(INTERRUPT_POLL)? {
+                continue;
+            }
+
             // Handle input events
             if let Event::Key(key) = event::read()? {
                 if key.kind == KeyEventKind::Press {
+                    self.status = None;
+                    if key.code == KeyCode::Char('c')
+                        && key.modifiers.contains(KeyModifiers::CONTROL)
+                    {
+                        log(LogLevel::Info, "Quitting TUI application (Ctrl+C)");
+                        return Ok(());
+                    }
                     // Popups swallow input until dismissed
                     if self.show_help {
                         if matches!(
@@ -671,10 +778,11 @@ impl App {
                             KeyCode::Char(c) if c == keybindings_normal::TOGGLE_HELP => {
                                 self.show_help = true;
                             }
-                            KeyCode::Enter => {
-                                if !self.get_filtered_items().is_empty() {
-                                    self.show_detail = true;
-                                }
+                            KeyCode::Enter if !self.get_filtered_items().is_empty() => {
+                                self.show_detail = true;
+                            }
+                            KeyCode::Char(c) if c == keybindings_normal::OPEN_REPOSITORY => {
+                                self.open_selected_repository()
                             }
                             // Quit
                             KeyCode::Esc => {
@@ -723,6 +831,9 @@ impl App {
                             KeyCode::Char(c) if c == keybindings_normal::FILTER_OSI_UNKNOWN => {
                                 self.toggle_osi_unknown_filter()
                             }
+                            KeyCode::Char(c) if c == keybindings_normal::FILTER_CHANGES => {
+                                self.toggle_changes_filter()
+                            }
                             KeyCode::Char(c) if c == keybindings_normal::FILTER_CLEAR_ALL => {
                                 self.clear_filters()
                             }
@@ -841,6 +952,26 @@ impl App {
                 Style::new().fg(self.colors.restrictive_color),
             ));
         }
+        if let Some(diff) = &self.baseline {
+            let (added, removed, changed) = diff.counts();
+            spans.push(Span::styled("  │  ", Style::new().fg(self.colors.dim_fg)));
+            spans.push(Span::styled(
+                "Baseline: ",
+                Style::new().fg(self.colors.dim_fg),
+            ));
+            spans.push(Span::styled(
+                format!("+{added} "),
+                Style::new().fg(self.colors.added_color),
+            ));
+            spans.push(Span::styled(
+                format!("-{removed} "),
+                Style::new().fg(self.colors.removed_color),
+            ));
+            spans.push(Span::styled(
+                format!("~{changed}"),
+                Style::new().fg(self.colors.changed_color),
+            ));
+        }
         if let Some(column) = self.sort_column {
             let direction = match self.sort_direction {
                 SortDirection::Ascending => "↑",
@@ -960,11 +1091,29 @@ impl App {
                 Text::from("No").fg(self.colors.non_restrictive_color)
             };
 
+            // Baseline marker in front of the name; removed rows are dimmed
+            let (marker, row_fg) = match self.row_change(data) {
+                Some(RowChange::Added) => (
+                    Span::styled("+ ", Style::new().fg(self.colors.added_color)),
+                    self.colors.row_fg,
+                ),
+                Some(RowChange::Removed) => (
+                    Span::styled("- ", Style::new().fg(self.colors.removed_color)),
+                    self.colors.dim_fg,
+                ),
+                Some(RowChange::Changed(_)) => (
+                    Span::styled("~ ", Style::new().fg(self.colors.changed_color)),
+                    self.colors.row_fg,
+                ),
+                Some(RowChange::Unchanged) => (Span::raw("  "), self.colors.row_fg),
+                None => (Span::raw(""), self.colors.row_fg),
+            };
+
             Row::new([
-                Cell::from(Text::from(truncate_with_ellipsis(
-                    &data.name,
-                    MAX_NAME_WIDTH,
-                ))),
+                Cell::from(Line::from(vec![
+                    marker,
+                    Span::raw(truncate_with_ellipsis(&data.name, MAX_NAME_WIDTH)),
+                ])),
                 Cell::from(Text::from(truncate_with_ellipsis(
                     &data.version,
                     MAX_VERSION_WIDTH,
@@ -977,7 +1126,7 @@ impl App {
                 Cell::from(compatibility_text),
                 Cell::from(osi_status_text),
             ])
-            .style(Style::new().fg(self.colors.row_fg).bg(color))
+            .style(Style::new().fg(row_fg).bg(color))
             .height(ITEM_HEIGHT as u16)
         });
 
@@ -1034,6 +1183,9 @@ impl App {
         if self.filters.show_osi_unknown_only {
             filter_tags.push("OSI-Unknown");
         }
+        if self.filters.show_changes_only {
+            filter_tags.push("Changed-since-baseline");
+        }
 
         let filter_text = format!("Active Filters: {}", filter_tags.join(", "));
         let filtered_count = self.get_filtered_items().len();
@@ -1103,8 +1255,9 @@ impl App {
             vec![
                 ("↑↓", "move"),
                 ("Enter", "details"),
+                ("o", "open"),
                 ("s", "sort"),
-                ("r/i/c/a/n/u", "filter"),
+                ("r/i/c/a/n/u/d", "filter"),
                 ("x", "clear"),
                 ("?", "help"),
                 ("q", "quit"),
@@ -1121,8 +1274,15 @@ impl App {
                     .add_modifier(Modifier::BOLD),
             ));
         }
-        for (key, label) in hints {
-            spans.extend(self.key_hint(key, label));
+        if let Some(status) = &self.status {
+            spans.push(Span::styled(
+                format!(" {status} "),
+                Style::new().fg(self.colors.accent),
+            ));
+        } else {
+            for (key, label) in hints {
+                spans.extend(self.key_hint(key, label));
+            }
         }
 
         let footer = Paragraph::new(Line::from(spans)).style(
@@ -1338,6 +1498,55 @@ impl App {
                 Span::styled(sub_project.clone(), value_style),
             ]));
         }
+        if let Some(upstream) = item.inherited_from() {
+            lines.push(Line::from(vec![
+                Span::styled("Inherited from ", label_style),
+                Span::styled(upstream.to_string(), value_style),
+            ]));
+        }
+        for (i, suggestion) in item.suggestions().iter().enumerate() {
+            let label = if i == 0 {
+                "Suggested fix  "
+            } else {
+                "               "
+            };
+            lines.push(Line::from(vec![
+                Span::styled(label, label_style),
+                Span::styled(suggestion.to_string(), value_style),
+            ]));
+        }
+        if item.found_in.len() > 1 {
+            lines.push(Line::from(vec![
+                Span::styled("Found in       ", label_style),
+                Span::styled(item.found_in.join(", "), value_style),
+            ]));
+        }
+        let baseline_text = match self.row_change(item) {
+            Some(RowChange::Added) => {
+                Some(("new since baseline".to_string(), self.colors.added_color))
+            }
+            Some(RowChange::Removed) => Some((
+                "removed since baseline".to_string(),
+                self.colors.removed_color,
+            )),
+            Some(RowChange::Changed(was)) => {
+                Some((format!("changed: {was}"), self.colors.changed_color))
+            }
+            Some(RowChange::Unchanged) => Some(("unchanged".to_string(), self.colors.row_fg)),
+            None => None,
+        };
+        if let Some((text, color)) = baseline_text {
+            lines.push(Line::from(vec![
+                Span::styled("Baseline       ", label_style),
+                Span::styled(text, Style::new().fg(color)),
+            ]));
+        }
+        if let Some(repository) = item.repository() {
+            lines.push(Line::from(vec![
+                Span::styled("Repository     ", label_style),
+                Span::styled(repository.to_string(), value_style),
+            ]));
+        }
         lines.push(Line::from(vec![
             Span::styled("Same license   ", label_style),
             Span::styled(shared_text, value_style),
@@ -1479,10 +1688,9 @@ mod tests {
             name: "test_package".to_string(),
             version: "1.0.0".to_string(),
             license: Some("MIT".to_string()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: crate::licenses::OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         }];
 
         let app = App::new(test_data.clone(), Some("MIT".to_string()));
@@ -1495,6 +1703,61 @@ mod tests {
         assert!(app_no_license.project_license.is_none());
     }
 
+    #[test]
+    fn test_open_selected_repository_without_url() {
+        let test_data = vec![LicenseInfo {
+            name: "vendored_lib".to_string(),
+            version: "1.0.0".to_string(),
+            license: Some("MIT".to_string()),
+            compatibility: LicenseCompatibility::Compatible,
+            osi_status: crate::licenses::OsiStatus::Approved,
+            ..Default::default()
+        }];
+
+        let mut app = App::new(test_data, None);
+        app.open_selected_repository();
+        assert_eq!(
+            app.status.as_deref(),
+            Some("No repository URL known for vendored_lib")
+        );
+    }
+
+    #[test]
+    fn test_app_with_baseline() {
+        let dep = |name: &str, version: &str| LicenseInfo {
+            name: name.to_string(),
+            version: version.to_string(),
+            license: Some("MIT".to_string()),
+            compatibility: LicenseCompatibility::Compatible,
+            osi_status: crate::licenses::OsiStatus::Approved,
+            ..Default::default()
+        };
+        let current = vec![
+            dep("kept", "1.0.0"),
+            dep("bumped", "2.0.0"),
+            dep("new", "1.0.0"),
+        ];
+        let baseline = vec![
+            dep("kept", "1.0.0"),
+            dep("bumped", "1.0.0"),
+            dep("gone", "1.0.0"),
+        ];
+
+        let mut app = App::new(current, None).with_baseline(&baseline);
+        assert_eq!(app.items.len(), 4);
+        assert_eq!(app.get_filtered_items().len(), 4);
+        assert_eq!(app.row_change(&app.items[3]), Some(&RowChange::Removed));
+
+        app.toggle_changes_filter();
+        let names: Vec<&str> = app.get_filtered_items().iter().map(|i| i.name()).collect();
+        assert_eq!(names, vec!["bumped", "new", "gone"]);
+
+        let mut without_baseline = App::new(vec![dep("kept", "1.0.0")], None);
+        without_baseline.toggle_changes_filter();
+        assert!(!without_baseline.filters.show_changes_only);
+        assert!(without_baseline.status.is_some());
+    }
+
     #[test]
     fn test_app_new_empty_data() {
         let test_data = vec![];
@@ -1512,19 +1775,17 @@ mod tests {
                 name: "package1".to_string(),
                 version: "1.0.0".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "package2".to_string(),
                 version: "2.0.0".to_string(),
                 license: Some("Apache-2.0".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "package3".to_string(),
@@ -1533,7 +1794,7 @@ mod tests {
                 is_restrictive: true,
                 compatibility: LicenseCompatibility::Incompatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ];
 
@@ -1572,10 +1833,9 @@ mod tests {
             name: "single_package".to_string(),
             version: "1.0.0".to_string(),
             license: Some("MIT".to_string()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: crate::licenses::OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         }];
 
         let mut app = App::new(test_data, None);
@@ -1610,10 +1870,9 @@ mod tests {
                 name: "very_long_package_name_that_exceeds_normal_length".to_string(),
                 version: "1.0.0-beta.1+build.123".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "short".to_string(),
@@ -1622,7 +1881,7 @@ mod tests {
                 is_restrictive: true,
                 compatibility: LicenseCompatibility::Incompatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ];
 
@@ -1658,10 +1917,9 @@ mod tests {
             name: "package_with_émojis_🚀_and_ünïcödé".to_string(),
             version: "1.0.0".to_string(),
             license: Some("MIT".to_string()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: crate::licenses::OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         }];
 
         let (name_len, _, _, _, _, _) = constraint_len_calculator(&test_data);
@@ -1676,10 +1934,9 @@ mod tests {
                 name: "compatible".to_string(),
                 version: "1.0.0".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "incompatible".to_string(),
@@ -1688,16 +1945,13 @@ mod tests {
                 is_restrictive: true,
                 compatibility: LicenseCompatibility::Incompatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "unknown".to_string(),
                 version: "1.0.0".to_string(),
                 license: Some("Custom".to_string()),
-                is_restrictive: false,
-                compatibility: LicenseCompatibility::Unknown,
-                osi_status: crate::licenses::OsiStatus::Unknown,
-                sub_project: None,
+                ..Default::default()
             },
         ];
 
@@ -1713,19 +1967,20 @@ mod tests {
                 name: "package".to_string(),
                 version: "1.0.0".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: true, // true
+                is_restrictive: true,
+                // true
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "package2".to_string(),
                 version: "1.0.0".to_string(),
                 license: Some("Apache".to_string()),
-                is_restrictive: false, // false
+                // false
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ];
 
@@ -1751,6 +2006,7 @@ mod tests {
         assert!(help.contains("sort mode"));
         assert!(help.contains("Enter"));
         assert!(help.contains("details"));
+        assert!(help.contains("open repository"));
     }
 
     #[test]
@@ -1771,10 +2027,9 @@ mod tests {
                 name: "short".to_string(),
                 version: "1.0".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "much_longer_name".to_string(),
@@ -1783,7 +2038,7 @@ mod tests {
                 is_restrictive: true,
                 compatibility: LicenseCompatibility::Incompatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ];
 
@@ -1803,19 +2058,17 @@ mod tests {
                 name: "zebra".to_string(),
                 version: "1.0.0".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "apple".to_string(),
                 version: "2.0.0".to_string(),
                 license: Some("Apache-2.0".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "banana".to_string(),
@@ -1824,7 +2077,7 @@ mod tests {
                 is_restrictive: true,
                 compatibility: LicenseCompatibility::Incompatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ];
 
@@ -1848,19 +2101,17 @@ mod tests {
                 name: "apple".to_string(),
                 version: "1.0.0".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "zebra".to_string(),
                 version: "2.0.0".to_string(),
                 license: Some("Apache-2.0".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ];
 
@@ -1885,16 +2136,15 @@ mod tests {
                 is_restrictive: true,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "package2".to_string(),
                 version: "2.0.0".to_string(),
                 license: Some("Apache-2.0".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ];
 
@@ -1918,10 +2168,9 @@ mod tests {
             name: "test".to_string(),
             version: "1.0.0".to_string(),
             license: Some("MIT".to_string()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: crate::licenses::OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         }];
 
         let mut app = App::new(test_data, None);
@@ -1947,10 +2196,9 @@ mod tests {
             name: "package".to_string(),
             version: "1.0.0".to_string(),
             license: Some("MIT".to_string()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: crate::licenses::OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         }];
 
         let mut app = App::new(test_data, None);
@@ -1978,19 +2226,17 @@ mod tests {
                 name: "zebra".to_string(),
                 version: "1.0.0".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "apple".to_string(),
                 version: "5.0.0".to_string(),
                 license: Some("Apache-2.0".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ];
 
@@ -2016,10 +2262,9 @@ mod tests {
             name: "test".to_string(),
             version: "1.0.0".to_string(),
             license: Some("MIT".to_string()),
-            is_restrictive: false,
             compatibility: LicenseCompatibility::Compatible,
             osi_status: crate::licenses::OsiStatus::Approved,
-            sub_project: None,
+            ..Default::default()
         }];
 
         let app = App::new(test_data, None);
@@ -2037,19 +2282,17 @@ mod tests {
                 name: "package1".to_string(),
                 version: "v3.0.0".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "package2".to_string(),
                 version: "v1.0.0".to_string(),
                 license: Some("Apache-2.0".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "package3".to_string(),
@@ -2058,7 +2301,7 @@ mod tests {
                 is_restrictive: true,
                 compatibility: LicenseCompatibility::Incompatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ];
 
@@ -2083,19 +2326,17 @@ mod tests {
                 name: "package1".to_string(),
                 version: "3.0.0".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "package2".to_string(),
                 version: "v1.5.0".to_string(),
                 license: Some("Apache-2.0".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "package3".to_string(),
@@ -2104,7 +2345,7 @@ mod tests {
                 is_restrictive: true,
                 compatibility: LicenseCompatibility::Incompatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ];
 
@@ -2127,19 +2368,17 @@ mod tests {
                 name: "package1".to_string(),
                 version: "v10.14.0".to_string(),
                 license: Some("MIT".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "package2".to_string(),
                 version: "0.14".to_string(),
                 license: Some("Apache-2.0".to_string()),
-                is_restrictive: false,
                 compatibility: LicenseCompatibility::Compatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
             LicenseInfo {
                 name: "package3".to_string(),
@@ -2148,7 +2387,7 @@ mod tests {
                 is_restrictive: true,
                 compatibility: LicenseCompatibility::Incompatible,
                 osi_status: crate::licenses::OsiStatus::Approved,
-                sub_project: None,
+                ..Default::default()
             },
         ];
 
@@ -0,0 +1,824 @@
+//! Dependabot and Renovate awareness
+//!
+//! The repository's update bot config says which dependencies are kept current and which
+//! are frozen: every dependency in an ecosystem a bot watches gets an [`UpdatePolicy`] in the
+//! results. `--pending-updates` goes one step further and predicts what the bot's open pull
+//! requests would do to the license picture, by reading the package versions their lockfile
+//! diffs bring in and looking up those versions' licenses before anything is merged.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use colored::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::debug::{log, record_parse_warning, FeludaError, FeludaResult, LogLevel};
+use crate::licenses::{fetch_licenses_from_github, is_license_restrictive, LicenseInfo};
+use crate::resolver::{github_api_get, resolve_license, LicenseQuery};
+
+const DEPENDABOT_FILES: [&str; 2] = [".github/dependabot.yml", ".github/dependabot.yaml"];
+
+const RENOVATE_FILES: [&str; 5] = [
+    "renovate.json",
+    ".github/renovate.json",
+    ".gitlab/renovate.json",
+    ".renovaterc",
+    ".renovaterc.json",
+];
+
+/// Most open pull requests read when predicting update impact
+const MAX_PULL_PAGES: usize = 5;
+
+/// How the repository's update bot treats a dependency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UpdatePolicy {
+    /// The bot is told to leave it alone, so it stays on its current version
+    Pinned,
+    /// The bot opens pull requests to update it
+    AutoUpdated,
+    /// The bot updates it and merges the pull requests itself
+    AutoMerged,
+}
+
+impl fmt::Display for UpdatePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pinned => write!(f, "pinned"),
+            Self::AutoUpdated => write!(f, "auto-updated"),
+            Self::AutoMerged => write!(f, "auto-merged"),
+        }
+    }
+}
+
+/// Dependabot's `package-ecosystem` values for a Feluda ecosystem
+fn dependabot_ecosystems(ecosystem: &str) -> &'static [&'static str] {
+    match ecosystem {
+        "rust" => &["cargo"],
+        "node" => &["npm"],
+        "go" => &["gomod"],
+        "python" => &["pip", "uv"],
+        "php" => &["composer"],
+        "ruby" => &["bundler"],
+        "elixir" => &["mix"],
+        "dotnet" => &["nuget"],
+        "java" => &["maven", "gradle"],
+        "android" => &["gradle"],
+        _ => &[],
+    }
+}
+
+/// Renovate's manager names for a Feluda ecosystem
+fn renovate_managers(ecosystem: &str) -> &'static [&'static str] {
+    match ecosystem {
+        "rust" => &["cargo"],
+        "node" => &["npm"],
+        "go" => &["gomod"],
+        "python" => &[
+            "pip_requirements",
+            "pip_setup",
+            "pipenv",
+            "poetry",
+            "pep621",
+        ],
+        "php" => &["composer"],
+        "ruby" => &["bundler"],
+        "elixir" => &["mix"],
+        "dotnet" => &["nuget"],
+        "java" => &["maven", "gradle"],
+        "android" => &["gradle"],
+        "cpp" => &["conan"],
+        _ => &[],
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DependabotConfig {
+    #[serde(default)]
+    updates: Vec<DependabotUpdate>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct DependabotUpdate {
+    package_ecosystem: String,
+    #[serde(default)]
+    ignore: Vec<DependabotIgnore>,
+    open_pull_requests_limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct DependabotIgnore {
+    dependency_name: String,
+    #[serde(default)]
+    versions: Vec<String>,
+    #[serde(default)]
+    update_types: Vec<String>,
+}
+
+impl DependabotConfig {
+    fn policy(&self, ecosystem: &str, name: &str) -> Option<UpdatePolicy> {
+        let ecosystems = dependabot_ecosystems(ecosystem);
+        let update = self
+            .updates
+            .iter()
+            .find(|update| ecosystems.contains(&update.package_ecosystem.as_str()))?;
+
+        // A limit of 0 turns version updates off, leaving only security updates
+        if update.open_pull_requests_limit == Some(0) {
+            return Some(UpdatePolicy::Pinned);
+        }
+        // An ignore narrowed to some versions or update types still lets others through
+        let ignored = update.ignore.iter().any(|ignore| {
+            ignore.versions.is_empty()
+                && ignore.update_types.is_empty()
+                && glob_matches(&ignore.dependency_name, name)
+        });
+        Some(if ignored {
+            UpdatePolicy::Pinned
+        } else {
+            UpdatePolicy::AutoUpdated
+        })
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RenovateConfig {
+    enabled: Option<bool>,
+    automerge: Option<bool>,
+    enabled_managers: Option<Vec<String>>,
+    #[serde(default)]
+    ignore_deps: Vec<String>,
+    #[serde(default)]
+    package_rules: Vec<Value>,
+}
+
+impl RenovateConfig {
+    fn policy(&self, ecosystem: &str, name: &str) -> Option<UpdatePolicy> {
+        let managers = renovate_managers(ecosystem);
+        if managers.is_empty() {
+            return None;
+        }
+        if let Some(enabled) = &self.enabled_managers {
+            if !enabled.iter().any(|m| managers.contains(&m.as_str())) {
+                return None;
+            }
+        }
+        if self.enabled == Some(false) || self.ignore_deps.iter().any(|dep| dep == name) {
+            return Some(UpdatePolicy::Pinned);
+        }
+
+        let mut enabled = true;
+        let mut automerge = self.automerge.unwrap_or(false);
+        // Later rules override earlier ones, as in Renovate
+        for rule in &self.package_rules {
+            if !renovate_rule_applies(rule, managers, name) {
+                continue;
+            }
+            if let Some(value) = rule["enabled"].as_bool() {
+                enabled = value;
+            }
+            if let Some(value) = rule["automerge"].as_bool() {
+                automerge = value;
+            }
+        }
+
+        Some(match (enabled, automerge) {
+            (false, _) => UpdatePolicy::Pinned,
+            (true, true) => UpdatePolicy::AutoMerged,
+            (true, false) => UpdatePolicy::AutoUpdated,
+        })
+    }
+}
+
+/// Whether a `packageRules` entry covers every update of `name`. Rules narrowed by anything
+/// but manager and package name (update type, dependency type, ...) only cover some of its
+/// updates, so they're left out.
+fn renovate_rule_applies(rule: &Value, managers: &[&str], name: &str) -> bool {
+    let Some(rule) = rule.as_object() else {
+        return false;
+    };
+    if rule.keys().any(|key| {
+        key.starts_with("match")
+            && !matches!(
+                key.as_str(),
+                "matchManagers" | "matchPackageNames" | "matchPackagePatterns"
+            )
+    }) {
+        return false;
+    }
+
+    let strings = |key: &str| -> Option<Vec<&str>> {
+        rule.get(key)?
+            .as_array()
+            .map(|values| values.iter().filter_map(Value::as_str).collect())
+    };
+    if let Some(rule_managers) = strings("matchManagers") {
+        if !rule_managers.iter().any(|m| managers.contains(m)) {
+            return false;
+        }
+    }
+    let names = strings("matchPackageNames");
+    let patterns = strings("matchPackagePatterns");
+    if names.is_none() && patterns.is_none() {
+        return true;
+    }
+
+    // `!pattern` excludes; with only exclusions, every other package matches
+    let (excluded, included): (Vec<&str>, Vec<&str>) = names
+        .unwrap_or_default()
+        .into_iter()
+        .partition(|pattern| pattern.starts_with('!'));
+    let patterns = patterns.unwrap_or_default();
+    if excluded
+        .iter()
+        .any(|pattern| renovate_name_matches(&pattern[1..], name))
+    {
+        return false;
+    }
+    (included.is_empty() && patterns.is_empty())
+        || included
+            .iter()
+            .any(|pattern| renovate_name_matches(pattern, name))
+        || patterns
+            .iter()
+            .any(|pattern| Regex::new(pattern).is_ok_and(|re| re.is_match(name)))
+}
+
+/// Renovate package name patterns: exact names, `*` globs and `/regex/`
+fn renovate_name_matches(pattern: &str, name: &str) -> bool {
+    match pattern
+        .strip_prefix('/')
+        .and_then(|rest| rest.rsplit_once('/'))
+    {
+        Some((regex, flags)) => {
+            let regex = if flags.contains('i') {
+                format!("(?i){regex}")
+            } else {
+                regex.to_string()
+            };
+            Regex::new(&regex).is_ok_and(|re| re.is_match(name))
+        }
+        None => glob_matches(pattern, name),
+    }
+}
+
+/// `*` matches any run of characters; everything else is literal
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    if !pattern.contains('*') {
+        return pattern == name;
+    }
+    let regex = format!(
+        "^{}$",
+        pattern
+            .split('*')
+            .map(regex::escape)
+            .collect::<Vec<_>>()
+            .join(".*")
+    );
+    Regex::new(&regex).is_ok_and(|re| re.is_match(name))
+}
+
+/// The update bot configs found in a repository
+#[derive(Debug, Default)]
+pub struct UpdateConfig {
+    dependabot: Option<DependabotConfig>,
+    renovate: Option<RenovateConfig>,
+}
+
+impl UpdateConfig {
+    /// Read `.github/dependabot.yml` and Renovate's config (its JSON files or the `renovate`
+    /// key of `package.json`) under `root`
+    pub fn load(root: &Path) -> Self {
+        let dependabot = DEPENDABOT_FILES
+            .iter()
+            .map(|file| root.join(file))
+            .find(|path| path.is_file())
+            .and_then(|path| {
+                let content = fs::read_to_string(&path).ok()?;
+                serde_yaml::from_str(&content)
+                    .map_err(|e| record_parse_warning(path.display().to_string(), e.to_string()))
+                    .ok()
+            });
+
+        let renovate = RENOVATE_FILES
+            .iter()
+            .map(|file| root.join(file))
+            .find(|path| path.is_file())
+            .and_then(|path| {
+                let content = fs::read_to_string(&path).ok()?;
+                serde_json::from_str(&content)
+                    .map_err(|e| record_parse_warning(path.display().to_string(), e.to_string()))
+                    .ok()
+            })
+            .or_else(|| {
+                let content = fs::read_to_string(root.join("package.json")).ok()?;
+                let package: Value = serde_json::from_str(&content).ok()?;
+                serde_json::from_value(package.get("renovate")?.clone()).ok()
+            });
+
+        if dependabot.is_some() {
+            log(LogLevel::Info, "Found a Dependabot config");
+        }
+        if renovate.is_some() {
+            log(LogLevel::Info, "Found a Renovate config");
+        }
+        Self {
+            dependabot,
+            renovate,
+        }
+    }
+
+    /// How the bots treat `name` in `ecosystem`; Renovate's answer wins when both cover it
+    pub fn policy(&self, ecosystem: &str, name: &str) -> Option<UpdatePolicy> {
+        self.renovate
+            .as_ref()
+            .and_then(|renovate| renovate.policy(ecosystem, name))
+            .or_else(|| {
+                self.dependabot
+                    .as_ref()
+                    .and_then(|dependabot| dependabot.policy(ecosystem, name))
+            })
+    }
+}
+
+/// Record each dependency's update policy from the bot configs under `root`
+pub fn annotate_update_policies(dependencies: &mut [LicenseInfo], root: &Path) {
+    let config = UpdateConfig::load(root);
+    if config.dependabot.is_none() && config.renovate.is_none() {
+        return;
+    }
+    for dep in dependencies.iter_mut() {
+        if let Some(ecosystem) = dep.ecosystem.clone() {
+            dep.update_policy = config.policy(&ecosystem, &dep.name);
+        }
+    }
+}
+
+/// Package versions a lockfile diff adds and removes
+#[derive(Debug, Default, PartialEq)]
+struct LockfileDiff {
+    added: Vec<(String, String)>,
+    removed: Vec<(String, String)>,
+}
+
+/// How a lockfile records a package: a name line followed by a version line, or both on one
+enum LockfileFormat {
+    Blocks { name: Regex, version: Regex },
+    Lines(Regex),
+}
+
+/// The ecosystem and record format of a lockfile, by file name
+fn lockfile_format(file_name: &str) -> Option<(&'static str, LockfileFormat)> {
+    let blocks = |name: &str, version: &str| LockfileFormat::Blocks {
+        name: Regex::new(name).unwrap(),
+        version: Regex::new(version).unwrap(),
+    };
+    let lines = |re: &str| LockfileFormat::Lines(Regex::new(re).unwrap());
+
+    Some(match file_name {
+        "Cargo.lock" => (
+            "rust",
+            blocks(r#"^name = "([^"]+)""#, r#"^version = "([^"]+)""#),
+        ),
+        "poetry.lock" | "uv.lock" => (
+            "python",
+            blocks(r#"^name = "([^"]+)""#, r#"^version = "([^"]+)""#),
+        ),
+        "package-lock.json" | "npm-shrinkwrap.json" => (
+            "node",
+            blocks(
+                r#"^\s*"(?:.*/)?node_modules/((?:@[^/"]+/)?[^/"]+)":\s*\{"#,
+                r#"^\s*"version":\s*"([^"]+)""#,
+            ),
+        ),
+        "yarn.lock" => (
+            "node",
+            blocks(
+                r#"^"?((?:@[^@/"]+/)?[^@"\s,]+)@"#,
+                r#"^\s+version:?\s+"?([^"\s]+)"?"#,
+            ),
+        ),
+        "pnpm-lock.yaml" => (
+            "node",
+            lines(r#"^\s+'?/?((?:@[^@/\s']+/)?[^@/\s']+)@(\d[^:('\s]*)"#),
+        ),
+        "composer.lock" => (
+            "php",
+            blocks(
+                r#"^\s*"name":\s*"([^"]+)""#,
+                r#"^\s*"version":\s*"v?([^"]+)""#,
+            ),
+        ),
+        "packages.lock.json" => (
+            "dotnet",
+            blocks(r#"^\s*"([^"]+)":\s*\{"#, r#"^\s*"resolved":\s*"([^"]+)""#),
+        ),
+        "Gemfile.lock" => ("ruby", lines(r"^ {4}([^\s(]+) \((\d[^)\s]*)\)$")),
+        "mix.lock" => (
+            "elixir",
+            lines(r#"^\s*"[^"]+":\s*\{:hex,\s*:"?([A-Za-z0-9_]+)"?,\s*"([^"]+)""#),
+        ),
+        "go.mod" => (
+            "go",
+            lines(r"^\s*(?:require\s+)?([^\s()]+\.[^\s()]+/\S+)\s+(v\d\S*)"),
+        ),
+        "gradle.lockfile" => ("java", lines(r"^([^:#\s]+:[^:\s]+):([^=\s]+)=")),
+        "requirements.txt" => (
+            "python",
+            lines(r"^([A-Za-z0-9_.\-]+)(?:\[[^\]]*\])?==([^\s;#]+)"),
+        ),
+        _ => return None,
+    })
+}
+
+/// The package versions a unified diff `patch` of a lockfile adds and removes. Lines moved
+/// within the file cancel out.
+fn parse_lockfile_patch(format: &LockfileFormat, patch: &str) -> LockfileDiff {
+    let mut added = Vec::new();
+    let mut removed = Vec::new();
+    let mut current_name: Option<String> = None;
+
+    for line in patch.lines() {
+        if line.starts_with("@@") {
+            current_name = None;
+            continue;
+        }
+        let (marker, text) = match line.chars().next() {
+            Some(marker @ ('+' | '-' | ' ')) => (marker, &line[1..]),
+            _ => continue,
+        };
+
+        let entry = match format {
+            LockfileFormat::Lines(re) => re
+                .captures(text)
+                .map(|cap| (cap[1].to_string(), cap[2].to_string())),
+            LockfileFormat::Blocks { name, version } => {
+                if let Some(cap) = name.captures(text) {
+                    current_name = Some(cap[1].to_string());
+                    None
+                } else {
+                    version
+                        .captures(text)
+                        .and_then(|cap| current_name.clone().map(|name| (name, cap[1].to_string())))
+                }
+            }
+        };
+        match (marker, entry) {
+            ('+', Some(entry)) => added.push(entry),
+            ('-', Some(entry)) => removed.push(entry),
+            _ => {}
+        }
+    }
+
+    let added_set: HashSet<_> = added.iter().cloned().collect();
+    let removed_set: HashSet<_> = removed.iter().cloned().collect();
+    added.retain(|entry| !removed_set.contains(entry));
+    removed.retain(|entry| !added_set.contains(entry));
+    added.dedup();
+    removed.dedup();
+    LockfileDiff { added, removed }
+}
+
+/// One package version an update pull request brings in, with the license it would carry
+#[derive(Debug)]
+struct LicenseChange {
+    name: String,
+    from_version: Option<String>,
+    to_version: String,
+    from_license: Option<String>,
+    to_license: Option<String>,
+    becomes_restrictive: bool,
+}
+
+/// An open update pull request and the license changes it would make
+#[derive(Debug)]
+struct PendingUpdate {
+    number: u64,
+    title: String,
+    url: String,
+    changes: Vec<LicenseChange>,
+    unchanged: usize,
+}
+
+/// Whether a pull request was opened by Dependabot or Renovate
+fn is_update_bot_pull(pull: &Value) -> bool {
+    let login = pull["user"]["login"].as_str().unwrap_or_default();
+    let branch = pull["head"]["ref"].as_str().unwrap_or_default();
+    matches!(login, "dependabot[bot]" | "renovate[bot]")
+        || branch.starts_with("dependabot/")
+        || branch.starts_with("renovate/")
+}
+
+/// `--pending-updates`: predict the license impact of the open Dependabot/Renovate pull
+/// requests in `repository` (OWNER/REPO) against the scanned dependencies
+pub fn report_pending_updates(
+    dependencies: &[LicenseInfo],
+    repository: &str,
+    strict: bool,
+) -> FeludaResult<()> {
+    let Some((owner, name)) = repository
+        .split_once('/')
+        .filter(|(owner, name)| !owner.is_empty() && !name.is_empty() && !name.contains('/'))
+    else {
+        return Err(FeludaError::InvalidData(format!(
+            "--pending-updates expects OWNER/REPO, got '{repository}'"
+        )));
+    };
+
+    let mut pulls = Vec::new();
+    for page in 1..=MAX_PULL_PAGES {
+        let Some(Value::Array(batch)) = github_api_get(&format!(
+            "repos/{owner}/{name}/pulls?state=open&per_page=100&page={page}"
+        )) else {
+            return Err(FeludaError::InvalidData(format!(
+                "Failed to list the open pull requests in {repository}"
+            )));
+        };
+        let last_page = batch.len() < 100;
+        pulls.extend(batch.into_iter().filter(is_update_bot_pull));
+        if last_page {
+            break;
+        }
+    }
+
+    let current: HashMap<(&str, &str), &LicenseInfo> = dependencies
+        .iter()
+        .map(|dep| ((dep.ecosystem().unwrap_or_default(), dep.name()), dep))
+        .collect();
+    let known_licenses = fetch_licenses_from_github().unwrap_or_default();
+
+    let mut pending = Vec::new();
+    for pull in &pulls {
+        let Some(number) = pull["number"].as_u64() else {
+            continue;
+        };
+        let Some(Value::Array(files)) = github_api_get(&format!(
+            "repos/{owner}/{name}/pulls/{number}/files?per_page=100"
+        )) else {
+            log(
+                LogLevel::Warn,
+                &format!("Failed to read the files of pull request #{number}"),
+            );
+            continue;
+        };
+
+        let mut update = PendingUpdate {
+            number,
+            title: pull["title"].as_str().unwrap_or_default().to_string(),
+            url: pull["html_url"].as_str().unwrap_or_default().to_string(),
+            changes: Vec::new(),
+            unchanged: 0,
+        };
+        let mut seen = HashSet::new();
+        for file in &files {
+            let path = file["filename"].as_str().unwrap_or_default();
+            let file_name = path.rsplit('/').next().unwrap_or(path);
+            let (Some((ecosystem, format)), Some(patch)) =
+                (lockfile_format(file_name), file["patch"].as_str())
+            else {
+                continue;
+            };
+
+            let diff = parse_lockfile_patch(&format, patch);
+            for (package, version) in diff.added {
+                if !seen.insert((ecosystem, package.clone(), version.clone())) {
+                    continue;
+                }
+                let previous = current.get(&(ecosystem, package.as_str()));
+                let from_version = diff
+                    .removed
+                    .iter()
+                    .find(|(removed, _)| *removed == package)
+                    .map(|(_, version)| version.clone())
+                    .or_else(|| previous.map(|dep| dep.version().to_string()));
+                let from_license = previous.and_then(|dep| dep.license.clone());
+                let to_license = resolve_license(
+                    &LicenseQuery::new(ecosystem, &package, &version),
+                    None,
+                    None,
+                );
+
+                let becomes_restrictive = to_license.is_some()
+                    && is_license_restrictive(&to_license, &known_licenses, strict)
+                    && !previous.is_some_and(|dep| *dep.is_restrictive());
+                if to_license.is_some() && to_license == from_license {
+                    update.unchanged += 1;
+                    continue;
+                }
+                update.changes.push(LicenseChange {
+                    name: package,
+                    from_version,
+                    to_version: version,
+                    from_license,
+                    to_license,
+                    becomes_restrictive,
+                });
+            }
+        }
+        pending.push(update);
+    }
+
+    print_pending_updates(&pending, repository);
+    Ok(())
+}
+
+fn print_pending_updates(pending: &[PendingUpdate], repository: &str) {
+    if pending.is_empty() {
+        eprintln!("🔮 No open Dependabot or Renovate pull requests in {repository}");
+        return;
+    }
+
+    eprintln!(
+        "\n{}",
+        format!("🔮 License impact of {} pending update(s)", pending.len()).bold()
+    );
+    for update in pending {
+        eprintln!("\n  #{} {}", update.number, update.title.bold());
+        if !update.url.is_empty() {
+            eprintln!("     {}", update.url.dimmed());
+        }
+        if update.changes.is_empty() {
+            eprintln!(
+                "     {} ({} package version(s) keep their license)",
+                "no license changes".green(),
+                update.unchanged
+            );
+            continue;
+        }
+        for change in &update.changes {
+            let versions = match &change.from_version {
+                Some(from) => format!("{from} → {}", change.to_version),
+                None => format!("new at {}", change.to_version),
+            };
+            let licenses = match (&change.from_license, &change.to_license) {
+                (_, None) => "license of the new version not found".yellow().to_string(),
+                (Some(from), Some(to)) => format!("{from} → {to}"),
+                (None, Some(to)) => to.to_string(),
+            };
+            let line = format!("     {} {versions}: {licenses}", change.name);
+            if change.becomes_restrictive {
+                eprintln!("{} {}", line.red(), "⚠️  restrictive".red().bold());
+            } else {
+                eprintln!("{line}");
+            }
+        }
+    }
+    eprintln!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_dependabot_policies() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join(".github")).unwrap();
+        fs::write(
+            temp_dir.path().join(".github/dependabot.yml"),
+            r#"version: 2
+updates:
+  - package-ecosystem: "cargo"
+    directory: "/"
+    schedule:
+      interval: "weekly"
+    ignore:
+      - dependency-name: "openssl*"
+      - dependency-name: "tokio"
+        update-types: ["version-update:semver-major"]
+  - package-ecosystem: "npm"
+    directory: "/"
+    open-pull-requests-limit: 0
+"#,
+        )
+        .unwrap();
+
+        let config = UpdateConfig::load(temp_dir.path());
+        assert_eq!(
+            config.policy("rust", "serde"),
+            Some(UpdatePolicy::AutoUpdated)
+        );
+        assert_eq!(
+            config.policy("rust", "openssl-sys"),
+            Some(UpdatePolicy::Pinned)
+        );
+        assert_eq!(
+            config.policy("rust", "tokio"),
+            Some(UpdatePolicy::AutoUpdated)
+        );
+        assert_eq!(config.policy("node", "lodash"), Some(UpdatePolicy::Pinned));
+        assert_eq!(config.policy("go", "golang.org/x/net"), None);
+    }
+
+    #[test]
+    fn test_renovate_policies() {
+        let config: RenovateConfig = serde_json::from_str(
+            r#"{
+  "extends": ["config:recommended"],
+  "ignoreDeps": ["left-pad"],
+  "enabledManagers": ["npm", "cargo"],
+  "packageRules": [
+    { "matchManagers": ["npm"], "automerge": true },
+    { "matchPackageNames": ["/^@internal//"], "enabled": false },
+    { "matchUpdateTypes": ["major"], "automerge": false },
+    { "matchPackageNames": ["react*", "!react-dom"], "automerge": false }
+  ]
+}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.policy("node", "lodash"),
+            Some(UpdatePolicy::AutoMerged)
+        );
+        assert_eq!(
+            config.policy("node", "left-pad"),
+            Some(UpdatePolicy::Pinned)
+        );
+        assert_eq!(
+            config.policy("node", "@internal/ui"),
+            Some(UpdatePolicy::Pinned)
+        );
+        assert_eq!(
+            config.policy("node", "react"),
+            Some(UpdatePolicy::AutoUpdated)
+        );
+        assert_eq!(
+            config.policy("node", "react-dom"),
+            Some(UpdatePolicy::AutoMerged)
+        );
+        assert_eq!(
+            config.policy("rust", "serde"),
+            Some(UpdatePolicy::AutoUpdated)
+        );
+        // Not an enabled manager
+        assert_eq!(config.policy("python", "requests"), None);
+    }
+
+    #[test]
+    fn test_parse_cargo_lock_patch() {
+        let (ecosystem, format) = lockfile_format("Cargo.lock").unwrap();
+        assert_eq!(ecosystem, "rust");
+        let patch = r#"@@ -1200,7 +1200,7 @@ dependencies = [
+
+ [[package]]
+ name = "serde"
+-version = "1.0.195"
++version = "1.0.196"
+ source = "registry+https://github.com/rust-lang/crates.io-index"
+@@ -1300,6 +1300,12 @@ dependencies = [
++[[package]]
++name = "serde_spanned"
++version = "0.6.5"
++source = "registry+https://github.com/rust-lang/crates.io-index"
++
+"#;
+        assert_eq!(
+            parse_lockfile_patch(&format, patch),
+            LockfileDiff {
+                added: vec![
+                    ("serde".to_string(), "1.0.196".to_string()),
+                    ("serde_spanned".to_string(), "0.6.5".to_string()),
+                ],
+                removed: vec![("serde".to_string(), "1.0.195".to_string())],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_package_lock_and_gemfile_patches() {
+        let (_, format) = lockfile_format("package-lock.json").unwrap();
+        let patch = r#"@@ -10,9 +10,9 @@
+     "node_modules/@babel/core": {
+-      "version": "7.23.0",
+-      "resolved": "https://registry.npmjs.org/@babel/core/-/core-7.23.0.tgz",
++      "version": "7.24.0",
++      "resolved": "https://registry.npmjs.org/@babel/core/-/core-7.24.0.tgz",
+"#;
+        let diff = parse_lockfile_patch(&format, patch);
+        assert_eq!(
+            diff.added,
+            vec![("@babel/core".to_string(), "7.24.0".to_string())]
+        );
+        assert_eq!(
+            diff.removed,
+            vec![("@babel/core".to_string(), "7.23.0".to_string())]
+        );
+
+        let (_, format) = lockfile_format("Gemfile.lock").unwrap();
+        let patch =
+            "@@ -80,7 +80,7 @@ GEM\n-    rack (3.0.8)\n+    rack (3.0.9)\n       rack (>= 1.3)\n";
+        assert_eq!(
+            parse_lockfile_patch(&format, patch).added,
+            vec![("rack".to_string(), "3.0.9".to_string())]
+        );
+    }
+}
@@ -95,11 +95,51 @@ fn validate_ssh_key(key_path: &Path) -> Result<(), git2::Error> {
     Ok(())
 }
 
+/// Open `url` in the default browser without waiting for it.
+///
+/// Repository URLs come from package metadata, so only `http` and `https` URLs are opened, and
+/// they are handed to the opener as a single argument that no shell interprets.
+pub fn open_in_browser(url: &str) -> std::io::Result<()> {
+    let url = reqwest::Url::parse(url)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("not an http(s) URL: {url}"),
+            )
+        })?;
+
+    let mut command = if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = std::process::Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+
+    // Keep the opener's output off the terminal the caller may be drawing on
+    command
+        .arg(url.as_str())
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
 pub fn clone_repository(args: &Cli, dest_path: &Path) -> FeludaResult<()> {
     let token = &args.token;
     let ssh_key = &args.ssh_key;
     let ssh_passphrase = &args.ssh_passphrase;
     let repo_url = &args.repo.as_deref().unwrap();
+    if crate::cancel::is_offline() {
+        return Err(FeludaError::RepositoryClone(format!(
+            "Cannot clone {repo_url} with --offline"
+        )));
+    }
 
     log(
         LogLevel::Info,
@@ -110,6 +150,11 @@ pub fn clone_repository(args: &Cli, dest_path: &Path) -> FeludaResult<()> {
         ),
     );
 
+    // Only the checked-out tree is scanned, unless --rev or --changed-since need the history.
+    // libgit2's local transport can't fetch shallow, so local repositories are cloned whole.
+    let local = repo_url.starts_with("file://") || Path::new(repo_url).exists();
+    let shallow = !local && args.rev.is_none() && args.changed_since.is_none();
+
     let auth_attempts = AtomicUsize::new(0);
     const MAX_AUTH_ATTEMPTS: usize = 5;
 
@@ -168,6 +213,9 @@ pub fn clone_repository(args: &Cli, dest_path: &Path) -> FeludaResult<()> {
 
     let mut fetch_options = git2::FetchOptions::new();
     fetch_options.remote_callbacks(callbacks);
+    if shallow {
+        fetch_options.depth(1);
+    }
     let mut builder = git2::build::RepoBuilder::new();
     builder.fetch_options(fetch_options);
 
@@ -199,6 +247,9 @@ pub fn clone_repository(args: &Cli, dest_path: &Path) -> FeludaResult<()> {
                     });
                     let mut https_fetch_options = git2::FetchOptions::new();
                     https_fetch_options.remote_callbacks(https_callbacks);
+                    if shallow {
+                        https_fetch_options.depth(1);
+                    }
                     let mut https_builder = git2::build::RepoBuilder::new();
                     https_builder.fetch_options(https_fetch_options);
 
@@ -234,6 +285,19 @@ mod tests {
     use std::fs::File;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_open_in_browser_rejects_non_http_urls() {
+        for url in [
+            "file:///etc/passwd",
+            "javascript:alert(1)",
+            "https://x\" & calc & \"",
+            "not a url",
+        ] {
+            let err = open_in_browser(url).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput, "{url}");
+        }
+    }
+
     #[test]
     fn test_ssh_to_https_url_github_ssh() {
         let url = "git@github.com:anistark/feluda.git";
@@ -421,6 +485,8 @@ mod tests {
             command: None,
             path: "./".to_string(),
             repo: Some("invalid-repo-url".to_string()),
+            stdin: false,
+            stdin_format: None,
             token: None,
             ssh_key: None,
             ssh_passphrase: None,
@@ -436,12 +502,50 @@ mod tests {
             fail_on_restrictive: false,
             incompatible: false,
             fail_on_incompatible: false,
+            fail_on_resolution_errors: false,
+            osi_only: false,
             project_license: None,
             gist: false,
             osi: None,
             strict: false,
             no_local: false,
+            offline: false,
             no_vendor_scan: false,
+            artifacts: Vec::new(),
+            resolve_forks: false,
+            vulnerabilities: false,
+            strict_parse: false,
+            skip: Vec::new(),
+            changed_since: None,
+            no_dev: false,
+            direct_only: false,
+            include_transitive: false,
+            no_dev_deps: false,
+            no_build_deps: false,
+            features: Vec::new(),
+            no_default_features: false,
+            github_sbom: false,
+            limit: None,
+            sample: None,
+            rev: None,
+            baseline: None,
+            write_baseline: None,
+            force: false,
+            output_dir: None,
+            keep_reports: None,
+            compress: None,
+            chart: false,
+            locked: false,
+            frozen: false,
+            dry_run: false,
+            sbom: None,
+            output_format: None,
+            licenses_dir: None,
+            obligations: None,
+            obligations_issues: None,
+            pending_updates: None,
+            result_file: None,
+            timeout: None,
         };
 
         let result = clone_repository(&args, temp_dir.path());
@@ -478,6 +582,8 @@ mod tests {
             command: None,
             path: "./".to_string(),
             repo: Some("https://github.com/nonexistent/repo.git".to_string()),
+            stdin: false,
+            stdin_format: None,
             token: None,
             ssh_key: None,
             ssh_passphrase: None,
@@ -493,12 +599,50 @@ mod tests {
             fail_on_restrictive: false,
             incompatible: false,
             fail_on_incompatible: false,
+            fail_on_resolution_errors: false,
+            osi_only: false,
             project_license: None,
             gist: false,
             osi: None,
             strict: false,
             no_local: false,
+            offline: false,
             no_vendor_scan: false,
+            artifacts: Vec::new(),
+            resolve_forks: false,
+            vulnerabilities: false,
+            strict_parse: false,
+            skip: Vec::new(),
+            changed_since: None,
+            no_dev: false,
+            direct_only: false,
+            include_transitive: false,
+            no_dev_deps: false,
+            no_build_deps: false,
+            features: Vec::new(),
+            no_default_features: false,
+            github_sbom: false,
+            limit: None,
+            sample: None,
+            rev: None,
+            baseline: None,
+            write_baseline: None,
+            force: false,
+            output_dir: None,
+            keep_reports: None,
+            compress: None,
+            chart: false,
+            locked: false,
+            frozen: false,
+            dry_run: false,
+            sbom: None,
+            output_format: None,
+            licenses_dir: None,
+            obligations: None,
+            obligations_issues: None,
+            pending_updates: None,
+            result_file: None,
+            timeout: None,
         };
 
         // Enable debug mode for this test
@@ -534,6 +678,8 @@ mod tests {
             command: None,
             path: "./".to_string(),
             repo: Some("".to_string()),
+            stdin: false,
+            stdin_format: None,
             token: None,
             ssh_key: None,
             ssh_passphrase: None,
@@ -549,12 +695,50 @@ mod tests {
             fail_on_restrictive: false,
             incompatible: false,
             fail_on_incompatible: false,
+            fail_on_resolution_errors: false,
+            osi_only: false,
             project_license: None,
             gist: false,
             osi: None,
             strict: false,
             no_local: false,
+            offline: false,
             no_vendor_scan: false,
+            artifacts: Vec::new(),
+            resolve_forks: false,
+            vulnerabilities: false,
+            strict_parse: false,
+            skip: Vec::new(),
+            changed_since: None,
+            no_dev: false,
+            direct_only: false,
+            include_transitive: false,
+            no_dev_deps: false,
+            no_build_deps: false,
+            features: Vec::new(),
+            no_default_features: false,
+            github_sbom: false,
+            limit: None,
+            sample: None,
+            rev: None,
+            baseline: None,
+            write_baseline: None,
+            force: false,
+            output_dir: None,
+            keep_reports: None,
+            compress: None,
+            chart: false,
+            locked: false,
+            frozen: false,
+            dry_run: false,
+            sbom: None,
+            output_format: None,
+            licenses_dir: None,
+            obligations: None,
+            obligations_issues: None,
+            pending_updates: None,
+            result_file: None,
+            timeout: None,
         };
 
         let result = clone_repository(&args, temp_dir.path());
@@ -93,7 +93,7 @@ struct Finding {
 }
 
 /// Whether `name` is a conventional vendor directory.
-fn is_vendor_dir_name(name: &str) -> bool {
+pub(crate) fn is_vendor_dir_name(name: &str) -> bool {
     VENDOR_DIR_NAMES
         .iter()
         .any(|known| known.eq_ignore_ascii_case(name))
@@ -361,6 +361,20 @@ pub fn scan_vendored_packages(
                 compatibility: LicenseCompatibility::Unknown,
                 osi_status,
                 sub_project: None,
+                found_in: Vec::new(),
+                repository: None,
+                declared_in: None,
+                internal: false,
+                inherited_from: None,
+                ecosystem: None,
+                suggestions: Vec::new(),
+                update_policy: None,
+                introduced_by: Vec::new(),
+                purl: None,
+                vulnerabilities: None,
+                manifest: None,
+                data_license: None,
+                project_dirs: Vec::new(),
             }
         })
         .collect()
@@ -11,7 +11,8 @@
 
 use crate::debug::{log, FeludaError, FeludaResult, LogLevel};
 use crate::manifest;
-use crate::{analyze_dependencies, annotate_compatibility, report_analysis, CheckConfig};
+use crate::report_metadata::ReportMetadata;
+use crate::{analyze_dependencies, annotate_compatibility, report_analysis, Analysis, CheckConfig};
 use colored::Colorize;
 use notify::{Event, RecursiveMode, Watcher};
 use std::path::Path;
@@ -24,13 +25,32 @@ use std::time::{Duration, Instant};
 /// writing a half-finished manifest) doesn't tear down the watch session.
 fn scan_once(config: &CheckConfig) {
     match analyze_dependencies(config) {
-        Ok((mut analyzed_data, project_license)) => {
+        Ok(Analysis {
+            dependencies: mut analyzed_data,
+            project_license,
+            warnings,
+            resolution_errors,
+            ignored,
+        }) => {
             if analyzed_data.is_empty() {
                 log(LogLevel::Warn, "No dependencies found to analyze.");
+                crate::reporter::print_parse_warnings(&warnings);
+                crate::ignore::print_ignored_dependencies(&ignored);
+                crate::reporter::print_resolution_errors(&resolution_errors);
                 return;
             }
             annotate_compatibility(&mut analyzed_data, &project_license, config.strict);
-            let _ = report_analysis(analyzed_data, project_license, config);
+            crate::suggestions::annotate_suggestions(&mut analyzed_data);
+            let metadata = ReportMetadata::collect(Path::new(&config.path));
+            let _ = report_analysis(
+                analyzed_data,
+                project_license,
+                warnings,
+                resolution_errors,
+                ignored,
+                metadata,
+                config,
+            );
         }
         Err(e) => {
             // Keep watching even if this pass failed.
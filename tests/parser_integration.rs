@@ -1,15 +1,17 @@
 //! Integration tests across the language parsers (#120).
 //!
 //! Each test builds a self-contained fixture project in a temp directory and drives the real
-//! `feluda` binary (`CARGO_BIN_EXE_feluda`) against it, asserting on the `--json` report. The
+//! `feluda` binary (`CARGO_BIN_EXE_feluda`), or the library's `feluda::scan`, against it,
+//! asserting on the `--json` report. The
 //! fixtures are crafted so license resolution succeeds from local sources alone — Node from
 //! `node_modules/*/package.json`, Rust from `cargo metadata` on a path dependency, Go from a
 //! module cache directory pointed at by `GOMODCACHE`/`GOPATH` — so the tests hold with or
 //! without network access.
 
 use std::fs;
+use std::io::Write;
 use std::path::Path;
-use std::process::{Command, Output};
+use std::process::{Command, Output, Stdio};
 
 use serde_json::Value;
 
@@ -29,8 +31,8 @@ fn run_feluda(dir: &Path, args: &[&str], envs: &[(&str, &str)]) -> Output {
     cmd.output().expect("failed to run feluda binary")
 }
 
-/// Run `feluda --json` on `dir` and parse the report. An empty report (no dependencies at
-/// all) produces no stdout, which parses as an empty list.
+/// Run `feluda --json` on `dir` and return the report's dependencies. An empty report (no
+/// dependencies at all) produces no stdout, which parses as an empty list.
 fn scan_json(dir: &Path, extra_args: &[&str], envs: &[(&str, &str)]) -> Vec<Value> {
     let mut args = vec!["--json"];
     args.extend_from_slice(extra_args);
@@ -46,8 +48,12 @@ fn scan_json(dir: &Path, extra_args: &[&str], envs: &[(&str, &str)]) -> Vec<Valu
     if trimmed.is_empty() {
         return Vec::new();
     }
-    serde_json::from_str(trimmed)
-        .unwrap_or_else(|e| panic!("feluda emitted invalid JSON: {e}\n{stdout}"))
+    let report: Value = serde_json::from_str(trimmed)
+        .unwrap_or_else(|e| panic!("feluda emitted invalid JSON: {e}\n{stdout}"));
+    report["dependencies"]
+        .as_array()
+        .unwrap_or_else(|| panic!("report has no dependencies list:\n{stdout}"))
+        .clone()
 }
 
 fn entry<'a>(entries: &'a [Value], name: &str) -> &'a Value {
@@ -142,6 +148,110 @@ fn node_dependencies_resolve_from_local_node_modules() {
     assert_eq!(copyleft["compatibility"], "Incompatible");
 }
 
+#[test]
+fn library_scan_returns_the_report_dependencies() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    fs::write(root.join("LICENSE"), MIT_TEXT).unwrap();
+    write_node_fixture(
+        root,
+        &[
+            ("fixture-permissive", "1.3.0", "ISC"),
+            ("fixture-copyleft", "2.0.0", "GPL-3.0-only"),
+        ],
+    );
+
+    let mut options = feluda::ScanOptions::default();
+    options.offline = true;
+    let result = feluda::scan(root, options).unwrap();
+
+    assert_eq!(result.project_license.as_deref(), Some("MIT"));
+    let restrictive: Vec<&str> = result.restrictive().map(|dep| dep.name()).collect();
+    assert_eq!(restrictive, vec!["fixture-copyleft"]);
+    assert!(result.has_incompatible());
+
+    // The serialized report is the one `feluda --json` writes
+    let report: Value = serde_json::from_str(&result.to_json().unwrap()).unwrap();
+    let copyleft = entry(
+        report["dependencies"].as_array().unwrap(),
+        "fixture-copyleft",
+    );
+    assert_eq!(copyleft["license"], "GPL-3.0-only");
+    assert_eq!(copyleft["compatibility"], "Incompatible");
+}
+
+#[test]
+fn json_report_matches_the_published_schema() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    fs::write(root.join("LICENSE"), MIT_TEXT).unwrap();
+    write_node_fixture(root, &[("fixture-copyleft", "2.0.0", "GPL-3.0-only")]);
+
+    let output = run_feluda(root, &["--json", "--offline"], &[]);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let schema: Value = serde_json::from_str(include_str!("../schema/report.schema.json")).unwrap();
+    let validator = jsonschema::validator_for(&schema).unwrap();
+    let errors: Vec<String> = validator
+        .iter_errors(&report)
+        .map(|error| error.to_string())
+        .collect();
+    assert!(errors.is_empty(), "{errors:#?}");
+
+    assert_eq!(report["schema_version"], 2);
+    assert_eq!(report["project_license"], "MIT");
+    assert_eq!(report["metadata"]["path"], root.to_str().unwrap());
+    let copyleft = entry(
+        report["dependencies"].as_array().unwrap(),
+        "fixture-copyleft",
+    );
+    assert_eq!(copyleft["ecosystem"], "node");
+    assert_eq!(copyleft["manifest"], "package.json");
+}
+
+#[test]
+fn limit_marks_the_report_as_a_partial_scan() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    fs::write(root.join("LICENSE"), MIT_TEXT).unwrap();
+    write_node_fixture(
+        root,
+        &[
+            ("fixture-one", "1.0.0", "MIT"),
+            ("fixture-two", "1.0.0", "ISC"),
+            ("fixture-three", "1.0.0", "Apache-2.0"),
+        ],
+    );
+
+    let output = run_feluda(
+        root,
+        &["--json", "--offline", "--no-vendor-scan", "--limit", "2"],
+        &[],
+    );
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["dependencies"].as_array().unwrap().len(), 2);
+    let partial = &report["metadata"]["partial"];
+    assert_eq!(partial["found"], 3);
+    assert_eq!(partial["kept"], 2);
+    assert_eq!(partial["limit"], 2);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Partial scan: 2 of 3"));
+
+    // A full scan carries no marker
+    let output = run_feluda(root, &["--json", "--offline", "--no-vendor-scan"], &[]);
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["dependencies"].as_array().unwrap().len(), 3);
+    assert!(report["metadata"].get("partial").is_none());
+}
+
 #[test]
 fn fail_on_restrictive_sets_exit_code() {
     let temp = tempfile::TempDir::new().unwrap();
@@ -164,6 +274,443 @@ fn fail_on_restrictive_sets_exit_code() {
     );
 }
 
+#[test]
+fn deny_list_is_not_lifted_by_a_linking_exception() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    fs::write(root.join("LICENSE"), MIT_TEXT).unwrap();
+    write_node_fixture(
+        root,
+        &[(
+            "fixture-classpath",
+            "1.0.0",
+            "GPL-2.0-only WITH Classpath-exception-2.0",
+        )],
+    );
+
+    let entries = scan_json(root, &[], &[]);
+    let classpath = entry(&entries, "fixture-classpath");
+    assert_eq!(classpath["is_restrictive"], false);
+    assert_eq!(classpath["compatibility"], "Compatible");
+
+    fs::write(
+        root.join(".feluda.toml"),
+        "[licenses.deny]\nlicenses = [\"GPL-*\"]\n",
+    )
+    .unwrap();
+    let entries = scan_json(root, &[], &[]);
+    let classpath = entry(&entries, "fixture-classpath");
+    assert_eq!(classpath["is_restrictive"], true);
+    assert_eq!(classpath["compatibility"], "Incompatible");
+}
+
+#[test]
+fn baseline_only_fails_on_new_findings() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path().join("project");
+    fs::create_dir(&root).unwrap();
+    fs::write(root.join("LICENSE"), MIT_TEXT).unwrap();
+    write_node_fixture(&root, &[("fixture-copyleft", "2.0.0", "AGPL-3.0")]);
+    let baseline = temp.path().join("baseline.json");
+    let baseline_path = baseline.to_str().unwrap();
+
+    run_feluda(&root, &["--write-baseline", baseline_path], &[]);
+    let known = run_feluda(
+        &root,
+        &["--fail-on-restrictive", "--baseline", baseline_path],
+        &[],
+    );
+    assert!(
+        known.status.success(),
+        "a finding already in the baseline must not fail the scan\nstderr: {}",
+        String::from_utf8_lossy(&known.stderr)
+    );
+
+    write_node_fixture(
+        &root,
+        &[
+            ("fixture-copyleft", "2.1.0", "AGPL-3.0"),
+            ("fixture-gpl", "1.0.0", "GPL-3.0-only"),
+        ],
+    );
+    let new = run_feluda(
+        &root,
+        &["--fail-on-restrictive", "--baseline", baseline_path],
+        &[],
+    );
+    assert_eq!(new.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&new.stderr);
+    assert!(stderr.contains("fixture-gpl 1.0.0"), "stderr: {stderr}");
+    assert!(
+        !stderr.contains("fixture-copyleft 2.1.0"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn diff_fails_only_on_regressions() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path().join("project");
+    fs::create_dir(&root).unwrap();
+    fs::write(root.join("LICENSE"), MIT_TEXT).unwrap();
+    let report = |deps: &[(&str, &str, &str)], file: &str| {
+        write_node_fixture(&root, deps);
+        let output = run_feluda(&root, &["--json"], &[]);
+        let path = temp.path().join(file);
+        fs::write(&path, &output.stdout).unwrap();
+        path.to_str().unwrap().to_string()
+    };
+    let old = report(
+        &[
+            ("fixture-copyleft", "2.0.0", "AGPL-3.0"),
+            ("fixture-lib", "1.0.0", "MIT"),
+        ],
+        "old.json",
+    );
+    let same = report(
+        &[
+            ("fixture-copyleft", "2.1.0", "AGPL-3.0"),
+            ("fixture-lib", "1.0.0", "MIT"),
+        ],
+        "same.json",
+    );
+    let new = report(
+        &[
+            ("fixture-copyleft", "2.1.0", "AGPL-3.0"),
+            ("fixture-lib", "1.1.0", "GPL-3.0-only"),
+        ],
+        "new.json",
+    );
+
+    let unchanged = run_feluda(
+        &root,
+        &["diff", &old, &same, "--fail-on-restrictive", "--json"],
+        &[],
+    );
+    assert!(
+        unchanged.status.success(),
+        "a finding both reports have must not fail the diff\nstderr: {}",
+        String::from_utf8_lossy(&unchanged.stderr)
+    );
+    let diff: Value = serde_json::from_slice(&unchanged.stdout).unwrap();
+    assert_eq!(diff["regressions"].as_array().unwrap().len(), 0);
+
+    let regressed = run_feluda(&root, &["diff", &old, &new, "--fail-on-restrictive"], &[]);
+    assert_eq!(regressed.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&regressed.stdout);
+    assert!(stdout.contains("fixture-lib"), "stdout: {stdout}");
+    assert!(stdout.contains("MIT → GPL-3.0-only"), "stdout: {stdout}");
+}
+
+#[test]
+fn rev_scans_a_committed_revision() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    let repo = git2::Repository::init(root).unwrap();
+    let signature = git2::Signature::now("test", "test@example.com").unwrap();
+    fs::write(root.join("LICENSE"), MIT_TEXT).unwrap();
+    write_node_fixture(root, &[("fixture-lib", "1.0.0", "MIT")]);
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    repo.commit(Some("HEAD"), &signature, &signature, "base", &tree, &[])
+        .unwrap();
+
+    // The working tree moves on; --rev HEAD still sees the committed dependency
+    write_node_fixture(
+        root,
+        &[
+            ("fixture-lib", "1.0.0", "MIT"),
+            ("fixture-copyleft", "2.0.0", "AGPL-3.0"),
+        ],
+    );
+    assert!(has_entry(&scan_json(root, &[], &[]), "fixture-copyleft"));
+
+    let committed = scan_json(root, &["--rev", "HEAD"], &[]);
+    assert_eq!(entry(&committed, "fixture-lib")["license"], "MIT");
+    assert!(!has_entry(&committed, "fixture-copyleft"));
+
+    let missing = run_feluda(root, &["--json", "--rev", "no-such-rev"], &[]);
+    assert!(!missing.status.success());
+}
+
+#[test]
+fn scan_subcommand_clones_a_repository_url() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path().join("upstream");
+    fs::create_dir_all(&root).unwrap();
+    let repo = git2::Repository::init(&root).unwrap();
+    let signature = git2::Signature::now("test", "test@example.com").unwrap();
+    fs::write(root.join("LICENSE"), MIT_TEXT).unwrap();
+    write_node_fixture(&root, &[("fixture-copyleft", "2.0.0", "AGPL-3.0")]);
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    repo.commit(Some("HEAD"), &signature, &signature, "base", &tree, &[])
+        .unwrap();
+
+    // Scan options follow the URL, and still gate the exit status
+    let url = format!("file://{}", root.display());
+    let output = Command::new(env!("CARGO_BIN_EXE_feluda"))
+        .current_dir(temp.path())
+        .args(["scan", &url, "--json", "--fail-on-restrictive"])
+        .output()
+        .expect("failed to run feluda binary");
+    assert_eq!(output.status.code(), Some(1));
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let dependencies = report["dependencies"].as_array().unwrap();
+    assert!(dependencies
+        .iter()
+        .any(|dep| dep["name"] == "fixture-copyleft" && dep["is_restrictive"] == true));
+
+    // The report can go to a file instead, relative to where feluda was run
+    let output = Command::new(env!("CARGO_BIN_EXE_feluda"))
+        .current_dir(temp.path())
+        .args(["scan", &url, "--json", "--output-file", "repo.json"])
+        .output()
+        .expect("failed to run feluda binary");
+    assert!(output.status.success());
+    let report: Value =
+        serde_json::from_str(&fs::read_to_string(temp.path().join("repo.json")).unwrap()).unwrap();
+    assert_eq!(report["dependencies"][0]["name"], "fixture-copyleft");
+}
+
+#[test]
+fn history_reports_the_commit_that_introduced_a_restrictive_dependency() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    let repo = git2::Repository::init(root).unwrap();
+    // A day apart, so the commit dates order the scans
+    let commit = |message: &str, time: i64| {
+        let signature =
+            git2::Signature::new("test", "test@example.com", &git2::Time::new(time, 0)).unwrap();
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+        .to_string()
+    };
+    fs::write(root.join("LICENSE"), MIT_TEXT).unwrap();
+    write_node_fixture(root, &[("fixture-lib", "1.0.0", "MIT")]);
+    let base = commit("base", 1_700_000_000);
+    write_node_fixture(
+        root,
+        &[
+            ("fixture-lib", "1.0.0", "MIT"),
+            ("fixture-copyleft", "2.0.0", "AGPL-3.0"),
+        ],
+    );
+    let head = commit("Add charts", 1_700_086_400);
+
+    // The working tree is recorded first; the older commit is backfilled with --rev
+    for args in [
+        &["history", "record"][..],
+        &["history", "record", "--rev", "HEAD~1"][..],
+    ] {
+        let output = run_feluda(root, args, &[]);
+        assert!(
+            output.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    assert!(root.join(".feluda/history.db").is_file());
+
+    let output = run_feluda(root, &["history", "report", "--json"], &[]);
+    assert!(output.status.success());
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["scans"], 2);
+    let restrictive = report["restrictive"].as_array().unwrap();
+    assert_eq!(restrictive.len(), 1);
+    assert_eq!(restrictive[0]["name"], "fixture-copyleft");
+    assert_eq!(restrictive[0]["introduced"]["commit"], head.as_str());
+    assert_eq!(restrictive[0]["introduced"]["summary"], "Add charts");
+    assert_eq!(restrictive[0]["previous"]["commit"], base.as_str());
+}
+
+#[test]
+fn outputs_send_the_report_to_each_sink() {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    fs::write(root.join("LICENSE"), MIT_TEXT).unwrap();
+    write_node_fixture(root, &[("fixture-lib", "1.0.0", "MIT")]);
+
+    // A compliance service that accepts one report
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut head = Vec::new();
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    length = value.trim().parse().unwrap();
+                }
+            }
+            head.push(line.trim().to_string());
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        reader
+            .get_mut()
+            .write_all(b"HTTP/1.1 201 Created\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+        (head, body)
+    });
+
+    fs::write(
+        root.join(".feluda.toml"),
+        format!(
+            r#"
+[outputs.compliance]
+type = "http"
+url = "http://127.0.0.1:{port}/reports"
+token_env = "FIXTURE_COMPLIANCE_TOKEN"
+
+[outputs.artifact]
+type = "file"
+path = "reports/{{project}}.yaml"
+format = "yaml"
+"#
+        ),
+    )
+    .unwrap();
+
+    let output = run_feluda(root, &["--gist"], &[("FIXTURE_COMPLIANCE_TOKEN", "s3cret")]);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let (head, body) = server.join().unwrap();
+    assert!(head[0].starts_with("POST /reports "), "{head:?}");
+    assert!(
+        head.contains(&"authorization: Bearer s3cret".to_string()),
+        "{head:?}"
+    );
+    let posted: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(posted["dependencies"][0]["name"], "fixture-lib");
+    assert!(posted["metadata"]["version"].is_string());
+
+    let name = root.file_name().unwrap().to_str().unwrap();
+    let written = fs::read_to_string(root.join(format!("reports/{name}.yaml"))).unwrap();
+    assert!(written.contains("name: fixture-lib"), "{written}");
+}
+
+#[test]
+fn failed_output_sink_fails_the_scan() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    fs::write(root.join("LICENSE"), MIT_TEXT).unwrap();
+    write_node_fixture(root, &[("fixture-lib", "1.0.0", "MIT")]);
+    // `blocker` is a file, so nothing can be written beneath it
+    fs::write(root.join("blocker"), "").unwrap();
+    fs::write(
+        root.join(".feluda.toml"),
+        r#"
+[outputs.broken]
+type = "file"
+path = "blocker/report.json"
+
+[outputs.artifact]
+type = "file"
+path = "report.json"
+"#,
+    )
+    .unwrap();
+
+    let output = run_feluda(root, &["--gist"], &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "stderr: {stderr}");
+    assert!(stderr.contains("output broken"), "{stderr}");
+    // The other sinks still ran
+    assert!(root.join("report.json").exists());
+}
+
+#[test]
+fn project_licenses_apply_per_workspace_member() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    fs::write(root.join("LICENSE"), MIT_TEXT).unwrap();
+    write_node_fixture(
+        root,
+        &[
+            ("fixture-gpl", "1.0.0", "GPL-3.0-only"),
+            ("fixture-agpl", "1.0.0", "AGPL-3.0-only"),
+        ],
+    );
+    // Make the fixture a workspace whose `services/api` member uses the GPL dependency
+    let manifest = fs::read_to_string(root.join("package.json"))
+        .unwrap()
+        .replace("\"fixture-gpl\": \"1.0.0\",\n", "")
+        .replace(
+            "\"license\": \"MIT\",",
+            "\"license\": \"MIT\",\n  \"workspaces\": [\"services/*\"],",
+        );
+    fs::write(root.join("package.json"), manifest).unwrap();
+    let api = root.join("services/api");
+    fs::create_dir_all(&api).unwrap();
+    fs::write(
+        api.join("package.json"),
+        "{\n  \"name\": \"api\",\n  \"version\": \"1.0.0\",\n  \"dependencies\": {\n    \"fixture-gpl\": \"1.0.0\"\n  }\n}\n",
+    )
+    .unwrap();
+
+    let entries = scan_json(root, &[], &[]);
+    assert_eq!(
+        entry(&entries, "fixture-gpl")["compatibility"],
+        "Incompatible"
+    );
+
+    fs::write(
+        root.join(".feluda.toml"),
+        "[project_licenses]\n\"services/api\" = \"GPL-3.0-only\"\n",
+    )
+    .unwrap();
+    let entries = scan_json(root, &[], &[]);
+    assert_eq!(
+        entry(&entries, "fixture-gpl")["compatibility"],
+        "Compatible"
+    );
+    // The root's own dependencies are still checked against its MIT license
+    assert_eq!(
+        entry(&entries, "fixture-agpl")["compatibility"],
+        "Incompatible"
+    );
+}
+
 #[test]
 fn rust_path_dependency_license_from_cargo_metadata() {
     let temp = tempfile::TempDir::new().unwrap();
@@ -225,6 +772,55 @@ fn python_requirements_without_dependencies_scans_clean() {
     );
 }
 
+#[test]
+fn unparseable_manifest_warns_by_default_and_fails_with_strict_parse() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    fs::write(root.join("LICENSE"), MIT_TEXT).unwrap();
+    fs::write(root.join("package.json"), "{ \"dependencies\": ").unwrap();
+
+    let lenient = run_feluda(root, &["--json", "--no-vendor-scan"], &[]);
+    assert!(
+        lenient.status.success(),
+        "a broken manifest must only warn by default\nstderr: {}",
+        String::from_utf8_lossy(&lenient.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&lenient.stderr);
+    assert!(
+        stderr.contains("package.json") && stderr.contains("invalid package.json"),
+        "warning must name the file and reason: {stderr}"
+    );
+
+    let strict = run_feluda(root, &["--json", "--no-vendor-scan", "--strict-parse"], &[]);
+    assert_eq!(
+        strict.status.code(),
+        Some(1),
+        "--strict-parse must fail on a broken manifest"
+    );
+}
+
+#[test]
+fn stdin_invalid_lockfile_error_reaches_the_user() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_feluda"))
+        .current_dir(temp.path())
+        .args(["--stdin", "--stdin-format", "package-lock.json", "--json"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run feluda binary");
+    child.stdin.take().unwrap().write_all(b"{{bad\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("package-lock.json from stdin"),
+        "the invalid lockfile must be reported without --debug: {stderr}"
+    );
+}
+
 #[test]
 fn own_source_header_findings_reported() {
     let temp = tempfile::TempDir::new().unwrap();
@@ -385,6 +981,58 @@ fn multi_language_root_parses_all_ecosystems() {
     assert!(has_entry(&entries, "example.com/mylib"));
 }
 
+#[test]
+fn offline_scan_reports_unresolvable_licenses_as_unknown() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    fs::write(root.join("LICENSE"), MIT_TEXT).unwrap();
+    write_node_fixture(root, &[("fixture-permissive", "1.3.0", "ISC")]);
+    // Declared but never installed: only the registry could answer for it
+    let manifest = fs::read_to_string(root.join("package.json")).unwrap();
+    fs::write(
+        root.join("package.json"),
+        manifest.replace(
+            "\"fixture-permissive\": \"1.3.0\"",
+            "\"fixture-permissive\": \"1.3.0\",\n    \"fixture-missing\": \"4.0.0\"",
+        ),
+    )
+    .unwrap();
+    let cache = root.join("cache");
+    let cache_dir = cache.display().to_string();
+
+    let output = run_feluda(
+        root,
+        &["--json", "--offline"],
+        &[("XDG_CACHE_HOME", &cache_dir)],
+    );
+    assert!(
+        output.status.success(),
+        "offline scan failed\nstderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let entries = report["dependencies"].as_array().unwrap();
+
+    // The report records how it was produced
+    assert_eq!(report["metadata"]["tool"], "feluda");
+    assert_eq!(report["metadata"]["offline"], true);
+    assert!(report["metadata"]["arguments"]
+        .as_array()
+        .unwrap()
+        .contains(&Value::from("--offline")));
+
+    assert_eq!(entry(entries, "fixture-permissive")["license"], "ISC");
+    let missing = entry(entries, "fixture-missing")["license"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    assert!(missing.starts_with("Unknown"), "got {missing:?}");
+    assert!(
+        !String::from_utf8_lossy(&output.stderr).contains("Resolution errors"),
+        "offline lookups are skipped, not failed"
+    );
+}
+
 #[test]
 fn language_filter_limits_scan() {
     let temp = tempfile::TempDir::new().unwrap();